@@ -0,0 +1,86 @@
+//! Criterion benchmarks of the ledger's workloads, see
+//! [`anoma_apps::node::ledger::bench`]. Run with `cargo bench` from the `apps`
+//! directory, after the WASMs for tests are built.
+
+use std::time::{Duration, Instant};
+
+use anoma_apps::node::ledger::bench::{
+    self, BenchEnv, NUM_KEYS, NUM_VPS, TX_NO_OP_WASM,
+};
+use criterion::{
+    criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion,
+};
+use tempfile::TempDir;
+
+const WASM_DIR: &str = "../wasm_for_tests";
+
+fn env() -> (BenchEnv, TempDir) {
+    let dir = TempDir::new().expect("Unable to create a temporary directory");
+    (BenchEnv::new(dir.path(), WASM_DIR), dir)
+}
+
+fn apply_tx(c: &mut Criterion) {
+    let (mut env, _dir) = env();
+    let mut group = c.benchmark_group("apply_tx");
+    for num_vps in NUM_VPS {
+        let tx = env.prepare_apply_tx(num_vps);
+        group.bench_with_input(BenchmarkId::new("vps", num_vps), &tx, |b, tx| {
+            b.iter_batched(
+                || tx.clone(),
+                |tx| env.apply_tx(tx),
+                BatchSize::SmallInput,
+            )
+        });
+    }
+    group.finish();
+}
+
+fn commit_block(c: &mut Criterion) {
+    let (mut env, _dir) = env();
+    let mut group = c.benchmark_group("commit_block");
+    for num_keys in NUM_KEYS {
+        group.bench_function(BenchmarkId::new("keys", num_keys), |b| {
+            // Only the commit is measured, without writing the keys
+            b.iter_custom(|iters| {
+                let mut total = Duration::default();
+                for _ in 0..iters {
+                    env.prepare_commit_block(num_keys);
+                    let start = Instant::now();
+                    env.commit_block();
+                    total += start.elapsed();
+                }
+                total
+            })
+        });
+    }
+    group.finish();
+}
+
+fn tx_wasm(c: &mut Criterion) {
+    let (mut env, _dir) = env();
+    let tx_code = env.read_wasm(TX_NO_OP_WASM);
+    let mut group = c.benchmark_group("tx_wasm");
+    group.bench_function("cold_cache", |b| {
+        b.iter(|| env.run_tx_cold(&tx_code))
+    });
+    group.bench_function("warm_cache", |b| {
+        b.iter(|| env.run_tx_warm(&tx_code))
+    });
+    group.finish();
+}
+
+fn merkle_tree(c: &mut Criterion) {
+    let mut group = c.benchmark_group("merkle_tree");
+    for num_keys in NUM_KEYS {
+        let updates = bench::merkle_tree_updates(num_keys);
+        group.bench_with_input(
+            BenchmarkId::new("keys", num_keys),
+            &updates,
+            |b, updates| b.iter(|| bench::update_merkle_tree(updates)),
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, apply_tx, commit_block, tx_wasm, merkle_tree);
+criterion_main!(benches);
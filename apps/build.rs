@@ -107,4 +107,14 @@ fn main() {
         // serde::Deserialize)]")
         .compile(&[format!("{}/services.proto", PROTO_SRC)], &[PROTO_SRC])
         .unwrap();
+
+    // The gRPC query service is only built with the "grpc" feature
+    if env::var("CARGO_FEATURE_GRPC").is_ok() {
+        tonic_build::configure()
+            .out_dir("src/lib/proto/generated")
+            .format(true)
+            .server_mod_attribute(".", "#[allow(clippy::unit_arg)]")
+            .compile(&[format!("{}/query.proto", PROTO_SRC)], &[PROTO_SRC])
+            .unwrap();
+    }
 }
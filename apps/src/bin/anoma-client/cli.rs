@@ -1,8 +1,9 @@
 //! Anoma client CLI.
 
+use anoma::types::transaction::token::SupplyChange;
 use anoma_apps::cli;
 use anoma_apps::cli::cmds::*;
-use anoma_apps::client::{gossip, rpc, tx, utils};
+use anoma_apps::client::{gossip, rpc, tx, txgen, utils};
 use color_eyre::eyre::Result;
 
 pub async fn main() -> Result<()> {
@@ -15,18 +16,41 @@ pub async fn main() -> Result<()> {
                 Sub::TxCustom(TxCustom(args)) => {
                     tx::submit_custom(ctx, args).await;
                 }
+                Sub::TxBatch(TxBatch(args)) => {
+                    tx::submit_batch(ctx, args).await;
+                }
                 Sub::TxTransfer(TxTransfer(args)) => {
                     tx::submit_transfer(ctx, args).await;
                 }
+                Sub::TxIbcTransfer(TxIbcTransfer(args)) => {
+                    tx::submit_ibc_transfer(ctx, args).await;
+                }
                 Sub::TxUpdateVp(TxUpdateVp(args)) => {
                     tx::submit_update_vp(ctx, args).await;
                 }
                 Sub::TxInitAccount(TxInitAccount(args)) => {
                     tx::submit_init_account(ctx, args).await;
                 }
+                Sub::TxInitMultisigAccount(TxInitMultisigAccount(args)) => {
+                    tx::submit_init_multisig_account(ctx, args).await;
+                }
+                Sub::TxInitToken(TxInitToken(args)) => {
+                    tx::submit_init_token(ctx, args).await;
+                }
+                Sub::TxMint(TxMint(args)) => {
+                    tx::submit_change_supply(ctx, args, SupplyChange::Mint)
+                        .await;
+                }
+                Sub::TxBurn(TxBurn(args)) => {
+                    tx::submit_change_supply(ctx, args, SupplyChange::Burn)
+                        .await;
+                }
                 Sub::TxInitValidator(TxInitValidator(args)) => {
                     tx::submit_init_validator(ctx, args).await;
                 }
+                Sub::TxRevealPk(TxRevealPk(args)) => {
+                    tx::submit_reveal_pk(ctx, args).await;
+                }
                 Sub::TxInitNft(TxInitNft(args)) => {
                     tx::submit_init_nft(ctx, args).await;
                 }
@@ -39,6 +63,18 @@ pub async fn main() -> Result<()> {
                 Sub::TxVoteProposal(TxVoteProposal(args)) => {
                     tx::submit_vote_proposal(ctx, args).await;
                 }
+                Sub::TxSign(TxSign(args)) => {
+                    tx::sign_tx_file(ctx, args).await;
+                }
+                Sub::TxBroadcast(TxBroadcast(args)) => {
+                    tx::broadcast_signed_file(ctx, args).await;
+                }
+                Sub::TxMultisigSign(TxMultisigSign(args)) => {
+                    tx::sign_multisig_tx_file(ctx, args).await;
+                }
+                Sub::TxMultisigSubmit(TxMultisigSubmit(args)) => {
+                    tx::submit_multisig_tx_file(ctx, args).await;
+                }
                 Sub::Bond(Bond(args)) => {
                     tx::submit_bond(ctx, args).await;
                 }
@@ -48,19 +84,39 @@ pub async fn main() -> Result<()> {
                 Sub::Withdraw(Withdraw(args)) => {
                     tx::submit_withdraw(ctx, args).await;
                 }
+                Sub::TxChangeConsensusKey(TxChangeConsensusKey(args)) => {
+                    tx::submit_change_consensus_key(ctx, args).await;
+                }
+                Sub::TxUpdateValidatorMetadata(TxUpdateValidatorMetadata(
+                    args,
+                )) => {
+                    tx::submit_update_validator_metadata(ctx, args).await;
+                }
                 // Ledger queries
                 Sub::QueryEpoch(QueryEpoch(args)) => {
-                    rpc::query_epoch(args).await;
+                    rpc::print_epoch(ctx, args).await;
+                }
+                Sub::QueryBlockResults(QueryBlockResults(args)) => {
+                    rpc::query_block_results(ctx, args).await;
+                }
+                Sub::QueryIndexedTxs(QueryIndexedTxs(args)) => {
+                    rpc::query_indexed_txs(ctx, args).await;
                 }
                 Sub::QueryBalance(QueryBalance(args)) => {
                     rpc::query_balance(ctx, args).await;
                 }
+                Sub::QueryTokens(QueryTokens(args)) => {
+                    rpc::query_tokens(ctx, args).await;
+                }
                 Sub::QueryBonds(QueryBonds(args)) => {
                     rpc::query_bonds(ctx, args).await;
                 }
                 Sub::QueryVotingPower(QueryVotingPower(args)) => {
                     rpc::query_voting_power(ctx, args).await;
                 }
+                Sub::QueryValidators(QueryValidators(args)) => {
+                    rpc::query_validators(ctx, args).await;
+                }
                 Sub::QuerySlashes(QuerySlashes(args)) => {
                     rpc::query_slashes(ctx, args).await;
                 }
@@ -87,19 +143,35 @@ pub async fn main() -> Result<()> {
                 Sub::SubscribeTopic(SubscribeTopic(args)) => {
                     gossip::subscribe_topic(ctx, args).await;
                 }
+                Sub::UnsubscribeTopic(UnsubscribeTopic(args)) => {
+                    gossip::unsubscribe_topic(ctx, args).await;
+                }
             }
         }
         cli::AnomaClient::WithoutContext(cmd, global_args) => match cmd {
             // Utils cmds
+            Utils::Init(Init(args)) => utils::init(global_args, args).await,
             Utils::JoinNetwork(JoinNetwork(args)) => {
                 utils::join_network(global_args, args).await
             }
             Utils::InitNetwork(InitNetwork(args)) => {
                 utils::init_network(global_args, args)
             }
+            Utils::InitLocalnet(InitLocalnet(args)) => {
+                utils::init_localnet(global_args, args)
+            }
             Utils::InitGenesisValidator(InitGenesisValidator(args)) => {
                 utils::init_genesis_validator(global_args, args)
             }
+            Utils::Db(Db::Dump(DbDump(args))) => {
+                utils::db_dump(global_args, args)
+            }
+            Utils::Db(Db::Get(DbGet(args))) => utils::db_get(global_args, args),
+            Utils::VerifyState(VerifyState) => utils::verify_state(global_args),
+            Utils::Bench(Bench(args)) => utils::bench(global_args, args),
+            Utils::TxGen(TxGen(args)) => {
+                txgen::generate(global_args, args).await
+            }
         },
     }
     Ok(())
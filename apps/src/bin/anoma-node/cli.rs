@@ -1,5 +1,6 @@
 //! Anoma node CLI.
 
+use anoma::types::intent::IntentFilter;
 use anoma_apps::cli::{self, args, cmds};
 use anoma_apps::node::{gossip, ledger, matchmaker};
 use eyre::{Context, Result};
@@ -15,10 +16,19 @@ pub fn main() -> Result<()> {
                 let wasm_dir = ctx.wasm_dir();
                 ledger::run(ctx.config.ledger, wasm_dir);
             }
-            cmds::Ledger::Reset(_) => {
-                ledger::reset(ctx.config.ledger)
+            cmds::Ledger::RunReadOnly(cmds::LedgerRunReadOnly(args)) => {
+                ledger::run_read_only(ctx.config.ledger, args)
+                    .wrap_err("Failed to run the read-only node")?;
+            }
+            cmds::Ledger::Reset(cmds::LedgerReset(args)) => {
+                ledger::reset(ctx.config.ledger, args)
                     .wrap_err("Failed to reset Anoma node")?;
             }
+            cmds::Ledger::Replay(cmds::LedgerReplay(args)) => {
+                let wasm_dir = ctx.wasm_dir();
+                ledger::replay(ctx.config.ledger, wasm_dir, args)
+                    .wrap_err("Failed to replay the blocks")?;
+            }
         },
         cmds::AnomaNode::Gossip(sub) => match sub {
             cmds::Gossip::Run(cmds::GossipRun(args::GossipRun {
@@ -46,12 +56,22 @@ pub fn main() -> Result<()> {
             ledger_addr,
             tx_signing_key,
             tx_source_address,
+            token_pairs,
+            min_sell,
         })) => {
             let tx_signing_key = ctx.get_cached(&tx_signing_key);
             let tx_source_address = ctx.get(&tx_source_address);
+            let intent_filter = IntentFilter {
+                token_pairs: token_pairs
+                    .iter()
+                    .map(|(sell, buy)| (ctx.get(sell), ctx.get(buy)))
+                    .collect(),
+                min_sell,
+            };
 
             let wasm_dir = ctx.wasm_dir();
             let config = ctx.config;
+            let chain_id = config.ledger.chain_id;
             let mut mm_config = config.matchmaker;
             if matchmaker_path.is_some() {
                 mm_config.matchmaker_path = matchmaker_path;
@@ -64,8 +84,10 @@ pub fn main() -> Result<()> {
                 mm_config,
                 intent_gossiper_addr,
                 ledger_addr,
+                chain_id,
                 tx_signing_key,
                 tx_source_address,
+                intent_filter,
                 wasm_dir,
             );
         }
@@ -1,13 +1,13 @@
 //! Anoma Wallet CLI.
 
-use std::fs::File;
+use std::fs::{self, File};
 use std::io::{self, Write};
 
 use anoma::types::key::*;
 use anoma_apps::cli;
 use anoma_apps::cli::{args, cmds, Context};
 use anoma_apps::wallet::DecryptionError;
-use borsh::BorshSerialize;
+use borsh::{BorshDeserialize, BorshSerialize};
 use color_eyre::eyre::Result;
 use itertools::sorted;
 
@@ -23,6 +23,9 @@ pub fn main() -> Result<()> {
             cmds::WalletKey::Export(cmds::Export(args)) => {
                 key_export(ctx, args)
             }
+            cmds::WalletKey::Import(cmds::Import(args)) => {
+                key_import(ctx, args)
+            }
         },
         cmds::AnomaWallet::Address(sub) => match sub {
             cmds::WalletAddress::Gen(cmds::AddressGen(args)) => {
@@ -47,10 +50,19 @@ fn key_and_address_gen(
     args::KeyAndAddressGen {
         alias,
         unsafe_dont_encrypt,
+        hd,
+        restore,
+        derivation_path,
     }: args::KeyAndAddressGen,
 ) {
     let mut wallet = ctx.wallet;
-    let (alias, _key) = wallet.gen_key(alias, unsafe_dont_encrypt);
+    let (alias, _key) = if restore {
+        wallet.restore_hd_key(alias, unsafe_dont_encrypt, &derivation_path)
+    } else if hd {
+        wallet.gen_hd_key(alias, unsafe_dont_encrypt, &derivation_path)
+    } else {
+        wallet.gen_key(alias, unsafe_dont_encrypt)
+    };
     wallet.save().unwrap_or_else(|err| eprintln!("{}", err));
     println!(
         "Successfully added a key and an address with alias: \"{}\"",
@@ -170,6 +182,37 @@ fn key_export(ctx: Context, args::KeyExport { alias }: args::KeyExport) {
         })
 }
 
+/// Import a keypair from a file exported with `key export`.
+fn key_import(
+    ctx: Context,
+    args::KeyImport {
+        file_path,
+        alias,
+        unsafe_dont_encrypt,
+    }: args::KeyImport,
+) {
+    let file_data = fs::read(&file_path).unwrap_or_else(|err| {
+        eprintln!("Couldn't read the file {}: {}", file_path.display(), err);
+        cli::safe_exit(1)
+    });
+    let keypair =
+        common::SecretKey::try_from_slice(&file_data).unwrap_or_else(|err| {
+            eprintln!(
+                "Couldn't decode the keypair from the file {}: {}",
+                file_path.display(),
+                err
+            );
+            cli::safe_exit(1)
+        });
+    let mut wallet = ctx.wallet;
+    let (alias, _key) = wallet.import_key(keypair, alias, unsafe_dont_encrypt);
+    wallet.save().unwrap_or_else(|err| eprintln!("{}", err));
+    println!(
+        "Successfully imported a key and an address with alias: \"{}\"",
+        alias
+    );
+}
+
 /// List all known addresses.
 fn address_list(ctx: Context) {
     let wallet = ctx.wallet;
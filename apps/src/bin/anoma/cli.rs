@@ -38,6 +38,13 @@ fn handle_command(cmd: cli::cmds::Anoma, raw_sub_cmd: String) -> Result<()> {
             .map(|e| sub_args.remove(e));
     }
 
+    if let cli::cmds::Anoma::Init(_) = cmd {
+        // The command is nested in the client's utils
+        if let Some(pos) = sub_args.iter().position(|arg| arg == &raw_sub_cmd) {
+            sub_args.insert(pos, "utils".to_owned());
+        }
+    }
+
     match cmd {
         cli::cmds::Anoma::Node(_)
         | cli::cmds::Anoma::Ledger(_)
@@ -46,6 +53,7 @@ fn handle_command(cmd: cli::cmds::Anoma, raw_sub_cmd: String) -> Result<()> {
             handle_subcommand("anoman", sub_args)
         }
         cli::cmds::Anoma::Client(_)
+        | cli::cmds::Anoma::Init(_)
         | cli::cmds::Anoma::TxCustom(_)
         | cli::cmds::Anoma::TxTransfer(_)
         | cli::cmds::Anoma::TxUpdateVp(_)
@@ -45,6 +45,7 @@ pub mod cmds {
         Matchmaker(Matchmaker),
 
         // Inlined commands from the client.
+        Init(Init),
         TxCustom(TxCustom),
         TxTransfer(TxTransfer),
         TxUpdateVp(TxUpdateVp),
@@ -63,6 +64,7 @@ pub mod cmds {
                 .subcommand(Ledger::def())
                 .subcommand(Gossip::def())
                 .subcommand(Matchmaker::def())
+                .subcommand(Init::def())
                 .subcommand(TxCustom::def())
                 .subcommand(TxTransfer::def())
                 .subcommand(TxUpdateVp::def())
@@ -80,6 +82,7 @@ pub mod cmds {
             let ledger = SubCmd::parse(matches).map(Self::Ledger);
             let gossip = SubCmd::parse(matches).map(Self::Gossip);
             let matchmaker = SubCmd::parse(matches).map(Self::Matchmaker);
+            let init = SubCmd::parse(matches).map(Self::Init);
             let tx_custom = SubCmd::parse(matches).map(Self::TxCustom);
             let tx_transfer = SubCmd::parse(matches).map(Self::TxTransfer);
             let tx_update_vp = SubCmd::parse(matches).map(Self::TxUpdateVp);
@@ -95,6 +98,7 @@ pub mod cmds {
                 .or(ledger)
                 .or(gossip)
                 .or(matchmaker)
+                .or(init)
                 .or(tx_custom)
                 .or(tx_transfer)
                 .or(tx_update_vp)
@@ -169,25 +173,44 @@ pub mod cmds {
             app
                 // Simple transactions
                 .subcommand(TxCustom::def().display_order(1))
+                .subcommand(TxBatch::def().display_order(1))
                 .subcommand(TxTransfer::def().display_order(1))
+                .subcommand(TxIbcTransfer::def().display_order(1))
                 .subcommand(TxUpdateVp::def().display_order(1))
                 .subcommand(TxInitAccount::def().display_order(1))
+                .subcommand(TxInitMultisigAccount::def().display_order(1))
+                .subcommand(TxInitToken::def().display_order(1))
+                .subcommand(TxMint::def().display_order(1))
+                .subcommand(TxBurn::def().display_order(1))
                 .subcommand(TxInitValidator::def().display_order(1))
+                .subcommand(TxRevealPk::def().display_order(1))
                 // Nft transactions
                 .subcommand(TxInitNft::def().display_order(1))
                 .subcommand(TxMintNft::def().display_order(1))
                 // Proposal transactions
                 .subcommand(TxInitProposal::def().display_order(1))
                 .subcommand(TxVoteProposal::def().display_order(1))
+                // Offline signing
+                .subcommand(TxSign::def().display_order(1))
+                .subcommand(TxBroadcast::def().display_order(1))
+                // Multisignature accounts
+                .subcommand(TxMultisigSign::def().display_order(1))
+                .subcommand(TxMultisigSubmit::def().display_order(1))
                 // PoS transactions
                 .subcommand(Bond::def().display_order(2))
                 .subcommand(Unbond::def().display_order(2))
                 .subcommand(Withdraw::def().display_order(2))
+                .subcommand(TxChangeConsensusKey::def().display_order(2))
+                .subcommand(TxUpdateValidatorMetadata::def().display_order(2))
                 // Queries
                 .subcommand(QueryEpoch::def().display_order(3))
+                .subcommand(QueryBlockResults::def().display_order(3))
+                .subcommand(QueryIndexedTxs::def().display_order(3))
                 .subcommand(QueryBalance::def().display_order(3))
+                .subcommand(QueryTokens::def().display_order(3))
                 .subcommand(QueryBonds::def().display_order(3))
                 .subcommand(QueryVotingPower::def().display_order(3))
+                .subcommand(QueryValidators::def().display_order(3))
                 .subcommand(QuerySlashes::def().display_order(3))
                 .subcommand(QueryResult::def().display_order(3))
                 .subcommand(QueryRawBytes::def().display_order(3))
@@ -197,6 +220,7 @@ pub mod cmds {
                 // Intents
                 .subcommand(Intent::def().display_order(4))
                 .subcommand(SubscribeTopic::def().display_order(4))
+                .subcommand(UnsubscribeTopic::def().display_order(4))
                 // Utils
                 .subcommand(Utils::def().display_order(5))
         }
@@ -204,25 +228,50 @@ pub mod cmds {
         fn parse(matches: &ArgMatches) -> Option<Self> {
             use AnomaClientWithContext::*;
             let tx_custom = Self::parse_with_ctx(matches, TxCustom);
+            let tx_batch = Self::parse_with_ctx(matches, TxBatch);
             let tx_transfer = Self::parse_with_ctx(matches, TxTransfer);
+            let tx_ibc_transfer = Self::parse_with_ctx(matches, TxIbcTransfer);
             let tx_update_vp = Self::parse_with_ctx(matches, TxUpdateVp);
             let tx_init_account = Self::parse_with_ctx(matches, TxInitAccount);
+            let tx_init_multisig_account =
+                Self::parse_with_ctx(matches, TxInitMultisigAccount);
+            let tx_init_token = Self::parse_with_ctx(matches, TxInitToken);
+            let tx_mint = Self::parse_with_ctx(matches, TxMint);
+            let tx_burn = Self::parse_with_ctx(matches, TxBurn);
             let tx_init_validator =
                 Self::parse_with_ctx(matches, TxInitValidator);
+            let tx_reveal_pk = Self::parse_with_ctx(matches, TxRevealPk);
             let tx_nft_create = Self::parse_with_ctx(matches, TxInitNft);
             let tx_nft_mint = Self::parse_with_ctx(matches, TxMintNft);
             let tx_init_proposal =
                 Self::parse_with_ctx(matches, TxInitProposal);
             let tx_vote_proposal =
                 Self::parse_with_ctx(matches, TxVoteProposal);
+            let tx_sign = Self::parse_with_ctx(matches, TxSign);
+            let tx_broadcast = Self::parse_with_ctx(matches, TxBroadcast);
+            let tx_multisig_sign =
+                Self::parse_with_ctx(matches, TxMultisigSign);
+            let tx_multisig_submit =
+                Self::parse_with_ctx(matches, TxMultisigSubmit);
             let bond = Self::parse_with_ctx(matches, Bond);
             let unbond = Self::parse_with_ctx(matches, Unbond);
             let withdraw = Self::parse_with_ctx(matches, Withdraw);
+            let tx_change_consensus_key =
+                Self::parse_with_ctx(matches, TxChangeConsensusKey);
+            let tx_update_validator_metadata =
+                Self::parse_with_ctx(matches, TxUpdateValidatorMetadata);
             let query_epoch = Self::parse_with_ctx(matches, QueryEpoch);
+            let query_block_results =
+                Self::parse_with_ctx(matches, QueryBlockResults);
+            let query_indexed_txs =
+                Self::parse_with_ctx(matches, QueryIndexedTxs);
             let query_balance = Self::parse_with_ctx(matches, QueryBalance);
+            let query_tokens = Self::parse_with_ctx(matches, QueryTokens);
             let query_bonds = Self::parse_with_ctx(matches, QueryBonds);
             let query_voting_power =
                 Self::parse_with_ctx(matches, QueryVotingPower);
+            let query_validators =
+                Self::parse_with_ctx(matches, QueryValidators);
             let query_slashes = Self::parse_with_ctx(matches, QuerySlashes);
             let query_result = Self::parse_with_ctx(matches, QueryResult);
             let query_raw_bytes = Self::parse_with_ctx(matches, QueryRawBytes);
@@ -233,23 +282,42 @@ pub mod cmds {
                 Self::parse_with_ctx(matches, QueryProtocolParameters);
             let intent = Self::parse_with_ctx(matches, Intent);
             let subscribe_topic = Self::parse_with_ctx(matches, SubscribeTopic);
+            let unsubscribe_topic =
+                Self::parse_with_ctx(matches, UnsubscribeTopic);
             let utils = SubCmd::parse(matches).map(Self::WithoutContext);
             tx_custom
+                .or(tx_batch)
                 .or(tx_transfer)
+                .or(tx_ibc_transfer)
                 .or(tx_update_vp)
                 .or(tx_init_account)
+                .or(tx_init_multisig_account)
+                .or(tx_init_token)
+                .or(tx_mint)
+                .or(tx_burn)
                 .or(tx_init_validator)
+                .or(tx_reveal_pk)
                 .or(tx_nft_create)
                 .or(tx_nft_mint)
                 .or(tx_init_proposal)
                 .or(tx_vote_proposal)
+                .or(tx_sign)
+                .or(tx_broadcast)
+                .or(tx_multisig_sign)
+                .or(tx_multisig_submit)
                 .or(bond)
                 .or(unbond)
                 .or(withdraw)
+                .or(tx_change_consensus_key)
+                .or(tx_update_validator_metadata)
                 .or(query_epoch)
+                .or(query_block_results)
+                .or(query_indexed_txs)
                 .or(query_balance)
+                .or(query_tokens)
                 .or(query_bonds)
                 .or(query_voting_power)
+                .or(query_validators)
                 .or(query_slashes)
                 .or(query_result)
                 .or(query_raw_bytes)
@@ -258,6 +326,7 @@ pub mod cmds {
                 .or(query_protocol_parameters)
                 .or(intent)
                 .or(subscribe_topic)
+                .or(unsubscribe_topic)
                 .or(utils)
         }
     }
@@ -295,22 +364,39 @@ pub mod cmds {
     pub enum AnomaClientWithContext {
         // Ledger cmds
         TxCustom(TxCustom),
+        TxBatch(TxBatch),
         TxTransfer(TxTransfer),
+        TxIbcTransfer(TxIbcTransfer),
         QueryResult(QueryResult),
         TxUpdateVp(TxUpdateVp),
         TxInitAccount(TxInitAccount),
+        TxInitMultisigAccount(TxInitMultisigAccount),
+        TxInitToken(TxInitToken),
+        TxMint(TxMint),
+        TxBurn(TxBurn),
         TxInitValidator(TxInitValidator),
+        TxRevealPk(TxRevealPk),
         TxInitNft(TxInitNft),
         TxMintNft(TxMintNft),
         TxInitProposal(TxInitProposal),
         TxVoteProposal(TxVoteProposal),
+        TxSign(TxSign),
+        TxBroadcast(TxBroadcast),
+        TxMultisigSign(TxMultisigSign),
+        TxMultisigSubmit(TxMultisigSubmit),
         Bond(Bond),
         Unbond(Unbond),
         Withdraw(Withdraw),
+        TxChangeConsensusKey(TxChangeConsensusKey),
+        TxUpdateValidatorMetadata(TxUpdateValidatorMetadata),
         QueryEpoch(QueryEpoch),
+        QueryBlockResults(QueryBlockResults),
+        QueryIndexedTxs(QueryIndexedTxs),
         QueryBalance(QueryBalance),
+        QueryTokens(QueryTokens),
         QueryBonds(QueryBonds),
         QueryVotingPower(QueryVotingPower),
+        QueryValidators(QueryValidators),
         QuerySlashes(QuerySlashes),
         QueryRawBytes(QueryRawBytes),
         QueryProposal(QueryProposal),
@@ -319,6 +405,7 @@ pub mod cmds {
         // Gossip cmds
         Intent(Intent),
         SubscribeTopic(SubscribeTopic),
+        UnsubscribeTopic(UnsubscribeTopic),
     }
 
     #[derive(Clone, Debug)]
@@ -367,6 +454,7 @@ pub mod cmds {
         Find(KeyFind),
         List(KeyList),
         Export(Export),
+        Import(Import),
     }
 
     impl SubCmd for WalletKey {
@@ -378,21 +466,23 @@ pub mod cmds {
                 let lookup = SubCmd::parse(matches).map(Self::Find);
                 let list = SubCmd::parse(matches).map(Self::List);
                 let export = SubCmd::parse(matches).map(Self::Export);
-                generate.or(lookup).or(list).or(export)
+                let import = SubCmd::parse(matches).map(Self::Import);
+                generate.or(lookup).or(list).or(export).or(import)
             })
         }
 
         fn def() -> App {
             App::new(Self::CMD)
                 .about(
-                    "Keypair management, including methods to generate and \
-                     look-up keys.",
+                    "Keypair management, including methods to generate, \
+                     look-up, export and import keys.",
                 )
                 .setting(AppSettings::SubcommandRequiredElseHelp)
                 .subcommand(KeyGen::def())
                 .subcommand(KeyFind::def())
                 .subcommand(KeyList::def())
                 .subcommand(Export::def())
+                .subcommand(Import::def())
         }
     }
 
@@ -477,6 +567,29 @@ pub mod cmds {
         }
     }
 
+    #[derive(Clone, Debug)]
+    pub struct Import(pub args::KeyImport);
+
+    impl SubCmd for Import {
+        const CMD: &'static str = "import";
+
+        fn parse(matches: &ArgMatches) -> Option<Self> {
+            matches
+                .subcommand_matches(Self::CMD)
+                .map(|matches| (Self(args::KeyImport::parse(matches))))
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about(
+                    "Imports a keypair from a file created with `key export` \
+                     and derives the implicit address from its public key. \
+                     The address will be stored with the same alias.",
+                )
+                .add_args::<args::KeyImport>()
+        }
+    }
+
     #[derive(Clone, Debug)]
     pub enum WalletAddress {
         Gen(AddressGen),
@@ -597,7 +710,9 @@ pub mod cmds {
     #[derive(Clone, Debug)]
     pub enum Ledger {
         Run(LedgerRun),
+        RunReadOnly(LedgerRunReadOnly),
         Reset(LedgerReset),
+        Replay(LedgerReplay),
     }
 
     impl SubCmd for Ledger {
@@ -606,8 +721,13 @@ pub mod cmds {
         fn parse(matches: &ArgMatches) -> Option<Self> {
             matches.subcommand_matches(Self::CMD).and_then(|matches| {
                 let run = SubCmd::parse(matches).map(Self::Run);
+                let run_read_only =
+                    SubCmd::parse(matches).map(Self::RunReadOnly);
                 let reset = SubCmd::parse(matches).map(Self::Reset);
-                run.or(reset)
+                let replay = SubCmd::parse(matches).map(Self::Replay);
+                run.or(run_read_only)
+                    .or(reset)
+                    .or(replay)
                     // The `run` command is the default if no sub-command given
                     .or(Some(Self::Run(LedgerRun)))
             })
@@ -620,7 +740,9 @@ pub mod cmds {
                      defaults to run the node.",
                 )
                 .subcommand(LedgerRun::def())
+                .subcommand(LedgerRunReadOnly::def())
                 .subcommand(LedgerReset::def())
+                .subcommand(LedgerReplay::def())
         }
     }
 
@@ -640,20 +762,72 @@ pub mod cmds {
     }
 
     #[derive(Clone, Debug)]
-    pub struct LedgerReset;
+    pub struct LedgerRunReadOnly(pub args::LedgerRunReadOnly);
+
+    impl SubCmd for LedgerRunReadOnly {
+        const CMD: &'static str = "run-read-only";
+
+        fn parse(matches: &ArgMatches) -> Option<Self> {
+            matches
+                .subcommand_matches(Self::CMD)
+                .map(|matches| Self(args::LedgerRunReadOnly::parse(matches)))
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about(
+                    "Run a read-only ledger node that serves the gRPC \
+                     queries and the event stream from the DB of a running \
+                     node, without taking part in consensus. The servers' \
+                     addresses must differ from the running node's, e.g. \
+                     set with the environment variables.",
+                )
+                .add_args::<args::LedgerRunReadOnly>()
+        }
+    }
+
+    #[derive(Clone, Debug)]
+    pub struct LedgerReset(pub args::LedgerReset);
 
     impl SubCmd for LedgerReset {
         const CMD: &'static str = "reset";
 
         fn parse(matches: &ArgMatches) -> Option<Self> {
-            matches.subcommand_matches(Self::CMD).map(|_matches| Self)
+            matches
+                .subcommand_matches(Self::CMD)
+                .map(|matches| Self(args::LedgerReset::parse(matches)))
         }
 
         fn def() -> App {
-            App::new(Self::CMD).about(
-                "Delete Anoma ledger node's and Tendermint node's storage \
-                 data.",
-            )
+            App::new(Self::CMD)
+                .about(
+                    "Delete Anoma ledger node's and Tendermint node's storage \
+                     data.",
+                )
+                .add_args::<args::LedgerReset>()
+        }
+    }
+
+    #[derive(Clone, Debug)]
+    pub struct LedgerReplay(pub args::LedgerReplay);
+
+    impl SubCmd for LedgerReplay {
+        const CMD: &'static str = "replay";
+
+        fn parse(matches: &ArgMatches) -> Option<Self> {
+            matches
+                .subcommand_matches(Self::CMD)
+                .map(|matches| Self(args::LedgerReplay::parse(matches)))
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about(
+                    "Re-execute the chain's blocks from the Tendermint block \
+                     store against a fresh state and report the first block \
+                     whose app hash diverges.",
+                )
+                .add_args::<args::LedgerReplay>()
         }
     }
 
@@ -769,7 +943,7 @@ pub mod cmds {
     pub struct QueryResult(pub args::QueryResult);
 
     impl SubCmd for QueryResult {
-        const CMD: &'static str = "tx-result";
+        const CMD: &'static str = "tx-status";
 
         fn parse(matches: &ArgMatches) -> Option<Self> {
             matches
@@ -779,7 +953,11 @@ pub mod cmds {
 
         fn def() -> App {
             App::new(Self::CMD)
-                .about("Query the result of a transaction.")
+                .visible_alias("tx-result")
+                .about(
+                    "Query the status of a transaction: its height, result \
+                     code, gas used and emitted events.",
+                )
                 .add_args::<args::QueryResult>()
         }
     }
@@ -823,7 +1001,7 @@ pub mod cmds {
 
         fn def() -> App {
             App::new(Self::CMD)
-                .about("Query proposals result.")
+                .about("Query the result and the votes of proposals.")
                 .add_args::<args::QueryProposalResult>()
         }
     }
@@ -872,305 +1050,686 @@ pub mod cmds {
     }
 
     #[derive(Clone, Debug)]
-    pub struct TxTransfer(pub args::TxTransfer);
+    pub struct TxBatch(pub args::TxBatch);
 
-    impl SubCmd for TxTransfer {
-        const CMD: &'static str = "transfer";
+    impl SubCmd for TxBatch {
+        const CMD: &'static str = "tx-batch";
 
         fn parse(matches: &ArgMatches) -> Option<Self> {
             matches
                 .subcommand_matches(Self::CMD)
-                .map(|matches| TxTransfer(args::TxTransfer::parse(matches)))
+                .map(|matches| TxBatch(args::TxBatch::parse(matches)))
         }
 
         fn def() -> App {
             App::new(Self::CMD)
-                .about("Send a signed transfer transaction.")
-                .add_args::<args::TxTransfer>()
+                .about(
+                    "Send a batch of transactions with custom WASM codes, \
+                     which are applied atomically.",
+                )
+                .add_args::<args::TxBatch>()
         }
     }
 
     #[derive(Clone, Debug)]
-    pub struct TxUpdateVp(pub args::TxUpdateVp);
+    pub struct TxSign(pub args::TxSign);
 
-    impl SubCmd for TxUpdateVp {
-        const CMD: &'static str = "update";
+    impl SubCmd for TxSign {
+        const CMD: &'static str = "sign";
 
         fn parse(matches: &ArgMatches) -> Option<Self> {
             matches
                 .subcommand_matches(Self::CMD)
-                .map(|matches| TxUpdateVp(args::TxUpdateVp::parse(matches)))
+                .map(|matches| TxSign(args::TxSign::parse(matches)))
         }
 
         fn def() -> App {
             App::new(Self::CMD)
                 .about(
-                    "Send a signed transaction to update account's validity \
-                     predicate.",
+                    "Sign a transaction written with `--dump-tx` and write \
+                     the signed transaction to a file, to be submitted with \
+                     `broadcast`.",
                 )
-                .add_args::<args::TxUpdateVp>()
+                .add_args::<args::TxSign>()
         }
     }
 
     #[derive(Clone, Debug)]
-    pub struct TxInitAccount(pub args::TxInitAccount);
+    pub struct TxBroadcast(pub args::TxBroadcast);
 
-    impl SubCmd for TxInitAccount {
-        const CMD: &'static str = "init-account";
+    impl SubCmd for TxBroadcast {
+        const CMD: &'static str = "broadcast";
+
+        fn parse(matches: &ArgMatches) -> Option<Self> {
+            matches
+                .subcommand_matches(Self::CMD)
+                .map(|matches| TxBroadcast(args::TxBroadcast::parse(matches)))
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about("Submit a transaction signed with `sign`.")
+                .add_args::<args::TxBroadcast>()
+        }
+    }
+
+    #[derive(Clone, Debug)]
+    pub struct TxMultisigSign(pub args::TxMultisigSign);
+
+    impl SubCmd for TxMultisigSign {
+        const CMD: &'static str = "multisig-sign";
 
         fn parse(matches: &ArgMatches) -> Option<Self> {
             matches.subcommand_matches(Self::CMD).map(|matches| {
-                TxInitAccount(args::TxInitAccount::parse(matches))
+                TxMultisigSign(args::TxMultisigSign::parse(matches))
             })
         }
 
         fn def() -> App {
             App::new(Self::CMD)
                 .about(
-                    "Send a signed transaction to create a new established \
-                     account.",
+                    "Sign a transaction written with `--dump-tx` as one of \
+                     the signers of a multisignature account and write the \
+                     partial signature to a file, to be submitted with \
+                     `multisig-submit`.",
                 )
-                .add_args::<args::TxInitAccount>()
+                .add_args::<args::TxMultisigSign>()
         }
     }
 
     #[derive(Clone, Debug)]
-    pub struct TxInitValidator(pub args::TxInitValidator);
+    pub struct TxMultisigSubmit(pub args::TxMultisigSubmit);
 
-    impl SubCmd for TxInitValidator {
-        const CMD: &'static str = "init-validator";
+    impl SubCmd for TxMultisigSubmit {
+        const CMD: &'static str = "multisig-submit";
 
         fn parse(matches: &ArgMatches) -> Option<Self> {
             matches.subcommand_matches(Self::CMD).map(|matches| {
-                TxInitValidator(args::TxInitValidator::parse(matches))
+                TxMultisigSubmit(args::TxMultisigSubmit::parse(matches))
             })
         }
 
         fn def() -> App {
             App::new(Self::CMD)
                 .about(
-                    "Send a signed transaction to create a new validator and \
-                     its staking reward account.",
+                    "Aggregate the partial signatures written with \
+                     `multisig-sign` of the signers of a multisignature \
+                     account and submit the transaction.",
                 )
-                .add_args::<args::TxInitValidator>()
+                .add_args::<args::TxMultisigSubmit>()
         }
     }
 
     #[derive(Clone, Debug)]
-    pub struct Bond(pub args::Bond);
+    pub struct TxTransfer(pub args::TxTransfer);
 
-    impl SubCmd for Bond {
-        const CMD: &'static str = "bond";
+    impl SubCmd for TxTransfer {
+        const CMD: &'static str = "transfer";
 
         fn parse(matches: &ArgMatches) -> Option<Self> {
             matches
                 .subcommand_matches(Self::CMD)
-                .map(|matches| Bond(args::Bond::parse(matches)))
+                .map(|matches| TxTransfer(args::TxTransfer::parse(matches)))
         }
 
         fn def() -> App {
             App::new(Self::CMD)
-                .about("Bond tokens in PoS system.")
-                .add_args::<args::Bond>()
+                .about("Send a signed transfer transaction.")
+                .add_args::<args::TxTransfer>()
         }
     }
 
     #[derive(Clone, Debug)]
-    pub struct Unbond(pub args::Unbond);
+    pub struct TxIbcTransfer(pub args::TxIbcTransfer);
 
-    impl SubCmd for Unbond {
-        const CMD: &'static str = "unbond";
+    impl SubCmd for TxIbcTransfer {
+        const CMD: &'static str = "ibc-transfer";
 
         fn parse(matches: &ArgMatches) -> Option<Self> {
-            matches
-                .subcommand_matches(Self::CMD)
-                .map(|matches| Unbond(args::Unbond::parse(matches)))
+            matches.subcommand_matches(Self::CMD).map(|matches| {
+                TxIbcTransfer(args::TxIbcTransfer::parse(matches))
+            })
         }
 
         fn def() -> App {
             App::new(Self::CMD)
-                .about("Unbond tokens from a PoS bond.")
-                .add_args::<args::Unbond>()
+                .about(
+                    "Send a signed IBC transfer transaction to a connected \
+                     chain.",
+                )
+                .add_args::<args::TxIbcTransfer>()
         }
     }
 
     #[derive(Clone, Debug)]
-    pub struct Withdraw(pub args::Withdraw);
+    pub struct TxUpdateVp(pub args::TxUpdateVp);
 
-    impl SubCmd for Withdraw {
-        const CMD: &'static str = "withdraw";
+    impl SubCmd for TxUpdateVp {
+        const CMD: &'static str = "update";
 
         fn parse(matches: &ArgMatches) -> Option<Self> {
             matches
                 .subcommand_matches(Self::CMD)
-                .map(|matches| Withdraw(args::Withdraw::parse(matches)))
+                .map(|matches| TxUpdateVp(args::TxUpdateVp::parse(matches)))
         }
 
         fn def() -> App {
             App::new(Self::CMD)
-                .about("Withdraw tokens from previously unbonded PoS bond.")
-                .add_args::<args::Withdraw>()
+                .about(
+                    "Send a signed transaction to update account's validity \
+                     predicate.",
+                )
+                .add_args::<args::TxUpdateVp>()
         }
     }
 
     #[derive(Clone, Debug)]
-    pub struct QueryEpoch(pub args::Query);
+    pub struct TxInitAccount(pub args::TxInitAccount);
 
-    impl SubCmd for QueryEpoch {
-        const CMD: &'static str = "epoch";
+    impl SubCmd for TxInitAccount {
+        const CMD: &'static str = "init-account";
 
         fn parse(matches: &ArgMatches) -> Option<Self> {
-            matches
-                .subcommand_matches(Self::CMD)
-                .map(|matches| QueryEpoch(args::Query::parse(matches)))
+            matches.subcommand_matches(Self::CMD).map(|matches| {
+                TxInitAccount(args::TxInitAccount::parse(matches))
+            })
         }
 
         fn def() -> App {
             App::new(Self::CMD)
-                .about("Query the epoch of the last committed block.")
-                .add_args::<args::Query>()
+                .about(
+                    "Send a signed transaction to create a new established \
+                     account.",
+                )
+                .add_args::<args::TxInitAccount>()
         }
     }
 
     #[derive(Clone, Debug)]
-    pub struct QueryBalance(pub args::QueryBalance);
+    pub struct TxInitMultisigAccount(pub args::TxInitMultisigAccount);
 
-    impl SubCmd for QueryBalance {
-        const CMD: &'static str = "balance";
+    impl SubCmd for TxInitMultisigAccount {
+        const CMD: &'static str = "init-multisig-account";
 
         fn parse(matches: &ArgMatches) -> Option<Self> {
-            matches
-                .subcommand_matches(Self::CMD)
-                .map(|matches| QueryBalance(args::QueryBalance::parse(matches)))
+            matches.subcommand_matches(Self::CMD).map(|matches| {
+                TxInitMultisigAccount(args::TxInitMultisigAccount::parse(
+                    matches,
+                ))
+            })
         }
 
         fn def() -> App {
             App::new(Self::CMD)
-                .about("Query balance(s) of tokens.")
-                .add_args::<args::QueryBalance>()
+                .about(
+                    "Send a signed transaction to create a new established \
+                     k-of-n multisignature account.",
+                )
+                .add_args::<args::TxInitMultisigAccount>()
         }
     }
 
     #[derive(Clone, Debug)]
-    pub struct QueryBonds(pub args::QueryBonds);
+    pub struct TxInitToken(pub args::TxInitToken);
 
-    impl SubCmd for QueryBonds {
-        const CMD: &'static str = "bonds";
+    impl SubCmd for TxInitToken {
+        const CMD: &'static str = "init-token";
 
         fn parse(matches: &ArgMatches) -> Option<Self> {
             matches
                 .subcommand_matches(Self::CMD)
-                .map(|matches| QueryBonds(args::QueryBonds::parse(matches)))
+                .map(|matches| TxInitToken(args::TxInitToken::parse(matches)))
         }
 
         fn def() -> App {
             App::new(Self::CMD)
-                .about("Query PoS bond(s).")
-                .add_args::<args::QueryBonds>()
+                .about(
+                    "Send a signed transaction to create a new token account \
+                     with the given metadata.",
+                )
+                .add_args::<args::TxInitToken>()
         }
     }
 
     #[derive(Clone, Debug)]
-    pub struct QueryVotingPower(pub args::QueryVotingPower);
+    pub struct TxMint(pub args::TxChangeSupply);
 
-    impl SubCmd for QueryVotingPower {
-        const CMD: &'static str = "voting-power";
+    impl SubCmd for TxMint {
+        const CMD: &'static str = "mint";
 
         fn parse(matches: &ArgMatches) -> Option<Self> {
-            matches.subcommand_matches(Self::CMD).map(|matches| {
-                QueryVotingPower(args::QueryVotingPower::parse(matches))
-            })
+            matches
+                .subcommand_matches(Self::CMD)
+                .map(|matches| TxMint(args::TxChangeSupply::parse(matches)))
         }
 
         fn def() -> App {
             App::new(Self::CMD)
-                .about("Query PoS voting power.")
-                .add_args::<args::QueryVotingPower>()
+                .about(
+                    "Send a transaction signed by a token's minter to mint \
+                     new tokens.",
+                )
+                .add_args::<args::TxChangeSupply>()
         }
     }
 
     #[derive(Clone, Debug)]
-    pub struct QuerySlashes(pub args::QuerySlashes);
+    pub struct TxBurn(pub args::TxChangeSupply);
 
-    impl SubCmd for QuerySlashes {
-        const CMD: &'static str = "slashes";
+    impl SubCmd for TxBurn {
+        const CMD: &'static str = "burn";
 
-        fn parse(matches: &ArgMatches) -> Option<Self>
-        where
-            Self: Sized,
-        {
+        fn parse(matches: &ArgMatches) -> Option<Self> {
             matches
                 .subcommand_matches(Self::CMD)
-                .map(|matches| QuerySlashes(args::QuerySlashes::parse(matches)))
+                .map(|matches| TxBurn(args::TxChangeSupply::parse(matches)))
         }
 
         fn def() -> App {
             App::new(Self::CMD)
-                .about("Query PoS applied slashes.")
-                .add_args::<args::QuerySlashes>()
+                .about(
+                    "Send a transaction signed by a token's minter to burn \
+                     tokens.",
+                )
+                .add_args::<args::TxChangeSupply>()
         }
     }
 
     #[derive(Clone, Debug)]
-    pub struct QueryRawBytes(pub args::QueryRawBytes);
+    pub struct TxRevealPk(pub args::TxRevealPk);
 
-    impl SubCmd for QueryRawBytes {
-        const CMD: &'static str = "query-bytes";
+    impl SubCmd for TxRevealPk {
+        const CMD: &'static str = "reveal-pk";
 
         fn parse(matches: &ArgMatches) -> Option<Self> {
-            matches.subcommand_matches(Self::CMD).map(|matches| {
-                QueryRawBytes(args::QueryRawBytes::parse(matches))
-            })
+            matches
+                .subcommand_matches(Self::CMD)
+                .map(|matches| TxRevealPk(args::TxRevealPk::parse(matches)))
         }
 
         fn def() -> App {
             App::new(Self::CMD)
-                .about("Query the raw bytes of a given storage key")
-                .add_args::<args::QueryRawBytes>()
+                .about(
+                    "Submit a transaction to reveal the public key of an \
+                     implicit address, needed to verify its signatures. This \
+                     is done automatically before the first transaction \
+                     signed for an implicit address.",
+                )
+                .add_args::<args::TxRevealPk>()
         }
     }
 
     #[derive(Clone, Debug)]
-    pub struct TxInitNft(pub args::NftCreate);
+    pub struct TxInitValidator(pub args::TxInitValidator);
 
-    impl SubCmd for TxInitNft {
-        const CMD: &'static str = "init-nft";
+    impl SubCmd for TxInitValidator {
+        const CMD: &'static str = "init-validator";
 
-        fn parse(matches: &ArgMatches) -> Option<Self>
-        where
-            Self: Sized,
-        {
-            matches
-                .subcommand_matches(Self::CMD)
-                .map(|matches| TxInitNft(args::NftCreate::parse(matches)))
+        fn parse(matches: &ArgMatches) -> Option<Self> {
+            matches.subcommand_matches(Self::CMD).map(|matches| {
+                TxInitValidator(args::TxInitValidator::parse(matches))
+            })
         }
 
         fn def() -> App {
             App::new(Self::CMD)
-                .about("Create a new NFT.")
-                .add_args::<args::NftCreate>()
+                .about(
+                    "Send a signed transaction to create a new validator and \
+                     its staking reward account.",
+                )
+                .add_args::<args::TxInitValidator>()
         }
     }
 
     #[derive(Clone, Debug)]
-    pub struct TxMintNft(pub args::NftMint);
+    pub struct Bond(pub args::Bond);
 
-    impl SubCmd for TxMintNft {
-        const CMD: &'static str = "mint-nft";
+    impl SubCmd for Bond {
+        const CMD: &'static str = "bond";
 
-        fn parse(matches: &ArgMatches) -> Option<Self>
-        where
-            Self: Sized,
-        {
+        fn parse(matches: &ArgMatches) -> Option<Self> {
             matches
                 .subcommand_matches(Self::CMD)
-                .map(|matches| TxMintNft(args::NftMint::parse(matches)))
+                .map(|matches| Bond(args::Bond::parse(matches)))
         }
 
         fn def() -> App {
             App::new(Self::CMD)
-                .about("Mint new NFT tokens.")
-                .add_args::<args::NftMint>()
+                .about("Bond tokens in PoS system.")
+                .add_args::<args::Bond>()
+        }
+    }
+
+    #[derive(Clone, Debug)]
+    pub struct Unbond(pub args::Unbond);
+
+    impl SubCmd for Unbond {
+        const CMD: &'static str = "unbond";
+
+        fn parse(matches: &ArgMatches) -> Option<Self> {
+            matches
+                .subcommand_matches(Self::CMD)
+                .map(|matches| Unbond(args::Unbond::parse(matches)))
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about("Unbond tokens from a PoS bond.")
+                .add_args::<args::Unbond>()
+        }
+    }
+
+    #[derive(Clone, Debug)]
+    pub struct Withdraw(pub args::Withdraw);
+
+    impl SubCmd for Withdraw {
+        const CMD: &'static str = "withdraw";
+
+        fn parse(matches: &ArgMatches) -> Option<Self> {
+            matches
+                .subcommand_matches(Self::CMD)
+                .map(|matches| Withdraw(args::Withdraw::parse(matches)))
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about("Withdraw tokens from previously unbonded PoS bond.")
+                .add_args::<args::Withdraw>()
+        }
+    }
+
+    #[derive(Clone, Debug)]
+    pub struct TxChangeConsensusKey(pub args::TxChangeConsensusKey);
+
+    impl SubCmd for TxChangeConsensusKey {
+        const CMD: &'static str = "change-consensus-key";
+
+        fn parse(matches: &ArgMatches) -> Option<Self> {
+            matches.subcommand_matches(Self::CMD).map(|matches| {
+                TxChangeConsensusKey(args::TxChangeConsensusKey::parse(matches))
+            })
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about(
+                    "Send a signed transaction to change a validator's \
+                     consensus key. The new key takes effect from the \
+                     pipeline epoch, from which the ledger node has to sign \
+                     with it.",
+                )
+                .add_args::<args::TxChangeConsensusKey>()
+        }
+    }
+
+    #[derive(Clone, Debug)]
+    pub struct TxUpdateValidatorMetadata(pub args::TxUpdateValidatorMetadata);
+
+    impl SubCmd for TxUpdateValidatorMetadata {
+        const CMD: &'static str = "update-validator-metadata";
+
+        fn parse(matches: &ArgMatches) -> Option<Self> {
+            matches.subcommand_matches(Self::CMD).map(|matches| {
+                TxUpdateValidatorMetadata(
+                    args::TxUpdateValidatorMetadata::parse(matches),
+                )
+            })
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about(
+                    "Send a signed transaction to register or update a \
+                     validator's metadata.",
+                )
+                .add_args::<args::TxUpdateValidatorMetadata>()
+        }
+    }
+
+    #[derive(Clone, Debug)]
+    pub struct QueryEpoch(pub args::Query);
+
+    impl SubCmd for QueryEpoch {
+        const CMD: &'static str = "epoch";
+
+        fn parse(matches: &ArgMatches) -> Option<Self> {
+            matches
+                .subcommand_matches(Self::CMD)
+                .map(|matches| QueryEpoch(args::Query::parse(matches)))
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about("Query the epoch of the last committed block.")
+                .add_args::<args::Query>()
+        }
+    }
+
+    #[derive(Clone, Debug)]
+    pub struct QueryBlockResults(pub args::QueryBlockResults);
+
+    impl SubCmd for QueryBlockResults {
+        const CMD: &'static str = "block-results";
+
+        fn parse(matches: &ArgMatches) -> Option<Self> {
+            matches.subcommand_matches(Self::CMD).map(|matches| {
+                QueryBlockResults(args::QueryBlockResults::parse(matches))
+            })
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about(
+                    "Query the results of a committed block: the results of \
+                     its transactions, its events, gas used, validator \
+                     updates and storage changes.",
+                )
+                .add_args::<args::QueryBlockResults>()
+        }
+    }
+
+    #[derive(Clone, Debug)]
+    pub struct QueryIndexedTxs(pub args::QueryIndexedTxs);
+
+    impl SubCmd for QueryIndexedTxs {
+        const CMD: &'static str = "indexed-txs";
+
+        fn parse(matches: &ArgMatches) -> Option<Self> {
+            matches.subcommand_matches(Self::CMD).map(|matches| {
+                QueryIndexedTxs(args::QueryIndexedTxs::parse(matches))
+            })
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about(
+                    "Query the transactions recorded by a node's transaction \
+                     indexer.",
+                )
+                .add_args::<args::QueryIndexedTxs>()
+        }
+    }
+
+    #[derive(Clone, Debug)]
+    pub struct QueryBalance(pub args::QueryBalance);
+
+    impl SubCmd for QueryBalance {
+        const CMD: &'static str = "balance";
+
+        fn parse(matches: &ArgMatches) -> Option<Self> {
+            matches
+                .subcommand_matches(Self::CMD)
+                .map(|matches| QueryBalance(args::QueryBalance::parse(matches)))
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about("Query balance(s) of tokens.")
+                .add_args::<args::QueryBalance>()
+        }
+    }
+
+    #[derive(Clone, Debug)]
+    pub struct QueryTokens(pub args::QueryTokens);
+
+    impl SubCmd for QueryTokens {
+        const CMD: &'static str = "tokens";
+
+        fn parse(matches: &ArgMatches) -> Option<Self> {
+            matches
+                .subcommand_matches(Self::CMD)
+                .map(|matches| QueryTokens(args::QueryTokens::parse(matches)))
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about("Query the metadata and the total supply of tokens.")
+                .add_args::<args::QueryTokens>()
+        }
+    }
+
+    #[derive(Clone, Debug)]
+    pub struct QueryBonds(pub args::QueryBonds);
+
+    impl SubCmd for QueryBonds {
+        const CMD: &'static str = "bonds";
+
+        fn parse(matches: &ArgMatches) -> Option<Self> {
+            matches
+                .subcommand_matches(Self::CMD)
+                .map(|matches| QueryBonds(args::QueryBonds::parse(matches)))
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about("Query PoS bond(s).")
+                .add_args::<args::QueryBonds>()
+        }
+    }
+
+    #[derive(Clone, Debug)]
+    pub struct QueryVotingPower(pub args::QueryVotingPower);
+
+    impl SubCmd for QueryVotingPower {
+        const CMD: &'static str = "voting-power";
+
+        fn parse(matches: &ArgMatches) -> Option<Self> {
+            matches.subcommand_matches(Self::CMD).map(|matches| {
+                QueryVotingPower(args::QueryVotingPower::parse(matches))
+            })
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about("Query PoS voting power.")
+                .add_args::<args::QueryVotingPower>()
+        }
+    }
+
+    #[derive(Clone, Debug)]
+    pub struct QueryValidators(pub args::QueryValidators);
+
+    impl SubCmd for QueryValidators {
+        const CMD: &'static str = "validators";
+
+        fn parse(matches: &ArgMatches) -> Option<Self> {
+            matches.subcommand_matches(Self::CMD).map(|matches| {
+                QueryValidators(args::QueryValidators::parse(matches))
+            })
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about(
+                    "Query PoS validators with their state, stake and \
+                     scheduled stake changes.",
+                )
+                .add_args::<args::QueryValidators>()
+        }
+    }
+
+    #[derive(Clone, Debug)]
+    pub struct QuerySlashes(pub args::QuerySlashes);
+
+    impl SubCmd for QuerySlashes {
+        const CMD: &'static str = "slashes";
+
+        fn parse(matches: &ArgMatches) -> Option<Self>
+        where
+            Self: Sized,
+        {
+            matches
+                .subcommand_matches(Self::CMD)
+                .map(|matches| QuerySlashes(args::QuerySlashes::parse(matches)))
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about("Query PoS applied slashes.")
+                .add_args::<args::QuerySlashes>()
+        }
+    }
+
+    #[derive(Clone, Debug)]
+    pub struct QueryRawBytes(pub args::QueryRawBytes);
+
+    impl SubCmd for QueryRawBytes {
+        const CMD: &'static str = "query-bytes";
+
+        fn parse(matches: &ArgMatches) -> Option<Self> {
+            matches.subcommand_matches(Self::CMD).map(|matches| {
+                QueryRawBytes(args::QueryRawBytes::parse(matches))
+            })
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about("Query the raw bytes of a given storage key")
+                .add_args::<args::QueryRawBytes>()
+        }
+    }
+
+    #[derive(Clone, Debug)]
+    pub struct TxInitNft(pub args::NftCreate);
+
+    impl SubCmd for TxInitNft {
+        const CMD: &'static str = "init-nft";
+
+        fn parse(matches: &ArgMatches) -> Option<Self>
+        where
+            Self: Sized,
+        {
+            matches
+                .subcommand_matches(Self::CMD)
+                .map(|matches| TxInitNft(args::NftCreate::parse(matches)))
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about("Create a new NFT.")
+                .add_args::<args::NftCreate>()
+        }
+    }
+
+    #[derive(Clone, Debug)]
+    pub struct TxMintNft(pub args::NftMint);
+
+    impl SubCmd for TxMintNft {
+        const CMD: &'static str = "mint-nft";
+
+        fn parse(matches: &ArgMatches) -> Option<Self>
+        where
+            Self: Sized,
+        {
+            matches
+                .subcommand_matches(Self::CMD)
+                .map(|matches| TxMintNft(args::NftMint::parse(matches)))
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about("Mint new NFT tokens.")
+                .add_args::<args::NftMint>()
         }
     }
 
@@ -1259,11 +1818,36 @@ pub mod cmds {
         }
     }
 
+    #[derive(Clone, Debug)]
+    pub struct UnsubscribeTopic(pub args::UnsubscribeTopic);
+
+    impl SubCmd for UnsubscribeTopic {
+        const CMD: &'static str = "unsubscribe-topic";
+
+        fn parse(matches: &ArgMatches) -> Option<Self> {
+            matches.subcommand_matches(Self::CMD).map(|matches| {
+                UnsubscribeTopic(args::UnsubscribeTopic::parse(matches))
+            })
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about("Unsubscribe intent gossip node from a topic.")
+                .add_args::<args::UnsubscribeTopic>()
+        }
+    }
+
     #[derive(Clone, Debug)]
     pub enum Utils {
+        Init(Init),
         JoinNetwork(JoinNetwork),
         InitNetwork(InitNetwork),
+        InitLocalnet(InitLocalnet),
         InitGenesisValidator(InitGenesisValidator),
+        Db(Db),
+        VerifyState(VerifyState),
+        Bench(Bench),
+        TxGen(TxGen),
     }
 
     impl SubCmd for Utils {
@@ -1271,23 +1855,205 @@ pub mod cmds {
 
         fn parse(matches: &ArgMatches) -> Option<Self> {
             matches.subcommand_matches(Self::CMD).and_then(|matches| {
+                let init = SubCmd::parse(matches).map(Self::Init);
                 let join_network =
                     SubCmd::parse(matches).map(Self::JoinNetwork);
                 let init_network =
                     SubCmd::parse(matches).map(Self::InitNetwork);
+                let init_localnet =
+                    SubCmd::parse(matches).map(Self::InitLocalnet);
                 let init_genesis =
                     SubCmd::parse(matches).map(Self::InitGenesisValidator);
-                join_network.or(init_network).or(init_genesis)
+                let db = SubCmd::parse(matches).map(Self::Db);
+                let verify_state =
+                    SubCmd::parse(matches).map(Self::VerifyState);
+                let bench = SubCmd::parse(matches).map(Self::Bench);
+                let tx_gen = SubCmd::parse(matches).map(Self::TxGen);
+                init.or(join_network)
+                    .or(init_network)
+                    .or(init_localnet)
+                    .or(init_genesis)
+                    .or(db)
+                    .or(verify_state)
+                    .or(bench)
+                    .or(tx_gen)
+            })
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about("Utilities.")
+                .subcommand(Init::def())
+                .subcommand(JoinNetwork::def())
+                .subcommand(InitNetwork::def())
+                .subcommand(InitLocalnet::def())
+                .subcommand(InitGenesisValidator::def())
+                .subcommand(Db::def())
+                .subcommand(VerifyState::def())
+                .subcommand(Bench::def())
+                .subcommand(TxGen::def())
+                .setting(AppSettings::SubcommandRequiredElseHelp)
+        }
+    }
+
+    #[derive(Clone, Debug)]
+    pub enum Db {
+        Dump(DbDump),
+        Get(DbGet),
+    }
+
+    impl SubCmd for Db {
+        const CMD: &'static str = "db";
+
+        fn parse(matches: &ArgMatches) -> Option<Self> {
+            matches.subcommand_matches(Self::CMD).and_then(|matches| {
+                let dump = SubCmd::parse(matches).map(Self::Dump);
+                let get = SubCmd::parse(matches).map(Self::Get);
+                dump.or(get)
             })
         }
 
         fn def() -> App {
             App::new(Self::CMD)
-                .about("Utilities.")
-                .subcommand(JoinNetwork::def())
-                .subcommand(InitNetwork::def())
-                .subcommand(InitGenesisValidator::def())
-                .setting(AppSettings::SubcommandRequiredElseHelp)
+                .about(
+                    "Inspect the state in the ledger node's DB. The DB is \
+                     opened read-only, so it can be inspected while the node \
+                     is running.",
+                )
+                .subcommand(DbDump::def())
+                .subcommand(DbGet::def())
+                .setting(AppSettings::SubcommandRequiredElseHelp)
+        }
+    }
+
+    #[derive(Clone, Debug)]
+    pub struct VerifyState;
+
+    impl SubCmd for VerifyState {
+        const CMD: &'static str = "verify-state";
+
+        fn parse(matches: &ArgMatches) -> Option<Self> {
+            matches.subcommand_matches(Self::CMD).map(|_matches| Self)
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD).about(
+                "Recompute the Merkle root from all the stored storage keys \
+                 and values and compare it with the stored root of the last \
+                 committed block, to detect a corrupted DB.",
+            )
+        }
+    }
+
+    #[derive(Clone, Debug)]
+    pub struct Bench(pub args::Bench);
+
+    impl SubCmd for Bench {
+        const CMD: &'static str = "bench";
+
+        fn parse(matches: &ArgMatches) -> Option<Self> {
+            matches
+                .subcommand_matches(Self::CMD)
+                .map(|matches| Self(args::Bench::parse(matches)))
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about(
+                    "Run the ledger's benchmark workloads in a temporary \
+                     directory and print their mean durations.",
+                )
+                .add_args::<args::Bench>()
+        }
+    }
+
+    #[derive(Clone, Debug)]
+    pub struct TxGen(pub args::TxGen);
+
+    impl SubCmd for TxGen {
+        const CMD: &'static str = "txgen";
+
+        fn parse(matches: &ArgMatches) -> Option<Self> {
+            matches
+                .subcommand_matches(Self::CMD)
+                .map(|matches| Self(args::TxGen::parse(matches)))
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about(
+                    "Generate load on a network by broadcasting a mix of \
+                     transfers and custom transactions at a target rate and \
+                     report the mempool check latencies and rejections.",
+                )
+                .add_args::<args::TxGen>()
+        }
+    }
+
+    #[derive(Clone, Debug)]
+    pub struct DbDump(pub args::DbDump);
+
+    impl SubCmd for DbDump {
+        const CMD: &'static str = "dump";
+
+        fn parse(matches: &ArgMatches) -> Option<Self> {
+            matches
+                .subcommand_matches(Self::CMD)
+                .map(|matches| Self(args::DbDump::parse(matches)))
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about(
+                    "Print the storage keys and values of the accounts, in \
+                     hex and decoded for the known value types.",
+                )
+                .add_args::<args::DbDump>()
+        }
+    }
+
+    #[derive(Clone, Debug)]
+    pub struct DbGet(pub args::DbGet);
+
+    impl SubCmd for DbGet {
+        const CMD: &'static str = "get";
+
+        fn parse(matches: &ArgMatches) -> Option<Self> {
+            matches
+                .subcommand_matches(Self::CMD)
+                .map(|matches| Self(args::DbGet::parse(matches)))
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about(
+                    "Print the value of a storage key, in hex and decoded \
+                     for the known value types.",
+                )
+                .add_args::<args::DbGet>()
+        }
+    }
+
+    #[derive(Clone, Debug)]
+    pub struct Init(pub args::Init);
+
+    impl SubCmd for Init {
+        const CMD: &'static str = "init";
+
+        fn parse(matches: &ArgMatches) -> Option<Self> {
+            matches
+                .subcommand_matches(Self::CMD)
+                .map(|matches| Self(args::Init::parse(matches)))
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about(
+                    "Bootstrap a node for a chain: create the chain directory \
+                     with the default config, copy the genesis file and \
+                     initialize the Tendermint home directory.",
+                )
+                .add_args::<args::Init>()
         }
     }
 
@@ -1329,6 +2095,28 @@ pub mod cmds {
         }
     }
 
+    #[derive(Clone, Debug)]
+    pub struct InitLocalnet(pub args::InitLocalnet);
+
+    impl SubCmd for InitLocalnet {
+        const CMD: &'static str = "init-localnet";
+
+        fn parse(matches: &ArgMatches) -> Option<Self> {
+            matches
+                .subcommand_matches(Self::CMD)
+                .map(|matches| Self(args::InitLocalnet::parse(matches)))
+        }
+
+        fn def() -> App {
+            App::new(Self::CMD)
+                .about(
+                    "Initialize a local network with multiple validator nodes \
+                     on localhost, each with its own node directory.",
+                )
+                .add_args::<args::InitLocalnet>()
+        }
+    }
+
     #[derive(Clone, Debug)]
     pub struct InitGenesisValidator(pub args::InitGenesisValidator);
 
@@ -1362,12 +2150,14 @@ pub mod args {
     use std::path::PathBuf;
     use std::str::FromStr;
 
+    use anoma::ibc::core::ics24_host::identifier::{ChannelId, PortId};
     use anoma::types::address::Address;
     use anoma::types::chain::{ChainId, ChainIdPrefix};
     use anoma::types::governance::ProposalVote;
     use anoma::types::intent::{DecimalWrapper, Exchange};
     use anoma::types::key::*;
     use anoma::types::storage::{self, Epoch};
+    use anoma::types::time::{DateTimeUtc, Rfc3339String};
     use anoma::types::token;
     use anoma::types::transaction::GasLimit;
     use libp2p::Multiaddr;
@@ -1384,15 +2174,18 @@ pub mod args {
     use super::context::{WalletAddress, WalletKeypair, WalletPublicKey};
     use super::utils::*;
     use super::ArgMatches;
-    use crate::config;
-    use crate::config::TendermintMode;
+    use crate::config::{ConfigOverride, TendermintMode};
+    use crate::wallet::hd::DerivationPath;
+    use crate::{config, logging};
 
+    const ADDRESS_OPT: ArgOpt<WalletAddress> = ADDRESS.opt();
     const ADDRESS: Arg<WalletAddress> = arg("address");
     const ALIAS_OPT: ArgOpt<String> = ALIAS.opt();
     const ALIAS: Arg<String> = arg("alias");
     const ALLOW_DUPLICATE_IP: ArgFlag = flag("allow-duplicate-ip");
     const AMOUNT: Arg<token::Amount> = arg("amount");
     const ARCHIVE_DIR: ArgOpt<PathBuf> = arg_opt("archive-dir");
+    const ARCHIVE_HASH: ArgOpt<String> = arg_opt("archive-hash");
     const BASE_DIR: ArgDefault<PathBuf> = arg_default(
         "base-dir",
         DefaultFn(|| match env::var("ANOMA_BASE_DIR") {
@@ -1400,30 +2193,55 @@ pub mod args {
             Err(_) => config::DEFAULT_BASE_DIR.into(),
         }),
     );
+    const BROADCAST_MODE: ArgDefault<BroadcastMode> =
+        arg_default("broadcast-mode", DefaultFn(|| BroadcastMode::Commit));
     const BROADCAST_ONLY: ArgFlag = flag("broadcast-only");
     const CHAIN_ID: Arg<ChainId> = arg("chain-id");
+    const CHANNEL_ID: Arg<ChannelId> = arg("channel-id");
     const CHAIN_ID_OPT: ArgOpt<ChainId> = CHAIN_ID.opt();
     const CHAIN_ID_PREFIX: Arg<ChainIdPrefix> = arg("chain-prefix");
     const CODE_PATH: Arg<PathBuf> = arg("code-path");
     const CODE_PATH_OPT: ArgOpt<PathBuf> = CODE_PATH.opt();
+    const CODE_PATHS: ArgMulti<PathBuf> = arg_multi("code-paths");
+    const COMMISSION_RATE: Arg<u64> = arg("commission-rate");
+    const CONFIG_OVERRIDES: ArgMulti<ConfigOverride> = arg_multi("config");
+    const CONFIGS_SERVER: ArgOpt<String> = arg_opt("configs-server");
     const CONSENSUS_TIMEOUT_COMMIT: ArgDefault<Timeout> = arg_default(
         "consensus-timeout-commit",
         DefaultFn(|| Timeout::from_str("1s").unwrap()),
     );
+    const CONTACT: ArgOpt<String> = arg_opt("contact");
+    const COUNT: ArgDefault<u64> = arg_default("count", DefaultFn(|| 100));
+    const CUSTOM_SHARE: ArgDefault<u8> =
+        arg_default("custom-share", DefaultFn(|| 0));
     const DATA_PATH_OPT: ArgOpt<PathBuf> = arg_opt("data-path");
     const DATA_PATH: Arg<PathBuf> = arg("data-path");
+    const DATA_PATHS: ArgMulti<PathBuf> = arg_multi("data-paths");
+    const DB_DIR: ArgOpt<PathBuf> = arg_opt("db-dir");
+    const DECIMALS: ArgDefault<u8> = arg_default("decimals", DefaultFn(|| 6));
     const DECRYPT: ArgFlag = flag("decrypt");
+    const DERIVATION_PATH: ArgDefault<DerivationPath> =
+        arg_default("derivation-path", DefaultFn(DerivationPath::default));
     const DONT_ARCHIVE: ArgFlag = flag("dont-archive");
     const DRY_RUN_TX: ArgFlag = flag("dry-run");
+    const DUMP_TX: ArgOpt<PathBuf> = arg_opt("dump-tx");
     const EPOCH: ArgOpt<Epoch> = arg_opt("epoch");
+    const EXPIRATION: ArgOpt<DateTimeUtc> = arg_opt("expiration");
+    const FIRST_PORT: ArgDefault<u16> =
+        arg_default("first-port", DefaultFn(|| 27656));
     const FEE_AMOUNT: ArgDefault<token::Amount> =
         arg_default("fee-amount", DefaultFn(|| token::Amount::from(0)));
     const FEE_TOKEN: ArgDefaultFromCtx<WalletAddress> =
         arg_default_from_ctx("fee-token", DefaultFn(|| "XAN".into()));
+    const FILE_PATH: Arg<PathBuf> = arg("file-path");
     const FORCE: ArgFlag = flag("force");
+    const FROM_HEIGHT: Arg<u64> = arg("from");
     const GAS_LIMIT: ArgDefault<token::Amount> =
         arg_default("gas-limit", DefaultFn(|| token::Amount::from(0)));
     const GENESIS_PATH: Arg<PathBuf> = arg("genesis-path");
+    const GENESIS_PATH_OPT: ArgOpt<PathBuf> = GENESIS_PATH.opt();
+    const HD: ArgFlag = flag("hd");
+    const HEIGHT: ArgOpt<u64> = arg_opt("height");
     const GENESIS_VALIDATOR: ArgOpt<String> = arg("genesis-validator").opt();
     const INTENT_GOSSIPER_ADDR: ArgDefault<SocketAddr> = arg_default(
         "intent-gossiper",
@@ -1432,6 +2250,7 @@ pub mod args {
             SocketAddr::from_str(raw).unwrap()
         }),
     );
+    const KEY_PREFIX: ArgOpt<String> = arg_opt("key-prefix");
     const LEDGER_ADDRESS_ABOUT: &str =
         "Address of a ledger node as \"{scheme}://{host}:{port}\". If the \
          scheme is not supplied, it is assumed to be TCP.";
@@ -1442,40 +2261,72 @@ pub mod args {
         }));
 
     const LEDGER_ADDRESS: Arg<TendermintAddress> = arg("ledger-address");
+    const LEDGER_ADDRESS_OPT: ArgOpt<TendermintAddress> = LEDGER_ADDRESS.opt();
+    const KEEP_ADDR_BOOK: ArgFlag = flag("keep-addr-book");
+    const KEEP_KEYS: ArgFlag = flag("keep-keys");
     const LOCALHOST: ArgFlag = flag("localhost");
+    const LOG_LEVEL: ArgOpt<String> = arg_opt(logging::ARG_KEY);
     const MATCHMAKER_PATH: ArgOpt<PathBuf> = arg_opt("matchmaker-path");
+    const MAX_COMMISSION_RATE_CHANGE: ArgOpt<u64> =
+        arg_opt("max-commission-rate-change");
+    const MIN_SELL: ArgOpt<token::Amount> = arg_opt("min-sell");
+    const MINTER: ArgOpt<WalletAddress> = arg_opt("minter");
     const MODE: ArgOpt<String> = arg_opt("mode");
+    const MONIKER: Arg<String> = arg("moniker");
     const MULTIADDR_OPT: ArgOpt<Multiaddr> = arg_opt("address");
     const NET_ADDRESS: Arg<SocketAddr> = arg("net-address");
     const NODE_OPT: ArgOpt<String> = arg_opt("node");
     const NODE: Arg<String> = arg("node");
     const NFT_ADDRESS: Arg<Address> = arg("nft-address");
-    const OWNER: ArgOpt<WalletAddress> = arg_opt("owner");
+    const OUTPUT: ArgDefault<OutputFormat> =
+        arg_default("output", DefaultFn(|| OutputFormat::Human));
+    const OUTPUT_FILE: ArgOpt<PathBuf> = arg_opt("output-file");
+    const OWNER: Arg<WalletAddress> = arg("owner");
+    const OWNER_OPT: ArgOpt<WalletAddress> = OWNER.opt();
+    const PORT_ID: ArgDefault<PortId> =
+        arg_default("port-id", DefaultFn(PortId::transfer));
+    const PREFIX: ArgOpt<storage::Key> = arg_opt("prefix");
     const PROPOSAL_OFFLINE: ArgFlag = flag("offline");
     const PROTOCOL_KEY: ArgOpt<WalletPublicKey> = arg_opt("protocol-key");
     const PRE_GENESIS_PATH: ArgOpt<PathBuf> = arg_opt("pre-genesis-path");
     const PUBLIC_KEY: Arg<WalletPublicKey> = arg("public-key");
+    const PUBLIC_KEYS: ArgMulti<WalletPublicKey> = arg_multi("public-keys");
     const PROPOSAL_ID: Arg<u64> = arg("proposal-id");
     const PROPOSAL_ID_OPT: ArgOpt<u64> = arg_opt("proposal-id");
     const PROPOSAL_VOTE: Arg<ProposalVote> = arg("vote");
+    const RATE: ArgDefault<u32> = arg_default("rate", DefaultFn(|| 10));
     const RAW_ADDRESS: Arg<Address> = arg("address");
     const RAW_PUBLIC_KEY_OPT: ArgOpt<common::PublicKey> = arg_opt("public-key");
+    const RECEIVER: Arg<String> = arg("receiver");
+    const RESTORE: ArgFlag = flag("restore");
     const REWARDS_CODE_PATH: ArgOpt<PathBuf> = arg_opt("rewards-code-path");
     const REWARDS_KEY: ArgOpt<WalletPublicKey> = arg_opt("rewards-key");
     const RPC_SOCKET_ADDR: ArgOpt<SocketAddr> = arg_opt("rpc");
+    const RUNS: ArgDefault<u32> = arg_default("runs", DefaultFn(|| 10));
+    const SIGNATURE_FILES: ArgMulti<PathBuf> = arg_multi("signature-files");
+    const SIGNED_FILE: Arg<PathBuf> = arg("signed-file");
     const SIGNER: ArgOpt<WalletAddress> = arg_opt("signer");
     const SIGNING_KEY_OPT: ArgOpt<WalletKeypair> = SIGNING_KEY.opt();
     const SIGNING_KEY: Arg<WalletKeypair> = arg("signing-key");
     const SOURCE: Arg<WalletAddress> = arg("source");
     const SOURCE_OPT: ArgOpt<WalletAddress> = SOURCE.opt();
     const STORAGE_KEY: Arg<storage::Key> = arg("storage-key");
+    const SYMBOL: Arg<String> = arg("symbol");
     const TARGET: Arg<WalletAddress> = arg("target");
+    const TEST_WASM_DIR: ArgDefault<PathBuf> =
+        arg_default("test-wasm-dir", DefaultFn(|| "wasm_for_tests".into()));
+    const THRESHOLD: Arg<u8> = arg("threshold");
+    const TIMEOUT_HEIGHT: ArgOpt<u64> = arg_opt("timeout-height");
+    const TIMEOUT_SEC_OFFSET: ArgOpt<u64> = arg_opt("timeout-sec-offset");
+    const TO_HEIGHT: Arg<u64> = arg("to");
     const TO_STDOUT: ArgFlag = flag("stdout");
     const TOKEN_OPT: ArgOpt<WalletAddress> = TOKEN.opt();
     const TOKEN: Arg<WalletAddress> = arg("token");
+    const TOKEN_PAIRS: ArgMulti<String> = arg_multi("token-pairs");
     const TOPIC_OPT: ArgOpt<String> = arg_opt("topic");
     const TOPIC: Arg<String> = arg("topic");
     const TX_CODE_PATH: ArgOpt<PathBuf> = arg_opt("tx-code-path");
+    const TX_FILE: Arg<PathBuf> = arg("tx-file");
     const TX_HASH: Arg<String> = arg("tx-hash");
     const UNSAFE_DONT_ENCRYPT: ArgFlag = flag("unsafe-dont-encrypt");
     const UNSAFE_SHOW_SECRET: ArgFlag = flag("unsafe-show-secret");
@@ -1487,8 +2338,12 @@ pub mod args {
         arg_opt("consensus-key");
     const VALIDATOR_CODE_PATH: ArgOpt<PathBuf> = arg_opt("validator-code-path");
     const VALUE: ArgOpt<String> = arg_opt("value");
+    const VALIDATORS: ArgDefault<u64> =
+        arg_default("validators", DefaultFn(|| 4));
+    const WAIT_ACK: ArgFlag = flag("wait-ack");
     const WASM_CHECKSUMS_PATH: Arg<PathBuf> = arg("wasm-checksums-path");
     const WASM_DIR: ArgOpt<PathBuf> = arg_opt("wasm-dir");
+    const WEBSITE: ArgOpt<String> = arg_opt("website");
 
     /// Global command arguments
     #[derive(Clone, Debug)]
@@ -1497,6 +2352,10 @@ pub mod args {
         pub base_dir: PathBuf,
         pub wasm_dir: Option<PathBuf>,
         pub mode: Option<TendermintMode>,
+        /// The format of the client commands' output
+        pub output: OutputFormat,
+        /// The overrides of the config values
+        pub config_overrides: Vec<ConfigOverride>,
     }
 
     impl Global {
@@ -1506,37 +2365,78 @@ pub mod args {
             let base_dir = BASE_DIR.parse(matches);
             let wasm_dir = WASM_DIR.parse(matches);
             let mode = MODE.parse(matches).map(TendermintMode::from);
+            let output = OUTPUT.parse(matches);
+            let config_overrides = CONFIG_OVERRIDES.parse(matches);
             Global {
                 chain_id,
                 base_dir,
                 wasm_dir,
                 mode,
+                output,
+                config_overrides,
             }
         }
 
         /// Add global args definition. Should be added to every top-level
         /// command.
         pub fn def(app: App) -> App {
-            app.arg(CHAIN_ID_OPT.def().about("The chain ID."))
-                .arg(BASE_DIR.def().about(
-                    "The base directory is where the nodes, client and wallet \
-                     configuration and state is stored. This value can also \
-                     be set via `ANOMA_BASE_DIR` environment variable, but \
-                     the argument takes precedence, if specified. Defaults to \
-                     `.anoma`.",
-                ))
-                .arg(WASM_DIR.def().about(
-                    "Directory with built WASM validity predicates, \
-                     transactions and matchmaker files. This must not be an \
-                     absolute path as the directory is nested inside the \
-                     chain directory. This value can also be set via \
-                     `ANOMA_WASM_DIR` environment variable, but the argument \
-                     takes precedence, if specified.",
-                ))
-                .arg(MODE.def().about(
-                    "The mode in which to run Anoma. Options are \n\t * \
-                     Validator (default)\n\t * Full\n\t * Seed",
-                ))
+            app.arg(CHAIN_ID_OPT.def().about(
+                "The chain ID. Selects the chain whose state in the base \
+                 directory is used by the command. This value can also be set \
+                 via `ANOMA_CHAIN_ID` environment variable, but the argument \
+                 takes precedence, if specified. Defaults to the default \
+                 chain ID set in the global config.",
+            ))
+            .arg(BASE_DIR.def().about(
+                "The base directory is where the nodes, client and wallet \
+                 configuration and state is stored. This value can also be \
+                 set via `ANOMA_BASE_DIR` environment variable, but the \
+                 argument takes precedence, if specified. Defaults to \
+                 `.anoma`.",
+            ))
+            .arg(WASM_DIR.def().about(
+                "Directory with built WASM validity predicates, transactions \
+                 and matchmaker files. This must not be an absolute path as \
+                 the directory is nested inside the chain directory. This \
+                 value can also be set via `ANOMA_WASM_DIR` environment \
+                 variable, but the argument takes precedence, if specified.",
+            ))
+            .arg(MODE.def().about(
+                "The mode in which to run Anoma. Options are \n\t * Validator \
+                 (default)\n\t * Full\n\t * Seed",
+            ))
+            .arg(
+                OUTPUT
+                    .def()
+                    .about(
+                        "The format of the client commands' output, either \
+                         \"human\" or \"json\". With \"json\", the results \
+                         are printed as JSON to the standard output and any \
+                         other messages to the standard error. Defaults to \
+                         \"human\".",
+                    )
+                    .possible_values(&["human", "json"]),
+            )
+            .arg(
+                CONFIG_OVERRIDES
+                    .def()
+                    .multiple(false)
+                    .multiple_occurrences(true)
+                    .about(
+                        "Override a config value as `key=value`, where the \
+                         key is the dot-separated path of the value in the \
+                         config file, e.g. \
+                         `ledger.shell.ledger_address=127.0.0.1:26658`. Can \
+                         be repeated. The overrides take precedence over the \
+                         config file and the `ANOMA_*` environment variables \
+                         with `__` separated keys, e.g. \
+                         `ANOMA_LEDGER__SHELL__LEDGER_ADDRESS`.",
+                    ),
+            )
+            .arg(LOG_LEVEL.def().about(
+                "The log filter directives, e.g. `info` or `anoma=debug`. \
+                 Takes precedence over the `ANOMA_LOG` environment variable.",
+            ))
         }
     }
 
@@ -1603,6 +2503,44 @@ pub mod args {
         }
     }
 
+    /// Batch transaction arguments
+    #[derive(Clone, Debug)]
+    pub struct TxBatch {
+        /// Common tx arguments
+        pub tx: Tx,
+        /// Paths to the WASM code files of the inner txs, in order
+        pub code_paths: Vec<PathBuf>,
+        /// Paths to the data files of the inner txs, in the same order as
+        /// their codes
+        pub data_paths: Vec<PathBuf>,
+    }
+
+    impl Args for TxBatch {
+        fn parse(matches: &ArgMatches) -> Self {
+            let tx = Tx::parse(matches);
+            let code_paths = CODE_PATHS.parse(matches);
+            let data_paths = DATA_PATHS.parse(matches);
+            Self {
+                tx,
+                code_paths,
+                data_paths,
+            }
+        }
+
+        fn def(app: App) -> App {
+            app.add_args::<Tx>()
+                .arg(CODE_PATHS.def().required(true).about(
+                    "The paths to the WASM codes of the transactions in the \
+                     batch, in the order of their execution.",
+                ))
+                .arg(DATA_PATHS.def().about(
+                    "The paths to the data files of the transactions in the \
+                     batch, in the same order as their codes. Either none or \
+                     all of the transactions must be given a data file.",
+                ))
+        }
+    }
+
     /// Transfer transaction arguments
     #[derive(Clone, Debug)]
     pub struct TxTransfer {
@@ -1646,6 +2584,88 @@ pub mod args {
         }
     }
 
+    /// IBC transfer transaction arguments
+    #[derive(Clone, Debug)]
+    pub struct TxIbcTransfer {
+        /// Common tx arguments
+        pub tx: Tx,
+        /// Transfer source address
+        pub source: WalletAddress,
+        /// Transfer target address on the counterparty chain
+        pub receiver: String,
+        /// Transferred token address
+        pub token: WalletAddress,
+        /// Transferred token amount
+        pub amount: token::Amount,
+        /// The port of the channel
+        pub port_id: PortId,
+        /// The channel to the counterparty chain
+        pub channel_id: ChannelId,
+        /// The block height of the counterparty chain after which the
+        /// transfer times out
+        pub timeout_height: Option<u64>,
+        /// The number of seconds from now after which the transfer times out
+        pub timeout_sec_offset: Option<u64>,
+        /// Wait for the acknowledgement of the transfer from the counterparty
+        /// chain
+        pub wait_ack: bool,
+    }
+
+    impl Args for TxIbcTransfer {
+        fn parse(matches: &ArgMatches) -> Self {
+            let tx = Tx::parse(matches);
+            let source = SOURCE.parse(matches);
+            let receiver = RECEIVER.parse(matches);
+            let token = TOKEN.parse(matches);
+            let amount = AMOUNT.parse(matches);
+            let port_id = PORT_ID.parse(matches);
+            let channel_id = CHANNEL_ID.parse(matches);
+            let timeout_height = TIMEOUT_HEIGHT.parse(matches);
+            let timeout_sec_offset = TIMEOUT_SEC_OFFSET.parse(matches);
+            let wait_ack = WAIT_ACK.parse(matches);
+            Self {
+                tx,
+                source,
+                receiver,
+                token,
+                amount,
+                port_id,
+                channel_id,
+                timeout_height,
+                timeout_sec_offset,
+                wait_ack,
+            }
+        }
+
+        fn def(app: App) -> App {
+            app.add_args::<Tx>()
+                .arg(SOURCE.def().about(
+                    "The source account address. The source's key is used to \
+                     produce the signature.",
+                ))
+                .arg(RECEIVER.def().about(
+                    "The receiver address on the counterparty chain.",
+                ))
+                .arg(TOKEN.def().about("The transfer token."))
+                .arg(AMOUNT.def().about("The amount to transfer in decimal."))
+                .arg(PORT_ID.def().about("The port ID."))
+                .arg(CHANNEL_ID.def().about("The channel ID."))
+                .arg(TIMEOUT_HEIGHT.def().about(
+                    "The timeout height of the counterparty chain. Without a \
+                     timeout height or offset, the transfer times out after \
+                     an hour.",
+                ))
+                .arg(TIMEOUT_SEC_OFFSET.def().about(
+                    "The timeout as seconds from now. Without a timeout \
+                     height or offset, the transfer times out after an hour.",
+                ))
+                .arg(WAIT_ACK.def().about(
+                    "Wait until the transfer is acknowledged by the \
+                     counterparty chain.",
+                ))
+        }
+    }
+
     /// Transaction to initialize a new account
     #[derive(Clone, Debug)]
     pub struct TxInitAccount {
@@ -1690,6 +2710,184 @@ pub mod args {
         }
     }
 
+    /// Transaction to initialize a new multisignature account
+    #[derive(Clone, Debug)]
+    pub struct TxInitMultisigAccount {
+        /// Common tx arguments
+        pub tx: Tx,
+        /// Address of the source account
+        pub source: WalletAddress,
+        /// Path to the VP WASM code file for the new account
+        pub vp_code_path: Option<PathBuf>,
+        /// Public keys of the signers of the new account
+        pub public_keys: Vec<WalletPublicKey>,
+        /// The number of signers required to sign a transaction
+        pub threshold: u8,
+    }
+
+    impl Args for TxInitMultisigAccount {
+        fn parse(matches: &ArgMatches) -> Self {
+            let tx = Tx::parse(matches);
+            let source = SOURCE.parse(matches);
+            let vp_code_path = CODE_PATH_OPT.parse(matches);
+            let public_keys = PUBLIC_KEYS.parse(matches);
+            let threshold = THRESHOLD.parse(matches);
+            Self {
+                tx,
+                source,
+                vp_code_path,
+                public_keys,
+                threshold,
+            }
+        }
+
+        fn def(app: App) -> App {
+            app.add_args::<Tx>()
+                .arg(SOURCE.def().about(
+                    "The source account's address that signs the transaction.",
+                ))
+                .arg(CODE_PATH_OPT.def().about(
+                    "The path to the validity predicate WASM code to be used \
+                     for the new account. Uses the default multisignature VP \
+                     if none specified.",
+                ))
+                .arg(PUBLIC_KEYS.def().required(true).about(
+                    "The public keys of the signers of the new account in \
+                     hexadecimal encoding or their aliases. The order of the \
+                     keys determines the signers' indices.",
+                ))
+                .arg(THRESHOLD.def().about(
+                    "The number of signers required to sign a transaction of \
+                     the new account.",
+                ))
+        }
+    }
+
+    /// Transaction to initialize a new token account
+    #[derive(Clone, Debug)]
+    pub struct TxInitToken {
+        /// Common tx arguments
+        pub tx: Tx,
+        /// Address of the source account
+        pub source: WalletAddress,
+        /// Path to the VP WASM code file for the new token
+        pub vp_code_path: Option<PathBuf>,
+        /// The token symbol
+        pub symbol: String,
+        /// The number of decimal places of the token amounts
+        pub decimals: u8,
+        /// An optional address allowed to mint and burn the token
+        pub minter: Option<WalletAddress>,
+    }
+
+    impl Args for TxInitToken {
+        fn parse(matches: &ArgMatches) -> Self {
+            let tx = Tx::parse(matches);
+            let source = SOURCE.parse(matches);
+            let vp_code_path = CODE_PATH_OPT.parse(matches);
+            let symbol = SYMBOL.parse(matches);
+            let decimals = DECIMALS.parse(matches);
+            let minter = MINTER.parse(matches);
+            Self {
+                tx,
+                source,
+                vp_code_path,
+                symbol,
+                decimals,
+                minter,
+            }
+        }
+
+        fn def(app: App) -> App {
+            app.add_args::<Tx>()
+                .arg(SOURCE.def().about(
+                    "The source account's address that signs the transaction.",
+                ))
+                .arg(CODE_PATH_OPT.def().about(
+                    "The path to the validity predicate WASM code to be used \
+                     for the new token. Uses the default token VP if none \
+                     specified.",
+                ))
+                .arg(SYMBOL.def().about(
+                    "The symbol of the new token, e.g. \"BTC\". It must be \
+                     non-empty and contain only ASCII alphanumeric characters.",
+                ))
+                .arg(
+                    DECIMALS
+                        .def()
+                        .about("The number of decimal places of the token."),
+                )
+                .arg(MINTER.def().about(
+                    "An optional address that is allowed to mint and burn the \
+                     new token.",
+                ))
+        }
+    }
+
+    /// Transaction to mint or burn tokens
+    #[derive(Clone, Debug)]
+    pub struct TxChangeSupply {
+        /// Common tx arguments
+        pub tx: Tx,
+        /// The token's address
+        pub token: WalletAddress,
+        /// The owner whose balance is credited or debited
+        pub owner: WalletAddress,
+        /// The amount of tokens to mint or burn
+        pub amount: token::Amount,
+    }
+
+    impl Args for TxChangeSupply {
+        fn parse(matches: &ArgMatches) -> Self {
+            let tx = Tx::parse(matches);
+            let token = TOKEN.parse(matches);
+            let owner = OWNER.parse(matches);
+            let amount = AMOUNT.parse(matches);
+            Self {
+                tx,
+                token,
+                owner,
+                amount,
+            }
+        }
+
+        fn def(app: App) -> App {
+            app.add_args::<Tx>()
+                .arg(TOKEN.def().about("The token's address."))
+                .arg(
+                    OWNER.def().about(
+                        "The account whose balance is credited or debited.",
+                    ),
+                )
+                .arg(AMOUNT.def().about("The amount of tokens."))
+        }
+    }
+
+    /// Transaction to reveal a public key
+    #[derive(Clone, Debug)]
+    pub struct TxRevealPk {
+        /// Common tx arguments
+        pub tx: Tx,
+        /// The public key to reveal
+        pub public_key: WalletPublicKey,
+    }
+
+    impl Args for TxRevealPk {
+        fn parse(matches: &ArgMatches) -> Self {
+            let tx = Tx::parse(matches);
+            let public_key = PUBLIC_KEY.parse(matches);
+            Self { tx, public_key }
+        }
+
+        fn def(app: App) -> App {
+            app.add_args::<Tx>().arg(PUBLIC_KEY.def().about(
+                "The public key to reveal in hexadecimal encoding or its \
+                 alias. The transaction is signed with this key unless a \
+                 different signer is specified.",
+            ))
+        }
+    }
+
     /// Transaction to initialize a new account
     #[derive(Clone, Debug)]
     pub struct TxInitValidator {
@@ -2061,54 +3259,157 @@ pub mod args {
     }
 
     #[derive(Clone, Debug)]
-    pub struct QueryProtocolParameters {
-        /// Common query args
-        pub query: Query,
+    pub struct QueryProtocolParameters {
+        /// Common query args
+        pub query: Query,
+    }
+
+    impl Args for QueryProtocolParameters {
+        fn parse(matches: &ArgMatches) -> Self {
+            let query = Query::parse(matches);
+
+            Self { query }
+        }
+
+        fn def(app: App) -> App {
+            app.add_args::<Query>()
+        }
+    }
+
+    /// Withdraw arguments
+    #[derive(Clone, Debug)]
+    pub struct Withdraw {
+        /// Common tx arguments
+        pub tx: Tx,
+        /// Validator address
+        pub validator: WalletAddress,
+        /// Source address for withdrawing from delegations. For withdrawing
+        /// from self-bonds, the validator is also the source
+        pub source: Option<WalletAddress>,
+    }
+
+    impl Args for Withdraw {
+        fn parse(matches: &ArgMatches) -> Self {
+            let tx = Tx::parse(matches);
+            let validator = VALIDATOR.parse(matches);
+            let source = SOURCE_OPT.parse(matches);
+            Self {
+                tx,
+                validator,
+                source,
+            }
+        }
+
+        fn def(app: App) -> App {
+            app.add_args::<Tx>()
+                .arg(VALIDATOR.def().about("Validator address."))
+                .arg(SOURCE_OPT.def().about(
+                    "Source address for withdrawing from delegations. For \
+                     withdrawing from self-bonds, the validator is also the \
+                     source.",
+                ))
+        }
+    }
+
+    /// Consensus key change arguments
+    #[derive(Clone, Debug)]
+    pub struct TxChangeConsensusKey {
+        /// Common tx arguments
+        pub tx: Tx,
+        /// Validator address
+        pub validator: WalletAddress,
+        /// The new consensus key
+        pub consensus_key: Option<WalletKeypair>,
+        /// Don't encrypt the generated keypair
+        pub unsafe_dont_encrypt: bool,
     }
 
-    impl Args for QueryProtocolParameters {
+    impl Args for TxChangeConsensusKey {
         fn parse(matches: &ArgMatches) -> Self {
-            let query = Query::parse(matches);
-
-            Self { query }
+            let tx = Tx::parse(matches);
+            let validator = VALIDATOR.parse(matches);
+            let consensus_key = VALIDATOR_CONSENSUS_KEY.parse(matches);
+            let unsafe_dont_encrypt = UNSAFE_DONT_ENCRYPT.parse(matches);
+            Self {
+                tx,
+                validator,
+                consensus_key,
+                unsafe_dont_encrypt,
+            }
         }
 
         fn def(app: App) -> App {
-            app.add_args::<Query>()
+            app.add_args::<Tx>()
+                .arg(VALIDATOR.def().about("Validator address."))
+                .arg(VALIDATOR_CONSENSUS_KEY.def().about(
+                    "The new consensus key for the validator. A new one will \
+                     be generated if none given.",
+                ))
+                .arg(UNSAFE_DONT_ENCRYPT.def().about(
+                    "UNSAFE: Do not encrypt the generated keypair. Do not use \
+                     this for keys used in a live network.",
+                ))
         }
     }
 
-    /// Withdraw arguments
+    /// Validator metadata update arguments
     #[derive(Clone, Debug)]
-    pub struct Withdraw {
+    pub struct TxUpdateValidatorMetadata {
         /// Common tx arguments
         pub tx: Tx,
         /// Validator address
         pub validator: WalletAddress,
-        /// Source address for withdrawing from delegations. For withdrawing
-        /// from self-bonds, the validator is also the source
-        pub source: Option<WalletAddress>,
-    }
-
-    impl Args for Withdraw {
+        /// A human-readable name of the validator
+        pub moniker: String,
+        /// The website of the validator
+        pub website: Option<String>,
+        /// Contact information of the validator
+        pub contact: Option<String>,
+        /// The commission rate in basis points
+        pub commission_rate: u64,
+        /// The maximum commission rate change per epoch in basis points
+        pub max_commission_rate_change: Option<u64>,
+    }
+
+    impl Args for TxUpdateValidatorMetadata {
         fn parse(matches: &ArgMatches) -> Self {
             let tx = Tx::parse(matches);
             let validator = VALIDATOR.parse(matches);
-            let source = SOURCE_OPT.parse(matches);
+            let moniker = MONIKER.parse(matches);
+            let website = WEBSITE.parse(matches);
+            let contact = CONTACT.parse(matches);
+            let commission_rate = COMMISSION_RATE.parse(matches);
+            let max_commission_rate_change =
+                MAX_COMMISSION_RATE_CHANGE.parse(matches);
             Self {
                 tx,
                 validator,
-                source,
+                moniker,
+                website,
+                contact,
+                commission_rate,
+                max_commission_rate_change,
             }
         }
 
         fn def(app: App) -> App {
             app.add_args::<Tx>()
                 .arg(VALIDATOR.def().about("Validator address."))
-                .arg(SOURCE_OPT.def().about(
-                    "Source address for withdrawing from delegations. For \
-                     withdrawing from self-bonds, the validator is also the \
-                     source.",
+                .arg(MONIKER.def().about("A human-readable validator name."))
+                .arg(WEBSITE.def().about("The validator's website."))
+                .arg(
+                    CONTACT
+                        .def()
+                        .about("The validator's contact, e.g. an e-mail."),
+                )
+                .arg(COMMISSION_RATE.def().about(
+                    "The portion of staking rewards taken by the validator \
+                     from its delegators in basis points (1/100 of a percent).",
+                ))
+                .arg(MAX_COMMISSION_RATE_CHANGE.def().about(
+                    "The maximum change of the commission rate in a single \
+                     epoch in basis points. Required when the metadata are \
+                     first registered, after which it cannot be changed.",
                 ))
         }
     }
@@ -2187,7 +3488,7 @@ pub mod args {
     impl Args for QueryBalance {
         fn parse(matches: &ArgMatches) -> Self {
             let query = Query::parse(matches);
-            let owner = OWNER.parse(matches);
+            let owner = OWNER_OPT.parse(matches);
             let token = TOKEN_OPT.parse(matches);
             Self {
                 query,
@@ -2199,7 +3500,7 @@ pub mod args {
         fn def(app: App) -> App {
             app.add_args::<Query>()
                 .arg(
-                    OWNER
+                    OWNER_OPT
                         .def()
                         .about("The account address whose balance to query."),
                 )
@@ -2211,6 +3512,97 @@ pub mod args {
         }
     }
 
+    /// Query token metadata and supply
+    #[derive(Clone, Debug)]
+    pub struct QueryTokens {
+        /// Common query args
+        pub query: Query,
+        /// Address of a token
+        pub token: Option<WalletAddress>,
+    }
+
+    impl Args for QueryTokens {
+        fn parse(matches: &ArgMatches) -> Self {
+            let query = Query::parse(matches);
+            let token = TOKEN_OPT.parse(matches);
+            Self { query, token }
+        }
+
+        fn def(app: App) -> App {
+            app.add_args::<Query>().arg(TOKEN_OPT.def().about(
+                "The token's address to query. Lists all the known tokens if \
+                 none specified.",
+            ))
+        }
+    }
+
+    /// Query block results arguments
+    #[derive(Clone, Debug)]
+    pub struct QueryBlockResults {
+        /// Common query args
+        pub query: Query,
+        /// The height of the block, defaults to the last committed block
+        pub height: Option<u64>,
+    }
+
+    impl Args for QueryBlockResults {
+        fn parse(matches: &ArgMatches) -> Self {
+            let query = Query::parse(matches);
+            let height = HEIGHT.parse(matches);
+            Self { query, height }
+        }
+
+        fn def(app: App) -> App {
+            app.add_args::<Query>().arg(HEIGHT.def().about(
+                "The height of the block. Defaults to the last committed \
+                 block.",
+            ))
+        }
+    }
+
+    /// Query indexed transactions arguments
+    #[derive(Clone, Debug)]
+    pub struct QueryIndexedTxs {
+        /// Common query args
+        pub query: Query,
+        /// Find the transactions that touched this address
+        pub address: Option<WalletAddress>,
+        /// Find the transactions that changed a storage key with this prefix
+        pub key_prefix: Option<String>,
+    }
+
+    impl Args for QueryIndexedTxs {
+        fn parse(matches: &ArgMatches) -> Self {
+            let query = Query::parse(matches);
+            let address = ADDRESS_OPT.parse(matches);
+            let key_prefix = KEY_PREFIX.parse(matches);
+            Self {
+                query,
+                address,
+                key_prefix,
+            }
+        }
+
+        fn def(app: App) -> App {
+            app.add_args::<Query>()
+                .arg(
+                    ADDRESS_OPT
+                        .def()
+                        .about("Find the transactions that touched an address.")
+                        .conflicts_with(KEY_PREFIX.name),
+                )
+                .arg(
+                    KEY_PREFIX
+                        .def()
+                        .about(
+                            "Find the transactions that changed a storage key \
+                             with a prefix.",
+                        )
+                        .conflicts_with(ADDRESS_OPT.name),
+                )
+        }
+    }
+
     /// Helper struct for generating intents
     #[derive(Debug, Clone, Deserialize)]
     pub struct ExchangeDefinition {
@@ -2228,6 +3620,9 @@ pub mod args {
         pub min_buy: String,
         /// The path to the wasm vp code
         pub vp_path: Option<String>,
+        /// The RFC 3339 time after which the exchange can no longer be
+        /// matched
+        pub expiry: Option<String>,
     }
 
     impl TryFrom<ExchangeDefinition> for Exchange {
@@ -2259,6 +3654,10 @@ pub mod args {
                 .expect("Max_sell must be convertible to number");
             let rate_min = DecimalWrapper::from_str(&value.rate_min)
                 .expect("Max_sell must be convertible to decimal.");
+            let expiry = value.expiry.map(|expiry| {
+                DateTimeUtc::try_from(Rfc3339String(expiry))
+                    .expect("Expiry must be a valid RFC 3339 time")
+            });
 
             Ok(Exchange {
                 addr,
@@ -2268,6 +3667,7 @@ pub mod args {
                 token_buy,
                 min_buy,
                 vp,
+                expiry,
             })
         }
     }
@@ -2286,7 +3686,7 @@ pub mod args {
     impl Args for QueryBonds {
         fn parse(matches: &ArgMatches) -> Self {
             let query = Query::parse(matches);
-            let owner = OWNER.parse(matches);
+            let owner = OWNER_OPT.parse(matches);
             let validator = VALIDATOR_OPT.parse(matches);
             Self {
                 query,
@@ -2298,7 +3698,7 @@ pub mod args {
         fn def(app: App) -> App {
             app.add_args::<Query>()
                 .arg(
-                    OWNER.def().about(
+                    OWNER_OPT.def().about(
                         "The owner account address whose bonds to query.",
                     ),
                 )
@@ -2345,6 +3745,30 @@ pub mod args {
         }
     }
 
+    /// Query PoS validators
+    #[derive(Clone, Debug)]
+    pub struct QueryValidators {
+        /// Common query args
+        pub query: Query,
+        /// Epoch in which to find the validators
+        pub epoch: Option<Epoch>,
+    }
+
+    impl Args for QueryValidators {
+        fn parse(matches: &ArgMatches) -> Self {
+            let query = Query::parse(matches);
+            let epoch = EPOCH.parse(matches);
+            Self { query, epoch }
+        }
+
+        fn def(app: App) -> App {
+            app.add_args::<Query>().arg(EPOCH.def().about(
+                "The epoch at which to query (last committed, if not \
+                 specified).",
+            ))
+        }
+    }
+
     /// Query PoS slashes
     #[derive(Clone, Debug)]
     pub struct QuerySlashes {
@@ -2519,6 +3943,31 @@ pub mod args {
         }
     }
 
+    /// Unsubscribe intent topic arguments
+    #[derive(Clone, Debug)]
+    pub struct UnsubscribeTopic {
+        /// Gossip node address
+        pub node_addr: String,
+        /// Intent topic
+        pub topic: String,
+    }
+
+    impl Args for UnsubscribeTopic {
+        fn parse(matches: &ArgMatches) -> Self {
+            let node_addr = NODE.parse(matches);
+            let topic = TOPIC.parse(matches);
+            Self { node_addr, topic }
+        }
+
+        fn def(app: App) -> App {
+            app.arg(NODE.def().about("The gossip node address.")).arg(
+                TOPIC.def().about(
+                    "The topic that is no longer of interest for that node.",
+                ),
+            )
+        }
+    }
+
     #[derive(Clone, Debug)]
     pub struct GossipRun {
         pub addr: Option<Multiaddr>,
@@ -2550,6 +3999,11 @@ pub mod args {
         pub ledger_addr: TendermintAddress,
         pub tx_signing_key: WalletKeypair,
         pub tx_source_address: WalletAddress,
+        /// The pairs of the token sold and the token bought of the intents to
+        /// receive
+        pub token_pairs: Vec<(WalletAddress, WalletAddress)>,
+        /// The minimum amount of token sold of the intents to receive
+        pub min_sell: Option<token::Amount>,
     }
 
     impl Args for Matchmaker {
@@ -2560,6 +4014,25 @@ pub mod args {
             let ledger_addr = LEDGER_ADDRESS_DEFAULT.parse(matches);
             let tx_signing_key = SIGNING_KEY.parse(matches);
             let tx_source_address = SOURCE.parse(matches);
+            let token_pairs = TOKEN_PAIRS
+                .parse(matches)
+                .into_iter()
+                .map(|pair| match pair.split_once(':') {
+                    Some((sell, buy)) => (
+                        WalletAddress::new(sell.to_owned()),
+                        WalletAddress::new(buy.to_owned()),
+                    ),
+                    None => {
+                        eprintln!(
+                            "Invalid token pair {}, expected \
+                             \"{{sell}}:{{buy}}\"",
+                            pair
+                        );
+                        safe_exit(1)
+                    }
+                })
+                .collect();
+            let min_sell = MIN_SELL.parse(matches);
             Self {
                 intent_gossiper_addr,
                 matchmaker_path,
@@ -2567,6 +4040,8 @@ pub mod args {
                 ledger_addr,
                 tx_signing_key,
                 tx_source_address,
+                token_pairs,
+                min_sell,
             }
         }
 
@@ -2599,6 +4074,70 @@ pub mod args {
                  created by the matchmaker. This must be matching the signing \
                  key.",
             ))
+            .arg(TOKEN_PAIRS.def().about(
+                "Only receive the intents with an exchange of the given token \
+                 pairs as \"{sell}:{buy}\", with the token addresses or their \
+                 aliases.",
+            ))
+            .arg(MIN_SELL.def().about(
+                "Only receive the intents with an exchange selling at least \
+                 the given amount.",
+            ))
+        }
+    }
+
+    /// The format of a command's output
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub enum OutputFormat {
+        /// Human-readable output
+        Human,
+        /// JSON output
+        Json,
+    }
+
+    impl FromStr for OutputFormat {
+        type Err = String;
+
+        fn from_str(s: &str) -> Result<Self, Self::Err> {
+            match s {
+                "human" => Ok(Self::Human),
+                "json" => Ok(Self::Json),
+                _ => Err(format!(
+                    "Unknown output format \"{}\", expected \"human\" or \
+                     \"json\"",
+                    s
+                )),
+            }
+        }
+    }
+
+    /// How a transaction is broadcast to the ledger
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub enum BroadcastMode {
+        /// Return right after the transaction is sent, without waiting for
+        /// the result of its mempool check
+        Async,
+        /// Return once the transaction has been checked and added to the
+        /// mempool
+        Sync,
+        /// Wait for the transaction to be applied on chain
+        Commit,
+    }
+
+    impl FromStr for BroadcastMode {
+        type Err = String;
+
+        fn from_str(s: &str) -> Result<Self, Self::Err> {
+            match s {
+                "async" => Ok(Self::Async),
+                "sync" => Ok(Self::Sync),
+                "commit" => Ok(Self::Commit),
+                _ => Err(format!(
+                    "Unknown broadcast mode \"{}\", expected one of \
+                     \"async\", \"sync\" or \"commit\"",
+                    s
+                )),
+            }
         }
     }
 
@@ -2609,8 +4148,8 @@ pub mod args {
         pub dry_run: bool,
         /// Submit the transaction even if it doesn't pass client checks
         pub force: bool,
-        /// Do not wait for the transaction to be added to the blockchain
-        pub broadcast_only: bool,
+        /// How to broadcast the transaction and how long to wait for it
+        pub broadcast_mode: BroadcastMode,
         /// The address of the ledger node as host:port
         pub ledger_address: TendermintAddress,
         /// If any new account is initialized by the tx, use the given alias to
@@ -2626,6 +4165,10 @@ pub mod args {
         pub signing_key: Option<WalletKeypair>,
         /// Sign the tx with the keypair of the public key of the given address
         pub signer: Option<WalletAddress>,
+        /// Write the unsigned tx to the given file instead of submitting it
+        pub dump_tx: Option<PathBuf>,
+        /// The time after which the tx can no longer be included in a block
+        pub expiration: Option<DateTimeUtc>,
     }
 
     impl Args for Tx {
@@ -2638,9 +4181,24 @@ pub mod args {
             .arg(FORCE.def().about(
                 "Submit the transaction even if it doesn't pass client checks.",
             ))
+            .arg(
+                BROADCAST_MODE
+                    .def()
+                    .about(
+                        "How to broadcast the transaction. \"async\" returns \
+                         right after the transaction is sent, \"sync\" \
+                         returns once the transaction is checked and added to \
+                         the mempool and \"commit\" waits for the transaction \
+                         to be applied and reports its result and gas used. \
+                         Defaults to \"commit\".",
+                    )
+                    .possible_values(&["async", "sync", "commit"])
+                    .conflicts_with(BROADCAST_ONLY.name),
+            )
             .arg(BROADCAST_ONLY.def().about(
                 "Do not wait for the transaction to be applied. This will \
-                 return once the transaction is added to the mempool.",
+                 return once the transaction is added to the mempool. The \
+                 same as `--broadcast-mode sync`.",
             ))
             .arg(LEDGER_ADDRESS_DEFAULT.def().about(LEDGER_ADDRESS_ABOUT))
             .arg(ALIAS_OPT.def().about(
@@ -2677,33 +4235,203 @@ pub mod args {
                     )
                     .conflicts_with(SIGNING_KEY_OPT.name),
             )
+            .arg(DUMP_TX.def().about(
+                "Write the unsigned transaction to the file at the given path \
+                 instead of submitting it. The transaction can then be signed \
+                 offline with `sign` and submitted with `broadcast`.",
+            ))
+            .arg(EXPIRATION.def().about(
+                "The RFC 3339 time after which the transaction is no longer \
+                 valid, e.g. \"2022-06-01T12:00:00Z\". By default, the \
+                 transaction doesn't expire.",
+            ))
+        }
+
+        fn parse(matches: &ArgMatches) -> Self {
+            let dry_run = DRY_RUN_TX.parse(matches);
+            let force = FORCE.parse(matches);
+            let broadcast_mode = if BROADCAST_ONLY.parse(matches) {
+                BroadcastMode::Sync
+            } else {
+                BROADCAST_MODE.parse(matches)
+            };
+            let ledger_address = LEDGER_ADDRESS_DEFAULT.parse(matches);
+            let initialized_account_alias = ALIAS_OPT.parse(matches);
+            let fee_amount = FEE_AMOUNT.parse(matches);
+            let fee_token = FEE_TOKEN.parse(matches);
+            let gas_limit = GAS_LIMIT.parse(matches).into();
+
+            let signing_key = SIGNING_KEY_OPT.parse(matches);
+            let signer = SIGNER.parse(matches);
+            let dump_tx = DUMP_TX.parse(matches);
+            let expiration = EXPIRATION.parse(matches);
+            Self {
+                dry_run,
+                force,
+                broadcast_mode,
+                ledger_address,
+                initialized_account_alias,
+                fee_amount,
+                fee_token,
+                gas_limit,
+                signing_key,
+                signer,
+                dump_tx,
+                expiration,
+            }
+        }
+    }
+
+    /// Offline transaction signing arguments
+    #[derive(Clone, Debug)]
+    pub struct TxSign {
+        /// Common tx arguments
+        pub tx: Tx,
+        /// Path to the unsigned tx file
+        pub tx_file: PathBuf,
+        /// The epoch of the wrapper tx. If none given, the last committed
+        /// epoch will be queried from the ledger.
+        pub epoch: Option<Epoch>,
+        /// Path to the signed tx file to be written
+        pub output_file: Option<PathBuf>,
+    }
+
+    impl Args for TxSign {
+        fn parse(matches: &ArgMatches) -> Self {
+            let tx = Tx::parse(matches);
+            let tx_file = TX_FILE.parse(matches);
+            let epoch = EPOCH.parse(matches);
+            let output_file = OUTPUT_FILE.parse(matches);
+            Self {
+                tx,
+                tx_file,
+                epoch,
+                output_file,
+            }
+        }
+
+        fn def(app: App) -> App {
+            app.add_args::<Tx>()
+                .arg(TX_FILE.def().about(
+                    "The path to the unsigned transaction file written with \
+                     `--dump-tx`.",
+                ))
+                .arg(EPOCH.def().about(
+                    "The epoch of the wrapper transaction. It must be given \
+                     when signing offline, otherwise the last committed epoch \
+                     is queried from the ledger.",
+                ))
+                .arg(OUTPUT_FILE.def().about(
+                    "The path to write the signed transaction to. Defaults to \
+                     the transaction file path with a \".signed\" extension.",
+                ))
+        }
+    }
+
+    /// Signed transaction broadcast arguments
+    #[derive(Clone, Debug)]
+    pub struct TxBroadcast {
+        /// Common tx arguments
+        pub tx: Tx,
+        /// Path to the signed tx file
+        pub signed_file: PathBuf,
+    }
+
+    impl Args for TxBroadcast {
+        fn parse(matches: &ArgMatches) -> Self {
+            let tx = Tx::parse(matches);
+            let signed_file = SIGNED_FILE.parse(matches);
+            Self { tx, signed_file }
+        }
+
+        fn def(app: App) -> App {
+            app.add_args::<Tx>().arg(SIGNED_FILE.def().about(
+                "The path to the signed transaction file written with `sign`.",
+            ))
+        }
+    }
+
+    /// Multisignature partial signing arguments
+    #[derive(Clone, Debug)]
+    pub struct TxMultisigSign {
+        /// Path to the unsigned tx file
+        pub tx_file: PathBuf,
+        /// The key of the signer
+        pub signing_key: WalletKeypair,
+        /// Path to the partial signature file to be written
+        pub output_file: Option<PathBuf>,
+    }
+
+    impl Args for TxMultisigSign {
+        fn parse(matches: &ArgMatches) -> Self {
+            let tx_file = TX_FILE.parse(matches);
+            let signing_key = SIGNING_KEY.parse(matches);
+            let output_file = OUTPUT_FILE.parse(matches);
+            Self {
+                tx_file,
+                signing_key,
+                output_file,
+            }
+        }
+
+        fn def(app: App) -> App {
+            app.arg(TX_FILE.def().about(
+                "The path to the unsigned transaction file written with \
+                 `--dump-tx`.",
+            ))
+            .arg(SIGNING_KEY.def().about(
+                "The key of one of the signers of the multisignature account.",
+            ))
+            .arg(OUTPUT_FILE.def().about(
+                "The path to write the partial signature to. Defaults to the \
+                 transaction file path with a \".sig\" extension.",
+            ))
         }
+    }
 
-        fn parse(matches: &ArgMatches) -> Self {
-            let dry_run = DRY_RUN_TX.parse(matches);
-            let force = FORCE.parse(matches);
-            let broadcast_only = BROADCAST_ONLY.parse(matches);
-            let ledger_address = LEDGER_ADDRESS_DEFAULT.parse(matches);
-            let initialized_account_alias = ALIAS_OPT.parse(matches);
-            let fee_amount = FEE_AMOUNT.parse(matches);
-            let fee_token = FEE_TOKEN.parse(matches);
-            let gas_limit = GAS_LIMIT.parse(matches).into();
+    /// Multisignature transaction submission arguments
+    #[derive(Clone, Debug)]
+    pub struct TxMultisigSubmit {
+        /// Common tx arguments
+        pub tx: Tx,
+        /// Path to the unsigned tx file
+        pub tx_file: PathBuf,
+        /// Address of the multisignature account
+        pub address: WalletAddress,
+        /// Paths to the partial signature files
+        pub signature_files: Vec<PathBuf>,
+    }
 
-            let signing_key = SIGNING_KEY_OPT.parse(matches);
-            let signer = SIGNER.parse(matches);
+    impl Args for TxMultisigSubmit {
+        fn parse(matches: &ArgMatches) -> Self {
+            let tx = Tx::parse(matches);
+            let tx_file = TX_FILE.parse(matches);
+            let address = ADDRESS.parse(matches);
+            let signature_files = SIGNATURE_FILES.parse(matches);
             Self {
-                dry_run,
-                force,
-                broadcast_only,
-                ledger_address,
-                initialized_account_alias,
-                fee_amount,
-                fee_token,
-                gas_limit,
-                signing_key,
-                signer,
+                tx,
+                tx_file,
+                address,
+                signature_files,
             }
         }
+
+        fn def(app: App) -> App {
+            app.add_args::<Tx>()
+                .arg(TX_FILE.def().about(
+                    "The path to the unsigned transaction file written with \
+                     `--dump-tx`.",
+                ))
+                .arg(
+                    ADDRESS
+                        .def()
+                        .about("The address of the multisignature account."),
+                )
+                .arg(SIGNATURE_FILES.def().required(true).about(
+                    "The paths to the partial signature files written with \
+                     `multisig-sign`.",
+                ))
+        }
     }
 
     /// Common query arguments
@@ -2731,15 +4459,27 @@ pub mod args {
         pub alias: Option<String>,
         /// Don't encrypt the keypair
         pub unsafe_dont_encrypt: bool,
+        /// Derive the keypair from a new mnemonic code
+        pub hd: bool,
+        /// Restore the keypair from an existing mnemonic code
+        pub restore: bool,
+        /// The HD derivation path of the keypair
+        pub derivation_path: DerivationPath,
     }
 
     impl Args for KeyAndAddressGen {
         fn parse(matches: &ArgMatches) -> Self {
             let alias = ALIAS_OPT.parse(matches);
             let unsafe_dont_encrypt = UNSAFE_DONT_ENCRYPT.parse(matches);
+            let hd = HD.parse(matches);
+            let restore = RESTORE.parse(matches);
+            let derivation_path = DERIVATION_PATH.parse(matches);
             Self {
                 alias,
                 unsafe_dont_encrypt,
+                hd,
+                restore,
+                derivation_path,
             }
         }
 
@@ -2752,6 +4492,20 @@ pub mod args {
                 "UNSAFE: Do not encrypt the keypair. Do not use this for keys \
                  used in a live network.",
             ))
+            .arg(HD.def().about(
+                "Derive the keypair from a newly generated 24 words mnemonic \
+                 code. The mnemonic code is shown only once and must be \
+                 stored safely, as it can be used to restore the keypair.",
+            ))
+            .arg(RESTORE.def().conflicts_with(HD.name).about(
+                "Restore the keypair from an existing mnemonic code, which \
+                 will be read from the standard input.",
+            ))
+            .arg(DERIVATION_PATH.def().about(
+                "The HD derivation path of the keypair derived from a \
+                 mnemonic code. Only hardened indices are supported. Defaults \
+                 to m/44'/877'/0'/0'/0'.",
+            ))
         }
     }
 
@@ -2854,6 +4608,46 @@ pub mod args {
         }
     }
 
+    /// Wallet key import arguments
+    #[derive(Clone, Debug)]
+    pub struct KeyImport {
+        /// Path to the exported key file
+        pub file_path: PathBuf,
+        /// Key alias
+        pub alias: Option<String>,
+        /// Don't encrypt the keypair
+        pub unsafe_dont_encrypt: bool,
+    }
+
+    impl Args for KeyImport {
+        fn parse(matches: &ArgMatches) -> Self {
+            let file_path = FILE_PATH.parse(matches);
+            let alias = ALIAS_OPT.parse(matches);
+            let unsafe_dont_encrypt = UNSAFE_DONT_ENCRYPT.parse(matches);
+            Self {
+                file_path,
+                alias,
+                unsafe_dont_encrypt,
+            }
+        }
+
+        fn def(app: App) -> App {
+            app.arg(
+                FILE_PATH
+                    .def()
+                    .about("The path to the file with the exported key."),
+            )
+            .arg(ALIAS_OPT.def().about(
+                "The key and address alias. If none provided, the alias will \
+                 be the public key hash.",
+            ))
+            .arg(UNSAFE_DONT_ENCRYPT.def().about(
+                "UNSAFE: Do not encrypt the keypair. Do not use this for keys \
+                 used in a live network.",
+            ))
+        }
+    }
+
     /// Wallet address lookup arguments
     #[derive(Clone, Debug)]
     pub struct AddressFind {
@@ -2903,11 +4697,40 @@ pub mod args {
         }
     }
 
+    #[derive(Clone, Debug)]
+    pub struct Init {
+        pub chain_id: ChainId,
+        pub genesis_path: Option<PathBuf>,
+    }
+
+    impl Args for Init {
+        fn parse(matches: &ArgMatches) -> Self {
+            let chain_id = CHAIN_ID.parse(matches);
+            let genesis_path = GENESIS_PATH_OPT.parse(matches);
+            Self {
+                chain_id,
+                genesis_path,
+            }
+        }
+
+        fn def(app: App) -> App {
+            app.arg(CHAIN_ID.def().about("The chain ID.")).arg(
+                GENESIS_PATH_OPT.def().about(
+                    "The path to the genesis file of the chain. It's \
+                     required, unless the node is built with the \"dev\" \
+                     feature, which has a built-in genesis.",
+                ),
+            )
+        }
+    }
+
     #[derive(Clone, Debug)]
     pub struct JoinNetwork {
         pub chain_id: ChainId,
         pub genesis_validator: Option<String>,
         pub pre_genesis_path: Option<PathBuf>,
+        pub configs_server: Option<String>,
+        pub archive_hash: Option<String>,
     }
 
     impl Args for JoinNetwork {
@@ -2915,10 +4738,14 @@ pub mod args {
             let chain_id = CHAIN_ID.parse(matches);
             let genesis_validator = GENESIS_VALIDATOR.parse(matches);
             let pre_genesis_path = PRE_GENESIS_PATH.parse(matches);
+            let configs_server = CONFIGS_SERVER.parse(matches);
+            let archive_hash = ARCHIVE_HASH.parse(matches);
             Self {
                 chain_id,
                 genesis_validator,
                 pre_genesis_path,
+                configs_server,
+                archive_hash,
             }
         }
 
@@ -2926,6 +4753,256 @@ pub mod args {
             app.arg(CHAIN_ID.def().about("The chain ID. The chain must be known in the https://github.com/heliaxdev/anoma-network-config repository."))
                 .arg(GENESIS_VALIDATOR.def().about("The alias of the genesis validator that you want to set up as, if any."))
                 .arg(PRE_GENESIS_PATH.def().about("The path to the pre-genesis directory for genesis validator, if any. Defaults to \"{base-dir}/pre-genesis/{genesis-validator}\"."))
+                .arg(CONFIGS_SERVER.def().about("The URL prefix of the network config release archive, if any. Overrides the `ANOMA_NETWORK_CONFIGS_SERVER` environment variable."))
+                .arg(ARCHIVE_HASH.def().about("The expected hex-encoded SHA256 hash of the network config release archive, if any. The download is rejected when its hash doesn't match."))
+        }
+    }
+
+    /// DB dump arguments
+    #[derive(Clone, Debug)]
+    pub struct DbDump {
+        /// The prefix of the storage keys to print, if any
+        pub prefix: Option<storage::Key>,
+    }
+
+    impl Args for DbDump {
+        fn parse(matches: &ArgMatches) -> Self {
+            let prefix = PREFIX.parse(matches);
+            Self { prefix }
+        }
+
+        fn def(app: App) -> App {
+            app.arg(PREFIX.def().about(
+                "Only print the keys with this prefix, e.g. the address of an \
+                 account. Prints all the keys when not given.",
+            ))
+        }
+    }
+
+    /// DB get arguments
+    #[derive(Clone, Debug)]
+    pub struct DbGet {
+        /// The storage key to read
+        pub storage_key: storage::Key,
+    }
+
+    impl Args for DbGet {
+        fn parse(matches: &ArgMatches) -> Self {
+            let storage_key = STORAGE_KEY.parse(matches);
+            Self { storage_key }
+        }
+
+        fn def(app: App) -> App {
+            app.arg(STORAGE_KEY.def().about("Storage key"))
+        }
+    }
+
+    /// Bench arguments
+    #[derive(Clone, Debug)]
+    pub struct Bench {
+        /// The directory with the WASMs built for tests
+        pub test_wasm_dir: PathBuf,
+        /// The number of runs of every workload
+        pub runs: u32,
+    }
+
+    impl Args for Bench {
+        fn parse(matches: &ArgMatches) -> Self {
+            let test_wasm_dir = TEST_WASM_DIR.parse(matches);
+            let runs = RUNS.parse(matches);
+            Self {
+                test_wasm_dir,
+                runs,
+            }
+        }
+
+        fn def(app: App) -> App {
+            app.arg(TEST_WASM_DIR.def().about(
+                "The directory with the WASMs built for tests. Defaults to \
+                 \"wasm_for_tests\".",
+            ))
+            .arg(RUNS.def().about(
+                "The number of runs of every workload. Defaults to 10.",
+            ))
+        }
+    }
+
+    /// Load generation arguments
+    #[derive(Clone, Debug)]
+    pub struct TxGen {
+        /// Common tx arguments
+        pub tx: Tx,
+        /// Transfer source address, which also signs the custom txs
+        pub source: WalletAddress,
+        /// Transfer target address
+        pub target: WalletAddress,
+        /// Transferred token address
+        pub token: WalletAddress,
+        /// Transferred token amount
+        pub amount: token::Amount,
+        /// Path to the WASM code file of the custom txs
+        pub code_path: Option<PathBuf>,
+        /// Path to the data file of the custom txs
+        pub data_path: Option<PathBuf>,
+        /// The percentage of the custom txs among the generated txs
+        pub custom_share: u8,
+        /// The target number of txs broadcast per second
+        pub rate: u32,
+        /// The number of txs to generate
+        pub count: u64,
+    }
+
+    impl Args for TxGen {
+        fn parse(matches: &ArgMatches) -> Self {
+            let tx = Tx::parse(matches);
+            let source = SOURCE.parse(matches);
+            let target = TARGET.parse(matches);
+            let token = TOKEN.parse(matches);
+            let amount = AMOUNT.parse(matches);
+            let code_path = CODE_PATH_OPT.parse(matches);
+            let data_path = DATA_PATH_OPT.parse(matches);
+            let custom_share = CUSTOM_SHARE.parse(matches);
+            let rate = RATE.parse(matches);
+            let count = COUNT.parse(matches);
+            Self {
+                tx,
+                source,
+                target,
+                token,
+                amount,
+                code_path,
+                data_path,
+                custom_share,
+                rate,
+                count,
+            }
+        }
+
+        fn def(app: App) -> App {
+            app.add_args::<Tx>()
+                .arg(SOURCE.def().about(
+                    "The source account address of the transfers. The \
+                     source's key is used to sign all the transactions.",
+                ))
+                .arg(TARGET.def().about("The target account address."))
+                .arg(TOKEN.def().about("The transfer token."))
+                .arg(AMOUNT.def().about(
+                    "The amount of every transfer in decimal.",
+                ))
+                .arg(CODE_PATH_OPT.def().about(
+                    "The path to the WASM code of the custom transactions. \
+                     Required with a non-zero custom share.",
+                ))
+                .arg(DATA_PATH_OPT.def().about(
+                    "The data file at this path containing arbitrary bytes \
+                     will be passed to the custom transactions' code.",
+                ))
+                .arg(CUSTOM_SHARE.def().about(
+                    "The percentage of the custom transactions among the \
+                     generated transactions, the rest are transfers. \
+                     Defaults to 0.",
+                ))
+                .arg(RATE.def().about(
+                    "The target number of transactions broadcast per second. \
+                     Defaults to 10.",
+                ))
+                .arg(COUNT.def().about(
+                    "The number of transactions to generate. Defaults to \
+                     100.",
+                ))
+        }
+    }
+
+    /// Ledger replay arguments
+    #[derive(Clone, Debug)]
+    pub struct LedgerReplay {
+        /// The first block height whose app hash is reported
+        pub from: u64,
+        /// The last block height to replay
+        pub to: u64,
+        /// The address of the Tendermint RPC to read the blocks from, if
+        /// not the node's own
+        pub ledger_address: Option<TendermintAddress>,
+    }
+
+    impl Args for LedgerReplay {
+        fn parse(matches: &ArgMatches) -> Self {
+            let from = FROM_HEIGHT.parse(matches);
+            let to = TO_HEIGHT.parse(matches);
+            let ledger_address = LEDGER_ADDRESS_OPT.parse(matches);
+            Self {
+                from,
+                to,
+                ledger_address,
+            }
+        }
+
+        fn def(app: App) -> App {
+            app.arg(FROM_HEIGHT.def().about(
+                "The first block height whose app hash is compared. The \
+                 blocks before it are replayed too, starting from the \
+                 genesis.",
+            ))
+            .arg(TO_HEIGHT.def().about(
+                "The last block height to replay. The next block must be in \
+                 the block store to verify its app hash.",
+            ))
+            .arg(LEDGER_ADDRESS_OPT.def().about(
+                "The address of the Tendermint RPC to read the blocks from. \
+                 Defaults to the node's configured RPC address.",
+            ))
+        }
+    }
+
+    /// Ledger read-only node arguments
+    #[derive(Clone, Debug)]
+    pub struct LedgerRunReadOnly {
+        /// The directory of the DB to read, if not the node's own, e.g. of
+        /// a replica
+        pub db_dir: Option<PathBuf>,
+    }
+
+    impl Args for LedgerRunReadOnly {
+        fn parse(matches: &ArgMatches) -> Self {
+            let db_dir = DB_DIR.parse(matches);
+            Self { db_dir }
+        }
+
+        fn def(app: App) -> App {
+            app.arg(DB_DIR.def().about(
+                "The directory of the DB to read, e.g. of a replica of the \
+                 node's DB. Defaults to the node's DB directory.",
+            ))
+        }
+    }
+
+    /// Ledger reset arguments
+    #[derive(Clone, Debug)]
+    pub struct LedgerReset {
+        /// Keep the Tendermint node key and the validator key
+        pub keep_keys: bool,
+        /// Keep the Tendermint address book with the known peers
+        pub keep_addr_book: bool,
+    }
+
+    impl Args for LedgerReset {
+        fn parse(matches: &ArgMatches) -> Self {
+            let keep_keys = KEEP_KEYS.parse(matches);
+            let keep_addr_book = KEEP_ADDR_BOOK.parse(matches);
+            Self {
+                keep_keys,
+                keep_addr_book,
+            }
+        }
+
+        fn def(app: App) -> App {
+            app.arg(KEEP_KEYS.def().about(
+                "Keep the Tendermint node key and the validator's consensus \
+                 key.",
+            ))
+            .arg(KEEP_ADDR_BOOK.def().about(
+                "Keep the Tendermint address book with the known peers.",
+            ))
         }
     }
 
@@ -3010,6 +5087,66 @@ pub mod args {
         }
     }
 
+    #[derive(Clone, Debug)]
+    pub struct InitLocalnet {
+        pub genesis_path: PathBuf,
+        pub wasm_checksums_path: PathBuf,
+        pub chain_id_prefix: ChainIdPrefix,
+        pub validators: u64,
+        pub first_port: u16,
+        pub consensus_timeout_commit: Timeout,
+    }
+
+    impl Args for InitLocalnet {
+        fn parse(matches: &ArgMatches) -> Self {
+            let genesis_path = GENESIS_PATH.parse(matches);
+            let wasm_checksums_path = WASM_CHECKSUMS_PATH.parse(matches);
+            let chain_id_prefix = CHAIN_ID_PREFIX.parse(matches);
+            let validators = VALIDATORS.parse(matches);
+            let first_port = FIRST_PORT.parse(matches);
+            let consensus_timeout_commit =
+                CONSENSUS_TIMEOUT_COMMIT.parse(matches);
+            Self {
+                genesis_path,
+                wasm_checksums_path,
+                chain_id_prefix,
+                validators,
+                first_port,
+                consensus_timeout_commit,
+            }
+        }
+
+        fn def(app: App) -> App {
+            app.arg(GENESIS_PATH.def().about(
+                "Path to the template genesis configuration file. Its first \
+                 validator is used as a template for the validators of the \
+                 local network and its accounts are pre-funded as given in \
+                 the file.",
+            ))
+            .arg(
+                WASM_CHECKSUMS_PATH
+                    .def()
+                    .about("Path to the WASM checksums file."),
+            )
+            .arg(CHAIN_ID_PREFIX.def().about(
+                "The chain ID prefix. Up to 19 alphanumeric, '.', '-' or '_' \
+                 characters.",
+            ))
+            .arg(VALIDATORS.def().about(
+                "The number of validators in the local network. Defaults to 4.",
+            ))
+            .arg(FIRST_PORT.def().about(
+                "The first port of the first validator's node. Every node \
+                 uses 10 ports starting from `first-port + 10 * index`. \
+                 Defaults to 27656.",
+            ))
+            .arg(CONSENSUS_TIMEOUT_COMMIT.def().about(
+                "The Tendermint consensus timeout_commit configuration as \
+                 e.g. `1s` or `1000ms`. Defaults to 1 second.",
+            ))
+        }
+    }
+
     #[derive(Clone, Debug)]
     pub struct InitGenesisValidator {
         pub alias: String,
@@ -50,13 +50,19 @@ pub struct Context {
 
 impl Context {
     pub fn new(global_args: args::Global) -> Self {
-        let global_config = read_or_try_new_global_config(&global_args);
+        let mut global_config = read_or_try_new_global_config(&global_args);
+        // The chain ID given in the args or in the env var selects the chain
+        // for this command, without changing the default chain
+        if let Some(chain_id) = chain_id_from_args_or_env(&global_args) {
+            global_config.default_chain_id = chain_id;
+        }
         tracing::info!("Chain ID: {}", global_config.default_chain_id);
 
-        let mut config = Config::load(
+        let mut config = Config::load_with_overrides(
             &global_args.base_dir,
             &global_config.default_chain_id,
             global_args.mode.clone(),
+            &global_args.config_overrides,
         );
 
         let chain_dir = global_args
@@ -177,17 +183,9 @@ pub fn read_or_try_new_global_config(
 ) -> GlobalConfig {
     GlobalConfig::read(&global_args.base_dir).unwrap_or_else(|err| {
         if let config::global::Error::FileNotFound(_) = err {
-            let chain_id = global_args.chain_id.clone().or_else(|| {
-                env::var(ENV_VAR_CHAIN_ID).ok().map(|chain_id| {
-                    ChainId::from_str(&chain_id).unwrap_or_else(|err| {
-                        eprintln!("Invalid chain ID: {}", err);
-                        super::safe_exit(1)
-                    })
-                })
-            });
-
             // If not specified, use the default
-            let chain_id = chain_id.unwrap_or_default();
+            let chain_id =
+                chain_id_from_args_or_env(global_args).unwrap_or_default();
 
             let config = GlobalConfig::new(chain_id);
             config.write(&global_args.base_dir).unwrap_or_else(|err| {
@@ -202,6 +200,18 @@ pub fn read_or_try_new_global_config(
     })
 }
 
+/// Get the chain ID from the global args or from the env var, if any.
+fn chain_id_from_args_or_env(global_args: &args::Global) -> Option<ChainId> {
+    global_args.chain_id.clone().or_else(|| {
+        env::var(ENV_VAR_CHAIN_ID).ok().map(|chain_id| {
+            ChainId::from_str(&chain_id).unwrap_or_else(|err| {
+                eprintln!("Invalid chain ID: {}", err);
+                super::safe_exit(1)
+            })
+        })
+    })
+}
+
 /// Argument that can be given raw or found in the [`Context`].
 #[derive(Debug, Clone)]
 pub struct FromContext<T> {
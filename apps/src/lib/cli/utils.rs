@@ -76,7 +76,6 @@ pub struct ArgFlag {
     pub name: &'static str,
 }
 
-#[allow(dead_code)]
 pub struct ArgMulti<T> {
     pub name: &'static str,
     pub r#type: PhantomData<T>,
@@ -122,7 +121,6 @@ pub const fn flag(name: &'static str) -> ArgFlag {
     ArgFlag { name }
 }
 
-#[allow(dead_code)]
 pub const fn arg_multi<T>(name: &'static str) -> ArgMulti<T> {
     ArgMulti {
         name,
@@ -249,16 +247,20 @@ impl ArgFlag {
     }
 }
 
-#[allow(dead_code)]
+impl<T> ArgMulti<T> {
+    pub fn def(&self) -> ClapArg {
+        ClapArg::new(self.name)
+            .long(self.name)
+            .takes_value(true)
+            .multiple(true)
+    }
+}
+
 impl<T> ArgMulti<T>
 where
     T: FromStr,
     <T as FromStr>::Err: Debug,
 {
-    pub fn def(&self) -> ClapArg {
-        ClapArg::new(self.name).long(self.name).multiple(true)
-    }
-
     pub fn parse(&self, matches: &ArgMatches) -> Vec<T> {
         matches
             .values_of(self.name)
@@ -277,6 +279,16 @@ where
     }
 }
 
+impl<T> ArgMulti<FromContext<T>> {
+    pub fn parse(&self, matches: &ArgMatches) -> Vec<FromContext<T>> {
+        matches
+            .values_of(self.name)
+            .unwrap_or_default()
+            .map(|raw| FromContext::new(raw.to_string()))
+            .collect()
+    }
+}
+
 /// Extensions for defining commands and arguments.
 /// Every function here should have a matcher in [`ArgMatchesExt`].
 pub trait AppExt {
@@ -9,7 +9,9 @@ use tendermint_config::net::Address as TendermintAddress;
 #[cfg(feature = "ABCI")]
 use tendermint_config_abci::net::Address as TendermintAddress;
 
+use super::output::print_json;
 use super::signing;
+use crate::cli::args::OutputFormat;
 use crate::cli::{self, args, Context};
 use crate::proto::services::rpc_service_client::RpcServiceClient;
 use crate::proto::{services, RpcMessage};
@@ -81,7 +83,7 @@ pub async fn gossip_intent(
                 let response = client.send_message(message).await.expect(
                     "Failed to send message and/or receive rpc response",
                 );
-                println!("{:#?}", response);
+                print_response(ctx.global_args.output, response);
             }
             Err(e) => {
                 eprintln!(
@@ -96,7 +98,7 @@ pub async fn gossip_intent(
 /// Request an intent gossip node with a  matchmaker to subscribe to a given
 /// topic.
 pub async fn subscribe_topic(
-    _ctx: Context,
+    ctx: Context,
     args::SubscribeTopic { node_addr, topic }: args::SubscribeTopic,
 ) {
     let mut client = RpcServiceClient::connect(node_addr).await.unwrap();
@@ -105,7 +107,35 @@ pub async fn subscribe_topic(
         .send_message(message)
         .await
         .expect("failed to send message and/or receive rpc response");
-    println!("{:#?}", response);
+    print_response(ctx.global_args.output, response);
+}
+
+/// Request an intent gossip node to unsubscribe from a given topic.
+pub async fn unsubscribe_topic(
+    ctx: Context,
+    args::UnsubscribeTopic { node_addr, topic }: args::UnsubscribeTopic,
+) {
+    let mut client = RpcServiceClient::connect(node_addr).await.unwrap();
+    let message: services::RpcMessage =
+        RpcMessage::new_unsubscribe_topic(topic).into();
+    let response = client
+        .send_message(message)
+        .await
+        .expect("failed to send message and/or receive rpc response");
+    print_response(ctx.global_args.output, response);
+}
+
+/// Print the response of a gossip node
+fn print_response(
+    output: OutputFormat,
+    response: tonic::Response<services::RpcResponse>,
+) {
+    match output {
+        OutputFormat::Human => println!("{:#?}", response),
+        OutputFormat::Json => print_json(&serde_json::json!({
+            "result": response.into_inner().result,
+        })),
+    }
 }
 
 async fn sign_exchange(
@@ -1,6 +1,8 @@
 pub mod gossip;
+pub mod output;
 pub mod rpc;
 pub mod signing;
 mod tendermint_websocket_client;
 pub mod tx;
+pub mod txgen;
 pub mod utils;
@@ -0,0 +1,36 @@
+//! Printing of the client commands' output in the format selected with the
+//! global `--output` argument.
+
+use std::fmt::Display;
+
+use serde::Serialize;
+
+use crate::cli::args::OutputFormat;
+
+/// Print a message that is not a part of a command's result. With
+/// [`OutputFormat::Json`], the message goes to stderr so that stdout only
+/// contains the JSON result.
+pub fn print_progress(output: OutputFormat, msg: impl Display) {
+    match output {
+        OutputFormat::Human => println!("{}", msg),
+        OutputFormat::Json => eprintln!("{}", msg),
+    }
+}
+
+/// Print the result of a command as JSON to stdout
+pub fn print_json(result: &impl Serialize) {
+    println!("{}", serde_json::to_string_pretty(result).unwrap());
+}
+
+/// Print the result of a command in the given format. The human-readable
+/// output is printed by the given function.
+pub fn print_result<T: Serialize>(
+    output: OutputFormat,
+    result: &T,
+    print_human: impl FnOnce(&T),
+) {
+    match output {
+        OutputFormat::Human => print_human(result),
+        OutputFormat::Json => print_json(result),
+    }
+}
@@ -1,35 +1,42 @@
 //! Client RPC queries
 
 use std::borrow::Cow;
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::convert::TryInto;
 use std::fs::File;
 use std::io::{self, Write};
 use std::iter::Iterator;
 
 use anoma::ledger::governance::storage as gov_storage;
-use anoma::ledger::governance::utils::Votes;
-use anoma::ledger::parameters::{storage as param_storage, EpochDuration};
+use anoma::ledger::governance::utils::{ProposalResult, Votes};
+use anoma::ledger::light_client;
+use anoma::ledger::parameters::inflation::{Inflation, InflationParams};
+use anoma::ledger::parameters::{
+    self, storage as param_storage, EpochDuration, FeeTokens,
+};
 use anoma::ledger::pos::types::{
-    Epoch as PosEpoch, VotingPower, WeightedValidator,
+    Epoch as PosEpoch, VotingPower, VotingPowerDelta, WeightedValidator,
 };
 use anoma::ledger::pos::{
     self, is_validator_slashes_key, BondId, Bonds, PosParams, Slash, Unbonds,
 };
+use anoma::ledger::storage::write_log::SizeLimits;
+use anoma::ledger::storage::MerkleRoot;
 use anoma::ledger::treasury::storage as treasury_storage;
 use anoma::types::address::Address;
 use anoma::types::governance::{
-    OfflineProposal, OfflineVote, ProposalVote, TallyResult,
+    OfflineProposal, OfflineVote, ProposalVote,
 };
 use anoma::types::key::*;
-use anoma::types::storage::{Epoch, PrefixValue};
+use anoma::types::storage::{BlockHeight, Epoch, PrefixValue};
 use anoma::types::token::{balance_key, Amount};
-use anoma::types::{address, storage, token};
+use anoma::types::{address, multisig, storage, token};
 use async_std::fs::{self};
 use async_std::path::PathBuf;
 use async_std::prelude::*;
 use borsh::BorshDeserialize;
 use itertools::Itertools;
+use serde::Serialize;
 #[cfg(not(feature = "ABCI"))]
 use tendermint::abci::Code;
 #[cfg(not(feature = "ABCI"))]
@@ -55,12 +62,36 @@ use tendermint_rpc_abci::{Order, SubscriptionClient, WebSocketClient};
 #[cfg(feature = "ABCI")]
 use tendermint_stable::abci::Code;
 
+use crate::cli::args::OutputFormat;
+use crate::cli::context::WalletAddress;
 use crate::cli::{self, args, Context};
+use crate::client::output::{print_json, print_progress};
 use crate::client::tx::TxResponse;
+use crate::node::ledger::block_results::BlockResults;
+use crate::node::ledger::indexer::IndexedTx;
 use crate::node::ledger::rpc::Path;
+use crate::node::ledger::storage::PersistentStorageHasher;
 
 /// Query the epoch of the last committed block
-pub async fn query_epoch(args: args::Query) -> Epoch {
+pub async fn query_epoch(args: args::Query, output: OutputFormat) -> Epoch {
+    let epoch = query_last_epoch(args).await;
+    print_progress(output, format_args!("Last committed epoch: {}", epoch));
+    epoch
+}
+
+/// Query and print the epoch of the last committed block
+pub async fn print_epoch(ctx: Context, args: args::Query) {
+    let epoch = query_last_epoch(args).await;
+    match ctx.global_args.output {
+        OutputFormat::Human => println!("Last committed epoch: {}", epoch),
+        OutputFormat::Json => {
+            print_json(&serde_json::json!({ "epoch": epoch }))
+        }
+    }
+}
+
+/// Query the last committed epoch without printing it
+pub async fn query_last_epoch(args: args::Query) -> Epoch {
     let client = HttpClient::new(args.ledger_address).unwrap();
     let path = Path::Epoch;
     let data = vec![];
@@ -70,10 +101,7 @@ pub async fn query_epoch(args: args::Query) -> Epoch {
         .unwrap();
     match response.code {
         Code::Ok => match Epoch::try_from_slice(&response.value[..]) {
-            Ok(epoch) => {
-                println!("Last committed epoch: {}", epoch);
-                return epoch;
-            }
+            Ok(epoch) => return epoch,
 
             Err(err) => {
                 eprintln!("Error decoding the epoch value: {}", err)
@@ -88,31 +116,231 @@ pub async fn query_epoch(args: args::Query) -> Epoch {
 }
 
 /// Query the raw bytes of given storage key
-pub async fn query_raw_bytes(_ctx: Context, args: args::QueryRawBytes) {
+pub async fn query_raw_bytes(ctx: Context, args: args::QueryRawBytes) {
     let client = HttpClient::new(args.query.ledger_address).unwrap();
-    let path = Path::Value(args.storage_key);
-    let data = vec![];
+    match query_storage_value_bytes_proven(&client, &args.storage_key).await {
+        Some(value) => match ctx.global_args.output {
+            OutputFormat::Human => println!("{}", hex::encode(&value)),
+            OutputFormat::Json => print_json(&serde_json::json!({
+                "key": args.storage_key.to_string(),
+                "value": hex::encode(&value),
+            })),
+        },
+        None => {
+            eprintln!("No value found for key: {}", args.storage_key);
+            cli::safe_exit(1)
+        }
+    }
+}
+
+/// Query the results of a committed block
+pub async fn query_block_results(ctx: Context, args: args::QueryBlockResults) {
+    let client = HttpClient::new(args.query.ledger_address).unwrap();
+    let path = Path::BlockResults(args.height.map(BlockHeight));
     let response = client
-        .abci_query(Some(path.into()), data, None, false)
+        .abci_query(Some(path.into()), vec![], None, false)
         .await
         .unwrap();
-    match response.code {
-        Code::Ok => {
-            println!("{}", hex::encode(&response.value));
+    if let Code::Err(err) = response.code {
+        eprintln!("Error in the query {} (error code {})", response.info, err);
+        cli::safe_exit(1)
+    }
+    let results = match BlockResults::try_from_slice(&response.value[..]) {
+        Ok(results) => results,
+        Err(err) => {
+            eprintln!("Error decoding the block results: {}", err);
+            cli::safe_exit(1)
         }
-        Code::Err(err) => {
-            eprintln!(
-                "Error in the query {}  (error code {})",
-                response.info, err
-            );
+    };
+    let hex = |value: &Option<Vec<u8>>| value.as_ref().map(hex::encode);
+    match ctx.global_args.output {
+        OutputFormat::Human => {
+            println!("Results of the block at height {}", results.height);
+            println!("Gas used: {}", results.gas_used);
+            println!("Transactions: {}", results.tx_results.len());
+            for tx in &results.tx_results {
+                println!(
+                    "  {} {}: code {}, gas used {}, info: {}",
+                    tx.event_type, tx.hash, tx.code, tx.gas_used, tx.info
+                );
+            }
+            println!("Events: {}", results.events.len());
+            for event in &results.events {
+                let attributes = event
+                    .attributes
+                    .iter()
+                    .map(|(key, value)| format!("{}={}", key, value))
+                    .join(", ");
+                println!("  {}: {}", event.event_type, attributes);
+            }
+            println!("Validator updates: {}", results.validator_updates.len());
+            for update in &results.validator_updates {
+                println!(
+                    "  {}: voting power {}",
+                    update.consensus_key, update.voting_power
+                );
+            }
+            println!("Storage changes: {}", results.storage_changes.len());
+            for change in &results.storage_changes {
+                let or_none = |value: Option<String>| {
+                    value.unwrap_or_else(|| "none".to_owned())
+                };
+                println!(
+                    "  {}: {} -> {}",
+                    change.key,
+                    or_none(hex(&change.old_value)),
+                    or_none(hex(&change.new_value))
+                );
+            }
+        }
+        OutputFormat::Json => {
+            let tx_results: Vec<_> = results
+                .tx_results
+                .iter()
+                .map(|tx| {
+                    serde_json::json!({
+                        "hash": tx.hash,
+                        "event_type": tx.event_type,
+                        "code": tx.code,
+                        "gas_used": tx.gas_used,
+                        "info": tx.info,
+                    })
+                })
+                .collect();
+            let events: Vec<_> = results
+                .events
+                .iter()
+                .map(|event| {
+                    serde_json::json!({
+                        "event_type": event.event_type,
+                        "attributes": event.attributes,
+                    })
+                })
+                .collect();
+            let validator_updates: Vec<_> = results
+                .validator_updates
+                .iter()
+                .map(|update| {
+                    serde_json::json!({
+                        "consensus_key": update.consensus_key.to_string(),
+                        "voting_power": update.voting_power,
+                    })
+                })
+                .collect();
+            let storage_changes: Vec<_> = results
+                .storage_changes
+                .iter()
+                .map(|change| {
+                    serde_json::json!({
+                        "key": change.key.to_string(),
+                        "old_value": hex(&change.old_value),
+                        "new_value": hex(&change.new_value),
+                    })
+                })
+                .collect();
+            print_json(&serde_json::json!({
+                "height": results.height.0,
+                "gas_used": results.gas_used,
+                "tx_results": tx_results,
+                "events": events,
+                "validator_updates": validator_updates,
+                "storage_changes": storage_changes,
+            }));
+        }
+    }
+}
+
+/// Query the transactions recorded by the node's transaction indexer
+pub async fn query_indexed_txs(ctx: Context, args: args::QueryIndexedTxs) {
+    let path = match (args.address, args.key_prefix) {
+        (Some(address), _) => Path::TxsByAddress(ctx.get(&address)),
+        (None, Some(prefix)) => Path::TxsByKeyPrefix(prefix),
+        (None, None) => {
+            eprintln!("Either an address or a storage key prefix is required");
+            cli::safe_exit(1)
+        }
+    };
+    let client = HttpClient::new(args.query.ledger_address).unwrap();
+    let response = client
+        .abci_query(Some(path.into()), vec![], None, false)
+        .await
+        .unwrap();
+    if let Code::Err(err) = response.code {
+        eprintln!("Error in the query {} (error code {})", response.info, err);
+        cli::safe_exit(1)
+    }
+    let txs = match Vec::<IndexedTx>::try_from_slice(&response.value[..]) {
+        Ok(txs) => txs,
+        Err(err) => {
+            eprintln!("Error decoding the indexed transactions: {}", err);
             cli::safe_exit(1)
         }
+    };
+    match ctx.global_args.output {
+        OutputFormat::Human => {
+            if txs.is_empty() {
+                println!("No transactions found");
+            }
+            for tx in txs {
+                println!("Transaction {} at height {}", tx.hash, tx.height);
+                if let Some(sender) = &tx.sender {
+                    println!("  Sender: {}", sender);
+                }
+                if let Some(code) = &tx.code {
+                    println!("  Code: {}", code);
+                }
+                println!("  Gas used: {}", tx.gas_used);
+                println!("  Addresses: {}", tx.addresses.iter().join(", "));
+                println!(
+                    "  Changed keys: {}",
+                    tx.changed_keys.iter().join(", ")
+                );
+                let events =
+                    tx.events.iter().map(|event| &event.event_type).join(", ");
+                println!("  Events: {}", events);
+            }
+        }
+        OutputFormat::Json => {
+            let txs: Vec<_> = txs
+                .iter()
+                .map(|tx| {
+                    serde_json::json!({
+                        "hash": tx.hash,
+                        "height": tx.height,
+                        "sender": tx.sender,
+                        "code": tx.code,
+                        "gas_used": tx.gas_used,
+                        "addresses": tx.addresses,
+                        "changed_keys": tx.changed_keys,
+                        "events": tx.events.iter().map(|event| {
+                            serde_json::json!({
+                                "event_type": event.event_type,
+                                "height": event.height,
+                                "attributes": event.attributes,
+                            })
+                        }).collect::<Vec<_>>(),
+                    })
+                })
+                .collect();
+            print_json(&txs);
+        }
     }
 }
 
+/// A token balance in the JSON output
+#[derive(Debug, Serialize)]
+struct BalanceEntry {
+    token: Address,
+    owner: Address,
+    amount: String,
+}
+
 /// Query token balance(s)
 pub async fn query_balance(ctx: Context, args: args::QueryBalance) {
-    let client = HttpClient::new(args.query.ledger_address).unwrap();
+    let client = HttpClient::new(args.query.ledger_address.clone()).unwrap();
+    if ctx.global_args.output == OutputFormat::Json {
+        return print_balances_json(&ctx, client, args).await;
+    }
     let tokens = address::tokens();
     match (args.token, args.owner) {
         (Some(token), Some(owner)) => {
@@ -123,7 +351,7 @@ pub async fn query_balance(ctx: Context, args: args::QueryBalance) {
                 .get(&token)
                 .map(|c| Cow::Borrowed(*c))
                 .unwrap_or_else(|| Cow::Owned(token.to_string()));
-            match query_storage_value::<token::Amount>(&client, &key).await {
+            match query_balance_proven(&client, &key).await {
                 Some(balance) => {
                     println!("{}: {}", currency_code, balance);
                 }
@@ -201,14 +429,196 @@ pub async fn query_balance(ctx: Context, args: args::QueryBalance) {
     }
 }
 
+/// A token in the JSON output
+#[derive(Debug, Serialize)]
+struct TokenEntry {
+    address: Address,
+    symbol: String,
+    decimals: Option<u8>,
+    minter: Option<Address>,
+    total_supply: String,
+}
+
+/// Query token metadata and total supply
+pub async fn query_tokens(ctx: Context, args: args::QueryTokens) {
+    let client = HttpClient::new(args.query.ledger_address.clone()).unwrap();
+    let known_tokens = address::tokens();
+    let tokens: Vec<Address> = match args.token {
+        Some(token) => vec![ctx.get(&token)],
+        None => {
+            // The known tokens and any tokens in the wallet
+            let mut tokens: Vec<Address> =
+                known_tokens.keys().cloned().collect();
+            for address in ctx.wallet.get_addresses().into_values() {
+                if !tokens.contains(&address) {
+                    tokens.push(address);
+                }
+            }
+            tokens
+        }
+    };
+    let output = ctx.global_args.output;
+    let stdout = io::stdout();
+    let mut w = stdout.lock();
+    let mut found_any = false;
+    let mut entries = vec![];
+    for token in tokens {
+        let metadata = query_storage_value::<token::TokenMetadata>(
+            &client,
+            &token::metadata_key(&token),
+        )
+        .await;
+        let symbol = match (&metadata, known_tokens.get(&token)) {
+            (Some(metadata), _) => Cow::Owned(metadata.symbol.clone()),
+            (None, Some(currency_code)) => Cow::Borrowed(*currency_code),
+            (None, None) => continue,
+        };
+        found_any = true;
+        let supply = query_storage_prefix::<token::Amount>(
+            client.clone(),
+            token::balance_prefix(&token),
+        )
+        .await
+        .map(|balances| {
+            balances.fold(token::Amount::default(), |acc, (_key, balance)| {
+                acc + balance
+            })
+        })
+        .unwrap_or_default();
+        if output == OutputFormat::Json {
+            entries.push(TokenEntry {
+                symbol: symbol.into_owned(),
+                decimals: metadata.as_ref().map(|metadata| metadata.decimals),
+                minter: metadata.and_then(|metadata| metadata.minter),
+                total_supply: supply.to_string(),
+                address: token,
+            });
+            continue;
+        }
+        writeln!(w, "Token {}: {}", symbol, token).unwrap();
+        if let Some(metadata) = metadata {
+            writeln!(w, "  Decimals: {}", metadata.decimals).unwrap();
+            match metadata.minter {
+                Some(minter) => writeln!(w, "  Minter: {}", minter).unwrap(),
+                None => writeln!(w, "  Minter: none").unwrap(),
+            }
+        }
+        writeln!(w, "  Total supply: {}", supply).unwrap();
+    }
+    if output == OutputFormat::Json {
+        print_json(&entries);
+    } else if !found_any {
+        writeln!(w, "No tokens found").unwrap();
+    }
+}
+
+/// Query a balance and verify its proof
+async fn query_balance_proven(
+    client: &HttpClient,
+    key: &storage::Key,
+) -> Option<token::Amount> {
+    query_storage_value_bytes_proven(client, key)
+        .await
+        .map(|bytes| {
+            token::Amount::try_from_slice(&bytes[..]).unwrap_or_else(|err| {
+                eprintln!("Error decoding the balance: {}", err);
+                cli::safe_exit(1)
+            })
+        })
+}
+
+/// Query token balance(s) and print them as JSON
+async fn print_balances_json(
+    ctx: &Context,
+    client: HttpClient,
+    args: args::QueryBalance,
+) {
+    let tokens: Vec<Address> = match &args.token {
+        Some(token) => vec![ctx.get(token)],
+        None => address::tokens().into_keys().collect(),
+    };
+    let mut balances = vec![];
+    for token in tokens {
+        match &args.owner {
+            Some(owner) => {
+                let owner = ctx.get(owner);
+                let key = token::balance_key(&token, &owner);
+                if let Some(amount) = query_balance_proven(&client, &key).await
+                {
+                    balances.push(BalanceEntry {
+                        token,
+                        owner,
+                        amount: amount.to_string(),
+                    });
+                }
+            }
+            None => {
+                let key = token::balance_prefix(&token);
+                let token_balances =
+                    query_storage_prefix::<token::Amount>(client.clone(), key)
+                        .await;
+                for (key, amount) in token_balances.into_iter().flatten() {
+                    let owner =
+                        token::is_any_token_balance_key(&key).unwrap().clone();
+                    balances.push(BalanceEntry {
+                        token: token.clone(),
+                        owner,
+                        amount: amount.to_string(),
+                    });
+                }
+            }
+        }
+    }
+    print_json(&balances);
+}
+
+/// A proposal in the JSON output
+#[derive(Debug, Serialize)]
+struct ProposalEntry {
+    id: u64,
+    author: Address,
+    /// Only set in the details of a single proposal
+    #[serde(skip_serializing_if = "Option::is_none")]
+    content: Option<HashMap<String, String>>,
+    start_epoch: Epoch,
+    end_epoch: Epoch,
+    /// Only set in the details of a single proposal
+    #[serde(skip_serializing_if = "Option::is_none")]
+    grace_epoch: Option<Epoch>,
+    status: &'static str,
+    /// Only set in the details of a single proposal, once it's done
+    #[serde(skip_serializing_if = "Option::is_none")]
+    result: Option<ProposalResultEntry>,
+}
+
+/// The result of a proposal in the JSON output
+#[derive(Debug, Serialize)]
+struct ProposalResultEntry {
+    result: String,
+    total_voting_power: String,
+    total_yay_power: String,
+    total_nay_power: String,
+}
+
+impl From<&ProposalResult> for ProposalResultEntry {
+    fn from(result: &ProposalResult) -> Self {
+        Self {
+            result: result.result.to_string(),
+            total_voting_power: result.total_voting_power.to_string(),
+            total_yay_power: result.total_yay_power.to_string(),
+            total_nay_power: result.total_nay_power.to_string(),
+        }
+    }
+}
+
 /// Query Proposals
-pub async fn query_proposal(_ctx: Context, args: args::QueryProposal) {
-    async fn print_proposal(
+pub async fn query_proposal(ctx: Context, args: args::QueryProposal) {
+    async fn query_proposal_entry(
         client: &HttpClient,
         id: u64,
         current_epoch: Epoch,
         details: bool,
-    ) -> Option<()> {
+    ) -> Option<ProposalEntry> {
         let author_key = gov_storage::get_author_key(id);
         let start_epoch_key = gov_storage::get_voting_start_epoch_key(id);
         let end_epoch_key = gov_storage::get_voting_end_epoch_key(id);
@@ -220,6 +630,23 @@ pub async fn query_proposal(_ctx: Context, args: args::QueryProposal) {
         let end_epoch =
             query_storage_value::<Epoch>(client, &end_epoch_key).await?;
 
+        let status = if start_epoch > current_epoch {
+            "pending"
+        } else if start_epoch <= current_epoch && current_epoch <= end_epoch {
+            "on-going"
+        } else {
+            "done"
+        };
+        let mut proposal = ProposalEntry {
+            id,
+            author,
+            content: None,
+            start_epoch,
+            end_epoch,
+            grace_epoch: None,
+            status,
+            result: None,
+        };
         if details {
             let content_key = gov_storage::get_content_key(id);
             let grace_epoch_key = gov_storage::get_grace_epoch_key(id);
@@ -230,55 +657,51 @@ pub async fn query_proposal(_ctx: Context, args: args::QueryProposal) {
             .await?;
             let grace_epoch =
                 query_storage_value::<Epoch>(client, &grace_epoch_key).await?;
+            proposal.content = Some(content);
+            proposal.grace_epoch = Some(grace_epoch);
+            if status == "done" {
+                let votes = get_proposal_votes(client, start_epoch, id).await;
+                let proposal_result =
+                    compute_tally(client, start_epoch, &votes).await;
+                proposal.result = Some((&proposal_result).into());
+            }
+        }
+        Some(proposal)
+    }
 
-            println!("Proposal: {}", id);
-            println!("{:4}Author: {}", "", author);
+    fn print_proposal(proposal: &ProposalEntry) {
+        println!("Proposal: {}", proposal.id);
+        println!("{:4}Author: {}", "", proposal.author);
+        if let Some(content) = &proposal.content {
             println!("{:4}Content:", "");
-            for (key, value) in &content {
+            for (key, value) in content {
                 println!("{:8}{}: {}", "", key, value);
             }
-            println!("{:4}Start Epoch: {}", "", start_epoch);
-            println!("{:4}End Epoch: {}", "", end_epoch);
+        }
+        println!("{:4}Start Epoch: {}", "", proposal.start_epoch);
+        println!("{:4}End Epoch: {}", "", proposal.end_epoch);
+        if let Some(grace_epoch) = proposal.grace_epoch {
             println!("{:4}Grace Epoch: {}", "", grace_epoch);
-            if start_epoch > current_epoch {
-                println!("{:4}Status: pending", "");
-            } else if start_epoch <= current_epoch && current_epoch <= end_epoch
-            {
-                println!("{:4}Status: on-going", "");
-            } else {
-                let votes = get_proposal_votes(client, start_epoch, id).await;
-                let proposal_result =
-                    compute_tally(client, start_epoch, votes).await;
-                println!("{:4}Status: done", "");
-                println!("{:4}Result: {}", "", proposal_result);
-            }
-        } else {
-            println!("Proposal: {}", id);
-            println!("{:4}Author: {}", "", author);
-            println!("{:4}Start Epoch: {}", "", start_epoch);
-            println!("{:4}End Epoch: {}", "", end_epoch);
-            if start_epoch > current_epoch {
-                println!("{:4}Status: pending", "");
-            } else if start_epoch <= current_epoch && current_epoch <= end_epoch
-            {
-                println!("{:4}Status: on-going", "");
-            } else {
-                println!("{:4}Status: done", "");
-            }
         }
-
-        Some(())
+        println!("{:4}Status: {}", "", proposal.status);
+        if let Some(result) = &proposal.result {
+            print_proposal_result(result);
+        }
     }
 
+    let output = ctx.global_args.output;
     let client = HttpClient::new(args.query.ledger_address.clone()).unwrap();
-    let current_epoch = query_epoch(args.query.clone()).await;
+    let current_epoch = query_epoch(args.query.clone(), output).await;
     match args.proposal_id {
         Some(id) => {
-            if print_proposal(&client, id, current_epoch, true)
-                .await
-                .is_none()
-            {
-                eprintln!("No valid proposal was found with id {}", id)
+            match query_proposal_entry(&client, id, current_epoch, true).await {
+                Some(proposal) => match output {
+                    OutputFormat::Human => print_proposal(&proposal),
+                    OutputFormat::Json => print_json(&proposal),
+                },
+                None => {
+                    eprintln!("No valid proposal was found with id {}", id)
+                }
             }
         }
         None => {
@@ -288,13 +711,22 @@ pub async fn query_proposal(_ctx: Context, args: args::QueryProposal) {
                     .await
                     .unwrap();
 
+            let mut proposals = vec![];
             for id in 0..last_proposal_id {
-                if print_proposal(&client, id, current_epoch, false)
+                match query_proposal_entry(&client, id, current_epoch, false)
                     .await
-                    .is_none()
                 {
-                    eprintln!("No valid proposal was found with id {}", id)
-                };
+                    Some(proposal) => match output {
+                        OutputFormat::Human => print_proposal(&proposal),
+                        OutputFormat::Json => proposals.push(proposal),
+                    },
+                    None => {
+                        eprintln!("No valid proposal was found with id {}", id)
+                    }
+                }
+            }
+            if output == OutputFormat::Json {
+                print_json(&proposals);
             }
         }
     }
@@ -311,11 +743,12 @@ pub async fn get_token_balance(
 }
 
 pub async fn query_proposal_result(
-    _ctx: Context,
+    ctx: Context,
     args: args::QueryProposalResult,
 ) {
+    let output = ctx.global_args.output;
     let client = HttpClient::new(args.query.ledger_address.clone()).unwrap();
-    let current_epoch = query_epoch(args.query.clone()).await;
+    let current_epoch = query_epoch(args.query.clone(), output).await;
 
     match args.proposal_id {
         Some(id) => {
@@ -327,17 +760,39 @@ pub async fn query_proposal_result(
                 query_storage_value::<Epoch>(&client, &end_epoch_key).await;
 
             match (start_epoch, end_epoch) {
+                (Some(start_epoch), _) if current_epoch < start_epoch => {
+                    eprintln!("The voting on the proposal hasn't started.");
+                    cli::safe_exit(1)
+                }
                 (Some(start_epoch), Some(end_epoch)) => {
-                    if current_epoch > end_epoch {
-                        let votes =
-                            get_proposal_votes(&client, start_epoch, id).await;
-                        let proposal_result =
-                            compute_tally(&client, start_epoch, votes).await;
-                        println!("Proposal: {}", id);
-                        println!("{:4}Result: {}", "", proposal_result);
-                    } else {
-                        eprintln!("Proposal is still in progress.");
-                        cli::safe_exit(1)
+                    let votes =
+                        get_proposal_votes(&client, start_epoch, id).await;
+                    let proposal_result =
+                        compute_tally(&client, start_epoch, &votes).await;
+                    let is_final = current_epoch > end_epoch;
+                    match output {
+                        OutputFormat::Human => {
+                            println!("Proposal: {}", id);
+                            if !is_final {
+                                println!(
+                                    "{:4}The voting is still in progress, the \
+                                     result is not final.",
+                                    ""
+                                );
+                            }
+                            print_proposal_result(&(&proposal_result).into());
+                            print_votes(&votes);
+                        }
+                        OutputFormat::Json => {
+                            let result =
+                                ProposalResultEntry::from(&proposal_result);
+                            print_json(&serde_json::json!({
+                                "id": id,
+                                "final": is_final,
+                                "result": result,
+                                "votes": votes_json(&votes),
+                            }))
+                        }
                     }
                 }
                 _ => {
@@ -417,11 +872,29 @@ pub async fn query_proposal_result(
                             files,
                         )
                         .await;
-                        let proposal_result =
-                            compute_tally(&client, proposal.tally_epoch, votes)
-                                .await;
+                        let proposal_result = compute_tally(
+                            &client,
+                            proposal.tally_epoch,
+                            &votes,
+                        )
+                        .await;
 
-                        println!("{:4}Result: {}", "", proposal_result);
+                        match output {
+                            OutputFormat::Human => {
+                                print_proposal_result(
+                                    &(&proposal_result).into(),
+                                );
+                                print_votes(&votes);
+                            }
+                            OutputFormat::Json => {
+                                let result =
+                                    ProposalResultEntry::from(&proposal_result);
+                                print_json(&serde_json::json!({
+                                    "result": result,
+                                    "votes": votes_json(&votes),
+                                }))
+                            }
+                        }
                     }
                     None => {
                         eprintln!(
@@ -439,98 +912,217 @@ pub async fn query_proposal_result(
 }
 
 pub async fn query_protocol_parameters(
-    _ctx: Context,
+    ctx: Context,
     args: args::QueryProtocolParameters,
 ) {
     let client = HttpClient::new(args.query.ledger_address).unwrap();
 
-    println!("Goveranance parameters");
     let key = gov_storage::get_max_proposal_code_size_key();
     let max_proposal_code_size = query_storage_value::<u64>(&client, &key)
         .await
         .expect("Parameter should be definied.");
-    println!(
-        "{:4}Max. proposal code size: {}",
-        "", max_proposal_code_size
-    );
-
     let key = gov_storage::get_max_proposal_content_key();
     let max_proposal_content = query_storage_value::<u64>(&client, &key)
         .await
         .expect("Parameter should be definied.");
-    println!(
-        "{:4}Max. proposal content size: {}",
-        "", max_proposal_content
-    );
-
     let key = gov_storage::get_min_proposal_fund_key();
     let min_proposal_fund = query_storage_value::<Amount>(&client, &key)
         .await
         .expect("Parameter should be definied.");
-    println!("{:4}Min. proposal funds: {}", "", min_proposal_fund);
-
     let key = gov_storage::get_min_proposal_grace_epoch_key();
     let min_proposal_grace_epoch = query_storage_value::<u64>(&client, &key)
         .await
         .expect("Parameter should be definied.");
-    println!(
-        "{:4}Min. proposal grace epoch: {}",
-        "", min_proposal_grace_epoch
-    );
-
     let key = gov_storage::get_min_proposal_period_key();
     let min_proposal_period = query_storage_value::<u64>(&client, &key)
         .await
         .expect("Parameter should be definied.");
-    println!("{:4}Min. proposal period: {}", "", min_proposal_period);
 
-    println!("Protocol parameters");
     let key = param_storage::get_epoch_storage_key();
     let epoch_duration = query_storage_value::<EpochDuration>(&client, &key)
         .await
         .expect("Parameter should be definied.");
-    println!(
-        "{:4}Min. epoch duration: {}",
-        "", epoch_duration.min_duration
-    );
-    println!(
-        "{:4}Min. number of blocks: {}",
-        "", epoch_duration.min_num_of_blocks
-    );
-
     let key = param_storage::get_max_expected_time_per_block_key();
     let max_block_duration = query_storage_value::<u64>(&client, &key)
         .await
         .expect("Parameter should be definied.");
-    println!("{:4}Max. block duration: {}", "", max_block_duration);
-
     let key = param_storage::get_tx_whitelist_storage_key();
     let vp_whitelist = query_storage_value::<Vec<String>>(&client, &key)
         .await
         .expect("Parameter should be definied.");
-    println!("{:4}VP whitelist: {:?}", "", vp_whitelist);
-
     let key = param_storage::get_tx_whitelist_storage_key();
     let tx_whitelist = query_storage_value::<Vec<String>>(&client, &key)
         .await
         .expect("Parameter should be definied.");
-    println!("{:4}Transactions whitelist: {:?}", "", tx_whitelist);
+    // Not set on the chains initialized before the limits were added
+    let key = param_storage::get_tx_write_size_limits_key();
+    let tx_write_size_limits = query_storage_value::<SizeLimits>(&client, &key)
+        .await
+        .unwrap_or_default();
+    // Not set on the chains initialized before the fee tokens were added
+    let key = param_storage::get_fee_tokens_key();
+    let fee_tokens = query_storage_value::<FeeTokens>(&client, &key)
+        .await
+        .unwrap_or_else(parameters::default_fee_tokens);
+    // Not set on the chains initialized before the inflation was added
+    let key = param_storage::get_inflation_params_key();
+    let inflation_params = query_storage_value::<InflationParams>(&client, &key)
+        .await
+        .unwrap_or_default();
+    let key = param_storage::get_inflation_key();
+    let inflation = query_storage_value::<Inflation>(&client, &key).await;
 
-    println!("Treasury parameters");
     let key = treasury_storage::get_max_transferable_fund_key();
     let max_transferable_amount = query_storage_value::<Amount>(&client, &key)
         .await
         .expect("Parameter should be definied.");
+    // Not set on the chains initialized before the fee share was added
+    let key = treasury_storage::get_fee_share_key();
+    let fee_share = query_storage_value::<u64>(&client, &key)
+        .await
+        .unwrap_or_default();
+
+    let key = pos::params_key();
+    let pos_params = query_storage_value::<PosParams>(&client, &key)
+        .await
+        .expect("Parameter should be definied.");
+
+    if ctx.global_args.output == OutputFormat::Json {
+        let fee_tokens: BTreeMap<_, _> = fee_tokens
+            .into_iter()
+            .map(|(token, min_gas_price)| {
+                (token.encode(), min_gas_price.to_string())
+            })
+            .collect();
+        let inflation = inflation.map(|inflation| {
+            serde_json::json!({
+                "epoch": inflation.epoch,
+                "total_supply": inflation.total_supply.to_string(),
+                "total_staked": inflation.total_staked.to_string(),
+                "staked_ratio": inflation.staked_ratio,
+                "rate": inflation.rate,
+                "block_reward": inflation.block_reward.to_string(),
+            })
+        });
+        return print_json(&serde_json::json!({
+            "governance": {
+                "max_proposal_code_size": max_proposal_code_size,
+                "max_proposal_content_size": max_proposal_content,
+                "min_proposal_fund": min_proposal_fund.to_string(),
+                "min_proposal_grace_epoch": min_proposal_grace_epoch,
+                "min_proposal_period": min_proposal_period,
+            },
+            "protocol": {
+                "min_epoch_duration": epoch_duration.min_duration.0,
+                "min_num_of_blocks": epoch_duration.min_num_of_blocks,
+                "max_block_duration": max_block_duration,
+                "vp_whitelist": vp_whitelist,
+                "tx_whitelist": tx_whitelist,
+                "max_tx_key_bytes": tx_write_size_limits.max_key_bytes,
+                "max_tx_value_bytes": tx_write_size_limits.max_value_bytes,
+                "fee_tokens": fee_tokens,
+                "inflation": {
+                    "target_staked_ratio": inflation_params.target_staked_ratio,
+                    "min_rate": inflation_params.min_rate,
+                    "max_rate": inflation_params.max_rate,
+                    "max_rate_change": inflation_params.max_rate_change,
+                    "current": inflation,
+                },
+            },
+            "treasury": {
+                "max_transferable_amount": max_transferable_amount.to_string(),
+                "fee_share": fee_share,
+            },
+            "pos": {
+                "block_proposer_reward": pos_params
+                    .block_proposer_reward
+                    .to_string(),
+                "block_vote_reward": pos_params.block_vote_reward.to_string(),
+                "duplicate_vote_slash_rate": pos_params
+                    .duplicate_vote_slash_rate
+                    .to_string(),
+                "light_client_attack_slash_rate": pos_params
+                    .light_client_attack_slash_rate
+                    .to_string(),
+                "max_validator_slots": pos_params.max_validator_slots,
+                "pipeline_len": pos_params.pipeline_len,
+                "unbonding_len": pos_params.unbonding_len,
+                "votes_per_token": pos_params.votes_per_token.to_string(),
+            },
+        }));
+    }
+
+    println!("Goveranance parameters");
+    println!(
+        "{:4}Max. proposal code size: {}",
+        "", max_proposal_code_size
+    );
+    println!(
+        "{:4}Max. proposal content size: {}",
+        "", max_proposal_content
+    );
+    println!("{:4}Min. proposal funds: {}", "", min_proposal_fund);
+    println!(
+        "{:4}Min. proposal grace epoch: {}",
+        "", min_proposal_grace_epoch
+    );
+    println!("{:4}Min. proposal period: {}", "", min_proposal_period);
+
+    println!("Protocol parameters");
+    println!(
+        "{:4}Min. epoch duration: {}",
+        "", epoch_duration.min_duration
+    );
+    println!(
+        "{:4}Min. number of blocks: {}",
+        "", epoch_duration.min_num_of_blocks
+    );
+    println!("{:4}Max. block duration: {}", "", max_block_duration);
+    println!("{:4}VP whitelist: {:?}", "", vp_whitelist);
+    println!("{:4}Transactions whitelist: {:?}", "", tx_whitelist);
+    println!(
+        "{:4}Max. transaction key length: {}",
+        "", tx_write_size_limits.max_key_bytes
+    );
+    println!(
+        "{:4}Max. transaction value size: {}",
+        "", tx_write_size_limits.max_value_bytes
+    );
+    println!("{:4}Fee tokens:", "");
+    for (token, min_gas_price) in fee_tokens {
+        println!("{:8}{}: min. gas price {}", "", token, min_gas_price);
+    }
+    println!("{:4}Inflation:", "");
+    println!(
+        "{:8}Target staked ratio (basis points): {}",
+        "", inflation_params.target_staked_ratio
+    );
+    println!("{:8}Min. rate (basis points): {}", "", inflation_params.min_rate);
+    println!("{:8}Max. rate (basis points): {}", "", inflation_params.max_rate);
+    println!(
+        "{:8}Max. rate change per epoch (basis points): {}",
+        "", inflation_params.max_rate_change
+    );
+    if let Some(inflation) = inflation {
+        println!("{:8}Epoch {}:", "", inflation.epoch);
+        println!("{:12}Total supply: {}", "", inflation.total_supply);
+        println!("{:12}Total staked: {}", "", inflation.total_staked);
+        println!(
+            "{:12}Staked ratio (basis points): {}",
+            "", inflation.staked_ratio
+        );
+        println!("{:12}Rate (basis points): {}", "", inflation.rate);
+        println!("{:12}Block reward: {}", "", inflation.block_reward);
+    }
+
+    println!("Treasury parameters");
     println!(
         "{:4}Max. transferable amount: {}",
         "", max_transferable_amount
     );
+    println!("{:4}Fee share (basis points): {}", "", fee_share);
 
     println!("PoS parameters");
-    let key = pos::params_key();
-    let pos_params = query_storage_value::<PosParams>(&client, &key)
-        .await
-        .expect("Parameter should be definied.");
     println!(
         "{:4}Block proposer reward: {}",
         "", pos_params.block_proposer_reward
@@ -558,8 +1150,11 @@ pub async fn query_protocol_parameters(
 
 /// Query PoS bond(s)
 pub async fn query_bonds(ctx: Context, args: args::QueryBonds) {
-    let epoch = query_epoch(args.query.clone()).await;
-    let client = HttpClient::new(args.query.ledger_address).unwrap();
+    let epoch = query_epoch(args.query.clone(), ctx.global_args.output).await;
+    let client = HttpClient::new(args.query.ledger_address.clone()).unwrap();
+    if ctx.global_args.output == OutputFormat::Json {
+        return print_bonds_json(&ctx, client, args, epoch).await;
+    }
     match (args.owner, args.validator) {
         (Some(owner), Some(validator)) => {
             let source = ctx.get(&owner);
@@ -911,11 +1506,214 @@ pub async fn query_bonds(ctx: Context, args: args::QueryBonds) {
     }
 }
 
+/// A bond in the JSON output
+#[derive(Debug, Serialize)]
+struct BondEntry {
+    source: Address,
+    validator: Address,
+    /// The bonded amounts after slashing by the epoch from which they're
+    /// active
+    deltas: Vec<BondDeltaEntry>,
+    total: String,
+    active: String,
+}
+
+/// A bonded amount in the JSON output
+#[derive(Debug, Serialize)]
+struct BondDeltaEntry {
+    epoch: u64,
+    amount: String,
+}
+
+/// An unbond in the JSON output
+#[derive(Debug, Serialize)]
+struct UnbondEntry {
+    source: Address,
+    validator: Address,
+    /// The unbonded amounts after slashing
+    deltas: Vec<UnbondDeltaEntry>,
+    total: String,
+    withdrawable: String,
+}
+
+/// An unbonded amount in the JSON output
+#[derive(Debug, Serialize)]
+struct UnbondDeltaEntry {
+    active_from: u64,
+    withdrawable_from: u64,
+    amount: String,
+}
+
+/// Query PoS bond(s) and print them as JSON
+async fn print_bonds_json(
+    ctx: &Context,
+    client: HttpClient,
+    args: args::QueryBonds,
+    epoch: Epoch,
+) {
+    // Find the bonds and unbonds with their IDs
+    let (bonds, unbonds): (Vec<_>, Vec<_>) =
+        match (&args.owner, &args.validator) {
+            (owner, Some(validator)) => {
+                let validator = ctx.get(validator);
+                let source = match owner {
+                    Some(owner) => ctx.get(owner),
+                    None => validator.clone(),
+                };
+                let bond_id = pos::BondId { source, validator };
+                let bonds = query_storage_value::<pos::Bonds>(
+                    &client,
+                    &pos::bond_key(&bond_id),
+                )
+                .await;
+                let unbonds = query_storage_value::<pos::Unbonds>(
+                    &client,
+                    &pos::unbond_key(&bond_id),
+                )
+                .await;
+                (
+                    bonds
+                        .map(|bonds| (bond_id.clone(), bonds))
+                        .into_iter()
+                        .collect(),
+                    unbonds
+                        .map(|unbonds| (bond_id, unbonds))
+                        .into_iter()
+                        .collect(),
+                )
+            }
+            (owner, None) => {
+                let (bonds_prefix, unbonds_prefix) = match owner {
+                    Some(owner) => {
+                        let owner = ctx.get(owner);
+                        (
+                            pos::bonds_for_source_prefix(&owner),
+                            pos::unbonds_for_source_prefix(&owner),
+                        )
+                    }
+                    None => (pos::bonds_prefix(), pos::unbonds_prefix()),
+                };
+                let bonds = query_storage_prefix::<pos::Bonds>(
+                    client.clone(),
+                    bonds_prefix,
+                )
+                .await;
+                let unbonds = query_storage_prefix::<pos::Unbonds>(
+                    client.clone(),
+                    unbonds_prefix,
+                )
+                .await;
+                (
+                    bonds
+                        .into_iter()
+                        .flatten()
+                        .map(|(key, bonds)| match pos::is_bond_key(&key) {
+                            Some(bond_id) => (bond_id, bonds),
+                            None => panic!("Unexpected storage key {}", key),
+                        })
+                        .collect(),
+                    unbonds
+                        .into_iter()
+                        .flatten()
+                        .map(|(key, unbonds)| match pos::is_unbond_key(&key) {
+                            Some(bond_id) => (bond_id, unbonds),
+                            None => panic!("Unexpected storage key {}", key),
+                        })
+                        .collect(),
+                )
+            }
+        };
+
+    let mut bond_entries = vec![];
+    for (pos::BondId { source, validator }, bonds) in bonds {
+        let slashes = query_storage_value::<pos::Slashes>(
+            &client,
+            &pos::validator_slashes_key(&validator),
+        )
+        .await
+        .unwrap_or_default();
+        let mut deltas = vec![];
+        let mut total: token::Amount = 0.into();
+        let mut active: token::Amount = 0.into();
+        for bond in bonds.iter() {
+            for (epoch_start, &delta) in bond.deltas.iter().sorted() {
+                let delta =
+                    apply_slashes(&slashes, delta, *epoch_start, None, None);
+                total += delta;
+                if epoch >= Epoch::from(*epoch_start) {
+                    active += delta;
+                }
+                deltas.push(BondDeltaEntry {
+                    epoch: (*epoch_start).into(),
+                    amount: delta.to_string(),
+                });
+            }
+        }
+        bond_entries.push(BondEntry {
+            source,
+            validator,
+            deltas,
+            total: total.to_string(),
+            active: active.to_string(),
+        });
+    }
+
+    let mut unbond_entries = vec![];
+    for (pos::BondId { source, validator }, unbonds) in unbonds {
+        let slashes = query_storage_value::<pos::Slashes>(
+            &client,
+            &pos::validator_slashes_key(&validator),
+        )
+        .await
+        .unwrap_or_default();
+        let mut deltas = vec![];
+        let mut total: token::Amount = 0.into();
+        let mut withdrawable: token::Amount = 0.into();
+        for unbond in unbonds.iter() {
+            for ((epoch_start, epoch_end), &delta) in
+                unbond.deltas.iter().sorted()
+            {
+                let withdraw_epoch = *epoch_end + 1_u64;
+                let delta = apply_slashes(
+                    &slashes,
+                    delta,
+                    *epoch_start,
+                    Some(withdraw_epoch),
+                    None,
+                );
+                total += delta;
+                if epoch > Epoch::from(*epoch_end) {
+                    withdrawable += delta;
+                }
+                deltas.push(UnbondDeltaEntry {
+                    active_from: (*epoch_start).into(),
+                    withdrawable_from: withdraw_epoch.into(),
+                    amount: delta.to_string(),
+                });
+            }
+        }
+        unbond_entries.push(UnbondEntry {
+            source,
+            validator,
+            deltas,
+            total: total.to_string(),
+            withdrawable: withdrawable.to_string(),
+        });
+    }
+
+    print_json(&serde_json::json!({
+        "epoch": epoch.0,
+        "bonds": bond_entries,
+        "unbonds": unbond_entries,
+    }));
+}
+
 /// Query PoS voting power
 pub async fn query_voting_power(ctx: Context, args: args::QueryVotingPower) {
+    let output = ctx.global_args.output;
     let epoch = match args.epoch {
         Some(epoch) => epoch,
-        None => query_epoch(args.query.clone()).await,
+        None => query_epoch(args.query.clone(), output).await,
     };
     let client = HttpClient::new(args.query.ledger_address).unwrap();
 
@@ -928,6 +1726,36 @@ pub async fn query_voting_power(ctx: Context, args: args::QueryVotingPower) {
     let validator_set = validator_sets
         .get(epoch)
         .expect("Validator set should be always set in the current epoch");
+    if output == OutputFormat::Json {
+        let validators: Vec<_> = validator_set
+            .active
+            .iter()
+            .map(|validator| (validator, true))
+            .chain(
+                validator_set
+                    .inactive
+                    .iter()
+                    .map(|validator| (validator, false)),
+            )
+            .filter(|(validator, _)| match &args.validator {
+                Some(address) => validator.address == ctx.get(address),
+                None => true,
+            })
+            .map(|(validator, is_active)| {
+                serde_json::json!({
+                    "address": validator.address,
+                    "voting_power": u64::from(validator.voting_power),
+                    "active": is_active,
+                })
+            })
+            .collect();
+        let total_voting_power = query_total_voting_power(&client, epoch).await;
+        return print_json(&serde_json::json!({
+            "epoch": epoch.0,
+            "validators": validators,
+            "total_voting_power": i64::from(total_voting_power),
+        }));
+    }
     match args.validator {
         Some(validator) => {
             let validator = ctx.get(&validator);
@@ -951,9 +1779,9 @@ pub async fn query_voting_power(ctx: Context, args: args::QueryVotingPower) {
                     };
                     let is_active = validator_set.active.contains(&weighted);
                     if !is_active {
-                        debug_assert!(
-                            validator_set.inactive.contains(&weighted)
-                        );
+                        debug_assert!(validator_set
+                            .inactive
+                            .contains(&weighted));
                     }
                     println!(
                         "Validator {} is {}, voting power: {}",
@@ -996,22 +1824,192 @@ pub async fn query_voting_power(ctx: Context, args: args::QueryVotingPower) {
             }
         }
     }
+    let total_voting_power = query_total_voting_power(&client, epoch).await;
+    println!("Total voting power: {}", total_voting_power);
+}
+
+/// Query the PoS total voting power in the given epoch
+async fn query_total_voting_power(
+    client: &HttpClient,
+    epoch: Epoch,
+) -> VotingPowerDelta {
     let total_voting_power_key = pos::total_voting_power_key();
     let total_voting_powers = query_storage_value::<pos::TotalVotingPowers>(
-        &client,
+        client,
         &total_voting_power_key,
     )
     .await
     .expect("Total voting power should always be set");
-    let total_voting_power = total_voting_powers
+    total_voting_powers
         .get(epoch)
-        .expect("Total voting power should be always set in the current epoch");
-    println!("Total voting power: {}", total_voting_power);
+        .expect("Total voting power should be always set in the current epoch")
+}
+
+/// Query PoS validators with their state, stake and scheduled stake changes
+pub async fn query_validators(ctx: Context, args: args::QueryValidators) {
+    let output = ctx.global_args.output;
+    let epoch = match args.epoch {
+        Some(epoch) => epoch,
+        None => query_epoch(args.query.clone(), output).await,
+    };
+    let client = HttpClient::new(args.query.ledger_address).unwrap();
+
+    let params_key = pos::params_key();
+    let params = query_storage_value::<PosParams>(&client, &params_key)
+        .await
+        .expect("PoS parameters should always be set");
+    let validator_set_key = pos::validator_set_key();
+    let validator_sets =
+        query_storage_value::<pos::ValidatorSets>(&client, &validator_set_key)
+            .await
+            .expect("Validator set should always be set");
+    let validator_set = validator_sets
+        .get(epoch)
+        .expect("Validator set should be always set in the current epoch");
+
+    let stdout = io::stdout();
+    let mut w = stdout.lock();
+    if output == OutputFormat::Human {
+        writeln!(w, "Validators in epoch {}:", epoch).unwrap();
+    }
+    let mut entries = vec![];
+    let validators = validator_set
+        .active
+        .iter()
+        .map(|validator| (validator, true))
+        .chain(
+            validator_set
+                .inactive
+                .iter()
+                .map(|validator| (validator, false)),
+        );
+    for (validator, is_active) in validators {
+        let address = &validator.address;
+        let state_key = pos::validator_state_key(address);
+        let state =
+            query_storage_value::<pos::ValidatorStates>(&client, &state_key)
+                .await
+                .and_then(|states| states.get(epoch));
+        let state = match state {
+            Some(pos::types::ValidatorState::Pending) => "pending",
+            Some(pos::types::ValidatorState::Inactive) => "inactive",
+            Some(pos::types::ValidatorState::Candidate) if is_active => {
+                "active"
+            }
+            Some(pos::types::ValidatorState::Candidate) | None => "inactive",
+        };
+        let slashes_key = pos::validator_slashes_key(address);
+        let slashes =
+            query_storage_value::<pos::Slashes>(&client, &slashes_key)
+                .await
+                .unwrap_or_default();
+        let total_deltas_key = pos::validator_total_deltas_key(address);
+        let total_deltas = query_storage_value::<pos::ValidatorTotalDeltas>(
+            &client,
+            &total_deltas_key,
+        )
+        .await
+        .expect("Total deltas should be defined");
+        let stake_at = |epoch: Epoch| {
+            total_deltas
+                .get(epoch)
+                .map(token::Amount::from_change)
+                .unwrap_or_default()
+        };
+        let stake = stake_at(epoch);
+
+        let metadata_key = pos::validator_metadata_key(address);
+        let metadata = query_storage_value::<pos::ValidatorMetadata>(
+            &client,
+            &metadata_key,
+        )
+        .await;
+
+        // Stake changes are scheduled at most until the unbonding epoch
+        let mut scheduled = vec![];
+        let mut prev_stake = stake;
+        for offset in 1..=params.unbonding_len {
+            let epoch = epoch + offset;
+            let stake = stake_at(epoch);
+            if stake != prev_stake {
+                scheduled.push((epoch, stake));
+            }
+            prev_stake = stake;
+        }
+
+        if output == OutputFormat::Json {
+            let scheduled: Vec<_> = scheduled
+                .into_iter()
+                .map(|(epoch, stake)| {
+                    serde_json::json!({
+                        "epoch": epoch.0,
+                        "stake": stake.to_string(),
+                    })
+                })
+                .collect();
+            entries.push(serde_json::json!({
+                "address": address,
+                "moniker": metadata.as_ref().map(|m| &m.moniker),
+                "website": metadata.as_ref().and_then(|m| m.website.as_ref()),
+                "contact": metadata.as_ref().and_then(|m| m.contact.as_ref()),
+                "commission_rate": metadata
+                    .as_ref()
+                    .map(|m| m.commission_rate.to_string()),
+                "max_commission_rate_change": metadata
+                    .as_ref()
+                    .map(|m| m.max_commission_rate_change.to_string()),
+                "state": state,
+                "stake": stake.to_string(),
+                "voting_power": u64::from(validator.voting_power),
+                "slashes": slashes.len(),
+                "scheduled_stake_changes": scheduled,
+            }));
+            continue;
+        }
+
+        writeln!(w, "  {}:", address.encode()).unwrap();
+        if let Some(metadata) = &metadata {
+            writeln!(w, "    Moniker: {}", metadata.moniker).unwrap();
+            if let Some(website) = &metadata.website {
+                writeln!(w, "    Website: {}", website).unwrap();
+            }
+            if let Some(contact) = &metadata.contact {
+                writeln!(w, "    Contact: {}", contact).unwrap();
+            }
+            writeln!(
+                w,
+                "    Commission rate: {} (max. change per epoch: {})",
+                metadata.commission_rate, metadata.max_commission_rate_change
+            )
+            .unwrap();
+        }
+        writeln!(w, "    State: {}", state).unwrap();
+        writeln!(w, "    Stake: {}", stake).unwrap();
+        writeln!(w, "    Voting power: {}", validator.voting_power).unwrap();
+        if !slashes.is_empty() {
+            writeln!(w, "    Slashes: {}", slashes.len()).unwrap();
+        }
+        if !scheduled.is_empty() {
+            writeln!(w, "    Scheduled stake changes:").unwrap();
+            for (epoch, stake) in scheduled {
+                writeln!(w, "      From epoch {}: {}", epoch, stake).unwrap();
+            }
+        }
+    }
+    if output == OutputFormat::Json {
+        print_json(&serde_json::json!({
+            "epoch": epoch.0,
+            "validators": entries,
+        }));
+    }
 }
 
 /// Query PoS slashes
 pub async fn query_slashes(ctx: Context, args: args::QuerySlashes) {
     let client = HttpClient::new(args.query.ledger_address).unwrap();
+    if ctx.global_args.output == OutputFormat::Json {
+        return print_slashes_json(&ctx, client, args.validator).await;
+    }
     match args.validator {
         Some(validator) => {
             let validator = ctx.get(&validator);
@@ -1081,15 +2079,77 @@ pub async fn query_slashes(ctx: Context, args: args::QuerySlashes) {
     }
 }
 
+/// Query PoS slashes and print them as JSON
+async fn print_slashes_json(
+    ctx: &Context,
+    client: HttpClient,
+    validator: Option<WalletAddress>,
+) {
+    let slashes: Vec<(Address, pos::Slashes)> = match validator {
+        Some(validator) => {
+            let validator = ctx.get(&validator);
+            let slashes_key = pos::validator_slashes_key(&validator);
+            query_storage_value::<pos::Slashes>(&client, &slashes_key)
+                .await
+                .map(|slashes| (validator, slashes))
+                .into_iter()
+                .collect()
+        }
+        None => query_storage_prefix::<pos::Slashes>(
+            client.clone(),
+            pos::slashes_prefix(),
+        )
+        .await
+        .into_iter()
+        .flatten()
+        .filter_map(|(slashes_key, slashes)| {
+            match is_validator_slashes_key(&slashes_key) {
+                Some(validator) => Some((validator.clone(), slashes)),
+                None => {
+                    eprintln!("Unexpected slashes key {}", slashes_key);
+                    None
+                }
+            }
+        })
+        .collect(),
+    };
+    let slashes: Vec<_> = slashes
+        .into_iter()
+        .flat_map(|(validator, slashes)| {
+            slashes.into_iter().map(move |slash| {
+                serde_json::json!({
+                    "validator": validator,
+                    "epoch": u64::from(slash.epoch),
+                    "block_height": slash.block_height,
+                    "rate": slash.rate.to_string(),
+                    "type": slash.r#type.to_string(),
+                })
+            })
+        })
+        .collect();
+    print_json(&slashes);
+}
+
 /// Dry run a transaction
-pub async fn dry_run_tx(ledger_address: &TendermintAddress, tx_bytes: Vec<u8>) {
+pub async fn dry_run_tx(
+    ledger_address: &TendermintAddress,
+    tx_bytes: Vec<u8>,
+    output: OutputFormat,
+) {
     let client = HttpClient::new(ledger_address.clone()).unwrap();
     let path = Path::DryRunTx;
     let response = client
         .abci_query(Some(path.into()), tx_bytes, None, false)
         .await
         .unwrap();
-    println!("{:#?}", response);
+    match output {
+        OutputFormat::Human => println!("{:#?}", response),
+        OutputFormat::Json => print_json(&serde_json::json!({
+            "code": u32::from(response.code),
+            "info": response.info,
+            "log": response.log.to_string(),
+        })),
+    }
 }
 
 /// Get account's public key stored in its storage sub-space
@@ -1102,6 +2162,31 @@ pub async fn get_public_key(
     query_storage_value(&client, &key).await
 }
 
+/// Get a validator's metadata
+pub async fn get_validator_metadata(
+    validator: &Address,
+    ledger_address: TendermintAddress,
+) -> Option<pos::ValidatorMetadata> {
+    let client = HttpClient::new(ledger_address).unwrap();
+    let key = pos::validator_metadata_key(validator);
+    query_storage_value(&client, &key).await
+}
+
+/// Get the signer set and threshold of a multisignature account stored in its
+/// storage sub-space
+pub async fn get_multisig_params(
+    address: &Address,
+    ledger_address: TendermintAddress,
+) -> Option<(Vec<common::PublicKey>, u8)> {
+    let client = HttpClient::new(ledger_address).unwrap();
+    let public_keys =
+        query_storage_value(&client, &multisig::public_keys_key(address))
+            .await?;
+    let threshold =
+        query_storage_value(&client, &multisig::threshold_key(address)).await?;
+    Some((public_keys, threshold))
+}
+
 /// Check if the given address is a known validator.
 pub async fn is_validator(
     address: &Address,
@@ -1327,6 +2412,59 @@ where
     cli::safe_exit(1)
 }
 
+/// Query the raw bytes of a storage value at the last committed height
+/// together with a proof and verify the proof against the app hash of that
+/// height. Returns `None` if the key has no value. A value or its absence
+/// without a proof is returned with a warning.
+pub async fn query_storage_value_bytes_proven(
+    client: &HttpClient,
+    key: &storage::Key,
+) -> Option<Vec<u8>> {
+    let info = client.abci_info().await.unwrap();
+    let path = Path::Value(key.to_owned());
+    let data = vec![];
+    let response = client
+        .abci_query(Some(path.into()), data, Some(info.last_block_height), true)
+        .await
+        .unwrap();
+    let value = match response.code {
+        Code::Ok => Some(response.value),
+        Code::Err(1) => None,
+        Code::Err(err) => {
+            eprintln!(
+                "Error in the query {} (error code {})",
+                response.info, err
+            );
+            cli::safe_exit(1)
+        }
+    };
+    match &response.proof {
+        Some(proof) => {
+            let root = MerkleRoot(info.last_block_app_hash);
+            let verified =
+                light_client::verify_value::<PersistentStorageHasher>(
+                    &root,
+                    key,
+                    value.as_deref(),
+                    proof,
+                );
+            if let Err(err) = verified {
+                eprintln!(
+                    "The proof of the value at height {} cannot be verified: \
+                     {}",
+                    info.last_block_height, err
+                );
+                cli::safe_exit(1)
+            }
+        }
+        None => eprintln!(
+            "Warning: the value of {} is returned without a proof",
+            key
+        ),
+    }
+    value
+}
+
 /// Query a range of storage values with a matching prefix and decode them with
 /// [`BorshDeserialize`]. Returns an iterator of the storage keys paired with
 /// their associated values.
@@ -1444,11 +2582,30 @@ impl From<TxEventQuery> for Query {
     }
 }
 
+/// The status of a transaction found on chain
+#[derive(Debug, Serialize)]
+pub struct TxStatus {
+    /// The result of the transaction
+    #[serde(flatten)]
+    pub response: TxResponse,
+    /// Other events emitted by the transaction, e.g. IBC events
+    pub events: Vec<TxEmittedEvent>,
+}
+
+/// An event emitted by a transaction
+#[derive(Debug, Serialize)]
+pub struct TxEmittedEvent {
+    /// The type of the event
+    pub event_type: String,
+    /// The attributes of the event
+    pub attributes: BTreeMap<String, String>,
+}
+
 /// Lookup the full response accompanying the specified transaction event
 pub async fn query_tx_response(
     ledger_address: &TendermintAddress,
     tx_query: TxEventQuery,
-) -> Result<TxResponse, TError> {
+) -> Result<TxStatus, TError> {
     // Connect to the Tendermint server holding the transactions
     let (client, driver) = WebSocketClient::new(ledger_address.clone()).await?;
     let driver_handle = tokio::spawn(async move { driver.run().await });
@@ -1473,28 +2630,26 @@ pub async fn query_tx_response(
         .block_results(block.header.height)
         .await
         .expect("Unable to retrieve block containing transaction");
+    let events = response_block_results.end_block_events.unwrap_or_default();
     // Search for the event where the specified transaction is
     // applied to the blockchain
-    let query_event_opt =
-        response_block_results.end_block_events.and_then(|events| {
-            (&events)
-                .iter()
-                .find(|event| {
-                    event.type_str == tx_query.event_type()
-                        && (&event.attributes).iter().any(|tag| {
-                            tag.key.as_ref() == "hash"
-                                && tag.value.as_ref() == tx_query.tx_hash()
-                        })
+    let query_event_ix = events
+        .iter()
+        .position(|event| {
+            event.type_str == tx_query.event_type()
+                && (&event.attributes).iter().any(|tag| {
+                    tag.key.as_ref() == "hash"
+                        && tag.value.as_ref() == tx_query.tx_hash()
                 })
-                .cloned()
-        });
-    let query_event = query_event_opt.ok_or_else(|| {
-        TError::server(
-            "Unable to find the event corresponding to the specified \
-             transaction"
-                .to_string(),
-        )
-    })?;
+        })
+        .ok_or_else(|| {
+            TError::server(
+                "Unable to find the event corresponding to the specified \
+                 transaction"
+                    .to_string(),
+            )
+        })?;
+    let query_event = &events[query_event_ix];
     // Reformat the event attributes so as to ease value extraction
     let event_map: std::collections::HashMap<&str, &str> = (&query_event
         .attributes)
@@ -1502,7 +2657,7 @@ pub async fn query_tx_response(
         .map(|tag| (tag.key.as_ref(), tag.value.as_ref()))
         .collect();
     // Summarize the transaction results that we were searching for
-    let result = TxResponse {
+    let response = TxResponse {
         info: event_map["info"].to_string(),
         log: event_map["log"].to_string(),
         height: event_map["height"].to_string(),
@@ -1514,6 +2669,27 @@ pub async fn query_tx_response(
         )
         .unwrap_or_default(),
     };
+    // The other events emitted by the transaction are tagged with its hash
+    let emitted_events = events
+        .iter()
+        .filter(|event| {
+            event.type_str != "accepted"
+                && event.type_str != "applied"
+                && (&event.attributes).iter().any(|tag| {
+                    tag.key.as_ref() == "tx_hash"
+                        && tag.value.as_ref() == tx_query.tx_hash()
+                })
+        })
+        .map(|event| TxEmittedEvent {
+            event_type: event.type_str.clone(),
+            attributes: (&event.attributes)
+                .iter()
+                .map(|tag| {
+                    (tag.key.as_ref().to_owned(), tag.value.as_ref().to_owned())
+                })
+                .collect(),
+        })
+        .collect();
     // Signal to the driver to terminate.
     client.close()?;
     // Await the driver's termination to ensure proper connection closure.
@@ -1521,38 +2697,33 @@ pub async fn query_tx_response(
         eprintln!("{}", x);
         cli::safe_exit(1)
     });
-    Ok(result)
+    Ok(TxStatus {
+        response,
+        events: emitted_events,
+    })
 }
 
 /// Lookup the results of applying the specified transaction to the
 /// blockchain.
-
-pub async fn query_result(_ctx: Context, args: args::QueryResult) {
+pub async fn query_result(ctx: Context, args: args::QueryResult) {
+    let output = ctx.global_args.output;
     // First try looking up application event pertaining to given hash.
-    let tx_response = query_tx_response(
+    let tx_status = query_tx_response(
         &args.query.ledger_address,
         TxEventQuery::Applied(args.tx_hash.clone()),
     )
     .await;
-    match tx_response {
-        Ok(result) => {
-            println!(
-                "Transaction was applied with result: {}",
-                serde_json::to_string_pretty(&result).unwrap()
-            )
-        }
+    match tx_status {
+        Ok(status) => print_tx_status("applied", &status, output),
         Err(err1) => {
             // If this fails then instead look for an acceptance event.
-            let tx_response = query_tx_response(
+            let tx_status = query_tx_response(
                 &args.query.ledger_address,
                 TxEventQuery::Accepted(args.tx_hash),
             )
             .await;
-            match tx_response {
-                Ok(result) => println!(
-                    "Transaction was accepted with result: {}",
-                    serde_json::to_string_pretty(&result).unwrap()
-                ),
+            match tx_status {
+                Ok(status) => print_tx_status("accepted", &status, output),
                 Err(err2) => {
                     // Print the errors that caused the lookups to fail
                     eprintln!("{}\n{}", err1, err2);
@@ -1563,6 +2734,35 @@ pub async fn query_result(_ctx: Context, args: args::QueryResult) {
     }
 }
 
+/// Print the status of a transaction in the given format
+fn print_tx_status(event_type: &str, status: &TxStatus, output: OutputFormat) {
+    match output {
+        OutputFormat::Json => print_json(status),
+        OutputFormat::Human => {
+            let TxStatus { response, events } = status;
+            println!("Transaction {} was {}", response.hash, event_type);
+            println!("{:4}Height: {}", "", response.height);
+            println!("{:4}Code: {}", "", response.code);
+            println!("{:4}Gas used: {}", "", response.gas_used);
+            if !response.info.is_empty() {
+                println!("{:4}Info: {}", "", response.info);
+            }
+            if !response.log.is_empty() {
+                println!("{:4}Log: {}", "", response.log);
+            }
+            for address in &response.initialized_accounts {
+                println!("{:4}Initialized account: {}", "", address);
+            }
+            for event in events {
+                println!("{:4}Event {}:", "", event.event_type);
+                for (key, value) in &event.attributes {
+                    println!("{:8}{}: {}", "", key, value);
+                }
+            }
+        }
+    }
+}
+
 pub async fn get_proposal_votes(
     client: &HttpClient,
     epoch: Epoch,
@@ -1576,20 +2776,21 @@ pub async fn get_proposal_votes(
         query_storage_prefix::<ProposalVote>(client.clone(), vote_prefix_key)
             .await;
 
-    let mut yay_validators: HashMap<Address, Amount> = HashMap::new();
-    let mut yay_delegators: HashMap<Address, Amount> = HashMap::new();
-    let mut nay_delegators: HashMap<Address, Amount> = HashMap::new();
-
+    let mut votes = Votes::default();
     if let Some(vote_iter) = vote_iter {
         for (key, vote) in vote_iter {
             let voter_address = gov_storage::get_voter_address(&key)
                 .expect("Vote key should contains the voting address.")
                 .clone();
-            if vote.is_yay() && validators.contains(&voter_address) {
+            if validators.contains(&voter_address) {
                 let amount =
                     get_validator_stake(client, epoch, &voter_address).await;
-                yay_validators.insert(voter_address, amount);
-            } else if !validators.contains(&voter_address) {
+                if vote.is_yay() {
+                    votes.yay_validators.insert(voter_address, amount);
+                } else {
+                    votes.nay_validators.insert(voter_address, amount);
+                }
+            } else {
                 let validator_address =
                     gov_storage::get_vote_delegation_address(&key)
                         .expect(
@@ -1604,21 +2805,18 @@ pub async fn get_proposal_votes(
                 )
                 .await;
                 if let Some(amount) = delegator_token_amount {
-                    if vote.is_yay() {
-                        yay_delegators.insert(voter_address, amount);
-                    } else {
-                        nay_delegators.insert(voter_address, amount);
-                    }
+                    insert_delegator_vote(
+                        &mut votes,
+                        vote.is_yay(),
+                        voter_address,
+                        validator_address,
+                        amount,
+                    );
                 }
             }
         }
     }
-
-    Votes {
-        yay_validators,
-        yay_delegators,
-        nay_delegators,
-    }
+    votes
 }
 
 pub async fn get_proposal_offline_votes(
@@ -1630,10 +2828,7 @@ pub async fn get_proposal_offline_votes(
 
     let proposal_hash = proposal.compute_hash();
 
-    let mut yay_validators: HashMap<Address, Amount> = HashMap::new();
-    let mut yay_delegators: HashMap<Address, Amount> = HashMap::new();
-    let mut nay_delegators: HashMap<Address, Amount> = HashMap::new();
-
+    let mut votes = Votes::default();
     for path in files {
         let file = File::open(&path).expect("Proposal file must exist.");
         let proposal_vote: OfflineVote = serde_json::from_reader(file)
@@ -1650,16 +2845,18 @@ pub async fn get_proposal_offline_votes(
             continue;
         }
 
-        if proposal_vote.vote.is_yay()
-            && validators.contains(&proposal_vote.address)
-        {
+        if validators.contains(&proposal_vote.address) {
             let amount = get_validator_stake(
                 client,
                 proposal.tally_epoch,
                 &proposal_vote.address,
             )
             .await;
-            yay_validators.insert(proposal_vote.address, amount);
+            if proposal_vote.vote.is_yay() {
+                votes.yay_validators.insert(proposal_vote.address, amount);
+            } else {
+                votes.nay_validators.insert(proposal_vote.address, amount);
+            }
         } else if is_delegator_at(
             client,
             &proposal_vote.address,
@@ -1667,82 +2864,135 @@ pub async fn get_proposal_offline_votes(
         )
         .await
         {
-            let key = pos::bonds_for_source_prefix(&proposal_vote.address);
-            let bonds_iter =
-                query_storage_prefix::<pos::Bonds>(client.clone(), key).await;
-            if let Some(bonds) = bonds_iter {
-                for (key, epoched_amount) in bonds {
-                    let bond = epoched_amount
-                        .get(proposal.tally_epoch)
-                        .expect("Delegation bond should be definied.");
-                    let epoch = anoma::ledger::pos::types::Epoch::from(
-                        proposal.tally_epoch.0,
+            let delegations = get_delegators_delegation(
+                client,
+                &proposal_vote.address,
+                proposal.tally_epoch,
+            )
+            .await;
+            for validator_address in delegations {
+                let amount = get_bond_amount_at(
+                    client,
+                    &proposal_vote.address,
+                    &validator_address,
+                    proposal.tally_epoch,
+                )
+                .await;
+                if let Some(amount) = amount {
+                    insert_delegator_vote(
+                        &mut votes,
+                        proposal_vote.vote.is_yay(),
+                        proposal_vote.address.clone(),
+                        validator_address,
+                        amount,
                     );
-                    let amount = *bond
-                        .deltas
-                        .get(&epoch)
-                        .expect("Delegation amount should be definied.");
-                    let validator_address =
-                        pos::get_validator_address_from_bond(&key).expect(
-                            "Delegation key should contain validator address.",
-                        );
-                    if proposal_vote.vote.is_yay() {
-                        yay_delegators.insert(validator_address, amount);
-                    } else {
-                        nay_delegators.insert(validator_address, amount);
-                    }
                 }
             }
         }
     }
+    votes
+}
 
-    Votes {
-        yay_validators,
-        yay_delegators,
-        nay_delegators,
+/// Add the vote of a delegator with its bond to a validator
+fn insert_delegator_vote(
+    votes: &mut Votes,
+    is_yay: bool,
+    delegator: Address,
+    validator: Address,
+    amount: token::Amount,
+) {
+    if amount == token::Amount::default() {
+        return;
     }
+    let delegators = if is_yay {
+        &mut votes.yay_delegators
+    } else {
+        &mut votes.nay_delegators
+    };
+    delegators
+        .entry(delegator)
+        .or_default()
+        .insert(validator, amount);
 }
 
 // Compute the result of a proposal
 pub async fn compute_tally(
     client: &HttpClient,
     epoch: Epoch,
-    votes: Votes,
-) -> TallyResult {
+    votes: &Votes,
+) -> ProposalResult {
     let validators = get_all_validators(client, epoch).await;
     let total_stacked_tokens =
         get_total_staked_tokes(client, epoch, &validators).await;
+    votes.tally(total_stacked_tokens)
+}
 
-    let Votes {
-        yay_validators,
-        yay_delegators,
-        nay_delegators,
-    } = votes;
-
-    let mut total_yay_stacked_tokens = Amount::from(0);
-    for (_, amount) in yay_validators.clone().into_iter() {
-        total_yay_stacked_tokens += amount;
-    }
-
-    // YAY: Add delegator amount whose validator didn't vote / voted nay
-    for (validator_address, amount) in yay_delegators.into_iter() {
-        if !yay_validators.contains_key(&validator_address) {
-            total_yay_stacked_tokens += amount;
+/// Print the votes of a proposal with the stake that they're counted with
+fn print_votes(votes: &Votes) {
+    println!("{:4}Votes:", "");
+    for (vote, validators) in
+        [("yay", &votes.yay_validators), ("nay", &votes.nay_validators)]
+    {
+        for (validator, stake) in validators {
+            println!("{:8}{} voted {} with {}", "", validator, vote, stake);
         }
     }
-
-    // NAY: Remove delegator amount whose validator validator vote yay
-    for (validator_address, amount) in nay_delegators.into_iter() {
-        if yay_validators.contains_key(&validator_address) {
-            total_yay_stacked_tokens -= amount;
+    for (vote, delegators) in
+        [("yay", &votes.yay_delegators), ("nay", &votes.nay_delegators)]
+    {
+        for (delegator, bonds) in delegators {
+            for (validator, amount) in bonds {
+                println!(
+                    "{:8}{} voted {} with {} delegated to {}",
+                    "", delegator, vote, amount, validator
+                );
+            }
         }
     }
+}
 
-    if 3 * total_yay_stacked_tokens >= 2 * total_stacked_tokens {
-        TallyResult::Passed
-    } else {
-        TallyResult::Rejected
-    }
+/// The votes on a proposal in the JSON output
+fn votes_json(votes: &Votes) -> Vec<serde_json::Value> {
+    let validator_votes = [
+        ("yay", &votes.yay_validators),
+        ("nay", &votes.nay_validators),
+    ]
+    .into_iter()
+    .flat_map(|(vote, validators)| {
+        validators.iter().map(move |(validator, stake)| {
+            serde_json::json!({
+                "voter": validator,
+                "vote": vote,
+                "amount": stake.to_string(),
+            })
+        })
+    });
+    let delegator_votes = [
+        ("yay", &votes.yay_delegators),
+        ("nay", &votes.nay_delegators),
+    ]
+    .into_iter()
+    .flat_map(|(vote, delegators)| {
+        delegators.iter().flat_map(move |(delegator, bonds)| {
+            bonds.iter().map(move |(validator, amount)| {
+                serde_json::json!({
+                    "voter": delegator,
+                    "vote": vote,
+                    "amount": amount.to_string(),
+                    "validator": validator,
+                })
+            })
+        })
+    });
+    validator_votes.chain(delegator_votes).collect()
+}
+
+/// Print the result of a proposal with the breakdown of its voting power
+fn print_proposal_result(result: &ProposalResultEntry) {
+    println!("{:4}Result: {}", "", result.result);
+    println!("{:8}Total voting power: {}", "", result.total_voting_power);
+    println!("{:8}Yay voting power: {}", "", result.total_yay_power);
+    println!("{:8}Nay voting power: {}", "", result.total_nay_power);
 }
 
 pub async fn get_bond_amount_at(
@@ -5,6 +5,7 @@ use std::rc::Rc;
 
 use anoma::types::address::{Address, ImplicitAddress};
 use anoma::types::key::*;
+use borsh::{BorshDeserialize, BorshSerialize};
 #[cfg(not(feature = "ABCI"))]
 use tendermint_config::net::Address as TendermintAddress;
 #[cfg(feature = "ABCI")]
@@ -14,6 +15,17 @@ use super::rpc;
 use crate::cli;
 use crate::wallet::Wallet;
 
+/// A signature of a transaction made by one of the signers of a
+/// multisignature account, to be aggregated with the signatures of the other
+/// signers
+#[derive(BorshSerialize, BorshDeserialize)]
+pub struct PartialSignature {
+    /// The public key of the signer
+    pub public_key: common::PublicKey,
+    /// The signature of [`anoma::proto::Tx::tx_to_sign`]
+    pub sig: common::Signature,
+}
+
 /// Find the public key for the given address and try to load the keypair
 /// for it from the wallet. Panics if the key cannot be found or loaded.
 pub async fn find_keypair(
@@ -23,7 +35,7 @@ pub async fn find_keypair(
 ) -> Rc<common::SecretKey> {
     match addr {
         Address::Established(_) => {
-            println!(
+            eprintln!(
                 "Looking-up public key of {} from the ledger...",
                 addr.encode()
             );
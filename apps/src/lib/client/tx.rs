@@ -1,9 +1,22 @@
 use std::borrow::Cow;
+use std::collections::BTreeMap;
 use std::convert::TryFrom;
+use std::fmt::Display;
 use std::fs::File;
-
+use std::rc::Rc;
+use std::time::Duration;
+
+use anoma::ibc::applications::ics20_fungible_token_transfer::msgs::transfer::MsgTransfer;
+use anoma::ibc::core::ics24_host::identifier::PortChannelId;
+use anoma::ibc::signer::Signer;
+use anoma::ibc::timestamp::Timestamp;
+use anoma::ibc::tx_msg::Msg;
+use anoma::ibc::Height;
+use anoma::ibc_proto::cosmos::base::v1beta1::Coin;
 use anoma::ledger::governance::storage as gov_storage;
-use anoma::ledger::pos::{BondId, Bonds, Unbonds};
+use anoma::ledger::ibc::storage as ibc_storage;
+use anoma::ledger::pos::types::{BasisPoints, ValidatorMetadata};
+use anoma::ledger::pos::{BondId, Bonds, PosParams, Unbonds};
 use anoma::proto::Tx;
 use anoma::types::address::{xan as m1t, Address};
 use anoma::types::governance::{
@@ -17,15 +30,18 @@ use anoma::types::transaction::governance::{
     InitProposalData, VoteProposalData,
 };
 use anoma::types::transaction::nft::{CreateNft, MintNft};
+use anoma::types::transaction::token::{ChangeSupply, InitToken, SupplyChange};
 use anoma::types::transaction::{
-    hash_tx, pos, Fee, InitAccount, InitValidator, UpdateVp, WrapperTx,
+    hash_tx, pos, BatchedTx, Fee, InitAccount, InitMultisigAccount,
+    InitValidator, PayloadV1, TxBatch, TxPayload, UpdateVp, WrapperTx,
 };
-use anoma::types::{address, token};
+use anoma::types::{address, multisig, token};
 use anoma::{ledger, vm};
 use async_std::io::{self, WriteExt};
-use borsh::BorshSerialize;
+use borsh::{BorshDeserialize, BorshSerialize};
 use itertools::Either::*;
 use jsonpath_lib as jsonpath;
+use prost::Message;
 use serde::Serialize;
 #[cfg(not(feature = "ABCI"))]
 use tendermint_config::net::Address as TendermintAddress;
@@ -44,7 +60,10 @@ use tendermint_rpc_abci::query::{EventType, Query};
 #[cfg(feature = "ABCI")]
 use tendermint_rpc_abci::{Client, HttpClient};
 
+use super::output::{print_json, print_progress};
+use super::signing::PartialSignature;
 use super::{rpc, signing};
+use crate::cli::args::{BroadcastMode, OutputFormat};
 use crate::cli::context::WalletAddress;
 use crate::cli::{args, safe_exit, Context};
 use crate::client::tendermint_websocket_client::{
@@ -55,17 +74,27 @@ use crate::node::ledger::events::{Attributes, EventType as TmEventType};
 use crate::node::ledger::tendermint_node;
 
 const TX_INIT_ACCOUNT_WASM: &str = "tx_init_account.wasm";
+const TX_INIT_MULTISIG_ACCOUNT_WASM: &str = "tx_init_multisig_account.wasm";
 const TX_INIT_VALIDATOR_WASM: &str = "tx_init_validator.wasm";
+const TX_INIT_TOKEN_WASM: &str = "tx_init_token.wasm";
 const TX_INIT_PROPOSAL: &str = "tx_init_proposal.wasm";
 const TX_VOTE_PROPOSAL: &str = "tx_vote_proposal.wasm";
 const TX_UPDATE_VP_WASM: &str = "tx_update_vp.wasm";
 const TX_TRANSFER_WASM: &str = "tx_transfer.wasm";
+const TX_IBC_WASM: &str = "tx_ibc.wasm";
 const TX_INIT_NFT: &str = "tx_init_nft.wasm";
 const TX_MINT_NFT: &str = "tx_mint_nft.wasm";
+const TX_REVEAL_PK: &str = "tx_reveal_pk.wasm";
 const VP_USER_WASM: &str = "vp_user.wasm";
+const VP_MULTISIG_WASM: &str = "vp_multisig.wasm";
+const VP_TOKEN_WASM: &str = "vp_token.wasm";
 const TX_BOND_WASM: &str = "tx_bond.wasm";
 const TX_UNBOND_WASM: &str = "tx_unbond.wasm";
 const TX_WITHDRAW_WASM: &str = "tx_withdraw.wasm";
+const TX_CHANGE_CONSENSUS_KEY_WASM: &str = "tx_change_consensus_key.wasm";
+const TX_CHANGE_SUPPLY_WASM: &str = "tx_change_supply.wasm";
+const TX_UPDATE_VALIDATOR_METADATA_WASM: &str =
+    "tx_update_validator_metadata.wasm";
 const VP_NFT: &str = "vp_nft.wasm";
 
 /// Data needed for broadcasting a tx and
@@ -74,12 +103,16 @@ const VP_NFT: &str = "vp_nft.wasm";
 /// Txs may be either a dry run or else
 /// they should be encrypted and included
 /// in a wrapper.
+#[derive(BorshSerialize, BorshDeserialize)]
 pub enum TxBroadcastData {
     DryRun(Tx),
     Wrapper {
         tx: Tx,
         wrapper_hash: String,
         decrypted_hash: Option<String>,
+        /// The canonical hash of the tx, i.e. the hash of the signed inner
+        /// tx, which identifies it in the tx index and to the VPs
+        tx_hash: String,
     },
 }
 
@@ -93,6 +126,36 @@ pub async fn submit_custom(ctx: Context, args: args::TxCustom) {
     save_initialized_accounts(ctx, &args.tx, initialized_accounts).await;
 }
 
+pub async fn submit_batch(ctx: Context, args: args::TxBatch) {
+    if !args.data_paths.is_empty()
+        && args.data_paths.len() != args.code_paths.len()
+    {
+        eprintln!(
+            "Expected a data file for each of the {} transactions in the \
+             batch, got {}.",
+            args.code_paths.len(),
+            args.data_paths.len()
+        );
+        safe_exit(1)
+    }
+    let mut data_paths = args.data_paths.iter();
+    let txs = args
+        .code_paths
+        .iter()
+        .map(|code_path| {
+            let code = ctx.read_wasm(code_path);
+            let data = data_paths.next().map(|data_path| {
+                std::fs::read(data_path)
+                    .expect("Expected a file at given data path")
+            });
+            BatchedTx { code, data }
+        })
+        .collect();
+    let tx = TxBatch::new(txs).into_tx();
+    let (ctx, initialized_accounts) = process_tx(ctx, &args.tx, tx, None).await;
+    save_initialized_accounts(ctx, &args.tx, initialized_accounts).await;
+}
+
 pub async fn submit_update_vp(ctx: Context, args: args::TxUpdateVp) {
     let addr = ctx.get(&args.addr);
 
@@ -166,6 +229,89 @@ pub async fn submit_init_account(mut ctx: Context, args: args::TxInitAccount) {
         public_key,
         vp_code,
     };
+    let data = TxPayload::from(PayloadV1::InitAccount(data)).encode();
+
+    let tx = Tx::new(tx_code, Some(data));
+    let (ctx, initialized_accounts) =
+        process_tx(ctx, &args.tx, tx, Some(&args.source)).await;
+    save_initialized_accounts(ctx, &args.tx, initialized_accounts).await;
+}
+
+pub async fn submit_init_multisig_account(
+    mut ctx: Context,
+    args: args::TxInitMultisigAccount,
+) {
+    let public_keys: Vec<common::PublicKey> = args
+        .public_keys
+        .iter()
+        .map(|public_key| ctx.get_cached(public_key))
+        .collect();
+    if !multisig::validate_params(&public_keys, args.threshold) {
+        eprintln!(
+            "The public keys must be unique and the threshold must be between \
+             1 and the number of the public keys."
+        );
+        if !args.tx.force {
+            safe_exit(1)
+        }
+    }
+    let vp_code = args
+        .vp_code_path
+        .map(|path| ctx.read_wasm(path))
+        .unwrap_or_else(|| ctx.read_wasm(VP_MULTISIG_WASM));
+    // Validate the VP code
+    if let Err(err) = vm::validate_untrusted_wasm(&vp_code) {
+        eprintln!("Validity predicate code validation failed with {}", err);
+        if !args.tx.force {
+            safe_exit(1)
+        }
+    }
+
+    let tx_code = ctx.read_wasm(TX_INIT_MULTISIG_ACCOUNT_WASM);
+    let data = InitMultisigAccount {
+        public_keys,
+        threshold: args.threshold,
+        vp_code,
+    };
+    let data = data.try_to_vec().expect("Encoding tx data shouldn't fail");
+
+    let tx = Tx::new(tx_code, Some(data));
+    let (ctx, initialized_accounts) =
+        process_tx(ctx, &args.tx, tx, Some(&args.source)).await;
+    save_initialized_accounts(ctx, &args.tx, initialized_accounts).await;
+}
+
+pub async fn submit_init_token(mut ctx: Context, args: args::TxInitToken) {
+    let minter = args.minter.as_ref().map(|minter| ctx.get(minter));
+    let metadata = token::TokenMetadata {
+        symbol: args.symbol,
+        decimals: args.decimals,
+        minter,
+    };
+    if !metadata.is_well_formed() {
+        eprintln!(
+            "The token symbol must be non-empty, at most {} characters long \
+             and contain only ASCII alphanumeric characters.",
+            token::MAX_TOKEN_SYMBOL_LEN
+        );
+        if !args.tx.force {
+            safe_exit(1)
+        }
+    }
+    let vp_code = args
+        .vp_code_path
+        .map(|path| ctx.read_wasm(path))
+        .unwrap_or_else(|| ctx.read_wasm(VP_TOKEN_WASM));
+    // Validate the VP code
+    if let Err(err) = vm::validate_untrusted_wasm(&vp_code) {
+        eprintln!("Validity predicate code validation failed with {}", err);
+        if !args.tx.force {
+            safe_exit(1)
+        }
+    }
+
+    let tx_code = ctx.read_wasm(TX_INIT_TOKEN_WASM);
+    let data = InitToken { metadata, vp_code };
     let data = data.try_to_vec().expect("Encoding tx data shouldn't fail");
 
     let tx = Tx::new(tx_code, Some(data));
@@ -174,6 +320,83 @@ pub async fn submit_init_account(mut ctx: Context, args: args::TxInitAccount) {
     save_initialized_accounts(ctx, &args.tx, initialized_accounts).await;
 }
 
+/// Submit a transaction to mint or burn tokens, signed by the token's minter.
+pub async fn submit_change_supply(
+    ctx: Context,
+    args: args::TxChangeSupply,
+    change: SupplyChange,
+) {
+    let token = ctx.get(&args.token);
+    let owner = ctx.get(&args.owner);
+    let metadata_key = token::metadata_key(&token);
+    let client = HttpClient::new(args.tx.ledger_address.clone()).unwrap();
+    let minter = match rpc::query_storage_value::<token::TokenMetadata>(
+        &client,
+        &metadata_key,
+    )
+    .await
+    .and_then(|metadata| metadata.minter)
+    {
+        Some(minter) => minter,
+        None => {
+            eprintln!("No minter found for the token {}", token);
+            safe_exit(1)
+        }
+    };
+    if change == SupplyChange::Burn {
+        // Check the owner's balance
+        let balance_key = token::balance_key(&token, &owner);
+        let balance =
+            rpc::query_storage_value::<token::Amount>(&client, &balance_key)
+                .await
+                .unwrap_or_default();
+        if balance < args.amount {
+            eprintln!(
+                "The balance of the owner {} of token {} is lower than the \
+                 amount to be burned. Amount to burn is {} and the balance is \
+                 {}.",
+                owner, token, args.amount, balance
+            );
+            if !args.tx.force {
+                safe_exit(1)
+            }
+        }
+    }
+
+    let signer = Some(WalletAddress::new(minter.encode()));
+
+    let data = ChangeSupply {
+        token,
+        owner,
+        amount: args.amount,
+        change,
+    };
+    let data = data.try_to_vec().expect("Encoding tx data shouldn't fail");
+
+    let tx_code = ctx.read_wasm(TX_CHANGE_SUPPLY_WASM);
+    let tx = Tx::new(tx_code, Some(data));
+    process_tx(ctx, &args.tx, tx, signer.as_ref()).await;
+}
+
+pub async fn submit_reveal_pk(mut ctx: Context, args: args::TxRevealPk) {
+    let public_key = ctx.get_cached(&args.public_key);
+    let addr = WalletAddress::new(Address::from(&public_key).encode());
+    if is_pk_revealed(&args.tx, &public_key).await {
+        print_progress(
+            ctx.global_args.output,
+            format_args!(
+                "The public key {} has been already revealed",
+                public_key
+            ),
+        );
+        return;
+    }
+    let keypair = find_signing_key(&mut ctx, &args.tx, Some(&addr))
+        .await
+        .expect("A default signer is given");
+    submit_reveal_pk_tx(ctx, &args.tx, &public_key, &keypair).await;
+}
+
 pub async fn submit_init_validator(
     mut ctx: Context,
     args::TxInitValidator {
@@ -188,6 +411,7 @@ pub async fn submit_init_validator(
         unsafe_dont_encrypt,
     }: args::TxInitValidator,
 ) {
+    let output = ctx.global_args.output;
     let alias = tx_args
         .initialized_account_alias
         .as_ref()
@@ -198,7 +422,7 @@ pub async fn submit_init_validator(
     let consensus_key_alias = format!("{}-consensus-key", alias);
     let rewards_key_alias = format!("{}-rewards-key", alias);
     let account_key = ctx.get_opt_cached(&account_key).unwrap_or_else(|| {
-        println!("Generating validator account key...");
+        print_progress(output, "Generating validator account key...");
         ctx.wallet
             .gen_key(Some(validator_key_alias.clone()), unsafe_dont_encrypt)
             .1
@@ -207,7 +431,7 @@ pub async fn submit_init_validator(
 
     let consensus_key =
         ctx.get_opt_cached(&consensus_key).unwrap_or_else(|| {
-            println!("Generating consensus key...");
+            print_progress(output, "Generating consensus key...");
             ctx.wallet
                 .gen_key(Some(consensus_key_alias.clone()), unsafe_dont_encrypt)
                 .1
@@ -215,7 +439,7 @@ pub async fn submit_init_validator(
 
     let rewards_account_key =
         ctx.get_opt_cached(&rewards_account_key).unwrap_or_else(|| {
-            println!("Generating staking reward account key...");
+            print_progress(output, "Generating staking reward account key...");
             ctx.wallet
                 .gen_key(Some(rewards_key_alias.clone()), unsafe_dont_encrypt)
                 .1
@@ -224,7 +448,7 @@ pub async fn submit_init_validator(
     let protocol_key = ctx.get_opt_cached(&protocol_key);
 
     if protocol_key.is_none() {
-        println!("Generating protocol signing key...");
+        print_progress(output, "Generating protocol signing key...");
     }
     // Generate the validator keys
     let validator_keys = ctx.wallet.gen_validator_keys(protocol_key).unwrap();
@@ -300,10 +524,18 @@ pub async fn submit_init_validator(
                     {
                         Some(alias) => alias,
                         None => {
-                            print!(
-                                "Choose an alias for the validator address: "
-                            );
-                            io::stdout().flush().await.unwrap();
+                            let prompt =
+                                "Choose an alias for the validator address: ";
+                            match output {
+                                OutputFormat::Human => {
+                                    print!("{}", prompt);
+                                    io::stdout().flush().await.unwrap();
+                                }
+                                OutputFormat::Json => {
+                                    eprint!("{}", prompt);
+                                    io::stderr().flush().await.unwrap();
+                                }
+                            }
                             let mut alias = String::new();
                             io::stdin().read_line(&mut alias).await.unwrap();
                             alias.trim().to_owned()
@@ -311,9 +543,12 @@ pub async fn submit_init_validator(
                     };
                     let validator_address_alias =
                         if validator_address_alias.is_empty() {
-                            println!(
-                                "Empty alias given, using {} as the alias.",
-                                validator_address.encode()
+                            print_progress(
+                                output,
+                                format_args!(
+                                    "Empty alias given, using {} as the alias.",
+                                    validator_address.encode()
+                                ),
                             );
                             validator_address.encode()
                         } else {
@@ -323,10 +558,13 @@ pub async fn submit_init_validator(
                         validator_address_alias.clone(),
                         validator_address.clone(),
                     ) {
-                        println!(
-                            "Added alias {} for address {}.",
-                            new_alias,
-                            validator_address.encode()
+                        print_progress(
+                            output,
+                            format_args!(
+                                "Added alias {} for address {}.",
+                                new_alias,
+                                validator_address.encode()
+                            ),
                         );
                     }
                     let rewards_address_alias =
@@ -335,10 +573,13 @@ pub async fn submit_init_validator(
                         rewards_address_alias.clone(),
                         rewards_address.clone(),
                     ) {
-                        println!(
-                            "Added alias {} for address {}.",
-                            new_alias,
-                            rewards_address.encode()
+                        print_progress(
+                            output,
+                            format_args!(
+                                "Added alias {} for address {}.",
+                                new_alias,
+                                rewards_address.encode()
+                            ),
                         );
                     }
                     (
@@ -358,28 +599,42 @@ pub async fn submit_init_validator(
         ctx.wallet.save().unwrap_or_else(|err| eprintln!("{}", err));
 
         let tendermint_home = ctx.config.ledger.tendermint_dir();
-        tendermint_node::write_validator_key(
-            &tendermint_home,
-            &validator_address,
-            &consensus_key,
-        );
+        tendermint_node::write_validator_key(&tendermint_home, &consensus_key);
         tendermint_node::write_validator_state(tendermint_home);
 
-        println!();
-        println!(
-            "The validator's addresses and keys were stored in the wallet:"
-        );
-        println!("  Validator address \"{}\"", validator_address_alias);
-        println!("  Staking reward address \"{}\"", rewards_address_alias);
-        println!("  Validator account key \"{}\"", validator_key_alias);
-        println!("  Consensus key \"{}\"", consensus_key_alias);
-        println!("  Staking reward key \"{}\"", rewards_key_alias);
-        println!(
-            "The ledger node has been setup to use this validator's address \
-             and consensus key."
-        );
+        match output {
+            OutputFormat::Human => {
+                println!();
+                println!(
+                    "The validator's addresses and keys were stored in the \
+                     wallet:"
+                );
+                println!("  Validator address \"{}\"", validator_address_alias);
+                println!(
+                    "  Staking reward address \"{}\"",
+                    rewards_address_alias
+                );
+                println!("  Validator account key \"{}\"", validator_key_alias);
+                println!("  Consensus key \"{}\"", consensus_key_alias);
+                println!("  Staking reward key \"{}\"", rewards_key_alias);
+                println!(
+                    "The ledger node has been setup to use this validator's \
+                     address and consensus key."
+                );
+            }
+            OutputFormat::Json => print_json(&serde_json::json!({
+                "validator_address": validator_address_alias,
+                "rewards_address": rewards_address_alias,
+                "validator_account_key": validator_key_alias,
+                "consensus_key": consensus_key_alias,
+                "rewards_key": rewards_key_alias,
+            })),
+        }
     } else {
-        println!("Transaction dry run. No addresses have been saved.")
+        print_progress(
+            output,
+            "Transaction dry run. No addresses have been saved.",
+        )
     }
 }
 
@@ -450,14 +705,193 @@ pub async fn submit_transfer(ctx: Context, args: args::TxTransfer) {
         amount: args.amount,
     };
     tracing::debug!("Transfer data {:?}", transfer);
-    let data = transfer
-        .try_to_vec()
-        .expect("Encoding tx data shouldn't fail");
+    let data = TxPayload::from(PayloadV1::Transfer(transfer)).encode();
 
     let tx = Tx::new(tx_code, Some(data));
     process_tx(ctx, &args.tx, tx, Some(&args.source)).await;
 }
 
+/// The timeout of an IBC transfer without a timeout height or offset
+const DEFAULT_IBC_TIMEOUT: Duration = Duration::from_secs(60 * 60);
+
+pub async fn submit_ibc_transfer(ctx: Context, args: args::TxIbcTransfer) {
+    let source = ctx.get(&args.source);
+    // Check that the source address exists on chain
+    let source_exists =
+        rpc::known_address(&source, args.tx.ledger_address.clone()).await;
+    if !source_exists {
+        eprintln!("The source address {} doesn't exist on chain.", source);
+        if !args.tx.force {
+            safe_exit(1)
+        }
+    }
+    let token = ctx.get(&args.token);
+    // Check that the token address exists on chain
+    let token_exists =
+        rpc::known_address(&token, args.tx.ledger_address.clone()).await;
+    if !token_exists {
+        eprintln!("The token address {} doesn't exist on chain.", token);
+        if !args.tx.force {
+            safe_exit(1)
+        }
+    }
+    // Check source balance
+    let balance_key = token::balance_key(&token, &source);
+    let client = HttpClient::new(args.tx.ledger_address.clone()).unwrap();
+    match rpc::query_storage_value::<token::Amount>(&client, &balance_key).await
+    {
+        Some(balance) => {
+            if balance < args.amount {
+                eprintln!(
+                    "The balance of the source {} of token {} is lower than \
+                     the amount to be transferred. Amount to transfer is {} \
+                     and the balance is {}.",
+                    source, token, args.amount, balance
+                );
+                if !args.tx.force {
+                    safe_exit(1)
+                }
+            }
+        }
+        None => {
+            eprintln!(
+                "No balance found for the source {} of token {}",
+                source, token
+            );
+            if !args.tx.force {
+                safe_exit(1)
+            }
+        }
+    }
+    // Check that the channel exists on chain
+    let port_channel_id = PortChannelId {
+        port_id: args.port_id.clone(),
+        channel_id: args.channel_id.clone(),
+    };
+    let channel_key = ibc_storage::channel_key(&port_channel_id);
+    if !rpc::query_has_storage_key(client.clone(), channel_key).await {
+        eprintln!(
+            "The channel {} of the port {} doesn't exist on chain.",
+            args.channel_id, args.port_id
+        );
+        if !args.tx.force {
+            safe_exit(1)
+        }
+    }
+    // The sequence of the packet that the transfer will be sent in, unless
+    // another transfer over the channel is applied first
+    let seq_key = ibc_storage::next_sequence_send_key(&port_channel_id);
+    let sequence = rpc::query_storage_value_bytes_proven(&client, &seq_key)
+        .await
+        .and_then(|bytes| <[u8; 8]>::try_from(bytes).ok())
+        .map(u64::from_be_bytes)
+        .unwrap_or(1);
+
+    let tx_code = ctx.read_wasm(TX_IBC_WASM);
+    let timeout_height = match args.timeout_height {
+        Some(height) => Height::new(0, height),
+        None => Height::zero(),
+    };
+    let timeout_offset = match (args.timeout_height, args.timeout_sec_offset) {
+        (_, Some(offset)) => Some(Duration::from_secs(offset)),
+        (Some(_), None) => None,
+        (None, None) => Some(DEFAULT_IBC_TIMEOUT),
+    };
+    let timeout_timestamp = match timeout_offset {
+        Some(offset) => (Timestamp::now() + offset).unwrap_or_else(|err| {
+            eprintln!("The timeout is out of range: {}", err);
+            safe_exit(1)
+        }),
+        None => Timestamp::none(),
+    };
+    let msg = MsgTransfer {
+        source_port: args.port_id.clone(),
+        source_channel: args.channel_id.clone(),
+        token: Some(Coin {
+            denom: token.encode(),
+            amount: args.amount.to_string(),
+        }),
+        sender: Signer::new(source.encode()),
+        receiver: Signer::new(args.receiver.clone()),
+        timeout_height,
+        timeout_timestamp,
+    };
+    tracing::debug!("IBC transfer message {:?}", msg);
+    let mut data = vec![];
+    msg.to_any()
+        .encode(&mut data)
+        .expect("Encoding an IBC message shouldn't fail");
+
+    let tx = Tx::new(tx_code, Some(data));
+    let ledger_address = args.tx.ledger_address.clone();
+    let output = ctx.global_args.output;
+    process_tx(ctx, &args.tx, tx, Some(&args.source)).await;
+    if args.tx.dry_run || args.tx.dump_tx.is_some() {
+        return;
+    }
+    print_progress(
+        output,
+        format_args!(
+            "The transfer is expected to be sent in the packet with the \
+             sequence {} over the channel {} of the port {}.",
+            sequence, args.channel_id, args.port_id
+        ),
+    );
+    if args.wait_ack {
+        if let Err(err) =
+            wait_ibc_ack(ledger_address, &port_channel_id, sequence, output)
+        {
+            eprintln!(
+                "Couldn't receive the acknowledgement of the transfer: {}",
+                err
+            );
+            safe_exit(1)
+        }
+    }
+}
+
+/// Wait for the event of the acknowledgement of the packet with the given
+/// sequence, which is emitted once a relayer has submitted the
+/// acknowledgement from the counterparty chain
+fn wait_ibc_ack(
+    address: TendermintAddress,
+    port_channel_id: &PortChannelId,
+    sequence: u64,
+    output: OutputFormat,
+) -> Result<(), Error> {
+    let mut subscription = TendermintWebsocketClient::open(
+        WebSocketAddress::try_from(address)?,
+        None,
+    )?;
+    let query = Query::from(EventType::NewBlock)
+        .and_eq(
+            "acknowledge_packet.packet_src_port",
+            port_channel_id.port_id.to_string(),
+        )
+        .and_eq(
+            "acknowledge_packet.packet_src_channel",
+            port_channel_id.channel_id.to_string(),
+        )
+        .and_eq("acknowledge_packet.packet_sequence", sequence.to_string());
+    subscription.subscribe(query)?;
+    print_progress(
+        output,
+        format_args!("Waiting for the acknowledgement of the transfer..."),
+    );
+    let response = subscription.receive_response();
+    subscription.unsubscribe()?;
+    subscription.close();
+    response?;
+    print_progress(
+        output,
+        format_args!(
+            "The packet with the sequence {} has been acknowledged.",
+            sequence
+        ),
+    );
+    Ok(())
+}
+
 pub async fn submit_init_nft(ctx: Context, args: args::NftCreate) {
     let file = File::open(&args.nft_data).expect("File must exist.");
     let nft: Nft = serde_json::from_reader(file)
@@ -547,9 +981,11 @@ pub async fn submit_init_proposal(mut ctx: Context, args: args::InitProposal) {
         let proposal_filename = "proposal".to_string();
         let out = File::create(&proposal_filename).unwrap();
         match serde_json::to_writer_pretty(out, &offline_proposal) {
-            Ok(_) => {
-                println!("Proposal created: {}.", proposal_filename);
-            }
+            Ok(_) => print_file_result(
+                ctx.global_args.output,
+                format_args!("Proposal created: {}.", proposal_filename),
+                &proposal_filename,
+            ),
             Err(e) => {
                 eprintln!("Error while creating proposal file: {}.", e);
                 safe_exit(1)
@@ -566,6 +1002,23 @@ pub async fn submit_init_proposal(mut ctx: Context, args: args::InitProposal) {
             safe_exit(1)
         };
 
+        if let Some(proposal_code) = &init_proposal_data.proposal_code {
+            let max_code_size_key =
+                gov_storage::get_max_proposal_code_size_key();
+            let max_code_size: u64 =
+                rpc::query_storage_value(&client, &max_code_size_key)
+                    .await
+                    .unwrap();
+            if proposal_code.len() as u64 > max_code_size {
+                eprintln!(
+                    "The proposal code is larger than the maximum size of {} \
+                     bytes.",
+                    max_code_size
+                );
+                safe_exit(1);
+            }
+        }
+
         let min_proposal_funds_key = gov_storage::get_min_proposal_fund_key();
         let min_proposal_funds: Amount =
             rpc::query_storage_value(&client, &min_proposal_funds_key)
@@ -598,9 +1051,9 @@ pub async fn submit_init_proposal(mut ctx: Context, args: args::InitProposal) {
             safe_exit(1);
         }
 
-        let data = init_proposal_data
-            .try_to_vec()
-            .expect("Encoding proposal data shouldn't fail");
+        let data =
+            TxPayload::from(PayloadV1::InitProposal(init_proposal_data))
+                .encode();
         let tx_code = ctx.read_wasm(TX_INIT_PROPOSAL);
         let tx = Tx::new(tx_code, Some(data));
 
@@ -652,9 +1105,14 @@ pub async fn submit_vote_proposal(mut ctx: Context, args: args::VoteProposal) {
             format!("proposal-vote-{}", &signer.to_string());
         let out = File::create(&proposal_vote_filename).unwrap();
         match serde_json::to_writer_pretty(out, &offline_vote) {
-            Ok(_) => {
-                println!("Proposal vote created: {}.", proposal_vote_filename);
-            }
+            Ok(_) => print_file_result(
+                ctx.global_args.output,
+                format_args!(
+                    "Proposal vote created: {}.",
+                    proposal_vote_filename
+                ),
+                &proposal_vote_filename,
+            ),
             Err(e) => {
                 eprintln!("Error while creating proposal vote file: {}.", e);
                 safe_exit(1)
@@ -691,6 +1149,7 @@ pub async fn submit_vote_proposal(mut ctx: Context, args: args::VoteProposal) {
                 if !args.tx.force
                     && is_safe_voting_window(
                         args.tx.ledger_address.clone(),
+                        ctx.global_args.output,
                         &client,
                         proposal_id,
                         epoch,
@@ -713,9 +1172,8 @@ pub async fn submit_vote_proposal(mut ctx: Context, args: args::VoteProposal) {
                     delegations: delegation_addresses,
                 };
 
-                let data = tx_data
-                    .try_to_vec()
-                    .expect("Encoding proposal data shouldn't fail");
+                let data =
+                    TxPayload::from(PayloadV1::VoteProposal(tx_data)).encode();
                 let tx_code = ctx.read_wasm(TX_VOTE_PROPOSAL);
                 let tx = Tx::new(tx_code, Some(data));
 
@@ -733,11 +1191,13 @@ pub async fn submit_vote_proposal(mut ctx: Context, args: args::VoteProposal) {
 /// storage.
 async fn is_safe_voting_window(
     ledger_address: TendermintAddress,
+    output: OutputFormat,
     client: &HttpClient,
     proposal_id: u64,
     proposal_start_epoch: Epoch,
 ) -> bool {
-    let current_epoch = rpc::query_epoch(args::Query { ledger_address }).await;
+    let current_epoch =
+        rpc::query_epoch(args::Query { ledger_address }, output).await;
 
     let proposal_end_epoch_key =
         gov_storage::get_voting_end_epoch_key(proposal_id);
@@ -852,7 +1312,7 @@ pub async fn submit_bond(ctx: Context, args: args::Bond) {
         amount: args.amount,
         source,
     };
-    let data = bond.try_to_vec().expect("Encoding tx data shouldn't fail");
+    let data = TxPayload::from(PayloadV1::Bond(bond)).encode();
 
     let tx = Tx::new(tx_code, Some(data));
     let default_signer = args.source.as_ref().unwrap_or(&args.validator);
@@ -919,7 +1379,7 @@ pub async fn submit_unbond(ctx: Context, args: args::Unbond) {
         amount: args.amount,
         source,
     };
-    let data = data.try_to_vec().expect("Encoding tx data shouldn't fail");
+    let data = TxPayload::from(PayloadV1::Unbond(data)).encode();
 
     let tx = Tx::new(tx_code, Some(data));
     let default_signer = args.source.as_ref().unwrap_or(&args.validator);
@@ -927,9 +1387,12 @@ pub async fn submit_unbond(ctx: Context, args: args::Unbond) {
 }
 
 pub async fn submit_withdraw(ctx: Context, args: args::Withdraw) {
-    let epoch = rpc::query_epoch(args::Query {
-        ledger_address: args.tx.ledger_address.clone(),
-    })
+    let epoch = rpc::query_epoch(
+        args::Query {
+            ledger_address: args.tx.ledger_address.clone(),
+        },
+        ctx.global_args.output,
+    )
     .await;
 
     let validator = ctx.get(&args.validator);
@@ -986,16 +1449,174 @@ pub async fn submit_withdraw(ctx: Context, args: args::Withdraw) {
     }
 
     let data = pos::Withdraw { validator, source };
-    let data = data.try_to_vec().expect("Encoding tx data shouldn't fail");
+    let data = TxPayload::from(PayloadV1::Withdraw(data)).encode();
 
     let tx = Tx::new(tx_code, Some(data));
     let default_signer = args.source.as_ref().unwrap_or(&args.validator);
     process_tx(ctx, &args.tx, tx, Some(default_signer)).await;
 }
 
+pub async fn submit_change_consensus_key(
+    mut ctx: Context,
+    args::TxChangeConsensusKey {
+        tx: tx_args,
+        validator,
+        consensus_key,
+        unsafe_dont_encrypt,
+    }: args::TxChangeConsensusKey,
+) {
+    let output = ctx.global_args.output;
+    let validator_address = ctx.get(&validator);
+    // Check that the validator address exists on chain
+    let is_validator =
+        rpc::is_validator(&validator_address, tx_args.ledger_address.clone())
+            .await;
+    if !is_validator {
+        eprintln!(
+            "The address {} doesn't belong to any known validator account.",
+            validator_address
+        );
+        if !tx_args.force {
+            safe_exit(1)
+        }
+    }
+
+    let consensus_key =
+        ctx.get_opt_cached(&consensus_key).unwrap_or_else(|| {
+            print_progress(output, "Generating consensus key...");
+            let (alias, key) = ctx.wallet.gen_key(None, unsafe_dont_encrypt);
+            ctx.wallet.save().unwrap_or_else(|err| eprintln!("{}", err));
+            print_progress(
+                output,
+                format_args!(
+                    "The new consensus key was stored as \"{}\".",
+                    alias
+                ),
+            );
+            key
+        });
+    let consensus_pk: common::PublicKey = consensus_key.ref_to();
+
+    let epoch = rpc::query_epoch(
+        args::Query {
+            ledger_address: tx_args.ledger_address.clone(),
+        },
+        output,
+    )
+    .await;
+    let client = HttpClient::new(tx_args.ledger_address.clone()).unwrap();
+    let pos_params = rpc::query_storage_value::<PosParams>(
+        &client,
+        &ledger::pos::params_key(),
+    )
+    .await
+    .expect("PoS parameters should always exist in storage");
+    let pipeline_epoch = epoch + pos_params.pipeline_len;
+
+    let data = pos::ChangeConsensusKey {
+        validator: validator_address,
+        consensus_key: consensus_pk.clone(),
+    };
+    let data = data.try_to_vec().expect("Encoding tx data shouldn't fail");
+    let tx_code = ctx.read_wasm(TX_CHANGE_CONSENSUS_KEY_WASM);
+    let tx = Tx::new(tx_code, Some(data));
+    process_tx(ctx, &tx_args, tx, Some(&validator)).await;
+
+    if !tx_args.dry_run {
+        print_progress(
+            output,
+            format_args!(
+                "The new consensus key {} will be used from epoch {}. The \
+                 ledger node must be switched to sign with this key at the \
+                 beginning of that epoch.",
+                consensus_pk, pipeline_epoch
+            ),
+        );
+    }
+}
+
+pub async fn submit_update_validator_metadata(
+    ctx: Context,
+    args: args::TxUpdateValidatorMetadata,
+) {
+    let validator = ctx.get(&args.validator);
+    // Check that the validator address exists on chain
+    let is_validator =
+        rpc::is_validator(&validator, args.tx.ledger_address.clone()).await;
+    if !is_validator {
+        eprintln!(
+            "The address {} doesn't belong to any known validator account.",
+            validator
+        );
+        if !args.tx.force {
+            safe_exit(1)
+        }
+    }
+
+    let commission_rate = BasisPoints::new(args.commission_rate);
+    let pre =
+        rpc::get_validator_metadata(&validator, args.tx.ledger_address.clone())
+            .await;
+    let max_commission_rate_change =
+        match (&pre, args.max_commission_rate_change) {
+            (Some(pre), Some(max_change))
+                if BasisPoints::new(max_change)
+                    != pre.max_commission_rate_change =>
+            {
+                eprintln!(
+                    "The maximum commission rate change cannot be changed \
+                     after the metadata have been registered."
+                );
+                if !args.tx.force {
+                    safe_exit(1)
+                }
+                pre.max_commission_rate_change
+            }
+            (Some(pre), _) => pre.max_commission_rate_change,
+            (None, Some(max_change)) => BasisPoints::new(max_change),
+            (None, None) => {
+                eprintln!(
+                    "The maximum commission rate change is required to \
+                     register the validator's metadata."
+                );
+                safe_exit(1)
+            }
+        };
+    if let Some(ValidatorMetadata {
+        commission_rate: pre_rate,
+        ..
+    }) = &pre
+    {
+        if commission_rate.abs_diff(*pre_rate) > max_commission_rate_change {
+            eprintln!(
+                "The commission rate can be changed by at most {} per epoch, \
+                 the current rate is {}.",
+                max_commission_rate_change, pre_rate
+            );
+            if !args.tx.force {
+                safe_exit(1)
+            }
+        }
+    }
+
+    let data = pos::UpdateValidatorMetadata {
+        validator,
+        moniker: args.moniker,
+        website: args.website,
+        contact: args.contact,
+        commission_rate,
+        max_commission_rate_change,
+    };
+    let data = data.try_to_vec().expect("Encoding tx data shouldn't fail");
+    let tx_code = ctx.read_wasm(TX_UPDATE_VALIDATOR_METADATA_WASM);
+    let tx = Tx::new(tx_code, Some(data));
+    process_tx(ctx, &args.tx, tx, Some(&args.validator)).await;
+}
+
 /// Sign a transaction with a given signing key or public key of a given signer.
 /// If no explicit signer given, use the `default`. If no `default` is given,
-/// panics.
+/// the transaction can only be dry-run without a signature, otherwise exits
+/// with an error.
 ///
 /// If this is not a dry run, the tx is put in a wrapper and returned along with
 /// hashes needed for monitoring the tx on chain.
@@ -1007,28 +1628,39 @@ async fn sign_tx(
     args: &args::Tx,
     default: Option<&WalletAddress>,
 ) -> (Context, TxBroadcastData) {
-    let (tx, keypair) = if let Some(signing_key) = &args.signing_key {
-        let signing_key = ctx.get_cached(signing_key);
-        (tx.sign(&signing_key), signing_key)
-    } else if let Some(signer) = args.signer.as_ref().or(default) {
-        let signer = ctx.get(signer);
-        let signing_key = signing::find_keypair(
-            &mut ctx.wallet,
-            &signer,
-            args.ledger_address.clone(),
-        )
-        .await;
-        (tx.sign(&signing_key), signing_key)
-    } else {
-        panic!(
-            "All transactions must be signed; please either specify the key \
-             or the address from which to look up the signing key."
-        );
+    let keypair = match find_signing_key(&mut ctx, args, default).await {
+        Some(keypair) => keypair,
+        None if args.dry_run => {
+            // A dry run doesn't need a wrapper, so it can be unsigned
+            return (ctx, TxBroadcastData::DryRun(tx));
+        }
+        None => {
+            eprintln!(
+                "All transactions must be signed; please either specify the \
+                 key or the address from which to look up the signing key."
+            );
+            safe_exit(1)
+        }
     };
-    let epoch = rpc::query_epoch(args::Query {
+    // The public key of an implicit signer has to be revealed before its
+    // signature can be verified
+    if !args.dry_run && args.signing_key.is_none() {
+        let signer_addr =
+            args.signer.as_ref().or(default).map(|addr| ctx.get(addr));
+        if let Some(Address::Implicit(_)) = signer_addr {
+            ctx = reveal_pk_if_needed(ctx, args, &keypair.ref_to(), &keypair)
+                .await;
+        }
+    }
+    let tx = tx.sign(&keypair);
+    let epoch = rpc::query_last_epoch(args::Query {
         ledger_address: args.ledger_address.clone(),
     })
     .await;
+    print_progress(
+        ctx.global_args.output,
+        format_args!("Last committed epoch: {}", epoch),
+    );
     let broadcast_data = if args.dry_run {
         TxBroadcastData::DryRun(tx)
     } else {
@@ -1037,10 +1669,326 @@ async fn sign_tx(
     (ctx, broadcast_data)
 }
 
+/// Submit a transaction to reveal the given public key, signed and paid for by
+/// the given keypair, unless the key has been already revealed.
+pub(super) async fn reveal_pk_if_needed(
+    ctx: Context,
+    args: &args::Tx,
+    public_key: &common::PublicKey,
+    keypair: &common::SecretKey,
+) -> Context {
+    if is_pk_revealed(args, public_key).await {
+        ctx
+    } else {
+        submit_reveal_pk_tx(ctx, args, public_key, keypair).await
+    }
+}
+
+/// Check if the given public key has been revealed on chain.
+async fn is_pk_revealed(
+    args: &args::Tx,
+    public_key: &common::PublicKey,
+) -> bool {
+    let addr: Address = public_key.into();
+    rpc::get_public_key(&addr, args.ledger_address.clone())
+        .await
+        .is_some()
+}
+
+/// Submit a transaction to reveal the given public key, signed and paid for by
+/// the given keypair.
+async fn submit_reveal_pk_tx(
+    ctx: Context,
+    args: &args::Tx,
+    public_key: &common::PublicKey,
+    keypair: &common::SecretKey,
+) -> Context {
+    let addr: Address = public_key.into();
+    print_progress(
+        ctx.global_args.output,
+        format_args!(
+            "Submitting a transaction to reveal the public key {} of {}...",
+            public_key,
+            addr.encode()
+        ),
+    );
+    let tx_code = ctx.read_wasm(TX_REVEAL_PK);
+    let data = public_key
+        .try_to_vec()
+        .expect("Encoding a public key shouldn't fail");
+    let mut tx = Tx::new(tx_code, Some(data))
+        .with_chain_id(ctx.config.ledger.chain_id.clone());
+    if let Some(expiration) = args.expiration {
+        tx = tx.with_expiration(expiration);
+    }
+    let tx = tx.sign(keypair);
+    let epoch = rpc::query_last_epoch(args::Query {
+        ledger_address: args.ledger_address.clone(),
+    })
+    .await;
+    let to_broadcast = sign_wrapper(&ctx, args, epoch, tx, keypair).await;
+    let (ctx, _) = broadcast_or_submit_tx(ctx, args, to_broadcast).await;
+    ctx
+}
+
+/// Find the key to sign a transaction with, either the given signing key or
+/// the key of the given signer. If no explicit signer given, use the
+/// `default`.
+pub(super) async fn find_signing_key(
+    ctx: &mut Context,
+    args: &args::Tx,
+    default: Option<&WalletAddress>,
+) -> Option<Rc<common::SecretKey>> {
+    if let Some(signing_key) = &args.signing_key {
+        Some(ctx.get_cached(signing_key))
+    } else if let Some(signer) = args.signer.as_ref().or(default) {
+        let signer = ctx.get(signer);
+        Some(
+            signing::find_keypair(
+                &mut ctx.wallet,
+                &signer,
+                args.ledger_address.clone(),
+            )
+            .await,
+        )
+    } else {
+        None
+    }
+}
+
+/// Sign a transaction from a file written with `--dump-tx` and write it in a
+/// signed wrapper to a file that can be submitted with `broadcast`. With an
+/// explicit epoch and signing key from the wallet, this doesn't need to
+/// connect to a ledger node.
+pub async fn sign_tx_file(mut ctx: Context, args: args::TxSign) {
+    let tx = read_tx_file(&args.tx_file);
+    let keypair = find_signing_key(&mut ctx, &args.tx, None)
+        .await
+        .unwrap_or_else(|| {
+            eprintln!(
+                "Please either specify the key or the address from which to \
+                 look up the signing key."
+            );
+            safe_exit(1)
+        });
+    let epoch = match args.epoch {
+        Some(epoch) => epoch,
+        None => {
+            rpc::query_epoch(
+                args::Query {
+                    ledger_address: args.tx.ledger_address.clone(),
+                },
+                ctx.global_args.output,
+            )
+            .await
+        }
+    };
+    let tx = tx.sign(&keypair);
+    let signed = sign_wrapper(&ctx, &args.tx, epoch, tx, &keypair).await;
+    let output_file = args
+        .output_file
+        .unwrap_or_else(|| args.tx_file.with_extension("signed"));
+    let signed_bytes = signed
+        .try_to_vec()
+        .expect("Encoding a signed transaction shouldn't fail");
+    std::fs::write(&output_file, signed_bytes).unwrap_or_else(|err| {
+        eprintln!(
+            "Couldn't write the signed transaction to {}: {}",
+            output_file.display(),
+            err
+        );
+        safe_exit(1)
+    });
+    print_file_result(
+        ctx.global_args.output,
+        format_args!("Signed transaction written to {}", output_file.display()),
+        output_file.display(),
+    );
+}
+
+/// Sign a transaction from a file written with `--dump-tx` as one of the
+/// signers of a multisignature account and write the partial signature to a
+/// file that can be aggregated with `multisig-submit`. This doesn't need to
+/// connect to a ledger node.
+pub async fn sign_multisig_tx_file(
+    mut ctx: Context,
+    args: args::TxMultisigSign,
+) {
+    let tx = read_tx_file(&args.tx_file);
+    let keypair = ctx.get_cached(&args.signing_key);
+    let partial = PartialSignature {
+        public_key: keypair.ref_to(),
+        sig: common::SigScheme::sign(&keypair, &tx.tx_to_sign()),
+    };
+    let output_file = args
+        .output_file
+        .unwrap_or_else(|| args.tx_file.with_extension("sig"));
+    let partial_bytes = partial
+        .try_to_vec()
+        .expect("Encoding a partial signature shouldn't fail");
+    std::fs::write(&output_file, partial_bytes).unwrap_or_else(|err| {
+        eprintln!(
+            "Couldn't write the partial signature to {}: {}",
+            output_file.display(),
+            err
+        );
+        safe_exit(1)
+    });
+    print_file_result(
+        ctx.global_args.output,
+        format_args!("Partial signature written to {}", output_file.display()),
+        output_file.display(),
+    );
+}
+
+/// Aggregate the partial signatures written with `multisig-sign` for a
+/// transaction from a file written with `--dump-tx` and submit it. The
+/// signatures are checked against the signer set of the multisignature
+/// account before the submission.
+pub async fn submit_multisig_tx_file(
+    mut ctx: Context,
+    args: args::TxMultisigSubmit,
+) {
+    let tx = read_tx_file(&args.tx_file);
+    let address = ctx.get(&args.address);
+    let (public_keys, threshold) =
+        rpc::get_multisig_params(&address, args.tx.ledger_address.clone())
+            .await
+            .unwrap_or_else(|| {
+                eprintln!(
+                    "The address {} is not a multisignature account",
+                    address.encode()
+                );
+                safe_exit(1)
+            });
+    let to_sign = tx.tx_to_sign();
+    let mut signatures = BTreeMap::new();
+    for path in &args.signature_files {
+        let partial_bytes = std::fs::read(path).unwrap_or_else(|err| {
+            eprintln!(
+                "Couldn't read the partial signature file {}: {}",
+                path.display(),
+                err
+            );
+            safe_exit(1)
+        });
+        let partial = PartialSignature::try_from_slice(&partial_bytes)
+            .unwrap_or_else(|err| {
+                eprintln!("Couldn't decode the partial signature: {}", err);
+                safe_exit(1)
+            });
+        let index = public_keys
+            .iter()
+            .position(|pk| pk == &partial.public_key)
+            .unwrap_or_else(|| {
+                eprintln!(
+                    "The public key {} of the partial signature {} is not a \
+                     signer of {}",
+                    partial.public_key,
+                    path.display(),
+                    address.encode()
+                );
+                safe_exit(1)
+            });
+        if let Err(err) = common::SigScheme::verify_signature_raw(
+            &partial.public_key,
+            &to_sign,
+            &partial.sig,
+        ) {
+            eprintln!(
+                "The partial signature {} is not valid for the transaction: {}",
+                path.display(),
+                err
+            );
+            safe_exit(1)
+        }
+        signatures.insert(index as u8, partial.sig);
+    }
+    if signatures.len() < threshold as usize {
+        eprintln!(
+            "The transaction has been signed by {} signers, but {} signers \
+             are required.",
+            signatures.len(),
+            threshold
+        );
+        safe_exit(1)
+    }
+    let keypair = find_signing_key(&mut ctx, &args.tx, None)
+        .await
+        .unwrap_or_else(|| {
+            eprintln!(
+                "Please either specify the key or the address from which to \
+                 look up the key to sign and pay the fee for the transaction."
+            );
+            safe_exit(1)
+        });
+    let sig = common::SigScheme::sign(&keypair, &to_sign);
+    let tx = tx.attach_signatures(sig, signatures);
+    if args.tx.dry_run {
+        rpc::dry_run_tx(
+            &args.tx.ledger_address,
+            tx.to_bytes(),
+            ctx.global_args.output,
+        )
+        .await;
+        return;
+    }
+    let epoch = rpc::query_last_epoch(args::Query {
+        ledger_address: args.tx.ledger_address.clone(),
+    })
+    .await;
+    let to_broadcast = sign_wrapper(&ctx, &args.tx, epoch, tx, &keypair).await;
+    let (ctx, initialized_accounts) =
+        broadcast_or_submit_tx(ctx, &args.tx, to_broadcast).await;
+    save_initialized_accounts(ctx, &args.tx, initialized_accounts).await;
+}
+
+/// Read a transaction from a file written with `--dump-tx`. Exits if the file
+/// cannot be read or decoded.
+fn read_tx_file(path: &std::path::Path) -> Tx {
+    let tx_bytes = std::fs::read(path).unwrap_or_else(|err| {
+        eprintln!(
+            "Couldn't read the transaction file {}: {}",
+            path.display(),
+            err
+        );
+        safe_exit(1)
+    });
+    Tx::try_from(&tx_bytes[..]).unwrap_or_else(|err| {
+        eprintln!("Couldn't decode the transaction: {}", err);
+        safe_exit(1)
+    })
+}
+
+/// Submit a transaction signed with `sign` and wait for the result as
+/// specified by the broadcast mode.
+pub async fn broadcast_signed_file(ctx: Context, args: args::TxBroadcast) {
+    let signed_bytes = std::fs::read(&args.signed_file).unwrap_or_else(|err| {
+        eprintln!(
+            "Couldn't read the signed transaction file {}: {}",
+            args.signed_file.display(),
+            err
+        );
+        safe_exit(1)
+    });
+    let to_broadcast = TxBroadcastData::try_from_slice(&signed_bytes)
+        .unwrap_or_else(|err| {
+            eprintln!("Couldn't decode the signed transaction: {}", err);
+            safe_exit(1)
+        });
+    if let TxBroadcastData::DryRun(_) = to_broadcast {
+        eprintln!("The transaction file doesn't contain a signed wrapper");
+        safe_exit(1)
+    }
+    let (ctx, initialized_accounts) =
+        broadcast_or_submit_tx(ctx, &args.tx, to_broadcast).await;
+    save_initialized_accounts(ctx, &args.tx, initialized_accounts).await;
+}
+
 /// Create a wrapper tx from a normal tx. Get the hash of the
 /// wrapper and its payload which is needed for monitoring its
 /// progress on chain.
-async fn sign_wrapper(
+pub(super) async fn sign_wrapper(
     ctx: &Context,
     args: &args::Tx,
     epoch: Epoch,
@@ -1075,12 +2023,18 @@ async fn sign_wrapper(
     } else {
         None
     };
+    let tx_hash = tx.tx_hash.to_string();
+    // Bind the wrapper to the chain, so that it can't be replayed on another
+    let chain_id = ctx.config.ledger.chain_id.clone();
+    let mut wrapper = tx.to_unsigned_tx(chain_id);
+    if let Some(expiration) = args.expiration {
+        wrapper = wrapper.with_expiration(expiration);
+    }
     TxBroadcastData::Wrapper {
-        tx: tx
-            .sign(keypair)
-            .expect("Wrapper tx signing keypair should be correct"),
+        tx: wrapper.sign(keypair),
         wrapper_hash,
         decrypted_hash,
+        tx_hash,
     }
 }
 
@@ -1092,6 +2046,31 @@ async fn process_tx(
     tx: Tx,
     default_signer: Option<&WalletAddress>,
 ) -> (Context, Vec<Address>) {
+    // Bind the tx to the chain before it's dumped or signed, so that it can't
+    // be replayed on another one, and to its expiration, if any
+    let mut tx = tx.with_chain_id(ctx.config.ledger.chain_id.clone());
+    if let Some(expiration) = args.expiration {
+        tx = tx.with_expiration(expiration);
+    }
+    if let Some(dump_path) = &args.dump_tx {
+        std::fs::write(dump_path, tx.to_bytes()).unwrap_or_else(|err| {
+            eprintln!(
+                "Couldn't write the transaction to {}: {}",
+                dump_path.display(),
+                err
+            );
+            safe_exit(1)
+        });
+        print_file_result(
+            ctx.global_args.output,
+            format_args!(
+                "Unsigned transaction written to {}",
+                dump_path.display()
+            ),
+            dump_path.display(),
+        );
+        return (ctx, vec![]);
+    }
     let (ctx, to_broadcast) = sign_tx(ctx, tx, args, default_signer).await;
     // NOTE: use this to print the request JSON body:
 
@@ -1105,7 +2084,12 @@ async fn process_tx(
 
     if args.dry_run {
         if let TxBroadcastData::DryRun(tx) = to_broadcast {
-            rpc::dry_run_tx(&args.ledger_address, tx.to_bytes()).await;
+            rpc::dry_run_tx(
+                &args.ledger_address,
+                tx.to_bytes(),
+                ctx.global_args.output,
+            )
+            .await;
             (ctx, vec![])
         } else {
             panic!(
@@ -1114,25 +2098,67 @@ async fn process_tx(
             );
         }
     } else {
-        // Either broadcast or submit transaction and collect result into
-        // sum type
-        let result = if args.broadcast_only {
-            Left(broadcast_tx(args.ledger_address.clone(), &to_broadcast).await)
-        } else {
-            Right(submit_tx(args.ledger_address.clone(), to_broadcast).await)
-        };
-        // Return result based on executed operation, otherwise deal with
-        // the encountered errors uniformly
-        match result {
-            Right(Ok(result)) => (ctx, result.initialized_accounts),
-            Left(Ok(_)) => (ctx, Vec::default()),
-            Right(Err(err)) | Left(Err(err)) => {
-                eprintln!(
-                    "Encountered error while broadcasting transaction: {}",
-                    err
-                );
-                safe_exit(1)
+        broadcast_or_submit_tx(ctx, args, to_broadcast).await
+    }
+}
+
+/// Either broadcast or submit a signed transaction depending on the broadcast
+/// mode. Returns a list of addresses initialized in the transaction if any.
+async fn broadcast_or_submit_tx(
+    ctx: Context,
+    args: &args::Tx,
+    to_broadcast: TxBroadcastData,
+) -> (Context, Vec<Address>) {
+    let output = ctx.global_args.output;
+    let tx_hash = match &to_broadcast {
+        TxBroadcastData::Wrapper { tx_hash, .. } => tx_hash.clone(),
+        TxBroadcastData::DryRun(_) => {
+            panic!("Cannot broadcast a dry-run transaction")
+        }
+    };
+    // Either broadcast or submit transaction and collect result into
+    // sum type
+    let result = match args.broadcast_mode {
+        mode @ (BroadcastMode::Async | BroadcastMode::Sync) => Left(
+            broadcast_tx(
+                args.ledger_address.clone(),
+                &to_broadcast,
+                mode,
+                output,
+            )
+            .await,
+        ),
+        BroadcastMode::Commit => Right(
+            submit_tx(args.ledger_address.clone(), to_broadcast, output).await,
+        ),
+    };
+    // Return result based on executed operation, otherwise deal with
+    // the encountered errors uniformly
+    match result {
+        Right(Ok(result)) => {
+            if output == OutputFormat::Json {
+                print_json(&result);
+            }
+            (ctx, result.initialized_accounts)
+        }
+        Left(Ok(response)) => {
+            if output == OutputFormat::Json {
+                let response = serde_json::json!({
+                    "hash": response.hash.to_string(),
+                    "tx_hash": tx_hash,
+                    "code": u32::from(response.code),
+                    "log": response.log.to_string(),
+                });
+                print_json(&response);
             }
+            (ctx, Vec::default())
+        }
+        Right(Err(err)) | Left(Err(err)) => {
+            eprintln!(
+                "Encountered error while broadcasting transaction: {}",
+                err
+            );
+            safe_exit(1)
         }
     }
 }
@@ -1143,15 +2169,19 @@ async fn save_initialized_accounts(
     args: &args::Tx,
     initialized_accounts: Vec<Address>,
 ) {
+    let output = ctx.global_args.output;
     let len = initialized_accounts.len();
     if len != 0 {
         // Store newly initialized account addresses in the wallet
-        println!(
-            "The transaction initialized {} new account{}",
-            len,
-            if len == 1 { "" } else { "s" }
+        print_progress(
+            output,
+            format_args!(
+                "The transaction initialized {} new account{}",
+                len,
+                if len == 1 { "" } else { "s" }
+            ),
         );
-        // Store newly initialized account addresses in the wallet
+        let mut aliases = BTreeMap::new();
         let wallet = &mut ctx.wallet;
         for (ix, address) in initialized_accounts.iter().enumerate() {
             let encoded = address.encode();
@@ -1169,8 +2199,17 @@ async fn save_initialized_accounts(
                     }
                 }
                 None => {
-                    print!("Choose an alias for {}: ", encoded);
-                    io::stdout().flush().await.unwrap();
+                    let prompt = format!("Choose an alias for {}: ", encoded);
+                    match output {
+                        OutputFormat::Human => {
+                            print!("{}", prompt);
+                            io::stdout().flush().await.unwrap();
+                        }
+                        OutputFormat::Json => {
+                            eprint!("{}", prompt);
+                            io::stderr().flush().await.unwrap();
+                        }
+                    }
                     let mut alias = String::new();
                     io::stdin().read_line(&mut alias).await.unwrap();
                     alias.trim().to_owned().into()
@@ -1180,36 +2219,56 @@ async fn save_initialized_accounts(
             let added = wallet.add_address(alias.clone(), address.clone());
             match added {
                 Some(new_alias) if new_alias != encoded => {
-                    println!(
-                        "Added alias {} for address {}.",
-                        new_alias, encoded
+                    print_progress(
+                        output,
+                        format_args!(
+                            "Added alias {} for address {}.",
+                            new_alias, encoded
+                        ),
                     );
+                    aliases.insert(new_alias, encoded);
                 }
-                _ => println!("No alias added for address {}.", encoded),
+                _ => print_progress(
+                    output,
+                    format_args!("No alias added for address {}.", encoded),
+                ),
             };
         }
         if !args.dry_run {
             wallet.save().unwrap_or_else(|err| eprintln!("{}", err));
         } else {
-            println!("Transaction dry run. No addresses have been saved.")
+            print_progress(
+                output,
+                "Transaction dry run. No addresses have been saved.",
+            )
+        }
+        if output == OutputFormat::Json {
+            print_json(&aliases);
         }
     }
 }
 
-/// Broadcast a transaction to be included in the blockchain and checks that
-/// the tx has been successfully included into the mempool of a validator
+/// Broadcast a transaction to be included in the blockchain. With
+/// [`BroadcastMode::Sync`], this also checks that the tx has been
+/// successfully included into the mempool of a validator. With
+/// [`BroadcastMode::Async`], this returns without waiting for the mempool
+/// check. [`BroadcastMode::Commit`] is handled by [`submit_tx`] and here it's
+/// the same as [`BroadcastMode::Sync`].
 ///
 /// In the case of errors in any of those stages, an error message is returned
 pub async fn broadcast_tx(
     address: TendermintAddress,
     to_broadcast: &TxBroadcastData,
+    mode: BroadcastMode,
+    output: OutputFormat,
 ) -> Result<Response, Error> {
-    let (tx, wrapper_tx_hash, _decrypted_tx_hash) = match to_broadcast {
+    let (tx, _wrapper_tx_hash, tx_hash) = match to_broadcast {
         TxBroadcastData::Wrapper {
             tx,
             wrapper_hash,
-            decrypted_hash,
-        } => (tx, wrapper_hash, decrypted_hash),
+            tx_hash,
+            ..
+        } => (tx, wrapper_hash, tx_hash),
         _ => panic!("Cannot broadcast a dry-run transaction"),
     };
     let mut wrapper_tx_subscription = TendermintWebsocketClient::open(
@@ -1217,24 +2276,49 @@ pub async fn broadcast_tx(
         None,
     )?;
 
-    let response = wrapper_tx_subscription
-        .broadcast_tx_sync(tx.to_bytes().into())
-        .await
-        .map_err(|err| Error::Response(format!("{:?}", err)))?;
+    let response = match mode {
+        BroadcastMode::Async => {
+            let response = wrapper_tx_subscription
+                .broadcast_tx_async(tx.to_bytes().into())
+                .await
+                .map_err(|err| Error::Response(format!("{:?}", err)))?;
+            // The response has the same fields, but the mempool check
+            // hasn't been performed yet
+            Response {
+                code: response.code,
+                data: response.data,
+                log: response.log,
+                hash: response.hash,
+            }
+        }
+        BroadcastMode::Sync | BroadcastMode::Commit => wrapper_tx_subscription
+            .broadcast_tx_sync(tx.to_bytes().into())
+            .await
+            .map_err(|err| Error::Response(format!("{:?}", err)))?,
+    };
 
     wrapper_tx_subscription.close();
 
     if response.code == 0.into() {
-        println!("Transaction added to mempool: {:?}", response);
+        if mode == BroadcastMode::Async {
+            print_progress(
+                output,
+                format_args!("Transaction sent: {:?}", response),
+            );
+        } else {
+            print_progress(
+                output,
+                format_args!("Transaction added to mempool: {:?}", response),
+            );
+        }
         // Print the transaction identifiers to enable the extraction of
         // acceptance/application results later
         #[cfg(not(feature = "ABCI"))]
-        {
-            println!("Wrapper transaction hash: {:?}", wrapper_tx_hash);
-            println!("Inner transaction hash: {:?}", _decrypted_tx_hash);
-        }
-        #[cfg(feature = "ABCI")]
-        println!("Transaction hash: {:?}", wrapper_tx_hash);
+        print_progress(
+            output,
+            format_args!("Wrapper transaction hash: {}", _wrapper_tx_hash),
+        );
+        print_progress(output, format_args!("Transaction hash: {}", tx_hash));
         Ok(response)
     } else {
         Err(Error::Response(response.log.to_string()))
@@ -1252,12 +2336,14 @@ pub async fn broadcast_tx(
 pub async fn submit_tx(
     address: TendermintAddress,
     to_broadcast: TxBroadcastData,
+    output: OutputFormat,
 ) -> Result<TxResponse, Error> {
     let (_, wrapper_hash, _decrypted_hash) = match &to_broadcast {
         TxBroadcastData::Wrapper {
             tx,
             wrapper_hash,
             decrypted_hash,
+            ..
         } => (tx, wrapper_hash, decrypted_hash),
         _ => panic!("Cannot broadcast a dry-run transaction"),
     };
@@ -1292,7 +2378,7 @@ pub async fn submit_tx(
         decrypted_tx_subscription
     };
     // Broadcast the supplied transaction
-    broadcast_tx(address, &to_broadcast).await?;
+    broadcast_tx(address, &to_broadcast, BroadcastMode::Sync, output).await?;
 
     #[cfg(not(feature = "ABCI"))]
     let parsed = {
@@ -1301,9 +2387,12 @@ pub async fn submit_tx(
             TmEventType::Accepted,
             wrapper_hash,
         );
-        println!(
-            "Transaction accepted with result: {}",
-            serde_json::to_string_pretty(&parsed).unwrap()
+        print_progress(
+            output,
+            format_args!(
+                "Transaction accepted with result: {}",
+                serde_json::to_string_pretty(&parsed).unwrap()
+            ),
         );
         // The transaction is now on chain. We wait for it to be decrypted
         // and applied
@@ -1313,9 +2402,12 @@ pub async fn submit_tx(
                 TmEventType::Applied,
                 _decrypted_hash.as_ref().unwrap(),
             );
-            println!(
-                "Transaction applied with result: {}",
-                serde_json::to_string_pretty(&parsed).unwrap()
+            print_progress(
+                output,
+                format_args!(
+                    "Transaction applied with result: {}",
+                    serde_json::to_string_pretty(&parsed).unwrap()
+                ),
             );
             Ok(parsed)
         } else {
@@ -1329,9 +2421,12 @@ pub async fn submit_tx(
             wrapper_tx_subscription.receive_response()?,
             wrapper_hash,
         );
-        println!(
-            "Transaction applied with result: {}",
-            serde_json::to_string_pretty(&parsed).unwrap()
+        print_progress(
+            output,
+            format_args!(
+                "Transaction applied with result: {}",
+                serde_json::to_string_pretty(&parsed).unwrap()
+            ),
         );
         Ok(parsed)
     };
@@ -1347,12 +2442,29 @@ pub async fn submit_tx(
     parsed
 }
 
+/// Print the file written by a command. With [`OutputFormat::Json`], the file
+/// is printed as the JSON result instead of the message.
+fn print_file_result(
+    output: OutputFormat,
+    msg: impl Display,
+    file: impl Display,
+) {
+    match output {
+        OutputFormat::Human => println!("{}", msg),
+        OutputFormat::Json => {
+            print_json(&serde_json::json!({ "file": file.to_string() }))
+        }
+    }
+}
+
 #[derive(Debug, Serialize)]
 pub struct TxResponse {
     pub info: String,
     pub log: String,
     pub height: String,
     pub hash: String,
+    /// The canonical hash of the tx
+    pub tx_hash: String,
     pub code: String,
     pub gas_used: String,
     pub initialized_accounts: Vec<Address>,
@@ -1387,6 +2499,7 @@ fn parse(
     let log = event.take("log").unwrap();
     let height = event.take("height").unwrap();
     let hash = event.take("hash").unwrap();
+    let canonical_hash = event.take("tx_hash").unwrap_or_default();
     let code = event.take("code").unwrap();
     let gas_used = event.take("gas_used").unwrap_or_else(|| String::from("0"));
     let initialized_accounts = event.take("initialized_accounts");
@@ -1399,6 +2512,7 @@ fn parse(
         log,
         height,
         hash,
+        tx_hash: canonical_hash,
         code,
         gas_used,
         initialized_accounts,
@@ -1441,6 +2555,9 @@ impl TxResponse {
         let height =
             selector(&format!("$.events.['{}.height'][{}]", evt_key, index))
                 .unwrap();
+        let canonical_hash =
+            selector(&format!("$.events.['{}.tx_hash'][{}]", evt_key, index))
+                .unwrap();
         let code =
             selector(&format!("$.events.['{}.code'][{}]", evt_key, index))
                 .unwrap();
@@ -1474,6 +2591,8 @@ impl TxResponse {
             log: serde_json::from_value(log[0].clone()).unwrap(),
             height: serde_json::from_value(height[0].clone()).unwrap(),
             hash: serde_json::from_value(hash).unwrap(),
+            tx_hash: serde_json::from_value(canonical_hash[0].clone())
+                .unwrap(),
             code: serde_json::from_value(code[0].clone()).unwrap(),
             gas_used: serde_json::from_value(gas_used[0].clone()).unwrap(),
             initialized_accounts,
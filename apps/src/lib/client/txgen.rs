@@ -0,0 +1,300 @@
+//! Load generation for devnets. The txs are all signed up front and then
+//! broadcast at the target rate, each without waiting for the responses to
+//! the previous ones, so that the rate doesn't depend on the node's latency.
+
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+
+use anoma::proto::Tx;
+use anoma::types::address::Address;
+use anoma::types::key::RefTo;
+use anoma::types::token;
+use anoma::types::transaction::{PayloadV1, TxPayload};
+#[cfg(not(feature = "ABCI"))]
+use tendermint_rpc::{Client, HttpClient};
+#[cfg(feature = "ABCI")]
+use tendermint_rpc_abci::{Client, HttpClient};
+
+use super::output::{print_json, print_progress};
+use super::rpc;
+use super::tx::{self, TxBroadcastData};
+use crate::cli::args::OutputFormat;
+use crate::cli::{self, args, Context};
+
+const TX_TRANSFER_WASM: &str = "tx_transfer.wasm";
+
+/// The kinds of the generated txs
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+enum TxKind {
+    Transfer,
+    Custom,
+}
+
+impl TxKind {
+    fn name(&self) -> &'static str {
+        match self {
+            TxKind::Transfer => "Transfers",
+            TxKind::Custom => "Custom transactions",
+        }
+    }
+}
+
+/// The outcomes of the broadcast txs of a kind
+#[derive(Debug, Default)]
+struct Outcomes {
+    /// The number of the txs added to the mempool
+    accepted: u64,
+    /// The numbers of the txs rejected by the mempool, by the rejection log
+    rejected: BTreeMap<String, u64>,
+    /// The number of the txs whose broadcast request failed
+    failed: u64,
+}
+
+/// Generate the txs, broadcast them at the target rate and report the
+/// latencies of the mempool checks and the rejections.
+pub async fn generate(global_args: args::Global, args: args::TxGen) {
+    if args.custom_share > 100 {
+        eprintln!("The custom share must be a percentage of at most 100.");
+        cli::safe_exit(1)
+    }
+    if args.custom_share > 0 && args.code_path.is_none() {
+        eprintln!("The code path of the custom transactions is required.");
+        cli::safe_exit(1)
+    }
+    if args.rate == 0 || args.count == 0 {
+        eprintln!("The rate and the count must be greater than 0.");
+        cli::safe_exit(1)
+    }
+    let output = global_args.output;
+    let mut ctx = Context::new(global_args);
+
+    let transfer = token::Transfer {
+        source: ctx.get(&args.source),
+        target: ctx.get(&args.target),
+        token: ctx.get(&args.token),
+        amount: args.amount,
+    };
+    let transfer_code = ctx.read_wasm(TX_TRANSFER_WASM);
+    let transfer_data = TxPayload::from(PayloadV1::Transfer(transfer)).encode();
+    let custom_code = args
+        .code_path
+        .as_ref()
+        .map(|code_path| ctx.read_wasm(code_path))
+        .unwrap_or_default();
+    let custom_data = args.data_path.as_ref().map(|data_path| {
+        std::fs::read(data_path).expect("Expected a file at given data path")
+    });
+
+    let keypair = tx::find_signing_key(&mut ctx, &args.tx, Some(&args.source))
+        .await
+        .expect("The source is the default signer");
+    // The public key of an implicit signer has to be revealed before its
+    // signature can be verified
+    if args.tx.signing_key.is_none() {
+        let signer_addr =
+            ctx.get(args.tx.signer.as_ref().unwrap_or(&args.source));
+        if let Address::Implicit(_) = signer_addr {
+            ctx = tx::reveal_pk_if_needed(
+                ctx,
+                &args.tx,
+                &keypair.ref_to(),
+                &keypair,
+            )
+            .await;
+        }
+    }
+    let epoch = rpc::query_last_epoch(args::Query {
+        ledger_address: args.tx.ledger_address.clone(),
+    })
+    .await;
+
+    print_progress(
+        output,
+        format_args!("Signing {} transactions...", args.count),
+    );
+    let mut txs = Vec::with_capacity(args.count as usize);
+    for index in 0..args.count {
+        let kind = tx_kind(index, args.custom_share);
+        let tx = match kind {
+            TxKind::Transfer => {
+                Tx::new(transfer_code.clone(), Some(transfer_data.clone()))
+            }
+            TxKind::Custom => Tx::new(custom_code.clone(), custom_data.clone()),
+        };
+        let mut tx = tx.with_chain_id(ctx.config.ledger.chain_id.clone());
+        if let Some(expiration) = args.tx.expiration {
+            tx = tx.with_expiration(expiration);
+        }
+        let tx = tx.sign(&keypair);
+        match tx::sign_wrapper(&ctx, &args.tx, epoch, tx, &keypair).await {
+            TxBroadcastData::Wrapper { tx, .. } => {
+                txs.push((kind, tx.to_bytes()))
+            }
+            TxBroadcastData::DryRun(_) => {
+                unreachable!("A signed wrapper is never a dry run")
+            }
+        }
+    }
+
+    print_progress(
+        output,
+        format_args!(
+            "Broadcasting {} transactions at {} per second...",
+            args.count, args.rate
+        ),
+    );
+    let client = HttpClient::new(args.tx.ledger_address.clone()).unwrap();
+    let mut interval = tokio::time::interval(Duration::from_secs_f64(
+        1.0 / f64::from(args.rate),
+    ));
+    let start = Instant::now();
+    let mut requests = Vec::with_capacity(txs.len());
+    for (kind, tx_bytes) in txs {
+        interval.tick().await;
+        let client = client.clone();
+        requests.push(tokio::spawn(async move {
+            let sent = Instant::now();
+            let response = client.broadcast_tx_sync(tx_bytes.into()).await;
+            (kind, sent.elapsed(), response)
+        }));
+    }
+    let sending_time = start.elapsed();
+
+    let mut outcomes: BTreeMap<TxKind, Outcomes> = BTreeMap::new();
+    let mut latencies = Vec::with_capacity(requests.len());
+    for request in requests {
+        let (kind, latency, response) =
+            request.await.expect("Broadcasting a tx shouldn't panic");
+        let outcomes = outcomes.entry(kind).or_default();
+        match response {
+            Ok(response) => {
+                latencies.push(latency);
+                if response.code == 0.into() {
+                    outcomes.accepted += 1;
+                } else {
+                    *outcomes
+                        .rejected
+                        .entry(response.log.to_string())
+                        .or_default() += 1;
+                }
+            }
+            Err(err) => {
+                tracing::debug!("Broadcasting a tx failed: {:?}", err);
+                outcomes.failed += 1;
+            }
+        }
+    }
+
+    latencies.sort();
+    match output {
+        OutputFormat::Human => {
+            println!(
+                "Sent {} transactions in {:.2?}, {:.1} per second.",
+                args.count,
+                sending_time,
+                args.count as f64 / sending_time.as_secs_f64()
+            );
+            for (kind, outcomes) in outcomes {
+                let rejected: u64 = outcomes.rejected.values().sum();
+                println!(
+                    "{}: {} accepted, {} rejected by the mempool, {} failed \
+                     requests.",
+                    kind.name(),
+                    outcomes.accepted,
+                    rejected,
+                    outcomes.failed
+                );
+                for (log, count) in outcomes.rejected {
+                    println!("  {} rejected with: {}", count, log);
+                }
+            }
+            if !latencies.is_empty() {
+                println!(
+                    "Mempool check latency: p50 {:.2?}, p90 {:.2?}, p99 \
+                     {:.2?}, max {:.2?}",
+                    percentile(&latencies, 50),
+                    percentile(&latencies, 90),
+                    percentile(&latencies, 99),
+                    percentile(&latencies, 100),
+                );
+            }
+        }
+        OutputFormat::Json => {
+            let outcomes: Vec<_> = outcomes
+                .into_iter()
+                .map(|(kind, outcomes)| {
+                    serde_json::json!({
+                        "kind": kind.name(),
+                        "accepted": outcomes.accepted,
+                        "rejected": outcomes.rejected,
+                        "failed": outcomes.failed,
+                    })
+                })
+                .collect();
+            let latency = (!latencies.is_empty()).then(|| {
+                let percentile_secs =
+                    |percent| percentile(&latencies, percent).as_secs_f64();
+                serde_json::json!({
+                    "p50_secs": percentile_secs(50),
+                    "p90_secs": percentile_secs(90),
+                    "p99_secs": percentile_secs(99),
+                    "max_secs": percentile_secs(100),
+                })
+            });
+            print_json(&serde_json::json!({
+                "count": args.count,
+                "sending_secs": sending_time.as_secs_f64(),
+                "outcomes": outcomes,
+                "mempool_check_latency": latency,
+            }));
+        }
+    }
+}
+
+/// The kind of the tx with the given index, with the custom txs spread
+/// evenly among the transfers
+fn tx_kind(index: u64, custom_share: u8) -> TxKind {
+    let share = u64::from(custom_share);
+    if (index + 1) * share / 100 > index * share / 100 {
+        TxKind::Custom
+    } else {
+        TxKind::Transfer
+    }
+}
+
+/// The latency at the given percentile of the non-empty sorted latencies,
+/// using the nearest-rank method
+fn percentile(sorted: &[Duration], percent: usize) -> Duration {
+    let rank = (percent * sorted.len() + 99) / 100;
+    sorted[rank.max(1) - 1]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tx_kind() {
+        let customs = |share: u8, count: u64| {
+            (0..count)
+                .filter(|index| tx_kind(*index, share) == TxKind::Custom)
+                .count()
+        };
+        assert_eq!(customs(0, 1000), 0);
+        assert_eq!(customs(25, 1000), 250);
+        assert_eq!(customs(100, 1000), 1000);
+        // The custom txs are spread evenly
+        assert_eq!(customs(50, 2), 1);
+        assert_eq!(customs(10, 10), 1);
+    }
+
+    #[test]
+    fn test_percentile() {
+        let latencies: Vec<_> = (1..=10).map(Duration::from_millis).collect();
+        assert_eq!(percentile(&latencies, 50), Duration::from_millis(5));
+        assert_eq!(percentile(&latencies, 90), Duration::from_millis(9));
+        assert_eq!(percentile(&latencies, 99), Duration::from_millis(10));
+        assert_eq!(percentile(&latencies, 100), Duration::from_millis(10));
+        assert_eq!(percentile(&latencies[..1], 0), Duration::from_millis(1));
+    }
+}
@@ -1,21 +1,26 @@
 use std::collections::{HashMap, HashSet};
 use std::env;
 use std::fs::{self, File, OpenOptions};
-use std::io::Write;
+use std::io::{Read, Write};
 use std::net::{IpAddr, Ipv4Addr, SocketAddr};
 use std::path::{Path, PathBuf};
 use std::str::FromStr;
 
+use anoma::ledger::storage::MerkleTree;
 use anoma::types::address;
 use anoma::types::chain::ChainId;
 use anoma::types::key::*;
-use borsh::BorshSerialize;
+use anoma::types::time::{DateTimeUtc, Rfc3339String};
+use anoma::types::{storage, token};
+use borsh::{BorshDeserialize, BorshSerialize};
 use flate2::read::GzDecoder;
 use flate2::write::GzEncoder;
 use flate2::Compression;
+use itertools::Itertools;
 use prost::bytes::Bytes;
 use rand::prelude::ThreadRng;
 use rand::thread_rng;
+use serde::Serialize;
 use serde_json::json;
 use sha2::{Digest, Sha256};
 #[cfg(not(feature = "ABCI"))]
@@ -27,8 +32,10 @@ use tendermint_config_abci::net::Address as TendermintAddress;
 #[cfg(feature = "ABCI")]
 use tendermint_stable::node::Id as TendermintNodeId;
 
+use crate::cli::args::OutputFormat;
 use crate::cli::context::ENV_VAR_WASM_DIR;
 use crate::cli::{self, args};
+use crate::client::output::{print_json, print_progress, print_result};
 use crate::config::genesis::genesis_config::{
     self, HexString, ValidatorPreGenesisConfig,
 };
@@ -37,6 +44,9 @@ use crate::config::{
     self, Config, IntentGossiper, PeerAddress, TendermintMode,
 };
 use crate::node::gossip;
+use crate::node::ledger::storage::{
+    self as ledger_storage, PersistentStorageHasher, ReadOnlyDB,
+};
 use crate::node::ledger::tendermint_node;
 use crate::wallet::{pre_genesis, Wallet};
 use crate::wasm_loader;
@@ -51,6 +61,99 @@ const DEFAULT_NETWORK_CONFIGS_SERVER: &str =
 /// We do pre-genesis validator set up in this directory
 pub const PRE_GENESIS_DIR: &str = "pre-genesis";
 
+/// Bootstrap a node for the given chain in one step. Creates the chain
+/// directory with the default config, copies the genesis file, sets the chain
+/// as the default chain and initializes the Tendermint home directory with the
+/// node's keys and config.
+pub async fn init(
+    global_args: args::Global,
+    args::Init {
+        chain_id,
+        genesis_path,
+    }: args::Init,
+) {
+    let output = global_args.output;
+    let base_dir = global_args.base_dir;
+    let chain_dir = base_dir.join(chain_id.as_str());
+    if chain_dir.exists() {
+        eprintln!("The chain directory for {} already exists.", chain_id);
+        cli::safe_exit(1);
+    }
+
+    // Copy the genesis file. Without it, the built-in genesis is used in the
+    // "dev" build.
+    let genesis_time: Rfc3339String = match genesis_path {
+        Some(genesis_path) => {
+            let genesis_file_path =
+                base_dir.join(format!("{}.toml", chain_id.as_str()));
+            fs::create_dir_all(&base_dir).unwrap();
+            fs::copy(&genesis_path, &genesis_file_path).unwrap_or_else(|err| {
+                eprintln!(
+                    "Couldn't copy the genesis file {}: {}",
+                    genesis_path.to_string_lossy(),
+                    err
+                );
+                cli::safe_exit(1)
+            });
+            genesis_config::open_genesis_config(genesis_file_path).genesis_time
+        }
+        #[cfg(feature = "dev")]
+        None => config::genesis::genesis().genesis_time.into(),
+        #[cfg(not(feature = "dev"))]
+        None => {
+            eprintln!("The argument `--genesis-path` is required.");
+            cli::safe_exit(1)
+        }
+    };
+
+    let mode = global_args.mode.unwrap_or(TendermintMode::Full);
+    let mut config = Config::new(&base_dir, chain_id.clone(), mode);
+    config.ledger.genesis_time = genesis_time.clone();
+    config
+        .write(&base_dir, &chain_id, false)
+        .unwrap_or_else(|err| {
+            eprintln!("Error writing the config: {}", err);
+            cli::safe_exit(1)
+        });
+
+    // Use the chain by default
+    GlobalConfig::new(chain_id.clone())
+        .write(&base_dir)
+        .unwrap_or_else(|err| {
+            eprintln!("Error writing the global config: {}", err);
+            cli::safe_exit(1)
+        });
+
+    let genesis_time: DateTimeUtc =
+        genesis_time.try_into().unwrap_or_else(|err| {
+            eprintln!("Invalid genesis time: {}", err);
+            cli::safe_exit(1)
+        });
+    tendermint_node::init(
+        &config.ledger.tendermint_dir(),
+        chain_id.clone(),
+        genesis_time,
+        config.ledger.tendermint,
+    )
+    .await
+    .unwrap_or_else(|err| {
+        eprintln!("Error initializing Tendermint: {}", err);
+        cli::safe_exit(1)
+    });
+
+    match output {
+        OutputFormat::Human => println!(
+            "Successfully initialized a node for chain ID {} in {}",
+            chain_id,
+            chain_dir.to_string_lossy()
+        ),
+        OutputFormat::Json => print_json(&json!({
+            "chain_id": chain_id,
+            "chain_dir": chain_dir,
+        })),
+    }
+}
+
 /// Configure Anoma to join an existing network. The chain must be released in
 /// the <https://github.com/heliaxdev/anoma-network-config> repository.
 pub async fn join_network(
@@ -59,10 +162,13 @@ pub async fn join_network(
         chain_id,
         genesis_validator,
         pre_genesis_path,
+        configs_server,
+        archive_hash,
     }: args::JoinNetwork,
 ) {
     use tokio::fs;
 
+    let output = global_args.output;
     let base_dir = global_args.base_dir;
 
     // If the base-dir doesn't exist yet, create it
@@ -89,9 +195,12 @@ pub async fn join_network(
             match alias {
                 std::path::Component::Normal(alias) => {
                     let alias = alias.to_string_lossy().to_string();
-                    println!(
-                        "Using {alias} parsed from the given \
-                         --pre-genesis-path"
+                    print_progress(
+                        output,
+                        format_args!(
+                            "Using {alias} parsed from the given \
+                             --pre-genesis-path"
+                        ),
                     );
                     Some((alias, path))
                 }
@@ -144,12 +253,15 @@ pub async fn join_network(
     let release_filename = format!("{}.tar.gz", chain_id);
     let release_url = format!(
         "{}/{}",
-        network_configs_url_prefix(&chain_id),
+        network_configs_url_prefix(&chain_id, configs_server),
         release_filename
     );
 
     // Read or download the release archive
-    println!("Downloading config release from {} ...", release_url);
+    print_progress(
+        output,
+        format_args!("Downloading config release from {} ...", release_url),
+    );
     let release = match download_file(release_url).await {
         Ok(contents) => contents,
         Err(error) => {
@@ -158,6 +270,33 @@ pub async fn join_network(
         }
     };
 
+    // Verify the archive's hash, if given
+    let release_hash = format!("{:x}", Sha256::digest(&release[..]));
+    match archive_hash {
+        Some(expected_hash)
+            if !expected_hash.eq_ignore_ascii_case(&release_hash) =>
+        {
+            eprintln!(
+                "The release archive hash {} doesn't match the expected hash \
+                 {}.",
+                release_hash, expected_hash
+            );
+            cli::safe_exit(1);
+        }
+        Some(_) => print_progress(output, "The release archive hash is valid."),
+        None => print_progress(
+            output,
+            format_args!("The release archive hash is {}.", release_hash),
+        ),
+    }
+
+    // Check that the chain ID is derived from the released genesis before
+    // unpacking anything
+    if let Err(err) = validate_release_genesis(&release, &chain_id) {
+        eprintln!("Invalid release archive: {}", err);
+        cli::safe_exit(1);
+    }
+
     // Decode and unpack the archive
     let decoder = GzDecoder::new(&release[..]);
     let mut archive = tar::Archive::new(decoder);
@@ -294,7 +433,6 @@ pub async fn join_network(
         // Write consensus key to tendermint home
         tendermint_node::write_validator_key(
             &tm_home_dir,
-            &address,
             &*pre_genesis_wallet.consensus_key,
         );
 
@@ -350,7 +488,12 @@ pub async fn join_network(
         .unwrap();
     }
 
-    println!("Successfully configured for chain ID {}", chain_id);
+    match output {
+        OutputFormat::Human => {
+            println!("Successfully configured for chain ID {}", chain_id)
+        }
+        OutputFormat::Json => print_json(&json!({ "chain_id": chain_id })),
+    }
 }
 
 /// Length of a Tendermint Node ID in bytes
@@ -364,13 +507,45 @@ fn id_from_pk(pk: &ed25519::PublicKey) -> TendermintNodeId {
     TendermintNodeId::new(bytes)
 }
 
+/// The files of a network initialized with [`init_network`]
+#[derive(Debug, Serialize)]
+struct InitializedNetwork {
+    chain_id: ChainId,
+    genesis_path: PathBuf,
+    /// The release archive, unless it's disabled
+    release_archive: Option<PathBuf>,
+}
+
+impl InitializedNetwork {
+    fn print_human(&self) {
+        println!("Derived chain ID: {}", self.chain_id);
+        println!(
+            "Genesis file generated at {}",
+            self.genesis_path.to_string_lossy()
+        );
+        if let Some(release_archive) = &self.release_archive {
+            println!(
+                "Release archive created at {}",
+                release_archive.to_string_lossy()
+            );
+        }
+    }
+}
+
 /// Initialize a new test network from the given configuration.
 ///
 /// For any public keys that are not specified in the genesis configuration,
 /// this command will generate them and place them in the "setup" directory
 /// inside the chain-dir, so it can be used for testing (we're using it in the
 /// e2e tests), dev/test-nets and public networks setup.
-pub fn init_network(
+pub fn init_network(global_args: args::Global, args: args::InitNetwork) {
+    let output = global_args.output;
+    let network = init_network_files(global_args, args);
+    print_result(output, &network, InitializedNetwork::print_human);
+}
+
+/// Initialize the files of a new network, see [`init_network`].
+fn init_network_files(
     global_args: args::Global,
     args::InitNetwork {
         genesis_path,
@@ -383,7 +558,8 @@ pub fn init_network(
         dont_archive,
         archive_dir,
     }: args::InitNetwork,
-) {
+) -> InitializedNetwork {
+    let output = global_args.output;
     let mut config = genesis_config::open_genesis_config(&genesis_path);
 
     // Update the WASM checksums
@@ -517,16 +693,15 @@ pub fn init_network(
         )
         .unwrap_or_else(|| {
             let alias = format!("{}-consensus-key", name);
-            println!("Generating validator {} consensus key...", name);
+            print_progress(
+                output,
+                format_args!("Generating validator {} consensus key...", name),
+            );
             let (_alias, keypair) =
                 wallet.gen_key(Some(alias), unsafe_dont_encrypt);
 
             // Write consensus key for Tendermint
-            tendermint_node::write_validator_key(
-                &tm_home_dir,
-                &address,
-                &keypair,
-            );
+            tendermint_node::write_validator_key(&tm_home_dir, &keypair);
 
             keypair.ref_to()
         });
@@ -537,7 +712,10 @@ pub fn init_network(
         )
         .unwrap_or_else(|| {
             let alias = format!("{}-account-key", name);
-            println!("Generating validator {} account key...", name);
+            print_progress(
+                output,
+                format_args!("Generating validator {} account key...", name),
+            );
             let (_alias, keypair) =
                 wallet.gen_key(Some(alias), unsafe_dont_encrypt);
             keypair.ref_to()
@@ -549,9 +727,12 @@ pub fn init_network(
         )
         .unwrap_or_else(|| {
             let alias = format!("{}-reward-key", name);
-            println!(
-                "Generating validator {} staking reward account key...",
-                name
+            print_progress(
+                output,
+                format_args!(
+                    "Generating validator {} staking reward account key...",
+                    name
+                ),
             );
             let (_alias, keypair) =
                 wallet.gen_key(Some(alias), unsafe_dont_encrypt);
@@ -564,7 +745,13 @@ pub fn init_network(
         )
         .unwrap_or_else(|| {
             let alias = format!("{}-protocol-key", name);
-            println!("Generating validator {} protocol signing key...", name);
+            print_progress(
+                output,
+                format_args!(
+                    "Generating validator {} protocol signing key...",
+                    name
+                ),
+            );
             let (_alias, keypair) =
                 wallet.gen_key(Some(alias), unsafe_dont_encrypt);
             keypair.ref_to()
@@ -581,9 +768,12 @@ pub fn init_network(
                 })
             })
             .unwrap_or_else(|| {
-                println!(
-                    "Generating validator {} DKG session keypair...",
-                    name
+                print_progress(
+                    output,
+                    format_args!(
+                        "Generating validator {} DKG session keypair...",
+                        name
+                    ),
                 );
 
                 let validator_keys = wallet
@@ -632,6 +822,7 @@ pub fn init_network(
                             &mut wallet,
                             &mut matchmaker,
                             unsafe_dont_encrypt,
+                            output,
                         );
                         validator_owned_accounts
                             .insert(account.clone(), matchmaker);
@@ -695,6 +886,7 @@ pub fn init_network(
                         &mut wallet,
                         config,
                         unsafe_dont_encrypt,
+                        output,
                     );
                 }
             }
@@ -717,9 +909,12 @@ pub fn init_network(
     if let Some(implicit) = &mut config.implicit {
         implicit.iter_mut().for_each(|(name, config)| {
             if config.public_key.is_none() {
-                println!(
-                    "Generating implicit account {} key and address ...",
-                    name
+                print_progress(
+                    output,
+                    format_args!(
+                        "Generating implicit account {} key and address ...",
+                        name
+                    ),
                 );
                 let (_alias, keypair) =
                     wallet.gen_key(Some(name.clone()), unsafe_dont_encrypt);
@@ -890,7 +1085,7 @@ pub fn init_network(
                 .tendermint
                 .rpc_address
                 .set_port(first_port + 1);
-            config.ledger.shell.ledger_address.set_port(first_port + 2);
+            config.ledger.shell.set_ledger_port(first_port + 2);
             // Validator node should turned off peer exchange reactor
             config.ledger.tendermint.p2p_pex = false;
 
@@ -939,19 +1134,13 @@ pub fn init_network(
         .write(&global_args.base_dir, &chain_id, true)
         .unwrap();
 
-    println!("Derived chain ID: {}", chain_id);
-    println!(
-        "Genesis file generated at {}",
-        genesis_path.to_string_lossy()
-    );
-
     // Create a release tarball for anoma-network-config
-    if !dont_archive {
+    let release_archive = if !dont_archive {
         let mut release = tar::Builder::new(Vec::new());
         let release_genesis_path = PathBuf::from(config::DEFAULT_BASE_DIR)
             .join(format!("{}.toml", chain_id.as_str()));
         release
-            .append_path_with_name(genesis_path, release_genesis_path)
+            .append_path_with_name(&genesis_path, release_genesis_path)
             .unwrap();
         let global_config_path = GlobalConfig::file_path(&global_args.base_dir);
         let release_global_config_path =
@@ -990,18 +1179,207 @@ pub fn init_network(
             GzEncoder::new(compressed_file, Compression::default());
         encoder.write_all(&release.into_inner().unwrap()).unwrap();
         encoder.finish().unwrap();
-        println!(
-            "Release archive created at {}",
-            release_file.to_string_lossy()
+        Some(release_file)
+    } else {
+        None
+    };
+    InitializedNetwork {
+        chain_id,
+        genesis_path,
+        release_archive,
+    }
+}
+
+/// The number of ports reserved for each node of a local network. A node
+/// uses the ports from `first_port` up to `first_port + 5`.
+const LOCALNET_PORTS_PER_NODE: u16 = 10;
+
+/// Initialize a local network with the given number of validators from a
+/// template genesis configuration.
+///
+/// The validators are generated from the template's first validator and their
+/// nodes are configured to run on localhost with distinct ports and with each
+/// other as persistent peers. The network is then initialized as with
+/// [`init_network`], without an archive and with unencrypted keys.
+pub fn init_localnet(
+    global_args: args::Global,
+    args::InitLocalnet {
+        genesis_path,
+        wasm_checksums_path,
+        chain_id_prefix,
+        validators,
+        first_port,
+        consensus_timeout_commit,
+    }: args::InitLocalnet,
+) {
+    if validators == 0 {
+        eprintln!("A local network needs at least one validator.");
+        cli::safe_exit(1)
+    }
+    let last_port =
+        u64::from(first_port) + validators * u64::from(LOCALNET_PORTS_PER_NODE);
+    if last_port > u64::from(u16::MAX) {
+        eprintln!(
+            "Not enough ports for {} validators starting from port {}.",
+            validators, first_port
         );
+        cli::safe_exit(1)
+    }
+
+    let template = genesis_config::open_genesis_config(&genesis_path);
+    let config = localnet_genesis_config(template, validators, first_port);
+    fs::create_dir_all(&global_args.base_dir).unwrap();
+    let localnet_genesis_path =
+        global_args.base_dir.join("localnet-genesis.toml");
+    genesis_config::write_genesis_config(&config, &localnet_genesis_path);
+    print_progress(
+        global_args.output,
+        format_args!(
+            "Local network genesis file generated at {}",
+            localnet_genesis_path.to_string_lossy()
+        ),
+    );
+
+    let network = init_network_files(
+        global_args.clone(),
+        args::InitNetwork {
+            genesis_path: localnet_genesis_path,
+            wasm_checksums_path,
+            chain_id_prefix,
+            unsafe_dont_encrypt: true,
+            consensus_timeout_commit,
+            localhost: true,
+            allow_duplicate_ip: true,
+            dont_archive: true,
+            archive_dir: None,
+        },
+    );
+
+    let accounts_dir = global_args
+        .base_dir
+        .join(network.chain_id.as_str())
+        .join(NET_ACCOUNTS_DIR);
+    let base_dirs: Vec<PathBuf> = (0..validators)
+        .map(|index| {
+            accounts_dir
+                .join(localnet_validator_alias(index))
+                .join(config::DEFAULT_BASE_DIR)
+        })
+        .collect();
+    match global_args.output {
+        OutputFormat::Human => {
+            network.print_human();
+            println!("The validator nodes can be started with:");
+            for base_dir in base_dirs {
+                println!(
+                    "  anoma --base-dir {} ledger",
+                    base_dir.to_string_lossy()
+                );
+            }
+        }
+        OutputFormat::Json => print_json(&json!({
+            "chain_id": network.chain_id,
+            "genesis_path": network.genesis_path,
+            "validator_base_dirs": base_dirs,
+        })),
     }
 }
 
+/// The alias of a local network validator at the given index.
+fn localnet_validator_alias(index: u64) -> String {
+    format!("validator-{}", index)
+}
+
+/// Make a genesis configuration with the given number of validators on
+/// localhost from a template genesis configuration. The template's first
+/// validator by alias is replaced by the new validators, which inherit its
+/// stake and its token balances. With more than one validator, the first one
+/// is an intent gossip seed node.
+fn localnet_genesis_config(
+    mut config: genesis_config::GenesisConfig,
+    validators: u64,
+    first_port: u16,
+) -> genesis_config::GenesisConfig {
+    let (template_alias, template) = config
+        .validator
+        .iter()
+        .min_by(|(alias, _), (other, _)| alias.cmp(other))
+        .map(|(alias, validator)| (alias.clone(), validator.clone()))
+        .unwrap_or_else(|| {
+            eprintln!(
+                "The template genesis configuration must contain a validator."
+            );
+            cli::safe_exit(1)
+        });
+    let template = genesis_config::ValidatorConfig {
+        consensus_public_key: None,
+        account_public_key: None,
+        staking_reward_public_key: None,
+        protocol_public_key: None,
+        dkg_public_key: None,
+        address: None,
+        staking_reward_address: None,
+        net_address: None,
+        tendermint_node_key: None,
+        ..template
+    };
+
+    config.validator = (0..validators)
+        .map(|index| {
+            let port = first_port + index as u16 * LOCALNET_PORTS_PER_NODE;
+            let is_seed = index == 0 && validators > 1;
+            // A seed node cannot run matchmakers, so the matchmaker runs on
+            // the first validator that is not a seed
+            let runs_matchmaker = index == u64::from(validators > 1);
+            let validator = genesis_config::ValidatorConfig {
+                net_address: Some(format!("127.0.0.1:{}", port)),
+                intent_gossip_seed: Some(is_seed),
+                matchmaker_account: template
+                    .matchmaker_account
+                    .clone()
+                    .filter(|_| runs_matchmaker),
+                matchmaker_code: template
+                    .matchmaker_code
+                    .clone()
+                    .filter(|_| runs_matchmaker),
+                matchmaker_tx: template
+                    .matchmaker_tx
+                    .clone()
+                    .filter(|_| runs_matchmaker),
+                ..template.clone()
+            };
+            (localnet_validator_alias(index), validator)
+        })
+        .collect();
+
+    // Give the new validators the template validator's token balances
+    let template_pk_alias = format!("{}.public_key", template_alias);
+    if let Some(tokens) = config.token.as_mut() {
+        for balances in tokens.values_mut().filter_map(|t| t.balances.as_mut())
+        {
+            let balance = balances.remove(&template_alias);
+            let pk_balance = balances.remove(&template_pk_alias);
+            for index in 0..validators {
+                let alias = localnet_validator_alias(index);
+                if let Some(balance) = balance {
+                    balances.insert(alias.clone(), balance);
+                }
+                if let Some(pk_balance) = pk_balance {
+                    balances
+                        .insert(format!("{}.public_key", alias), pk_balance);
+                }
+            }
+        }
+    }
+    config
+}
+
 fn init_established_account(
     name: impl AsRef<str>,
     wallet: &mut Wallet,
     config: &mut genesis_config::EstablishedAccountConfig,
     unsafe_dont_encrypt: bool,
+    output: OutputFormat,
 ) {
     if config.address.is_none() {
         let address = address::gen_established_address("established");
@@ -1009,7 +1387,13 @@ fn init_established_account(
         wallet.add_address(&name, address);
     }
     if config.public_key.is_none() {
-        println!("Generating established account {} key...", name.as_ref());
+        print_progress(
+            output,
+            format_args!(
+                "Generating established account {} key...",
+                name.as_ref()
+            ),
+        );
         let (_alias, keypair) = wallet.gen_key(
             Some(format!("{}-key", name.as_ref())),
             unsafe_dont_encrypt,
@@ -1034,9 +1418,10 @@ pub fn init_genesis_validator(
         unsafe_dont_encrypt,
     }: args::InitGenesisValidator,
 ) {
+    let output = global_args.output;
     let pre_genesis_dir =
         validator_pre_genesis_dir(&global_args.base_dir, &alias);
-    println!("Generating validator keys...");
+    print_progress(output, "Generating validator keys...");
     let pre_genesis = pre_genesis::ValidatorWallet::gen_and_store(
         unsafe_dont_encrypt,
         &pre_genesis_dir,
@@ -1048,9 +1433,13 @@ pub fn init_genesis_validator(
         );
         cli::safe_exit(1)
     });
-    println!(
-        "The validator's keys were stored in the wallet at {}",
-        pre_genesis::validator_file_name(&pre_genesis_dir).to_string_lossy()
+    print_progress(
+        output,
+        format_args!(
+            "The validator's keys were stored in the wallet at {}",
+            pre_genesis::validator_file_name(&pre_genesis_dir)
+                .to_string_lossy()
+        ),
     );
 
     let validator_config = ValidatorPreGenesisConfig {
@@ -1093,12 +1482,14 @@ pub fn init_genesis_validator(
         )]),
     };
     let genesis_part = toml::to_string(&validator_config).unwrap();
-    println!("Your public partial pre-genesis TOML configuration:");
-    println!();
-    println!("{genesis_part}");
+    if output == OutputFormat::Human {
+        println!("Your public partial pre-genesis TOML configuration:");
+        println!();
+        println!("{genesis_part}");
+    }
 
     let file_name = validator_pre_genesis_file(&pre_genesis_dir);
-    fs::write(&file_name, genesis_part).unwrap_or_else(|err| {
+    fs::write(&file_name, &genesis_part).unwrap_or_else(|err| {
         eprintln!(
             "Couldn't write partial pre-genesis file to {}. Failed with: {}",
             file_name.to_string_lossy(),
@@ -1106,11 +1497,19 @@ pub fn init_genesis_validator(
         );
         cli::safe_exit(1)
     });
-    println!();
-    println!(
-        "Pre-genesis TOML written to {}",
-        file_name.to_string_lossy()
-    );
+    match output {
+        OutputFormat::Human => {
+            println!();
+            println!(
+                "Pre-genesis TOML written to {}",
+                file_name.to_string_lossy()
+            );
+        }
+        OutputFormat::Json => print_json(&json!({
+            "genesis_part": genesis_part,
+            "file": file_name,
+        })),
+    }
 }
 
 async fn download_file(url: impl AsRef<str>) -> reqwest::Result<Bytes> {
@@ -1134,10 +1533,52 @@ fn try_parse_public_key(
     })
 }
 
-fn network_configs_url_prefix(chain_id: &ChainId) -> String {
-    std::env::var(ENV_VAR_NETWORK_CONFIGS_SERVER).unwrap_or_else(|_| {
-        format!("{DEFAULT_NETWORK_CONFIGS_SERVER}/{chain_id}")
-    })
+/// The URL prefix of a network config release is the given server, if any,
+/// or the one set in the [`ENV_VAR_NETWORK_CONFIGS_SERVER`] or the default.
+fn network_configs_url_prefix(
+    chain_id: &ChainId,
+    configs_server: Option<String>,
+) -> String {
+    configs_server
+        .or_else(|| std::env::var(ENV_VAR_NETWORK_CONFIGS_SERVER).ok())
+        .unwrap_or_else(|| {
+            format!("{DEFAULT_NETWORK_CONFIGS_SERVER}/{chain_id}")
+        })
+}
+
+/// Check that the genesis file in a network config release archive matches
+/// the chain ID, which is derived from the genesis hash.
+fn validate_release_genesis(
+    release: &[u8],
+    chain_id: &ChainId,
+) -> Result<(), String> {
+    let genesis_path = PathBuf::from(config::DEFAULT_BASE_DIR)
+        .join(format!("{}.toml", chain_id.as_str()));
+    let mut archive = tar::Archive::new(GzDecoder::new(release));
+    let entries = archive.entries().map_err(|err| err.to_string())?;
+    for entry in entries {
+        let mut entry = entry.map_err(|err| err.to_string())?;
+        if *entry.path().map_err(|err| err.to_string())? != *genesis_path {
+            continue;
+        }
+        let mut genesis_toml = String::new();
+        entry
+            .read_to_string(&mut genesis_toml)
+            .map_err(|err| err.to_string())?;
+        let config: genesis_config::GenesisConfig =
+            toml::from_str(&genesis_toml).map_err(|err| err.to_string())?;
+        let genesis = genesis_config::load_genesis_config(config);
+        let errors = chain_id.validate(genesis.try_to_vec().unwrap());
+        return if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors.into_iter().join(". "))
+        };
+    }
+    Err(format!(
+        "The genesis file {} is missing",
+        genesis_path.to_string_lossy()
+    ))
 }
 
 fn write_tendermint_node_key(
@@ -1179,3 +1620,231 @@ pub fn validator_pre_genesis_file(pre_genesis_path: &Path) -> PathBuf {
 pub fn validator_pre_genesis_dir(base_dir: &Path, alias: &str) -> PathBuf {
     base_dir.join(PRE_GENESIS_DIR).join(alias)
 }
+
+/// Print the storage keys and values of the accounts in the node's DB, only
+/// the ones with the given key prefix, if any.
+pub fn db_dump(
+    global_args: args::Global,
+    args::DbDump { prefix }: args::DbDump,
+) {
+    let db = open_db_read_only(&global_args);
+    let entries = db.iter_prefix(prefix.as_ref()).map(|(key, value, _gas)| {
+        let key = storage::Key::parse(&key)
+            .expect("The keys in the DB should be valid storage keys");
+        (key, value)
+    });
+    match global_args.output {
+        OutputFormat::Human => {
+            for (key, value) in entries {
+                print_storage_value(&key, &value);
+            }
+        }
+        OutputFormat::Json => {
+            let entries: Vec<_> = entries
+                .map(|(key, value)| storage_value_json(&key, &value))
+                .collect();
+            print_json(&entries);
+        }
+    }
+}
+
+/// Print the value of a storage key in the node's DB.
+pub fn db_get(
+    global_args: args::Global,
+    args::DbGet { storage_key }: args::DbGet,
+) {
+    let db = open_db_read_only(&global_args);
+    match db.read_subspace_val(&storage_key) {
+        Ok(Some(value)) => match global_args.output {
+            OutputFormat::Human => print_storage_value(&storage_key, &value),
+            OutputFormat::Json => {
+                print_json(&storage_value_json(&storage_key, &value))
+            }
+        },
+        Ok(None) => {
+            eprintln!("No value found for key: {}", storage_key);
+            cli::safe_exit(1)
+        }
+        Err(err) => {
+            eprintln!("Failed to read the key {}: {}", storage_key, err);
+            cli::safe_exit(1)
+        }
+    }
+}
+
+/// Run the ledger's benchmark workloads in a temporary directory and print
+/// their mean durations.
+pub fn bench(global_args: args::Global, args: args::Bench) {
+    let dir =
+        env::temp_dir().join(format!("anoma-bench-{}", std::process::id()));
+    fs::create_dir_all(&dir)
+        .expect("Unable to create a temporary directory for the benchmarks");
+    let results =
+        crate::node::ledger::bench::run(&dir, args.test_wasm_dir, args.runs);
+    if let Err(err) = fs::remove_dir_all(&dir) {
+        eprintln!(
+            "Failed to remove the temporary directory {}: {}",
+            dir.to_string_lossy(),
+            err
+        );
+    }
+    match global_args.output {
+        OutputFormat::Human => {
+            for (name, mean) in results {
+                println!("{:<28} {:?}", name, mean);
+            }
+        }
+        OutputFormat::Json => {
+            let results: Vec<_> = results
+                .into_iter()
+                .map(|(name, mean)| {
+                    json!({ "name": name, "mean_secs": mean.as_secs_f64() })
+                })
+                .collect();
+            print_json(&results);
+        }
+    }
+}
+
+/// Recompute the Merkle root from all the storage key-vals in the node's DB
+/// and compare it with the stored root of the last committed block.
+pub fn verify_state(global_args: args::Global) {
+    let db = open_db_read_only(&global_args);
+    let (height, stored_root) = match db.read_last_root() {
+        Ok(Some(last_root)) => last_root,
+        Ok(None) => {
+            eprintln!("No block has been committed yet");
+            cli::safe_exit(1)
+        }
+        Err(err) => {
+            eprintln!("Failed to read the last Merkle root: {}", err);
+            cli::safe_exit(1)
+        }
+    };
+    let mut tree = MerkleTree::<PersistentStorageHasher>::default();
+    let mut keys_count: u64 = 0;
+    for (key, value, _gas) in db.iter_prefix(None) {
+        let key = storage::Key::parse(&key)
+            .expect("The keys in the DB should be valid storage keys");
+        if let Err(err) = tree.update(&key, value) {
+            eprintln!("Failed to update the Merkle tree with {}: {}", key, err);
+            cli::safe_exit(1)
+        }
+        keys_count += 1;
+    }
+    let root = tree.root();
+    if root == stored_root {
+        match global_args.output {
+            OutputFormat::Human => println!(
+                "The Merkle root of the {} stored keys matches the root {} of \
+                 the last block {}.",
+                keys_count, root, height
+            ),
+            OutputFormat::Json => print_json(&json!({
+                "height": height.0,
+                "root": root.to_string(),
+                "keys_count": keys_count,
+            })),
+        }
+    } else {
+        eprintln!(
+            "The Merkle root {} of the {} stored keys doesn't match the root \
+             {} of the last block {}.",
+            root, keys_count, stored_root, height
+        );
+        cli::safe_exit(1)
+    }
+}
+
+/// Open the DB of the node of the chain from the global arguments, or of the
+/// default chain, for reading only.
+fn open_db_read_only(global_args: &args::Global) -> ReadOnlyDB {
+    let chain_id = match global_args.chain_id.clone() {
+        Some(chain_id) => chain_id,
+        None => GlobalConfig::read(&global_args.base_dir)
+            .unwrap_or_else(|err| {
+                eprintln!("Failed to read the global config: {}", err);
+                cli::safe_exit(1)
+            })
+            .default_chain_id,
+    };
+    let config = Config::load_with_overrides(
+        &global_args.base_dir,
+        &chain_id,
+        global_args.mode.clone(),
+        &global_args.config_overrides,
+    );
+    let db_dir = config.ledger.db_dir();
+    ledger_storage::open_read_only(&db_dir).unwrap_or_else(|err| {
+        eprintln!("Failed to open the DB at {}: {}", db_dir.display(), err);
+        cli::safe_exit(1)
+    })
+}
+
+fn print_storage_value(key: &storage::Key, value: &[u8]) {
+    println!("{}", key);
+    println!("  hex: {}", hex::encode(value));
+    if let Some(decoded) = decode_storage_value(key, value) {
+        println!("  decoded: {}", decoded);
+    }
+}
+
+fn storage_value_json(key: &storage::Key, value: &[u8]) -> serde_json::Value {
+    json!({
+        "key": key.to_string(),
+        "value": hex::encode(value),
+        "decoded": decode_storage_value(key, value),
+    })
+}
+
+/// Decode a storage value whose type is known from its key, e.g. a token
+/// balance. Returns `None` for the other keys or if the value is invalid.
+fn decode_storage_value(key: &storage::Key, value: &[u8]) -> Option<String> {
+    if token::is_any_token_balance_key(key).is_some()
+        || token::is_any_supply_key(key).is_some()
+    {
+        token::Amount::try_from_slice(value)
+            .ok()
+            .map(|amount| amount.to_string())
+    } else if is_pk_key(key).is_some() || is_protocol_pk_key(key).is_some() {
+        common::PublicKey::try_from_slice(value)
+            .ok()
+            .map(|pk| pk.to_string())
+    } else if key.is_validity_predicate().is_some() {
+        Some(format!("validity predicate WASM code of {} bytes", value.len()))
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_localnet_genesis_config() {
+        let template = genesis_config::open_genesis_config(
+            "../genesis/e2e-tests-single-node.toml",
+        );
+        let config = localnet_genesis_config(template, 3, 27656);
+
+        let mut aliases: Vec<_> = config.validator.keys().cloned().collect();
+        aliases.sort();
+        assert_eq!(aliases, ["validator-0", "validator-1", "validator-2"]);
+        let net_address =
+            |alias: &str| config.validator[alias].net_address.clone();
+        assert_eq!(net_address("validator-0").unwrap(), "127.0.0.1:27656");
+        assert_eq!(net_address("validator-2").unwrap(), "127.0.0.1:27676");
+        assert_eq!(
+            config.validator["validator-0"].intent_gossip_seed,
+            Some(true)
+        );
+        assert!(config.validator["validator-0"].matchmaker_code.is_none());
+        assert!(config.validator["validator-1"].matchmaker_code.is_some());
+
+        let xan = &config.token.as_ref().unwrap()["XAN"];
+        let balances = xan.balances.as_ref().unwrap();
+        assert_eq!(balances.get("validator-2.public_key"), Some(&100));
+        assert!(balances.get("validator-0").is_none());
+    }
+}
@@ -27,9 +27,11 @@ pub mod genesis_config {
     use std::str::FromStr;
 
     use anoma::ledger::governance::parameters::GovParams;
-    use anoma::ledger::parameters::{EpochDuration, Parameters};
+    use anoma::ledger::parameters::inflation::InflationParams;
+    use anoma::ledger::parameters::{self, EpochDuration, Parameters};
     use anoma::ledger::pos::types::BasisPoints;
     use anoma::ledger::pos::{GenesisValidator, PosParams};
+    use anoma::ledger::storage::write_log::SizeLimits;
     use anoma::ledger::treasury::parameters::TreasuryParams;
     use anoma::types::address::Address;
     use anoma::types::key::dkg_session_keys::DkgPublicKey;
@@ -41,7 +43,8 @@ pub mod genesis_config {
     use thiserror::Error;
 
     use super::{
-        EstablishedAccount, Genesis, ImplicitAccount, TokenAccount, Validator,
+        ConsensusParams, EstablishedAccount, Genesis, ImplicitAccount,
+        TokenAccount, Validator,
     };
     use crate::cli;
 
@@ -123,6 +126,9 @@ pub mod genesis_config {
         pub treasury_params: TreasuryParamasConfig,
         // Wasm definitions
         pub wasm: HashMap<String, WasmConfig>,
+        // Tendermint consensus parameters (default: Tendermint's defaults)
+        #[serde(default)]
+        pub consensus_params: Option<ConsensusParamsConfig>,
     }
 
     #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -149,6 +155,10 @@ pub mod genesis_config {
         // Maximum funds that can be moved from treasury in a single transfer
         // XXX: u64 doesn't work with toml-rs!
         pub max_proposal_fund_transfer: u64,
+        // Share of the fees and the reward of every block paid to the
+        // treasury, in basis points (per ten thousand)
+        // XXX: u64 doesn't work with toml-rs!
+        pub fee_share: u64,
     }
 
     /// Validator pre-genesis configuration can be created with client utils
@@ -250,6 +260,56 @@ pub mod genesis_config {
         // Hashes of whitelisted txs array. `None` value or an empty array
         // disables whitelisting.
         pub tx_whitelist: Option<Vec<String>>,
+        // Maximum length of a storage key written by a transaction (in
+        // bytes, default: 1024).
+        // XXX: u64 doesn't work with toml-rs!
+        pub max_tx_key_bytes: Option<u64>,
+        // Maximum size of a storage value written by a transaction (in
+        // bytes, default: 1048576).
+        // XXX: u64 doesn't work with toml-rs!
+        pub max_tx_value_bytes: Option<u64>,
+        // Minimum gas prices of the tokens accepted for the fees of wrapper
+        // txs, keyed by the tokens' addresses (in micro units per unit of
+        // gas, default: only XAN without a minimum price).
+        // XXX: u64 doesn't work with toml-rs!
+        pub fee_tokens: Option<HashMap<String, u64>>,
+        // Parameters of the inflation controller (default: the defaults of
+        // the `InflationParams`).
+        pub inflation: Option<InflationParamsConfig>,
+    }
+
+    #[derive(Clone, Debug, Deserialize, Serialize)]
+    pub struct InflationParamsConfig {
+        // Ratio of the native token's supply that should be staked (in basis
+        // points).
+        // XXX: u64 doesn't work with toml-rs!
+        pub target_staked_ratio: u64,
+        // Minimum annual inflation rate (in basis points).
+        // XXX: u64 doesn't work with toml-rs!
+        pub min_rate: u64,
+        // Maximum annual inflation rate (in basis points).
+        // XXX: u64 doesn't work with toml-rs!
+        pub max_rate: u64,
+        // Maximum change of the annual inflation rate in one epoch (in basis
+        // points).
+        // XXX: u64 doesn't work with toml-rs!
+        pub max_rate_change: u64,
+    }
+
+    #[derive(Clone, Debug, Deserialize, Serialize)]
+    pub struct ConsensusParamsConfig {
+        // Maximum size of a block (in bytes).
+        // XXX: u64 doesn't work with toml-rs!
+        pub max_block_bytes: u64,
+        // Maximum gas of a block (default: unlimited).
+        // XXX: u64 doesn't work with toml-rs!
+        pub max_block_gas: Option<u64>,
+        // Maximum size of the evidence in a block (in bytes).
+        // XXX: u64 doesn't work with toml-rs!
+        pub max_evidence_bytes: u64,
+        // Public key types that the validators may use for their consensus
+        // keys.
+        pub validator_pub_key_types: Vec<String>,
     }
 
     #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -543,6 +603,43 @@ pub mod genesis_config {
             .into(),
             vp_whitelist: config.parameters.vp_whitelist.unwrap_or_default(),
             tx_whitelist: config.parameters.tx_whitelist.unwrap_or_default(),
+            tx_write_size_limits: SizeLimits {
+                max_key_bytes: config
+                    .parameters
+                    .max_tx_key_bytes
+                    .unwrap_or_else(|| SizeLimits::default().max_key_bytes),
+                max_value_bytes: config
+                    .parameters
+                    .max_tx_value_bytes
+                    .unwrap_or_else(|| SizeLimits::default().max_value_bytes),
+            },
+            fee_tokens: match config.parameters.fee_tokens {
+                Some(fee_tokens) => fee_tokens
+                    .iter()
+                    .map(|(token, min_gas_price)| {
+                        let token = Address::decode(token).unwrap_or_else(
+                            |err| {
+                                panic!(
+                                    "Invalid fee token address {}: {}",
+                                    token, err
+                                )
+                            },
+                        );
+                        (token, token::Amount::from(*min_gas_price))
+                    })
+                    .collect(),
+                None => parameters::default_fee_tokens(),
+            },
+            inflation_params: config
+                .parameters
+                .inflation
+                .map(|inflation| InflationParams {
+                    target_staked_ratio: inflation.target_staked_ratio,
+                    min_rate: inflation.min_rate,
+                    max_rate: inflation.max_rate,
+                    max_rate_change: inflation.max_rate_change,
+                })
+                .unwrap_or_default(),
         };
 
         let gov_params = GovParams {
@@ -558,7 +655,10 @@ pub mod genesis_config {
         };
 
         let treasury_params = TreasuryParams {
-            max_proposal_fund_transfer: 10_000,
+            max_proposal_fund_transfer: config
+                .treasury_params
+                .max_proposal_fund_transfer,
+            fee_share: config.treasury_params.fee_share,
         };
 
         let pos_params = PosParams {
@@ -578,6 +678,20 @@ pub mod genesis_config {
             ),
         };
 
+        let consensus_params = config
+            .consensus_params
+            .map(|config| ConsensusParams {
+                max_block_bytes: config.max_block_bytes,
+                max_block_gas: config.max_block_gas,
+                max_evidence_bytes: config.max_evidence_bytes,
+                validator_pub_key_types: config.validator_pub_key_types,
+            })
+            .unwrap_or_default();
+        if let Err(err) = consensus_params.validate() {
+            eprintln!("Invalid consensus parameters: {}", err);
+            cli::safe_exit(1);
+        }
+
         let mut genesis = Genesis {
             genesis_time: config.genesis_time.try_into().unwrap(),
             validators: validators.into_values().collect(),
@@ -588,6 +702,7 @@ pub mod genesis_config {
             pos_params,
             gov_params,
             treasury_params,
+            consensus_params,
         };
         genesis.init();
         genesis
@@ -623,6 +738,7 @@ pub struct Genesis {
     pub pos_params: PosParams,
     pub gov_params: GovParams,
     pub treasury_params: TreasuryParams,
+    pub consensus_params: ConsensusParams,
 }
 
 impl Genesis {
@@ -635,6 +751,63 @@ impl Genesis {
     }
 }
 
+/// Tendermint consensus parameters, which are given to Tendermint on the
+/// chain's initialization
+#[derive(Clone, Debug, BorshSerialize, BorshDeserialize, PartialEq, Eq)]
+pub struct ConsensusParams {
+    /// Maximum size of a block in bytes
+    pub max_block_bytes: u64,
+    /// Maximum gas of a block, unlimited when `None`
+    pub max_block_gas: Option<u64>,
+    /// Maximum size of the evidence in a block in bytes
+    pub max_evidence_bytes: u64,
+    /// Public key types that the validators may use for their consensus keys
+    pub validator_pub_key_types: Vec<String>,
+}
+
+impl ConsensusParams {
+    /// The public key type of the validators' consensus keys
+    pub const CONSENSUS_KEY_TYPE: &'static str = "ed25519";
+
+    /// Check that the parameters are accepted by Tendermint and that the
+    /// validators' consensus keys are allowed
+    pub fn validate(&self) -> Result<(), String> {
+        if self.max_block_bytes == 0 {
+            return Err("The maximum block size must be positive".into());
+        }
+        if self.max_evidence_bytes > self.max_block_bytes {
+            return Err(format!(
+                "The maximum evidence size {} is greater than the maximum \
+                 block size {}",
+                self.max_evidence_bytes, self.max_block_bytes
+            ));
+        }
+        if !self
+            .validator_pub_key_types
+            .iter()
+            .any(|key_type| key_type == Self::CONSENSUS_KEY_TYPE)
+        {
+            return Err(format!(
+                "The validator public key types must include \"{}\"",
+                Self::CONSENSUS_KEY_TYPE
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl Default for ConsensusParams {
+    /// Tendermint's default consensus parameters
+    fn default() -> Self {
+        Self {
+            max_block_bytes: 22020096,
+            max_block_gas: None,
+            max_evidence_bytes: 1048576,
+            validator_pub_key_types: vec![Self::CONSENSUS_KEY_TYPE.to_owned()],
+        }
+    }
+}
+
 #[derive(
     Clone,
     Debug,
@@ -731,7 +904,7 @@ pub fn genesis(base_dir: impl AsRef<Path>, chain_id: &ChainId) -> Genesis {
 }
 #[cfg(feature = "dev")]
 pub fn genesis() -> Genesis {
-    use anoma::ledger::parameters::EpochDuration;
+    use anoma::ledger::parameters::{self, EpochDuration};
     use anoma::types::address;
 
     use crate::wallet;
@@ -780,6 +953,9 @@ pub fn genesis() -> Genesis {
         max_expected_time_per_block: anoma::types::time::DurationSecs(30),
         vp_whitelist: vec![],
         tx_whitelist: vec![],
+        tx_write_size_limits: Default::default(),
+        fee_tokens: parameters::default_fee_tokens(),
+        inflation_params: Default::default(),
     };
     let albert = EstablishedAccount {
         address: wallet::defaults::albert_address(),
@@ -859,6 +1035,7 @@ pub fn genesis() -> Genesis {
         pos_params: PosParams::default(),
         gov_params: GovParams::default(),
         treasury_params: TreasuryParams::default(),
+        consensus_params: ConsensusParams::default(),
     }
 }
 
@@ -870,6 +1047,7 @@ pub mod tests {
     use rand::prelude::ThreadRng;
     use rand::thread_rng;
 
+    use super::ConsensusParams;
     use crate::wallet;
 
     /// Run `cargo test gen_genesis_validator -- --nocapture` to generate a
@@ -894,4 +1072,22 @@ pub mod tests {
         println!("protocol_keypair: {:?}", protocol_keypair);
         println!("dkg_keypair: {:?}", dkg_keypair.try_to_vec().unwrap());
     }
+
+    #[test]
+    fn test_consensus_params_validation() {
+        let params = ConsensusParams::default();
+        assert!(params.validate().is_ok());
+        assert!(ConsensusParams {
+            max_evidence_bytes: params.max_block_bytes + 1,
+            ..params.clone()
+        }
+        .validate()
+        .is_err());
+        assert!(ConsensusParams {
+            validator_pub_key_types: vec!["secp256k1".to_owned()],
+            ..params
+        }
+        .validate()
+        .is_err());
+    }
 }
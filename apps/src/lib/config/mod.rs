@@ -9,7 +9,7 @@ use std::fmt::Display;
 use std::fs::{create_dir_all, File};
 use std::io::Write;
 use std::net::{IpAddr, Ipv4Addr, SocketAddr};
-use std::path::{Path, PathBuf};
+use std::path::{Component, Path, PathBuf};
 use std::str::FromStr;
 
 use anoma::types::chain::ChainId;
@@ -44,6 +44,18 @@ pub const FILENAME: &str = "config.toml";
 pub const TENDERMINT_DIR: &str = "tendermint";
 /// Chain-specific Anoma DB. Nested in chain dirs.
 pub const DB_DIR: &str = "db";
+/// The ledger's transaction index sub-directory in the chain directory
+pub const TX_INDEX_DIR: &str = "tx_index";
+/// The name of the directory of the fresh DB used to replay blocks
+pub const REPLAY_DB_DIR: &str = "replay_db";
+/// The name of the directory of the secondary instance of the DB opened by a
+/// read-only node
+pub const READ_ONLY_DB_DIR: &str = "read_only_db";
+/// The prefix of the environment variables that override the config values
+pub const ENV_VAR_PREFIX: &str = "anoma";
+/// The separator of the nested keys in the environment variables that
+/// override the config values, e.g. `ANOMA_LEDGER__SHELL__LEDGER_ADDRESS`
+pub const ENV_VAR_SEPARATOR: &str = "__";
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Config {
@@ -93,7 +105,11 @@ pub struct Ledger {
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Shell {
     pub base_dir: PathBuf,
-    pub ledger_address: SocketAddr,
+    /// The address on which the ledger listens for ABCI connections from
+    /// Tendermint. Either a TCP address, e.g. `tcp://127.0.0.1:26658`, or a
+    /// Unix domain socket, e.g. `unix:///run/anoma/abci.sock`. The `tcp://`
+    /// prefix may be omitted.
+    pub ledger_address: TendermintAddress,
     /// RocksDB block cache maximum size in bytes.
     /// When not set, defaults to 1/3 of the available memory.
     pub block_cache_bytes: Option<u64>,
@@ -103,6 +119,65 @@ pub struct Shell {
     /// Tx WASM compilation in-memory cache maximum size in bytes.
     /// When not set, defaults to 1/6 of the available memory.
     pub tx_wasm_compilation_cache_bytes: Option<u64>,
+    /// In-memory cache of the values of the hot storage keys, i.e. the token
+    /// balances, the VPs and the protocol parameters, maximum size in bytes.
+    /// When not set, defaults to 64 MiB. The cache is disabled with 0.
+    #[serde(default)]
+    pub storage_read_cache_bytes: Option<u64>,
+    /// Forward the messages from WASM `log_string` calls to the node's log.
+    /// Useful for debugging WASM on a localnet.
+    #[serde(default)]
+    pub wasm_log: bool,
+    /// The address of the gRPC query server. The server is only available
+    /// with the "grpc" feature and it's not started when not set.
+    #[serde(default)]
+    pub grpc_address: Option<SocketAddr>,
+    /// The address of the WebSocket event stream server. It's not started
+    /// when not set.
+    #[serde(default)]
+    pub event_stream_address: Option<SocketAddr>,
+    /// The address of the HTTP status server for load balancers and
+    /// monitoring. It's not started when not set.
+    #[serde(default)]
+    pub status_address: Option<SocketAddr>,
+    /// Index the committed transactions by their addresses and changed
+    /// storage keys in a DB next to the ledger's DB, so that they can be
+    /// queried by the client.
+    #[serde(default)]
+    pub tx_index: bool,
+    /// The maximum size in bytes of a transaction accepted into the mempool.
+    /// When not set, defaults to 1 MiB.
+    #[serde(default)]
+    pub mempool_max_tx_bytes: Option<u64>,
+    /// The maximum number of pending wrapper transactions from the same fee
+    /// payer accepted into the mempool. When not set, defaults to 100.
+    #[serde(default)]
+    pub mempool_max_txs_per_sender: Option<u64>,
+    /// The number of the most recently rejected transactions kept with the
+    /// reasons of their rejection by the mempool or in a block, which are
+    /// served by the status server. When not set, defaults to 100. Only the
+    /// rejected transactions are counted with 0.
+    #[serde(default)]
+    pub rejected_txs_log_size: Option<u64>,
+    /// Sync the DB writes of a block to disk when it's committed. Otherwise,
+    /// the writes are flushed in the background and the last blocks may be
+    /// lost on a crash of the machine, in which case they're replayed by
+    /// Tendermint on restart.
+    #[serde(default)]
+    pub sync_commit: bool,
+    /// Execute the DB writes of a block in the background when it's committed,
+    /// so that its root is returned to Tendermint sooner. The next block waits
+    /// for the write and if it fails, the node shuts down and the block is
+    /// replayed by Tendermint on restart.
+    #[serde(default)]
+    pub async_commit: bool,
+    /// Run an archive node, which retains the state of every height instead
+    /// of pruning it, so that it can be queried with proofs at any height.
+    /// The DB grows faster and the historical proofs are slower to produce,
+    /// so it's intended for the operators of explorers and indexers. The
+    /// node should be synced from genesis in this mode.
+    #[serde(default)]
+    pub archive: bool,
     /// Use the [`Ledger::db_dir()`] method to read the value.
     db_dir: PathBuf,
     /// Use the [`Ledger::tendermint_dir()`] method to read the value.
@@ -130,6 +205,19 @@ pub struct Tendermint {
     pub instrumentation_prometheus: bool,
     pub instrumentation_prometheus_listen_addr: SocketAddr,
     pub instrumentation_namespace: String,
+    /// Don't launch the Tendermint node with the ledger. When set, the
+    /// Tendermint node has to be run separately against the ledger's address.
+    #[serde(default)]
+    pub external_tendermint: bool,
+    /// The path to the Tendermint binary. When not set, the `TENDERMINT`
+    /// environment variable is used if set, or otherwise `tendermint` from
+    /// the `PATH`.
+    #[serde(default)]
+    pub tendermint_path: Option<PathBuf>,
+    /// How many times the Tendermint node is restarted when it crashes,
+    /// before the ledger shuts down.
+    #[serde(default)]
+    pub max_restarts: u64,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -139,6 +227,9 @@ pub struct IntentGossiper {
     pub topics: HashSet<String>,
     /// The server address to which matchmakers can connect to receive intents
     pub matchmakers_server_addr: SocketAddr,
+    /// Intents older than this are dropped from the mempool and are not
+    /// propagated to peers
+    pub intent_expiry: Timeout,
 
     // Nested structures ⚠️ no simple values below any of these ⚠️
     pub subscription_filter: SubscriptionFilter,
@@ -158,6 +249,16 @@ pub struct Matchmaker {
     pub tx_code_path: Option<PathBuf>,
 }
 
+impl Shell {
+    /// Set the port of the ledger's ABCI address. Has no effect when the
+    /// ledger listens on a Unix domain socket.
+    pub fn set_ledger_port(&mut self, new_port: u16) {
+        if let TendermintAddress::Tcp { port, .. } = &mut self.ledger_address {
+            *port = new_port;
+        }
+    }
+}
+
 impl Ledger {
     pub fn new(
         base_dir: impl AsRef<Path>,
@@ -169,13 +270,26 @@ impl Ledger {
             chain_id,
             shell: Shell {
                 base_dir: base_dir.as_ref().to_owned(),
-                ledger_address: SocketAddr::new(
-                    IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)),
-                    26658,
-                ),
+                ledger_address: TendermintAddress::Tcp {
+                    peer_id: None,
+                    host: "127.0.0.1".to_owned(),
+                    port: 26658,
+                },
                 block_cache_bytes: None,
                 vp_wasm_compilation_cache_bytes: None,
                 tx_wasm_compilation_cache_bytes: None,
+                storage_read_cache_bytes: None,
+                wasm_log: false,
+                grpc_address: None,
+                event_stream_address: None,
+                status_address: None,
+                tx_index: false,
+                mempool_max_tx_bytes: None,
+                mempool_max_txs_per_sender: None,
+                rejected_txs_log_size: None,
+                sync_commit: false,
+                async_commit: false,
+                archive: false,
                 db_dir: DB_DIR.into(),
                 tendermint_dir: TENDERMINT_DIR.into(),
             },
@@ -200,6 +314,9 @@ impl Ledger {
                     26661,
                 ),
                 instrumentation_namespace: "anoman_tm".to_string(),
+                external_tendermint: false,
+                tendermint_path: None,
+                max_restarts: 3,
             },
         }
     }
@@ -218,6 +335,32 @@ impl Ledger {
     pub fn tendermint_dir(&self) -> PathBuf {
         self.shell.tendermint_dir(&self.chain_id)
     }
+
+    /// Get the directory path to the transaction index
+    pub fn tx_index_dir(&self) -> PathBuf {
+        self.shell.tx_index_dir(&self.chain_id)
+    }
+
+    /// Get the directory path to the secondary instance of the DB opened by
+    /// a read-only node
+    pub fn read_only_db_dir(&self) -> PathBuf {
+        self.chain_dir().join(READ_ONLY_DB_DIR)
+    }
+
+    /// Get a copy of this config for replaying the chain's blocks into a
+    /// fresh DB in the [`REPLAY_DB_DIR`] of the chain directory. The replay
+    /// runs as a full node without the optional servers and the transaction
+    /// index, so that it doesn't touch the node's own state.
+    pub fn for_replay(&self) -> Self {
+        let mut config = self.clone();
+        config.shell.db_dir = REPLAY_DB_DIR.into();
+        config.shell.tx_index = false;
+        config.shell.grpc_address = None;
+        config.shell.event_stream_address = None;
+        config.shell.status_address = None;
+        config.tendermint.tendermint_mode = TendermintMode::Full;
+        config
+    }
 }
 
 impl Shell {
@@ -232,6 +375,30 @@ impl Shell {
             .join(chain_id.as_str())
             .join(&self.tendermint_dir)
     }
+
+    /// Get the directory path to the transaction index
+    pub fn tx_index_dir(&self, chain_id: &ChainId) -> PathBuf {
+        self.base_dir.join(chain_id.as_str()).join(TX_INDEX_DIR)
+    }
+
+    /// Check that the DB and Tendermint directories are nested inside the
+    /// chain directory, so that the state of different chains in the same
+    /// base directory cannot be mixed up.
+    fn validate_dirs(&self) -> Result<()> {
+        for (key, dir) in [
+            ("ledger.shell.db_dir", &self.db_dir),
+            ("ledger.shell.tendermint_dir", &self.tendermint_dir),
+        ] {
+            let is_nested = dir.components().next().is_some()
+                && dir
+                    .components()
+                    .all(|component| matches!(component, Component::Normal(_)));
+            if !is_nested {
+                return Err(Error::NotInChainDir(key, dir.clone()));
+            }
+        }
+        Ok(())
+    }
 }
 
 // TODO maybe add also maxCount for a maximum number of subscription for a
@@ -283,10 +450,45 @@ pub enum Error {
          {{protocol}}/{{ip}}/tcp/{{port}}/p2p/{{peerid}}"
     )]
     BadBootstrapPeerFormat(String),
+    #[error(
+        "The config key {0} must be a relative path inside the chain \
+         directory, got {1}"
+    )]
+    NotInChainDir(&'static str, PathBuf),
+    #[error("Unknown config key {0}")]
+    UnknownKey(String),
+    #[error("Invalid value of the config key {0}: {1}")]
+    InvalidValue(String, config::ConfigError),
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
 
+/// An override of a config value given as `key=value`, where the key is the
+/// dot-separated path of the value in the config file, e.g.
+/// `ledger.shell.ledger_address=127.0.0.1:26658`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConfigOverride {
+    pub key: String,
+    pub value: String,
+}
+
+impl FromStr for ConfigOverride {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.split_once('=') {
+            Some((key, value)) if !key.is_empty() => Ok(Self {
+                key: key.to_owned(),
+                value: value.to_owned(),
+            }),
+            _ => Err(format!(
+                "Invalid config override \"{}\", expected \"key=value\"",
+                s
+            )),
+        }
+    }
+}
+
 #[derive(Error, Debug)]
 pub enum SerdeError {
     // This is needed for serde https://serde.rs/error-handling.html
@@ -320,9 +522,19 @@ impl Config {
         base_dir: impl AsRef<Path>,
         chain_id: &ChainId,
         mode: Option<TendermintMode>,
+    ) -> Self {
+        Self::load_with_overrides(base_dir, chain_id, mode, &[])
+    }
+
+    /// Load config as with [`Config::load`] and apply the given overrides.
+    pub fn load_with_overrides(
+        base_dir: impl AsRef<Path>,
+        chain_id: &ChainId,
+        mode: Option<TendermintMode>,
+        overrides: &[ConfigOverride],
     ) -> Self {
         let base_dir = base_dir.as_ref();
-        match Self::read(base_dir, chain_id, mode) {
+        match Self::read(base_dir, chain_id, mode, overrides) {
             Ok(mut config) => {
                 config.ledger.shell.base_dir = base_dir.to_path_buf();
                 config
@@ -341,34 +553,95 @@ impl Config {
     /// Read the config from a file, or generate a default one and write it to
     /// a file if it doesn't already exist. Keys that are expected but not set
     /// in the config file are filled in with default values.
+    ///
+    /// The values are layered with the following precedence, from the lowest:
+    /// 1. the default values
+    /// 2. the config file
+    /// 3. the `ANOMA_*` environment variables with `__` separated nested keys,
+    ///    e.g. `ANOMA_LEDGER__SHELL__DB_DIR`
+    /// 4. the given overrides, e.g. from the CLI
     pub fn read(
         base_dir: &Path,
         chain_id: &ChainId,
         mode: Option<TendermintMode>,
+        overrides: &[ConfigOverride],
     ) -> Result<Self> {
         let file_path = Self::file_path(base_dir, chain_id);
         let file_name = file_path.to_str().expect("Expected UTF-8 file path");
         let mode = mode.unwrap_or(TendermintMode::Full);
         if !file_path.exists() {
-            return Self::generate(base_dir, chain_id, mode, true);
+            Self::generate(base_dir, chain_id, mode.clone(), true)?;
         };
-        let defaults = config::Config::try_from(&Self::new(
-            base_dir,
-            chain_id.clone(),
-            mode,
-        ))
-        .map_err(Error::ReadError)?;
+        let defaults = Self::new(base_dir, chain_id.clone(), mode);
         let mut config = config::Config::new();
         config
-            .merge(defaults)
+            .merge(
+                config::Config::try_from(&defaults)
+                    .map_err(Error::ReadError)?,
+            )
             .and_then(|c| c.merge(config::File::with_name(file_name)))
-            .and_then(|c| {
-                c.merge(
-                    config::Environment::with_prefix("anoma").separator("__"),
-                )
-            })
             .map_err(Error::ReadError)?;
-        config.try_into().map_err(Error::DeserializationError)
+        // Keep the config without the overrides to find any invalid override
+        let base = config.clone();
+        config
+            .merge(
+                config::Environment::with_prefix(ENV_VAR_PREFIX)
+                    .separator(ENV_VAR_SEPARATOR),
+            )
+            .map_err(Error::ReadError)?;
+        for ConfigOverride { key, value } in overrides {
+            if !defaults.has_key(key) {
+                return Err(Error::UnknownKey(key.clone()));
+            }
+            config
+                .set(key, value.as_str())
+                .map_err(|err| Error::InvalidValue(key.clone(), err))?;
+        }
+        let config: Self = config
+            .try_into()
+            .map_err(|err| Self::find_invalid_override(base, overrides, err))?;
+        config.ledger.shell.validate_dirs()?;
+        Ok(config)
+    }
+
+    /// Check if the dot-separated key is a path of a value in the config.
+    fn has_key(&self, key: &str) -> bool {
+        let value = serde_json::to_value(self)
+            .expect("Serializing the config shouldn't fail");
+        key.split('.')
+            .try_fold(&value, |value, key| value.get(key))
+            .is_some()
+    }
+
+    /// Find which of the environment variables or the given overrides has an
+    /// invalid value, by applying them one by one on top of the `base` config.
+    /// Falls back to the given deserialization error, if none is found.
+    fn find_invalid_override(
+        base: config::Config,
+        overrides: &[ConfigOverride],
+        err: config::ConfigError,
+    ) -> Error {
+        use config::Source;
+
+        let env_overrides = config::Environment::with_prefix(ENV_VAR_PREFIX)
+            .separator(ENV_VAR_SEPARATOR)
+            .collect()
+            .unwrap_or_default();
+        let overrides = env_overrides.into_iter().chain(overrides.iter().map(
+            |ConfigOverride { key, value }| {
+                (key.clone(), config::Value::from(value.as_str()))
+            },
+        ));
+        for (key, value) in overrides {
+            let mut config = base.clone();
+            let res = config
+                .set(&key, value)
+                .and_then(|config| config.clone().try_into::<Self>());
+            if let Err(err) = res {
+                return Error::InvalidValue(key, err);
+            }
+        }
+        Error::DeserializationError(err)
     }
 
     /// Generate configuration and write it to a file.
@@ -427,6 +700,7 @@ impl Default for IntentGossiper {
                 IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)),
                 26661,
             ),
+            intent_expiry: Timeout::from_str("3600s").unwrap(),
             subscription_filter: SubscriptionFilter::RegexFilter(
                 Regex::new("asset_v\\d{1,2}").unwrap(),
             ),
@@ -531,3 +805,72 @@ And this is correct
        nested:Nested,
     }
 "#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_config_overrides() {
+        let base_dir = tempfile::tempdir().unwrap();
+        let chain_id = ChainId::default();
+        let read = |overrides: &[&str]| {
+            let overrides: Vec<ConfigOverride> =
+                overrides.iter().map(|raw| raw.parse().unwrap()).collect();
+            Config::read(base_dir.path(), &chain_id, None, &overrides)
+        };
+
+        let config = read(&[
+            "ledger.shell.ledger_address=127.0.0.1:30000",
+            "ledger.tendermint.max_restarts=10",
+        ])
+        .unwrap();
+        assert!(matches!(
+            config.ledger.shell.ledger_address,
+            TendermintAddress::Tcp { port: 30000, .. }
+        ));
+        assert_eq!(config.ledger.tendermint.max_restarts, 10);
+
+        assert!(matches!(
+            read(&["ledger.shell.unknown=1"]),
+            Err(Error::UnknownKey(key)) if key == "ledger.shell.unknown"
+        ));
+        assert!(matches!(
+            read(&["ledger.shell.ledger_address=invalid"]),
+            Err(Error::InvalidValue(key, _))
+                if key == "ledger.shell.ledger_address"
+        ));
+        let config =
+            read(&["ledger.shell.ledger_address=unix:///tmp/abci.sock"])
+                .unwrap();
+        assert!(matches!(
+            config.ledger.shell.ledger_address,
+            TendermintAddress::Unix { path } if path == "/tmp/abci.sock"
+        ));
+        assert!("no_value".parse::<ConfigOverride>().is_err());
+        assert!(matches!(
+            read(&["ledger.shell.db_dir=../db"]),
+            Err(Error::NotInChainDir("ledger.shell.db_dir", _))
+        ));
+        assert!(matches!(
+            read(&["ledger.shell.tendermint_dir=/tendermint"]),
+            Err(Error::NotInChainDir("ledger.shell.tendermint_dir", _))
+        ));
+    }
+
+    #[test]
+    fn test_config_for_replay() {
+        let mut config =
+            Ledger::new("base", ChainId::default(), TendermintMode::Validator);
+        config.shell.tx_index = true;
+        let replay = config.for_replay();
+        assert_ne!(replay.db_dir(), config.db_dir());
+        assert_eq!(replay.db_dir(), config.chain_dir().join(REPLAY_DB_DIR));
+        assert_eq!(replay.tendermint_dir(), config.tendermint_dir());
+        assert!(!replay.shell.tx_index);
+        assert!(matches!(
+            replay.tendermint.tendermint_mode,
+            TendermintMode::Full
+        ));
+    }
+}
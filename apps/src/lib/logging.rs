@@ -1,17 +1,30 @@
 //! A module for anything related to logging
-use std::env;
+use std::{env, fmt};
 
+use anoma::types::time::Utc;
 use color_eyre::eyre::Result;
 use eyre::WrapErr;
+use tracing::field::{Field, Visit};
+use tracing::Event;
 use tracing_log::LogTracer;
 use tracing_subscriber::filter::{Directive, EnvFilter};
-use tracing_subscriber::fmt::Subscriber;
+use tracing_subscriber::fmt::format::{self, FormatEvent, FormatFields};
+use tracing_subscriber::fmt::{FmtContext, FormattedFields, Subscriber};
+use tracing_subscriber::registry::LookupSpan;
 
 pub const ENV_KEY: &str = "ANOMA_LOG";
 
+/// The global CLI arg to set the log filter, which takes precedence over the
+/// [`ENV_KEY`] env var
+pub const ARG_KEY: &str = "log-level";
+
 // Env var to enable/disable color log
 const COLOR_ENV_KEY: &str = "ANOMA_LOG_COLOR";
 
+// Env var to select the log format. With "json", every log line is a JSON
+// object, which is useful for log aggregation.
+const FORMAT_ENV_KEY: &str = "ANOMA_LOG_FMT";
+
 pub fn init_from_env_or(default: impl Into<Directive>) -> Result<()> {
     let filter = filter_from_env_or(default);
     set_subscriber(filter)?;
@@ -19,9 +32,26 @@ pub fn init_from_env_or(default: impl Into<Directive>) -> Result<()> {
 }
 
 pub fn filter_from_env_or(default: impl Into<Directive>) -> EnvFilter {
-    env::var(ENV_KEY)
+    filter_from_args(env::args())
+        .or_else(|| env::var(ENV_KEY).ok())
         .map(EnvFilter::new)
-        .unwrap_or_else(|_| EnvFilter::default().add_directive(default.into()))
+        .unwrap_or_else(|| EnvFilter::default().add_directive(default.into()))
+}
+
+/// Find the log filter set with the [`ARG_KEY`] arg, if any. The logging is
+/// set up before the CLI is parsed, so the arg is looked up in the raw args.
+fn filter_from_args(args: impl IntoIterator<Item = String>) -> Option<String> {
+    let flag = format!("--{}", ARG_KEY);
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        if arg == flag {
+            return args.next();
+        }
+        if let Some(value) = arg.strip_prefix(&format!("{}=", flag)) {
+            return Some(value.to_owned());
+        }
+    }
+    None
 }
 
 pub fn set_subscriber(filter: EnvFilter) -> Result<()> {
@@ -30,15 +60,97 @@ pub fn set_subscriber(filter: EnvFilter) -> Result<()> {
     } else {
         true
     };
+    let with_json = if let Ok(val) = env::var(FORMAT_ENV_KEY) {
+        val.to_ascii_lowercase() == "json"
+    } else {
+        false
+    };
 
-    let my_collector = Subscriber::builder()
-        .with_ansi(with_color)
-        .with_env_filter(filter)
-        .finish();
-    tracing::subscriber::set_global_default(my_collector)
-        .wrap_err("Failed to set log subscriber")
+    let builder = Subscriber::builder().with_env_filter(filter);
+    let result = if with_json {
+        let my_collector =
+            builder.with_ansi(false).event_format(JsonFormat).finish();
+        tracing::subscriber::set_global_default(my_collector)
+    } else {
+        let my_collector = builder.with_ansi(with_color).finish();
+        tracing::subscriber::set_global_default(my_collector)
+    };
+    result.wrap_err("Failed to set log subscriber")
 }
 
 pub fn init_log_tracer() -> Result<()> {
     LogTracer::init().wrap_err("Failed to initialize log adapter")
 }
+
+/// Formats the events as JSON objects with the timestamp, level, target, the
+/// event's fields and the names and fields of the spans in which the event
+/// occurred, from the root span.
+struct JsonFormat;
+
+impl<S, N> FormatEvent<S, N> for JsonFormat
+where
+    S: tracing::Subscriber + for<'a> LookupSpan<'a>,
+    N: for<'a> FormatFields<'a> + 'static,
+{
+    fn format_event(
+        &self,
+        ctx: &FmtContext<'_, S, N>,
+        mut writer: format::Writer<'_>,
+        event: &Event<'_>,
+    ) -> fmt::Result {
+        let metadata = event.metadata();
+        let mut fields = JsonFields::default();
+        event.record(&mut fields);
+        let spans: Vec<serde_json::Value> = ctx
+            .event_scope()
+            .into_iter()
+            .flat_map(|scope| scope.from_root())
+            .map(|span| {
+                let extensions = span.extensions();
+                let fields = extensions
+                    .get::<FormattedFields<N>>()
+                    .map(|fields| fields.to_string())
+                    .unwrap_or_default();
+                serde_json::json!({
+                    "name": span.name(),
+                    "fields": fields,
+                })
+            })
+            .collect();
+        let line = serde_json::json!({
+            "timestamp": Utc::now().to_rfc3339(),
+            "level": metadata.level().to_string(),
+            "target": metadata.target(),
+            "spans": spans,
+            "fields": fields.0,
+        });
+        writeln!(writer, "{}", line)
+    }
+}
+
+/// Collects the fields of an event into a JSON map
+#[derive(Default)]
+struct JsonFields(serde_json::Map<String, serde_json::Value>);
+
+impl Visit for JsonFields {
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.0.insert(field.name().to_owned(), value.into());
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.0.insert(field.name().to_owned(), value.into());
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.0.insert(field.name().to_owned(), value.into());
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.0.insert(field.name().to_owned(), value.into());
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.0
+            .insert(field.name().to_owned(), format!("{:?}", value).into());
+    }
+}
@@ -1,9 +1,10 @@
 use std::net::ToSocketAddrs;
 use std::sync::{Arc, RwLock};
+use std::time::Duration;
 
 use anoma::proto::{Intent, IntentId};
 
-use super::mempool::IntentMempool;
+use super::mempool::{self, IntentMempool};
 use super::rpc::matchmakers::{
     MsgFromClient, MsgFromServer, ServerDialer, ServerListener,
 };
@@ -27,12 +28,16 @@ pub struct IntentGossiper {
     mempool: Arc<RwLock<IntentMempool>>,
     /// A dialer can send messages to the connected matchmaker
     dialer: ServerDialer,
+    /// Intents older than this are not added to the mempool and are pruned
+    /// from it
+    intent_expiry: Duration,
 }
 
 impl MatchmakersServer {
     /// Create a new gossip intent app with a matchmaker, if enabled.
     pub fn new_pair(
         matchmakers_server_addr: impl ToSocketAddrs,
+        intent_expiry: Duration,
     ) -> (Self, IntentGossiper) {
         // Prepare a server for matchmakers connections
         let (listener, dialer) =
@@ -42,6 +47,7 @@ impl MatchmakersServer {
         let intent_gossiper = IntentGossiper {
             mempool: mempool.clone(),
             dialer,
+            intent_expiry,
         };
         (
             Self {
@@ -92,6 +98,12 @@ impl MatchmakersServer {
                     let id = IntentId(id);
                     tracing::info!("No match found for intent ID {}", id);
                 }
+                MsgFromClient::SetFilter { filter } => {
+                    tracing::info!(
+                        "A connected matchmaker set an intent filter {:?}",
+                        filter
+                    );
+                }
             })
             .await
     }
@@ -102,6 +114,10 @@ impl IntentGossiper {
     // this one exists. If no matchmaker then returns true.
     pub async fn add_intent(&mut self, intent: Intent) {
         let id = intent.id();
+        if mempool::is_expired(&intent, self.intent_expiry) {
+            tracing::info!("Ignoring expired intent ID {}", id);
+            return;
+        }
 
         let r_mempool = self.mempool.read().unwrap();
         let is_known = r_mempool.contains(&id);
@@ -120,4 +136,12 @@ impl IntentGossiper {
             data: intent.data,
         })
     }
+
+    /// Remove the expired intents from the mempool.
+    pub fn prune_expired_intents(&mut self) {
+        let mut w_mempool = self.mempool.write().unwrap();
+        for id in w_mempool.prune_expired(self.intent_expiry) {
+            tracing::info!("Removed expired intent ID {}", id);
+        }
+    }
 }
@@ -1,6 +1,8 @@
 use std::collections::HashMap;
+use std::time::Duration;
 
 use anoma::proto::{Intent, IntentId};
+use anoma::types::time::{duration_passed, DateTimeUtc, DurationSecs};
 
 /// In-memory intent mempool
 #[derive(Clone, Debug, Default)]
@@ -23,4 +25,55 @@ impl IntentMempool {
     pub fn contains(&self, intent_id: &IntentId) -> bool {
         self.0.contains_key(intent_id)
     }
+
+    /// Remove the intents that are older than the given expiry duration.
+    /// Returns the IDs of the removed intents.
+    pub fn prune_expired(&mut self, expiry: Duration) -> Vec<IntentId> {
+        let now = DateTimeUtc::now();
+        let expired: Vec<IntentId> = self
+            .0
+            .iter()
+            .filter(|(_id, intent)| is_expired_at(intent, expiry, now))
+            .map(|(id, _intent)| id.clone())
+            .collect();
+        for id in &expired {
+            self.0.remove(id);
+        }
+        expired
+    }
+}
+
+/// Returns `true` if the intent is older than the given expiry duration.
+pub fn is_expired(intent: &Intent, expiry: Duration) -> bool {
+    is_expired_at(intent, expiry, DateTimeUtc::now())
+}
+
+fn is_expired_at(intent: &Intent, expiry: Duration, now: DateTimeUtc) -> bool {
+    duration_passed(now, intent.timestamp, DurationSecs::from(expiry))
+}
+
+#[cfg(test)]
+mod tests {
+    use anoma::types::time;
+
+    use super::*;
+
+    #[test]
+    fn test_prune_expired() {
+        let expiry = Duration::from_secs(60);
+        let mut mempool = IntentMempool::default();
+
+        let fresh = Intent::new(b"fresh".to_vec());
+        let mut stale = Intent::new(b"stale".to_vec());
+        stale.timestamp = stale.timestamp - time::Duration::seconds(61);
+        assert!(!is_expired(&fresh, expiry));
+        assert!(is_expired(&stale, expiry));
+
+        mempool.insert(fresh.clone());
+        mempool.insert(stale.clone());
+        let pruned = mempool.prune_expired(expiry);
+        assert_eq!(pruned, vec![stale.id()]);
+        assert!(mempool.contains(&fresh.id()));
+        assert!(!mempool.contains(&stale.id()));
+    }
 }
@@ -4,6 +4,7 @@ pub mod p2p;
 pub mod rpc;
 
 use std::path::Path;
+use std::time::Duration;
 
 use anoma::proto::Intent;
 use thiserror::Error;
@@ -22,6 +23,9 @@ pub enum Error {
 
 type Result<T> = std::result::Result<T, Error>;
 
+/// How often the expired intents are pruned from the mempool
+const PRUNE_EXPIRED_INTENTS_INTERVAL: Duration = Duration::from_secs(60);
+
 /// RPC async receiver end of the channel
 pub type RpcReceiver = tokio::sync::mpsc::Receiver<(
     rpc_message::Message,
@@ -37,6 +41,7 @@ pub async fn run(
     let (matchmakers_server, intent_gossiper) =
         intent_gossiper::MatchmakersServer::new_pair(
             &config.matchmakers_server_addr,
+            Duration::from(config.intent_expiry),
         );
 
     // Async channel for intents received from peer
@@ -83,6 +88,8 @@ pub async fn dispatcher(
     mut intent_gossiper: IntentGossiper,
     _mms_join_handle: tokio::task::JoinHandle<()>,
 ) -> Result<()> {
+    let mut prune_interval =
+        tokio::time::interval(PRUNE_EXPIRED_INTENTS_INTERVAL);
     loop {
         tokio::select! {
             Some((event, inject_response)) = recv_rpc_option(rpc_receiver.as_mut()), if rpc_receiver.is_some() =>
@@ -98,6 +105,9 @@ pub async fn dispatcher(
             Some(intent) = peer_intent_recv.recv() => {
                 intent_gossiper.add_intent(intent).await;
             }
+            _ = prune_interval.tick() => {
+                intent_gossiper.prune_expired_intents();
+            }
             swarm_event = p2p.0.next() => {
                 // Never occurs, but call for the event must exists.
                 tracing::info!("event, {:?}", swarm_event);
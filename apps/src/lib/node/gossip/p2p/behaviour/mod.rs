@@ -24,6 +24,7 @@ use tokio::sync::mpsc::Sender;
 
 use self::discovery::DiscoveryEvent;
 use crate::config;
+use crate::node::gossip::mempool;
 use crate::node::gossip::p2p::behaviour::discovery::{
     DiscoveryBehaviour, DiscoveryConfigBuilder,
 };
@@ -42,6 +43,9 @@ pub struct Behaviour {
     ping: Ping,
     #[behaviour(ignore)]
     pub peer_intent_send: Sender<Intent>,
+    /// Intents older than this are ignored and not propagated
+    #[behaviour(ignore)]
+    intent_expiry: Duration,
 }
 
 #[derive(Error, Debug)]
@@ -246,12 +250,17 @@ impl Behaviour {
             )),
             ping: Ping::default(),
             peer_intent_send,
+            intent_expiry: Duration::from(config.intent_expiry),
         }
     }
 
     /// tries to apply a new intent. Fails if the logic fails or if the intent
     /// is rejected. If the matchmaker fails the message is only ignore
     fn handle_intent(&mut self, intent: Intent) -> MessageAcceptance {
+        if mempool::is_expired(&intent, self.intent_expiry) {
+            tracing::info!("Expired intent ID {}, ignoring it", intent.id());
+            return MessageAcceptance::Ignore;
+        }
         if let Err(err) = self.peer_intent_send.try_send(intent) {
             tracing::error!("Error sending intent to the matchmaker: {}", err);
             // The buffer is full or the channel is closed
@@ -14,7 +14,9 @@ use crate::proto::services::rpc_service_server::{
     RpcService, RpcServiceServer,
 };
 use crate::proto::services::{rpc_message, RpcMessage, RpcResponse};
-use crate::proto::{IntentMessage, SubscribeTopicMessage};
+use crate::proto::{
+    IntentMessage, SubscribeTopicMessage, UnsubscribeTopicMessage,
+};
 
 #[derive(Debug)]
 struct Rpc {
@@ -162,5 +164,34 @@ pub async fn handle_rpc_event(
                 None,
             )
         }
+        rpc_message::Message::UnsubscribeTopic(topic_message) => {
+            let topic = UnsubscribeTopicMessage::from(topic_message);
+            let topic = IdentTopic::new(&topic.topic);
+            (
+                match gossip_sub.unsubscribe(&topic) {
+                    Ok(true) => {
+                        let result =
+                            format!("Node unsubscribed from {}", topic);
+                        tracing::info!("{}", result);
+                        RpcResponse { result }
+                    }
+                    Ok(false) => {
+                        let result =
+                            format!("Node was not subscribed to {}", topic);
+                        tracing::info!("{}", result);
+                        RpcResponse { result }
+                    }
+                    Err(err) => {
+                        let result = format!(
+                            "failed to unsubscribe from {}: {:?}",
+                            topic, err
+                        );
+                        tracing::error!("{}", result);
+                        RpcResponse { result }
+                    }
+                },
+                None,
+            )
+        }
     }
 }
@@ -4,12 +4,13 @@
 //! Both the server and the client can asynchronously listen for new messages
 //! and send messages to the other side.
 
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 use std::fmt::Debug;
 use std::net::{SocketAddr, ToSocketAddrs};
 use std::sync::atomic::{self, AtomicBool};
 use std::sync::{Arc, RwLock};
 
+use anoma::types::intent::IntentFilter;
 use borsh::{BorshDeserialize, BorshSerialize};
 use derivative::Derivative;
 use message_io::network::{Endpoint, ResourceId, ToRemoteAddr, Transport};
@@ -38,6 +39,8 @@ pub enum MsgFromClient {
     Matched { intent_ids: HashSet<Vec<u8>> },
     /// An intent was accepted and added, but no match found yet. Gossip it
     Unmatched { id: Vec<u8> },
+    /// Only send the intents that pass the given filter to this matchmaker
+    SetFilter { filter: IntentFilter },
 }
 
 /// Intent gossiper server listener handles connections from [`ClientDialer`]s.
@@ -46,8 +49,9 @@ pub enum MsgFromClient {
 pub struct ServerListener {
     /// The address on which the server is listening
     pub address: SocketAddr,
-    /// The accepted client connections, shared with the [`ServerDialer`]
-    clients: Arc<RwLock<HashSet<Endpoint>>>,
+    /// The accepted client connections with their intent filters, shared with
+    /// the [`ServerDialer`]
+    clients: Arc<RwLock<HashMap<Endpoint, IntentFilter>>>,
     /// A node listener and its abort receiver. These are consumed once the
     /// listener is started with [`ServerListener::listen`].
     #[derivative(Debug = "ignore")]
@@ -64,8 +68,9 @@ pub struct ServerDialer {
     handler: NodeHandler<()>,
     /// Connection resource ID
     resource_id: ResourceId,
-    /// The accepted client connections, shared with the [`ServerListener`]
-    clients: Arc<RwLock<HashSet<Endpoint>>>,
+    /// The accepted client connections with their intent filters, shared with
+    /// the [`ServerListener`]
+    clients: Arc<RwLock<HashMap<Endpoint, IntentFilter>>>,
     /// A message to abort the server must be sent to stop the
     /// [`ServerListener`]. This message will be sent on [`ServerDialer`]'s
     /// `drop` call.
@@ -125,7 +130,8 @@ impl ServerListener {
     /// a dialer that can be used to send messages to clients and to shut down
     /// the server.
     pub fn new_pair(address: impl ToSocketAddrs) -> (Self, ServerDialer) {
-        let clients: Arc<RwLock<HashSet<Endpoint>>> = Default::default();
+        let clients: Arc<RwLock<HashMap<Endpoint, IntentFilter>>> =
+            Default::default();
         let (handler, listener) = node::split::<()>();
 
         let (resource_id, address) = match handler
@@ -235,11 +241,26 @@ impl ServerListener {
                                 endpoint,
                                 msg
                             );
+                            if let MsgFromClient::SetFilter { filter } = &msg {
+                                let mut clients = self.clients.write().unwrap();
+                                match clients.get_mut(&endpoint) {
+                                    Some(client_filter) => {
+                                        *client_filter = filter.clone();
+                                    }
+                                    None => tracing::warn!(
+                                        "Filter from unknown matchmaker {}",
+                                        endpoint
+                                    ),
+                                }
+                            }
                             on_msg(msg);
                         }
                         ServerEvent::Accepted(endpoint, _id) => {
                             let mut clients = self.clients.write().unwrap();
-                            if !clients.insert(endpoint) {
+                            if clients
+                                .insert(endpoint, IntentFilter::default())
+                                .is_some()
+                            {
                                 tracing::warn!(
                                     "Accepted matchmaker already known {}",
                                     endpoint
@@ -248,7 +269,7 @@ impl ServerListener {
                         }
                         ServerEvent::Disconnected(endpoint) => {
                             let mut clients = self.clients.write().unwrap();
-                            if !clients.remove(&endpoint) {
+                            if clients.remove(&endpoint).is_none() {
                                 tracing::warn!(
                                     "Disconnected matchmaker unknown endpoint {}",
                                     endpoint
@@ -263,10 +284,22 @@ impl ServerListener {
 }
 
 impl ServerDialer {
-    /// Broadcast a message to all connected matchmaker clients
+    /// Broadcast a message to all connected matchmaker clients. An intent is
+    /// only sent to the clients whose filter it passes.
     pub fn send(&mut self, msg: MsgFromServer) {
         let net = self.handler.network();
-        for client in self.clients.read().unwrap().iter() {
+        for (client, filter) in self.clients.read().unwrap().iter() {
+            match &msg {
+                MsgFromServer::AddIntent { data, .. } => {
+                    if !filter.matches(data) {
+                        tracing::debug!(
+                            "Intent filtered out for matchmaker {}",
+                            client
+                        );
+                        continue;
+                    }
+                }
+            }
             let msg_bytes = msg.try_to_vec().unwrap();
             let status = net.send(*client, &msg_bytes);
             tracing::info!(
@@ -629,7 +662,7 @@ mod test {
                         // ... wait for the client to connect ...
                         while !dialer.is_connected() {}
                         // ... and for the server to accept it
-                        while !server.dialer.clients.read().unwrap().iter().any(
+                        while !server.dialer.clients.read().unwrap().keys().any(
                             |client| {
                                 // Client's address is added once it's accepted
                                 client.addr() == dialer.local_addr
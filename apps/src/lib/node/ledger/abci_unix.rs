@@ -0,0 +1,145 @@
+//! An ABCI server listening on a Unix domain socket, for a Tendermint node
+//! running on the same machine as the ledger. Tendermint opens a separate
+//! connection for each of its consensus, mempool, info and snapshot
+//! components. The requests of every connection are read and answered in
+//! order and they are all forwarded to the same ABCI service.
+
+use std::io;
+use std::path::Path;
+
+use prost::Message;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio::net::UnixListener;
+use tower::{Service, ServiceExt};
+#[cfg(not(feature = "ABCI"))]
+use tower_abci::{BoxError, Request as Req, Response as Resp};
+#[cfg(feature = "ABCI")]
+use tower_abci_old::{BoxError, Request as Req, Response as Resp};
+
+use super::shims::abcipp_shim::AbciService;
+
+/// The maximum number of bytes of a varint encoded `u64`
+const MAX_VARINT_LEN: usize = 10;
+
+/// Listen on the Unix domain socket at the given path and serve the ABCI
+/// connections with the given service. A stale socket file left behind by a
+/// previous run is removed first.
+pub async fn listen(
+    abci_service: AbciService,
+    path: impl AsRef<Path>,
+) -> io::Result<()> {
+    let path = path.as_ref();
+    match std::fs::remove_file(path) {
+        Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+        res => res?,
+    }
+    let listener = UnixListener::bind(path)?;
+    tracing::info!("ABCI server listening on {}", path.to_string_lossy());
+    loop {
+        let (socket, _addr) = listener.accept().await?;
+        let service = abci_service.clone();
+        tokio::spawn(async move {
+            let (mut reader, mut writer) = socket.into_split();
+            if let Err(err) =
+                serve_connection(service, &mut reader, &mut writer).await
+            {
+                tracing::error!("ABCI connection error: {}", err);
+            }
+        });
+    }
+}
+
+/// Answer the requests of a single ABCI connection until it's closed.
+async fn serve_connection(
+    mut service: AbciService,
+    reader: &mut (impl AsyncRead + Unpin),
+    writer: &mut (impl AsyncWrite + Unpin),
+) -> Result<(), BoxError> {
+    while let Some(request) = read_request(reader).await? {
+        let response = service.ready().await?.call(request).await?;
+        write_response(writer, response).await?;
+    }
+    Ok(())
+}
+
+/// Read a request prefixed with its varint encoded length. Returns `None`
+/// when the connection has been closed.
+async fn read_request(
+    reader: &mut (impl AsyncRead + Unpin),
+) -> io::Result<Option<Req>> {
+    let mut len: u64 = 0;
+    for i in 0..MAX_VARINT_LEN {
+        let byte = match reader.read_u8().await {
+            Ok(byte) => byte,
+            Err(err)
+                if i == 0 && err.kind() == io::ErrorKind::UnexpectedEof =>
+            {
+                return Ok(None);
+            }
+            Err(err) => return Err(err),
+        };
+        len |= u64::from(byte & 0x7f) << (7 * i);
+        if byte & 0x80 == 0 {
+            let mut buf = vec![0; len as usize];
+            reader.read_exact(&mut buf).await?;
+            return Req::decode(buf.as_slice()).map(Some).map_err(|err| {
+                io::Error::new(io::ErrorKind::InvalidData, err)
+            });
+        }
+    }
+    Err(io::Error::new(
+        io::ErrorKind::InvalidData,
+        "Invalid ABCI message length prefix",
+    ))
+}
+
+/// Write a response prefixed with its varint encoded length.
+async fn write_response(
+    writer: &mut (impl AsyncWrite + Unpin),
+    response: Resp,
+) -> io::Result<()> {
+    writer
+        .write_all(&response.encode_length_delimited_to_vec())
+        .await?;
+    writer.flush().await
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(not(feature = "ABCI"))]
+    use tendermint_proto::abci::{request, RequestEcho};
+    #[cfg(feature = "ABCI")]
+    use tendermint_proto_abci::abci::{request, RequestEcho};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_read_length_delimited_requests() {
+        let request = Req {
+            value: Some(request::Value::Echo(RequestEcho {
+                // Long enough for a multi-byte length prefix
+                message: "echo".repeat(100),
+            })),
+        };
+        let mut bytes = request.encode_length_delimited_to_vec();
+        bytes.extend(request.encode_length_delimited_to_vec());
+
+        let mut reader = bytes.as_slice();
+        assert_eq!(
+            read_request(&mut reader).await.unwrap(),
+            Some(request.clone())
+        );
+        assert_eq!(read_request(&mut reader).await.unwrap(), Some(request));
+        assert_eq!(read_request(&mut reader).await.unwrap(), None);
+
+        // A truncated message is an error
+        let mut bytes = Req::default().encode_length_delimited_to_vec();
+        bytes.extend([0x05, 0x0a]);
+        let mut reader = bytes.as_slice();
+        assert_eq!(
+            read_request(&mut reader).await.unwrap(),
+            Some(Req::default())
+        );
+        assert!(read_request(&mut reader).await.is_err());
+    }
+}
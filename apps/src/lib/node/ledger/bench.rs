@@ -0,0 +1,294 @@
+//! Benchmark workloads of the ledger, shared by the criterion benchmarks in
+//! `apps/benches` and the `anoma utils bench` runner, so that both measure the
+//! same code:
+//!
+//! - applying a tx whose changes trigger the VPs of a number of accounts,
+//! - committing a block with a number of changed storage keys,
+//! - running a tx WASM with a cold and with a warm compilation cache and
+//! - updating the Merkle tree with a number of storage keys.
+//!
+//! The workloads use the WASMs built for tests, i.e. the `wasm_for_tests`
+//! directory.
+
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+use anoma::ledger::gas::BlockGasMeter;
+use anoma::ledger::storage::write_log::WriteLog;
+use anoma::ledger::storage::{MerkleTree, DB};
+use anoma::types::chain::ChainId;
+use anoma::types::key::*;
+use anoma::types::storage::{BlockHash, Key};
+use anoma::types::transaction::batch::{BatchedTx, TxBatch};
+use anoma::types::transaction::{DecryptedTx, TxType};
+use anoma::vm::wasm::{self, TxCache, VpCache};
+use anoma::vm::WasmCacheRwAccess;
+use borsh::BorshSerialize;
+
+use super::protocol;
+use super::storage::{PersistentDB, PersistentStorage, PersistentStorageHasher};
+
+/// The tx WASM that does nothing
+pub const TX_NO_OP_WASM: &str = "tx_no_op.wasm";
+/// The tx WASM that writes the storage key given in its data
+pub const TX_WRITE_STORAGE_KEY_WASM: &str = "tx_write_storage_key.wasm";
+/// The VP WASM that accepts any tx
+pub const VP_ALWAYS_TRUE_WASM: &str = "vp_always_true.wasm";
+
+/// The numbers of the VPs triggered by the benchmarked txs
+pub const NUM_VPS: [usize; 3] = [1, 10, 50];
+/// The numbers of the changed keys of the benchmarked blocks and Merkle tree
+/// updates
+pub const NUM_KEYS: [usize; 3] = [100, 1_000, 10_000];
+
+/// The size of the in-memory WASM compilation caches
+const WASM_CACHE_SIZE: usize = 50 * 1024 * 1024;
+
+/// The environment of the workloads, with a storage and the WASM compilation
+/// caches in a directory
+pub struct BenchEnv {
+    dir: PathBuf,
+    wasm_dir: PathBuf,
+    storage: PersistentStorage,
+    write_log: WriteLog,
+    vp_wasm_cache: VpCache<WasmCacheRwAccess>,
+    tx_wasm_cache: TxCache<WasmCacheRwAccess>,
+    /// The number of the cold tx WASM compilation caches created so far,
+    /// each in its own directory
+    cold_caches: u64,
+}
+
+impl BenchEnv {
+    /// Create an environment in the given empty directory with the WASMs from
+    /// the given WASM directory
+    pub fn new(dir: impl Into<PathBuf>, wasm_dir: impl Into<PathBuf>) -> Self {
+        let dir = dir.into();
+        let storage =
+            PersistentStorage::open(dir.join("db"), ChainId::default(), None);
+        let vp_wasm_cache =
+            VpCache::new(dir.join("vp_wasm_cache"), WASM_CACHE_SIZE);
+        let tx_wasm_cache =
+            TxCache::new(dir.join("tx_wasm_cache"), WASM_CACHE_SIZE);
+        Self {
+            dir,
+            wasm_dir: wasm_dir.into(),
+            storage,
+            write_log: WriteLog::default(),
+            vp_wasm_cache,
+            tx_wasm_cache,
+            cold_caches: 0,
+        }
+    }
+
+    /// Read a WASM with the given file name from the WASM directory
+    pub fn read_wasm(&self, file_name: &str) -> Vec<u8> {
+        let path = self.wasm_dir.join(file_name);
+        std::fs::read(&path).unwrap_or_else(|err| {
+            panic!(
+                "Failed to read the WASM {}: {}",
+                path.to_string_lossy(),
+                err
+            )
+        })
+    }
+
+    /// Initialize the given number of accounts with a VP that accepts any tx
+    /// and make a tx that writes a key of every one of them, so that applying
+    /// the tx runs all their VPs
+    pub fn prepare_apply_tx(&mut self, num_vps: usize) -> TxType {
+        let vp_code = self.read_wasm(VP_ALWAYS_TRUE_WASM);
+        let tx_code = self.read_wasm(TX_WRITE_STORAGE_KEY_WASM);
+        let mut txs = Vec::with_capacity(num_vps);
+        for _ in 0..num_vps {
+            let addr = self.storage.address_gen.generate_address("bench");
+            self.storage
+                .write(&Key::validity_predicate(&addr), vp_code.clone())
+                .expect("Writing a VP shouldn't fail");
+            let key = Key::from(addr.to_db_key())
+                .push(&"bench".to_owned())
+                .expect("Cannot obtain a storage key");
+            txs.push(BatchedTx {
+                code: tx_code.clone(),
+                data: Some(key.to_string().into_bytes()),
+            });
+        }
+        let keypair = ed25519::SigScheme::generate(&mut rand::thread_rng())
+            .try_to_sk()
+            .unwrap();
+        let tx = TxBatch::new(txs).into_tx().sign(&keypair);
+        TxType::Decrypted(DecryptedTx::Decrypted(tx))
+    }
+
+    /// Apply the tx and drop its changes, so that it can be applied again
+    pub fn apply_tx(&mut self, tx: TxType) {
+        let result = protocol::apply_tx(
+            tx,
+            0,
+            &mut BlockGasMeter::default(),
+            &mut self.write_log,
+            &self.storage,
+            &mut self.vp_wasm_cache,
+            &mut self.tx_wasm_cache,
+        )
+        .expect("Applying the tx shouldn't fail");
+        assert!(result.is_accepted(), "The tx should be accepted");
+        self.write_log.drop_tx();
+    }
+
+    /// Write the given number of keys into the write log as the changes of
+    /// the next block
+    pub fn prepare_commit_block(&mut self, num_keys: usize) {
+        for i in 0..num_keys {
+            let key = Key::parse(format!("bench/{}", i))
+                .expect("Cannot obtain a storage key");
+            let value = (i as u64).try_to_vec().unwrap();
+            self.write_log
+                .write(&key, value)
+                .expect("Writing a key shouldn't fail");
+        }
+        self.write_log.commit_tx();
+    }
+
+    /// Commit the next block with the changes in the write log
+    pub fn commit_block(&mut self) {
+        let height = self.storage.last_height.next_height();
+        self.storage
+            .begin_block(BlockHash::default(), height)
+            .expect("Beginning a block shouldn't fail");
+        let mut batch = PersistentDB::batch();
+        self.write_log
+            .commit_block_to_merkle_tree(&mut self.storage)
+            .expect("Updating the Merkle tree shouldn't fail");
+        self.write_log
+            .commit_block_to_batch(&mut self.storage, &mut batch)
+            .expect("Committing the write log shouldn't fail");
+        self.storage
+            .commit_with_batch(batch)
+            .expect("Committing a block shouldn't fail");
+    }
+
+    /// Run the tx WASM with a new, empty compilation cache, so that the WASM
+    /// is compiled
+    pub fn run_tx_cold(&mut self, tx_code: &[u8]) {
+        self.cold_caches += 1;
+        let dir = self
+            .dir
+            .join(format!("cold_tx_wasm_cache_{}", self.cold_caches));
+        let mut tx_wasm_cache = TxCache::new(dir, WASM_CACHE_SIZE);
+        self.run_tx(tx_code, &mut tx_wasm_cache);
+    }
+
+    /// Run the tx WASM with the environment's compilation cache, which has
+    /// the compiled WASM after the first run
+    pub fn run_tx_warm(&mut self, tx_code: &[u8]) {
+        let mut tx_wasm_cache = self.tx_wasm_cache.clone();
+        self.run_tx(tx_code, &mut tx_wasm_cache);
+    }
+
+    fn run_tx(
+        &mut self,
+        tx_code: &[u8],
+        tx_wasm_cache: &mut TxCache<WasmCacheRwAccess>,
+    ) {
+        wasm::run::tx(
+            &self.storage,
+            &mut self.write_log,
+            &mut BlockGasMeter::default(),
+            tx_code,
+            b"",
+            &mut self.vp_wasm_cache,
+            tx_wasm_cache,
+        )
+        .expect("Running the tx shouldn't fail");
+        self.write_log.drop_tx();
+    }
+}
+
+/// Make the given number of storage keys with values for
+/// [`update_merkle_tree`]
+pub fn merkle_tree_updates(num_keys: usize) -> Vec<(Key, Vec<u8>)> {
+    (0..num_keys)
+        .map(|i| {
+            let key = Key::parse(format!("bench/{}", i))
+                .expect("Cannot obtain a storage key");
+            (key, (i as u64).try_to_vec().unwrap())
+        })
+        .collect()
+}
+
+/// Update an empty Merkle tree with the given keys and values
+pub fn update_merkle_tree(updates: &[(Key, Vec<u8>)]) {
+    let mut tree = MerkleTree::<PersistentStorageHasher>::default();
+    for (key, value) in updates {
+        tree.update(key, value)
+            .expect("Updating the Merkle tree shouldn't fail");
+    }
+}
+
+/// Run every workload the given number of times in a new environment in the
+/// given directory and return their names with their mean durations. This is
+/// a quick check, the criterion benchmarks give statistically sound results.
+pub fn run(
+    dir: impl Into<PathBuf>,
+    wasm_dir: impl Into<PathBuf>,
+    runs: u32,
+) -> Vec<(String, Duration)> {
+    let mut env = BenchEnv::new(dir, wasm_dir);
+    let runs = runs.max(1);
+    let mut results = vec![];
+    let mut report = |name: String, total: Duration| {
+        results.push((name, total / runs));
+    };
+
+    for num_vps in NUM_VPS {
+        let tx = env.prepare_apply_tx(num_vps);
+        let total = measure(&mut env, runs, |_| tx.clone(), BenchEnv::apply_tx);
+        report(format!("apply_tx/{}_vps", num_vps), total);
+    }
+    for num_keys in NUM_KEYS {
+        let total = measure(
+            &mut env,
+            runs,
+            |env| env.prepare_commit_block(num_keys),
+            |env, ()| env.commit_block(),
+        );
+        report(format!("commit_block/{}_keys", num_keys), total);
+    }
+    let tx_code = env.read_wasm(TX_NO_OP_WASM);
+    let total = measure(&mut env, runs, |_| (), |env, ()| {
+        env.run_tx_cold(&tx_code)
+    });
+    report("tx_wasm/cold_cache".to_owned(), total);
+    // Fill the cache before the runs with a warm cache
+    env.run_tx_warm(&tx_code);
+    let total = measure(&mut env, runs, |_| (), |env, ()| {
+        env.run_tx_warm(&tx_code)
+    });
+    report("tx_wasm/warm_cache".to_owned(), total);
+    for num_keys in NUM_KEYS {
+        let updates = merkle_tree_updates(num_keys);
+        let total = measure(&mut env, runs, |_| (), |_, ()| {
+            update_merkle_tree(&updates)
+        });
+        report(format!("merkle_tree/{}_keys", num_keys), total);
+    }
+    results
+}
+
+/// Run the routine the given number of times, each time with an input made
+/// by the setup, and return the total duration of the routine's runs
+fn measure<I>(
+    env: &mut BenchEnv,
+    runs: u32,
+    mut setup: impl FnMut(&mut BenchEnv) -> I,
+    mut routine: impl FnMut(&mut BenchEnv, I),
+) -> Duration {
+    let mut total = Duration::default();
+    for _ in 0..runs {
+        let input = setup(env);
+        let start = Instant::now();
+        routine(env, input);
+        total += start.elapsed();
+    }
+    total
+}
@@ -0,0 +1,137 @@
+//! The results of a finalized block, persisted in the ledger's DB by the
+//! block height so that they can be queried without replaying the block.
+
+use std::collections::BTreeMap;
+
+use anoma::ledger::storage::write_log::StorageChange;
+use anoma::types::key::common;
+use anoma::types::storage::BlockHeight;
+use borsh::{BorshDeserialize, BorshSerialize};
+
+use super::events::Event;
+
+/// The results of a block
+#[derive(
+    Clone, Debug, Default, PartialEq, Eq, BorshSerialize, BorshDeserialize,
+)]
+pub struct BlockResults {
+    /// The height of the block
+    pub height: BlockHeight,
+    /// The results of the transactions in the block
+    pub tx_results: Vec<TxResult>,
+    /// All the events emitted in the block
+    pub events: Vec<BlockEvent>,
+    /// The total gas used by the block
+    pub gas_used: u64,
+    /// The changes of the validators' voting powers at the start of a new
+    /// epoch
+    pub validator_updates: Vec<ValidatorUpdate>,
+    /// The storage changes of the block
+    pub storage_changes: Vec<StorageChange>,
+}
+
+/// The result of a transaction in a block
+#[derive(Clone, Debug, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+pub struct TxResult {
+    /// The hash of the transaction
+    pub hash: String,
+    /// The type of the transaction's event, "accepted" or "applied"
+    pub event_type: String,
+    /// The result code
+    pub code: String,
+    /// The gas used by the transaction
+    pub gas_used: u64,
+    /// The info message of the result
+    pub info: String,
+}
+
+/// An event emitted in a block
+#[derive(Clone, Debug, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+pub struct BlockEvent {
+    /// The type of the event
+    pub event_type: String,
+    /// The attributes of the event
+    pub attributes: BTreeMap<String, String>,
+}
+
+/// A change of a validator's voting power
+#[derive(Clone, Debug, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+pub struct ValidatorUpdate {
+    /// The validator's consensus key
+    pub consensus_key: common::PublicKey,
+    /// The new voting power, 0 for a deactivated validator
+    pub voting_power: i64,
+}
+
+impl BlockResults {
+    /// Create empty results for a block at the given height.
+    pub fn new(height: BlockHeight) -> Self {
+        Self {
+            height,
+            ..Default::default()
+        }
+    }
+
+    /// Add an event emitted in the block. The events with a transaction hash
+    /// are also recorded as a transaction result.
+    pub fn add_event(&mut self, event: &Event) {
+        let event_type = event.event_type.to_string();
+        let attributes: BTreeMap<String, String> = event
+            .attributes
+            .iter()
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect();
+        if let Some(hash) = attributes.get("hash") {
+            let attribute =
+                |key: &str| attributes.get(key).cloned().unwrap_or_default();
+            self.tx_results.push(TxResult {
+                hash: hash.clone(),
+                event_type: event_type.clone(),
+                code: attribute("code"),
+                gas_used: attribute("gas_used").parse().unwrap_or_default(),
+                info: attribute("info"),
+            });
+        }
+        self.events.push(BlockEvent {
+            event_type,
+            attributes,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+    use crate::node::ledger::events::EventType;
+
+    #[test]
+    fn test_add_event() {
+        let mut results = BlockResults::new(BlockHeight(1));
+        let mut attributes = HashMap::new();
+        attributes.insert("hash".to_owned(), "abcd".to_owned());
+        attributes.insert("code".to_owned(), "0".to_owned());
+        attributes.insert("gas_used".to_owned(), "10".to_owned());
+        results.add_event(&Event {
+            event_type: EventType::Applied,
+            attributes,
+        });
+        results.add_event(&Event {
+            event_type: EventType::Proposal,
+            attributes: HashMap::new(),
+        });
+
+        assert_eq!(results.events.len(), 2);
+        assert_eq!(
+            results.tx_results,
+            vec![TxResult {
+                hash: "abcd".to_owned(),
+                event_type: "applied".to_owned(),
+                code: "0".to_owned(),
+                gas_used: 10,
+                info: "".to_owned(),
+            }]
+        );
+    }
+}
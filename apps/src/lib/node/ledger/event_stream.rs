@@ -0,0 +1,307 @@
+//! A WebSocket server that streams the ledger's events to the subscribed
+//! clients. The events are emitted by the shell when a block is committed.
+//!
+//! A client subscribes by sending a JSON [`Subscription`] message. Until then,
+//! it doesn't receive any events. A new subscription message replaces the
+//! previous one. The matching events are sent to the client as JSON
+//! [`StreamEvent`] messages.
+//!
+//! Besides the events of the transactions, a
+//! [`STORAGE_CHANGES_EVENT`] event with the old and new values of all the
+//! storage keys changed in a block is sent when the block is committed, so
+//! that external indexers can mirror the state without re-executing the
+//! transactions.
+
+use std::collections::{BTreeSet, HashMap};
+use std::net::SocketAddr;
+use std::sync::{Arc, RwLock};
+
+use anoma::ledger::storage::write_log::StorageChange;
+use anoma::types::storage::{BlockHeight, Key};
+use message_io::network::{Endpoint, NetEvent, Transport};
+use message_io::node::{self, NodeHandler, NodeTask};
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc::UnboundedReceiver;
+
+use super::block_results::BlockEvent;
+use super::events::Event;
+
+/// The type of the event with the storage changes of a committed block
+pub const STORAGE_CHANGES_EVENT: &str = "storage_changes";
+
+/// An event sent to the subscribed clients
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StreamEvent {
+    /// The type of the event, e.g. "accepted", "applied" or "proposal"
+    pub event_type: String,
+    /// The height of the block in which the event occurred
+    pub height: u64,
+    /// The attributes of the event
+    pub attributes: HashMap<String, String>,
+    /// The storage keys changed by an applied transaction
+    pub changed_keys: Vec<String>,
+    /// The addresses found in the changed storage keys
+    pub addresses: Vec<String>,
+    /// The old and new values of the changed storage keys. Only set in a
+    /// [`STORAGE_CHANGES_EVENT`] event.
+    #[serde(default)]
+    pub storage_changes: Vec<StreamStorageChange>,
+}
+
+/// A change of a storage value with hex-encoded values
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StreamStorageChange {
+    /// The changed storage key
+    pub key: String,
+    /// The value before the block, if any
+    pub old_value: Option<String>,
+    /// The value after the block, `None` if it has been deleted
+    pub new_value: Option<String>,
+}
+
+impl StreamEvent {
+    /// Create a stream event from an event and the storage keys that have
+    /// been changed with it.
+    pub fn new(
+        event: &Event,
+        height: BlockHeight,
+        changed_keys: &BTreeSet<Key>,
+    ) -> Self {
+        Self {
+            event_type: event.event_type.to_string(),
+            height: height.0,
+            attributes: event.attributes.clone(),
+            changed_keys: changed_keys.iter().map(Key::to_string).collect(),
+            addresses: find_addresses(changed_keys.iter()),
+            storage_changes: vec![],
+        }
+    }
+
+    /// Create a stream event from an event persisted in the results of a
+    /// block, as read by a read-only node. The storage keys changed with the
+    /// event are not persisted, so they are left empty.
+    pub fn from_block_event(event: &BlockEvent, height: BlockHeight) -> Self {
+        Self {
+            event_type: event.event_type.clone(),
+            height: height.0,
+            attributes: event
+                .attributes
+                .iter()
+                .map(|(key, value)| (key.clone(), value.clone()))
+                .collect(),
+            changed_keys: vec![],
+            addresses: vec![],
+            storage_changes: vec![],
+        }
+    }
+
+    /// Create a [`STORAGE_CHANGES_EVENT`] event with the storage changes of
+    /// a committed block.
+    pub fn storage_changes(
+        height: BlockHeight,
+        changes: &[StorageChange],
+    ) -> Self {
+        Self {
+            event_type: STORAGE_CHANGES_EVENT.to_owned(),
+            height: height.0,
+            attributes: HashMap::new(),
+            changed_keys: changes
+                .iter()
+                .map(|change| change.key.to_string())
+                .collect(),
+            addresses: find_addresses(changes.iter().map(|change| &change.key)),
+            storage_changes: changes
+                .iter()
+                .map(|change| StreamStorageChange {
+                    key: change.key.to_string(),
+                    old_value: change.old_value.as_ref().map(hex::encode),
+                    new_value: change.new_value.as_ref().map(hex::encode),
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Find the unique addresses in the given storage keys.
+fn find_addresses<'a>(keys: impl Iterator<Item = &'a Key>) -> Vec<String> {
+    let addresses: BTreeSet<String> = keys
+        .flat_map(Key::find_addresses)
+        .map(|address| address.encode())
+        .collect();
+    addresses.into_iter().collect()
+}
+
+/// A filter of the events that a client is interested in. An event matches
+/// when it matches all the non-empty criteria, so an empty subscription
+/// matches every event.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Subscription {
+    /// Match the events that touched any of these addresses
+    #[serde(default)]
+    pub addresses: BTreeSet<String>,
+    /// Match the events that changed a storage key with any of these prefixes
+    #[serde(default)]
+    pub key_prefixes: Vec<String>,
+    /// Match the events of any of these types
+    #[serde(default)]
+    pub event_types: BTreeSet<String>,
+}
+
+impl Subscription {
+    /// Check if the event matches this subscription.
+    pub fn matches(&self, event: &StreamEvent) -> bool {
+        let address_matches = self.addresses.is_empty()
+            || event
+                .addresses
+                .iter()
+                .any(|address| self.addresses.contains(address));
+        let key_matches = self.key_prefixes.is_empty()
+            || event.changed_keys.iter().any(|key| {
+                self.key_prefixes
+                    .iter()
+                    .any(|prefix| key.starts_with(prefix.as_str()))
+            });
+        let type_matches = self.event_types.is_empty()
+            || self.event_types.contains(&event.event_type);
+        address_matches && key_matches && type_matches
+    }
+}
+
+/// The running server. The node is stopped on drop, before its task is
+/// dropped, because dropping the task waits for the node to stop.
+struct Server {
+    handler: NodeHandler<()>,
+    _task: NodeTask,
+}
+
+impl Drop for Server {
+    fn drop(&mut self) {
+        self.handler.stop();
+    }
+}
+
+/// Run the event stream server at the given address and forward the received
+/// events to the subscribed clients until the events channel is closed.
+pub async fn run(
+    address: SocketAddr,
+    mut events: UnboundedReceiver<StreamEvent>,
+) -> std::io::Result<()> {
+    let (handler, listener) = node::split::<()>();
+    let (_resource_id, address) =
+        handler.network().listen(Transport::Ws, address)?;
+    tracing::info!("Event stream server started at {}", address);
+
+    let clients: Arc<RwLock<HashMap<Endpoint, Subscription>>> =
+        Default::default();
+    let task = {
+        let clients = clients.clone();
+        listener.for_each_async(move |event| match event.network() {
+            NetEvent::Message(endpoint, msg) => {
+                match serde_json::from_slice::<Subscription>(msg) {
+                    Ok(subscription) => {
+                        tracing::debug!(
+                            "Event stream client {} subscribed with {:?}",
+                            endpoint,
+                            subscription
+                        );
+                        let mut clients = clients.write().unwrap();
+                        clients.insert(endpoint, subscription);
+                    }
+                    Err(err) => {
+                        tracing::info!(
+                            "Invalid subscription from an event stream client \
+                             {}: {}",
+                            endpoint,
+                            err
+                        );
+                    }
+                }
+            }
+            NetEvent::Connected(_, _) | NetEvent::Accepted(_, _) => {}
+            NetEvent::Disconnected(endpoint) => {
+                let mut clients = clients.write().unwrap();
+                clients.remove(&endpoint);
+            }
+        })
+    };
+    let server = Server {
+        handler,
+        _task: task,
+    };
+
+    while let Some(event) = events.recv().await {
+        let clients = clients.read().unwrap();
+        let mut msg: Option<Vec<u8>> = None;
+        for (endpoint, subscription) in clients.iter() {
+            if !subscription.matches(&event) {
+                continue;
+            }
+            let msg = msg.get_or_insert_with(|| {
+                serde_json::to_vec(&event)
+                    .expect("Serializing an event shouldn't fail")
+            });
+            server.handler.network().send(*endpoint, msg);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stream_event() -> StreamEvent {
+        StreamEvent {
+            event_type: "applied".to_owned(),
+            height: 1,
+            attributes: HashMap::new(),
+            changed_keys: vec!["#atest1/balance/#atest2".to_owned()],
+            addresses: vec!["atest1".to_owned(), "atest2".to_owned()],
+            storage_changes: vec![],
+        }
+    }
+
+    #[test]
+    fn test_subscription_matches() {
+        let event = stream_event();
+        assert!(Subscription::default().matches(&event));
+
+        let by_address = Subscription {
+            addresses: ["atest2".to_owned()].into_iter().collect(),
+            ..Default::default()
+        };
+        assert!(by_address.matches(&event));
+        let by_other_address = Subscription {
+            addresses: ["atest3".to_owned()].into_iter().collect(),
+            ..Default::default()
+        };
+        assert!(!by_other_address.matches(&event));
+
+        let by_prefix = Subscription {
+            key_prefixes: vec!["#atest1/balance".to_owned()],
+            ..Default::default()
+        };
+        assert!(by_prefix.matches(&event));
+        let by_other_prefix = Subscription {
+            key_prefixes: vec!["#atest2".to_owned()],
+            ..Default::default()
+        };
+        assert!(!by_other_prefix.matches(&event));
+
+        // All the non-empty criteria must match
+        let by_type = Subscription {
+            key_prefixes: vec!["#atest1".to_owned()],
+            event_types: ["accepted".to_owned()].into_iter().collect(),
+            ..Default::default()
+        };
+        assert!(!by_type.matches(&event));
+    }
+
+    #[test]
+    fn test_subscription_from_json() {
+        let subscription: Subscription =
+            serde_json::from_str(r#"{"event_types": ["applied"]}"#).unwrap();
+        assert!(subscription.addresses.is_empty());
+        assert!(subscription.matches(&stream_event()));
+    }
+}
@@ -3,7 +3,9 @@ use std::fmt::{self, Display};
 use std::ops::{Index, IndexMut};
 
 use anoma::ledger::governance::utils::ProposalEvent;
+use anoma::types::address::Address;
 use anoma::types::ibc::IbcEvent;
+use anoma::types::token;
 use anoma::types::transaction::{hash_tx, TxType};
 use borsh::BorshSerialize;
 #[cfg(not(feature = "ABCI"))]
@@ -30,6 +32,8 @@ pub enum EventType {
     Ibc(String),
     // The proposal that has been executed
     Proposal,
+    // The fees and reward paid to the proposer of a block
+    BlockRewards,
 }
 
 #[cfg(not(feature = "ABCI"))]
@@ -40,6 +44,7 @@ impl Display for EventType {
             EventType::Applied => write!(f, "applied"),
             EventType::Ibc(t) => write!(f, "{}", t),
             EventType::Proposal => write!(f, "proposal"),
+            EventType::BlockRewards => write!(f, "block_rewards"),
         }?;
         Ok(())
     }
@@ -53,6 +58,7 @@ impl Display for EventType {
             EventType::Applied => write!(f, "applied"),
             EventType::Ibc(t) => write!(f, "{}", t),
             EventType::Proposal => write!(f, "proposal"),
+            EventType::BlockRewards => write!(f, "block_rewards"),
         }?;
         Ok(())
     }
@@ -78,6 +84,8 @@ impl Event {
                 } else {
                     wrapper.tx_hash.to_string()
                 };
+                // The signer of the wrapper, who pays the fee
+                event["sender"] = Address::from(&wrapper.pk).encode();
                 event
             }
             TxType::Decrypted(decrypted) => {
@@ -102,11 +110,40 @@ impl Event {
             }
             _ => unreachable!(),
         };
+        // The canonical hash of the tx is the same for the events of a wrapper
+        // tx and of its decrypted inner tx
+        event["tx_hash"] = match tx {
+            TxType::Wrapper(wrapper) => wrapper.tx_hash.to_string(),
+            _ => event["hash"].clone(),
+        };
         event["height"] = height.to_string();
         event["log"] = "".to_string();
         event
     }
 
+    /// Creates a new event for the fees collected in a block in a token and
+    /// the block reward, if any, paid to the block proposer and the treasury
+    pub fn new_block_rewards_event(
+        height: u64,
+        proposer: &Address,
+        token: &Address,
+        fees: token::Amount,
+        reward: token::Amount,
+        treasury_share: token::Amount,
+    ) -> Self {
+        let mut event = Event {
+            event_type: EventType::BlockRewards,
+            attributes: HashMap::new(),
+        };
+        event["height"] = height.to_string();
+        event["proposer"] = proposer.encode();
+        event["token"] = token.encode();
+        event["fees"] = fees.to_string();
+        event["reward"] = reward.to_string();
+        event["treasury_share"] = treasury_share.to_string();
+        event
+    }
+
     pub fn contains_key(&self, key: &str) -> bool {
         self.attributes.contains_key(key)
     }
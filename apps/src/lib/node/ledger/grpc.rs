@@ -0,0 +1,311 @@
+//! A gRPC server with typed queries of the ledger state, so that clients
+//! don't have to construct the raw ABCI query paths. The queries are forwarded
+//! to the ledger via the Tendermint RPC, or in a read-only node, answered from
+//! the committed state in the DB.
+
+use std::collections::BTreeSet;
+use std::net::SocketAddr;
+
+use anoma::ledger::parameters::{storage as param_storage, EpochDuration};
+use anoma::ledger::pos;
+use anoma::ledger::pos::types::WeightedValidator;
+use anoma::types::address::Address;
+use anoma::types::storage::{self, Epoch};
+use anoma::types::time::DurationSecs;
+use anoma::types::token;
+use borsh::BorshDeserialize;
+#[cfg(not(feature = "ABCI"))]
+use tendermint::abci::Code;
+#[cfg(not(feature = "ABCI"))]
+use tendermint_config::net::Address as TendermintAddress;
+#[cfg(feature = "ABCI")]
+use tendermint_config_abci::net::Address as TendermintAddress;
+#[cfg(not(feature = "ABCI"))]
+use tendermint_rpc::{Client, HttpClient};
+#[cfg(feature = "ABCI")]
+use tendermint_rpc_abci::{Client, HttpClient};
+#[cfg(feature = "ABCI")]
+use tendermint_stable::abci::Code;
+use tonic::transport::Server;
+use tonic::{Request, Response, Status};
+#[cfg(not(feature = "ABCI"))]
+use tower_abci::request;
+#[cfg(feature = "ABCI")]
+use tower_abci_old::request;
+
+use crate::client::rpc::{query_tx_response, TxEventQuery};
+use crate::node::ledger::rpc::Path;
+use crate::node::ledger::shell::Shell;
+use crate::node::ledger::storage::SnapshotReader;
+use crate::proto::query::query_service_server::{
+    QueryService, QueryServiceServer,
+};
+use crate::proto::query::*;
+
+/// The query service
+enum Queries {
+    /// Forward the queries to the ledger via the Tendermint RPC
+    Rpc {
+        ledger_address: TendermintAddress,
+        client: HttpClient,
+    },
+    /// Answer the queries from the committed state in the DB
+    Snapshot(SnapshotReader),
+}
+
+/// Run the gRPC query server at the given address until it fails.
+pub async fn run(
+    address: SocketAddr,
+    ledger_address: TendermintAddress,
+) -> Result<(), tonic::transport::Error> {
+    let client = HttpClient::new(ledger_address.clone()).unwrap();
+    let queries = Queries::Rpc {
+        ledger_address,
+        client,
+    };
+    serve(address, queries).await
+}
+
+/// Run the gRPC query server of a read-only node at the given address until
+/// it fails. The queries are answered from the committed state read with the
+/// given reader.
+pub async fn run_read_only(
+    address: SocketAddr,
+    reader: SnapshotReader,
+) -> Result<(), tonic::transport::Error> {
+    serve(address, Queries::Snapshot(reader)).await
+}
+
+async fn serve(
+    address: SocketAddr,
+    queries: Queries,
+) -> Result<(), tonic::transport::Error> {
+    tracing::info!("gRPC query server started at {}", address);
+    Server::builder()
+        .add_service(QueryServiceServer::new(queries))
+        .serve(address)
+        .await
+}
+
+impl Queries {
+    /// Query the ledger at the given path. Returns `None` if the queried
+    /// value doesn't exist.
+    async fn query_bytes(&self, path: Path) -> Result<Option<Vec<u8>>, Status> {
+        let (code, value, info) = match self {
+            Self::Rpc { client, .. } => {
+                let response = client
+                    .abci_query(Some(path.into()), vec![], None, false)
+                    .await
+                    .map_err(|err| Status::unavailable(err.to_string()))?;
+                let code = match response.code {
+                    Code::Ok => 0,
+                    Code::Err(err) => err,
+                };
+                (code, response.value, response.info)
+            }
+            Self::Snapshot(reader) => {
+                let reader = reader.clone();
+                let query = request::Query {
+                    path: path.to_string(),
+                    ..Default::default()
+                };
+                let response = tokio::task::spawn_blocking(move || {
+                    Shell::query_snapshot(&reader, &query)
+                })
+                .await
+                .map_err(|err| Status::internal(err.to_string()))?;
+                (response.code, response.value, response.info)
+            }
+        };
+        match code {
+            0 => Ok(Some(value)),
+            // The error code 1 is returned for a missing value
+            1 => Ok(None),
+            err => Err(Status::internal(format!(
+                "Error in the query {} (error code {})",
+                info, err
+            ))),
+        }
+    }
+
+    /// Query and decode a value from the ledger at the given path.
+    async fn query<T>(&self, path: Path) -> Result<Option<T>, Status>
+    where
+        T: BorshDeserialize,
+    {
+        match self.query_bytes(path).await? {
+            Some(bytes) => T::try_from_slice(&bytes[..])
+                .map(Some)
+                .map_err(|err| Status::internal(err.to_string())),
+            None => Ok(None),
+        }
+    }
+
+    /// Query and decode a storage value that must be set.
+    async fn query_required<T>(&self, key: storage::Key) -> Result<T, Status>
+    where
+        T: BorshDeserialize,
+    {
+        self.query(Path::Value(key.clone())).await?.ok_or_else(|| {
+            Status::not_found(format!("No value found for the key {}", key))
+        })
+    }
+
+    async fn query_epoch(&self) -> Result<Epoch, Status> {
+        self.query(Path::Epoch)
+            .await?
+            .ok_or_else(|| Status::internal("The epoch is not set"))
+    }
+}
+
+/// Convert a set of validators to their response messages.
+fn to_validators(
+    validators: &BTreeSet<WeightedValidator<Address>>,
+) -> Vec<Validator> {
+    validators
+        .iter()
+        .map(|validator| Validator {
+            address: validator.address.encode(),
+            voting_power: validator.voting_power.into(),
+        })
+        .collect()
+}
+
+/// Parse an address from a request.
+fn parse_address(address: &str) -> Result<Address, Status> {
+    Address::decode(address).map_err(|err| {
+        Status::invalid_argument(format!(
+            "Invalid address {}: {}",
+            address, err
+        ))
+    })
+}
+
+#[tonic::async_trait]
+impl QueryService for Queries {
+    async fn balance(
+        &self,
+        request: Request<BalanceRequest>,
+    ) -> Result<Response<BalanceResponse>, Status> {
+        let BalanceRequest { token, owner } = request.into_inner();
+        let token = parse_address(&token)?;
+        let owner = parse_address(&owner)?;
+        let key = token::balance_key(&token, &owner);
+        let amount: token::Amount =
+            self.query(Path::Value(key)).await?.unwrap_or_default();
+        Ok(Response::new(BalanceResponse {
+            amount: amount.into(),
+        }))
+    }
+
+    async fn validity_predicate(
+        &self,
+        request: Request<ValidityPredicateRequest>,
+    ) -> Result<Response<ValidityPredicateResponse>, Status> {
+        let address = parse_address(&request.into_inner().address)?;
+        let key = storage::Key::validity_predicate(&address);
+        // The VP code is stored without encoding
+        let code = self.query_bytes(Path::Value(key)).await?;
+        Ok(Response::new(ValidityPredicateResponse {
+            found: code.is_some(),
+            code: code.unwrap_or_default(),
+        }))
+    }
+
+    async fn epoch(
+        &self,
+        _request: Request<EpochRequest>,
+    ) -> Result<Response<EpochResponse>, Status> {
+        let epoch = self.query_epoch().await?;
+        Ok(Response::new(EpochResponse { epoch: epoch.0 }))
+    }
+
+    async fn validator_set(
+        &self,
+        _request: Request<ValidatorSetRequest>,
+    ) -> Result<Response<ValidatorSetResponse>, Status> {
+        let epoch = self.query_epoch().await?;
+        let validator_sets: pos::ValidatorSets =
+            self.query_required(pos::validator_set_key()).await?;
+        let validator_set = validator_sets.get(epoch).ok_or_else(|| {
+            Status::internal(format!(
+                "The validator set is not set in the epoch {}",
+                epoch
+            ))
+        })?;
+        Ok(Response::new(ValidatorSetResponse {
+            epoch: epoch.0,
+            active: to_validators(&validator_set.active),
+            inactive: to_validators(&validator_set.inactive),
+        }))
+    }
+
+    async fn parameters(
+        &self,
+        _request: Request<ParametersRequest>,
+    ) -> Result<Response<ParametersResponse>, Status> {
+        let epoch_duration: EpochDuration = self
+            .query_required(param_storage::get_epoch_storage_key())
+            .await?;
+        let max_expected_time_per_block: DurationSecs =
+            self.query_required(
+                param_storage::get_max_expected_time_per_block_key(),
+            )
+            .await?;
+        let vp_whitelist: Vec<String> = self
+            .query_required(param_storage::get_vp_whitelist_storage_key())
+            .await?;
+        let tx_whitelist: Vec<String> = self
+            .query_required(param_storage::get_tx_whitelist_storage_key())
+            .await?;
+        Ok(Response::new(ParametersResponse {
+            epoch_min_num_of_blocks: epoch_duration.min_num_of_blocks,
+            epoch_min_duration_secs: epoch_duration.min_duration.0,
+            max_expected_time_per_block_secs: max_expected_time_per_block.0,
+            vp_whitelist,
+            tx_whitelist,
+        }))
+    }
+
+    async fn tx_by_hash(
+        &self,
+        request: Request<TxByHashRequest>,
+    ) -> Result<Response<TxByHashResponse>, Status> {
+        let ledger_address = match self {
+            Self::Rpc { ledger_address, .. } => ledger_address,
+            Self::Snapshot(_) => {
+                return Err(Status::unimplemented(
+                    "The txs are not indexed by a read-only node",
+                ));
+            }
+        };
+        let hash = request.into_inner().hash;
+        // Look for the application of the tx first, then for its acceptance
+        let queries = [
+            ("applied", TxEventQuery::Applied(hash.clone())),
+            ("accepted", TxEventQuery::Accepted(hash)),
+        ];
+        for (event_type, query) in queries {
+            if let Ok(status) =
+                query_tx_response(ledger_address, query).await
+            {
+                let response = status.response;
+                return Ok(Response::new(TxByHashResponse {
+                    found: true,
+                    event_type: event_type.to_owned(),
+                    info: response.info,
+                    log: response.log,
+                    height: response.height,
+                    code: response.code,
+                    gas_used: response.gas_used,
+                    initialized_accounts: response
+                        .initialized_accounts
+                        .iter()
+                        .map(Address::encode)
+                        .collect(),
+                }));
+            }
+        }
+        Ok(Response::new(TxByHashResponse::default()))
+    }
+}
@@ -0,0 +1,318 @@
+//! An optional transaction indexer for explorers and debugging. When enabled,
+//! the shell records the transactions of every committed block into an
+//! embedded database, separate from the ledger's storage, that can be queried
+//! for the transactions that touched an address or a storage key prefix.
+//!
+//! The transactions are indexed by their canonical hash, which is the hash of
+//! the signed inner transaction, so the events of a wrapper transaction and of
+//! its decrypted inner transaction are merged into a single record. The events
+//! without a canonical hash are indexed by their own hash.
+//!
+//! The DB keys are:
+//! - `tx/{hash}` for the Borsh encoded [`IndexedTx`]
+//! - `address/{address}/{height}/{hash}` for the addresses of a transaction
+//! - `key/{storage key}/{height}/{hash}` for the storage keys changed by a
+//!   transaction
+//!
+//! where the height is zero-padded to keep the entries ordered by the height.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt;
+use std::path::Path;
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use rocksdb::{Direction, IteratorMode, Options, WriteBatch};
+use thiserror::Error;
+
+use super::event_stream::StreamEvent;
+
+const TX_PREFIX: &str = "tx";
+const ADDRESS_PREFIX: &str = "address";
+const KEY_PREFIX: &str = "key";
+
+#[allow(missing_docs)]
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("Indexer DB error: {0}")]
+    Db(rocksdb::Error),
+    #[error("Error decoding an indexed transaction: {0}")]
+    Decoding(std::io::Error),
+}
+
+/// Result of indexer operations
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// An indexed transaction
+#[derive(
+    Clone, Debug, Default, PartialEq, Eq, BorshSerialize, BorshDeserialize,
+)]
+pub struct IndexedTx {
+    /// The hash of the transaction
+    pub hash: String,
+    /// The height of the last block with an event of the transaction
+    pub height: u64,
+    /// The signer of the wrapper transaction, who pays the fee, if known
+    pub sender: Option<String>,
+    /// The result code of the last event of the transaction
+    pub code: Option<String>,
+    /// The gas used by the transaction
+    pub gas_used: u64,
+    /// The addresses found in the storage keys changed by the transaction
+    pub addresses: BTreeSet<String>,
+    /// The storage keys changed by the transaction
+    pub changed_keys: BTreeSet<String>,
+    /// The events of the transaction
+    pub events: Vec<IndexedEvent>,
+}
+
+/// An event of an indexed transaction
+#[derive(Clone, Debug, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+pub struct IndexedEvent {
+    /// The type of the event, e.g. "accepted" or "applied"
+    pub event_type: String,
+    /// The height of the block in which the event occurred
+    pub height: u64,
+    /// The attributes of the event
+    pub attributes: BTreeMap<String, String>,
+}
+
+impl IndexedTx {
+    /// Add an event of this transaction to the record.
+    fn add_event(&mut self, event: &StreamEvent) {
+        self.height = event.height;
+        if let Some(sender) = event.attributes.get("sender") {
+            self.sender = Some(sender.clone());
+        }
+        if let Some(code) = event.attributes.get("code") {
+            self.code = Some(code.clone());
+        }
+        if let Some(gas_used) = event
+            .attributes
+            .get("gas_used")
+            .and_then(|gas_used| gas_used.parse().ok())
+        {
+            self.gas_used = gas_used;
+        }
+        self.addresses.extend(event.addresses.iter().cloned());
+        self.changed_keys.extend(event.changed_keys.iter().cloned());
+        self.events.push(IndexedEvent {
+            event_type: event.event_type.clone(),
+            height: event.height,
+            attributes: event
+                .attributes
+                .iter()
+                .map(|(key, value)| (key.clone(), value.clone()))
+                .collect(),
+        });
+    }
+}
+
+/// The transaction indexer backed by a RocksDB database
+pub struct Indexer(rocksdb::DB);
+
+impl fmt::Debug for Indexer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Indexer").field(&self.0.path()).finish()
+    }
+}
+
+impl Indexer {
+    /// Open the indexer DB at the given path, creating it if it doesn't
+    /// exist.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let mut opts = Options::default();
+        opts.create_if_missing(true);
+        rocksdb::DB::open(&opts, path).map(Self).map_err(Error::Db)
+    }
+
+    /// Index the events of the transactions in a committed block. The events
+    /// without a transaction hash are ignored.
+    pub fn index_block(&self, events: &[StreamEvent]) -> Result<()> {
+        let mut txs: HashMap<&str, IndexedTx> = HashMap::new();
+        for event in events {
+            let hash = match event
+                .attributes
+                .get("tx_hash")
+                .or_else(|| event.attributes.get("hash"))
+            {
+                Some(hash) => hash.as_str(),
+                None => continue,
+            };
+            if !txs.contains_key(hash) {
+                let tx = self.read_tx(hash)?.unwrap_or_else(|| IndexedTx {
+                    hash: hash.to_owned(),
+                    ..Default::default()
+                });
+                txs.insert(hash, tx);
+            }
+            txs.get_mut(hash).unwrap().add_event(event);
+        }
+
+        let mut batch = WriteBatch::default();
+        for (hash, tx) in txs {
+            let height = format!("{:020}", tx.height);
+            for address in &tx.addresses {
+                batch.put(
+                    format!(
+                        "{}/{}/{}/{}",
+                        ADDRESS_PREFIX, address, height, hash
+                    ),
+                    b"",
+                );
+            }
+            for key in &tx.changed_keys {
+                batch.put(
+                    format!("{}/{}/{}/{}", KEY_PREFIX, key, height, hash),
+                    b"",
+                );
+            }
+            batch.put(
+                format!("{}/{}", TX_PREFIX, hash),
+                tx.try_to_vec().expect("Encoding a tx shouldn't fail"),
+            );
+        }
+        self.0.write(batch).map_err(Error::Db)
+    }
+
+    /// Read an indexed transaction by its hash.
+    pub fn read_tx(&self, hash: &str) -> Result<Option<IndexedTx>> {
+        match self
+            .0
+            .get(format!("{}/{}", TX_PREFIX, hash))
+            .map_err(Error::Db)?
+        {
+            Some(bytes) => IndexedTx::try_from_slice(&bytes[..])
+                .map(Some)
+                .map_err(Error::Decoding),
+            None => Ok(None),
+        }
+    }
+
+    /// Find the transactions that touched the given address, ordered by the
+    /// height.
+    pub fn txs_by_address(&self, address: &str) -> Result<Vec<IndexedTx>> {
+        self.find_txs(&format!("{}/{}/", ADDRESS_PREFIX, address))
+    }
+
+    /// Find the transactions that changed a storage key with the given
+    /// prefix, ordered by the height.
+    pub fn txs_by_key_prefix(&self, prefix: &str) -> Result<Vec<IndexedTx>> {
+        self.find_txs(&format!("{}/{}", KEY_PREFIX, prefix))
+    }
+
+    /// Find the transactions of the index entries with the given prefix.
+    fn find_txs(&self, prefix: &str) -> Result<Vec<IndexedTx>> {
+        let iter = self.0.iterator(IteratorMode::From(
+            prefix.as_bytes(),
+            Direction::Forward,
+        ));
+        let mut hashes = BTreeSet::new();
+        for (key, _) in iter {
+            let key = String::from_utf8_lossy(&key);
+            if !key.starts_with(prefix) {
+                break;
+            }
+            // The hash is the last segment of the index entry
+            if let Some((_, hash)) = key.rsplit_once('/') {
+                hashes.insert(hash.to_owned());
+            }
+        }
+        let mut txs = Vec::with_capacity(hashes.len());
+        for hash in hashes {
+            if let Some(tx) = self.read_tx(&hash)? {
+                txs.push(tx);
+            }
+        }
+        txs.sort_by(|a, b| (a.height, &a.hash).cmp(&(b.height, &b.hash)));
+        Ok(txs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tx_event(
+        event_type: &str,
+        hash: &str,
+        height: u64,
+        changed_keys: &[&str],
+        addresses: &[&str],
+    ) -> StreamEvent {
+        let mut attributes = HashMap::new();
+        attributes.insert("hash".to_owned(), hash.to_owned());
+        attributes.insert("code".to_owned(), "0".to_owned());
+        attributes.insert("gas_used".to_owned(), "10".to_owned());
+        StreamEvent {
+            event_type: event_type.to_owned(),
+            height,
+            attributes,
+            changed_keys: changed_keys.iter().map(|k| k.to_string()).collect(),
+            addresses: addresses.iter().map(|a| a.to_string()).collect(),
+            storage_changes: vec![],
+        }
+    }
+
+    #[test]
+    fn test_index_and_query() {
+        let dir = tempfile::tempdir().unwrap();
+        let indexer = Indexer::open(dir.path()).unwrap();
+
+        let mut accepted = tx_event("accepted", "h1", 1, &[], &[]);
+        accepted
+            .attributes
+            .insert("sender".to_owned(), "atest1".to_owned());
+        indexer.index_block(&[accepted]).unwrap();
+        let applied = tx_event(
+            "applied",
+            "h1",
+            2,
+            &["#atest2/balance/#atest3"],
+            &["atest2", "atest3"],
+        );
+        let other = tx_event("applied", "h2", 2, &["#atest4/x"], &["atest4"]);
+        indexer.index_block(&[applied, other]).unwrap();
+
+        // The events of the same tx are merged
+        let tx = indexer.read_tx("h1").unwrap().unwrap();
+        assert_eq!(tx.height, 2);
+        assert_eq!(tx.sender.as_deref(), Some("atest1"));
+        assert_eq!(tx.gas_used, 10);
+        assert_eq!(tx.events.len(), 2);
+
+        let txs = indexer.txs_by_address("atest3").unwrap();
+        assert_eq!(txs, vec![tx.clone()]);
+        assert!(indexer.txs_by_address("atest").unwrap().is_empty());
+
+        let txs = indexer.txs_by_key_prefix("#atest2/balance").unwrap();
+        assert_eq!(txs, vec![tx]);
+        let txs = indexer.txs_by_key_prefix("#atest").unwrap();
+        assert_eq!(
+            txs.iter().map(|tx| tx.hash.as_str()).collect::<Vec<_>>(),
+            vec!["h1", "h2"]
+        );
+    }
+
+    #[test]
+    fn test_index_by_tx_hash() {
+        let dir = tempfile::tempdir().unwrap();
+        let indexer = Indexer::open(dir.path()).unwrap();
+
+        // The wrapper's event has its own hash, but the same canonical hash as
+        // the event of the inner tx
+        let mut accepted = tx_event("accepted", "w1", 1, &[], &[]);
+        accepted
+            .attributes
+            .insert("tx_hash".to_owned(), "h1".to_owned());
+        let mut applied = tx_event("applied", "h1", 2, &["#atest2/x"], &[]);
+        applied
+            .attributes
+            .insert("tx_hash".to_owned(), "h1".to_owned());
+        indexer.index_block(&[accepted, applied]).unwrap();
+
+        let tx = indexer.read_tx("h1").unwrap().unwrap();
+        assert_eq!(tx.events.len(), 2);
+        assert_eq!(tx.height, 2);
+        assert!(indexer.read_tx("w1").unwrap().is_none());
+    }
+}
@@ -1,38 +1,61 @@
+#[cfg(unix)]
+mod abci_unix;
+pub mod bench;
+pub mod block_results;
 mod broadcaster;
+mod event_stream;
 pub mod events;
+#[cfg(feature = "grpc")]
+mod grpc;
+pub mod indexer;
 pub mod protocol;
+pub mod read_only;
+mod rejected_txs;
+pub mod replay;
 pub mod rpc;
 mod shell;
 mod shims;
+mod status;
 pub mod storage;
 pub mod tendermint_node;
 
 use std::convert::TryInto;
-use std::net::SocketAddr;
 use std::path::PathBuf;
 use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
 
 use anoma::ledger::governance::storage as gov_storage;
+use anoma::types::chain::ChainId;
 use anoma::types::storage::Key;
 use byte_unit::Byte;
 use futures::future::TryFutureExt;
 use once_cell::unsync::Lazy;
+#[cfg(any(test, feature = "testing"))]
+pub use shell::test_utils;
 use sysinfo::{RefreshKind, System, SystemExt};
 #[cfg(not(feature = "ABCI"))]
-use tendermint_proto::abci::CheckTxType;
+use tendermint_config::net::Address as TendermintAddress;
+#[cfg(feature = "ABCI")]
+use tendermint_config_abci::net::Address as TendermintAddress;
+#[cfg(not(feature = "ABCI"))]
+use tendermint_proto::abci::{CheckTxType, RequestCheckTx};
 #[cfg(feature = "ABCI")]
-use tendermint_proto_abci::abci::CheckTxType;
+use tendermint_proto_abci::abci::{CheckTxType, RequestCheckTx};
 use tower::ServiceBuilder;
 #[cfg(not(feature = "ABCI"))]
 use tower_abci::{response, split, Server};
 #[cfg(feature = "ABCI")]
 use tower_abci_old::{response, split, Server};
 
+use self::rejected_txs::{RejectedTxs, RejectionStage};
 use self::shims::abcipp_shim::AbciService;
+use crate::cli::args;
 use crate::config::utils::num_of_threads;
 use crate::config::TendermintMode;
 use crate::node::ledger::broadcaster::Broadcaster;
-use crate::node::ledger::shell::{Error, MempoolTxType, Shell};
+use crate::node::ledger::shell::{Error, MempoolLimits, MempoolTxType, Shell};
 use crate::node::ledger::shims::abcipp_shim::AbcippShim;
 use crate::node::ledger::shims::abcipp_shim_types::shim::{Request, Response};
 use crate::{config, wasm_loader};
@@ -43,6 +66,9 @@ const ENV_VAR_TOKIO_THREADS: &str = "ANOMA_TOKIO_THREADS";
 /// Env. var to set a number of Rayon global worker threads
 const ENV_VAR_RAYON_THREADS: &str = "ANOMA_RAYON_THREADS";
 
+/// How long to wait on shutdown for the shell to finish the in-flight block
+const SHELL_SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(30);
+
 // Until ABCI++ is ready, the shim provides the service implementation.
 // We will add this part back in once the shim is no longer needed.
 //```
@@ -90,11 +116,20 @@ impl Shell {
     }
 
     fn call(&mut self, req: Request) -> Result<Response, Error> {
+        let span = tracing::info_span!(
+            "abci",
+            request = req.name(),
+            last_height = self.storage.last_height.0,
+        );
+        let _entered = span.enter();
         match req {
             Request::InitChain(init) => {
                 self.init_chain(init).map(Response::InitChain)
             }
-            Request::Info(_) => Ok(Response::Info(self.last_state())),
+            Request::Info(info) => {
+                tracing::info!("Tendermint version: {}", info.version);
+                Ok(Response::Info(self.last_state()))
+            }
             Request::Query(query) => Ok(Response::Query(self.query(query))),
             #[cfg(not(feature = "ABCI"))]
             Request::PrepareProposal(block) => {
@@ -116,6 +151,12 @@ impl Shell {
                 }
             }
             #[cfg(not(feature = "ABCI"))]
+            Request::ProcessBlockProposal(block) => {
+                Ok(Response::ProcessBlockProposal(
+                    self.process_block_proposal(block),
+                ))
+            }
+            #[cfg(not(feature = "ABCI"))]
             Request::RevertProposal(_req) => {
                 Ok(Response::RevertProposal(self.revert_proposal(_req)))
             }
@@ -131,20 +172,12 @@ impl Shell {
                 self.load_proposals();
                 self.finalize_block(finalize).map(Response::FinalizeBlock)
             }
-            Request::Commit(_) => Ok(Response::Commit(self.commit())),
+            Request::Commit(_) => self.commit().map(Response::Commit),
             Request::Flush(_) => Ok(Response::Flush(Default::default())),
             Request::Echo(msg) => Ok(Response::Echo(response::Echo {
                 message: msg.message,
             })),
-            Request::CheckTx(tx) => {
-                let r#type = match CheckTxType::from_i32(tx.r#type)
-                    .expect("received unexpected CheckTxType from ABCI")
-                {
-                    CheckTxType::New => MempoolTxType::NewTransaction,
-                    CheckTxType::Recheck => MempoolTxType::RecheckTransaction,
-                };
-                Ok(Response::CheckTx(self.mempool_validate(&*tx.tx, r#type)))
-            }
+            Request::CheckTx(tx) => Ok(Response::CheckTx(self.check_tx(&tx))),
             Request::ListSnapshots(_) => {
                 Ok(Response::ListSnapshots(Default::default()))
             }
@@ -161,6 +194,60 @@ impl Shell {
     }
 }
 
+impl Shell {
+    /// Answer a `CheckTx` request from Tendermint's mempool. The txs
+    /// re-checked after a block is committed are re-validated against the new
+    /// state. The rejected txs are added to the log of the rejected txs.
+    fn check_tx(&self, tx: &RequestCheckTx) -> response::CheckTx {
+        let (stage, response) = match Self::mempool_tx_type(tx) {
+            MempoolTxType::NewTransaction => (
+                RejectionStage::Mempool,
+                Self::check_new_tx(
+                    tx,
+                    &self.storage.chain_id,
+                    &self.mempool_limits,
+                ),
+            ),
+            MempoolTxType::RecheckTransaction => (
+                RejectionStage::MempoolRecheck,
+                self.mempool_recheck(&*tx.tx),
+            ),
+        };
+        self.rejected_txs.record_check_tx(
+            stage,
+            &*tx.tx,
+            response.code,
+            &response.log,
+        );
+        response
+    }
+
+    /// Answer a `CheckTx` request for a new tx. Unlike the other requests, it
+    /// doesn't need the shell's state, so the [`AbciService`] answers it
+    /// without waiting for the shell to finish executing a block.
+    fn check_new_tx(
+        tx: &RequestCheckTx,
+        chain_id: &ChainId,
+        limits: &MempoolLimits,
+    ) -> response::CheckTx {
+        Self::mempool_validate(
+            &*tx.tx,
+            MempoolTxType::NewTransaction,
+            chain_id,
+            limits,
+        )
+    }
+
+    fn mempool_tx_type(tx: &RequestCheckTx) -> MempoolTxType {
+        match CheckTxType::from_i32(tx.r#type)
+            .expect("received unexpected CheckTxType from ABCI")
+        {
+            CheckTxType::New => MempoolTxType::NewTransaction,
+            CheckTxType::Recheck => MempoolTxType::RecheckTransaction,
+        }
+    }
+}
+
 /// Run the ledger with an async runtime
 pub fn run(config: config::Ledger, wasm_dir: PathBuf) {
     let logical_cores = num_cpus::get();
@@ -200,8 +287,30 @@ pub fn run(config: config::Ledger, wasm_dir: PathBuf) {
 }
 
 /// Resets the tendermint_node state and removes database files
-pub fn reset(config: config::Ledger) -> Result<(), shell::Error> {
-    shell::reset(config)
+pub fn reset(
+    config: config::Ledger,
+    args: args::LedgerReset,
+) -> Result<(), shell::Error> {
+    shell::reset(config, args)
+}
+
+/// Replays the chain's blocks against a fresh state to find the first block
+/// whose app hash diverges
+pub fn replay(
+    config: config::Ledger,
+    wasm_dir: PathBuf,
+    args: args::LedgerReplay,
+) -> Result<(), replay::Error> {
+    replay::replay(config, wasm_dir, args)
+}
+
+/// Runs a read-only node that serves queries and event subscriptions from the
+/// DB of a running node
+pub fn run_read_only(
+    config: config::Ledger,
+    args: args::LedgerRunReadOnly,
+) -> Result<(), read_only::Error> {
+    read_only::run(config, args)
 }
 
 /// Runs three concurrent tasks: A tendermint node, a shell which contains an
@@ -270,6 +379,11 @@ async fn run_aux(config: config::Ledger, wasm_dir: PathBuf) {
             .get_appropriate_unit(true)
     );
 
+    if config.shell.wasm_log {
+        tracing::info!("WASM log_string messages are enabled");
+    }
+    anoma::vm::host_env::set_wasm_log_enabled(config.shell.wasm_log);
+
     // Setup DB cache, it must outlive the DB instance that's in the shell
     let block_cache_size_bytes = match config.shell.block_cache_bytes {
         Some(block_cache_bytes) => {
@@ -372,18 +486,86 @@ async fn run_aux(config: config::Ledger, wasm_dir: PathBuf) {
         None
     };
 
+    // Start the gRPC query server, if configured. Its failure doesn't stop
+    // the ledger.
+    #[cfg(feature = "grpc")]
+    let grpc = config.shell.grpc_address.map(|grpc_address| {
+        let tm_rpc_address = TendermintAddress::from_str(
+            &config.tendermint.rpc_address.to_string(),
+        )
+        .expect("Tendermint RPC address must be valid");
+        tokio::spawn(async move {
+            if let Err(err) = grpc::run(grpc_address, tm_rpc_address).await {
+                tracing::error!("gRPC query server error: {}", err);
+            }
+        })
+    });
+
+    // The log of the rejected txs is served by the status server
+    let rejected_txs = Arc::new(RejectedTxs::new(&config.shell));
+
+    // Start the status server, if configured. Its failure doesn't stop the
+    // ledger.
+    let status = config.shell.status_address.map(|status_address| {
+        let tm_rpc_address = TendermintAddress::from_str(
+            &config.tendermint.rpc_address.to_string(),
+        )
+        .expect("Tendermint RPC address must be valid");
+        let chain_id = config.chain_id.clone();
+        let db_dir = config.db_dir();
+        let tendermint_dir = config.tendermint_dir();
+        let rejected_txs = rejected_txs.clone();
+        tokio::spawn(async move {
+            if let Err(err) = status::run(
+                status_address,
+                tm_rpc_address,
+                chain_id,
+                db_dir,
+                tendermint_dir,
+                rejected_txs,
+            )
+            .await
+            {
+                tracing::error!("Status server error: {}", err);
+            }
+        })
+    });
+
+    // Start the event stream server, if configured. Its failure doesn't stop
+    // the ledger.
+    let (event_stream, event_stream_sender) =
+        match config.shell.event_stream_address {
+            Some(event_stream_address) => {
+                let (sender, receiver) = tokio::sync::mpsc::unbounded_channel();
+                let event_stream = tokio::spawn(async move {
+                    if let Err(err) =
+                        event_stream::run(event_stream_address, receiver).await
+                    {
+                        tracing::error!("Event stream server error: {}", err);
+                    }
+                });
+                (Some(event_stream), Some(sender))
+            }
+            None => (None, None),
+        };
+
     // Construct our ABCI application.
-    let ledger_address = config.shell.ledger_address;
+    let ledger_address = config.shell.ledger_address.clone();
+    let shell_shutdown = Arc::new(AtomicBool::new(false));
     let (shell, abci_service) = AbcippShim::new(
         config,
         wasm_dir,
         broadcaster_sender,
+        event_stream_sender,
         &db_cache,
         vp_wasm_compilation_cache,
         tx_wasm_compilation_cache,
+        rejected_txs,
+        shell_shutdown.clone(),
     );
 
     // Start the ABCI server
+    let abort_send_for_shell = abort_send.clone();
     let abci = tokio::spawn(async move {
         // On panic or exit, the `Drop` of `AbortSender` will send abort
         // message
@@ -403,16 +585,49 @@ async fn run_aux(config: config::Ledger, wasm_dir: PathBuf) {
         std::thread::Builder::new().name("ledger-shell".into());
     let shell_handler = thread_builder
         .spawn(move || {
+            // The shell stops on its own when a block fails to commit, in
+            // which case the other tasks are shut down too
+            let aborter = Aborter {
+                sender: abort_send_for_shell,
+                who: "Shell",
+            };
             tracing::info!("Anoma ledger node started.");
-            shell.run()
+            shell.run();
+            drop(aborter);
         })
         .expect("Must be able to start a thread for the shell");
 
     // Wait for interrupt signal or abort message
     let aborted = wait_for_abort(abort_recv).await;
 
+    // Ask the shell to stop accepting ABCI requests once the in-flight block,
+    // if any, is committed, and wait for it while the ABCI server is still
+    // running
+    shell_shutdown.store(true, Ordering::SeqCst);
+    let mut shell_handler =
+        tokio::task::spawn_blocking(move || shell_handler.join());
+    if tokio::time::timeout(SHELL_SHUTDOWN_TIMEOUT, &mut shell_handler)
+        .await
+        .is_err()
+    {
+        tracing::warn!(
+            "The shell didn't finish the in-flight block in time, shutting \
+             down..."
+        );
+    }
+
     // Abort the ABCI service task
     abci.abort();
+    #[cfg(feature = "grpc")]
+    if let Some(grpc) = grpc {
+        grpc.abort();
+    }
+    if let Some(status) = status {
+        status.abort();
+    }
+    if let Some(event_stream) = event_stream {
+        event_stream.abort();
+    }
 
     // Shutdown tendermint_node via a message to ensure that the child process
     // is properly cleaned-up.
@@ -465,19 +680,41 @@ async fn run_aux(config: config::Ledger, wasm_dir: PathBuf) {
         }
     }
 
-    tracing::info!("Anoma ledger node has shut down.");
-
-    if let Err(err) = shell_handler.join() {
-        std::panic::resume_unwind(err)
+    // The shell stops once the ABCI service is dropped
+    match shell_handler.await {
+        Ok(Ok(())) => {}
+        Ok(Err(err)) => std::panic::resume_unwind(err),
+        Err(err) => tracing::error!("Failed to join the shell: {}", err),
     }
+
+    tracing::info!("Anoma ledger node has shut down.");
 }
 
 /// Runs the an asynchronous ABCI server with four sub-components for consensus,
-/// mempool, snapshot, and info.
+/// mempool, snapshot, and info. When the ledger address is a Unix domain
+/// socket, the requests are served by the [`abci_unix`] server instead.
 async fn run_abci(
     abci_service: AbciService,
-    ledger_address: SocketAddr,
+    ledger_address: TendermintAddress,
 ) -> shell::Result<()> {
+    let (host, port) = match ledger_address {
+        TendermintAddress::Tcp { host, port, .. } => (host, port),
+        // The tower-abci server only listens on TCP
+        #[cfg(unix)]
+        TendermintAddress::Unix { path } => {
+            return abci_unix::listen(abci_service, path)
+                .await
+                .map_err(|err| Error::TowerServer(err.to_string()));
+        }
+        #[cfg(not(unix))]
+        TendermintAddress::Unix { path } => {
+            return Err(Error::TowerServer(format!(
+                "Unix domain sockets are not supported on this platform: {}",
+                path
+            )));
+        }
+    };
+
     // Split it into components.
     let (consensus, mempool, snapshot, info) = split::service(abci_service, 5);
 
@@ -504,7 +741,7 @@ async fn run_abci(
 
     // Run the server with the ABCI service
     server
-        .listen(ledger_address)
+        .listen(format!("{}:{}", host, port))
         .await
         .map_err(|err| Error::TowerServer(err.to_string()))
 }
@@ -1,6 +1,7 @@
 //! The ledger's protocol
-use std::collections::BTreeSet;
-use std::panic;
+use std::any::Any;
+use std::collections::{BTreeSet, HashMap};
+use std::panic::{self, AssertUnwindSafe};
 
 use anoma::ledger::eth_bridge::vp::EthBridge;
 use anoma::ledger::gas::{self, BlockGasMeter, VpGasMeter};
@@ -9,16 +10,25 @@ use anoma::ledger::ibc::vp::{Ibc, IbcToken};
 use anoma::ledger::native_vp::{self, NativeVp};
 use anoma::ledger::parameters::{self, ParametersVp};
 use anoma::ledger::pos::{self, PosVP};
-use anoma::ledger::storage::write_log::WriteLog;
+use anoma::ledger::storage::write_log::{
+    SpeculativeTx, StorageModification, WriteLog,
+};
 use anoma::ledger::storage::{DBIter, Storage, StorageHasher, DB};
+use anoma::ledger::token::{self, TokenVp};
 use anoma::ledger::treasury::TreasuryVp;
 use anoma::proto::{self, Tx};
 use anoma::types::address::{Address, InternalAddress};
+use anoma::types::key::{self, common};
 use anoma::types::storage;
-use anoma::types::transaction::{DecryptedTx, TxResult, TxType, VpsResult};
+use anoma::types::transaction::{
+    hash_tx, DecryptedTx, TxBatch, TxResult, TxType, VpsResult,
+};
 use anoma::vm::wasm::{TxCache, VpCache};
 use anoma::vm::{self, wasm, WasmCacheAccess};
-use rayon::iter::{IntoParallelRefIterator, ParallelIterator};
+use borsh::BorshDeserialize;
+use rayon::iter::{
+    IntoParallelIterator, IntoParallelRefIterator, ParallelIterator,
+};
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -29,12 +39,22 @@ pub enum Error {
     TxDecodingError(proto::Error),
     #[error("Transaction runner error: {0}")]
     TxRunnerError(vm::wasm::run::Error),
+    #[error("Transaction runner panicked: {0}")]
+    TxRunnerPanic(String),
     #[error("Txs must either be encrypted or a decryption of an encrypted tx")]
     TxTypeError,
+    #[error("Error decoding a tx batch: {0}")]
+    TxBatchError(std::io::Error),
+    #[error("Error reading the parameters: {0}")]
+    ParametersReadError(parameters::ReadError),
+    #[error("The code {0} of an inner tx of the batch isn't whitelisted")]
+    TxNotWhitelisted(String),
     #[error("Gas error: {0}")]
     GasError(gas::Error),
     #[error("Error executing VP for addresses: {0:?}")]
     VpRunnerError(vm::wasm::run::Error),
+    #[error("VP runner panicked: {0}")]
+    VpRunnerPanic(String),
     #[error("The address {0} doesn't exist")]
     MissingAddress(Address),
     #[error("IBC native VP: {0}")]
@@ -53,12 +73,25 @@ pub enum Error {
     TreasuryNativeVpError(anoma::ledger::treasury::Error),
     #[error("Ethereum bridge native VP error: {0}")]
     EthBridgeNativeVpError(anoma::ledger::eth_bridge::vp::Error),
+    #[error("Token native VP error: {0}")]
+    TokenNativeVpError(token::Error),
     #[error("Access to an internal address {0} is forbidden")]
     AccessForbidden(InternalAddress),
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
 
+/// The result of a tx applied speculatively with [`apply_txs_speculatively`]
+#[derive(Debug)]
+pub struct SpeculativeResult {
+    /// The result of [`apply_tx`]
+    result: Result<TxResult>,
+    /// The gas of the tx that hasn't been finalized because the tx failed
+    unfinalized_gas: u64,
+    /// The changes of the tx and the keys that it read
+    tx: SpeculativeTx,
+}
+
 /// Apply a given transaction
 ///
 /// The only Tx Types that should be input here are `Decrypted` and `Wrapper`
@@ -80,6 +113,7 @@ where
     H: 'static + StorageHasher + Sync,
     CA: 'static + WasmCacheAccess + Sync,
 {
+    let _span = tracing::debug_span!("apply_tx", tx_length).entered();
     // Base gas cost for applying the tx
     block_gas_meter
         .add_base_transaction_fee(tx_length)
@@ -95,6 +129,11 @@ where
                 vp_wasm_cache,
                 tx_wasm_cache,
             )?;
+            // Hashing the tx's changes into the Merkle tree is charged before
+            // the VPs are run
+            block_gas_meter
+                .add_merkle_tree_fee(write_log.num_merkle_tree_updates())
+                .map_err(Error::GasError)?;
 
             let vps_result = check_vps(
                 &tx,
@@ -110,14 +149,14 @@ where
                 .map_err(Error::GasError)?;
             let initialized_accounts = write_log.get_initialized_accounts();
             let changed_keys = write_log.get_keys();
-            let ibc_event = write_log.take_ibc_event();
+            let ibc_events = write_log.take_ibc_events();
 
             Ok(TxResult {
                 gas_used,
                 changed_keys,
                 vps_result,
                 initialized_accounts,
-                ibc_event,
+                ibc_events,
             })
         }
         _ => {
@@ -132,7 +171,91 @@ where
     }
 }
 
+/// Apply the given txs speculatively in parallel, each against a copy of the
+/// write log with the changes of the block before any of them, with
+/// [`apply_tx`]. The txs are given with their index in the block and their
+/// length. The results, by the txs' indices, must be merged in the order of
+/// the txs with [`merge_speculative_tx`].
+pub fn apply_txs_speculatively<D, H, CA>(
+    txs: Vec<(usize, TxType, usize)>,
+    write_log: &WriteLog,
+    storage: &Storage<D, H>,
+    vp_wasm_cache: &VpCache<CA>,
+    tx_wasm_cache: &TxCache<CA>,
+) -> HashMap<usize, SpeculativeResult>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+    CA: 'static + WasmCacheAccess + Sync,
+{
+    // The txs run on the rayon threads, so their spans need an explicit parent
+    let parent = tracing::Span::current();
+    txs.into_par_iter()
+        .map(|(index, tx, tx_length)| {
+            let _span =
+                tracing::debug_span!(parent: &parent, "speculative_tx", index)
+                    .entered();
+            let mut gas_meter = BlockGasMeter::default();
+            let mut write_log = write_log.speculate();
+            let result = apply_tx(
+                tx,
+                tx_length,
+                &mut gas_meter,
+                &mut write_log,
+                storage,
+                &mut vp_wasm_cache.clone(),
+                &mut tx_wasm_cache.clone(),
+            );
+            let result = SpeculativeResult {
+                result,
+                unfinalized_gas: gas_meter.get_current_transaction_gas(),
+                tx: write_log.into_speculative_tx(),
+            };
+            (index, result)
+        })
+        .collect()
+}
+
+/// Merge the result of a tx applied speculatively into the block as if the
+/// tx was applied with [`apply_tx`] now. Returns `None` if the tx read any
+/// storage key changed since it was applied, or if the gas meter has
+/// unfinalized gas of a failed tx that would be added to the gas of this tx,
+/// in which case the tx must be applied again.
+pub fn merge_speculative_tx(
+    speculative: SpeculativeResult,
+    block_gas_meter: &mut BlockGasMeter,
+    write_log: &mut WriteLog,
+) -> Option<Result<TxResult>> {
+    if block_gas_meter.get_current_transaction_gas() != 0
+        || !write_log.merge_speculative_tx(speculative.tx)
+    {
+        return None;
+    }
+    let result = match speculative.result {
+        Ok(mut result) => {
+            // The tx's gas is added to the block's gas, which may exceed the
+            // block's gas limit
+            block_gas_meter
+                .add(result.gas_used)
+                .and_then(|()| block_gas_meter.finalize_transaction())
+                .map(|gas_used| {
+                    result.gas_used = gas_used;
+                    result
+                })
+                .map_err(Error::GasError)
+        }
+        Err(err) => {
+            // The error has been already caused by the tx's gas, if by any
+            let _ = block_gas_meter.add(speculative.unfinalized_gas);
+            Err(err)
+        }
+    };
+    Some(result)
+}
+
 /// Execute a transaction code. Returns verifiers requested by the transaction.
+/// The inner txs of a batch are executed in order and the verifiers requested
+/// by any of them are returned.
 fn execute_tx<D, H, CA>(
     tx: &Tx,
     storage: &Storage<D, H>,
@@ -141,25 +264,96 @@ fn execute_tx<D, H, CA>(
     vp_wasm_cache: &mut VpCache<CA>,
     tx_wasm_cache: &mut TxCache<CA>,
 ) -> Result<BTreeSet<Address>>
+where
+    D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
+    H: 'static + StorageHasher + Sync,
+    CA: 'static + WasmCacheAccess + Sync,
+{
+    let _span = tracing::debug_span!("execute_tx").entered();
+    if !TxBatch::is_batch(tx) {
+        return run_tx_code(
+            &tx.code,
+            tx.data.as_deref().unwrap_or_default(),
+            storage,
+            gas_meter,
+            write_log,
+            vp_wasm_cache,
+            tx_wasm_cache,
+        );
+    }
+    let inner_txs =
+        TxBatch::signed_inner_txs(tx).map_err(Error::TxBatchError)?;
+    // The batch itself has no code, so the VPs can only check the whitelist
+    // against its empty code. The code of every inner tx is checked here,
+    // before any of them is run.
+    let (whitelist, gas) = parameters::read_tx_whitelist(storage)
+        .map_err(Error::ParametersReadError)?;
+    gas_meter.add(gas).map_err(Error::GasError)?;
+    if !whitelist.is_empty() {
+        for inner_tx in &inner_txs {
+            let code_hash = hash_tx(&inner_tx.code).to_string();
+            if !whitelist.contains(&code_hash) {
+                return Err(Error::TxNotWhitelisted(code_hash));
+            }
+        }
+    }
+    let mut verifiers = BTreeSet::new();
+    for (index, inner_tx) in inner_txs.iter().enumerate() {
+        let _span = tracing::debug_span!("inner_tx", index).entered();
+        // The changes of all the inner txs accumulate in the same write log,
+        // so a failure of any of them drops the whole batch
+        verifiers.extend(run_tx_code(
+            &inner_tx.code,
+            inner_tx.data.as_deref().unwrap_or_default(),
+            storage,
+            gas_meter,
+            write_log,
+            vp_wasm_cache,
+            tx_wasm_cache,
+        )?);
+        // The accounts initialized by this inner tx can only be modified by
+        // the following ones with the approval of their VPs
+        write_log.end_inner_tx();
+    }
+    Ok(verifiers)
+}
+
+/// Run a tx code with the given data.
+fn run_tx_code<D, H, CA>(
+    code: &[u8],
+    data: &[u8],
+    storage: &Storage<D, H>,
+    gas_meter: &mut BlockGasMeter,
+    write_log: &mut WriteLog,
+    vp_wasm_cache: &mut VpCache<CA>,
+    tx_wasm_cache: &mut TxCache<CA>,
+) -> Result<BTreeSet<Address>>
 where
     D: 'static + DB + for<'iter> DBIter<'iter> + Sync,
     H: 'static + StorageHasher + Sync,
     CA: 'static + WasmCacheAccess + Sync,
 {
     gas_meter
-        .add_compiling_fee(tx.code.len())
+        .add_compiling_fee(code.len())
         .map_err(Error::GasError)?;
-    let empty = vec![];
-    let tx_data = tx.data.as_ref().unwrap_or(&empty);
-    wasm::run::tx(
-        storage,
-        write_log,
-        gas_meter,
-        &tx.code,
-        tx_data,
-        vp_wasm_cache,
-        tx_wasm_cache,
-    )
+    // A panic in the runner must not crash the ledger. The caller is
+    // responsible for dropping the tx's write log on failure.
+    panic::catch_unwind(AssertUnwindSafe(|| {
+        wasm::run::tx(
+            storage,
+            write_log,
+            gas_meter,
+            code,
+            data,
+            vp_wasm_cache,
+            tx_wasm_cache,
+        )
+    }))
+    .map_err(|err| {
+        let msg = panic_message(err);
+        tracing::error!("Transaction runner panicked with {}", msg);
+        Error::TxRunnerPanic(msg)
+    })?
     .map_err(Error::TxRunnerError)
 }
 
@@ -200,6 +394,28 @@ where
     Ok(vps_result)
 }
 
+/// Check that a public key revealed for an implicit address, if any, is the
+/// key from which the address is derived.
+fn is_valid_pk_reveal(
+    addr: &Address,
+    keys_changed: &BTreeSet<storage::Key>,
+    write_log: &WriteLog,
+) -> bool {
+    let pk_key = key::pk_key(addr);
+    if !keys_changed.contains(&pk_key) {
+        return true;
+    }
+    match write_log.read(&pk_key).0 {
+        Some(StorageModification::Write { value }) => {
+            match common::PublicKey::try_from_slice(value) {
+                Ok(pk) => &Address::from(&pk) == addr,
+                Err(_) => false,
+            }
+        }
+        _ => false,
+    }
+}
+
 /// Execute verifiers' validity predicates
 fn execute_vps<D, H, CA>(
     verifiers: BTreeSet<Address>,
@@ -215,17 +431,58 @@ where
     H: 'static + StorageHasher + Sync,
     CA: 'static + WasmCacheAccess + Sync,
 {
+    // The VPs run on the rayon threads, so their spans need an explicit parent
+    let parent = tracing::Span::current();
     verifiers
         .par_iter()
-        // TODO temporary pending on <https://github.com/anoma/anoma/issues/193>
-        .filter(|addr| !matches!(addr, Address::Implicit(_)))
         .try_fold(VpsResult::default, |mut result, addr| {
-            let mut gas_meter = VpGasMeter::new(initial_gas);
+            let _span =
+                tracing::debug_span!(parent: &parent, "vp", address = %addr)
+                    .entered();
+            // The gas budget interrupts a VP that runs for too long
+            let mut gas_meter =
+                VpGasMeter::new_with_budget(initial_gas, gas::VP_GAS_BUDGET);
+            // The native check of a token runs in addition to the VP stored
+            // in its account, which may be upgraded by governance
+            let token_accept = if token::is_native_token(addr) {
+                let ctx = native_vp::Ctx::new(
+                    storage,
+                    write_log,
+                    tx,
+                    gas_meter,
+                    vp_wasm_cache.clone(),
+                );
+                let tx_data = match tx.data.as_ref() {
+                    Some(data) => &data[..],
+                    None => &[],
+                };
+                let token = TokenVp { ctx, token: addr };
+                let result = run_vp_catching_panic(|| {
+                    token
+                        .validate_tx(tx_data, &keys_changed, &verifiers)
+                        .map_err(Error::TokenNativeVpError)
+                });
+                gas_meter = token.ctx.gas_meter.into_inner();
+                result
+            } else {
+                Ok(true)
+            };
             let accept = match &addr {
+                // The stored VP isn't ran when the native check rejects the tx
+                _ if !matches!(token_accept, Ok(true)) => token_accept,
                 Address::Established(_) => {
-                    let (vp, gas) = storage
-                        .validity_predicate(addr)
-                        .map_err(Error::StorageError)?;
+                    // The VP of an account initialized by the tx is only in
+                    // the write log
+                    let vp_key = storage::Key::validity_predicate(addr);
+                    let (vp, gas) = match write_log.read(&vp_key) {
+                        (
+                            Some(StorageModification::InitAccount { vp }),
+                            gas,
+                        ) => (Some(vp.clone()), gas),
+                        _ => storage
+                            .validity_predicate(addr)
+                            .map_err(Error::StorageError)?,
+                    };
                     gas_meter.add(gas).map_err(Error::GasError)?;
                     let vp =
                         vp.ok_or_else(|| Error::MissingAddress(addr.clone()))?;
@@ -234,18 +491,20 @@ where
                         .add_compiling_fee(vp.len())
                         .map_err(Error::GasError)?;
 
-                    wasm::run::vp(
-                        vp,
-                        tx,
-                        addr,
-                        storage,
-                        write_log,
-                        &mut gas_meter,
-                        &keys_changed,
-                        &verifiers,
-                        vp_wasm_cache.clone(),
-                    )
-                    .map_err(Error::VpRunnerError)
+                    run_vp_catching_panic(|| {
+                        wasm::run::vp(
+                            vp,
+                            tx,
+                            addr,
+                            storage,
+                            write_log,
+                            &mut gas_meter,
+                            &keys_changed,
+                            &verifiers,
+                            vp_wasm_cache.clone(),
+                        )
+                        .map_err(Error::VpRunnerError)
+                    })
                 }
                 Address::Internal(internal_addr) => {
                     let ctx = native_vp::Ctx::new(
@@ -294,23 +553,35 @@ where
                         }
                         InternalAddress::Ibc => {
                             let ibc = Ibc { ctx };
-                            let result = ibc
-                                .validate_tx(tx_data, &keys_changed, &verifiers)
-                                .map_err(Error::IbcNativeVpError);
+                            let result = run_vp_catching_panic(|| {
+                                ibc.validate_tx(
+                                    tx_data,
+                                    &keys_changed,
+                                    &verifiers,
+                                )
+                                .map_err(Error::IbcNativeVpError)
+                            });
                             // Take the gas meter back out of the context
                             gas_meter = ibc.ctx.gas_meter.into_inner();
                             result
                         }
                         InternalAddress::Parameters => {
                             let parameters = ParametersVp { ctx };
-                            let result = parameters
-                                .validate_tx(tx_data, &keys_changed, &verifiers)
-                                .map_err(Error::ParametersNativeVpError);
+                            let result = run_vp_catching_panic(|| {
+                                parameters
+                                    .validate_tx(
+                                        tx_data,
+                                        &keys_changed,
+                                        &verifiers,
+                                    )
+                                    .map_err(Error::ParametersNativeVpError)
+                            });
                             // Take the gas meter back out of the context
                             gas_meter = parameters.ctx.gas_meter.into_inner();
                             result
                         }
-                        InternalAddress::PosSlashPool => {
+                        InternalAddress::PosSlashPool
+                        | InternalAddress::NativeToken => {
                             // Take the gas meter back out of the context
                             gas_meter = ctx.gas_meter.into_inner();
                             Err(Error::AccessForbidden(
@@ -319,17 +590,29 @@ where
                         }
                         InternalAddress::Governance => {
                             let governance = GovernanceVp { ctx };
-                            let result = governance
-                                .validate_tx(tx_data, &keys_changed, &verifiers)
-                                .map_err(Error::GovernanceNativeVpError);
+                            let result = run_vp_catching_panic(|| {
+                                governance
+                                    .validate_tx(
+                                        tx_data,
+                                        &keys_changed,
+                                        &verifiers,
+                                    )
+                                    .map_err(Error::GovernanceNativeVpError)
+                            });
                             gas_meter = governance.ctx.gas_meter.into_inner();
                             result
                         }
                         InternalAddress::Treasury => {
                             let treasury = TreasuryVp { ctx };
-                            let result = treasury
-                                .validate_tx(tx_data, &keys_changed, &verifiers)
-                                .map_err(Error::TreasuryNativeVpError);
+                            let result = run_vp_catching_panic(|| {
+                                treasury
+                                    .validate_tx(
+                                        tx_data,
+                                        &keys_changed,
+                                        &verifiers,
+                                    )
+                                    .map_err(Error::TreasuryNativeVpError)
+                            });
                             gas_meter = treasury.ctx.gas_meter.into_inner();
                             result
                         }
@@ -338,17 +621,29 @@ where
                         | InternalAddress::IbcMint => {
                             // validate the transfer
                             let ibc_token = IbcToken { ctx };
-                            let result = ibc_token
-                                .validate_tx(tx_data, &keys_changed, &verifiers)
-                                .map_err(Error::IbcTokenNativeVpError);
+                            let result = run_vp_catching_panic(|| {
+                                ibc_token
+                                    .validate_tx(
+                                        tx_data,
+                                        &keys_changed,
+                                        &verifiers,
+                                    )
+                                    .map_err(Error::IbcTokenNativeVpError)
+                            });
                             gas_meter = ibc_token.ctx.gas_meter.into_inner();
                             result
                         }
                         InternalAddress::EthBridge => {
                             let bridge = EthBridge { ctx };
-                            let result = bridge
-                                .validate_tx(tx_data, &keys_changed, &verifiers)
-                                .map_err(Error::EthBridgeNativeVpError);
+                            let result = run_vp_catching_panic(|| {
+                                bridge
+                                    .validate_tx(
+                                        tx_data,
+                                        &keys_changed,
+                                        &verifiers,
+                                    )
+                                    .map_err(Error::EthBridgeNativeVpError)
+                            });
                             gas_meter = bridge.ctx.gas_meter.into_inner();
                             result
                         }
@@ -356,8 +651,11 @@ where
 
                     accepted
                 }
-                // TODO temporary pending on <https://github.com/anoma/anoma/issues/193>
-                Address::Implicit(_) => unreachable!(),
+                Address::Implicit(_) => {
+                    // TODO the validation of other changes is temporarily
+                    // skipped, pending on <https://github.com/anoma/anoma/issues/193>
+                    Ok(is_valid_pk_reveal(addr, &keys_changed, write_log))
+                }
             };
 
             // Returning error from here will short-circuit the VP parallel
@@ -388,6 +686,27 @@ where
         })
 }
 
+/// Run a VP and convert a panic, if any, into an error so that it cannot
+/// crash the ledger.
+fn run_vp_catching_panic(run: impl FnOnce() -> Result<bool>) -> Result<bool> {
+    panic::catch_unwind(AssertUnwindSafe(run)).unwrap_or_else(|err| {
+        let msg = panic_message(err);
+        tracing::error!("VP runner panicked with {}", msg);
+        Err(Error::VpRunnerPanic(msg))
+    })
+}
+
+/// Get a message from a panic's payload
+fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    match payload.downcast::<&str>() {
+        Ok(msg) => msg.to_string(),
+        Err(payload) => match payload.downcast::<String>() {
+            Ok(msg) => *msg,
+            Err(_) => "unknown panic payload".to_string(),
+        },
+    }
+}
+
 /// Merge VP results from parallel runs
 fn merge_vp_results(
     a: VpsResult,
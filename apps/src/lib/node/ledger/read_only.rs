@@ -0,0 +1,214 @@
+//! A read-only ledger node that serves queries and event subscriptions from
+//! the DB of a running node, without taking part in consensus, so that heavy
+//! query traffic doesn't slow down the node's block processing. The DB, or a
+//! replica of it, is opened as a secondary instance that periodically catches
+//! up with the changes committed by the node.
+//!
+//! The queries are answered by the gRPC query server and the events of the
+//! newly committed blocks, read from their persisted results, are sent to the
+//! event stream server, at the addresses set in the configuration. The storage
+//! keys changed by the txs are not persisted with their events, so the stream
+//! events of the txs can't be matched by the changed keys or addresses, unlike
+//! the events with the storage changes of the blocks.
+
+use std::time::Duration;
+
+use anoma::ledger::storage::Error as StorageError;
+use anoma::types::storage::BlockHeight;
+use borsh::BorshDeserialize;
+use thiserror::Error;
+use tokio::sync::mpsc::UnboundedSender;
+
+use super::block_results::BlockResults;
+use super::event_stream::{self, StreamEvent};
+#[cfg(feature = "grpc")]
+use super::grpc;
+use super::storage::{self, SnapshotReader};
+use super::{wait_for_abort, Aborter};
+use crate::cli::args;
+use crate::config;
+
+/// The interval of catching up with the changes committed by the node
+const CATCH_UP_INTERVAL: Duration = Duration::from_millis(500);
+
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("Error opening the DB: {0}")]
+    OpenDB(StorageError),
+    #[error(
+        "Neither the gRPC query server nor the event stream server is \
+         configured"
+    )]
+    NoServer,
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// Run a read-only node against the DB of the node with the given config, or
+/// against the DB in the given directory, until it's interrupted.
+pub fn run(
+    config: config::Ledger,
+    args: args::LedgerRunReadOnly,
+) -> Result<()> {
+    #[cfg(feature = "grpc")]
+    let has_server = config.shell.grpc_address.is_some()
+        || config.shell.event_stream_address.is_some();
+    #[cfg(not(feature = "grpc"))]
+    let has_server = config.shell.event_stream_address.is_some();
+    if !has_server {
+        return Err(Error::NoServer);
+    }
+    let db_dir = args.db_dir.unwrap_or_else(|| config.db_dir());
+    let reader = storage::open_secondary(&db_dir, config.read_only_db_dir())
+        .map_err(Error::OpenDB)?;
+    tokio::runtime::Builder::new_multi_thread()
+        .thread_name("ledger-read-only-worker")
+        .enable_all()
+        .build()
+        .unwrap()
+        .block_on(run_aux(config, reader));
+    Ok(())
+}
+
+async fn run_aux(config: config::Ledger, reader: SnapshotReader) {
+    // Channel for signalling shut down when the DB follower stops
+    let (abort_send, abort_recv) =
+        tokio::sync::mpsc::unbounded_channel::<&'static str>();
+
+    // Start the gRPC query server, if configured
+    #[cfg(feature = "grpc")]
+    let grpc = config.shell.grpc_address.map(|grpc_address| {
+        let reader = reader.clone();
+        tokio::spawn(async move {
+            if let Err(err) = grpc::run_read_only(grpc_address, reader).await {
+                tracing::error!("gRPC query server error: {}", err);
+            }
+        })
+    });
+
+    // Start the event stream server, if configured
+    let (event_stream, event_stream_sender) =
+        match config.shell.event_stream_address {
+            Some(event_stream_address) => {
+                let (sender, receiver) = tokio::sync::mpsc::unbounded_channel();
+                let event_stream = tokio::spawn(async move {
+                    if let Err(err) =
+                        event_stream::run(event_stream_address, receiver).await
+                    {
+                        tracing::error!("Event stream server error: {}", err);
+                    }
+                });
+                (Some(event_stream), Some(sender))
+            }
+            None => (None, None),
+        };
+
+    let follower = tokio::spawn(async move {
+        // On panic or exit, the `Drop` of `AbortSender` will send abort message
+        let aborter = Aborter {
+            sender: abort_send,
+            who: "DB follower",
+        };
+        follow(reader, event_stream_sender).await;
+        drop(aborter);
+    });
+    tracing::info!("Anoma read-only ledger node started.");
+
+    wait_for_abort(abort_recv).await;
+
+    follower.abort();
+    #[cfg(feature = "grpc")]
+    if let Some(grpc) = grpc {
+        grpc.abort();
+    }
+    if let Some(event_stream) = event_stream {
+        event_stream.abort();
+    }
+    tracing::info!("Anoma read-only ledger node has shut down.");
+}
+
+/// Periodically catch up with the changes committed by the node and send the
+/// events of the newly committed blocks to the event stream, if it's running.
+async fn follow(
+    reader: SnapshotReader,
+    events: Option<UnboundedSender<StreamEvent>>,
+) {
+    let mut last_height: Option<BlockHeight> = None;
+    let mut interval = tokio::time::interval(CATCH_UP_INTERVAL);
+    loop {
+        interval.tick().await;
+        let reader = reader.clone();
+        let read_events = events.is_some();
+        let res = tokio::task::spawn_blocking(move || {
+            catch_up(&reader, last_height, read_events)
+        })
+        .await
+        .expect("Catching up with the DB shouldn't panic");
+        match res {
+            Ok((height, new_events)) => {
+                last_height = Some(height);
+                if let Some(sender) = &events {
+                    for event in new_events {
+                        if sender.send(event).is_err() {
+                            tracing::info!(
+                                "The event stream server is not running"
+                            );
+                            break;
+                        }
+                    }
+                }
+            }
+            Err(err) => {
+                tracing::debug!("Failed to catch up with the DB: {}", err);
+            }
+        }
+    }
+}
+
+/// Catch up with the changes committed by the node. Returns the height of
+/// the last committed block and, if requested, the stream events of the
+/// blocks committed after the given last height. The events of the blocks
+/// committed before the first catch up are not read.
+fn catch_up(
+    reader: &SnapshotReader,
+    last_height: Option<BlockHeight>,
+    read_events: bool,
+) -> std::result::Result<(BlockHeight, Vec<StreamEvent>), StorageError> {
+    reader.try_catch_up()?;
+    let height = reader.read_last_height()?;
+    let mut events = vec![];
+    if let Some(last_height) = last_height.filter(|_| read_events) {
+        for block_height in (last_height.0 + 1..=height.0).map(BlockHeight) {
+            let (_, results) = reader.read_block_results(Some(block_height))?;
+            let results = match results {
+                Some(results) => BlockResults::try_from_slice(&results),
+                None => {
+                    tracing::debug!(
+                        "The results of block {} are not found",
+                        block_height
+                    );
+                    continue;
+                }
+            };
+            match results {
+                Ok(results) => {
+                    events.extend(results.events.iter().map(|event| {
+                        StreamEvent::from_block_event(event, block_height)
+                    }));
+                    events.push(StreamEvent::storage_changes(
+                        block_height,
+                        &results.storage_changes,
+                    ));
+                }
+                Err(err) => {
+                    tracing::error!(
+                        "Failed to decode the results of block {}: {}",
+                        block_height,
+                        err
+                    );
+                }
+            }
+        }
+    }
+    Ok((height, events))
+}
@@ -0,0 +1,239 @@
+//! The log of the txs rejected by the node, so that operators can find out
+//! why a tx never made it into a block. The rejections are counted by the
+//! stage at which they happened, i.e. the mempool's checks of the new txs,
+//! the re-checks of the txs left in the mempool after a block is committed
+//! and the application of the txs in a block. The most recent rejections are
+//! also kept with their reasons, up to the configured number. The log is
+//! served by the status server.
+
+use std::collections::VecDeque;
+use std::sync::Mutex;
+
+use anoma::proto::Tx;
+use anoma::types::time::DateTimeUtc;
+use anoma::types::transaction::{hash_tx, TxType};
+use serde::Serialize;
+
+use super::events::{Event, EventType};
+use crate::config;
+
+/// The default number of the most recently rejected txs kept in the log
+pub const DEFAULT_REJECTED_TXS_LOG_SIZE: u64 = 100;
+
+/// The stage at which a tx was rejected
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RejectionStage {
+    /// A new tx was rejected by the mempool
+    Mempool,
+    /// A tx left in the mempool was rejected when it was re-checked after a
+    /// block was committed
+    MempoolRecheck,
+    /// A tx included in a block was rejected when the block was applied
+    Block,
+}
+
+/// A rejected tx with the reason of its rejection
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct RejectedTx {
+    /// The hash of the tx. For a wrapper tx, it's the hash of its inner tx,
+    /// which is printed by the client when the tx is submitted.
+    pub hash: String,
+    /// The stage at which the tx was rejected
+    pub stage: RejectionStage,
+    /// The error code of the rejection
+    pub code: u32,
+    /// The reason of the rejection
+    pub reason: String,
+    /// The height of the block that included the tx, if any
+    pub height: Option<u64>,
+    /// The time of the rejection by the node's clock
+    pub time: DateTimeUtc,
+}
+
+/// The counts of the rejected txs by their stage and the most recently
+/// rejected txs
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct RejectedTxsStatus {
+    /// The number of the new txs rejected by the mempool
+    pub mempool: u64,
+    /// The number of the txs rejected by the mempool's re-checks
+    pub mempool_recheck: u64,
+    /// The number of the txs rejected in blocks
+    pub block: u64,
+    /// The most recently rejected txs, the oldest first
+    pub recent: VecDeque<RejectedTx>,
+}
+
+/// The log of the rejected txs. Thread-safe, as it's shared by the shell, the
+/// concurrent `CheckTx` requests and the status server.
+#[derive(Debug)]
+pub struct RejectedTxs {
+    /// The maximum number of the most recently rejected txs kept, the log of
+    /// the rejected txs is disabled with 0
+    max_recent: usize,
+    status: Mutex<RejectedTxsStatus>,
+}
+
+impl RejectedTxs {
+    /// Create an empty log with the size set in the config
+    pub fn new(config: &config::Shell) -> Self {
+        let max_recent = config
+            .rejected_txs_log_size
+            .unwrap_or(DEFAULT_REJECTED_TXS_LOG_SIZE);
+        Self::with_size(max_recent as usize)
+    }
+
+    /// Create an empty log that keeps the given number of the most recently
+    /// rejected txs
+    pub fn with_size(max_recent: usize) -> Self {
+        Self {
+            max_recent,
+            status: Mutex::new(RejectedTxsStatus::default()),
+        }
+    }
+
+    /// Record the response of a mempool check of the given tx, if the tx was
+    /// rejected
+    pub fn record_check_tx(
+        &self,
+        stage: RejectionStage,
+        tx_bytes: &[u8],
+        code: u32,
+        log: &str,
+    ) {
+        if code == 0 {
+            return;
+        }
+        self.record(stage, || RejectedTx {
+            hash: tx_hash(tx_bytes),
+            stage,
+            code,
+            reason: log.to_owned(),
+            height: None,
+            time: DateTimeUtc::now(),
+        });
+    }
+
+    /// Record the tx of the given event of a block, if the tx was rejected.
+    /// The events other than the tx events are ignored.
+    pub fn record_tx_event(&self, event: &Event) {
+        if !matches!(event.event_type, EventType::Accepted | EventType::Applied)
+        {
+            return;
+        }
+        let attr =
+            |key: &str| event.attributes.get(key).cloned().unwrap_or_default();
+        let code = match attr("code").parse::<u32>() {
+            Ok(code) if code != 0 => code,
+            _ => return,
+        };
+        self.record(RejectionStage::Block, || {
+            // The reason is in the log of an undecryptable tx
+            let info = attr("info");
+            let reason = if info.is_empty() { attr("log") } else { info };
+            RejectedTx {
+                hash: attr("tx_hash"),
+                stage: RejectionStage::Block,
+                code,
+                reason,
+                height: attr("height").parse().ok(),
+                time: DateTimeUtc::now(),
+            }
+        });
+    }
+
+    /// Get the counts of the rejected txs and the most recently rejected txs
+    pub fn status(&self) -> RejectedTxsStatus {
+        self.status.lock().unwrap().clone()
+    }
+
+    /// Count a rejected tx and add it to the most recently rejected txs, if
+    /// they're kept. The tx is only made when it's kept.
+    fn record(&self, stage: RejectionStage, tx: impl FnOnce() -> RejectedTx) {
+        let mut status = self.status.lock().unwrap();
+        match stage {
+            RejectionStage::Mempool => status.mempool += 1,
+            RejectionStage::MempoolRecheck => status.mempool_recheck += 1,
+            RejectionStage::Block => status.block += 1,
+        }
+        if self.max_recent > 0 {
+            if status.recent.len() >= self.max_recent {
+                status.recent.pop_front();
+            }
+            status.recent.push_back(tx());
+        }
+    }
+}
+
+impl Default for RejectedTxs {
+    fn default() -> Self {
+        Self::with_size(DEFAULT_REJECTED_TXS_LOG_SIZE as usize)
+    }
+}
+
+/// The hash of the given tx bytes. The hash of a wrapper tx is the hash of its
+/// inner tx, otherwise it's the hash of the bytes.
+fn tx_hash(tx_bytes: &[u8]) -> String {
+    match Tx::try_from(tx_bytes).map(TxType::try_from) {
+        Ok(Ok(TxType::Wrapper(wrapper))) => wrapper.tx_hash.to_string(),
+        _ => hash_tx(tx_bytes).to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    fn tx_event(code: &str, info: &str) -> Event {
+        let mut event = Event {
+            event_type: EventType::Applied,
+            attributes: HashMap::new(),
+        };
+        event["tx_hash"] = "hash".to_owned();
+        event["height"] = "10".to_owned();
+        event["log"] = "".to_owned();
+        event["code"] = code.to_owned();
+        event["info"] = info.to_owned();
+        event
+    }
+
+    /// Test that only the rejected txs are recorded and that only the given
+    /// number of the most recent ones is kept
+    #[test]
+    fn test_rejected_txs() {
+        let rejected_txs = RejectedTxs::with_size(2);
+        rejected_txs.record_check_tx(RejectionStage::Mempool, b"", 0, "ok");
+        rejected_txs.record_tx_event(&tx_event("0", "accepted"));
+        assert_eq!(rejected_txs.status(), RejectedTxsStatus::default());
+
+        rejected_txs.record_check_tx(RejectionStage::Mempool, b"", 1, "1");
+        rejected_txs.record_check_tx(
+            RejectionStage::MempoolRecheck,
+            b"",
+            10,
+            "2",
+        );
+        rejected_txs.record_tx_event(&tx_event("3", "3"));
+        let status = rejected_txs.status();
+        assert_eq!(status.mempool, 1);
+        assert_eq!(status.mempool_recheck, 1);
+        assert_eq!(status.block, 1);
+        let reasons: Vec<_> =
+            status.recent.iter().map(|tx| tx.reason.as_str()).collect();
+        assert_eq!(reasons, ["2", "3"]);
+        let block_tx = &status.recent[1];
+        assert_eq!(block_tx.hash, "hash");
+        assert_eq!(block_tx.code, 3);
+        assert_eq!(block_tx.height, Some(10));
+
+        // The counts are kept without the log of the rejected txs
+        let counts_only = RejectedTxs::with_size(0);
+        counts_only.record_tx_event(&tx_event("1", ""));
+        let status = counts_only.status();
+        assert_eq!(status.block, 1);
+        assert!(status.recent.is_empty());
+    }
+}
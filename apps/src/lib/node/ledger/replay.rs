@@ -0,0 +1,292 @@
+//! Deterministic replay of the chain's blocks for debugging consensus
+//! failures. The blocks are read from the Tendermint block store via the
+//! Tendermint RPC and re-executed by a shell with a fresh DB from the genesis,
+//! in the same way as Tendermint drives it over ABCI. The app hash of every
+//! replayed block is compared with the one agreed on by the network, which is
+//! in the header of the next block, and the replay stops at the first
+//! divergence.
+//!
+//! Note that the evidence of misbehaviour included in the blocks is not
+//! replayed, so the state may diverge from a block with evidence.
+
+use std::convert::TryFrom;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
+
+#[cfg(not(feature = "ABCI"))]
+use tendermint::block::Height;
+#[cfg(not(feature = "ABCI"))]
+use tendermint_config::net::Address as TendermintAddress;
+#[cfg(feature = "ABCI")]
+use tendermint_config_abci::net::Address as TendermintAddress;
+#[cfg(not(feature = "ABCI"))]
+use tendermint_proto::abci::{
+    RequestBeginBlock, RequestCommit, RequestDeliverTx, RequestEndBlock,
+    RequestInitChain,
+};
+#[cfg(feature = "ABCI")]
+use tendermint_proto_abci::abci::{
+    RequestBeginBlock, RequestCommit, RequestDeliverTx, RequestEndBlock,
+    RequestInitChain,
+};
+#[cfg(not(feature = "ABCI"))]
+use tendermint_rpc::endpoint::block;
+#[cfg(not(feature = "ABCI"))]
+use tendermint_rpc::{Client, Error as RpcError, HttpClient};
+#[cfg(feature = "ABCI")]
+use tendermint_rpc_abci::endpoint::block;
+#[cfg(feature = "ABCI")]
+use tendermint_rpc_abci::{Client, Error as RpcError, HttpClient};
+#[cfg(feature = "ABCI")]
+use tendermint_stable::block::Height;
+use thiserror::Error;
+use tower::{Service, ServiceExt};
+#[cfg(not(feature = "ABCI"))]
+use tower_abci::{BoxError, Request as Req, Response as Resp};
+#[cfg(feature = "ABCI")]
+use tower_abci_old::{BoxError, Request as Req, Response as Resp};
+
+use crate::cli::args;
+use crate::config;
+use crate::node::ledger::shims::abcipp_shim::{AbciService, AbcippShim};
+
+/// The size of the DB block cache and of the WASM compilation caches used
+/// for the replay, unless configured
+const DEFAULT_CACHE_BYTES: u64 = 256 * 1024 * 1024;
+
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("Invalid range of block heights to replay: from {0} to {1}")]
+    InvalidRange(u64, u64),
+    #[error(
+        "The block {0} needed to verify the app hash of the last replayed \
+         block is not in the block store yet, the latest block is {1}"
+    )]
+    MissingNextBlock(u64, u64),
+    #[error("Tendermint RPC request failed: {0}")]
+    Rpc(RpcError),
+    #[error("Error removing the replay DB: {0}")]
+    RemoveDB(std::io::Error),
+    #[error("The shell failed to execute a request: {0}")]
+    Shell(BoxError),
+    #[error("Unexpected response from the shell: {0:?}")]
+    UnexpectedResponse(Resp),
+    #[error(
+        "The app hash of block {height} diverges, expected: {expected}, got: \
+         {actual}"
+    )]
+    Diverged {
+        height: u64,
+        expected: String,
+        actual: String,
+    },
+}
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// Replay the chain's blocks up to and including the `to` height against a
+/// fresh state and compare their app hashes from the `from` height on.
+pub fn replay(
+    config: config::Ledger,
+    wasm_dir: PathBuf,
+    args: args::LedgerReplay,
+) -> Result<()> {
+    if args.from == 0 || args.from > args.to {
+        return Err(Error::InvalidRange(args.from, args.to));
+    }
+    tokio::runtime::Builder::new_multi_thread()
+        .thread_name("ledger-replay-worker")
+        .enable_all()
+        .build()
+        .unwrap()
+        .block_on(replay_aux(config, wasm_dir, args))
+}
+
+async fn replay_aux(
+    config: config::Ledger,
+    wasm_dir: PathBuf,
+    args::LedgerReplay {
+        from,
+        to,
+        ledger_address,
+    }: args::LedgerReplay,
+) -> Result<()> {
+    let rpc_address = ledger_address.unwrap_or_else(|| {
+        TendermintAddress::from_str(&config.tendermint.rpc_address.to_string())
+            .expect("Tendermint RPC address must be valid")
+    });
+    let client = HttpClient::new(rpc_address).unwrap();
+
+    // The app hash of the last replayed block is in the header of the next one
+    let latest_height = client
+        .status()
+        .await
+        .map_err(Error::Rpc)?
+        .sync_info
+        .latest_block_height
+        .value();
+    if to >= latest_height {
+        return Err(Error::MissingNextBlock(to + 1, latest_height));
+    }
+    let genesis = client.genesis().await.map_err(Error::Rpc)?;
+
+    // Start from an empty DB, next to the node's own
+    let config = config.for_replay();
+    let db_dir = config.db_dir();
+    match std::fs::remove_dir_all(&db_dir) {
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => (),
+        res => res.map_err(Error::RemoveDB)?,
+    };
+    tracing::info!("Replaying blocks into a fresh DB at {}", db_dir.display());
+
+    let cache_bytes =
+        |configured: Option<u64>| configured.unwrap_or(DEFAULT_CACHE_BYTES);
+    let db_cache = rocksdb::Cache::new_lru_cache(
+        cache_bytes(config.shell.block_cache_bytes) as usize,
+    )
+    .unwrap();
+    let vp_wasm_compilation_cache =
+        cache_bytes(config.shell.vp_wasm_compilation_cache_bytes);
+    let tx_wasm_compilation_cache =
+        cache_bytes(config.shell.tx_wasm_compilation_cache_bytes);
+    // A full node doesn't broadcast any protocol txs
+    let (broadcast_sender, _broadcast_receiver) =
+        tokio::sync::mpsc::unbounded_channel();
+    let (shell, mut abci_service) = AbcippShim::new(
+        config,
+        wasm_dir,
+        broadcast_sender,
+        None,
+        &db_cache,
+        vp_wasm_compilation_cache,
+        tx_wasm_compilation_cache,
+        Default::default(),
+        Arc::new(AtomicBool::new(false)),
+    );
+    let shell_handler = std::thread::Builder::new()
+        .name("ledger-replay-shell".into())
+        .spawn(move || shell.run())
+        .expect("Must be able to start a thread for the shell");
+
+    let result = async {
+        call(
+            &mut abci_service,
+            Req::InitChain(RequestInitChain {
+                time: Some(genesis.genesis_time.into()),
+                chain_id: genesis.chain_id.to_string(),
+                initial_height: genesis.initial_height,
+                ..Default::default()
+            }),
+        )
+        .await?;
+
+        let initial_height = u64::try_from(genesis.initial_height)
+            .expect("The initial height should be positive");
+        let mut block = get_block(&client, initial_height).await?;
+        for height in initial_height..=to {
+            let next_block = get_block(&client, height + 1).await?;
+            let app_hash = replay_block(&mut abci_service, block).await?;
+            let expected = hex::encode(next_block.block.header.app_hash.value());
+            let actual = hex::encode(app_hash);
+            if expected != actual {
+                return Err(Error::Diverged {
+                    height,
+                    expected,
+                    actual,
+                });
+            }
+            if height >= from {
+                println!("Block {}: app hash {} matches", height, actual);
+            }
+            block = next_block;
+        }
+        Ok(())
+    }
+    .await;
+
+    // Closing the service's channel stops the shell
+    drop(abci_service);
+    if shell_handler.join().is_err() {
+        tracing::error!("The replay shell panicked");
+    }
+    match &result {
+        Ok(()) => {
+            println!("Blocks up to {} replayed without divergence.", to);
+            std::fs::remove_dir_all(&db_dir).map_err(Error::RemoveDB)?;
+        }
+        Err(Error::Diverged { .. }) => {
+            println!(
+                "The state after the divergence is kept for inspection at {}",
+                db_dir.display()
+            );
+        }
+        Err(_) => {}
+    }
+    result
+}
+
+/// Fetch the block at the given height from the Tendermint block store.
+async fn get_block(
+    client: &HttpClient,
+    height: u64,
+) -> Result<block::Response> {
+    let height = Height::try_from(height).expect("Invalid block height");
+    client.block(height).await.map_err(Error::Rpc)
+}
+
+/// Execute a block with the same requests that Tendermint sends when the
+/// block is decided and return the app hash after it's committed.
+async fn replay_block(
+    service: &mut AbciService,
+    block::Response { block_id, block }: block::Response,
+) -> Result<Vec<u8>> {
+    let height = block.header.height.value();
+    if block.evidence.iter().next().is_some() {
+        tracing::warn!(
+            "The evidence in block {} is not replayed, its state may diverge",
+            height
+        );
+    }
+    call(
+        service,
+        Req::BeginBlock(RequestBeginBlock {
+            hash: block_id.hash.as_bytes().to_vec(),
+            header: Some(block.header.into()),
+            ..Default::default()
+        }),
+    )
+    .await?;
+    for tx in block.data.iter() {
+        call(
+            service,
+            Req::DeliverTx(RequestDeliverTx {
+                tx: tx.as_bytes().to_vec(),
+            }),
+        )
+        .await?;
+    }
+    call(
+        service,
+        Req::EndBlock(RequestEndBlock {
+            height: height as i64,
+        }),
+    )
+    .await?;
+    match call(service, Req::Commit(RequestCommit {})).await? {
+        Resp::Commit(commit) => Ok(commit.data.to_vec()),
+        resp => Err(Error::UnexpectedResponse(resp)),
+    }
+}
+
+/// Send a request to the shell and wait for its response.
+async fn call(service: &mut AbciService, req: Req) -> Result<Resp> {
+    service
+        .ready()
+        .await
+        .map_err(Error::Shell)?
+        .call(req)
+        .await
+        .map_err(Error::Shell)
+}
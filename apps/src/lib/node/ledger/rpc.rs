@@ -3,6 +3,8 @@
 use std::fmt::Display;
 use std::str::FromStr;
 
+use anoma::ibc::core::ics24_host::identifier::ClientId;
+use anoma::ledger::ibc::storage as ibc_storage;
 use anoma::types::address::Address;
 use anoma::types::storage;
 #[cfg(not(feature = "ABCI"))]
@@ -18,12 +20,26 @@ pub enum Path {
     DryRunTx,
     /// Epoch of the last committed block
     Epoch,
+    /// Results of the block with the given height or of the last committed
+    /// block
+    BlockResults(Option<storage::BlockHeight>),
     /// Read a storage value with exact storage key
     Value(storage::Key),
     /// Read a range of storage values with a matching key prefix
     Prefix(storage::Key),
     /// Check if the given storage key exists
     HasKey(storage::Key),
+    /// Find the indexed transactions that touched the given address
+    TxsByAddress(Address),
+    /// Find the indexed transactions that changed a storage key with the
+    /// given prefix
+    TxsByKeyPrefix(String),
+    /// Read an IBC value by its ICS24 path, e.g.
+    /// `clients/07-tendermint-0/clientState`. The key is kept as the path to
+    /// be able to print it back.
+    IbcValue(String, storage::Key),
+    /// Heights of the consensus states of the IBC client with the given ID
+    IbcConsensusHeights(ClientId),
 }
 
 #[derive(Debug, Clone)]
@@ -36,15 +52,24 @@ pub struct BalanceQuery {
 
 const DRY_RUN_TX_PATH: &str = "dry_run_tx";
 const EPOCH_PATH: &str = "epoch";
+const BLOCK_RESULTS_PATH: &str = "block_results";
 const VALUE_PREFIX: &str = "value";
 const PREFIX_PREFIX: &str = "prefix";
 const HAS_KEY_PREFIX: &str = "has_key";
+const TXS_BY_ADDRESS_PREFIX: &str = "txs_by_address";
+const TXS_BY_KEY_PREFIX: &str = "txs_by_key";
+const IBC_PREFIX: &str = "ibc";
+const IBC_CONSENSUS_HEIGHTS_PREFIX: &str = "ibc_consensus_heights";
 
 impl Display for Path {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Path::DryRunTx => write!(f, "{}", DRY_RUN_TX_PATH),
             Path::Epoch => write!(f, "{}", EPOCH_PATH),
+            Path::BlockResults(None) => write!(f, "{}", BLOCK_RESULTS_PATH),
+            Path::BlockResults(Some(height)) => {
+                write!(f, "{}/{}", BLOCK_RESULTS_PATH, height)
+            }
             Path::Value(storage_key) => {
                 write!(f, "{}/{}", VALUE_PREFIX, storage_key)
             }
@@ -54,6 +79,16 @@ impl Display for Path {
             Path::HasKey(storage_key) => {
                 write!(f, "{}/{}", HAS_KEY_PREFIX, storage_key)
             }
+            Path::TxsByAddress(address) => {
+                write!(f, "{}/{}", TXS_BY_ADDRESS_PREFIX, address.encode())
+            }
+            Path::TxsByKeyPrefix(prefix) => {
+                write!(f, "{}/{}", TXS_BY_KEY_PREFIX, prefix)
+            }
+            Path::IbcValue(path, _) => write!(f, "{}/{}", IBC_PREFIX, path),
+            Path::IbcConsensusHeights(client_id) => {
+                write!(f, "{}/{}", IBC_CONSENSUS_HEIGHTS_PREFIX, client_id)
+            }
         }
     }
 }
@@ -65,7 +100,14 @@ impl FromStr for Path {
         match s {
             DRY_RUN_TX_PATH => Ok(Self::DryRunTx),
             EPOCH_PATH => Ok(Self::Epoch),
+            BLOCK_RESULTS_PATH => Ok(Self::BlockResults(None)),
             _ => match s.split_once('/') {
+                Some((BLOCK_RESULTS_PATH, height)) => {
+                    let height = height
+                        .parse()
+                        .map_err(PathParseError::InvalidHeight)?;
+                    Ok(Self::BlockResults(Some(storage::BlockHeight(height))))
+                }
                 Some((VALUE_PREFIX, storage_key)) => {
                     let key = storage::Key::parse(storage_key)
                         .map_err(PathParseError::InvalidStorageKey)?;
@@ -81,6 +123,26 @@ impl FromStr for Path {
                         .map_err(PathParseError::InvalidStorageKey)?;
                     Ok(Self::HasKey(key))
                 }
+                Some((TXS_BY_ADDRESS_PREFIX, address)) => {
+                    let address = Address::decode(address)
+                        .map_err(PathParseError::InvalidAddress)?;
+                    Ok(Self::TxsByAddress(address))
+                }
+                Some((TXS_BY_KEY_PREFIX, prefix)) => {
+                    Ok(Self::TxsByKeyPrefix(prefix.to_owned()))
+                }
+                Some((IBC_PREFIX, path)) => {
+                    let key = ibc_storage::ibc_key(path).map_err(|e| {
+                        PathParseError::InvalidIbcPath(e.to_string())
+                    })?;
+                    Ok(Self::IbcValue(path.to_owned(), key))
+                }
+                Some((IBC_CONSENSUS_HEIGHTS_PREFIX, client_id)) => {
+                    let client_id = ClientId::from_str(client_id).map_err(
+                        |e| PathParseError::InvalidIbcPath(e.to_string()),
+                    )?;
+                    Ok(Self::IbcConsensusHeights(client_id))
+                }
                 _ => Err(PathParseError::InvalidPath(s.to_string())),
             },
         }
@@ -103,4 +165,10 @@ pub enum PathParseError {
     InvalidPath(String),
     #[error("Invalid storage key: {0}")]
     InvalidStorageKey(storage::Error),
+    #[error("Invalid address: {0}")]
+    InvalidAddress(anoma::types::address::Error),
+    #[error("Invalid block height: {0}")]
+    InvalidHeight(std::num::ParseIntError),
+    #[error("Invalid IBC path: {0}")]
+    InvalidIbcPath(String),
 }
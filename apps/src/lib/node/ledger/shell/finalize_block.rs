@@ -1,12 +1,17 @@
 //! Implementation of the `FinalizeBlock` ABCI++ method for the Shell
 
+use std::collections::{BTreeMap, HashMap};
+
 use anoma::ledger::governance::storage as gov_storage;
 use anoma::ledger::governance::utils::{
     compute_tally, get_proposal_votes, ProposalEvent,
 };
 use anoma::ledger::governance::vp::ADDRESS as gov_address;
 use anoma::ledger::storage::types::encode;
-use anoma::ledger::treasury::ADDRESS as treasury_address;
+use anoma::ledger::storage::write_log::StorageModification;
+use anoma::ledger::treasury::{
+    self, storage as treasury_storage, ADDRESS as treasury_address,
+};
 use anoma::types::address::{xan as m1t, Address};
 use anoma::types::governance::TallyResult;
 use anoma::types::storage::{BlockHash, Epoch};
@@ -24,6 +29,7 @@ use tendermint_proto_abci::crypto::PublicKey as TendermintPublicKey;
 use tendermint_stable::block::Header;
 
 use super::*;
+use crate::node::ledger::block_results::{self, BlockResults};
 use crate::node::ledger::events::EventType;
 
 impl<D, H> Shell<D, H>
@@ -42,7 +48,9 @@ where
     /// included in the next `Shell::prepare_proposal` call.
     ///
     /// Incoming wrapper txs need no further validation. They
-    /// are added to the block.
+    /// are added to the block and their fees are charged. The fees
+    /// collected in the block and the block reward are paid to the
+    /// block proposer after all the txs are applied.
     ///
     /// Error codes:
     ///   0: Ok
@@ -51,14 +59,35 @@ where
     ///   3: Wasm runtime error
     ///   4: Invalid order of decrypted txs
     ///   5. More decrypted txs than expected
+    ///   9: Decrypted tx is not bound to this chain
+    ///  10: Tx has expired by the time of the block
     pub fn finalize_block(
         &mut self,
         req: shim::request::FinalizeBlock,
     ) -> Result<shim::response::FinalizeBlock> {
         let mut response = shim::response::FinalizeBlock::default();
+        // The proposer is looked up before the epoch may change, as the
+        // validator set updates only take effect in Tendermint later
+        let proposer = self.get_validator_from_tm_address(
+            req.header.proposer_address.as_bytes(),
+        );
+        // The fees collected in the block by their tokens
+        let mut block_fees = BTreeMap::new();
+        // The txs' expiration is checked against the time of the block
+        let block_time: DateTimeUtc = req
+            .header
+            .time
+            .try_into()
+            .expect("Time conversion shouldn't fail");
         // begin the next block and check if a new epoch began
         let (height, new_epoch) =
-            self.update_state(req.header, req.hash, req.byzantine_validators);
+            self.update_state(req.header, req.hash, req.byzantine_validators)?;
+        self.block_results = BlockResults::new(height);
+        // The limits may have been changed by a governance proposal
+        let (size_limits, _gas) =
+            parameters::read_tx_write_size_limits(&self.storage)
+                .expect("Couldn't read the tx write size limits parameter");
+        self.write_log.set_size_limits(size_limits);
 
         if new_epoch {
             for id in std::mem::take(&mut self.proposal_data) {
@@ -87,8 +116,9 @@ where
                     get_proposal_votes(&self.storage, proposal_start_epoch, id);
                 let tally_result =
                     compute_tally(&self.storage, proposal_start_epoch, votes);
+                tracing::info!("Proposal {} {}", id, tally_result);
 
-                let transfer_address = match tally_result {
+                let transfer_address = match tally_result.result {
                     TallyResult::Passed => {
                         let proposal_author_key =
                             gov_storage::get_author_key(id);
@@ -149,6 +179,12 @@ where
                                                     true,
                                                 )
                                                 .into();
+                                            // The changes of the proposal
+                                            // code are recorded with its event
+                                            self.record_event(
+                                                &proposal_event,
+                                                &tx_result.changed_keys,
+                                            );
                                             response
                                                 .events
                                                 .push(proposal_event.into());
@@ -166,6 +202,10 @@ where
                                                     false,
                                                 )
                                                 .into();
+                                            self.record_event(
+                                                &proposal_event,
+                                                &BTreeSet::new(),
+                                            );
                                             response
                                                 .events
                                                 .push(proposal_event.into());
@@ -173,7 +213,13 @@ where
                                             treasury_address
                                         }
                                     }
-                                    Err(_e) => {
+                                    Err(err) => {
+                                        tracing::error!(
+                                            "The code of the proposal {} \
+                                             failed: {}",
+                                            id,
+                                            err
+                                        );
                                         self.write_log.drop_tx();
                                         let proposal_event: Event =
                                             ProposalEvent::new(
@@ -184,6 +230,10 @@ where
                                                 false,
                                             )
                                             .into();
+                                        self.record_event(
+                                            &proposal_event,
+                                            &BTreeSet::new(),
+                                        );
                                         response
                                             .events
                                             .push(proposal_event.into());
@@ -201,6 +251,10 @@ where
                                     false,
                                 )
                                 .into();
+                                self.record_event(
+                                    &proposal_event,
+                                    &BTreeSet::new(),
+                                );
                                 response.events.push(proposal_event.into());
 
                                 proposal_author
@@ -216,6 +270,7 @@ where
                             false,
                         )
                         .into();
+                        self.record_event(&proposal_event, &BTreeSet::new());
                         response.events.push(proposal_event.into());
 
                         treasury_address
@@ -230,9 +285,15 @@ where
                     &transfer_address,
                 );
             }
+            self.update_inflation();
         }
 
-        for processed_tx in &req.txs {
+        // The decrypted txs are applied speculatively in parallel first and
+        // their results are merged in order below, unless they conflict
+        let mut speculative_txs =
+            self.apply_txs_speculatively(&req, block_time);
+
+        for (index, processed_tx) in req.txs.iter().enumerate() {
             let tx = if let Ok(tx) = Tx::try_from(processed_tx.tx.as_ref()) {
                 tx
             } else {
@@ -243,6 +304,7 @@ where
                 continue;
             };
             let tx_length = processed_tx.tx.len();
+            let tx_expiration = tx.expiration;
             // If [`process_proposal`] rejected a Tx due to invalid signature,
             // emit an event here and move on to next tx. If we are
             // rejecting all decrypted txs because they were
@@ -275,6 +337,7 @@ where
                 tx_result["info"] =
                     format!("Tx rejected: {}", &processed_tx.result.info);
                 tx_result["gas_used"] = "0".into();
+                self.record_event(&tx_result, &BTreeSet::new());
                 response.events.push(tx_result.into());
                 continue;
             }
@@ -301,6 +364,7 @@ where
                 tx_result["info"] =
                     format!("Tx rejected: {}", &processed_tx.result.info);
                 tx_result["gas_used"] = "0".into();
+                self.record_event(&tx_result, &BTreeSet::new());
                 response.events.push(tx_result.into());
                 // if the rejected tx was decrypted, remove it
                 // from the queue of txs to be processed
@@ -311,10 +375,23 @@ where
             }
 
             let mut tx_result = match &tx_type {
-                TxType::Wrapper(_wrapper) => {
+                TxType::Wrapper(wrapper) => {
+                    if let Err(msg) =
+                        check_expiration(tx_expiration, block_time)
+                    {
+                        let mut tx_result =
+                            Event::new_tx_event(&tx_type, height.0);
+                        tx_result["code"] = ErrorCodes::ExpiredTx.into();
+                        tx_result["info"] = format!("Tx rejected: {}", msg);
+                        tx_result["gas_used"] = "0".into();
+                        self.record_event(&tx_result, &BTreeSet::new());
+                        response.events.push(tx_result.into());
+                        continue;
+                    }
                     if !cfg!(feature = "ABCI") {
-                        self.storage.tx_queue.push(_wrapper.clone());
+                        self.storage.tx_queue.push(wrapper.clone());
                     }
+                    self.charge_fee(wrapper, &mut block_fees);
                     Event::new_tx_event(&tx_type, height.0)
                 }
                 TxType::Decrypted(inner) => {
@@ -331,6 +408,7 @@ where
                                              correct order"
                             .into();
                         tx_result["gas_used"] = "0".into();
+                        self.record_event(&tx_result, &BTreeSet::new());
                         response.events.push(tx_result.into());
                         continue;
                     }
@@ -339,10 +417,24 @@ where
                         self.storage.tx_queue.pop();
                     }
                     let mut event = Event::new_tx_event(&tx_type, height.0);
-                    if let DecryptedTx::Undecryptable(_) = inner {
-                        event["log"] =
-                            "Transaction could not be decrypted.".into();
-                        event["code"] = ErrorCodes::Undecryptable.into();
+                    match inner {
+                        DecryptedTx::Undecryptable(_) => {
+                            event["log"] =
+                                "Transaction could not be decrypted.".into();
+                            event["code"] = ErrorCodes::Undecryptable.into();
+                        }
+                        DecryptedTx::Decrypted(tx) => {
+                            if let Err((code, msg)) =
+                                self.check_decrypted_tx(tx, block_time)
+                            {
+                                event["code"] = code.into();
+                                event["info"] = format!("Tx rejected: {}", msg);
+                                event["gas_used"] = "0".into();
+                                self.record_event(&event, &BTreeSet::new());
+                                response.events.push(event.into());
+                                continue;
+                            }
+                        }
                     }
                     event
                 }
@@ -362,17 +454,28 @@ where
                 }
             };
 
-            match protocol::apply_tx(
-                tx_type,
-                tx_length,
-                &mut self.gas_meter,
-                &mut self.write_log,
-                &self.storage,
-                &mut self.vp_wasm_cache,
-                &mut self.tx_wasm_cache,
-            )
-            .map_err(Error::TxApply)
-            {
+            // The storage keys changed by the tx, if it's accepted
+            let mut changed_keys = BTreeSet::new();
+            let merged = speculative_txs.remove(&index).and_then(|result| {
+                protocol::merge_speculative_tx(
+                    result,
+                    &mut self.gas_meter,
+                    &mut self.write_log,
+                )
+            });
+            let result = match merged {
+                Some(result) => result,
+                None => protocol::apply_tx(
+                    tx_type,
+                    tx_length,
+                    &mut self.gas_meter,
+                    &mut self.write_log,
+                    &self.storage,
+                    &mut self.vp_wasm_cache,
+                    &mut self.tx_wasm_cache,
+                ),
+            };
+            match result.map_err(Error::TxApply) {
                 Ok(result) => {
                     if result.is_accepted() {
                         tracing::info!(
@@ -381,12 +484,16 @@ where
                             result
                         );
                         self.write_log.commit_tx();
+                        changed_keys = result.changed_keys.clone();
                         if !tx_result.contains_key("code") {
                             tx_result["code"] = ErrorCodes::Ok.into();
                         }
-                        if let Some(ibc_event) = &result.ibc_event {
-                            // Add the IBC event besides the tx_result
-                            let event = Event::from(ibc_event.clone());
+                        for ibc_event in &result.ibc_events {
+                            // Add the IBC events besides the tx_result, tagged
+                            // with the hash of the tx that emitted them
+                            let mut event = Event::from(ibc_event.clone());
+                            event["tx_hash"] = tx_result["tx_hash"].clone();
+                            self.record_event(&event, &result.changed_keys);
                             response.events.push(event.into());
                         }
                         match serde_json::to_string(
@@ -427,39 +534,311 @@ where
                     tx_result["code"] = ErrorCodes::WasmRuntimeError.into();
                 }
             }
+            self.record_event(&tx_result, &changed_keys);
             response.events.push(tx_result.into());
         }
         self.reset_tx_queue_iter();
+        self.pay_block_rewards(proposer, block_fees, height, &mut response);
 
         if new_epoch {
-            self.update_epoch(&mut response);
+            self.block_results.validator_updates =
+                self.update_epoch(&mut response);
         }
 
         response.gas_used = self
             .gas_meter
             .finalize_transaction()
             .map_err(|_| Error::GasOverflow)?;
+        self.block_results.gas_used = response.gas_used;
         Ok(response)
     }
 
+    /// Apply the decrypted txs of the block speculatively in parallel, each
+    /// against the state before any of the block's txs. The results are
+    /// merged in the order of the txs in [`Shell::finalize_block`], except
+    /// for the txs that read a storage key changed by the txs before them,
+    /// which are applied again. The results are therefore the same as if the
+    /// txs were applied one after another.
+    fn apply_txs_speculatively(
+        &self,
+        req: &shim::request::FinalizeBlock,
+        block_time: DateTimeUtc,
+    ) -> HashMap<usize, protocol::SpeculativeResult> {
+        if req.reject_all_decrypted {
+            return HashMap::new();
+        }
+        let txs: Vec<_> = req
+            .txs
+            .iter()
+            .enumerate()
+            .filter_map(|(index, processed_tx)| {
+                if ErrorCodes::from_u32(processed_tx.result.code)
+                    != Some(ErrorCodes::Ok)
+                {
+                    return None;
+                }
+                let tx = Tx::try_from(processed_tx.tx.as_ref()).ok()?;
+                match process_tx(tx) {
+                    Ok(TxType::Decrypted(DecryptedTx::Decrypted(tx)))
+                        if self.check_decrypted_tx(&tx, block_time).is_ok() =>
+                    {
+                        let tx_type =
+                            TxType::Decrypted(DecryptedTx::Decrypted(tx));
+                        Some((index, tx_type, processed_tx.tx.len()))
+                    }
+                    _ => None,
+                }
+            })
+            .collect();
+        // There's nothing to gain from a single tx
+        if txs.len() < 2 {
+            return HashMap::new();
+        }
+        protocol::apply_txs_speculatively(
+            txs,
+            &self.write_log,
+            &self.storage,
+            &self.vp_wasm_cache,
+            &self.tx_wasm_cache,
+        )
+    }
+
+    /// Check that a decrypted tx is bound to this chain and that it hasn't
+    /// expired by the given block time. Its wrapper has been already
+    /// committed, so a tx that fails the checks is still included in the
+    /// block, but it's not applied.
+    fn check_decrypted_tx(
+        &self,
+        tx: &Tx,
+        block_time: DateTimeUtc,
+    ) -> std::result::Result<(), (ErrorCodes, String)> {
+        check_chain_id(tx.chain_id.as_ref(), &self.storage.chain_id)
+            .map_err(|msg| (ErrorCodes::InvalidChainId, msg))?;
+        check_expiration(tx.expiration, block_time)
+            .map_err(|msg| (ErrorCodes::ExpiredTx, msg))
+    }
+
+    /// Charge the fee of an accepted wrapper tx to its payer. The fee is held
+    /// in the PoS account until it's paid to the block proposer. The payer is
+    /// charged no more than its balance, which might have been spent by the
+    /// txs applied earlier in the block.
+    fn charge_fee(
+        &mut self,
+        wrapper: &WrapperTx,
+        block_fees: &mut BTreeMap<Address, token::Amount>,
+    ) {
+        let fee_token = &wrapper.fee.token;
+        let payer = wrapper.fee_payer();
+        let balance = self.read_balance(fee_token, &payer);
+        let fee = std::cmp::min(wrapper.fee.amount, balance);
+        if fee == token::Amount::default() {
+            return;
+        }
+        self.transfer_balance(fee_token, fee, &payer, &pos::ADDRESS);
+        self.write_log.commit_tx();
+        let fees = block_fees.entry(fee_token.clone()).or_default();
+        match fees.checked_add(fee) {
+            Some(total) => *fees = total,
+            None => tracing::error!(
+                "The fees collected in the block in {} overflow, the fee of \
+                 {} is kept in the PoS account",
+                fee_token,
+                payer
+            ),
+        }
+    }
+
+    /// Pay the fees collected in the block and the block reward to the
+    /// block proposer, less the treasury's share. If the proposer can't be
+    /// found, the fees are left in the PoS account and no reward is paid.
+    fn pay_block_rewards(
+        &mut self,
+        proposer: Option<Address>,
+        mut block_fees: BTreeMap<Address, token::Amount>,
+        height: BlockHeight,
+        response: &mut shim::response::FinalizeBlock,
+    ) {
+        let proposer = match proposer {
+            Some(proposer) => proposer,
+            None => {
+                tracing::warn!(
+                    "The proposer of the block {} is not an active validator, \
+                     its fees are kept in the PoS account",
+                    height
+                );
+                return;
+            }
+        };
+        // The reward is set by the inflation controller, or by the PoS
+        // parameters on the chains initialized before it was added, until
+        // their next epoch
+        let (inflation, _gas) = parameters::read_inflation(&self.storage)
+            .expect("Couldn't read the inflation");
+        let reward = match inflation {
+            Some(inflation) => inflation.block_reward,
+            None => token::Amount::from(
+                self.storage.read_pos_params().block_proposer_reward,
+            ),
+        };
+        if reward != token::Amount::default() {
+            block_fees.entry(m1t()).or_default();
+        }
+        // Not set on the chains initialized before the fee share was added
+        let fee_share = self
+            .read_storage_key::<u64>(&treasury_storage::get_fee_share_key())
+            .unwrap_or_default();
+        for (fee_token, fees) in block_fees {
+            // The reward is minted in the native token into the PoS account,
+            // from where it's paid together with the fees
+            let reward = if fee_token == m1t() {
+                self.mint_block_reward(reward, height)
+            } else {
+                token::Amount::default()
+            };
+            // The fees and the reward are in the PoS balance, which is
+            // bounded by the total supply
+            let total = fees
+                .checked_add(reward)
+                .expect("The fees and the reward shouldn't overflow");
+            let treasury_share = treasury::fee_share_of(total, fee_share);
+            self.transfer_balance(
+                &fee_token,
+                treasury_share,
+                &pos::ADDRESS,
+                &treasury_address,
+            );
+            self.transfer_balance(
+                &fee_token,
+                total - treasury_share,
+                &pos::ADDRESS,
+                &proposer,
+            );
+            let event = Event::new_block_rewards_event(
+                height.0,
+                &proposer,
+                &fee_token,
+                fees,
+                reward,
+                treasury_share,
+            );
+            self.record_event(&event, &BTreeSet::new());
+            response.events.push(event.into());
+        }
+        self.write_log.commit_tx();
+    }
+
+    /// Mint the block reward into the PoS account and add it to the native
+    /// token's total supply. The reward isn't minted if it would overflow the
+    /// total supply. Returns the minted amount.
+    fn mint_block_reward(
+        &mut self,
+        reward: token::Amount,
+        height: BlockHeight,
+    ) -> token::Amount {
+        let supply_key = token::supply_key(&m1t());
+        // Not set on the chains initialized before the total supply was
+        // tracked, until their next epoch, when it's summed up from the
+        // balances with the rewards minted until then
+        if let Some(supply) = self.read_amount(&supply_key) {
+            let supply = match supply.checked_add(reward) {
+                Some(supply) => supply,
+                None => {
+                    tracing::error!(
+                        "The block reward of the block {} would overflow the \
+                         total supply, it's not minted",
+                        height
+                    );
+                    return token::Amount::default();
+                }
+            };
+            self.write_log
+                .write(&supply_key, encode(&supply))
+                .expect("Writing the total supply shouldn't fail");
+        }
+        let mut pos_balance = self.read_balance(&m1t(), &pos::ADDRESS);
+        pos_balance.receive(&reward);
+        self.write_balance(&m1t(), &pos::ADDRESS, pos_balance);
+        reward
+    }
+
+    /// Transfer an amount of a token through the write log, so that it's
+    /// committed together with the changes of the block's txs
+    fn transfer_balance(
+        &mut self,
+        token_addr: &Address,
+        amount: token::Amount,
+        src: &Address,
+        dest: &Address,
+    ) {
+        let mut src_balance = self.read_balance(token_addr, src);
+        src_balance.spend(&amount);
+        self.write_balance(token_addr, src, src_balance);
+        let mut dest_balance = self.read_balance(token_addr, dest);
+        dest_balance.receive(&amount);
+        self.write_balance(token_addr, dest, dest_balance);
+    }
+
+    /// Read a token balance with the changes of the current block
+    fn read_balance(
+        &self,
+        token_addr: &Address,
+        owner: &Address,
+    ) -> token::Amount {
+        let key = token::balance_key(token_addr, owner);
+        self.read_amount(&key).unwrap_or_default()
+    }
+
+    /// Read an amount with the changes of the current block
+    fn read_amount(&self, key: &Key) -> Option<token::Amount> {
+        match self.write_log.read(key).0 {
+            Some(StorageModification::Write { value }) => {
+                token::Amount::try_from_slice(value).ok()
+            }
+            Some(_) => None,
+            None => self.read_storage_key(key),
+        }
+    }
+
+    /// Write a token balance to the write log
+    fn write_balance(
+        &mut self,
+        token_addr: &Address,
+        owner: &Address,
+        balance: token::Amount,
+    ) {
+        let key = token::balance_key(token_addr, owner);
+        self.write_log
+            .write(&key, encode(&balance))
+            .expect("Writing a balance shouldn't fail");
+    }
+
     /// Sets the metadata necessary for a new block, including
     /// the hash, height, validator changes, and evidence of
     /// byzantine behavior. Applies slashes if necessary.
     /// Returns a bool indicating if a new epoch began and
-    /// the height of the new block.
+    /// the height of the new block. Fails if the block has already been
+    /// committed or if the last block couldn't be written to the DB.
     fn update_state(
         &mut self,
         header: Header,
         hash: BlockHash,
         byzantine_validators: Vec<Evidence>,
-    ) -> (BlockHeight, bool) {
+    ) -> Result<(BlockHeight, bool)> {
         let height = BlockHeight(header.height.into());
+        // Tendermint only replays the blocks above the last committed height
+        // reported by `Info`, so a committed block must never be applied again
+        if self.storage.last_root.is_some()
+            && height <= self.storage.last_height
+        {
+            return Err(Error::BlockReplayed(height, self.storage.last_height));
+        }
 
         self.gas_meter.reset();
 
+        // the last block may be still written in the background
         self.storage
             .begin_block(hash, height)
-            .expect("Beginning a block shouldn't fail");
+            .map_err(Error::StorageCommit)?;
 
         self.storage
             .set_header(header)
@@ -483,14 +862,19 @@ where
             .expect("Must be able to update epoch");
 
         self.slash();
-        (height, new_epoch)
+        Ok((height, new_epoch))
     }
 
     /// If a new epoch begins, we update the response to include
-    /// changes to the validator sets and consensus parameters
-    fn update_epoch(&self, response: &mut shim::response::FinalizeBlock) {
+    /// changes to the validator sets and consensus parameters. Returns the
+    /// validator updates for the block results.
+    fn update_epoch(
+        &self,
+        response: &mut shim::response::FinalizeBlock,
+    ) -> Vec<block_results::ValidatorUpdate> {
         // Apply validator set update
         let (current_epoch, _gas) = self.storage.get_current_epoch();
+        let mut validator_updates = vec![];
         // TODO ABCI validator updates on block H affects the validator set
         // on block H+2, do we need to update a block earlier?
         self.storage.validator_set_update(current_epoch, |update| {
@@ -519,6 +903,10 @@ where
             let pub_key = Some(pub_key);
             let update = ValidatorUpdate { pub_key, power };
             response.validator_updates.push(update);
+            validator_updates.push(block_results::ValidatorUpdate {
+                consensus_key,
+                voting_power: power,
+            });
         });
 
         // Update evidence parameters
@@ -532,6 +920,7 @@ where
             evidence: Some(evidence_params),
             ..response.consensus_param_updates.take().unwrap_or_default()
         });
+        validator_updates
     }
 }
 
@@ -541,7 +930,8 @@ where
 mod test_finalize_block {
     use anoma::types::address::xan;
     use anoma::types::storage::Epoch;
-    use anoma::types::transaction::{EncryptionKey, Fee};
+    use anoma::types::time::Duration;
+    use anoma::types::transaction::{BatchedTx, EncryptionKey, Fee, TxBatch};
 
     use super::*;
     use crate::node::ledger::shell::test_utils::*;
@@ -576,7 +966,9 @@ mod test_finalize_block {
                 raw_tx.clone(),
                 Default::default(),
             );
-            let tx = wrapper.sign(&keypair).expect("Test failed");
+            let tx = wrapper
+                .sign(&keypair, ChainId::default())
+                .expect("Test failed");
             if i > 1 {
                 processed_txs.push(ProcessedTx {
                     tx: tx.to_bytes(),
@@ -656,7 +1048,9 @@ mod test_finalize_block {
                 raw_tx.clone(),
                 Default::default(),
             );
-            let tx = wrapper.sign(&keypair).expect("Test failed");
+            let tx = wrapper
+                .sign(&keypair, ChainId::default())
+                .expect("Test failed");
             if i > 1 {
                 processed_txs.push(ProcessedTx {
                     tx: tx.to_bytes(),
@@ -753,6 +1147,318 @@ mod test_finalize_block {
         assert!(shell.next_wrapper().is_none());
     }
 
+    #[cfg(not(feature = "ABCI"))]
+    /// Test that a decrypted tx that is bound to another chain is not
+    /// applied, but it's still removed from the queue of txs to be included
+    /// in the next block proposal
+    #[test]
+    fn test_decrypted_tx_other_chain_rejected() {
+        let (mut shell, _) = setup();
+        let keypair = gen_keypair();
+        let other_chain =
+            ChainId::from_str("anoma-otherchain.0000000000000").unwrap();
+        let raw_tx = Tx::new(
+            "wasm_code".as_bytes().to_owned(),
+            Some(String::from("transaction data").as_bytes().to_owned()),
+        )
+        .with_chain_id(other_chain);
+        let wrapper = WrapperTx::new(
+            Fee {
+                amount: 0.into(),
+                token: xan(),
+            },
+            &keypair,
+            Epoch(0),
+            0.into(),
+            raw_tx.clone(),
+            Default::default(),
+        );
+
+        let processed_tx = ProcessedTx {
+            tx: Tx::from(TxType::Decrypted(DecryptedTx::Decrypted(raw_tx)))
+                .to_bytes(),
+            result: TxResult {
+                code: ErrorCodes::Ok.into(),
+                info: "".into(),
+            },
+        };
+        shell.enqueue_tx(wrapper);
+
+        let events = shell
+            .finalize_block(FinalizeBlock {
+                txs: vec![processed_tx],
+                reject_all_decrypted: false,
+                ..Default::default()
+            })
+            .expect("Test failed");
+        let code = events[0]
+            .attributes
+            .iter()
+            .find(|attr| attr.key.as_str() == "code")
+            .expect("Test failed")
+            .value
+            .as_str();
+        assert_eq!(code, String::from(ErrorCodes::InvalidChainId).as_str());
+        assert!(shell.next_wrapper().is_none());
+    }
+
+    #[cfg(not(feature = "ABCI"))]
+    /// Test that a batch is applied only if the codes of all its inner txs
+    /// are in a non-empty tx whitelist
+    #[test]
+    fn test_batch_inner_txs_whitelisted() {
+        let (mut shell, _) = setup();
+        let keypair = gen_keypair();
+        let mut wasm_path = top_level_directory();
+        wasm_path.push("wasm_for_tests/tx_no_op.wasm");
+        let tx_code = std::fs::read(wasm_path)
+            .expect("Expected a file at given code path");
+        let whitelist = vec![hash_tx(&tx_code).to_string()];
+        shell
+            .shell
+            .storage
+            .write(
+                &parameters::storage::get_tx_whitelist_storage_key(),
+                encode(&whitelist),
+            )
+            .expect("Test failed");
+
+        let whitelisted = BatchedTx {
+            code: tx_code,
+            data: None,
+        };
+        let not_whitelisted = BatchedTx {
+            code: "wasm_code".as_bytes().to_owned(),
+            data: None,
+        };
+        let mut processed_txs = vec![];
+        for inner_txs in vec![
+            vec![whitelisted.clone()],
+            vec![whitelisted, not_whitelisted],
+        ] {
+            let raw_tx = TxBatch::new(inner_txs)
+                .into_tx()
+                .with_chain_id(ChainId::default())
+                .sign(&keypair);
+            let wrapper = WrapperTx::new(
+                Fee {
+                    amount: 0.into(),
+                    token: xan(),
+                },
+                &keypair,
+                Epoch(0),
+                0.into(),
+                raw_tx.clone(),
+                Default::default(),
+            );
+            shell.enqueue_tx(wrapper);
+            processed_txs.push(ProcessedTx {
+                tx: Tx::from(TxType::Decrypted(DecryptedTx::Decrypted(raw_tx)))
+                    .to_bytes(),
+                result: TxResult {
+                    code: ErrorCodes::Ok.into(),
+                    info: "".into(),
+                },
+            });
+        }
+
+        let events = shell
+            .finalize_block(FinalizeBlock {
+                txs: processed_txs,
+                reject_all_decrypted: false,
+                ..Default::default()
+            })
+            .expect("Test failed");
+        let codes: Vec<_> = events[..2]
+            .iter()
+            .map(|event| {
+                event
+                    .attributes
+                    .iter()
+                    .find(|attr| attr.key.as_str() == "code")
+                    .expect("Test failed")
+                    .value
+                    .clone()
+            })
+            .collect();
+        assert_eq!(
+            codes,
+            vec![
+                String::from(ErrorCodes::Ok),
+                String::from(ErrorCodes::WasmRuntimeError),
+            ]
+        );
+        assert!(shell.next_wrapper().is_none());
+    }
+
+    #[cfg(not(feature = "ABCI"))]
+    /// Test that the decrypted txs applied speculatively in parallel have the
+    /// same results, gas and changes as when they're applied one after
+    /// another, both for the txs that write different keys and for a tx that
+    /// reads a key written by a tx before it
+    #[test]
+    fn test_speculative_txs_match_sequential() {
+        let (mut shell, _) = setup();
+        let (mut sequential, _) = setup();
+        let keypair = gen_keypair();
+        let mut wasm_path = top_level_directory();
+        wasm_path.push("wasm_for_tests/tx_write_storage_key.wasm");
+        let tx_code = std::fs::read(wasm_path)
+            .expect("Expected a file at given code path");
+        let keys = ["speculative_a", "speculative_b", "speculative_a"];
+
+        let mut processed_txs = vec![];
+        for key in keys {
+            let raw_tx =
+                Tx::new(tx_code.clone(), Some(key.as_bytes().to_vec()))
+                    .with_chain_id(ChainId::default())
+                    .sign(&keypair);
+            let wrapper = WrapperTx::new(
+                Fee {
+                    amount: 0.into(),
+                    token: xan(),
+                },
+                &keypair,
+                Epoch(0),
+                0.into(),
+                raw_tx.clone(),
+                Default::default(),
+            );
+            shell.enqueue_tx(wrapper);
+            processed_txs.push(ProcessedTx {
+                tx: Tx::from(TxType::Decrypted(DecryptedTx::Decrypted(raw_tx)))
+                    .to_bytes(),
+                result: TxResult {
+                    code: ErrorCodes::Ok.into(),
+                    info: "".into(),
+                },
+            });
+        }
+        let req = FinalizeBlock {
+            txs: processed_txs.clone(),
+            reject_all_decrypted: false,
+            ..Default::default()
+        };
+        // all the txs are applied speculatively
+        let speculative = shell
+            .shell
+            .apply_txs_speculatively(&req, DateTimeUtc::now());
+        assert_eq!(speculative.len(), keys.len());
+        let events = shell.finalize_block(req).expect("Test failed");
+
+        // apply the same txs one after another
+        for (event, processed_tx) in events.iter().zip(&processed_txs) {
+            let tx = Tx::try_from(processed_tx.tx.as_ref()).unwrap();
+            let tx_type = process_tx(tx).expect("Test failed");
+            let shell = &mut sequential.shell;
+            let result = protocol::apply_tx(
+                tx_type,
+                processed_tx.tx.len(),
+                &mut BlockGasMeter::default(),
+                &mut shell.write_log,
+                &shell.storage,
+                &mut shell.vp_wasm_cache,
+                &mut shell.tx_wasm_cache,
+            )
+            .expect("Test failed");
+            assert!(result.is_accepted());
+            shell.write_log.commit_tx();
+
+            let attribute = |key: &str| {
+                event
+                    .attributes
+                    .iter()
+                    .find(|attr| attr.key.as_str() == key)
+                    .expect("Test failed")
+                    .value
+                    .clone()
+            };
+            assert_eq!(attribute("code"), String::from(ErrorCodes::Ok));
+            assert_eq!(attribute("gas_used"), result.gas_used.to_string());
+            assert_eq!(attribute("info"), result.to_string());
+        }
+        for key in keys {
+            let key = Key::parse(key).expect("Test failed");
+            let value = |write_log: &WriteLog| match write_log.read(&key).0 {
+                Some(StorageModification::Write { value }) => value.clone(),
+                _ => panic!("Test failed"),
+            };
+            assert_eq!(
+                value(&shell.shell.write_log),
+                value(&sequential.shell.write_log)
+            );
+        }
+    }
+
+    #[cfg(not(feature = "ABCI"))]
+    /// Test that the wrapper and decrypted txs that expired before the time
+    /// of the block are not applied and that the expired wrapper isn't added
+    /// to the queue of txs to be decrypted
+    #[test]
+    fn test_expired_txs_rejected() {
+        let (mut shell, _) = setup();
+        let keypair = gen_keypair();
+        let expiration = DateTimeUtc::now() - Duration::hours(1);
+        let raw_tx = Tx::new(
+            "wasm_code".as_bytes().to_owned(),
+            Some(String::from("transaction data").as_bytes().to_owned()),
+        )
+        .with_chain_id(ChainId::default())
+        .with_expiration(expiration);
+        let wrapper = WrapperTx::new(
+            Fee {
+                amount: 0.into(),
+                token: xan(),
+            },
+            &keypair,
+            Epoch(0),
+            0.into(),
+            raw_tx.clone(),
+            Default::default(),
+        );
+        shell.enqueue_tx(wrapper.clone());
+        let expired_wrapper = wrapper
+            .to_unsigned_tx(ChainId::default())
+            .with_expiration(expiration)
+            .sign(&keypair);
+
+        let processed_txs = vec![
+            ProcessedTx {
+                tx: Tx::from(TxType::Decrypted(DecryptedTx::Decrypted(raw_tx)))
+                    .to_bytes(),
+                result: TxResult {
+                    code: ErrorCodes::Ok.into(),
+                    info: "".into(),
+                },
+            },
+            ProcessedTx {
+                tx: expired_wrapper.to_bytes(),
+                result: TxResult {
+                    code: ErrorCodes::Ok.into(),
+                    info: "".into(),
+                },
+            },
+        ];
+        let events = shell
+            .finalize_block(FinalizeBlock {
+                txs: processed_txs,
+                reject_all_decrypted: false,
+                ..Default::default()
+            })
+            .expect("Test failed");
+        for event in &events[..2] {
+            let code = event
+                .attributes
+                .iter()
+                .find(|attr| attr.key.as_str() == "code")
+                .expect("Test failed")
+                .value
+                .as_str();
+            assert_eq!(code, String::from(ErrorCodes::ExpiredTx).as_str());
+        }
+        assert!(shell.next_wrapper().is_none());
+    }
+
     #[cfg(feature = "ABCI")]
     /// Check that if a decrypted tx was rejected by [`process_proposal`],
     /// check that the correct event is returned.
@@ -814,7 +1520,11 @@ mod test_finalize_block {
         let ts = "01/10/1995".as_bytes();
         let inner_tx =
             anoma::types::transaction::encrypted::EncryptedTx::encrypt(
-                tx_code_hash,tx_code, tx_data, ts, pubkey,
+                tx_code_hash,
+                tx_code,
+                tx_data,
+                ts,
+                pubkey,
             );
         let wrapper = WrapperTx {
             fee: Fee {
@@ -831,7 +1541,7 @@ mod test_finalize_block {
             tx: Tx::from(TxType::Decrypted(DecryptedTx::Undecryptable(
                 wrapper.clone(),
             )))
-                .to_bytes(),
+            .to_bytes(),
             result: TxResult {
                 code: ErrorCodes::Ok.into(),
                 info: "".into(),
@@ -883,13 +1593,16 @@ mod test_finalize_block {
         // not valid tx bytes
         let tx_code = "garbage code".as_bytes().to_owned();
         let tx_data = "garbage data".as_bytes().to_owned();
-        let tx = Tx::new(tx_code,Some(tx_data));
-        let (hash_to_encrypt,code_to_encrypt,
-            data_to_encrypt, ts_to_encrypt) = tx.tx_to_encrypt();
+        let tx = Tx::new(tx_code, Some(tx_data));
+        let (hash_to_encrypt, code_to_encrypt, data_to_encrypt, ts_to_encrypt) =
+            tx.tx_to_encrypt();
         let inner_tx =
             anoma::types::transaction::encrypted::EncryptedTx::encrypt(
-                &hash_to_encrypt,&code_to_encrypt,
-                &data_to_encrypt, &ts_to_encrypt, pubkey,
+                &hash_to_encrypt,
+                &code_to_encrypt,
+                &data_to_encrypt,
+                &ts_to_encrypt,
+                pubkey,
             );
         let wrapper = WrapperTx {
             fee: Fee {
@@ -906,7 +1619,7 @@ mod test_finalize_block {
             tx: Tx::from(TxType::Decrypted(DecryptedTx::Undecryptable(
                 wrapper,
             )))
-                .to_bytes(),
+            .to_bytes(),
             result: TxResult {
                 code: ErrorCodes::Ok.into(),
                 info: "".into(),
@@ -944,7 +1657,7 @@ mod test_finalize_block {
                     .value
                     .clone(),
             )
-                .expect("Test failed");
+            .expect("Test failed");
             assert!(log.contains("Transaction could not be decrypted."))
         }
         // check that the corresponding wrapper tx was removed from the queue
@@ -974,7 +1687,8 @@ mod test_finalize_block {
                         .as_bytes()
                         .to_owned(),
                 ),
-            );
+            )
+            .with_chain_id(ChainId::default());
             let wrapper_tx = WrapperTx::new(
                 Fee {
                     amount: 0.into(),
@@ -1017,7 +1731,9 @@ mod test_finalize_block {
                 raw_tx.clone(),
                 Default::default(),
             );
-            let wrapper = wrapper_tx.sign(&keypair).expect("Test failed");
+            let wrapper = wrapper_tx
+                .sign(&keypair, ChainId::default())
+                .expect("Test failed");
             valid_txs.push(wrapper_tx);
             processed_txs.push(ProcessedTx {
                 tx: wrapper.to_bytes(),
@@ -1146,7 +1862,9 @@ mod test_finalize_block {
             raw_tx,
             Default::default(),
         );
-        let wrapper = wrapper_tx.sign(&keypair).expect("Test failed");
+        let wrapper = wrapper_tx
+            .sign(&keypair, ChainId::default())
+            .expect("Test failed");
         valid_txs.push(wrapper_tx);
         processed_txs.push(ProcessedTx {
             tx: wrapper.to_bytes(),
@@ -1277,4 +1995,124 @@ mod test_finalize_block {
         }
         assert_eq!(counter, 3);
     }
+
+    /// Test that the fees of the accepted wrapper txs and the block reward
+    /// are paid to the block proposer and to the treasury and that the reward
+    /// is added to the total supply
+    #[test]
+    fn test_block_rewards_paid_to_proposer() {
+        let (mut shell, _) = setup();
+        let keypair = gen_keypair();
+        let payer = Address::from(&keypair.ref_to());
+        shell.credit_balance(&xan(), &payer, token::Amount::from(100));
+
+        // the genesis validator proposes the block
+        let storage = &shell.shell.storage;
+        let (current_epoch, _) = storage.get_current_epoch();
+        let proposer = storage
+            .read_validator_set()
+            .get(current_epoch)
+            .expect("Test failed")
+            .active
+            .iter()
+            .next()
+            .expect("Test failed")
+            .address
+            .clone();
+        let tm_address = storage
+            .read_validator_consensus_key(&proposer)
+            .and_then(|keys| keys.get(current_epoch).cloned())
+            .map(|key| tm_consensus_key_raw_hash(&key))
+            .and_then(|raw_hash| hex::decode(raw_hash).ok())
+            .expect("Test failed");
+        let proposer_balance = shell.shell.read_balance(&xan(), &proposer);
+        let treasury_balance =
+            shell.shell.read_balance(&xan(), &treasury_address);
+        let (inflation, _) =
+            parameters::read_inflation(storage).expect("Test failed");
+        let reward = inflation.expect("Test failed").block_reward;
+        let supply_key = token::supply_key(&xan());
+        let supply = shell
+            .shell
+            .read_storage_key::<token::Amount>(&supply_key)
+            .expect("Test failed");
+        let fee_share = shell
+            .shell
+            .read_storage_key::<u64>(&treasury_storage::get_fee_share_key())
+            .expect("Test failed");
+        let treasury_share = treasury::fee_share_of(
+            token::Amount::from(10) + reward,
+            fee_share,
+        );
+        assert_ne!(treasury_share, token::Amount::default());
+
+        let wrapper = WrapperTx::new(
+            Fee {
+                amount: 10.into(),
+                token: xan(),
+            },
+            &keypair,
+            Epoch(0),
+            0.into(),
+            Tx::new("wasm_code".as_bytes().to_owned(), None),
+            Default::default(),
+        )
+        .sign(&keypair, ChainId::default())
+        .expect("Test failed");
+        let mut req = FinalizeBlock {
+            txs: vec![ProcessedTx {
+                tx: wrapper.to_bytes(),
+                result: TxResult {
+                    code: ErrorCodes::Ok.into(),
+                    info: "".into(),
+                },
+            }],
+            ..Default::default()
+        };
+        req.header.proposer_address =
+            tm_address.try_into().expect("Test failed");
+        let events = shell.finalize_block(req).expect("Test failed");
+        assert_eq!(
+            events.last().expect("Test failed").r#type,
+            "block_rewards"
+        );
+
+        assert_eq!(
+            shell.shell.read_balance(&xan(), &payer),
+            token::Amount::from(90)
+        );
+        assert_eq!(
+            shell.shell.read_balance(&xan(), &proposer),
+            proposer_balance + token::Amount::from(10) + reward
+                - treasury_share
+        );
+        assert_eq!(
+            shell.shell.read_balance(&xan(), &treasury_address),
+            treasury_balance + treasury_share
+        );
+        assert_eq!(
+            shell.shell.read_balance(&xan(), &pos::ADDRESS),
+            shell.shell.get_balance(&xan(), &pos::ADDRESS).unwrap_or_default()
+        );
+        assert_eq!(shell.shell.read_amount(&supply_key), Some(supply + reward));
+    }
+
+    /// Test that finalizing a block at an already committed height fails
+    /// instead of applying it again
+    #[test]
+    fn test_replayed_block_rejected() {
+        let (mut shell, _) = setup();
+        shell.apply_block(vec![]);
+        let last_height = shell.shell.storage.last_height;
+
+        let mut req = FinalizeBlock::default();
+        req.header.height = last_height.0.try_into().expect("Test failed");
+        match shell.finalize_block(req) {
+            Err(Error::BlockReplayed(height, last)) => {
+                assert_eq!(height, last_height);
+                assert_eq!(last, last_height);
+            }
+            _ => panic!("Test failed"),
+        }
+    }
 }
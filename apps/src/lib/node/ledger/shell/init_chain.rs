@@ -11,12 +11,18 @@ use tendermint_proto::abci;
 use tendermint_proto::crypto::PublicKey as TendermintPublicKey;
 #[cfg(not(feature = "ABCI"))]
 use tendermint_proto::google::protobuf;
+#[cfg(not(feature = "ABCI"))]
+use tendermint_proto::types::{BlockParams, ValidatorParams};
 #[cfg(feature = "ABCI")]
 use tendermint_proto_abci::abci;
 #[cfg(feature = "ABCI")]
+use tendermint_proto_abci::abci::BlockParams;
+#[cfg(feature = "ABCI")]
 use tendermint_proto_abci::crypto::PublicKey as TendermintPublicKey;
 #[cfg(feature = "ABCI")]
 use tendermint_proto_abci::google::protobuf;
+#[cfg(feature = "ABCI")]
+use tendermint_proto_abci::types::ValidatorParams;
 
 use super::*;
 use crate::wasm_loader;
@@ -68,8 +74,21 @@ where
             (Utc.timestamp(ts.seconds, ts.nanos as u32)).into();
 
         genesis.parameters.init_storage(&mut self.storage);
+        self.update_mempool_fee_tokens();
         genesis.gov_params.init_storage(&mut self.storage);
         genesis.treasury_params.init_storage(&mut self.storage);
+        // The maximum evidence size is needed for the evidence parameters
+        // updates after the genesis
+        self.storage
+            .write(
+                &parameters::storage::get_max_evidence_bytes_key(),
+                genesis
+                    .consensus_params
+                    .max_evidence_bytes
+                    .try_to_vec()
+                    .expect("encode max evidence bytes"),
+            )
+            .expect("Unable to set the max evidence bytes");
 
         // Depends on parameters being initialized
         self.storage
@@ -265,13 +284,31 @@ where
             current_epoch,
         );
         ibc::init_genesis_storage(&mut self.storage);
+        // Depends on the genesis balances and bonds
+        self.update_inflation();
 
         let evidence_params = self.get_evidence_params(
             &genesis.parameters.epoch_duration,
             &genesis.pos_params,
         );
+        let consensus_params = &genesis.consensus_params;
+        let block_params = BlockParams {
+            max_bytes: consensus_params
+                .max_block_bytes
+                .try_into()
+                .expect("unexpected max block bytes"),
+            // Unlimited when set to -1
+            max_gas: consensus_params.max_block_gas.map_or(-1, |max_gas| {
+                max_gas.try_into().expect("unexpected max block gas")
+            }),
+        };
+        let validator_params = ValidatorParams {
+            pub_key_types: consensus_params.validator_pub_key_types.clone(),
+        };
         response.consensus_params = Some(ConsensusParams {
+            block: Some(block_params),
             evidence: Some(evidence_params),
+            validator: Some(validator_params),
             ..response.consensus_params.unwrap_or_default()
         });
 
@@ -11,24 +11,29 @@ mod init_chain;
 mod prepare_proposal;
 mod process_proposal;
 mod queries;
+#[cfg(any(test, feature = "testing"))]
+pub mod test_utils;
 
-use std::collections::HashSet;
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::convert::{TryFrom, TryInto};
 use std::mem;
 use std::path::{Path, PathBuf};
 #[allow(unused_imports)]
 use std::rc::Rc;
 use std::str::FromStr;
+use std::sync::{Arc, Mutex, RwLock};
 
 use anoma::ledger::gas::BlockGasMeter;
 use anoma::ledger::pos::anoma_proof_of_stake::types::{
     ActiveValidator, ValidatorSetUpdate,
 };
 use anoma::ledger::pos::anoma_proof_of_stake::PosBase;
-use anoma::ledger::storage::write_log::WriteLog;
+use anoma::ledger::storage::read_cache::ReadCache;
+use anoma::ledger::storage::write_log::{Error as WriteLogError, WriteLog};
 use anoma::ledger::storage::{
-    DBIter, Sha256Hasher, Storage, StorageHasher, DB,
+    DBIter, Error as StorageError, Sha256Hasher, Storage, StorageHasher, DB,
 };
+use anoma::ledger::parameters::FeeTokens;
 use anoma::ledger::{ibc, parameters, pos};
 use anoma::proto::{self, Tx};
 use anoma::types::chain::ChainId;
@@ -36,8 +41,8 @@ use anoma::types::key::*;
 use anoma::types::storage::{BlockHeight, Key};
 use anoma::types::time::{DateTimeUtc, TimeZone, Utc};
 use anoma::types::transaction::{
-    hash_tx, process_tx, verify_decrypted_correctly, AffineCurve, DecryptedTx,
-    EllipticCurve, PairingEngine, TxType, WrapperTx,
+    hash_tx, process_tx, process_tx_unverified, verify_decrypted_correctly,
+    AffineCurve, DecryptedTx, EllipticCurve, PairingEngine, TxType, WrapperTx,
 };
 use anoma::types::{address, token};
 use anoma::vm::wasm::{TxCache, VpCache};
@@ -47,7 +52,8 @@ use num_derive::{FromPrimitive, ToPrimitive};
 use num_traits::{FromPrimitive, ToPrimitive};
 #[cfg(not(feature = "ABCI"))]
 use tendermint_proto::abci::{
-    self, Evidence, RequestPrepareProposal, ValidatorUpdate,
+    self, Evidence, RequestPrepareProposal, RequestProcessProposal,
+    ResponseProcessProposal, ValidatorUpdate,
 };
 #[cfg(not(feature = "ABCI"))]
 use tendermint_proto::crypto::public_key;
@@ -67,8 +73,13 @@ use tower_abci::{request, response};
 use tower_abci_old::{request, response};
 
 use super::rpc;
+use crate::cli::{anoma_version, args};
 use crate::config::{genesis, TendermintMode};
+use crate::node::ledger::block_results::BlockResults;
+use crate::node::ledger::event_stream::StreamEvent;
 use crate::node::ledger::events::Event;
+use crate::node::ledger::indexer::Indexer;
+use crate::node::ledger::rejected_txs::RejectedTxs;
 use crate::node::ledger::shims::abcipp_shim_types::shim;
 use crate::node::ledger::shims::abcipp_shim_types::shim::response::TxResult;
 use crate::node::ledger::{protocol, storage, tendermint_node};
@@ -103,6 +114,15 @@ pub enum Error {
     Broadcaster(tokio::sync::mpsc::error::TryRecvError),
     #[error("Error executing proposal {0}: {1}")]
     BadProposal(u64, String),
+    #[error("Error committing the block's write log: {0}")]
+    WriteLogCommit(WriteLogError),
+    #[error("Error committing the block to storage: {0}")]
+    StorageCommit(StorageError),
+    #[error(
+        "The block at height {0} has already been committed, the last \
+         committed height is {1}"
+    )]
+    BlockReplayed(BlockHeight, BlockHeight),
 }
 
 /// The different error codes that the ledger may
@@ -117,6 +137,11 @@ pub enum ErrorCodes {
     InvalidOrder = 4,
     ExtraTxs = 5,
     Undecryptable = 6,
+    TxTooLarge = 7,
+    TooManyPendingTxs = 8,
+    InvalidChainId = 9,
+    ExpiredTx = 10,
+    InvalidFee = 11,
 }
 
 impl From<ErrorCodes> for u32 {
@@ -133,15 +158,26 @@ impl From<ErrorCodes> for String {
 
 pub type Result<T> = std::result::Result<T, Error>;
 
-pub fn reset(config: config::Ledger) -> Result<()> {
+pub fn reset(
+    config: config::Ledger,
+    args::LedgerReset {
+        keep_keys,
+        keep_addr_book,
+    }: args::LedgerReset,
+) -> Result<()> {
     // simply nuke the DB files
     let db_path = &config.db_dir();
     match std::fs::remove_dir_all(&db_path) {
         Err(e) if e.kind() == std::io::ErrorKind::NotFound => (),
         res => res.map_err(Error::RemoveDB)?,
     };
+    // nuke the transaction index, which would be out of sync with the DB
+    match std::fs::remove_dir_all(config.tx_index_dir()) {
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => (),
+        res => res.map_err(Error::RemoveDB)?,
+    };
     // reset Tendermint state
-    tendermint_node::reset(config.tendermint_dir())
+    tendermint_node::reset(config.tendermint_dir(), keep_keys, keep_addr_book)
         .map_err(Error::Tendermint)?;
     Ok(())
 }
@@ -168,6 +204,18 @@ impl ShellMode {
     }
 }
 
+/// The version of the ledger's protocol reported to Tendermint. Nodes with
+/// different protocol versions produce different block headers, so it must be
+/// incremented with every consensus breaking change.
+pub const PROTOCOL_VERSION: u64 = 0;
+
+/// The default maximum size in bytes of a tx accepted into the mempool
+pub const DEFAULT_MEMPOOL_MAX_TX_BYTES: u64 = 1024 * 1024;
+
+/// The default maximum number of pending wrapper txs from the same fee payer
+/// accepted into the mempool
+pub const DEFAULT_MEMPOOL_MAX_TXS_PER_SENDER: u64 = 100;
+
 #[derive(Clone, Debug)]
 pub enum MempoolTxType {
     /// A transaction that has not been validated by this node before
@@ -209,6 +257,140 @@ where
     tx_wasm_cache: TxCache<WasmCacheRwAccess>,
     /// Proposal execution tracking
     pub proposal_data: HashSet<u64>,
+    /// A channel for the event stream server, if it's running
+    event_stream_sender: Option<UnboundedSender<StreamEvent>>,
+    /// The events of the current block to be sent to the event stream when
+    /// the block is committed
+    stream_events: Vec<StreamEvent>,
+    /// The results of the current block to be persisted when the block is
+    /// committed
+    block_results: BlockResults,
+    /// The transaction indexer, if it's enabled
+    indexer: Option<Indexer>,
+    /// The limits on the txs accepted into the mempool, shared with the
+    /// concurrent `CheckTx` requests
+    pub(super) mempool_limits: Arc<MempoolLimits>,
+    /// The log of the rejected txs, shared with the concurrent `CheckTx`
+    /// requests and the status server
+    pub(super) rejected_txs: Arc<RejectedTxs>,
+}
+
+/// The limits on the txs accepted into the mempool, to stop a sender from
+/// cheaply flooding it.
+#[derive(Debug)]
+pub struct MempoolLimits {
+    /// The maximum size in bytes of a tx
+    max_tx_bytes: u64,
+    /// The maximum number of pending wrapper txs from the same fee payer
+    max_txs_per_sender: u64,
+    /// The number of wrapper txs in the mempool from each fee payer.
+    /// Tendermint re-checks all the txs left in the mempool after a block is
+    /// committed, so the counts are reset on commit and rebuilt by the
+    /// re-checks.
+    pending_txs: Mutex<HashMap<address::Address, u64>>,
+    /// The tokens accepted for the fees with their minimum gas prices, as of
+    /// the last committed block
+    fee_tokens: RwLock<FeeTokens>,
+}
+
+impl MempoolLimits {
+    pub fn new(config: &config::Shell) -> Self {
+        Self {
+            max_tx_bytes: config
+                .mempool_max_tx_bytes
+                .unwrap_or(DEFAULT_MEMPOOL_MAX_TX_BYTES),
+            max_txs_per_sender: config
+                .mempool_max_txs_per_sender
+                .unwrap_or(DEFAULT_MEMPOOL_MAX_TXS_PER_SENDER),
+            pending_txs: Mutex::new(HashMap::new()),
+            fee_tokens: RwLock::new(parameters::default_fee_tokens()),
+        }
+    }
+
+    /// Set the tokens accepted for the fees, called when the parameters may
+    /// have changed
+    pub fn set_fee_tokens(&self, fee_tokens: FeeTokens) {
+        *self.fee_tokens.write().unwrap() = fee_tokens;
+    }
+
+    /// Forget the pending txs, called when a block is committed
+    pub fn reset_pending_txs(&self) {
+        self.pending_txs.lock().unwrap().clear();
+    }
+
+    /// Count a new pending tx from the given sender. Returns an error if the
+    /// sender already has the maximum number of pending txs.
+    fn add_pending_tx(
+        &self,
+        sender: address::Address,
+    ) -> std::result::Result<(), String> {
+        let mut pending_txs = self.pending_txs.lock().unwrap();
+        let count = pending_txs.entry(sender.clone()).or_insert(0);
+        if *count >= self.max_txs_per_sender {
+            return Err(format!(
+                "The sender {} already has the maximum of {} pending txs in \
+                 the mempool",
+                sender, self.max_txs_per_sender
+            ));
+        }
+        *count += 1;
+        Ok(())
+    }
+}
+
+/// Check that a wrapper tx is bound to the chain with the given ID, so that a
+/// tx signed for another chain can't be replayed on this one.
+fn check_chain_id(
+    tx_chain_id: Option<&ChainId>,
+    chain_id: &ChainId,
+) -> std::result::Result<(), String> {
+    match tx_chain_id {
+        Some(tx_chain_id) if tx_chain_id == chain_id => Ok(()),
+        Some(tx_chain_id) => Err(format!(
+            "The tx is for the chain {}, but this chain is {}",
+            tx_chain_id, chain_id
+        )),
+        None => Err(format!(
+            "The tx is not bound to a chain, it must be for the chain {}",
+            chain_id
+        )),
+    }
+}
+
+/// Check that the fee of a wrapper tx is paid in one of the tokens accepted
+/// by governance and that it covers the token's minimum gas price for the
+/// tx's gas limit.
+fn check_fee(
+    wrapper: &WrapperTx,
+    fee_tokens: &FeeTokens,
+) -> std::result::Result<(), String> {
+    let min_gas_price = fee_tokens.get(&wrapper.fee.token).ok_or_else(|| {
+        format!("The token {} is not accepted for fees", wrapper.fee.token)
+    })?;
+    let gas_limit = u64::from(&wrapper.gas_limit);
+    match u64::from(*min_gas_price).checked_mul(gas_limit) {
+        Some(min_fee) if u64::from(wrapper.fee.amount) >= min_fee => Ok(()),
+        _ => Err(format!(
+            "The fee of {} is below the minimum gas price of {} of the token \
+             {} for the gas limit of {}",
+            wrapper.fee.amount, min_gas_price, wrapper.fee.token, gas_limit
+        )),
+    }
+}
+
+/// Check that a tx hasn't expired by the given block time, so that a signed
+/// tx is only valid for as long as its signer allowed.
+fn check_expiration(
+    tx_expiration: Option<DateTimeUtc>,
+    block_time: DateTimeUtc,
+) -> std::result::Result<(), String> {
+    match tx_expiration {
+        Some(expiration) if expiration < block_time => Err(format!(
+            "The tx expired at {}, before the block time {}",
+            expiration.0, block_time.0
+        )),
+        _ => Ok(()),
+    }
 }
 
 impl<D, H> Shell<D, H>
@@ -218,24 +400,36 @@ where
 {
     /// Create a new shell from a path to a database and a chain id. Looks
     /// up the database with this data and tries to load the last state.
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         config: config::Ledger,
         wasm_dir: PathBuf,
         broadcast_sender: UnboundedSender<Vec<u8>>,
+        event_stream_sender: Option<UnboundedSender<StreamEvent>>,
         db_cache: Option<&D::Cache>,
         vp_wasm_compilation_cache: u64,
         tx_wasm_compilation_cache: u64,
+        rejected_txs: Arc<RejectedTxs>,
     ) -> Self {
         let chain_id = config.chain_id;
         let db_path = config.shell.db_dir(&chain_id);
+        let tx_index_dir = config.shell.tx_index_dir(&chain_id);
         let base_dir = config.shell.base_dir;
         let mode = config.tendermint.tendermint_mode;
+        let tx_index = config.shell.tx_index;
+        let mempool_limits = Arc::new(MempoolLimits::new(&config.shell));
         if !Path::new(&base_dir).is_dir() {
             std::fs::create_dir(&base_dir)
                 .expect("Creating directory for Anoma should not fail");
         }
         // load last state from storage
         let mut storage = Storage::open(db_path, chain_id.clone(), db_cache);
+        storage.sync_commit = config.shell.sync_commit;
+        storage.async_commit = config.shell.async_commit;
+        storage.archive = config.shell.archive;
+        if let Some(bytes) = config.shell.storage_read_cache_bytes {
+            storage.read_cache = ReadCache::new(bytes as usize);
+        }
         storage
             .load_last_state()
             .map_err(|e| {
@@ -247,6 +441,10 @@ where
             base_dir.join(chain_id.as_str()).join("vp_wasm_cache");
         let tx_wasm_cache_dir =
             base_dir.join(chain_id.as_str()).join("tx_wasm_cache");
+        let indexer = tx_index.then(|| {
+            Indexer::open(tx_index_dir)
+                .expect("The transaction indexer cannot be opened")
+        });
         // load in keys and address from wallet if mode is set to `Validator`
         let mode = match mode {
             TendermintMode::Validator => {
@@ -297,7 +495,7 @@ where
             TendermintMode::Seed => ShellMode::Seed,
         };
 
-        Self {
+        let shell = Self {
             chain_id,
             storage,
             gas_meter: BlockGasMeter::default(),
@@ -315,9 +513,111 @@ where
                 tx_wasm_compilation_cache as usize,
             ),
             proposal_data: HashSet::new(),
+            event_stream_sender,
+            stream_events: vec![],
+            block_results: BlockResults::default(),
+            indexer,
+            mempool_limits,
+            rejected_txs,
+        };
+        shell.update_mempool_fee_tokens();
+        shell
+    }
+
+    /// Update the tokens accepted for the fees in the mempool from the
+    /// parameters in storage
+    fn update_mempool_fee_tokens(&self) {
+        match parameters::read_fee_tokens(&self.storage) {
+            Ok((fee_tokens, _gas)) => {
+                self.mempool_limits.set_fee_tokens(fee_tokens)
+            }
+            Err(err) => {
+                tracing::error!("Failed to read the fee tokens: {}", err)
+            }
         }
     }
 
+    /// Compute the inflation of the current epoch from the native token's
+    /// total supply and stake and write it into the parameters storage. It's
+    /// written directly into the storage, so it must be called before the
+    /// txs of the block are applied.
+    fn update_inflation(&mut self) {
+        let (current_epoch, _gas) = self.storage.get_current_epoch();
+        let supply_key = token::supply_key(&address::xan());
+        let total_supply = match self.read_storage_key(&supply_key) {
+            Some(total_supply) => total_supply,
+            None => {
+                // The total supply is summed up from the balances at genesis
+                // and on the chains initialized before it was tracked. From
+                // then on, it's updated when the block rewards are minted.
+                let total_supply = self.sum_native_balances();
+                self.storage
+                    .write(&supply_key, total_supply.try_to_vec().unwrap())
+                    .expect("Writing the total supply shouldn't fail");
+                total_supply
+            }
+        };
+        let total_staked = self
+            .storage
+            .read_validator_set()
+            .get(current_epoch)
+            .map(|validator_set| {
+                validator_set
+                    .active
+                    .iter()
+                    .chain(validator_set.inactive.iter())
+                    .filter_map(|validator| {
+                        self.storage
+                            .read_validator_total_deltas(&validator.address)
+                            .and_then(|deltas| deltas.get(current_epoch))
+                    })
+                    .map(token::Amount::from_change)
+                    .try_fold(token::Amount::default(), |sum, stake| {
+                        sum.checked_add(stake)
+                    })
+                    .expect("The total stake shouldn't overflow")
+            })
+            .unwrap_or_default();
+
+        let (params, _gas) = parameters::read(&self.storage)
+            .expect("Couldn't read the protocol parameters");
+        let (last_inflation, _gas) = parameters::read_inflation(&self.storage)
+            .expect("Couldn't read the inflation");
+        let inflation = params.inflation_params.compute(
+            current_epoch,
+            last_inflation.map(|inflation| inflation.rate),
+            total_supply,
+            total_staked,
+            &params.epoch_duration,
+            &params.max_expected_time_per_block,
+        );
+        tracing::info!(
+            "Inflation rate in epoch {}: {} basis points at a staked ratio of \
+             {} basis points, block reward {}",
+            current_epoch,
+            inflation.rate,
+            inflation.staked_ratio,
+            inflation.block_reward
+        );
+        parameters::write_inflation(&mut self.storage, &inflation)
+            .expect("Writing the inflation shouldn't fail");
+    }
+
+    /// Sum up the balances of the native token in storage
+    fn sum_native_balances(&self) -> token::Amount {
+        let (balances, _gas) = self
+            .storage
+            .iter_prefix(&token::balance_prefix(&address::xan()));
+        balances
+            .filter_map(|(_key, value, _gas)| {
+                token::Amount::try_from_slice(&value).ok()
+            })
+            .try_fold(token::Amount::default(), |sum, balance| {
+                sum.checked_add(balance)
+            })
+            .expect("The total supply of the native token shouldn't overflow")
+    }
+
     /// Iterate lazily over the wrapper txs in order
     #[cfg(not(feature = "ABCI"))]
     fn next_wrapper(&mut self) -> Option<&WrapperTx> {
@@ -336,10 +636,27 @@ where
         self.storage.tx_queue.rewind()
     }
 
+    /// Flush the storage's DB to disk and wait for it to finish. Used on
+    /// shutdown.
+    pub fn flush_storage(&self) {
+        match self.storage.db.flush(true) {
+            Ok(()) => tracing::info!("Flushed the storage"),
+            Err(err) => tracing::error!("Failed to flush the storage: {}", err),
+        }
+    }
+
     /// Load the Merkle root hash and the height of the last committed block, if
-    /// any. This is returned when ABCI sends an `info` request.
+    /// any. This is returned when ABCI sends an `info` request, together with
+    /// the node's version, the protocol version and the stored chain ID, so
+    /// that mismatched binaries can be detected.
     pub fn last_state(&mut self) -> response::Info {
-        let mut response = response::Info::default();
+        let (chain_id, _gas) = self.storage.get_chain_id();
+        let mut response = response::Info {
+            data: chain_id,
+            version: anoma_version().to_owned(),
+            app_version: PROTOCOL_VERSION,
+            ..Default::default()
+        };
         let result = self.storage.get_state();
 
         match result {
@@ -451,19 +768,7 @@ where
                         }
                     };
                 let validator_raw_hash = match evidence.validator {
-                    Some(validator) => {
-                        match String::from_utf8(validator.address) {
-                            Ok(raw_hash) => raw_hash,
-                            Err(err) => {
-                                tracing::error!(
-                                    "Evidence failed to decode validator \
-                                     address from utf-8 with {}",
-                                    err
-                                );
-                                continue;
-                            }
-                        }
-                    }
+                    Some(validator) => tm_raw_hash_to_string(validator.address),
                     None => {
                         tracing::error!(
                             "Evidence without a validator {:#?}",
@@ -525,55 +830,256 @@ where
     }
 
     /// Commit a block. Persist the application state and return the Merkle root
-    /// hash.
-    pub fn commit(&mut self) -> response::Commit {
+    /// hash. On error, the block may be only partially committed in memory,
+    /// so the shell's state can't be used anymore and the node has to be shut
+    /// down. When the block is written to the DB in the background, the root
+    /// is returned before the write is finished and the next block waits for
+    /// it. If the write fails, or the writes aren't synced and the node
+    /// crashes, the block is missing from the DB and Tendermint replays it on
+    /// restart.
+    pub fn commit(&mut self) -> Result<response::Commit> {
         let mut response = response::Commit::default();
-        // commit changes from the write-log to storage
+        let span = tracing::info_span!(
+            "storage_commit",
+            height = self.storage.block.height.0
+        );
+        let _entered = span.enter();
+        // the old values of the changes must be read before they're committed
+        let block_changes = self
+            .write_log
+            .block_changes(&self.storage)
+            .map_err(Error::WriteLogCommit)?;
+        // the block's root is computed from the in-memory merkle tree, so it
+        // can be returned before the block's changes are written to the DB
+        let root = self
+            .write_log
+            .commit_block_to_merkle_tree(&mut self.storage)
+            .map_err(Error::WriteLogCommit)?;
+        // commit changes from the write-log to storage, they're written to
+        // the DB together with the block's state
+        let mut batch = D::batch();
         self.write_log
-            .commit_block(&mut self.storage)
-            .expect("Expected committing block write log success");
+            .commit_block_to_batch(&mut self.storage, &mut batch)
+            .map_err(Error::WriteLogCommit)?;
+        // store the block's results in DB together with the block
+        let mut block_results = mem::take(&mut self.block_results);
+        block_results.storage_changes = block_changes.clone();
+        let block_results =
+            anoma::ledger::storage::types::encode(&block_results);
+        self.storage
+            .write_block_results(&block_results, &mut batch)
+            .map_err(Error::StorageCommit)?;
         // store the block's data in DB
-        self.storage.commit().unwrap_or_else(|e| {
-            tracing::error!(
-                "Encountered a storage error while committing a block {:?}",
-                e
-            )
-        });
+        self.storage
+            .commit_with_batch(batch)
+            .map_err(Error::StorageCommit)?;
+        response.data = root.0.clone();
+        // the fee tokens may have been changed by governance. They're only
+        // read when changed, as reading the DB waits for the block's write.
+        let fee_tokens_key = parameters::storage::get_fee_tokens_key();
+        if block_changes
+            .iter()
+            .any(|change| change.key == fee_tokens_key)
+        {
+            self.update_mempool_fee_tokens();
+        }
 
-        let root = self.storage.merkle_root();
         tracing::info!(
             "Committed block hash: {}, height: {}",
             root,
             self.storage.last_height,
         );
-        response.data = root.0;
-        response
+
+        // send the events of the committed block to the event stream
+        let mut stream_events = mem::take(&mut self.stream_events);
+        if let Some(indexer) = &self.indexer {
+            if let Err(err) = indexer.index_block(&stream_events) {
+                tracing::error!("Failed to index the block's txs: {}", err);
+            }
+        }
+        if let Some(sender) = &self.event_stream_sender {
+            stream_events.push(StreamEvent::storage_changes(
+                self.storage.last_height,
+                &block_changes,
+            ));
+            for event in stream_events {
+                if sender.send(event).is_err() {
+                    tracing::info!("The event stream server is not running");
+                    break;
+                }
+            }
+        }
+        Ok(response)
+    }
+
+    /// Record an event in the results of the current block. The event is
+    /// also sent to the event stream and to the indexer when the block is
+    /// committed, together with the storage keys changed with it, if either
+    /// of them is running. The event of a rejected tx is also added to the log
+    /// of the rejected txs.
+    fn record_event(&mut self, event: &Event, changed_keys: &BTreeSet<Key>) {
+        self.block_results.add_event(event);
+        self.rejected_txs.record_tx_event(event);
+        if self.event_stream_sender.is_some() || self.indexer.is_some() {
+            let height = self.storage.get_block_height().0;
+            self.stream_events.push(StreamEvent::new(
+                event,
+                height,
+                changed_keys,
+            ));
+        }
     }
 
     /// Validate a transaction request. On success, the transaction will
     /// included in the mempool and propagated to peers, otherwise it will be
-    /// rejected.
+    /// rejected. The validation doesn't depend on the shell's state, so that
+    /// it can run concurrently with the block execution. For the same reason,
+    /// the tx's expiration is checked against the node's clock, as the time of
+    /// the next block isn't known yet.
+    ///
+    /// Error codes:
+    ///   0: Ok
+    ///   1: Invalid tx
+    ///   7: Tx is larger than the maximum size
+    ///   8: Tx's fee payer has too many pending txs
+    ///   9: Wrapper tx is not bound to this chain
+    ///  10: Tx has expired
+    ///  11: Wrapper tx's fee token or amount is not accepted
     pub fn mempool_validate(
-        &self,
         tx_bytes: &[u8],
         r#_type: MempoolTxType,
+        chain_id: &ChainId,
+        limits: &MempoolLimits,
     ) -> response::CheckTx {
         let mut response = response::CheckTx::default();
+        if tx_bytes.len() as u64 > limits.max_tx_bytes {
+            response.code = ErrorCodes::TxTooLarge.into();
+            response.log = format!(
+                "Tx of {} bytes exceeds the maximum size of {} bytes",
+                tx_bytes.len(),
+                limits.max_tx_bytes
+            );
+            return response;
+        }
         match Tx::try_from(tx_bytes).map_err(Error::TxDecoding) {
-            Ok(_) => response.log = String::from("Mempool validation passed"),
+            Ok(tx) => {
+                if let Err(msg) =
+                    check_expiration(tx.expiration, DateTimeUtc::now())
+                {
+                    response.code = ErrorCodes::ExpiredTx.into();
+                    response.log = msg;
+                    return response;
+                }
+                let tx_chain_id = tx.chain_id.clone();
+                // Only the wrapper txs have a sender, their fee payer
+                if let Ok(TxType::Wrapper(wrapper)) = process_tx(tx) {
+                    if let Err(msg) =
+                        check_chain_id(tx_chain_id.as_ref(), chain_id)
+                    {
+                        response.code = ErrorCodes::InvalidChainId.into();
+                        response.log = msg;
+                        return response;
+                    }
+                    if let Err(msg) =
+                        check_fee(&wrapper, &limits.fee_tokens.read().unwrap())
+                    {
+                        response.code = ErrorCodes::InvalidFee.into();
+                        response.log = msg;
+                        return response;
+                    }
+                    if let Err(msg) = limits.add_pending_tx(wrapper.fee_payer())
+                    {
+                        response.code = ErrorCodes::TooManyPendingTxs.into();
+                        response.log = msg;
+                        return response;
+                    }
+                }
+                response.log = String::from("Mempool validation passed")
+            }
             Err(msg) => {
-                response.code = 1;
+                response.code = ErrorCodes::InvalidTx.into();
                 response.log = msg.to_string();
             }
         }
         response
     }
 
+    /// Re-validate a tx left in the mempool after a block is committed. The
+    /// tx has already passed [`Shell::mempool_validate`], so its signature
+    /// isn't verified again and only the conditions that depend on the state
+    /// are checked against the last committed block.
+    ///
+    /// Error codes:
+    ///   0: Ok
+    ///   1: Invalid tx
+    ///   8: Tx's fee payer has too many pending txs
+    ///  10: Tx has expired
+    ///  11: Wrapper tx's fee token or amount is not accepted
+    pub fn mempool_recheck(&self, tx_bytes: &[u8]) -> response::CheckTx {
+        let mut response = response::CheckTx::default();
+        let tx = match Tx::try_from(tx_bytes) {
+            Ok(tx) => tx,
+            Err(err) => {
+                response.code = ErrorCodes::InvalidTx.into();
+                response.log = Error::TxDecoding(err).to_string();
+                return response;
+            }
+        };
+        if let Some(block_time) = self.storage.last_block_time {
+            if let Err(msg) = check_expiration(tx.expiration, block_time) {
+                response.code = ErrorCodes::ExpiredTx.into();
+                response.log = msg;
+                return response;
+            }
+        }
+        if let Ok(TxType::Wrapper(wrapper)) = process_tx_unverified(tx) {
+            // check that the fee is still accepted, the parameters may have
+            // changed
+            let fee_tokens = parameters::read_fee_tokens(&self.storage)
+                .map(|(fee_tokens, _gas)| fee_tokens)
+                .unwrap_or_default();
+            if let Err(msg) = check_fee(&wrapper, &fee_tokens) {
+                response.code = ErrorCodes::InvalidFee.into();
+                response.log = msg;
+                return response;
+            }
+            // check that the fee payer can still pay the fee
+            let balance = self
+                .get_balance(&wrapper.fee.token, &wrapper.fee_payer())
+                .unwrap_or_default();
+            if wrapper.fee.amount > balance {
+                response.code = ErrorCodes::InvalidTx.into();
+                response.log = String::from(
+                    "The address given does not have sufficient balance to \
+                     pay fee",
+                );
+                return response;
+            }
+            if let Err(msg) =
+                self.mempool_limits.add_pending_tx(wrapper.fee_payer())
+            {
+                response.code = ErrorCodes::TooManyPendingTxs.into();
+                response.log = msg;
+                return response;
+            }
+        }
+        response.log = String::from("Mempool validation passed");
+        response
+    }
+
     /// Simulate validation and application of a transaction.
     fn dry_run_tx(&self, tx_bytes: &[u8]) -> response::Query {
         let mut response = response::Query::default();
         let mut gas_meter = BlockGasMeter::default();
         let mut write_log = WriteLog::default();
+        match parameters::read_tx_write_size_limits(&self.storage) {
+            Ok((size_limits, _gas)) => write_log.set_size_limits(size_limits),
+            Err(err) => {
+                response.code = 1;
+                response.log = err.to_string();
+                return response;
+            }
+        }
         let mut vp_wasm_cache = self.vp_wasm_cache.read_only();
         let mut tx_wasm_cache = self.tx_wasm_cache.read_only();
         match Tx::try_from(tx_bytes) {
@@ -643,219 +1149,22 @@ where
     }
 }
 
-/// Helper functions and types for writing unit tests
-/// for the shell
 #[cfg(test)]
-mod test_utils {
-    use std::path::PathBuf;
-
+mod tests {
     use anoma::ledger::storage::mockdb::MockDB;
-    use anoma::ledger::storage::{BlockStateWrite, MerkleTree, Sha256Hasher};
-    use anoma::types::address::{xan, EstablishedAddressGen};
-    use anoma::types::chain::ChainId;
-    use anoma::types::key::*;
+    use anoma::ledger::storage::{BlockStateWrite, MerkleTree};
+    use anoma::types::address::{btc, xan, Address, EstablishedAddressGen};
     use anoma::types::storage::{BlockHash, Epoch};
-    use anoma::types::transaction::Fee;
+    use anoma::types::time::Duration;
+    use anoma::types::transaction::{Fee, GasLimit};
     use tempfile::tempdir;
-    #[cfg(not(feature = "ABCI"))]
-    use tendermint::block::{header::Version, Header};
-    #[cfg(not(feature = "ABCI"))]
-    use tendermint::{Hash, Time};
-    #[cfg(not(feature = "ABCI"))]
-    use tendermint_proto::abci::{
-        Event as TmEvent, RequestInitChain, ResponsePrepareProposal,
-    };
-    #[cfg(not(feature = "ABCI"))]
-    use tendermint_proto::google::protobuf::Timestamp;
-    #[cfg(feature = "ABCI")]
-    use tendermint_proto_abci::abci::{Event as TmEvent, RequestInitChain};
-    #[cfg(feature = "ABCI")]
-    use tendermint_proto_abci::google::protobuf::Timestamp;
-    #[cfg(feature = "ABCI")]
-    use tendermint_stable::block::{header::Version, Header};
-    #[cfg(feature = "ABCI")]
-    use tendermint_stable::{Hash, Time};
-    use tokio::sync::mpsc::UnboundedReceiver;
 
-    use super::*;
-    use crate::node::ledger::shims::abcipp_shim_types::shim::request::{
-        FinalizeBlock, ProcessProposal,
+    use super::test_utils::{
+        gen_keypair, setup, top_level_directory, TestShell,
     };
+    use super::*;
     use crate::node::ledger::storage::{PersistentDB, PersistentStorageHasher};
 
-    /// Gets the absolute path to root directory
-    pub fn top_level_directory() -> PathBuf {
-        let mut current_path = std::env::current_dir()
-            .expect("Current directory should exist")
-            .canonicalize()
-            .expect("Current directory should exist");
-        while current_path.file_name().unwrap() != "apps" {
-            current_path.pop();
-        }
-        current_path.pop();
-        current_path
-    }
-
-    /// Generate a random public/private keypair
-    pub(super) fn gen_keypair() -> common::SecretKey {
-        use rand::prelude::ThreadRng;
-        use rand::thread_rng;
-
-        let mut rng: ThreadRng = thread_rng();
-        ed25519::SigScheme::generate(&mut rng).try_to_sk().unwrap()
-    }
-
-    /// A wrapper around the shell that implements
-    /// Drop so as to clean up the files that it
-    /// generates. Also allows illegal state
-    /// modifications for testing purposes
-    pub(super) struct TestShell {
-        pub shell: Shell<MockDB, Sha256Hasher>,
-    }
-
-    impl TestShell {
-        /// Returns a new shell paired with a broadcast receiver, which will
-        /// receives any protocol txs sent by the shell.
-        pub fn new() -> (Self, UnboundedReceiver<Vec<u8>>) {
-            let (sender, receiver) = tokio::sync::mpsc::unbounded_channel();
-            let base_dir = tempdir().unwrap().as_ref().canonicalize().unwrap();
-            let vp_wasm_compilation_cache = 50 * 1024 * 1024; // 50 kiB
-            let tx_wasm_compilation_cache = 50 * 1024 * 1024; // 50 kiB
-            (
-                Self {
-                    shell: Shell::<MockDB, Sha256Hasher>::new(
-                        config::Ledger::new(
-                            base_dir,
-                            Default::default(),
-                            TendermintMode::Validator,
-                        ),
-                        top_level_directory().join("wasm"),
-                        sender,
-                        None,
-                        vp_wasm_compilation_cache,
-                        tx_wasm_compilation_cache,
-                    ),
-                },
-                receiver,
-            )
-        }
-
-        /// Forward a InitChain request and expect a success
-        pub fn init_chain(&mut self, req: RequestInitChain) {
-            self.shell
-                .init_chain(req)
-                .expect("Test shell failed to initialize");
-        }
-
-        /// Forward the prepare proposal request and return the response
-        #[cfg(not(feature = "ABCI"))]
-        pub fn prepare_proposal(
-            &mut self,
-            req: RequestPrepareProposal,
-        ) -> ResponsePrepareProposal {
-            self.shell.prepare_proposal(req)
-        }
-
-        /// Forward a ProcessProposal request and extract the relevant
-        /// response data to return
-        pub fn process_proposal(
-            &mut self,
-            req: ProcessProposal,
-        ) -> shim::response::ProcessProposal {
-            #[cfg(not(feature = "ABCI"))]
-            {
-                self.shell.process_proposal(req)
-            }
-            #[cfg(feature = "ABCI")]
-            {
-                self.shell.process_and_decode_proposal(req)
-            }
-        }
-
-        /// Forward a FinalizeBlock request return a vector of
-        /// the events created for each transaction
-        pub fn finalize_block(
-            &mut self,
-            req: FinalizeBlock,
-        ) -> Result<Vec<TmEvent>> {
-            match self.shell.finalize_block(req) {
-                Ok(resp) => Ok(resp.events),
-                Err(err) => Err(err),
-            }
-        }
-
-        /// Add a wrapper tx to the queue of txs to be decrypted
-        /// in the current block proposal
-        #[cfg(test)]
-        pub fn enqueue_tx(&mut self, wrapper: WrapperTx) {
-            self.shell.storage.tx_queue.push(wrapper);
-            self.shell.reset_tx_queue_iter();
-        }
-
-        #[cfg(not(feature = "ABCI"))]
-        /// Get the next wrapper tx to be decoded
-        pub fn next_wrapper(&mut self) -> Option<&WrapperTx> {
-            self.shell.next_wrapper()
-        }
-
-        #[cfg(feature = "ABCI")]
-        /// Get the next wrapper tx to be decoded
-        pub fn next_wrapper(&mut self) -> Option<WrapperTx> {
-            self.shell.next_wrapper()
-        }
-    }
-
-    /// Start a new test shell and initialize it. Returns the shell paired with
-    /// a broadcast receiver, which will receives any protocol txs sent by the
-    /// shell.
-    pub(super) fn setup() -> (TestShell, UnboundedReceiver<Vec<u8>>) {
-        let (mut test, receiver) = TestShell::new();
-        test.init_chain(RequestInitChain {
-            time: Some(Timestamp {
-                seconds: 0,
-                nanos: 0,
-            }),
-            chain_id: ChainId::default().to_string(),
-            ..Default::default()
-        });
-        (test, receiver)
-    }
-
-    /// This is just to be used in testing. It is not
-    /// a meaningful default.
-    impl Default for FinalizeBlock {
-        fn default() -> Self {
-            FinalizeBlock {
-                hash: BlockHash([0u8; 32]),
-                header: Header {
-                    version: Version { block: 0, app: 0 },
-                    chain_id: String::from("test")
-                        .try_into()
-                        .expect("Should not fail"),
-                    height: 0u64.try_into().expect("Should not fail"),
-                    time: Time::now(),
-                    last_block_id: None,
-                    last_commit_hash: None,
-                    data_hash: None,
-                    validators_hash: Hash::None,
-                    next_validators_hash: Hash::None,
-                    consensus_hash: Hash::None,
-                    app_hash: Vec::<u8>::new()
-                        .try_into()
-                        .expect("Should not fail"),
-                    last_results_hash: None,
-                    evidence_hash: None,
-                    proposer_address: vec![0u8; 20]
-                        .try_into()
-                        .expect("Should not fail"),
-                },
-                byzantine_validators: vec![],
-                txs: vec![],
-                reject_all_decrypted: false,
-            }
-        }
-    }
-
     /// We test that on shell shutdown, the tx queue gets persisted in a DB, and
     /// on startup it is read successfully
     #[test]
@@ -874,6 +1183,7 @@ mod test_utils {
             top_level_directory().join("wasm"),
             sender.clone(),
             None,
+            None,
             vp_wasm_compilation_cache,
             tx_wasm_compilation_cache,
         );
@@ -902,22 +1212,28 @@ mod test_utils {
         let hash = BlockHash([0; 32]);
         let pred_epochs = Default::default();
         let address_gen = EstablishedAddressGen::new("test");
+        let mut batch = PersistentDB::batch();
         shell
             .storage
             .db
-            .write_block(BlockStateWrite {
-                merkle_tree_stores: stores,
-                header: None,
-                hash: &hash,
-                height: BlockHeight(1),
-                epoch: Epoch(0),
-                pred_epochs: &pred_epochs,
-                next_epoch_min_start_height: BlockHeight(3),
-                next_epoch_min_start_time: DateTimeUtc::now(),
-                address_gen: &address_gen,
-                tx_queue: &shell.storage.tx_queue,
-            })
+            .write_block(
+                BlockStateWrite {
+                    merkle_tree_stores: stores,
+                    header: None,
+                    hash: &hash,
+                    height: BlockHeight(1),
+                    epoch: Epoch(0),
+                    pred_epochs: &pred_epochs,
+                    next_epoch_min_start_height: BlockHeight(3),
+                    next_epoch_min_start_time: DateTimeUtc::now(),
+                    address_gen: &address_gen,
+                    tx_queue: &shell.storage.tx_queue,
+                    archive: false,
+                },
+                &mut batch,
+            )
             .expect("Test failed");
+        DB::exec_batch(&mut shell.storage.db, batch).expect("Test failed");
 
         // Drop the shell
         std::mem::drop(shell);
@@ -932,9 +1248,277 @@ mod test_utils {
             top_level_directory().join("wasm"),
             sender,
             None,
+            None,
             vp_wasm_compilation_cache,
             tx_wasm_compilation_cache,
         );
         assert!(!shell.storage.tx_queue.is_empty());
     }
+
+    /// Test that the test shell commits the applied blocks and credits the
+    /// accounts' balances
+    #[test]
+    fn test_test_shell_apply_block() {
+        let (mut test, _) = setup();
+        let keypair = gen_keypair();
+        let owner = test.add_implicit_account(&keypair);
+        test.credit_balance(&xan(), &owner, 100.into());
+        test.credit_balance(&xan(), &owner, 50.into());
+        assert_eq!(test.shell.get_balance(&xan(), &owner), Ok(150.into()));
+
+        let tx = test.wrap_tx(
+            Tx::new("wasm_code".as_bytes().to_owned(), None)
+                .with_chain_id(test.shell.storage.chain_id.clone()),
+            &keypair,
+        );
+        test.apply_block(vec![tx]);
+        test.apply_block(vec![]);
+        assert_eq!(test.shell.storage.last_height, BlockHeight(2));
+        assert!(test.shell.storage.tx_queue.is_empty());
+    }
+
+    /// Test that the `info` response identifies the node's version, protocol
+    /// version and chain ID, even before the chain is initialized
+    #[test]
+    fn test_info_response() {
+        let (mut test, _) = TestShell::new();
+        let info = test.shell.last_state();
+        assert_eq!(info.version, anoma_version());
+        assert_eq!(info.app_version, PROTOCOL_VERSION);
+        assert_eq!(info.data, ChainId::default().to_string());
+        assert_eq!(info.last_block_height, 0);
+    }
+
+    /// Test that the mempool rejects the txs larger than the maximum size
+    /// and the wrapper txs of a sender with too many pending txs until a block
+    /// is committed
+    #[test]
+    fn test_mempool_limits() {
+        let mut config = config::Ledger::new(
+            tempdir().unwrap().path(),
+            Default::default(),
+            TendermintMode::Validator,
+        );
+        config.shell.mempool_max_tx_bytes = Some(4096);
+        config.shell.mempool_max_txs_per_sender = Some(2);
+        let limits = MempoolLimits::new(&config.shell);
+
+        let large_tx = Tx::new(vec![0; 4096], None).to_bytes();
+        let response = Shell::<MockDB, Sha256Hasher>::mempool_validate(
+            &large_tx,
+            MempoolTxType::NewTransaction,
+            &config.chain_id,
+            &limits,
+        );
+        assert_eq!(response.code, u32::from(ErrorCodes::TxTooLarge));
+
+        let keypair = gen_keypair();
+        let wrapper = WrapperTx::new(
+            Fee {
+                amount: 0.into(),
+                token: xan(),
+            },
+            &keypair,
+            Epoch(0),
+            0.into(),
+            Tx::new("wasm_code".as_bytes().to_owned(), None),
+            Default::default(),
+        )
+        .sign(&keypair, ChainId::default())
+        .expect("Test failed")
+        .to_bytes();
+        let validate = || {
+            Shell::<MockDB, Sha256Hasher>::mempool_validate(
+                &wrapper,
+                MempoolTxType::NewTransaction,
+                &config.chain_id,
+                &limits,
+            )
+            .code
+        };
+        assert_eq!(validate(), u32::from(ErrorCodes::Ok));
+        assert_eq!(validate(), u32::from(ErrorCodes::Ok));
+        assert_eq!(validate(), u32::from(ErrorCodes::TooManyPendingTxs));
+
+        // The pending txs are counted again after a commit
+        limits.reset_pending_txs();
+        assert_eq!(validate(), u32::from(ErrorCodes::Ok));
+    }
+
+    /// Test that the mempool rejects the wrapper txs that are not bound to
+    /// this chain
+    #[test]
+    fn test_mempool_chain_id() {
+        let config = config::Ledger::new(
+            tempdir().unwrap().path(),
+            Default::default(),
+            TendermintMode::Validator,
+        );
+        let limits = MempoolLimits::new(&config.shell);
+        let keypair = gen_keypair();
+        let wrapper = WrapperTx::new(
+            Fee {
+                amount: 0.into(),
+                token: xan(),
+            },
+            &keypair,
+            Epoch(0),
+            0.into(),
+            Tx::new("wasm_code".as_bytes().to_owned(), None),
+            Default::default(),
+        );
+        let validate = |tx: Tx| {
+            Shell::<MockDB, Sha256Hasher>::mempool_validate(
+                &tx.to_bytes(),
+                MempoolTxType::NewTransaction,
+                &config.chain_id,
+                &limits,
+            )
+            .code
+        };
+
+        let other_chain =
+            ChainId::from_str("anoma-otherchain.0000000000000").unwrap();
+        let tx = wrapper.sign(&keypair, other_chain).expect("Test failed");
+        assert_eq!(validate(tx), u32::from(ErrorCodes::InvalidChainId));
+
+        // signed without a chain ID
+        let mut tx = wrapper.to_unsigned_tx(config.chain_id.clone());
+        tx.chain_id = None;
+        let tx = tx.sign(&keypair);
+        assert_eq!(validate(tx), u32::from(ErrorCodes::InvalidChainId));
+
+        let tx = wrapper
+            .sign(&keypair, config.chain_id.clone())
+            .expect("Test failed");
+        assert_eq!(validate(tx), u32::from(ErrorCodes::Ok));
+    }
+
+    /// Test that the mempool only accepts the fees paid in the tokens accepted
+    /// by governance, at no less than their minimum gas price
+    #[test]
+    fn test_mempool_fee_tokens() {
+        let config = config::Ledger::new(
+            tempdir().unwrap().path(),
+            Default::default(),
+            TendermintMode::Validator,
+        );
+        let limits = MempoolLimits::new(&config.shell);
+        limits.set_fee_tokens(
+            vec![(btc(), token::Amount::from(2))].into_iter().collect(),
+        );
+        let keypair = gen_keypair();
+        let gas_limit = GasLimit::from(1_u64);
+        let validate = |token: Address, amount: u64| {
+            let wrapper = WrapperTx::new(
+                Fee {
+                    amount: amount.into(),
+                    token,
+                },
+                &keypair,
+                Epoch(0),
+                gas_limit.clone(),
+                Tx::new("wasm_code".as_bytes().to_owned(), None),
+                Default::default(),
+            )
+            .sign(&keypair, config.chain_id.clone())
+            .expect("Test failed")
+            .to_bytes();
+            Shell::<MockDB, Sha256Hasher>::mempool_validate(
+                &wrapper,
+                MempoolTxType::NewTransaction,
+                &config.chain_id,
+                &limits,
+            )
+            .code
+        };
+        let min_fee = 2 * u64::from(&gas_limit);
+
+        // The native token is no longer accepted
+        assert_eq!(validate(xan(), min_fee), u32::from(ErrorCodes::InvalidFee));
+        assert_eq!(
+            validate(btc(), min_fee - 1),
+            u32::from(ErrorCodes::InvalidFee)
+        );
+        assert_eq!(validate(btc(), min_fee), u32::from(ErrorCodes::Ok));
+    }
+
+    /// Test that the mempool rejects the expired txs and that the txs left in
+    /// the mempool are re-checked against the time of the last block
+    #[test]
+    fn test_mempool_expiration() {
+        let (mut test, _) = TestShell::new();
+        let keypair = gen_keypair();
+        let now = DateTimeUtc::now();
+        let wrapper = |expiration: DateTimeUtc| {
+            WrapperTx::new(
+                Fee {
+                    amount: 0.into(),
+                    token: xan(),
+                },
+                &keypair,
+                Epoch(0),
+                0.into(),
+                Tx::new("wasm_code".as_bytes().to_owned(), None),
+                Default::default(),
+            )
+            .to_unsigned_tx(test.shell.storage.chain_id.clone())
+            .with_expiration(expiration)
+            .sign(&keypair)
+            .to_bytes()
+        };
+        let expired = wrapper(now - Duration::hours(1));
+        let valid = wrapper(DateTimeUtc(now.0 + Duration::hours(1)));
+        let validate = |tx: &[u8]| {
+            Shell::<MockDB, Sha256Hasher>::mempool_validate(
+                tx,
+                MempoolTxType::NewTransaction,
+                &test.shell.storage.chain_id,
+                &test.shell.mempool_limits,
+            )
+            .code
+        };
+        assert_eq!(validate(&expired), u32::from(ErrorCodes::ExpiredTx));
+        assert_eq!(validate(&valid), u32::from(ErrorCodes::Ok));
+
+        // once a block past the expiration is committed
+        test.shell.storage.last_block_time =
+            Some(DateTimeUtc(now.0 + Duration::hours(2)));
+        let response = test.shell.mempool_recheck(&valid);
+        assert_eq!(response.code, u32::from(ErrorCodes::ExpiredTx));
+    }
+
+    /// Test that a re-checked wrapper tx is rejected once its fee payer can
+    /// no longer pay the fee, but its signature isn't verified again
+    #[test]
+    fn test_mempool_recheck() {
+        let (test, _) = TestShell::new();
+        let keypair = gen_keypair();
+        let wrapper = |amount: u64| {
+            WrapperTx::new(
+                Fee {
+                    amount: amount.into(),
+                    token: xan(),
+                },
+                &keypair,
+                Epoch(0),
+                0.into(),
+                Tx::new("wasm_code".as_bytes().to_owned(), None),
+                Default::default(),
+            )
+        };
+
+        let unpaid = wrapper(100)
+            .sign(&keypair, ChainId::default())
+            .expect("Test failed");
+        let response = test.shell.mempool_recheck(&unpaid.to_bytes());
+        assert_eq!(response.code, u32::from(ErrorCodes::InvalidTx));
+
+        // signed with a key other than the fee payer's
+        let invalid_sig = wrapper(0)
+            .to_unsigned_tx(ChainId::default())
+            .sign(&gen_keypair());
+        let response = test.shell.mempool_recheck(&invalid_sig.to_bytes());
+        assert_eq!(response.code, u32::from(ErrorCodes::Ok));
+    }
 }
@@ -34,19 +34,27 @@ mod prepare_block {
 
                 // TODO: Craft the Ethereum state update tx
                 // filter in half of the new txs from Tendermint, only keeping
-                // wrappers
+                // the wrappers that would be accepted by the other validators
                 let number_of_new_txs = 1 + req.block_data.len() / 2;
                 let mut txs: Vec<TxBytes> = req
                     .block_data
                     .into_iter()
-                    .take(number_of_new_txs)
                     .filter(|tx_bytes| {
-                        if let Ok(tx) = Tx::try_from(tx_bytes.as_slice()) {
-                            matches!(process_tx(tx), Ok(TxType::Wrapper(_)))
-                        } else {
-                            false
-                        }
+                        let is_wrapper = match Tx::try_from(tx_bytes.as_slice())
+                        {
+                            Ok(tx) => {
+                                matches!(process_tx(tx), Ok(TxType::Wrapper(_)))
+                            }
+                            Err(_) => false,
+                        };
+                        is_wrapper
+                            && self
+                                .process_proposal(tx_bytes.clone().into())
+                                .result
+                                .code
+                                == u32::from(ErrorCodes::Ok)
                     })
+                    .take(number_of_new_txs)
                     .collect();
 
                 // decrypt the wrapper txs included in the previous block
@@ -136,6 +144,36 @@ mod prepare_block {
             assert_eq!(shell.prepare_proposal(req).block_data.len(), 0);
         }
 
+        /// Test that a wrapper tx whose fee payer doesn't have enough
+        /// balance to pay the fee is not included in the proposed block
+        #[test]
+        fn test_prepare_proposal_rejects_insufficient_fee_balance() {
+            let (mut shell, _) = TestShell::new();
+            let keypair = gen_keypair();
+            let tx = Tx::new(
+                "wasm_code".as_bytes().to_owned(),
+                Some("transaction_data".as_bytes().to_owned()),
+            );
+            let wrapper = WrapperTx::new(
+                Fee {
+                    amount: 100.into(),
+                    token: xan(),
+                },
+                &keypair,
+                Epoch(0),
+                0.into(),
+                tx,
+                Default::default(),
+            )
+            .sign(&keypair, ChainId::default())
+            .expect("Test failed");
+            let req = RequestPrepareProposal {
+                block_data: vec![wrapper.to_bytes()],
+                block_data_size: 0,
+            };
+            assert_eq!(shell.prepare_proposal(req).block_data.len(), 0);
+        }
+
         /// Test that the decrypted txs are included
         /// in the proposal in the same order as their
         /// corresponding wrappers
@@ -174,7 +212,9 @@ mod prepare_block {
                     tx,
                     Default::default(),
                 );
-                let wrapper = wrapper_tx.sign(&keypair).expect("Test failed");
+                let wrapper = wrapper_tx
+                    .sign(&keypair, ChainId::default())
+                    .expect("Test failed");
                 shell.enqueue_tx(wrapper_tx);
                 expected_wrapper.push(wrapper.clone());
                 req.block_data.push(wrapper.to_bytes());
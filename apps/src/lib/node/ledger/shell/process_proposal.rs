@@ -33,6 +33,9 @@ where
     ///   3: Wasm runtime error
     ///   4: Invalid order of decrypted txs
     ///   5. More decrypted txs than expected
+    ///   9: Wrapper tx is not bound to this chain
+    ///  10: Wrapper tx has expired by the time of the proposed block
+    ///  11: Wrapper tx's fee token or amount is not accepted
     ///
     /// INVARIANT: Any changes applied in this method must be reverted if the
     /// proposal is rejected (unless we can simply overwrite them in the
@@ -52,6 +55,8 @@ where
                 .into();
             }
         };
+        let tx_chain_id = tx.chain_id.clone();
+        let tx_expiration = tx.expiration;
         // TODO: This should not be hardcoded
         let privkey = <EllipticCurve as PairingEngine>::G2Affine::prime_subgroup_generator();
 
@@ -108,8 +113,28 @@ where
                     },
                 },
                 TxType::Wrapper(tx) => {
-                    // validate the ciphertext via Ferveo
-                    if !tx.validate_ciphertext() {
+                    let fee_tokens = parameters::read_fee_tokens(&self.storage)
+                        .map(|(fee_tokens, _gas)| fee_tokens)
+                        .unwrap_or_default();
+                    if let Err(msg) = check_chain_id(
+                        tx_chain_id.as_ref(),
+                        &self.storage.chain_id,
+                    ) {
+                        TxResult {
+                            code: ErrorCodes::InvalidChainId.into(),
+                            info: msg,
+                        }
+                    } else if let Some(Err(msg)) =
+                        req.block_time.map(|block_time| {
+                            check_expiration(tx_expiration, block_time)
+                        })
+                    {
+                        TxResult {
+                            code: ErrorCodes::ExpiredTx.into(),
+                            info: msg,
+                        }
+                    } else if !tx.validate_ciphertext() {
+                        // the ciphertext is validated via Ferveo
                         TxResult {
                             code: ErrorCodes::InvalidTx.into(),
                             info: format!(
@@ -118,6 +143,11 @@ where
                                 hash_tx(&req.tx)
                             ),
                         }
+                    } else if let Err(msg) = check_fee(&tx, &fee_tokens) {
+                        TxResult {
+                            code: ErrorCodes::InvalidFee.into(),
+                            info: msg,
+                        }
                     } else {
                         // check that the fee payer has sufficient balance
                         let balance = self
@@ -146,6 +176,64 @@ where
         .into()
     }
 
+    /// Check a block proposed by another validator before voting on it. The
+    /// proposal is accepted only if every tx in it is valid and it contains
+    /// the decrypted txs of all the wrapper txs from the previously committed
+    /// block, in the order determined in that block.
+    ///
+    /// INVARIANT: This method must be stateless, the txs are checked again
+    /// when the block is finalized.
+    #[cfg(not(feature = "ABCI"))]
+    pub fn process_block_proposal(
+        &mut self,
+        req: RequestProcessProposal,
+    ) -> ResponseProcessProposal {
+        let block_time = req
+            .header
+            .as_ref()
+            .and_then(|header| header.time.clone())
+            .map(|time| {
+                DateTimeUtc::from(
+                    Utc.timestamp(time.seconds, time.nanos as u32),
+                )
+            });
+        let tx_results = self.process_txs(&req.txs, block_time);
+        let all_valid = tx_results
+            .iter()
+            .all(|result| result.code == u32::from(ErrorCodes::Ok));
+        // the decrypted txs of any remaining wrappers are missing
+        let all_decrypted = self.next_wrapper().is_none();
+        self.reset_tx_queue_iter();
+        if !all_decrypted {
+            tracing::info!(
+                "Rejected a block proposal with missing decrypted txs"
+            );
+        }
+        ResponseProcessProposal {
+            accept: all_valid && all_decrypted,
+            ..Default::default()
+        }
+    }
+
+    /// Check the given txs of a proposed block with the given time in order
+    /// with [`Self::process_proposal`]
+    #[cfg(not(feature = "ABCI"))]
+    pub fn process_txs(
+        &mut self,
+        txs: &[shim::TxBytes],
+        block_time: Option<DateTimeUtc>,
+    ) -> Vec<TxResult> {
+        txs.iter()
+            .map(|tx_bytes| {
+                self.process_proposal(shim::request::ProcessProposal {
+                    tx: tx_bytes.clone(),
+                    block_time,
+                })
+                .result
+            })
+            .collect()
+    }
+
     /// If we are not using ABCI++, we check the wrapper,
     /// decode it, and check the decoded payload all at once
     #[cfg(feature = "ABCI")]
@@ -211,6 +299,7 @@ where
                 let mut decoded_resp =
                     self.process_proposal(shim::request::ProcessProposal {
                         tx: decoded.clone(),
+                        block_time: req.block_time,
                     });
 
                 // this ensures that emitted events are of the correct type
@@ -248,6 +337,7 @@ mod test_process_proposal {
     use anoma::types::hash::Hash;
     use anoma::types::key::*;
     use anoma::types::storage::Epoch;
+    use anoma::types::time::Duration;
     use anoma::types::token::Amount;
     use anoma::types::transaction::encrypted::EncryptedTx;
     use anoma::types::transaction::{EncryptionKey, Fee};
@@ -292,7 +382,10 @@ mod test_process_proposal {
         )
             .to_bytes();
         #[allow(clippy::redundant_clone)]
-            let request = ProcessProposal { tx: tx.clone() };
+            let request = ProcessProposal {
+                tx: tx.clone(),
+                block_time: None,
+            };
 
         let response = shell.process_proposal(request);
         assert_eq!(response.result.code, u32::from(ErrorCodes::InvalidSig));
@@ -328,11 +421,12 @@ mod test_process_proposal {
             tx,
             Default::default(),
         )
-            .sign(&keypair)
+            .sign(&keypair, ChainId::default())
             .expect("Test failed");
         let new_tx = if let Some(Ok(SignedTxData {
                                         data: Some(data),
                                         sig,
+                                        ..
                                     })) = wrapper
             .data
             .take()
@@ -358,17 +452,21 @@ mod test_process_proposal {
                     SignedTxData {
                         sig,
                         data: Some(new_data),
+                        multisig: Default::default(),
                     }
                         .try_to_vec()
                         .expect("Test failed"),
                 ),
                 timestamp,
+                chain_id: wrapper.chain_id.clone(),
+                expiration: wrapper.expiration,
             }
         } else {
             panic!("Test failed");
         };
         let request = ProcessProposal {
             tx: new_tx.to_bytes(),
+            block_time: None,
         };
         let response = shell.process_proposal(request);
         let expected_error = "Signature verification failed: Invalid signature";
@@ -386,6 +484,93 @@ mod test_process_proposal {
         }
     }
 
+    /// Test that a wrapper tx bound to another chain is rejected
+    #[test]
+    fn test_wrapper_other_chain_rejected() {
+        let (mut shell, _) = TestShell::new();
+        let keypair = gen_keypair();
+        let tx = Tx::new(
+            "wasm_code".as_bytes().to_owned(),
+            Some("transaction data".as_bytes().to_owned()),
+        );
+        let other_chain =
+            ChainId::from_str("anoma-otherchain.0000000000000").unwrap();
+        let wrapper = WrapperTx::new(
+            Fee {
+                amount: 0.into(),
+                token: xan(),
+            },
+            &keypair,
+            Epoch(0),
+            0.into(),
+            tx,
+            Default::default(),
+        )
+        .sign(&keypair, other_chain)
+        .expect("Test failed");
+        let request = ProcessProposal {
+            tx: wrapper.to_bytes(),
+            block_time: None,
+        };
+        let response = shell.process_proposal(request);
+        assert_eq!(
+            response.result.code,
+            u32::from(ErrorCodes::InvalidChainId)
+        );
+        #[cfg(feature = "ABCI")]
+        {
+            assert_eq!(response.tx, wrapper.to_bytes());
+            assert!(shell.shell.storage.tx_queue.is_empty())
+        }
+    }
+
+    /// Test that a wrapper tx that expired before the time of the proposed
+    /// block is rejected, even if it hadn't by the last committed block
+    #[test]
+    fn test_wrapper_expired_rejected() {
+        let (mut shell, _) = TestShell::new();
+        let keypair = gen_keypair();
+        let tx = Tx::new(
+            "wasm_code".as_bytes().to_owned(),
+            Some("transaction data".as_bytes().to_owned()),
+        );
+        let now = DateTimeUtc::now();
+        shell.shell.storage.last_block_time = Some(now - Duration::hours(1));
+        let wrapper = WrapperTx::new(
+            Fee {
+                amount: 0.into(),
+                token: xan(),
+            },
+            &keypair,
+            Epoch(0),
+            0.into(),
+            tx,
+            Default::default(),
+        )
+        .to_unsigned_tx(shell.shell.storage.chain_id.clone())
+        .with_expiration(now - Duration::seconds(1))
+        .sign(&keypair);
+        let request = ProcessProposal {
+            tx: wrapper.to_bytes(),
+            block_time: Some(now),
+        };
+        let response = shell.process_proposal(request);
+        assert_eq!(response.result.code, u32::from(ErrorCodes::ExpiredTx));
+        #[cfg(feature = "ABCI")]
+        {
+            assert_eq!(response.tx, wrapper.to_bytes());
+            assert!(shell.shell.storage.tx_queue.is_empty())
+        }
+
+        // in a block proposed before the expiration
+        let request = ProcessProposal {
+            tx: wrapper.to_bytes(),
+            block_time: Some(now - Duration::seconds(2)),
+        };
+        let response = shell.process_proposal(request);
+        assert_ne!(response.result.code, u32::from(ErrorCodes::ExpiredTx));
+    }
+
     /// Test that if the account submitting the tx is not known and the fee is
     /// non-zero, [`process_proposal`] rejects that tx
     #[test]
@@ -407,10 +592,11 @@ mod test_process_proposal {
             tx,
             Default::default(),
         )
-            .sign(&keypair)
+            .sign(&keypair, ChainId::default())
             .expect("Test failed");
         let request = ProcessProposal {
             tx: wrapper.to_bytes(),
+            block_time: None,
         };
         let response = shell.process_proposal(request);
         assert_eq!(response.result.code, u32::from(ErrorCodes::InvalidTx));
@@ -457,11 +643,12 @@ mod test_process_proposal {
             tx,
             Default::default(),
         )
-            .sign(&keypair)
+            .sign(&keypair, ChainId::default())
             .expect("Test failed");
 
         let request = ProcessProposal {
             tx: wrapper.to_bytes(),
+            block_time: None,
         };
 
         let response = shell.process_proposal(request);
@@ -508,12 +695,14 @@ mod test_process_proposal {
         }
         let req_1 = ProcessProposal {
             tx: txs[0].to_bytes(),
+            block_time: None,
         };
         let response_1 = shell.process_proposal(req_1);
         assert_eq!(response_1.result.code, u32::from(ErrorCodes::Ok));
 
         let req_2 = ProcessProposal {
             tx: txs[2].to_bytes(),
+            block_time: None,
         };
 
         let response_2 = shell.process_proposal(req_2);
@@ -555,7 +744,10 @@ mod test_process_proposal {
         let tx =
             Tx::from(TxType::Decrypted(DecryptedTx::Undecryptable(wrapper)));
 
-        let request = ProcessProposal { tx: tx.to_bytes() };
+        let request = ProcessProposal {
+            tx: tx.to_bytes(),
+            block_time: None,
+        };
 
         let response = shell.process_proposal(request);
         assert_eq!(response.result.code, u32::from(ErrorCodes::InvalidTx));
@@ -608,10 +800,13 @@ mod test_process_proposal {
                     wrapper.clone(),
             )))
         } else {
-            wrapper.sign(&keypair).expect("Test failed")
+            wrapper.sign(&keypair, ChainId::default()).expect("Test failed")
         };
 
-        let request = ProcessProposal { tx: tx.to_bytes() };
+        let request = ProcessProposal {
+            tx: tx.to_bytes(),
+            block_time: None,
+        };
         let response = shell.process_proposal(request);
         assert_eq!(response.result.code, u32::from(ErrorCodes::Ok));
         #[cfg(feature = "ABCI")]
@@ -683,10 +878,11 @@ mod test_process_proposal {
                     wrapper.clone(),
             )))
         } else {
-            wrapper.sign(&keypair).expect("Test failed")
+            wrapper.sign(&keypair, ChainId::default()).expect("Test failed")
         };
         let request = ProcessProposal {
             tx: signed.to_bytes(),
+            block_time: None,
         };
         let response = shell.process_proposal(request);
         assert_eq!(response.result.code, u32::from(ErrorCodes::Ok));
@@ -723,7 +919,10 @@ mod test_process_proposal {
 
         let tx = Tx::from(TxType::Decrypted(DecryptedTx::Decrypted(tx)));
 
-        let request = ProcessProposal { tx: tx.to_bytes() };
+        let request = ProcessProposal {
+            tx: tx.to_bytes(),
+            block_time: None,
+        };
         let response = shell.process_proposal(request);
         assert_eq!(response.result.code, u32::from(ErrorCodes::ExtraTxs));
         assert_eq!(
@@ -732,6 +931,53 @@ mod test_process_proposal {
         );
     }
 
+    #[cfg(not(feature = "ABCI"))]
+    /// Test that a block proposal is only accepted if it contains the
+    /// decrypted txs of all the wrappers from the previous block and that
+    /// checking it leaves the tx queue untouched
+    #[test]
+    fn test_block_proposal_missing_decrypted_txs() {
+        let (mut shell, _) = TestShell::new();
+        let keypair = gen_keypair();
+        let mut txs = vec![];
+        for i in 0..2 {
+            let tx = Tx::new(
+                "wasm_code".as_bytes().to_owned(),
+                Some(format!("transaction data: {}", i).as_bytes().to_owned()),
+            );
+            let wrapper = WrapperTx::new(
+                Fee {
+                    amount: 0.into(),
+                    token: xan(),
+                },
+                &keypair,
+                Epoch(0),
+                0.into(),
+                tx.clone(),
+                Default::default(),
+            );
+            shell.enqueue_tx(wrapper);
+            txs.push(
+                Tx::from(TxType::Decrypted(DecryptedTx::Decrypted(tx)))
+                    .to_bytes(),
+            );
+        }
+
+        let request = RequestProcessProposal {
+            txs: txs[..1].to_vec(),
+            ..Default::default()
+        };
+        assert!(!shell.shell.process_block_proposal(request).accept);
+
+        let request = RequestProcessProposal {
+            txs,
+            ..Default::default()
+        };
+        assert!(shell.shell.process_block_proposal(request.clone()).accept);
+        // the same proposal is accepted again
+        assert!(shell.shell.process_block_proposal(request).accept);
+    }
+
     /// Process Proposal should reject a RawTx, but not panic
     #[test]
     fn test_raw_tx_rejected() {
@@ -742,7 +988,10 @@ mod test_process_proposal {
             Some("transaction data".as_bytes().to_owned()),
         );
         let tx = Tx::from(TxType::Raw(tx));
-        let request = ProcessProposal { tx: tx.to_bytes() };
+        let request = ProcessProposal {
+            tx: tx.to_bytes(),
+            block_time: None,
+        };
         let response = shell.process_proposal(request);
         assert_eq!(response.result.code, u32::from(ErrorCodes::InvalidTx));
         assert_eq!(
@@ -1,6 +1,8 @@
 //! Shell methods for querying state
 use std::cmp::max;
 
+use anoma::ibc::core::ics24_host::identifier::ClientId;
+use anoma::ledger::ibc::storage as ibc_storage;
 use anoma::ledger::parameters::EpochDuration;
 use anoma::ledger::pos::PosParams;
 use anoma::types::address::Address;
@@ -24,6 +26,7 @@ use tendermint_proto_abci::google::protobuf;
 use tendermint_proto_abci::types::EvidenceParams;
 
 use super::*;
+use crate::node::ledger::indexer::{self, IndexedTx};
 use crate::node::ledger::response;
 
 impl<D, H> Shell<D, H>
@@ -62,6 +65,7 @@ where
                         ..Default::default()
                     }
                 }
+                Path::BlockResults(height) => self.read_block_results(height),
                 Path::Value(storage_key) => {
                     self.read_storage_value(&storage_key, height, query.prove)
                 }
@@ -69,6 +73,30 @@ where
                     self.read_storage_prefix(&storage_key, height, query.prove)
                 }
                 Path::HasKey(storage_key) => self.has_storage_key(&storage_key),
+                Path::TxsByAddress(address) => {
+                    self.read_indexed_txs(|indexer| {
+                        indexer.txs_by_address(&address.encode())
+                    })
+                }
+                Path::TxsByKeyPrefix(prefix) => {
+                    self.read_indexed_txs(|indexer| {
+                        indexer.txs_by_key_prefix(&prefix)
+                    })
+                }
+                Path::IbcValue(_, storage_key) => {
+                    // Relayers need the height of the proof
+                    response::Query {
+                        height: height.0 as i64,
+                        ..self.read_storage_value(
+                            &storage_key,
+                            height,
+                            query.prove,
+                        )
+                    }
+                }
+                Path::IbcConsensusHeights(client_id) => {
+                    self.read_ibc_consensus_heights(&client_id)
+                }
             },
             Err(err) => response::Query {
                 code: 1,
@@ -106,6 +134,60 @@ where
         }
     }
 
+    /// Query the results of the block with the given height or of the last
+    /// committed block.
+    fn read_block_results(
+        &self,
+        height: Option<BlockHeight>,
+    ) -> response::Query {
+        let height = height.unwrap_or(self.storage.last_height);
+        match self.storage.read_block_results(height) {
+            Ok(Some(value)) => response::Query {
+                value,
+                height: height.0 as i64,
+                ..Default::default()
+            },
+            Ok(None) => response::Query {
+                code: 1,
+                info: format!("No block results found at height {}", height),
+                ..Default::default()
+            },
+            Err(err) => response::Query {
+                code: 2,
+                info: format!("Storage error: {}", err),
+                ..Default::default()
+            },
+        }
+    }
+
+    /// Query the transaction indexer, if it's enabled
+    fn read_indexed_txs(
+        &self,
+        find: impl FnOnce(&Indexer) -> indexer::Result<Vec<IndexedTx>>,
+    ) -> response::Query {
+        let indexer = match &self.indexer {
+            Some(indexer) => indexer,
+            None => {
+                return response::Query {
+                    code: 1,
+                    info: "The transaction indexer is not enabled".to_owned(),
+                    ..Default::default()
+                };
+            }
+        };
+        match find(indexer) {
+            Ok(txs) => response::Query {
+                value: txs.try_to_vec().expect("Encoding txs shouldn't fail"),
+                ..Default::default()
+            },
+            Err(err) => response::Query {
+                code: 2,
+                info: err.to_string(),
+                ..Default::default()
+            },
+        }
+    }
+
     /// Query to read a value from storage
     pub fn read_storage_value(
         &self,
@@ -257,6 +339,41 @@ where
         }
     }
 
+    /// Query the heights of the consensus states of an IBC client at the last
+    /// committed block. The value in a successful response is a
+    /// [`Vec<(u64, u64)>`] of the revision numbers and heights in ascending
+    /// order, encoded with [`BorshSerialize`].
+    fn read_ibc_consensus_heights(
+        &self,
+        client_id: &ClientId,
+    ) -> response::Query {
+        let prefix = ibc_storage::consensus_state_prefix(client_id);
+        let (iter, _gas) = self.storage.iter_prefix(&prefix);
+        let heights: std::result::Result<Vec<(u64, u64)>, String> = iter
+            .map(|(key, _value, _gas)| {
+                let key = Key::parse(key).map_err(|e| e.to_string())?;
+                let height = ibc_storage::consensus_height(&key)
+                    .map_err(|e| e.to_string())?;
+                Ok((height.revision_number, height.revision_height))
+            })
+            .collect();
+        match heights {
+            Ok(mut heights) => {
+                heights.sort_unstable();
+                response::Query {
+                    value: heights.try_to_vec().unwrap(),
+                    height: self.storage.last_height.0 as i64,
+                    ..Default::default()
+                }
+            }
+            Err(err) => response::Query {
+                code: 1,
+                info: format!("Error parsing a consensus state key: {}", err),
+                ..Default::default()
+            },
+        }
+    }
+
     /// Query to check if a storage key exists.
     fn has_storage_key(&self, key: &Key) -> response::Query {
         match self.storage.has_key(key) {
@@ -287,10 +404,25 @@ where
             seconds: min_duration_secs * len_before_unbonded,
             nanos: 0,
         });
+        // The maximum evidence size is set from the genesis
+        let max_bytes_key = parameters::storage::get_max_evidence_bytes_key();
+        let max_bytes = match self.storage.read(&max_bytes_key) {
+            Ok((Some(bytes), _gas)) => u64::try_from_slice(&bytes)
+                .expect("Couldn't decode the max evidence bytes"),
+            // Not set on chains initialized before it was added to genesis
+            Ok((None, _gas)) => {
+                genesis::ConsensusParams::default().max_evidence_bytes
+            }
+            Err(err) => {
+                panic!("Couldn't read the max evidence bytes: {}", err)
+            }
+        };
         EvidenceParams {
             max_age_num_blocks,
             max_age_duration,
-            ..EvidenceParams::default()
+            max_bytes: max_bytes
+                .try_into()
+                .expect("unexpected max evidence bytes"),
         }
     }
 
@@ -342,4 +474,172 @@ where
                 }
             })
     }
+
+    /// Lookup the address of a validator from the Tendermint address of its
+    /// consensus key, e.g. the address of a block's proposer
+    pub fn get_validator_from_tm_address(
+        &self,
+        tm_address: &[u8],
+    ) -> Option<Address> {
+        let raw_hash = key::tm_raw_hash_to_string(tm_address);
+        self.storage.read_validator_address_raw_hash(raw_hash)
+    }
+}
+
+impl Shell {
+    /// Check if the query only reads the committed state, so that the
+    /// [`AbciService`] can answer it from a snapshot with
+    /// [`Shell::query_snapshot`] without waiting for the shell to finish
+    /// executing a block. The queries for a past height or with a proof need
+    /// the merkle tree and are left to the shell.
+    ///
+    /// [`AbciService`]: crate::node::ledger::shims::abcipp_shim::AbciService
+    pub fn is_snapshot_query(query: &request::Query) -> bool {
+        use rpc::Path;
+        query.height == 0
+            && !query.prove
+            && matches!(
+                Path::from_str(&query.path),
+                Ok(Path::Epoch)
+                    | Ok(Path::Value(_))
+                    | Ok(Path::Prefix(_))
+                    | Ok(Path::HasKey(_))
+                    | Ok(Path::BlockResults(_))
+            )
+    }
+
+    /// Answer a query accepted by [`Shell::is_snapshot_query`] from a
+    /// consistent snapshot of the committed state. The height of the response
+    /// is the height of the last block committed in the snapshot.
+    pub fn query_snapshot(
+        reader: &storage::SnapshotReader,
+        query: &request::Query,
+    ) -> response::Query {
+        use rpc::Path;
+        let resp = match Path::from_str(&query.path) {
+            Ok(Path::Epoch) => reader.read_epoch().map(|(height, epoch)| {
+                let resp = match epoch {
+                    Some(value) => response::Query {
+                        value,
+                        ..Default::default()
+                    },
+                    None => response::Query {
+                        code: 1,
+                        info: format!("No epoch found at height {}", height),
+                        ..Default::default()
+                    },
+                };
+                (height, resp)
+            }),
+            Ok(Path::Value(key)) => {
+                reader.read_subspace_val(&key).map(|(height, value)| {
+                    let resp = match value {
+                        Some(value) => response::Query {
+                            value,
+                            ..Default::default()
+                        },
+                        None => response::Query {
+                            code: 1,
+                            info: format!("No value found for key: {}", key),
+                            ..Default::default()
+                        },
+                    };
+                    (height, resp)
+                })
+            }
+            Ok(Path::Prefix(prefix)) => {
+                reader.read_subspace_prefix(&prefix).map(|(height, values)| {
+                    (height, Self::prefix_query_response(&prefix, values))
+                })
+            }
+            Ok(Path::HasKey(key)) => {
+                reader.read_subspace_val(&key).map(|(height, value)| {
+                    let resp = response::Query {
+                        value: value.is_some().try_to_vec().unwrap(),
+                        ..Default::default()
+                    };
+                    (height, resp)
+                })
+            }
+            Ok(Path::BlockResults(height)) => {
+                reader.read_block_results(height).map(|(height, results)| {
+                    let resp = match results {
+                        Some(value) => response::Query {
+                            value,
+                            ..Default::default()
+                        },
+                        None => response::Query {
+                            code: 1,
+                            info: format!(
+                                "No block results found at height {}",
+                                height
+                            ),
+                            ..Default::default()
+                        },
+                    };
+                    (height, resp)
+                })
+            }
+            _ => {
+                return response::Query {
+                    code: 1,
+                    info: format!(
+                        "The query {} can't be answered from a snapshot",
+                        query.path
+                    ),
+                    ..Default::default()
+                };
+            }
+        };
+        match resp {
+            Ok((height, resp)) => response::Query {
+                height: height.0 as i64,
+                ..resp
+            },
+            Err(err) => response::Query {
+                code: 2,
+                info: format!("Storage error: {}", err),
+                ..Default::default()
+            },
+        }
+    }
+
+    /// Build the response of a prefix query from the read key-vals, in the
+    /// same format as [`Shell::read_storage_prefix`]
+    fn prefix_query_response(
+        prefix: &Key,
+        values: Vec<(String, Vec<u8>)>,
+    ) -> response::Query {
+        if values.is_empty() {
+            return response::Query {
+                code: 1,
+                info: format!("No value found for key: {}", prefix),
+                ..Default::default()
+            };
+        }
+        let values: std::result::Result<
+            Vec<PrefixValue>,
+            anoma::types::storage::Error,
+        > = values
+            .into_iter()
+            .map(|(key, value)| {
+                let key = Key::parse(key)?;
+                Ok(PrefixValue { key, value })
+            })
+            .collect();
+        match values {
+            Ok(values) => response::Query {
+                value: values.try_to_vec().unwrap(),
+                ..Default::default()
+            },
+            Err(err) => response::Query {
+                code: 1,
+                info: format!(
+                    "Error parsing a storage key {}: {}",
+                    prefix, err
+                ),
+                ..Default::default()
+            },
+        }
+    }
 }
@@ -0,0 +1,310 @@
+//! A test shell that drives the ledger's shell directly against an in-memory
+//! storage, without Tendermint. It's used by the shell's unit tests and it's
+//! available to the integration tests of WASM and modules with the `testing`
+//! feature.
+
+use std::path::{Path, PathBuf};
+
+use anoma::ledger::storage::mockdb::MockDB;
+use anoma::ledger::storage::types::encode;
+use anoma::ledger::storage::Sha256Hasher;
+use anoma::types::address::{xan, Address};
+use anoma::types::chain::ChainId;
+use anoma::types::key::*;
+use anoma::types::storage::BlockHash;
+use anoma::types::transaction::Fee;
+use tempfile::tempdir;
+#[cfg(not(feature = "ABCI"))]
+use tendermint::block::{header::Version, Header};
+#[cfg(not(feature = "ABCI"))]
+use tendermint::{Hash, Time};
+#[cfg(not(feature = "ABCI"))]
+use tendermint_proto::abci::{
+    Event as TmEvent, RequestInitChain, ResponsePrepareProposal,
+};
+#[cfg(not(feature = "ABCI"))]
+use tendermint_proto::google::protobuf::Timestamp;
+#[cfg(feature = "ABCI")]
+use tendermint_proto_abci::abci::{Event as TmEvent, RequestInitChain};
+#[cfg(feature = "ABCI")]
+use tendermint_proto_abci::google::protobuf::Timestamp;
+#[cfg(feature = "ABCI")]
+use tendermint_stable::block::{header::Version, Header};
+#[cfg(feature = "ABCI")]
+use tendermint_stable::{Hash, Time};
+use tokio::sync::mpsc::UnboundedReceiver;
+
+use super::*;
+use crate::node::ledger::shims::abcipp_shim_types::shim::request::{
+    FinalizeBlock, ProcessProposal, ProcessedTx,
+};
+
+/// Gets the absolute path to root directory
+pub fn top_level_directory() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .parent()
+        .expect("The apps crate should be in the root directory")
+        .to_owned()
+}
+
+/// Generate a random public/private keypair
+pub fn gen_keypair() -> common::SecretKey {
+    use rand::prelude::ThreadRng;
+    use rand::thread_rng;
+
+    let mut rng: ThreadRng = thread_rng();
+    ed25519::SigScheme::generate(&mut rng).try_to_sk().unwrap()
+}
+
+/// A wrapper around the shell that implements
+/// Drop so as to clean up the files that it
+/// generates. Also allows illegal state
+/// modifications for testing purposes
+pub struct TestShell {
+    pub shell: Shell<MockDB, Sha256Hasher>,
+}
+
+impl TestShell {
+    /// Returns a new shell paired with a broadcast receiver, which will
+    /// receives any protocol txs sent by the shell.
+    pub fn new() -> (Self, UnboundedReceiver<Vec<u8>>) {
+        let (sender, receiver) = tokio::sync::mpsc::unbounded_channel();
+        let base_dir = tempdir().unwrap().as_ref().canonicalize().unwrap();
+        let vp_wasm_compilation_cache = 50 * 1024 * 1024; // 50 kiB
+        let tx_wasm_compilation_cache = 50 * 1024 * 1024; // 50 kiB
+        (
+            Self {
+                shell: Shell::<MockDB, Sha256Hasher>::new(
+                    config::Ledger::new(
+                        base_dir,
+                        Default::default(),
+                        TendermintMode::Validator,
+                    ),
+                    top_level_directory().join("wasm"),
+                    sender,
+                    None,
+                    None,
+                    vp_wasm_compilation_cache,
+                    tx_wasm_compilation_cache,
+                    Default::default(),
+                ),
+            },
+            receiver,
+        )
+    }
+
+    /// Forward a InitChain request and expect a success
+    pub fn init_chain(&mut self, req: RequestInitChain) {
+        self.shell
+            .init_chain(req)
+            .expect("Test shell failed to initialize");
+    }
+
+    /// Forward the prepare proposal request and return the response
+    #[cfg(not(feature = "ABCI"))]
+    pub fn prepare_proposal(
+        &mut self,
+        req: RequestPrepareProposal,
+    ) -> ResponsePrepareProposal {
+        self.shell.prepare_proposal(req)
+    }
+
+    /// Forward a ProcessProposal request and extract the relevant
+    /// response data to return
+    pub fn process_proposal(
+        &mut self,
+        req: ProcessProposal,
+    ) -> shim::response::ProcessProposal {
+        #[cfg(not(feature = "ABCI"))]
+        {
+            self.shell.process_proposal(req)
+        }
+        #[cfg(feature = "ABCI")]
+        {
+            self.shell.process_and_decode_proposal(req)
+        }
+    }
+
+    /// Forward a FinalizeBlock request return a vector of
+    /// the events created for each transaction
+    pub fn finalize_block(
+        &mut self,
+        req: FinalizeBlock,
+    ) -> Result<Vec<TmEvent>> {
+        match self.shell.finalize_block(req) {
+            Ok(resp) => Ok(resp.events),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Add a wrapper tx to the queue of txs to be decrypted
+    /// in the current block proposal
+    #[cfg(test)]
+    pub fn enqueue_tx(&mut self, wrapper: WrapperTx) {
+        self.shell.storage.tx_queue.push(wrapper);
+        self.shell.reset_tx_queue_iter();
+    }
+
+    #[cfg(not(feature = "ABCI"))]
+    /// Get the next wrapper tx to be decoded
+    pub fn next_wrapper(&mut self) -> Option<&WrapperTx> {
+        self.shell.next_wrapper()
+    }
+
+    #[cfg(feature = "ABCI")]
+    /// Get the next wrapper tx to be decoded
+    pub fn next_wrapper(&mut self) -> Option<WrapperTx> {
+        self.shell.next_wrapper()
+    }
+
+    /// Forward a Commit request and return the response
+    pub fn commit(&mut self) -> response::Commit {
+        self.shell.commit().expect("Committing a block shouldn't fail")
+    }
+
+    /// Finalize and commit a block with the given txs at the height after the
+    /// last committed block and return the events of its txs. Each tx is
+    /// first checked as in a block proposal. With ABCI++, the wrapper txs are
+    /// decrypted in the next block, so the block also includes the decrypted
+    /// txs of the wrapper txs from the previous block.
+    pub fn apply_block(&mut self, txs: Vec<Tx>) -> Vec<TmEvent> {
+        #[cfg(not(feature = "ABCI"))]
+        let txs = {
+            let mut txs = txs;
+            // TODO: This should not be hardcoded
+            let privkey = <EllipticCurve as PairingEngine>::G2Affine::prime_subgroup_generator();
+            txs.extend(self.shell.storage.tx_queue.iter().map(|wrapper| {
+                Tx::from(match wrapper.decrypt(privkey) {
+                    Ok(tx) => DecryptedTx::Decrypted(tx),
+                    _ => DecryptedTx::Undecryptable(wrapper.clone()),
+                })
+            }));
+            txs
+        };
+        let mut req = FinalizeBlock::default();
+        req.header.height = (self.shell.storage.last_height + 1)
+            .0
+            .try_into()
+            .expect("The block height should be valid");
+        let block_time = req.header.time.try_into().ok();
+        req.txs = txs
+            .into_iter()
+            .map(|tx| {
+                let tx_bytes = tx.to_bytes();
+                let resp = self.process_proposal(ProcessProposal {
+                    tx: tx_bytes.clone(),
+                    block_time,
+                });
+                ProcessedTx {
+                    #[cfg(not(feature = "ABCI"))]
+                    tx: tx_bytes,
+                    #[cfg(feature = "ABCI")]
+                    tx: resp.tx,
+                    result: resp.result,
+                }
+            })
+            .collect();
+        let events = self
+            .finalize_block(req)
+            .expect("Finalizing a block shouldn't fail");
+        self.commit();
+        events
+    }
+
+    /// Wrap the given tx in a wrapper tx for the current epoch that pays no
+    /// fee, signed with the given keypair
+    pub fn wrap_tx(&self, tx: Tx, keypair: &common::SecretKey) -> Tx {
+        WrapperTx::new(
+            Fee {
+                amount: 0.into(),
+                token: xan(),
+            },
+            keypair,
+            self.shell.storage.last_epoch,
+            0.into(),
+            tx,
+            Default::default(),
+        )
+        .sign(keypair, self.shell.storage.chain_id.clone())
+        .expect("Signing a wrapper tx shouldn't fail")
+    }
+
+    /// Reveal the public key of the given keypair in storage and return the
+    /// address of its implicit account
+    pub fn add_implicit_account(
+        &mut self,
+        keypair: &common::SecretKey,
+    ) -> Address {
+        let pk: common::PublicKey = keypair.ref_to();
+        let address = Address::from(&pk);
+        self.shell
+            .storage
+            .write(&pk_key(&address), encode(&pk))
+            .expect("Writing to storage shouldn't fail");
+        address
+    }
+
+    /// Credit the given amount of the token to the owner's balance
+    pub fn credit_balance(
+        &mut self,
+        token: &Address,
+        owner: &Address,
+        amount: token::Amount,
+    ) {
+        let balance =
+            self.shell.get_balance(token, owner).unwrap_or_default() + amount;
+        self.shell
+            .storage
+            .write(&token::balance_key(token, owner), encode(&balance))
+            .expect("Writing to storage shouldn't fail");
+    }
+}
+
+/// Start a new test shell and initialize it. Returns the shell paired with
+/// a broadcast receiver, which will receives any protocol txs sent by the
+/// shell.
+pub fn setup() -> (TestShell, UnboundedReceiver<Vec<u8>>) {
+    let (mut test, receiver) = TestShell::new();
+    test.init_chain(RequestInitChain {
+        time: Some(Timestamp {
+            seconds: 0,
+            nanos: 0,
+        }),
+        chain_id: ChainId::default().to_string(),
+        ..Default::default()
+    });
+    (test, receiver)
+}
+
+/// This is just to be used in testing. It is not
+/// a meaningful default.
+impl Default for FinalizeBlock {
+    fn default() -> Self {
+        FinalizeBlock {
+            hash: BlockHash([0u8; 32]),
+            header: Header {
+                version: Version { block: 0, app: 0 },
+                chain_id: String::from("test")
+                    .try_into()
+                    .expect("Should not fail"),
+                height: 0u64.try_into().expect("Should not fail"),
+                time: Time::now(),
+                last_block_id: None,
+                last_commit_hash: None,
+                data_hash: None,
+                validators_hash: Hash::None,
+                next_validators_hash: Hash::None,
+                consensus_hash: Hash::None,
+                app_hash: Vec::<u8>::new().try_into().expect("Should not fail"),
+                last_results_hash: None,
+                evidence_hash: None,
+                proposer_address: vec![0u8; 20]
+                    .try_into()
+                    .expect("Should not fail"),
+            },
+            byzantine_validators: vec![],
+            txs: vec![],
+            reject_all_decrypted: false,
+        }
+    }
+}
@@ -2,10 +2,16 @@ use std::convert::{TryFrom, TryInto};
 use std::future::Future;
 use std::path::PathBuf;
 use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::RecvTimeoutError;
+use std::sync::Arc;
 use std::task::{Context, Poll};
+use std::time::Duration;
 
+use anoma::types::chain::ChainId;
 use anoma::types::storage::BlockHeight;
-use futures::future::FutureExt;
+use futures::future::{FutureExt, TryFutureExt};
+use num_traits::FromPrimitive;
 use tokio::sync::mpsc::UnboundedSender;
 use tower::Service;
 #[cfg(not(feature = "ABCI"))]
@@ -13,13 +19,20 @@ use tower_abci::{BoxError, Request as Req, Response as Resp};
 #[cfg(feature = "ABCI")]
 use tower_abci_old::{BoxError, Request as Req, Response as Resp};
 
-use super::super::Shell;
+use super::super::{MempoolLimits, MempoolTxType, Shell};
 use super::abcipp_shim_types::shim::{request, Error, Request, Response};
 use crate::config;
+use crate::node::ledger::event_stream::StreamEvent;
+use crate::node::ledger::rejected_txs::{RejectedTxs, RejectionStage};
+use crate::node::ledger::shell::ErrorCodes;
+use crate::node::ledger::storage::SnapshotReader;
 use crate::node::ledger::shims::abcipp_shim_types::shim::request::{
     BeginBlock, ProcessedTx,
 };
 
+/// How often the shim checks for a shutdown request while it's idle
+const SHUTDOWN_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
 /// The shim wraps the shell, which implements ABCI++.
 /// The shim makes a crude translation between the ABCI interface currently used
 /// by tendermint and the shell's interface.
@@ -32,44 +45,93 @@ pub struct AbcippShim {
         Req,
         tokio::sync::oneshot::Sender<Result<Resp, BoxError>>,
     )>,
+    /// Set to request the shim to stop once the in-flight block, if any, is
+    /// committed
+    shutdown: Arc<AtomicBool>,
 }
 
 impl AbcippShim {
     /// Create a shell with a ABCI service that passes messages to and from the
-    /// shell.
+    /// shell. The `shutdown` flag is used to request the shell to stop.
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         config: config::Ledger,
         wasm_dir: PathBuf,
         broadcast_sender: UnboundedSender<Vec<u8>>,
+        event_stream_sender: Option<UnboundedSender<StreamEvent>>,
         db_cache: &rocksdb::Cache,
         vp_wasm_compilation_cache: u64,
         tx_wasm_compilation_cache: u64,
+        rejected_txs: Arc<RejectedTxs>,
+        shutdown: Arc<AtomicBool>,
     ) -> (Self, AbciService) {
         // We can use an unbounded channel here, because tower-abci limits the
         // the number of requests that can come in
         let (shell_send, shell_recv) = std::sync::mpsc::channel();
+        let service = Shell::new(
+            config,
+            wasm_dir,
+            broadcast_sender,
+            event_stream_sender,
+            Some(db_cache),
+            vp_wasm_compilation_cache,
+            tx_wasm_compilation_cache,
+            rejected_txs.clone(),
+        );
+        let mempool_limits = service.mempool_limits.clone();
+        let chain_id = service.storage.chain_id.clone();
+        let snapshot_reader = Some(service.storage.db.snapshot_reader());
         (
             Self {
-                service: Shell::new(
-                    config,
-                    wasm_dir,
-                    broadcast_sender,
-                    Some(db_cache),
-                    vp_wasm_compilation_cache,
-                    tx_wasm_compilation_cache,
-                ),
+                service,
                 begin_block_request: None,
                 block_txs: vec![],
                 shell_recv,
+                shutdown,
+            },
+            AbciService {
+                shell_send,
+                mempool_limits,
+                rejected_txs,
+                chain_id,
+                snapshot_reader,
             },
-            AbciService { shell_send },
         )
     }
 
     /// Run the shell's blocking loop that receives messages from the
-    /// [`AbciService`].
+    /// [`AbciService`]. When a shutdown is requested, the in-flight block, if
+    /// any, is finished and committed, any further requests are rejected and
+    /// the storage is flushed before returning. The loop also stops when a
+    /// block fails to commit, as the shell's state can't be used anymore.
     pub fn run(mut self) {
-        while let Ok((req, resp_sender)) = self.shell_recv.recv() {
+        // Set from `BeginBlock` until the block is committed
+        let mut in_block = false;
+        loop {
+            let (req, resp_sender) =
+                match self.shell_recv.recv_timeout(SHUTDOWN_POLL_INTERVAL) {
+                    Ok(msg) => msg,
+                    Err(RecvTimeoutError::Timeout) => {
+                        if !in_block && self.is_shutting_down() {
+                            break;
+                        }
+                        continue;
+                    }
+                    Err(RecvTimeoutError::Disconnected) => break,
+                };
+            if !in_block && self.is_shutting_down() {
+                let resp = Err(Error::ShuttingDown.into());
+                if resp_sender.send(resp).is_err() {
+                    tracing::info!("ABCI response channel is closed")
+                }
+                break;
+            }
+            let is_commit = matches!(req, Req::Commit(_));
+            match &req {
+                Req::BeginBlock(_) => in_block = true,
+                Req::Commit(_) => in_block = false,
+                _ => {}
+            }
             let resp = match req {
                 Req::BeginBlock(block) => {
                     // we save this data to be forwarded to finalize later
@@ -85,12 +147,19 @@ impl AbcippShim {
                     // Invariant: The service call with
                     // `Request::ProcessProposal`
                     // must always return `Response::ProcessProposal`
+                    let block_time = self
+                        .begin_block_request
+                        .as_ref()
+                        .and_then(|block| block.header.time.try_into().ok());
                     self.service
                         .call(Request::ProcessProposal(
-                            #[cfg(not(feature = "ABCI"))]
-                            deliver_tx.tx.clone().into(),
-                            #[cfg(feature = "ABCI")]
-                            deliver_tx.tx.into(),
+                            request::ProcessProposal {
+                                #[cfg(not(feature = "ABCI"))]
+                                tx: deliver_tx.tx.clone(),
+                                #[cfg(feature = "ABCI")]
+                                tx: deliver_tx.tx,
+                                block_time,
+                            },
                         ))
                         .map_err(Error::from)
                         .and_then(|res| match res {
@@ -115,8 +184,16 @@ impl AbcippShim {
                     std::mem::swap(&mut txs, &mut self.block_txs);
                     // If the wrapper txs were not properly submitted, reject
                     // all txs
-                    let out_of_order =
-                        txs.iter().any(|tx| tx.result.code > 3u32);
+                    let out_of_order = txs.iter().any(|tx| {
+                        matches!(
+                            ErrorCodes::from_u32(tx.result.code),
+                            Some(
+                                ErrorCodes::InvalidOrder
+                                    | ErrorCodes::ExtraTxs
+                                    | ErrorCodes::Undecryptable
+                            )
+                        )
+                    });
                     if out_of_order {
                         // The wrapper txs will need to be decrypted again
                         // and included in the proposed block after the current
@@ -152,24 +229,61 @@ impl AbcippShim {
                     Err(err) => Err(err),
                 },
             };
+            let commit_failed = match &resp {
+                Err(err) if is_commit => {
+                    tracing::error!(
+                        "Failed to commit the block, shutting down: {}",
+                        err
+                    );
+                    true
+                }
+                _ => false,
+            };
             let resp = resp.map_err(|e| e.into());
             if resp_sender.send(resp).is_err() {
                 tracing::info!("ABCI response channel is closed")
             }
+            if commit_failed {
+                break;
+            }
         }
+        if in_block {
+            tracing::warn!(
+                "The ABCI connection closed before the in-flight block was \
+                 committed"
+            );
+        }
+        self.service.flush_storage();
+    }
+
+    fn is_shutting_down(&self) -> bool {
+        self.shutdown.load(Ordering::SeqCst)
     }
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub struct AbciService {
     shell_send: std::sync::mpsc::Sender<(
         Req,
         tokio::sync::oneshot::Sender<Result<Resp, BoxError>>,
     )>,
+    mempool_limits: Arc<MempoolLimits>,
+    /// The log of the rejected txs, shared with the shell
+    rejected_txs: Arc<RejectedTxs>,
+    /// The chain that the new txs must be bound to
+    chain_id: ChainId,
+    /// Reads the committed state for the queries that don't need the shell
+    snapshot_reader: Option<SnapshotReader>,
 }
 
 /// The ABCI tower service implementation sends and receives messages to and
-/// from the [`AbcippShim`] for requests from Tendermint.
+/// from the [`AbcippShim`] for requests from Tendermint. The mempool's
+/// `CheckTx` requests for new txs are validated concurrently in a worker pool
+/// instead, so that they don't wait behind the block execution in the shell,
+/// whose requests are still handled one by one in the order they were
+/// received. Likewise, the queries of the committed state that don't need a
+/// proof are answered from a snapshot of the DB, so the ABCI queries and the
+/// gRPC service can read the state while a block is being executed.
 impl Service<Req> for AbciService {
     type Error = BoxError;
     type Future =
@@ -185,6 +299,56 @@ impl Service<Req> for AbciService {
     }
 
     fn call(&mut self, req: Req) -> Self::Future {
+        let req = match req {
+            Req::CheckTx(tx)
+                if matches!(
+                    Shell::mempool_tx_type(&tx),
+                    MempoolTxType::NewTransaction
+                ) =>
+            {
+                // Validate in the blocking worker pool, so that the
+                // validation of large txs doesn't hold up the ABCI server
+                let mempool_limits = self.mempool_limits.clone();
+                let rejected_txs = self.rejected_txs.clone();
+                let chain_id = self.chain_id.clone();
+                return Box::pin(
+                    tokio::task::spawn_blocking(move || {
+                        let resp = Shell::check_new_tx(
+                            &tx,
+                            &chain_id,
+                            &mempool_limits,
+                        );
+                        rejected_txs.record_check_tx(
+                            RejectionStage::Mempool,
+                            &*tx.tx,
+                            resp.code,
+                            &resp.log,
+                        );
+                        Resp::CheckTx(resp)
+                    })
+                    .map_err(BoxError::from),
+                );
+            }
+            Req::Query(query)
+                if self.snapshot_reader.is_some()
+                    && Shell::is_snapshot_query(&query) =>
+            {
+                let reader = self.snapshot_reader.clone().unwrap();
+                return Box::pin(
+                    tokio::task::spawn_blocking(move || {
+                        Resp::Query(Shell::query_snapshot(&reader, &query))
+                    })
+                    .map_err(BoxError::from),
+                );
+            }
+            // The re-checked txs are validated against the new state by the
+            // shell, which is idle after the commit that triggered them
+            req => req,
+        };
+        if let Req::Commit(_) = req {
+            // The txs left in the mempool are re-checked after the commit
+            self.mempool_limits.reset_pending_txs();
+        }
         let (resp_send, recv) = tokio::sync::oneshot::channel();
         let result = self.shell_send.send((req, resp_send));
         Box::pin(
@@ -205,3 +369,99 @@ impl Service<Req> for AbciService {
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use anoma::ledger::storage::{types, DBWriteBatch, DB};
+    use anoma::types::storage::Key;
+    #[cfg(not(feature = "ABCI"))]
+    use tendermint_proto::abci::{RequestCheckTx, RequestQuery};
+    #[cfg(feature = "ABCI")]
+    use tendermint_proto_abci::abci::{RequestCheckTx, RequestQuery};
+
+    use super::*;
+    use crate::node::ledger::rpc::Path;
+    use crate::node::ledger::storage::PersistentDB;
+
+    /// Test that the `CheckTx` requests are answered without the shell, which
+    /// may be busy executing a block.
+    #[tokio::test]
+    async fn test_check_tx_bypasses_shell() {
+        let (shell_send, shell_recv) = std::sync::mpsc::channel();
+        let config = config::Ledger::new(
+            tempfile::tempdir().unwrap().path(),
+            Default::default(),
+            config::TendermintMode::Full,
+        );
+        let mut service = AbciService {
+            shell_send,
+            mempool_limits: Arc::new(MempoolLimits::new(&config.shell)),
+            rejected_txs: Default::default(),
+            chain_id: config.chain_id.clone(),
+            snapshot_reader: None,
+        };
+
+        // An empty tx is not a valid tx
+        let resp = service
+            .call(Req::CheckTx(RequestCheckTx::default()))
+            .await
+            .unwrap();
+        assert!(matches!(resp, Resp::CheckTx(resp) if resp.code == 1));
+        assert!(shell_recv.try_recv().is_err());
+        // The rejection is logged
+        assert_eq!(service.rejected_txs.status().mempool, 1);
+    }
+
+    /// Test that the queries of the committed state are answered from a
+    /// snapshot without the shell, while the queries with a proof are left to
+    /// the shell.
+    #[tokio::test]
+    async fn test_query_bypasses_shell() {
+        let (shell_send, shell_recv) = std::sync::mpsc::channel();
+        let dir = tempfile::tempdir().unwrap();
+        let config = config::Ledger::new(
+            dir.path(),
+            Default::default(),
+            config::TendermintMode::Full,
+        );
+        let mut db = PersistentDB::open(dir.path().join("db"), None);
+        let key = Key::parse("test").unwrap();
+        let mut batch = PersistentDB::batch();
+        db.batch_write_subspace_val(&mut batch, BlockHeight(1), &key, [1_u8])
+            .unwrap();
+        batch.put("height", types::encode(&BlockHeight(1)));
+        db.exec_block_batch(batch, false).unwrap();
+        let mut service = AbciService {
+            shell_send,
+            mempool_limits: Arc::new(MempoolLimits::new(&config.shell)),
+            rejected_txs: Default::default(),
+            chain_id: config.chain_id.clone(),
+            snapshot_reader: Some(db.snapshot_reader()),
+        };
+
+        let resp = service
+            .call(Req::Query(RequestQuery {
+                path: Path::Value(key.clone()).to_string(),
+                ..Default::default()
+            }))
+            .await
+            .unwrap();
+        match resp {
+            Resp::Query(resp) => {
+                assert_eq!(resp.code, 0);
+                assert_eq!(resp.value, vec![1]);
+                assert_eq!(resp.height, 1);
+            }
+            resp => panic!("Unexpected response {:?}", resp),
+        }
+        assert!(shell_recv.try_recv().is_err());
+
+        // The shell is needed for the proof
+        let _resp = service.call(Req::Query(RequestQuery {
+            path: Path::Value(key).to_string(),
+            prove: true,
+            ..Default::default()
+        }));
+        assert!(matches!(shell_recv.try_recv(), Ok((Req::Query(_), _))));
+    }
+}
@@ -11,11 +11,12 @@ pub mod shim {
         RequestApplySnapshotChunk, RequestCheckTx, RequestCommit, RequestEcho,
         RequestExtendVote, RequestFlush, RequestInfo, RequestInitChain,
         RequestListSnapshots, RequestLoadSnapshotChunk, RequestOfferSnapshot,
-        RequestPrepareProposal, RequestQuery, RequestVerifyVoteExtension,
-        ResponseApplySnapshotChunk, ResponseCheckTx, ResponseCommit,
-        ResponseEcho, ResponseExtendVote, ResponseFlush, ResponseInfo,
-        ResponseInitChain, ResponseListSnapshots, ResponseLoadSnapshotChunk,
-        ResponseOfferSnapshot, ResponsePrepareProposal, ResponseQuery,
+        RequestPrepareProposal, RequestProcessProposal, RequestQuery,
+        RequestVerifyVoteExtension, ResponseApplySnapshotChunk,
+        ResponseCheckTx, ResponseCommit, ResponseEcho, ResponseExtendVote,
+        ResponseFlush, ResponseInfo, ResponseInitChain, ResponseListSnapshots,
+        ResponseLoadSnapshotChunk, ResponseOfferSnapshot,
+        ResponsePrepareProposal, ResponseProcessProposal, ResponseQuery,
         ResponseVerifyVoteExtension,
     };
     #[cfg(feature = "ABCI")]
@@ -44,6 +45,8 @@ pub mod shim {
         ConvertResp(Response),
         #[error("{0:?}")]
         Shell(shell::Error),
+        #[error("The ledger is shutting down")]
+        ShuttingDown,
     }
 
     /// Errors from the shell need to be propagated upward
@@ -70,6 +73,10 @@ pub mod shim {
         VerifyHeader(request::VerifyHeader),
         #[allow(dead_code)]
         ProcessProposal(request::ProcessProposal),
+        /// A block proposed by another validator, to be checked before
+        /// voting on it
+        #[cfg(not(feature = "ABCI"))]
+        ProcessBlockProposal(RequestProcessProposal),
         #[allow(dead_code)]
         #[cfg(not(feature = "ABCI"))]
         RevertProposal(request::RevertProposal),
@@ -88,6 +95,38 @@ pub mod shim {
         ApplySnapshotChunk(RequestApplySnapshotChunk),
     }
 
+    impl Request {
+        /// The name of the request, used in the tracing spans
+        pub fn name(&self) -> &'static str {
+            match self {
+                Request::InitChain(_) => "init_chain",
+                Request::Info(_) => "info",
+                Request::Query(_) => "query",
+                #[cfg(not(feature = "ABCI"))]
+                Request::PrepareProposal(_) => "prepare_proposal",
+                Request::VerifyHeader(_) => "verify_header",
+                Request::ProcessProposal(_) => "process_proposal",
+                #[cfg(not(feature = "ABCI"))]
+                Request::ProcessBlockProposal(_) => "process_block_proposal",
+                #[cfg(not(feature = "ABCI"))]
+                Request::RevertProposal(_) => "revert_proposal",
+                #[cfg(not(feature = "ABCI"))]
+                Request::ExtendVote(_) => "extend_vote",
+                #[cfg(not(feature = "ABCI"))]
+                Request::VerifyVoteExtension(_) => "verify_vote_extension",
+                Request::FinalizeBlock(_) => "finalize_block",
+                Request::Commit(_) => "commit",
+                Request::Flush(_) => "flush",
+                Request::Echo(_) => "echo",
+                Request::CheckTx(_) => "check_tx",
+                Request::ListSnapshots(_) => "list_snapshots",
+                Request::OfferSnapshot(_) => "offer_snapshot",
+                Request::LoadSnapshotChunk(_) => "load_snapshot_chunk",
+                Request::ApplySnapshotChunk(_) => "apply_snapshot_chunk",
+            }
+        }
+    }
+
     /// Attempt to convert a tower-abci request to an internal one
     impl TryFrom<Req> for Request {
         type Error = Error;
@@ -119,6 +158,10 @@ pub mod shim {
                 Req::PrepareProposal(inner) => {
                     Ok(Request::PrepareProposal(inner))
                 }
+                #[cfg(not(feature = "ABCI"))]
+                Req::ProcessProposal(inner) => {
+                    Ok(Request::ProcessBlockProposal(inner))
+                }
                 _ => Err(Error::ConvertReq(req)),
             }
         }
@@ -137,6 +180,8 @@ pub mod shim {
         VerifyHeader(response::VerifyHeader),
         ProcessProposal(response::ProcessProposal),
         #[cfg(not(feature = "ABCI"))]
+        ProcessBlockProposal(ResponseProcessProposal),
+        #[cfg(not(feature = "ABCI"))]
         RevertProposal(response::RevertProposal),
         #[cfg(not(feature = "ABCI"))]
         ExtendVote(ResponseExtendVote),
@@ -183,6 +228,10 @@ pub mod shim {
                     Ok(Resp::PrepareProposal(inner))
                 }
                 #[cfg(not(feature = "ABCI"))]
+                Response::ProcessBlockProposal(inner) => {
+                    Ok(Resp::ProcessProposal(inner))
+                }
+                #[cfg(not(feature = "ABCI"))]
                 Response::ExtendVote(inner) => Ok(Resp::ExtendVote(inner)),
                 #[cfg(not(feature = "ABCI"))]
                 Response::VerifyVoteExtension(inner) => {
@@ -198,6 +247,7 @@ pub mod shim {
         use std::convert::{TryFrom, TryInto};
 
         use anoma::types::storage::BlockHash;
+        use anoma::types::time::DateTimeUtc;
         #[cfg(not(feature = "ABCI"))]
         use tendermint::block::Header;
         #[cfg(not(feature = "ABCI"))]
@@ -212,12 +262,9 @@ pub mod shim {
         #[derive(Clone)]
         pub struct ProcessProposal {
             pub tx: super::TxBytes,
-        }
-
-        impl From<super::TxBytes> for ProcessProposal {
-            fn from(tx: super::TxBytes) -> Self {
-                Self { tx }
-            }
+            /// The time of the proposed block that the tx is in. The tx's
+            /// expiration is only checked when it's known.
+            pub block_time: Option<DateTimeUtc>,
         }
 
         #[cfg(not(feature = "ABCI"))]
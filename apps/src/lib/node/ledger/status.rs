@@ -0,0 +1,247 @@
+//! A lightweight HTTP server with the status of the node for load balancers
+//! and monitoring. A `GET /status` request is answered with a JSON
+//! [`NodeStatus`]. The response code is `200 OK` when the node is healthy,
+//! i.e. the ledger responds and Tendermint is not catching up, and
+//! `503 Service Unavailable` otherwise. The status includes the counts of the
+//! txs rejected by the node and the most recently rejected ones.
+
+use std::io;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use anoma::types::chain::ChainId;
+use serde::Serialize;
+#[cfg(not(feature = "ABCI"))]
+use tendermint_config::net::Address as TendermintAddress;
+#[cfg(feature = "ABCI")]
+use tendermint_config_abci::net::Address as TendermintAddress;
+#[cfg(not(feature = "ABCI"))]
+use tendermint_rpc::{Client, HttpClient};
+#[cfg(feature = "ABCI")]
+use tendermint_rpc_abci::{Client, HttpClient};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+
+use super::rejected_txs::{RejectedTxs, RejectedTxsStatus};
+use crate::cli::anoma_version;
+
+/// The status of the node
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize)]
+pub struct NodeStatus {
+    /// The version of the node
+    pub version: String,
+    /// The chain ID
+    pub chain_id: String,
+    /// The height of the last block committed by the ledger, `None` if the
+    /// ledger doesn't respond
+    pub last_committed_height: Option<u64>,
+    /// The hex-encoded app hash of the last committed block
+    pub app_hash: Option<String>,
+    /// The latest block height known to Tendermint, `None` if Tendermint
+    /// doesn't respond
+    pub tendermint_height: Option<u64>,
+    /// Whether Tendermint is catching up with the network
+    pub catching_up: Option<bool>,
+    /// The size in bytes of the ledger's DB directory
+    pub db_size: u64,
+    /// The size in bytes of the Tendermint directory
+    pub tendermint_dir_size: u64,
+    /// The txs rejected by the node since it started
+    pub rejected_txs: RejectedTxsStatus,
+}
+
+impl NodeStatus {
+    /// The node is healthy when the ledger responds and Tendermint has caught
+    /// up with the network.
+    pub fn is_healthy(&self) -> bool {
+        self.last_committed_height.is_some() && self.catching_up == Some(false)
+    }
+}
+
+/// The sources of the node's status
+struct StatusSource {
+    chain_id: ChainId,
+    db_dir: PathBuf,
+    tendermint_dir: PathBuf,
+    client: HttpClient,
+    rejected_txs: Arc<RejectedTxs>,
+}
+
+/// Run the status server at the given address until it fails. The status of
+/// the ledger and of Tendermint is queried via the Tendermint RPC.
+pub async fn run(
+    address: SocketAddr,
+    tendermint_address: TendermintAddress,
+    chain_id: ChainId,
+    db_dir: PathBuf,
+    tendermint_dir: PathBuf,
+    rejected_txs: Arc<RejectedTxs>,
+) -> io::Result<()> {
+    let client = HttpClient::new(tendermint_address).unwrap();
+    let source = StatusSource {
+        chain_id,
+        db_dir,
+        tendermint_dir,
+        client,
+        rejected_txs,
+    };
+    let listener = TcpListener::bind(address).await?;
+    tracing::info!("Status server started at {}", address);
+    loop {
+        let (stream, peer) = listener.accept().await?;
+        if let Err(err) = handle_request(stream, &source).await {
+            tracing::debug!("Status request from {} failed: {}", peer, err);
+        }
+    }
+}
+
+/// Respond to a single HTTP request and close the connection.
+async fn handle_request(
+    stream: TcpStream,
+    source: &StatusSource,
+) -> io::Result<()> {
+    let mut stream = BufReader::new(stream);
+    let mut request_line = String::new();
+    stream.read_line(&mut request_line).await?;
+    let response = match parse_request_line(&request_line) {
+        Some(("GET", "/status")) => {
+            let status = source.query().await;
+            let code = if status.is_healthy() {
+                "200 OK"
+            } else {
+                "503 Service Unavailable"
+            };
+            let body = serde_json::to_string(&status)
+                .expect("Serializing the status shouldn't fail");
+            http_response(code, "application/json", &body)
+        }
+        Some((_, "/status")) => {
+            http_response("405 Method Not Allowed", "text/plain", "")
+        }
+        Some(_) => http_response("404 Not Found", "text/plain", ""),
+        None => http_response("400 Bad Request", "text/plain", ""),
+    };
+    let stream = stream.get_mut();
+    stream.write_all(response.as_bytes()).await?;
+    stream.shutdown().await
+}
+
+/// Parse the method and the path from an HTTP request line.
+fn parse_request_line(line: &str) -> Option<(&str, &str)> {
+    let mut parts = line.split_whitespace();
+    let method = parts.next()?;
+    let path = parts.next()?;
+    parts.next()?.starts_with("HTTP/").then(|| (method, path))
+}
+
+fn http_response(code: &str, content_type: &str, body: &str) -> String {
+    format!(
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: \
+         close\r\n\r\n{}",
+        code,
+        content_type,
+        body.len(),
+        body
+    )
+}
+
+impl StatusSource {
+    /// Query the current status of the node. The parts of the status that
+    /// cannot be queried are left empty.
+    async fn query(&self) -> NodeStatus {
+        let mut status = NodeStatus {
+            version: anoma_version().to_owned(),
+            chain_id: self.chain_id.to_string(),
+            ..Default::default()
+        };
+        match self.client.abci_info().await {
+            Ok(info) => {
+                status.last_committed_height =
+                    Some(info.last_block_height.value());
+                status.app_hash = Some(hex::encode(info.last_block_app_hash));
+            }
+            Err(err) => {
+                tracing::debug!("Failed to query the ledger's info: {}", err)
+            }
+        }
+        match self.client.status().await {
+            Ok(tm_status) => {
+                status.tendermint_height =
+                    Some(tm_status.sync_info.latest_block_height.value());
+                status.catching_up = Some(tm_status.sync_info.catching_up);
+            }
+            Err(err) => {
+                tracing::debug!("Failed to query Tendermint's status: {}", err)
+            }
+        }
+        let db_dir = self.db_dir.clone();
+        let tendermint_dir = self.tendermint_dir.clone();
+        let (db_size, tendermint_dir_size) =
+            tokio::task::spawn_blocking(move || {
+                (dir_size(&db_dir), dir_size(&tendermint_dir))
+            })
+            .await
+            .unwrap_or_default();
+        status.db_size = db_size;
+        status.tendermint_dir_size = tendermint_dir_size;
+        status.rejected_txs = self.rejected_txs.status();
+        status
+    }
+}
+
+/// Get the total size in bytes of the files in a directory and its
+/// sub-directories. The entries that cannot be read are skipped.
+fn dir_size(path: &Path) -> u64 {
+    let entries = match std::fs::read_dir(path) {
+        Ok(entries) => entries,
+        Err(_) => return 0,
+    };
+    entries
+        .filter_map(Result::ok)
+        .map(|entry| match entry.metadata() {
+            Ok(metadata) if metadata.is_dir() => dir_size(&entry.path()),
+            Ok(metadata) => metadata.len(),
+            Err(_) => 0,
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_request_line() {
+        assert_eq!(
+            parse_request_line("GET /status HTTP/1.1\r\n"),
+            Some(("GET", "/status"))
+        );
+        assert_eq!(parse_request_line("GET /status\r\n"), None);
+        assert_eq!(parse_request_line(""), None);
+    }
+
+    #[test]
+    fn test_dir_size() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join("a"), [0_u8; 10]).unwrap();
+        std::fs::create_dir(dir.path().join("sub")).unwrap();
+        std::fs::write(dir.path().join("sub").join("b"), [0_u8; 5]).unwrap();
+        assert_eq!(dir_size(dir.path()), 15);
+        assert_eq!(dir_size(&dir.path().join("missing")), 0);
+    }
+
+    #[test]
+    fn test_is_healthy() {
+        let mut status = NodeStatus {
+            last_committed_height: Some(1),
+            catching_up: Some(true),
+            ..Default::default()
+        };
+        assert!(!status.is_healthy());
+        status.catching_up = Some(false);
+        assert!(status.is_healthy());
+        status.last_committed_height = None;
+        assert!(!status.is_healthy());
+    }
+}
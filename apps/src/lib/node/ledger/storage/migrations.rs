@@ -0,0 +1,287 @@
+//! The migrations of the DB schema. The version of the schema of a DB is
+//! stored under the `schema_version` key. When a node opens a DB with an older
+//! version, the migrations from the registry that are newer than the DB's
+//! version are applied in order, each one atomically together with the bump of
+//! the stored version, so that a change of the storage layout doesn't require
+//! a chain reset.
+//!
+//! To change the layout, add a [`Migration`] to the [`MIGRATIONS`] with the
+//! next version and increment the [`SCHEMA_VERSION`].
+
+use anoma::ledger::storage::{types, Error, Result, StoreType};
+use anoma::types::storage::BlockHeight;
+use rocksdb::{WriteBatch, WriteOptions};
+use sparse_merkle_tree::default_store::DefaultStore;
+use sparse_merkle_tree::H256;
+
+use super::rocksdb::{tree_branch_key, tree_leaf_key};
+
+/// The key of the DB schema version
+pub const SCHEMA_VERSION_KEY: &str = "schema_version";
+
+/// The current version of the DB schema. The DBs created before the schema
+/// was versioned have the version `0`.
+pub const SCHEMA_VERSION: u64 = 1;
+
+/// The registry of the migrations, ordered by their versions
+pub const MIGRATIONS: &[Migration] = &[Migration {
+    version: 1,
+    description: "store the merkle tree nodes instead of the whole tree at \
+                  every height",
+    migrate: split_tree_stores,
+}];
+
+/// A migration of the DB from the previous schema version
+pub struct Migration {
+    /// The schema version that the migration upgrades the DB to
+    pub version: u64,
+    /// A short description of the change, for the log
+    pub description: &'static str,
+    /// Add the changes of the migration to the batch
+    pub migrate: fn(&rocksdb::DB, &mut WriteBatch) -> Result<()>,
+}
+
+/// Bring the DB to the current [`SCHEMA_VERSION`]. A new DB is set to the
+/// current version without any migrations.
+pub fn migrate(db: &rocksdb::DB) -> Result<()> {
+    run_migrations(db, MIGRATIONS, SCHEMA_VERSION)
+}
+
+/// Apply the given migrations to the DB up to the target version.
+fn run_migrations(
+    db: &rocksdb::DB,
+    migrations: &[Migration],
+    target_version: u64,
+) -> Result<()> {
+    let version = match read_version(db)? {
+        Some(version) => version,
+        // A new DB is already in the current layout
+        None if is_new(db)? => {
+            return write_version(db, WriteBatch::default(), target_version);
+        }
+        None => 0,
+    };
+    if version > target_version {
+        return Err(Error::UnsupportedSchemaVersion {
+            found: version,
+            supported: target_version,
+        });
+    }
+    for migration in migrations
+        .iter()
+        .filter(|migration| migration.version > version)
+        .take_while(|migration| migration.version <= target_version)
+    {
+        tracing::info!(
+            "Migrating the DB to schema version {}: {}",
+            migration.version,
+            migration.description
+        );
+        let mut batch = WriteBatch::default();
+        (migration.migrate)(db, &mut batch)?;
+        write_version(db, batch, migration.version)?;
+    }
+    if version < target_version && read_version(db)? != Some(target_version) {
+        // The registry has no migration for some of the versions
+        return Err(Error::DBError(format!(
+            "No migration found to the DB schema version {}",
+            target_version
+        )));
+    }
+    Ok(())
+}
+
+/// Write the nodes of the merkle tree stores of the last block under their own
+/// keys and remove the whole stores written at every height.
+fn split_tree_stores(db: &rocksdb::DB, batch: &mut WriteBatch) -> Result<()> {
+    let last_height: BlockHeight = match db
+        .get("height")
+        .map_err(|e| Error::DBError(e.into_string()))?
+    {
+        Some(bytes) => types::decode(bytes).map_err(Error::CodingError)?,
+        None => return Ok(()),
+    };
+    for st in StoreType::iter() {
+        let store_key = |height: u64| format!("{}/tree/{}/store", height, st);
+        let bytes = db
+            .get(store_key(last_height.0))
+            .map_err(|e| Error::DBError(e.into_string()))?;
+        if let Some(bytes) = bytes {
+            let store: DefaultStore<H256> =
+                types::decode(bytes).map_err(Error::CodingError)?;
+            for (node_key, branch) in store.branches_map() {
+                batch.put(tree_branch_key(st, node_key), types::encode(branch));
+            }
+            for (node_key, leaf) in store.leaves_map() {
+                batch.put(tree_leaf_key(st, node_key), types::encode(leaf));
+            }
+        }
+        for height in 0..=last_height.0 {
+            batch.delete(store_key(height));
+        }
+    }
+    Ok(())
+}
+
+/// Read the schema version of the DB, if it's been set
+fn read_version(db: &rocksdb::DB) -> Result<Option<u64>> {
+    db.get(SCHEMA_VERSION_KEY)
+        .map_err(|e| Error::DBError(e.into_string()))?
+        .map(|bytes| types::decode(bytes).map_err(Error::CodingError))
+        .transpose()
+}
+
+/// Check if the DB doesn't have any block yet
+fn is_new(db: &rocksdb::DB) -> Result<bool> {
+    db.get("height")
+        .map(|height| height.is_none())
+        .map_err(|e| Error::DBError(e.into_string()))
+}
+
+/// Write the batch atomically together with the new schema version. Unlike
+/// the block writes, the migrations are written with the WAL, so that they are
+/// not lost on a crash before the next flush.
+fn write_version(
+    db: &rocksdb::DB,
+    mut batch: WriteBatch,
+    version: u64,
+) -> Result<()> {
+    batch.put(SCHEMA_VERSION_KEY, types::encode(&version));
+    db.write_opt(batch, &WriteOptions::default())
+        .map_err(|e| Error::DBError(e.into_string()))
+}
+
+#[cfg(test)]
+mod test {
+    use anoma::ledger::storage::{Sha256Hasher, StorageHasher};
+    use sparse_merkle_tree::SparseMerkleTree;
+    use tempfile::tempdir;
+
+    use super::*;
+
+    fn add_foo(_db: &rocksdb::DB, batch: &mut WriteBatch) -> Result<()> {
+        batch.put("foo", [1_u8]);
+        Ok(())
+    }
+
+    fn rename_foo(db: &rocksdb::DB, batch: &mut WriteBatch) -> Result<()> {
+        let foo = db
+            .get("foo")
+            .map_err(|e| Error::DBError(e.into_string()))?
+            .expect("The previous migration should have added the key");
+        batch.delete("foo");
+        batch.put("bar", foo);
+        Ok(())
+    }
+
+    const TEST_MIGRATIONS: &[Migration] = &[
+        Migration {
+            version: 1,
+            description: "add foo",
+            migrate: add_foo,
+        },
+        Migration {
+            version: 2,
+            description: "rename foo to bar",
+            migrate: rename_foo,
+        },
+    ];
+
+    /// Test that the versions of the registry are consecutive and end at the
+    /// current version.
+    #[test]
+    fn test_registry_versions() {
+        for (index, migration) in MIGRATIONS.iter().enumerate() {
+            assert_eq!(migration.version, index as u64 + 1);
+        }
+        assert_eq!(MIGRATIONS.len() as u64, SCHEMA_VERSION);
+    }
+
+    #[test]
+    fn test_new_db_is_not_migrated() {
+        let dir = tempdir().unwrap();
+        let db = rocksdb::DB::open_default(dir.path()).unwrap();
+        run_migrations(&db, TEST_MIGRATIONS, 2).unwrap();
+        assert_eq!(read_version(&db).unwrap(), Some(2));
+        assert_eq!(db.get("foo").unwrap(), None);
+        assert_eq!(db.get("bar").unwrap(), None);
+    }
+
+    #[test]
+    fn test_migrations_run_in_order() {
+        let dir = tempdir().unwrap();
+        let db = rocksdb::DB::open_default(dir.path()).unwrap();
+        // An existing DB without a version
+        db.put("height", [0_u8]).unwrap();
+
+        run_migrations(&db, TEST_MIGRATIONS, 1).unwrap();
+        assert_eq!(read_version(&db).unwrap(), Some(1));
+        assert_eq!(db.get("foo").unwrap(), Some(vec![1]));
+
+        run_migrations(&db, TEST_MIGRATIONS, 2).unwrap();
+        assert_eq!(read_version(&db).unwrap(), Some(2));
+        assert_eq!(db.get("foo").unwrap(), None);
+        assert_eq!(db.get("bar").unwrap(), Some(vec![1]));
+
+        // Already up to date
+        run_migrations(&db, TEST_MIGRATIONS, 2).unwrap();
+        assert_eq!(db.get("bar").unwrap(), Some(vec![1]));
+    }
+
+    #[test]
+    fn test_newer_version_is_rejected() {
+        let dir = tempdir().unwrap();
+        let db = rocksdb::DB::open_default(dir.path()).unwrap();
+        write_version(&db, WriteBatch::default(), 3).unwrap();
+        assert!(matches!(
+            run_migrations(&db, TEST_MIGRATIONS, 2),
+            Err(Error::UnsupportedSchemaVersion {
+                found: 3,
+                supported: 2
+            })
+        ));
+    }
+
+    #[test]
+    fn test_split_tree_stores() {
+        let dir = tempdir().unwrap();
+        let db = rocksdb::DB::open_default(dir.path()).unwrap();
+        let mut tree: SparseMerkleTree<Sha256Hasher, H256, DefaultStore<H256>> =
+            SparseMerkleTree::default();
+        tree.update(Sha256Hasher::hash("key"), Sha256Hasher::hash("value"))
+            .unwrap();
+        db.put("height", types::encode(&BlockHeight(2))).unwrap();
+        for height in [1, 2] {
+            db.put(
+                format!("{}/tree/account/store", height),
+                types::encode(tree.store()),
+            )
+            .unwrap();
+        }
+
+        let mut batch = WriteBatch::default();
+        split_tree_stores(&db, &mut batch).unwrap();
+        db.write(batch).unwrap();
+
+        for height in [1, 2] {
+            let store_key = format!("{}/tree/account/store", height);
+            assert_eq!(db.get(store_key).unwrap(), None);
+        }
+        for (node_key, branch) in tree.store().branches_map() {
+            let key = tree_branch_key(&StoreType::Account, node_key);
+            assert_eq!(db.get(key).unwrap(), Some(types::encode(branch)));
+        }
+        for (node_key, leaf) in tree.store().leaves_map() {
+            let key = tree_leaf_key(&StoreType::Account, node_key);
+            assert_eq!(db.get(key).unwrap(), Some(types::encode(leaf)));
+        }
+    }
+
+    #[test]
+    fn test_missing_migration_is_rejected() {
+        let dir = tempdir().unwrap();
+        let db = rocksdb::DB::open_default(dir.path()).unwrap();
+        db.put("height", [0_u8]).unwrap();
+        assert!(run_migrations(&db, TEST_MIGRATIONS, 3).is_err());
+    }
+}
@@ -1,6 +1,7 @@
 //! The storage module handles both the current state in-memory and the stored
 //! state in DB.
 
+mod migrations;
 mod rocksdb;
 
 use std::fmt;
@@ -16,6 +17,11 @@ pub struct PersistentStorageHasher(Blake2bHasher);
 
 pub type PersistentDB = rocksdb::RocksDB;
 
+/// A read-only handle of the persistent DB, see [`open_read_only`]
+pub type ReadOnlyDB = rocksdb::ReadOnlyRocksDB;
+
+pub use self::rocksdb::{open_read_only, open_secondary, SnapshotReader};
+
 pub type PersistentStorage = Storage<PersistentDB, PersistentStorageHasher>;
 
 impl Hasher for PersistentStorageHasher {
@@ -50,6 +56,7 @@ fn new_blake2b() -> Blake2b {
 
 #[cfg(test)]
 mod tests {
+    use anoma::ledger::gas::storage_read_gas;
     use anoma::ledger::storage::types;
     use anoma::types::chain::ChainId;
     use anoma::types::storage::{BlockHash, BlockHeight, Key};
@@ -71,10 +78,10 @@ mod tests {
         // before insertion
         let (result, gas) = storage.has_key(&key).expect("has_key failed");
         assert!(!result);
-        assert_eq!(gas, key.len() as u64);
+        assert_eq!(gas, storage_read_gas(key.len(), 0));
         let (result, gas) = storage.read(&key).expect("read failed");
         assert_eq!(result, None);
-        assert_eq!(gas, key.len() as u64);
+        assert_eq!(gas, storage_read_gas(key.len(), 0));
 
         // insert
         storage.write(&key, value_bytes).expect("write failed");
@@ -82,13 +89,13 @@ mod tests {
         // read
         let (result, gas) = storage.has_key(&key).expect("has_key failed");
         assert!(result);
-        assert_eq!(gas, key.len() as u64);
+        assert_eq!(gas, storage_read_gas(key.len(), 0));
         let (result, gas) = storage.read(&key).expect("read failed");
         let read_value: u64 =
             types::decode(&result.expect("value doesn't exist"))
                 .expect("decoding failed");
         assert_eq!(read_value, value);
-        assert_eq!(gas, key.len() as u64 + value_bytes_len as u64);
+        assert_eq!(gas, storage_read_gas(key.len(), value_bytes_len));
 
         // delete
         storage.delete(&key).expect("delete failed");
@@ -141,6 +148,48 @@ mod tests {
         assert_eq!(val.expect("no value"), value_bytes);
     }
 
+    /// Test that the state of a block that hasn't been committed is neither
+    /// reported nor loaded after a restart
+    #[test]
+    fn test_uncommitted_block_state() {
+        let db_path =
+            TempDir::new().expect("Unable to create a temporary DB directory");
+        let mut storage =
+            PersistentStorage::open(db_path.path(), ChainId::default(), None);
+        assert!(storage.get_state().is_none());
+        storage
+            .begin_block(BlockHash::default(), BlockHeight(100))
+            .expect("begin_block failed");
+        let key = Key::parse("key").expect("cannot parse the key string");
+        storage
+            .write(&key, types::encode(&1_u64))
+            .expect("write failed");
+        storage.commit().expect("commit failed");
+        let root = storage.merkle_root();
+
+        // begin the next block without committing it
+        storage
+            .begin_block(BlockHash::default(), BlockHeight(101))
+            .expect("begin_block failed");
+        let next_key =
+            Key::parse("next_key").expect("cannot parse the key string");
+        storage
+            .write(&next_key, types::encode(&2_u64))
+            .expect("write failed");
+        assert_eq!(storage.get_state(), Some((root.clone(), 100)));
+        drop(storage);
+
+        // load the last committed block
+        let mut storage =
+            PersistentStorage::open(db_path.path(), ChainId::default(), None);
+        storage
+            .load_last_state()
+            .expect("loading the last state failed");
+        assert_eq!(storage.get_state(), Some((root, 100)));
+        let (val, _) = storage.read(&next_key).expect("read failed");
+        assert!(val.is_none());
+    }
+
     #[test]
     fn test_iter() {
         let db_path =
@@ -167,14 +216,17 @@ mod tests {
         storage.commit().expect("commit failed");
 
         let (iter, gas) = storage.iter_prefix(&prefix);
-        assert_eq!(gas, prefix.len() as u64);
+        assert_eq!(gas, storage_read_gas(prefix.len(), 0));
         for (k, v, gas) in iter {
             match expected.pop() {
                 Some((expected_key, expected_val)) => {
                     assert_eq!(k, expected_key);
                     assert_eq!(v, expected_val);
-                    let expected_gas = expected_key.len() + expected_val.len();
-                    assert_eq!(gas, expected_gas as u64);
+                    let expected_gas = storage_read_gas(
+                        expected_key.len(),
+                        expected_val.len(),
+                    );
+                    assert_eq!(gas, expected_gas);
                 }
                 None => panic!("read a pair though no expected pair"),
             }
@@ -198,7 +250,7 @@ mod tests {
         let (vp, gas) =
             storage.validity_predicate(&addr).expect("VP load failed");
         assert_eq!(vp, None);
-        assert_eq!(gas, key.len() as u64);
+        assert_eq!(gas, storage_read_gas(key.len(), 0));
 
         // insert
         let vp1 = "vp1".as_bytes().to_vec();
@@ -208,6 +260,6 @@ mod tests {
         let (vp, gas) =
             storage.validity_predicate(&addr).expect("VP load failed");
         assert_eq!(vp.expect("no VP"), vp1);
-        assert_eq!(gas, (key.len() + vp1.len()) as u64);
+        assert_eq!(gas, storage_read_gas(key.len(), vp1.len()));
     }
 }
@@ -1,6 +1,7 @@
 //! The persistent storage in RocksDB.
 //!
 //! The current storage tree is:
+//! - `schema_version`: the version of this layout, see [`super::migrations`]
 //! - `chain_id`
 //! - `height`: the last committed block height
 //! - `tx_queue`: txs to be decrypted in the next block
@@ -15,10 +16,13 @@
 //!   - `next_epoch_min_start_time`
 //! - `subspace`: accounts sub-spaces
 //!   - `{address}/{dyn}`: any byte data associated with accounts
+//! - `tree`: the nodes of the last committed merkle tree, only the changed
+//!   nodes are written with a block
+//!   - `{store_type}/branch/{node_key}`: a branch node
+//!   - `{store_type}/leaf/{node_key}`: a leaf node
 //! - `h`: for each block at height `h`:
 //!   - `tree`: merkle tree
 //!     - `root`: root hash
-//!     - `store`: the tree's store
 //!   - `hash`: block hash
 //!   - `epoch`: block epoch
 //!   - `address_gen`: established address generator
@@ -30,11 +34,15 @@
 use std::cmp::Ordering;
 use std::path::Path;
 use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
 
+use anoma::ledger::gas::storage_read_gas;
 use anoma::ledger::storage::types::PrefixIterator;
 use anoma::ledger::storage::{
-    types, BlockStateRead, BlockStateWrite, DBIter, DBWriteBatch, Error,
-    MerkleTreeStoresRead, Result, StoreType, DB,
+    decode_node_key, encode_node_key, types, BlockStateRead, BlockStateWrite,
+    DBIter, DBWriteBatch, Error, MerkleRoot, MerkleTreeStoresRead,
+    MerkleTreeStoresWrite, Result, StoreType, DB,
 };
 use anoma::types::storage::{
     BlockHeight, Key, KeySeg, TxQueue, KEY_SEGMENT_SEPARATOR,
@@ -44,6 +52,7 @@ use rocksdb::{
     BlockBasedOptions, Direction, FlushOptions, IteratorMode, Options,
     ReadOptions, SliceTransform, WriteBatch, WriteOptions,
 };
+use sparse_merkle_tree::H256;
 #[cfg(not(feature = "ABCI"))]
 use tendermint::block::Header;
 #[cfg(not(feature = "ABCI"))]
@@ -53,27 +62,91 @@ use tendermint_proto_abci::Protobuf;
 #[cfg(feature = "ABCI")]
 use tendermint_stable::block::Header;
 
+use super::migrations;
 use crate::config::utils::num_of_threads;
 
-// TODO the DB schema will probably need some kind of versioning
-
 /// Env. var to set a number of Rayon global worker threads
 const ENV_VAR_ROCKSDB_COMPACTION_THREADS: &str =
     "ANOMA_ROCKSDB_COMPACTION_THREADS";
 
 /// RocksDB handle
 #[derive(Debug)]
-pub struct RocksDB(rocksdb::DB);
+pub struct RocksDB(Arc<rocksdb::DB>, PendingWrite);
+
+/// A handle for reading the committed state concurrently with the execution
+/// of the next block, e.g. to serve queries without waiting for the shell.
+/// Every read is made from a consistent snapshot of the DB and returns the
+/// height of the last block committed in it.
+#[derive(Clone, Debug)]
+pub struct SnapshotReader(Arc<rocksdb::DB>);
+
+/// The write of the last committed block, if it's been executed in the
+/// background
+type PendingWrite = Mutex<Option<BlockWrite>>;
+
+/// A write of a block executed in the background
+#[derive(Debug)]
+enum BlockWrite {
+    /// The write may still be running
+    Running(JoinHandle<std::result::Result<(), rocksdb::Error>>),
+    /// The write has failed, so the DB is missing the block and it mustn't be
+    /// accessed anymore
+    Failed(String),
+}
 
 /// DB Handle for batch writes.
 #[derive(Default)]
 pub struct RocksDBWriteBatch(WriteBatch);
 
-/// Open RocksDB for the DB
+/// A read-only handle of the DB for inspecting the state of a node. Unlike
+/// [`RocksDB`], it doesn't flush the DB on drop.
+#[derive(Debug)]
+pub struct ReadOnlyRocksDB(rocksdb::DB);
+
+/// Open RocksDB for the DB and migrate it to the current schema version
 pub fn open(
     path: impl AsRef<Path>,
     cache: Option<&rocksdb::Cache>,
 ) -> Result<RocksDB> {
+    let cf_opts = db_options(cache);
+    let db = rocksdb::DB::open_cf_descriptors(&cf_opts, path, vec![])
+        .map_err(|e| Error::DBError(e.into_string()))?;
+    migrations::migrate(&db)?;
+    Ok(RocksDB(Arc::new(db), Mutex::new(None)))
+}
+
+/// Open RocksDB for reading only. The DB may be opened by a running node at
+/// the same time, in which case the state as of the time of opening is read.
+pub fn open_read_only(path: impl AsRef<Path>) -> Result<ReadOnlyRocksDB> {
+    let cf_opts = db_options(None);
+    rocksdb::DB::open_for_read_only(&cf_opts, path, false)
+        .map(ReadOnlyRocksDB)
+        .map_err(|e| Error::DBError(e.into_string()))
+}
+
+/// Open RocksDB as a secondary instance of the DB at the primary path, which
+/// may be opened by a running node at the same time. The secondary path is
+/// used for the instance's own logs. The returned reader only sees the
+/// changes written by the primary instance after it catches up with it, see
+/// [`SnapshotReader::try_catch_up`].
+pub fn open_secondary(
+    primary_path: impl AsRef<Path>,
+    secondary_path: impl AsRef<Path>,
+) -> Result<SnapshotReader> {
+    let mut cf_opts = db_options(None);
+    // A secondary instance must keep all the files of the primary open
+    cf_opts.set_max_open_files(-1);
+    rocksdb::DB::open_as_secondary(
+        &cf_opts,
+        primary_path.as_ref(),
+        secondary_path.as_ref(),
+    )
+    .map(|db| SnapshotReader(Arc::new(db)))
+    .map_err(|e| Error::DBError(e.into_string()))
+}
+
+/// The options of the DB, which must be the same for every handle
+fn db_options(cache: Option<&rocksdb::Cache>) -> Options {
     let logical_cores = num_cpus::get();
     let compaction_threads = num_of_threads(
         ENV_VAR_ROCKSDB_COMPACTION_THREADS,
@@ -120,9 +193,7 @@ pub fn open(
     cf_opts.set_prefix_extractor(extractor);
     // TODO use column families
 
-    rocksdb::DB::open_cf_descriptors(&cf_opts, path, vec![])
-        .map(RocksDB)
-        .map_err(|e| Error::DBError(e.into_string()))
+    cf_opts
 }
 
 /// A custom key comparator is used to sort keys by the height. In
@@ -154,12 +225,18 @@ fn key_comparator(a: &[u8], b: &[u8]) -> Ordering {
 
 impl Drop for RocksDB {
     fn drop(&mut self) {
+        if let Err(err) = self.wait_for_pending_write() {
+            // The block missing from the DB is replayed on restart
+            tracing::error!("The write of the last block failed: {}", err);
+            return;
+        }
         self.flush(true).expect("flush failed");
     }
 }
 
 impl RocksDB {
     fn flush(&self, wait: bool) -> Result<()> {
+        self.wait_for_pending_write()?;
         let mut flush_opts = FlushOptions::default();
         flush_opts.set_wait(wait);
         self.0
@@ -238,12 +315,306 @@ impl RocksDB {
     }
 
     fn exec_batch(&mut self, batch: WriteBatch) -> Result<()> {
+        self.wait_for_pending_write()?;
         let mut write_opts = WriteOptions::default();
         write_opts.disable_wal(true);
         self.0
             .write_opt(batch, &write_opts)
             .map_err(|e| Error::DBError(e.into_string()))
     }
+
+    /// Get a handle for reading the committed state from other threads
+    pub fn snapshot_reader(&self) -> SnapshotReader {
+        SnapshotReader(self.0.clone())
+    }
+
+    /// Wait for the write of the last committed block to finish, if it's being
+    /// executed in the background, so that the DB is never accessed before
+    /// the block is in it. If the write has failed, this keeps failing, so
+    /// that nothing is read from or written on top of the DB without the
+    /// block.
+    fn wait_for_pending_write(&self) -> Result<()> {
+        let mut pending = self
+            .1
+            .lock()
+            .expect("The lock of the pending write shouldn't be poisoned");
+        let result = match pending.take() {
+            Some(BlockWrite::Running(handle)) => match handle.join() {
+                Ok(result) => result.map_err(|e| e.into_string()),
+                Err(_) => Err("The block writer thread panicked".to_owned()),
+            },
+            Some(BlockWrite::Failed(err)) => Err(err),
+            None => Ok(()),
+        };
+        result.map_err(|err| {
+            *pending = Some(BlockWrite::Failed(err.clone()));
+            Error::DBError(err)
+        })
+    }
+
+    /// Read the nodes of the last committed merkle tree into the stores
+    fn read_tree_nodes(
+        &self,
+        merkle_tree_stores: &mut MerkleTreeStoresRead,
+    ) -> Result<()> {
+        let prefix = "tree/";
+        let mut read_opts = ReadOptions::default();
+        // don't use the prefix bloom filter
+        read_opts.set_total_order_seek(true);
+        read_opts.set_iterate_upper_bound("tree0");
+        for (key, bytes) in self.0.iterator_opt(
+            IteratorMode::From(prefix.as_bytes(), Direction::Forward),
+            read_opts,
+        ) {
+            let path = &String::from_utf8((*key).to_vec()).map_err(|e| {
+                Error::Temporary {
+                    error: format!(
+                        "Cannot convert path from utf8 bytes to string: {}",
+                        e
+                    ),
+                }
+            })?;
+            let segments: Vec<&str> =
+                path.split(KEY_SEGMENT_SEPARATOR).collect();
+            match segments.as_slice() {
+                ["tree", st, kind, node_key] => {
+                    let st = StoreType::from_str(st)?;
+                    let node_key = decode_node_key(node_key)?;
+                    match *kind {
+                        "branch" => merkle_tree_stores.insert_branch(
+                            &st,
+                            node_key,
+                            types::decode(bytes).map_err(Error::CodingError)?,
+                        )?,
+                        "leaf" => merkle_tree_stores.insert_leaf(
+                            &st,
+                            node_key,
+                            types::decode(bytes).map_err(Error::CodingError)?,
+                        )?,
+                        _ => unknown_key_error(path)?,
+                    }
+                }
+                _ => unknown_key_error(path)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Write the batch of a committed block. Unless the write is synced, the
+/// write-ahead log is not used and the DB is flushed without waiting.
+fn write_block_batch(
+    db: &rocksdb::DB,
+    batch: WriteBatch,
+    sync: bool,
+) -> std::result::Result<(), rocksdb::Error> {
+    let mut write_opts = WriteOptions::default();
+    if sync {
+        // The block is durable once the write-ahead log is synced
+        write_opts.set_sync(true);
+    } else {
+        write_opts.disable_wal(true);
+    }
+    db.write_opt(batch, &write_opts)?;
+    if !sync {
+        let mut flush_opts = FlushOptions::default();
+        flush_opts.set_wait(false);
+        db.flush_opt(&flush_opts)?;
+    }
+    Ok(())
+}
+
+/// Add the nodes of the merkle tree changed in the block to the batch. The
+/// removed nodes are kept in the archive mode, so that the trees of the older
+/// heights, whose roots are written with every block, can be restored.
+fn write_tree_nodes(
+    batch: &mut WriteBatch,
+    merkle_tree_stores: &MerkleTreeStoresWrite,
+    archive: bool,
+) {
+    for st in StoreType::iter() {
+        let store = merkle_tree_stores.store(st);
+        for (node_key, branch) in store.changed_branches() {
+            let key = tree_branch_key(st, node_key);
+            match branch {
+                Some(branch) => batch.put(key, types::encode(branch)),
+                None if archive => {}
+                None => batch.delete(key),
+            }
+        }
+        for (node_key, leaf) in store.changed_leaves() {
+            let key = tree_leaf_key(st, node_key);
+            match leaf {
+                Some(leaf) => batch.put(key, types::encode(leaf)),
+                None if archive => {}
+                None => batch.delete(key),
+            }
+        }
+    }
+}
+
+/// The DB key of a branch node of the merkle tree
+pub(super) fn tree_branch_key(st: &StoreType, node_key: &H256) -> String {
+    format!("tree/{}/branch/{}", st, encode_node_key(node_key))
+}
+
+/// The DB key of a leaf node of the merkle tree
+pub(super) fn tree_leaf_key(st: &StoreType, node_key: &H256) -> String {
+    format!("tree/{}/leaf/{}", st, encode_node_key(node_key))
+}
+
+impl ReadOnlyRocksDB {
+    /// Read the value of an account subspace key
+    pub fn read_subspace_val(&self, key: &Key) -> Result<Option<Vec<u8>>> {
+        let subspace_key =
+            Key::parse("subspace").map_err(Error::KeyError)?.join(key);
+        self.0
+            .get(subspace_key.to_string())
+            .map_err(|e| Error::DBError(e.into_string()))
+    }
+
+    /// Read the last committed block height and the root of its Merkle tree,
+    /// if any block has been committed
+    pub fn read_last_root(&self) -> Result<Option<(BlockHeight, MerkleRoot)>> {
+        let height: BlockHeight = match self
+            .0
+            .get("height")
+            .map_err(|e| Error::DBError(e.into_string()))?
+        {
+            Some(bytes) => types::decode(bytes).map_err(Error::CodingError)?,
+            None => return Ok(None),
+        };
+        let root_key = Key::from(height.to_db_key())
+            .push(&"tree".to_owned())
+            .and_then(|key| key.push(&StoreType::Base.to_string()))
+            .and_then(|key| key.push(&"root".to_owned()))
+            .map_err(Error::KeyError)?;
+        match self
+            .0
+            .get(root_key.to_string())
+            .map_err(|e| Error::DBError(e.into_string()))?
+        {
+            Some(bytes) => {
+                let root: H256 =
+                    types::decode(bytes).map_err(Error::CodingError)?;
+                Ok(Some((height, root.into())))
+            }
+            None => Err(Error::Temporary {
+                error: format!(
+                    "The Merkle root of the last block {} is missing",
+                    height
+                ),
+            }),
+        }
+    }
+
+    /// Iterate over the account subspace key-vals with the given key prefix,
+    /// or over the whole subspace if there's no prefix
+    pub fn iter_prefix(
+        &self,
+        prefix: Option<&Key>,
+    ) -> PersistentPrefixIterator<'_> {
+        let prefix = prefix.map(Key::to_string).unwrap_or_default();
+        iter_subspace_prefix(&self.0, &prefix)
+    }
+}
+
+impl SnapshotReader {
+    /// Read the value of an account subspace key together with the height of
+    /// the last committed block
+    pub fn read_subspace_val(
+        &self,
+        key: &Key,
+    ) -> Result<(BlockHeight, Option<Vec<u8>>)> {
+        let snapshot = self.0.snapshot();
+        let height = read_snapshot_height(&snapshot)?;
+        let subspace_key =
+            Key::parse("subspace").map_err(Error::KeyError)?.join(key);
+        let value = snapshot
+            .get(subspace_key.to_string())
+            .map_err(|e| Error::DBError(e.into_string()))?;
+        Ok((height, value))
+    }
+
+    /// Read the account subspace key-vals whose keys start with the given
+    /// prefix together with the height of the last committed block
+    pub fn read_subspace_prefix(
+        &self,
+        prefix: &Key,
+    ) -> Result<(BlockHeight, Vec<(String, Vec<u8>)>)> {
+        let snapshot = self.0.snapshot();
+        let height = read_snapshot_height(&snapshot)?;
+        let (db_prefix, prefix, read_opts) =
+            subspace_prefix_read_opts(&prefix.to_string());
+        let iter = snapshot.iterator_opt(
+            IteratorMode::From(prefix.as_bytes(), Direction::Forward),
+            read_opts,
+        );
+        let key_vals =
+            PersistentPrefixIterator(PrefixIterator::new(iter, db_prefix))
+                .map(|(key, value, _gas)| (key, value))
+                .collect();
+        Ok((height, key_vals))
+    }
+
+    /// Read the epoch of the last committed block together with its height
+    pub fn read_epoch(&self) -> Result<(BlockHeight, Option<Vec<u8>>)> {
+        let snapshot = self.0.snapshot();
+        let height = read_snapshot_height(&snapshot)?;
+        let key = Key::from(height.to_db_key())
+            .push(&"epoch".to_owned())
+            .map_err(Error::KeyError)?;
+        let epoch = snapshot
+            .get(key.to_string())
+            .map_err(|e| Error::DBError(e.into_string()))?;
+        Ok((height, epoch))
+    }
+
+    /// Read the height of the last committed block
+    pub fn read_last_height(&self) -> Result<BlockHeight> {
+        read_snapshot_height(&self.0.snapshot())
+    }
+
+    /// Catch up with the changes written by the primary instance of the DB.
+    /// Only a reader opened with [`open_secondary`] can catch up.
+    pub fn try_catch_up(&self) -> Result<()> {
+        self.0
+            .try_catch_up_with_primary()
+            .map_err(|e| Error::DBError(e.into_string()))
+    }
+
+    /// Read the encoded results of the block with the given height or of the
+    /// last committed block, together with the block's height
+    pub fn read_block_results(
+        &self,
+        height: Option<BlockHeight>,
+    ) -> Result<(BlockHeight, Option<Vec<u8>>)> {
+        let snapshot = self.0.snapshot();
+        let height = match height {
+            Some(height) => height,
+            None => read_snapshot_height(&snapshot)?,
+        };
+        let key = Key::from(height.to_db_key())
+            .push(&"results".to_owned())
+            .map_err(Error::KeyError)?;
+        let results = snapshot
+            .get(key.to_string())
+            .map_err(|e| Error::DBError(e.into_string()))?;
+        Ok((height, results))
+    }
+}
+
+/// Read the height of the last committed block in the snapshot
+fn read_snapshot_height(snapshot: &rocksdb::Snapshot) -> Result<BlockHeight> {
+    match snapshot
+        .get("height")
+        .map_err(|e| Error::DBError(e.into_string()))?
+    {
+        Some(bytes) => types::decode(bytes).map_err(Error::CodingError),
+        None => Err(Error::Temporary {
+            error: "No block has been committed yet".to_owned(),
+        }),
+    }
 }
 
 impl DB for RocksDB {
@@ -258,14 +629,11 @@ impl DB for RocksDB {
     }
 
     fn flush(&self, wait: bool) -> Result<()> {
-        let mut flush_opts = FlushOptions::default();
-        flush_opts.set_wait(wait);
-        self.0
-            .flush_opt(&flush_opts)
-            .map_err(|e| Error::DBError(e.into_string()))
+        RocksDB::flush(self, wait)
     }
 
     fn read_last_block(&mut self) -> Result<Option<BlockStateRead>> {
+        self.wait_for_pending_write()?;
         // Block height
         let height: BlockHeight = match self
             .0
@@ -355,11 +723,6 @@ impl DB for RocksDB {
                                     types::decode(bytes)
                                         .map_err(Error::CodingError)?,
                                 ),
-                                Some(&"store") => merkle_tree_stores.set_store(
-                                    &st,
-                                    types::decode(bytes)
-                                        .map_err(Error::CodingError)?,
-                                ),
                                 _ => unknown_key_error(path)?,
                             }
                         }
@@ -396,6 +759,7 @@ impl DB for RocksDB {
                 None => unknown_key_error(path)?,
             }
         }
+        self.read_tree_nodes(&mut merkle_tree_stores)?;
         match (hash, epoch, pred_epochs, address_gen) {
             (Some(hash), Some(epoch), Some(pred_epochs), Some(address_gen)) => {
                 Ok(Some(BlockStateRead {
@@ -417,8 +781,13 @@ impl DB for RocksDB {
         }
     }
 
-    fn write_block(&mut self, state: BlockStateWrite) -> Result<()> {
-        let mut batch = WriteBatch::default();
+    fn write_block(
+        &mut self,
+        state: BlockStateWrite,
+        batch: &mut Self::WriteBatch,
+    ) -> Result<()> {
+        self.wait_for_pending_write()?;
+        let batch = &mut batch.0;
         let BlockStateWrite {
             merkle_tree_stores,
             header,
@@ -430,6 +799,7 @@ impl DB for RocksDB {
             next_epoch_min_start_time,
             address_gen,
             tx_queue,
+            archive,
         }: BlockStateWrite = state;
 
         // Epoch start height and time
@@ -486,14 +856,8 @@ impl DB for RocksDB {
                     root_key.to_string(),
                     types::encode(merkle_tree_stores.root(st)),
                 );
-                let store_key = prefix_key
-                    .push(&"store".to_owned())
-                    .map_err(Error::KeyError)?;
-                batch.put(
-                    store_key.to_string(),
-                    types::encode(merkle_tree_stores.store(st)),
-                );
             }
+            write_tree_nodes(batch, &merkle_tree_stores, archive);
         }
         // Block header
         {
@@ -538,15 +902,11 @@ impl DB for RocksDB {
 
         // Block height
         batch.put("height", types::encode(&height));
-
-        // Write the batch
-        self.exec_batch(batch)?;
-
-        // Flush without waiting
-        self.flush(false)
+        Ok(())
     }
 
     fn read_block_header(&self, height: BlockHeight) -> Result<Option<Header>> {
+        self.wait_for_pending_write()?;
         let prefix_key = Key::from(height.to_db_key());
         let key = prefix_key
             .push(&"header".to_owned())
@@ -563,10 +923,37 @@ impl DB for RocksDB {
         }
     }
 
+    fn write_block_results(
+        &mut self,
+        height: BlockHeight,
+        results: &[u8],
+        batch: &mut Self::WriteBatch,
+    ) -> Result<()> {
+        let key = Key::from(height.to_db_key())
+            .push(&"results".to_owned())
+            .map_err(Error::KeyError)?;
+        batch.0.put(key.to_string(), results);
+        Ok(())
+    }
+
+    fn read_block_results(
+        &self,
+        height: BlockHeight,
+    ) -> Result<Option<Vec<u8>>> {
+        self.wait_for_pending_write()?;
+        let key = Key::from(height.to_db_key())
+            .push(&"results".to_owned())
+            .map_err(Error::KeyError)?;
+        self.0
+            .get(key.to_string())
+            .map_err(|e| Error::DBError(e.into_string()))
+    }
+
     fn read_merkle_tree_stores(
         &self,
         height: BlockHeight,
     ) -> Result<Option<MerkleTreeStoresRead>> {
+        self.wait_for_pending_write()?;
         let mut merkle_tree_stores = MerkleTreeStoresRead::default();
         let height_key = Key::from(height.to_db_key());
         let tree_key = height_key
@@ -589,26 +976,13 @@ impl DB for RocksDB {
                 }
                 None => return Ok(None),
             }
-
-            let store_key = prefix_key
-                .push(&"store".to_owned())
-                .map_err(Error::KeyError)?;
-            let bytes = self
-                .0
-                .get(store_key.to_string())
-                .map_err(|e| Error::DBError(e.into_string()))?;
-            match bytes {
-                Some(b) => {
-                    let store = types::decode(b).map_err(Error::CodingError)?;
-                    merkle_tree_stores.set_store(st, store);
-                }
-                None => return Ok(None),
-            }
         }
+        self.read_tree_nodes(&mut merkle_tree_stores)?;
         Ok(Some(merkle_tree_stores))
     }
 
     fn read_subspace_val(&self, key: &Key) -> Result<Option<Vec<u8>>> {
+        self.wait_for_pending_write()?;
         let subspace_key =
             Key::parse("subspace").map_err(Error::KeyError)?.join(key);
         self.0
@@ -616,35 +990,84 @@ impl DB for RocksDB {
             .map_err(|e| Error::DBError(e.into_string()))
     }
 
+    fn prune_height(
+        &self,
+        height: BlockHeight,
+        batch: &mut Self::WriteBatch,
+    ) -> Result<()> {
+        self.wait_for_pending_write()?;
+        let height_key = Key::from(height.to_db_key());
+        let diffs_prefix = height_key
+            .push(&"diffs".to_owned())
+            .map_err(Error::KeyError)?
+            .to_string();
+        let mut read_opts = ReadOptions::default();
+        // don't use the prefix bloom filter
+        read_opts.set_total_order_seek(true);
+        read_opts.set_iterate_upper_bound(format!("{}0", diffs_prefix));
+        for (key, _) in self.0.iterator_opt(
+            IteratorMode::From(diffs_prefix.as_bytes(), Direction::Forward),
+            read_opts,
+        ) {
+            batch.0.delete(key);
+        }
+        let results_key = height_key
+            .push(&"results".to_owned())
+            .map_err(Error::KeyError)?;
+        batch.0.delete(results_key.to_string());
+        Ok(())
+    }
+
     fn read_subspace_val_with_height(
         &self,
         key: &Key,
         height: BlockHeight,
     ) -> Result<Option<Vec<u8>>> {
-        if self.read_subspace_val(key)?.is_none() {
-            return Ok(None);
-        }
-
-        let mut height = height.0;
-        while height > 0 {
-            let key_prefix = Key::from(BlockHeight(height).to_db_key())
+        self.wait_for_pending_write()?;
+        let last_height: BlockHeight = match self
+            .0
+            .get("height")
+            .map_err(|e| Error::DBError(e.into_string()))?
+        {
+            Some(bytes) => types::decode(bytes).map_err(Error::CodingError)?,
+            None => return Ok(None),
+        };
+        // The value at the height is the old value of its first change after
+        // the height, so that only the diffs of the later heights, which are
+        // retained longer, are needed
+        for diff_height in (height.0 + 1)..=last_height.0 {
+            let key_prefix = Key::from(BlockHeight(diff_height).to_db_key())
                 .push(&"diffs".to_owned())
                 .map_err(Error::KeyError)?;
+            let old_val_key = key_prefix
+                .push(&"old".to_owned())
+                .map_err(Error::KeyError)?
+                .join(key)
+                .to_string();
+            let old_val = self
+                .0
+                .get(old_val_key)
+                .map_err(|e| Error::DBError(e.into_string()))?;
+            if old_val.is_some() {
+                return Ok(old_val);
+            }
             let new_val_key = key_prefix
                 .push(&"new".to_owned())
                 .map_err(Error::KeyError)?
                 .join(key)
                 .to_string();
-            let val = self
+            let created = self
                 .0
                 .get(new_val_key)
-                .map_err(|e| Error::DBError(e.into_string()))?;
-            match val {
-                Some(bytes) => return Ok(Some(bytes)),
-                None => height -= 1,
+                .map_err(|e| Error::DBError(e.into_string()))?
+                .is_some();
+            if created {
+                // The key didn't have a value before this change
+                return Ok(None);
             }
         }
-        Ok(None)
+        // The value hasn't changed since the height
+        self.read_subspace_val(key)
     }
 
     fn write_subspace_val(
@@ -653,6 +1076,7 @@ impl DB for RocksDB {
         key: &Key,
         value: impl AsRef<[u8]>,
     ) -> Result<i64> {
+        self.wait_for_pending_write()?;
         let value = value.as_ref();
         let subspace_key =
             Key::parse("subspace").map_err(Error::KeyError)?.join(key);
@@ -690,6 +1114,7 @@ impl DB for RocksDB {
         height: BlockHeight,
         key: &Key,
     ) -> Result<i64> {
+        self.wait_for_pending_write()?;
         let subspace_key =
             Key::parse("subspace").map_err(Error::KeyError)?.join(key);
 
@@ -723,6 +1148,39 @@ impl DB for RocksDB {
         self.exec_batch(batch.0)
     }
 
+    fn exec_block_batch(
+        &mut self,
+        batch: Self::WriteBatch,
+        sync: bool,
+    ) -> Result<()> {
+        self.wait_for_pending_write()?;
+        write_block_batch(&self.0, batch.0, sync)
+            .map_err(|e| Error::DBError(e.into_string()))
+    }
+
+    fn exec_block_batch_in_background(
+        &mut self,
+        batch: Self::WriteBatch,
+        sync: bool,
+    ) -> Result<()> {
+        self.wait_for_pending_write()?;
+        let db = self.0.clone();
+        let handle = std::thread::Builder::new()
+            .name("block-writer".into())
+            .spawn(move || write_block_batch(&db, batch.0, sync))
+            .map_err(|e| Error::DBError(e.to_string()))?;
+        *self
+            .1
+            .get_mut()
+            .expect("The lock of the pending write shouldn't be poisoned") =
+            Some(BlockWrite::Running(handle));
+        Ok(())
+    }
+
+    fn wait_for_block_batch(&self) -> Result<()> {
+        self.wait_for_pending_write()
+    }
+
     fn batch_write_subspace_val(
         &self,
         batch: &mut Self::WriteBatch,
@@ -730,6 +1188,7 @@ impl DB for RocksDB {
         key: &Key,
         value: impl AsRef<[u8]>,
     ) -> Result<i64> {
+        self.wait_for_pending_write()?;
         let value = value.as_ref();
         let subspace_key =
             Key::parse("subspace").map_err(Error::KeyError)?.join(key);
@@ -774,6 +1233,7 @@ impl DB for RocksDB {
         height: BlockHeight,
         key: &Key,
     ) -> Result<i64> {
+        self.wait_for_pending_write()?;
         let subspace_key =
             Key::parse("subspace").map_err(Error::KeyError)?.join(key);
 
@@ -812,24 +1272,42 @@ impl<'iter> DBIter<'iter> for RocksDB {
         &'iter self,
         prefix: &Key,
     ) -> PersistentPrefixIterator<'iter> {
-        let db_prefix = "subspace/".to_owned();
-        let prefix = format!("{}{}", db_prefix, prefix);
-
-        let mut read_opts = ReadOptions::default();
-        // don't use the prefix bloom filter
-        read_opts.set_total_order_seek(true);
-        let mut upper_prefix = prefix.clone().into_bytes();
-        if let Some(last) = upper_prefix.pop() {
-            upper_prefix.push(last + 1);
+        if let Err(err) = self.wait_for_pending_write() {
+            tracing::error!("The write of the last block failed: {}", err);
         }
-        read_opts.set_iterate_upper_bound(upper_prefix);
+        iter_subspace_prefix(&self.0, &prefix.to_string())
+    }
+}
 
-        let iter = self.0.iterator_opt(
-            IteratorMode::From(prefix.as_bytes(), Direction::Forward),
-            read_opts,
-        );
-        PersistentPrefixIterator(PrefixIterator::new(iter, db_prefix))
+/// Iterate over the account subspace key-vals whose keys start with the given
+/// prefix
+fn iter_subspace_prefix<'iter>(
+    db: &'iter rocksdb::DB,
+    prefix: &str,
+) -> PersistentPrefixIterator<'iter> {
+    let (db_prefix, prefix, read_opts) = subspace_prefix_read_opts(prefix);
+    let iter = db.iterator_opt(
+        IteratorMode::From(prefix.as_bytes(), Direction::Forward),
+        read_opts,
+    );
+    PersistentPrefixIterator(PrefixIterator::new(iter, db_prefix))
+}
+
+/// Get the DB prefix of the account subspace, the DB key prefix of the given
+/// prefix and the options to iterate over the keys that start with it
+fn subspace_prefix_read_opts(prefix: &str) -> (String, String, ReadOptions) {
+    let db_prefix = "subspace/".to_owned();
+    let prefix = format!("{}{}", db_prefix, prefix);
+
+    let mut read_opts = ReadOptions::default();
+    // don't use the prefix bloom filter
+    read_opts.set_total_order_seek(true);
+    let mut upper_prefix = prefix.clone().into_bytes();
+    if let Some(last) = upper_prefix.pop() {
+        upper_prefix.push(last + 1);
     }
+    read_opts.set_iterate_upper_bound(upper_prefix);
+    (db_prefix, prefix, read_opts)
 }
 
 #[derive(Debug)]
@@ -848,8 +1326,8 @@ impl<'a> Iterator for PersistentPrefixIterator<'a> {
                     .expect("Cannot convert from bytes to key string");
                 match key.strip_prefix(&self.0.db_prefix) {
                     Some(k) => {
-                        let gas = k.len() + val.len();
-                        Some((k.to_owned(), val.to_vec(), gas as _))
+                        let gas = storage_read_gas(k.len(), val.len());
+                        Some((k.to_owned(), val.to_vec(), gas))
                     }
                     None => self.next(),
                 }
@@ -964,7 +1442,10 @@ mod test {
         .unwrap();
         db.exec_batch(batch.0).unwrap();
 
-        let merkle_tree = MerkleTree::<Sha256Hasher>::default();
+        let mut merkle_tree = MerkleTree::<Sha256Hasher>::default();
+        merkle_tree
+            .update(&Key::parse("test").unwrap(), [1_u8, 1, 1, 1])
+            .unwrap();
         let merkle_tree_stores = merkle_tree.stores();
         let hash = BlockHash::default();
         let epoch = Epoch::default();
@@ -985,14 +1466,149 @@ mod test {
             next_epoch_min_start_time,
             address_gen: &address_gen,
             tx_queue: &tx_queue,
+            archive: false,
         };
 
-        db.write_block(block).unwrap();
+        let mut batch = RocksDB::batch();
+        db.write_block(block, &mut batch).unwrap();
+        db.exec_batch(batch.0).unwrap();
 
-        let _state = db
+        let state = db
             .read_last_block()
             .expect("Should be able to read last block")
             .expect("Block should have been written");
+        let restored_tree =
+            MerkleTree::<Sha256Hasher>::new(state.merkle_tree_stores);
+        assert_eq!(restored_tree.root(), merkle_tree.root());
+
+        drop(db);
+        let db = open_read_only(dir.path()).unwrap();
+        let (last_height, root) = db
+            .read_last_root()
+            .expect("Should be able to read last root")
+            .expect("Block should have been written");
+        assert_eq!(last_height, height);
+        assert_eq!(root, merkle_tree.root());
+    }
+
+    #[test]
+    fn test_read_only() {
+        let dir = tempdir().unwrap();
+        let key = Key::parse("test").unwrap();
+        let other_key = Key::parse("other").unwrap();
+        {
+            let mut db = open(dir.path(), None).unwrap();
+            let mut batch = RocksDB::batch();
+            for key in [&key, &other_key] {
+                db.batch_write_subspace_val(
+                    &mut batch,
+                    BlockHeight(1),
+                    key,
+                    vec![1_u8, 2],
+                )
+                .unwrap();
+            }
+            db.exec_batch(batch.0).unwrap();
+        }
+
+        let db = open_read_only(dir.path()).unwrap();
+        assert_eq!(db.read_subspace_val(&key).unwrap(), Some(vec![1, 2]));
+        let keys: Vec<String> =
+            db.iter_prefix(None).map(|(key, _, _)| key).collect();
+        assert_eq!(keys, vec!["other".to_owned(), "test".to_owned()]);
+        let keys: Vec<String> =
+            db.iter_prefix(Some(&key)).map(|(key, _, _)| key).collect();
+        assert_eq!(keys, vec!["test".to_owned()]);
+    }
+
+    #[test]
+    fn test_block_results() {
+        let dir = tempdir().unwrap();
+        let mut db = open(dir.path(), None).unwrap();
+
+        let height = BlockHeight(2);
+        assert_eq!(db.read_block_results(height).unwrap(), None);
+        let mut batch = RocksDB::batch();
+        db.write_block_results(height, &[1_u8, 2, 3], &mut batch).unwrap();
+        db.exec_block_batch(batch, true).unwrap();
+        assert_eq!(db.read_block_results(height).unwrap(), Some(vec![1, 2, 3]));
+        assert_eq!(db.read_block_results(BlockHeight(1)).unwrap(), None);
+    }
+
+    #[test]
+    fn test_snapshot_reader() {
+        let dir = tempdir().unwrap();
+        let mut db = open(dir.path(), None).unwrap();
+        let reader = db.snapshot_reader();
+        let key = Key::parse("test").unwrap();
+        assert!(reader.read_subspace_val(&key).is_err());
+
+        let mut batch = RocksDB::batch();
+        db.batch_write_subspace_val(&mut batch, BlockHeight(1), &key, [1_u8])
+            .unwrap();
+        batch.put("height", types::encode(&BlockHeight(1)));
+        db.exec_block_batch(batch, false).unwrap();
+
+        assert_eq!(
+            reader.read_subspace_val(&key).unwrap(),
+            (BlockHeight(1), Some(vec![1]))
+        );
+        assert_eq!(
+            reader.read_subspace_prefix(&key).unwrap(),
+            (BlockHeight(1), vec![("test".to_owned(), vec![1])])
+        );
+    }
+
+    /// Test that the DB is not read before a block written in the background
+    /// is in it and that the write can be awaited.
+    #[test]
+    fn test_block_batch_in_background() {
+        let dir = tempdir().unwrap();
+        let mut db = open(dir.path(), None).unwrap();
+        let key = Key::parse("test").unwrap();
+
+        for height in 1..=10 {
+            let height = BlockHeight(height);
+            let mut batch = RocksDB::batch();
+            db.batch_write_subspace_val(
+                &mut batch,
+                height,
+                &key,
+                types::encode(&height),
+            )
+            .unwrap();
+            db.exec_block_batch_in_background(batch, false).unwrap();
+            assert_eq!(
+                db.read_subspace_val(&key).unwrap(),
+                Some(types::encode(&height))
+            );
+        }
+
+        // The write's result can be awaited without accessing the DB
+        let mut batch = RocksDB::batch();
+        db.batch_delete_subspace_val(&mut batch, BlockHeight(11), &key)
+            .unwrap();
+        db.exec_block_batch_in_background(batch, false).unwrap();
+        db.wait_for_block_batch().unwrap();
+        assert_eq!(db.read_subspace_val(&key).unwrap(), None);
+    }
+
+    /// Test that the DB can't be accessed anymore after a block's write in the
+    /// background has failed.
+    #[test]
+    fn test_failed_block_batch_in_background() {
+        let dir = tempdir().unwrap();
+        let mut db = open(dir.path(), None).unwrap();
+        let key = Key::parse("test").unwrap();
+
+        *db.1.get_mut().unwrap() =
+            Some(BlockWrite::Failed("The disk is full".to_owned()));
+        assert!(db.wait_for_block_batch().is_err());
+        // The failure isn't cleared by the first access
+        assert!(db.read_subspace_val(&key).is_err());
+        assert!(db.wait_for_block_batch().is_err());
+        let batch = RocksDB::batch();
+        assert!(db.exec_block_batch_in_background(batch, false).is_err());
     }
 
     #[test]
@@ -1023,6 +1639,8 @@ mod test {
         )
         .unwrap();
         db.exec_batch(batch.0).unwrap();
+        // The last committed height
+        db.0.put("height", types::encode(&last_height)).unwrap();
 
         let prev_value = db
             .read_subspace_val_with_height(&key, BlockHeight(100))
@@ -1033,4 +1651,43 @@ mod test {
             db.read_subspace_val(&key).expect("read should succeed");
         assert_eq!(latest_value, Some(vec![2_u8, 2, 2, 2]));
     }
+
+    #[test]
+    fn test_prune_height() {
+        let dir = tempdir().unwrap();
+        let mut db = open(dir.path(), None).unwrap();
+
+        let key = Key::parse("test").unwrap();
+        for height in [1, 2] {
+            let mut batch = RocksDB::batch();
+            let height = BlockHeight(height);
+            db.batch_write_subspace_val(
+                &mut batch,
+                height,
+                &key,
+                types::encode(&height),
+            )
+            .unwrap();
+            db.write_block_results(height, &[1], &mut batch).unwrap();
+            db.exec_batch(batch.0).unwrap();
+        }
+        db.0.put("height", types::encode(&BlockHeight(2))).unwrap();
+
+        let mut batch = RocksDB::batch();
+        db.prune_height(BlockHeight(1), &mut batch).unwrap();
+        db.exec_batch(batch.0).unwrap();
+
+        assert_eq!(db.read_block_results(BlockHeight(1)).unwrap(), None);
+        assert_eq!(db.0.get("1/diffs/new/test").unwrap(), None);
+        // The later height is retained, so the value at the pruned height can
+        // still be read
+        assert_eq!(
+            db.read_block_results(BlockHeight(2)).unwrap(),
+            Some(vec![1])
+        );
+        assert_eq!(
+            db.read_subspace_val_with_height(&key, BlockHeight(1)).unwrap(),
+            Some(types::encode(&BlockHeight(1)))
+        );
+    }
 }
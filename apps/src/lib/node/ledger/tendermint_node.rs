@@ -1,7 +1,7 @@
 use std::path::{Path, PathBuf};
 use std::str::FromStr;
+use std::time::Duration;
 
-use anoma::types::address::Address;
 use anoma::types::chain::ChainId;
 use anoma::types::key::*;
 use anoma::types::time::DateTimeUtc;
@@ -26,7 +26,7 @@ use tendermint_stable::Genesis;
 use thiserror::Error;
 use tokio::fs::{self, File, OpenOptions};
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
-use tokio::process::Command;
+use tokio::process::{Child, Command};
 
 use crate::config;
 
@@ -48,10 +48,37 @@ pub enum Error {
     Runtime(String),
     #[error("Failed to convert to String: {0:?}")]
     TendermintPath(std::ffi::OsString),
+    #[error("Incompatible Tendermint version \"{0}\", expected version {1}x")]
+    Version(String, &'static str),
+    #[error("Failed to reset Tendermint: {0}")]
+    Reset(std::io::Error),
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
 
+/// The version prefix of the Tendermint binary compatible with the ledger, if
+/// any is required
+#[cfg(feature = "ABCI")]
+const COMPATIBLE_VERSION_PREFIX: Option<&str> = Some("0.34.");
+#[cfg(not(feature = "ABCI"))]
+const COMPATIBLE_VERSION_PREFIX: Option<&str> = None;
+
+/// Tendermint's node key file in its config dir
+const NODE_KEY_FILE: &str = "node_key.json";
+/// Tendermint's validator key file in its config dir
+const VALIDATOR_KEY_FILE: &str = "priv_validator_key.json";
+/// Tendermint's address book file in its config dir
+const ADDR_BOOK_FILE: &str = "addrbook.json";
+/// A temporary dir in the Tendermint home dir for the files kept on reset
+const RESET_KEPT_DIR: &str = "reset_kept";
+
+/// The delay before restarting a crashed Tendermint node
+const RESTART_DELAY: Duration = Duration::from_secs(1);
+
+/// How long to wait for the Tendermint node to shut down gracefully before
+/// killing it
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(10);
+
 /// Check if the TENDERMINT env var has been set and use that as the
 /// location of the tendermint binary. Otherwise, assume it is on path
 ///
@@ -69,19 +96,172 @@ fn from_env_or_default() -> Result<String> {
     }
 }
 
-/// Run the tendermint node.
+/// Get the path to the Tendermint binary from the config, if set, or from the
+/// environment or the default.
+fn tendermint_path(config: &config::Tendermint) -> Result<String> {
+    match config.tendermint_path.clone() {
+        Some(path) => path
+            .into_os_string()
+            .into_string()
+            .map_err(Error::TendermintPath),
+        None => from_env_or_default(),
+    }
+}
+
+/// Run the tendermint node. The node is restarted when it crashes, up to the
+/// configured maximum number of restarts. With an external Tendermint, this
+/// only waits for the abort signal.
 pub async fn run(
     home_dir: PathBuf,
     chain_id: ChainId,
     genesis_time: DateTimeUtc,
     ledger_address: String,
     config: config::Tendermint,
-    abort_recv: tokio::sync::oneshot::Receiver<
+    mut abort_recv: tokio::sync::oneshot::Receiver<
         tokio::sync::oneshot::Sender<()>,
     >,
 ) -> Result<()> {
+    if config.external_tendermint {
+        tracing::info!(
+            "Expecting an external Tendermint node to connect to the ledger \
+             at {}",
+            ledger_address
+        );
+        if let Ok(resp_sender) = abort_recv.await {
+            let _ = resp_sender.send(());
+        }
+        return Ok(());
+    }
+
     let home_dir_string = home_dir.to_string_lossy().to_string();
-    let tendermint_path = from_env_or_default()?;
+    let tendermint_path = tendermint_path(&config)?;
+    let mode = config.tendermint_mode.to_str().to_owned();
+    let max_restarts = config.max_restarts;
+
+    check_version(&tendermint_path).await?;
+    init(&home_dir, chain_id, genesis_time, config).await?;
+
+    let mut restarts = 0;
+    loop {
+        let mut tendermint_node = if !cfg!(feature = "ABCI") {
+            Command::new(&tendermint_path)
+                .args(&[
+                    "start",
+                    "--mode",
+                    &mode,
+                    "--proxy-app",
+                    &ledger_address,
+                    "--home",
+                    &home_dir_string,
+                ])
+                .kill_on_drop(true)
+                .spawn()
+                .map_err(Error::StartUp)?
+        } else {
+            Command::new(&tendermint_path)
+                .args(&[
+                    "start",
+                    "--proxy_app",
+                    &ledger_address,
+                    "--home",
+                    &home_dir_string,
+                ])
+                .kill_on_drop(true)
+                .spawn()
+                .map_err(Error::StartUp)?
+        };
+        tracing::info!("Tendermint node started");
+
+        tokio::select! {
+            status = tendermint_node.wait() => {
+                let err = match status {
+                    Ok(status) if status.success() => return Ok(()),
+                    Ok(status) => status.to_string(),
+                    Err(err) => err.to_string(),
+                };
+                if restarts >= max_restarts {
+                    return Err(Error::Runtime(err));
+                }
+                restarts += 1;
+                tracing::warn!(
+                    "Tendermint node crashed with {}, restarting it ({}/{})...",
+                    err,
+                    restarts,
+                    max_restarts
+                );
+                tokio::time::sleep(RESTART_DELAY).await;
+            },
+            resp_sender = &mut abort_recv => {
+                match resp_sender {
+                    Ok(resp_sender) => {
+                        shutdown(&mut tendermint_node).await;
+                        resp_sender.send(()).unwrap();
+                    },
+                    Err(err) => {
+                        tracing::error!("The Tendermint abort sender has unexpectedly dropped: {}", err);
+                        shutdown(&mut tendermint_node).await;
+                    }
+                }
+                return Ok(());
+            }
+        }
+    }
+}
+
+/// Check that the version of the Tendermint binary is compatible with the
+/// ledger.
+async fn check_version(tendermint_path: &str) -> Result<()> {
+    let output = Command::new(tendermint_path)
+        .arg("version")
+        .output()
+        .await
+        .map_err(Error::StartUp)?;
+    let version = String::from_utf8_lossy(&output.stdout).trim().to_owned();
+    tracing::info!("Tendermint version: {}", version);
+    match COMPATIBLE_VERSION_PREFIX {
+        Some(prefix) if !version.starts_with(prefix) => {
+            Err(Error::Version(version, prefix))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Ask the Tendermint node to shut down gracefully and kill it if it doesn't
+/// exit within the [`SHUTDOWN_TIMEOUT`].
+async fn shutdown(tendermint_node: &mut Child) {
+    tracing::info!("Shutting down Tendermint node...");
+    #[cfg(unix)]
+    if let Some(pid) = tendermint_node.id() {
+        // Safe because the process is our child that hasn't been awaited yet,
+        // so its PID cannot have been reused
+        unsafe { libc::kill(pid as libc::pid_t, libc::SIGTERM) };
+        match tokio::time::timeout(SHUTDOWN_TIMEOUT, tendermint_node.wait())
+            .await
+        {
+            Ok(Ok(_)) => return,
+            Ok(Err(err)) => {
+                tracing::error!("Failed to wait for Tendermint node: {}", err)
+            }
+            Err(_) => tracing::warn!(
+                "Tendermint node didn't shut down in time, killing it"
+            ),
+        }
+    }
+    if let Err(err) = tendermint_node.kill().await {
+        tracing::error!("Failed to kill Tendermint node: {}", err);
+    }
+}
+
+/// Initialize the Tendermint home directory with the node's keys, the genesis
+/// file and the config. The existing keys are kept.
+pub async fn init(
+    home_dir: &Path,
+    chain_id: ChainId,
+    genesis_time: DateTimeUtc,
+    config: config::Tendermint,
+) -> Result<()> {
+    let home_dir_string = home_dir.to_string_lossy().to_string();
+    let tendermint_path = tendermint_path(&config)?;
     let mode = config.tendermint_mode.to_str().to_owned();
 
     #[cfg(feature = "dev")]
@@ -91,7 +271,7 @@ pub async fn run(
         Path::new(&path).exists()
     };
 
-    // init and run a tendermint node child process
+    // init a tendermint node home
     let output = if !cfg!(feature = "ABCI") {
         Command::new(&tendermint_path)
             .args(&["init", &mode, "--home", &home_dir_string])
@@ -111,95 +291,52 @@ pub async fn run(
 
     #[cfg(feature = "dev")]
     {
-        let genesis = &crate::config::genesis::genesis();
         let consensus_key = crate::wallet::defaults::validator_keypair();
         // write the validator key file if it didn't already exist
         if !has_validator_key {
-            write_validator_key_async(
-                &home_dir,
-                &genesis
-                    .validators
-                    .first()
-                    .expect(
-                        "There should be one genesis validator in \"dev\" mode",
-                    )
-                    .pos_data
-                    .address,
-                &consensus_key,
-            )
-            .await;
+            write_validator_key_async(home_dir, &consensus_key).await;
         }
     }
 
-    write_tm_genesis(&home_dir, chain_id, genesis_time).await;
+    write_tm_genesis(home_dir, chain_id, genesis_time).await;
 
-    update_tendermint_config(&home_dir, config).await?;
-
-    let mut tendermint_node = if !cfg!(feature = "ABCI") {
-        Command::new(&tendermint_path)
-            .args(&[
-                "start",
-                "--mode",
-                &mode,
-                "--proxy-app",
-                &ledger_address,
-                "--home",
-                &home_dir_string,
-            ])
-            .kill_on_drop(true)
-            .spawn()
-            .map_err(Error::StartUp)?
-    } else {
-        Command::new(&tendermint_path)
-            .args(&[
-                "start",
-                "--proxy_app",
-                &ledger_address,
-                "--home",
-                &home_dir_string,
-            ])
-            .kill_on_drop(true)
-            .spawn()
-            .map_err(Error::StartUp)?
-    };
-    tracing::info!("Tendermint node started");
-
-    tokio::select! {
-        status = tendermint_node.wait() => {
-            match status {
-                Ok(status) => {
-                    if status.success() {
-                        Ok(())
-                    } else {
-                        Err(Error::Runtime(status.to_string()))
-                    }
-                },
-                Err(err) => {
-                    Err(Error::Runtime(err.to_string()))
-                }
-            }
-        },
-        resp_sender = abort_recv => {
-            match resp_sender {
-                Ok(resp_sender) => {
-                    tracing::info!("Shutting down Tendermint node...");
-                    tendermint_node.kill().await.unwrap();
-                    resp_sender.send(()).unwrap();
-                },
-                Err(err) => {
-                    tracing::error!("The Tendermint abort sender has unexpectedly dropped: {}", err);
-                    tracing::info!("Shutting down Tendermint node...");
-                    tendermint_node.kill().await.unwrap();
-                }
-            }
-            Ok(())
-        }
-    }
+    update_tendermint_config(home_dir, config).await
 }
 
-pub fn reset(tendermint_dir: impl AsRef<Path>) -> Result<()> {
+/// Reset all the Tendermint state and config. With `keep_keys`, the node key
+/// and the validator key are kept and with `keep_addr_book`, the address book
+/// with the known peers is kept.
+pub fn reset(
+    tendermint_dir: impl AsRef<Path>,
+    keep_keys: bool,
+    keep_addr_book: bool,
+) -> Result<()> {
     let tendermint_path = from_env_or_default()?;
+    let config_dir = tendermint_dir.as_ref().join("config");
+    let kept_dir = tendermint_dir.as_ref().join(RESET_KEPT_DIR);
     let tendermint_dir = tendermint_dir.as_ref().to_string_lossy();
+
+    let mut kept_files = vec![];
+    if keep_keys {
+        kept_files.extend([NODE_KEY_FILE, VALIDATOR_KEY_FILE]);
+    }
+    if keep_addr_book {
+        kept_files.push(ADDR_BOOK_FILE);
+    }
+    // Move the kept files out of the config dir. Moving the files preserves
+    // their permissions.
+    let kept_files: Vec<&str> = kept_files
+        .into_iter()
+        .filter(|file| config_dir.join(file).is_file())
+        .collect();
+    if !kept_files.is_empty() {
+        std::fs::create_dir_all(&kept_dir).map_err(Error::Reset)?;
+        for file in &kept_files {
+            std::fs::rename(config_dir.join(file), kept_dir.join(file))
+                .map_err(Error::Reset)?;
+        }
+    }
+
     // reset all the Tendermint state, if any
     std::process::Command::new(tendermint_path)
         .args(&[
@@ -213,18 +350,29 @@ pub fn reset(tendermint_dir: impl AsRef<Path>) -> Result<()> {
         .expect("Failed to reset tendermint node's data");
     std::fs::remove_dir_all(format!("{}/config", tendermint_dir,))
         .expect("Failed to reset tendermint node's config");
+
+    // Restore the kept files
+    if !kept_files.is_empty() {
+        std::fs::create_dir_all(&config_dir).map_err(Error::Reset)?;
+        for file in kept_files {
+            tracing::info!("Keeping Tendermint's {}", file);
+            std::fs::rename(kept_dir.join(file), config_dir.join(file))
+                .map_err(Error::Reset)?;
+        }
+        std::fs::remove_dir_all(&kept_dir).map_err(Error::Reset)?;
+    }
     Ok(())
 }
 
 /// Convert a common signing scheme validator key into JSON for
 /// Tendermint
 fn validator_key_to_json<SK: SecretKey>(
-    address: &Address,
     sk: &SK,
 ) -> std::result::Result<serde_json::Value, ParseSecretKeyError> {
-    let address = address.raw_hash().unwrap();
     ed25519::SecretKey::try_from_sk(sk).map(|sk| {
         let pk: ed25519::PublicKey = sk.ref_to();
+        // The validator's address in Tendermint is derived from its key
+        let address: String = PublicKeyHash::from(&pk).into();
         let ck_arr =
             [sk.try_to_vec().unwrap(), pk.try_to_vec().unwrap()].concat();
         json!({
@@ -244,7 +392,6 @@ fn validator_key_to_json<SK: SecretKey>(
 /// Initialize validator private key for Tendermint
 pub async fn write_validator_key_async(
     home_dir: impl AsRef<Path>,
-    address: &Address,
     consensus_key: &common::SecretKey,
 ) {
     let home_dir = home_dir.as_ref();
@@ -261,7 +408,7 @@ pub async fn write_validator_key_async(
         .open(&path)
         .await
         .expect("Couldn't create private validator key file");
-    let key = validator_key_to_json(address, consensus_key).unwrap();
+    let key = validator_key_to_json(consensus_key).unwrap();
     let data = serde_json::to_vec_pretty(&key)
         .expect("Couldn't encode private validator key file");
     file.write_all(&data[..])
@@ -272,7 +419,6 @@ pub async fn write_validator_key_async(
 /// Initialize validator private key for Tendermint
 pub fn write_validator_key(
     home_dir: impl AsRef<Path>,
-    address: &Address,
     consensus_key: &common::SecretKey,
 ) {
     let home_dir = home_dir.as_ref();
@@ -287,7 +433,7 @@ pub fn write_validator_key(
         .truncate(true)
         .open(&path)
         .expect("Couldn't create private validator key file");
-    let key = validator_key_to_json(address, consensus_key).unwrap();
+    let key = validator_key_to_json(consensus_key).unwrap();
     serde_json::to_writer_pretty(file, &key)
         .expect("Couldn't write private validator key file");
 }
@@ -3,11 +3,13 @@ use std::net::SocketAddr;
 use std::path::{Path, PathBuf};
 use std::rc::Rc;
 use std::sync::Arc;
+use std::time::Duration;
 
 use anoma::proto::Tx;
 use anoma::types::address::{self, Address};
+use anoma::types::chain::ChainId;
 use anoma::types::dylib;
-use anoma::types::intent::{IntentTransfers, MatchedExchanges};
+use anoma::types::intent::{IntentFilter, IntentTransfers, MatchedExchanges};
 use anoma::types::key::*;
 use anoma::types::matchmaker::AddIntentResult;
 use anoma::types::transaction::{hash_tx, Fee, WrapperTx};
@@ -26,12 +28,13 @@ use tendermint_config_abci::net::Address as TendermintAddress;
 use super::gossip::rpc::matchmakers::{
     ClientDialer, ClientListener, MsgFromClient, MsgFromServer,
 };
-use crate::cli::args;
+use crate::cli::args::{self, BroadcastMode, OutputFormat};
 use crate::client::rpc;
 use crate::client::tx::{broadcast_tx, TxBroadcastData};
 use crate::{cli, config, wasm_loader};
 
 /// Run a matchmaker
+#[allow(clippy::too_many_arguments)]
 #[tokio::main]
 pub async fn run(
     config::Matchmaker {
@@ -40,8 +43,10 @@ pub async fn run(
     }: config::Matchmaker,
     intent_gossiper_addr: SocketAddr,
     ledger_addr: TendermintAddress,
+    chain_id: ChainId,
     tx_signing_key: Rc<common::SecretKey>,
     tx_source_address: Address,
+    intent_filter: IntentFilter,
     wasm_dir: impl AsRef<Path>,
 ) {
     let matchmaker_path = matchmaker_path.unwrap_or_else(|| {
@@ -58,8 +63,10 @@ pub async fn run(
         matchmaker_path,
         tx_code_path,
         ledger_addr,
+        chain_id,
         tx_signing_key,
         tx_source_address,
+        intent_filter,
         wasm_dir,
     );
 
@@ -98,12 +105,16 @@ pub struct ResultHandler {
     result_recv: tokio::sync::mpsc::UnboundedReceiver<AddIntentResult>,
     /// The ledger address to send any crafted transaction to
     ledger_address: net::Address,
+    /// The ID of the chain that the crafted transactions are for
+    chain_id: ChainId,
     /// The code of the transaction that is going to be send to a ledger.
     tx_code: Vec<u8>,
     /// A source address for transactions created from intents.
     tx_source_address: Address,
     /// A keypair that will be used to sign transactions.
     tx_signing_key: Rc<common::SecretKey>,
+    /// The filter of intents to be registered with the intent gossip node.
+    intent_filter: IntentFilter,
 }
 
 /// The loaded implementation's dylib and its state
@@ -126,13 +137,16 @@ struct MatchmakerState(Arc<*mut c_void>);
 impl Runner {
     /// Create a new matchmaker and a dialer that can be used to send messages
     /// to the intent gossiper node.
+    #[allow(clippy::too_many_arguments)]
     pub fn new_pair(
         intent_gossiper_addr: SocketAddr,
         matchmaker_path: PathBuf,
         tx_code_path: PathBuf,
         ledger_address: TendermintAddress,
+        chain_id: ChainId,
         tx_signing_key: Rc<common::SecretKey>,
         tx_source_address: Address,
+        intent_filter: IntentFilter,
         wasm_dir: impl AsRef<Path>,
     ) -> (Self, ResultHandler) {
         // Setup a channel for sending matchmaker results from `Self` to the
@@ -154,9 +168,11 @@ impl Runner {
                 dialer,
                 result_recv,
                 ledger_address,
+                chain_id,
                 tx_code,
                 tx_source_address,
                 tx_signing_key,
+                intent_filter,
             },
         )
     }
@@ -295,6 +311,16 @@ impl Drop for MatchmakerImpl {
 
 impl ResultHandler {
     async fn run(mut self) {
+        // Register the intent filter, if any, once connected to the intent
+        // gossip node
+        if !self.intent_filter.is_empty() {
+            while !self.dialer.is_connected() {
+                tokio::time::sleep(Duration::from_millis(100)).await;
+            }
+            self.dialer.send(MsgFromClient::SetFilter {
+                filter: self.intent_filter.clone(),
+            });
+        }
         while let Some(result) = self.result_recv.recv().await {
             if let Some(tx) = result.tx {
                 self.submit_tx(tx).await
@@ -314,9 +340,12 @@ impl ResultHandler {
         };
         let tx_data = intent_transfers.try_to_vec().unwrap();
         let to_broadcast = {
-            let epoch = rpc::query_epoch(args::Query {
-                ledger_address: self.ledger_address.clone(),
-            })
+            let epoch = rpc::query_epoch(
+                args::Query {
+                    ledger_address: self.ledger_address.clone(),
+                },
+                OutputFormat::Human,
+            )
             .await;
             let tx = WrapperTx::new(
                 Fee {
@@ -326,7 +355,9 @@ impl ResultHandler {
                 &self.tx_signing_key,
                 epoch,
                 0.into(),
-                Tx::new(tx_code, Some(tx_data)).sign(&self.tx_signing_key),
+                Tx::new(tx_code, Some(tx_data))
+                    .with_chain_id(self.chain_id.clone())
+                    .sign(&self.tx_signing_key),
                 // TODO: Actually use the fetched encryption key
                 Default::default(),
             );
@@ -341,17 +372,24 @@ impl ResultHandler {
             } else {
                 None
             };
+            let tx_hash = tx.tx_hash.to_string();
             TxBroadcastData::Wrapper {
                 tx: tx
-                    .sign(&self.tx_signing_key)
+                    .sign(&self.tx_signing_key, self.chain_id.clone())
                     .expect("Wrapper tx signing keypair should be correct"),
                 wrapper_hash,
                 decrypted_hash,
+                tx_hash,
             }
         };
 
-        let response =
-            broadcast_tx(self.ledger_address.clone(), &to_broadcast).await;
+        let response = broadcast_tx(
+            self.ledger_address.clone(),
+            &to_broadcast,
+            BroadcastMode::Sync,
+            OutputFormat::Human,
+        )
+        .await;
         match response {
             Ok(tx_response) => {
                 tracing::info!(
@@ -373,5 +411,9 @@ impl ResultHandler {
 /// Return the path of the file returned by `lazy_path` argument, if it exists.
 fn check_file_exists(lazy_path: impl Fn() -> PathBuf) -> Option<PathBuf> {
     let path = lazy_path();
-    if path.exists() { Some(path) } else { None }
+    if path.exists() {
+        Some(path)
+    } else {
+        None
+    }
 }
@@ -1 +1,3 @@
+#[cfg(feature = "grpc")]
+pub mod query;
 pub mod services;
@@ -1,5 +1,9 @@
 mod generated;
 mod types;
 
+#[cfg(feature = "grpc")]
+pub use generated::query;
 pub use generated::services;
-pub use types::{IntentMessage, RpcMessage, SubscribeTopicMessage};
+pub use types::{
+    IntentMessage, RpcMessage, SubscribeTopicMessage, UnsubscribeTopicMessage,
+};
@@ -9,6 +9,7 @@ pub type Result<T> = std::result::Result<T, Error>;
 pub enum RpcMessage {
     IntentMessage(IntentMessage),
     SubscribeTopicMessage(SubscribeTopicMessage),
+    UnsubscribeTopicMessage(UnsubscribeTopicMessage),
     Dkg(Dkg),
 }
 
@@ -21,6 +22,9 @@ impl From<RpcMessage> for services::RpcMessage {
             RpcMessage::SubscribeTopicMessage(m) => {
                 services::rpc_message::Message::Topic(m.into())
             }
+            RpcMessage::UnsubscribeTopicMessage(m) => {
+                services::rpc_message::Message::UnsubscribeTopic(m.into())
+            }
             RpcMessage::Dkg(d) => services::rpc_message::Message::Dkg(d.into()),
         };
         services::RpcMessage {
@@ -38,6 +42,10 @@ impl RpcMessage {
         RpcMessage::SubscribeTopicMessage(SubscribeTopicMessage::new(topic))
     }
 
+    pub fn new_unsubscribe_topic(topic: String) -> Self {
+        RpcMessage::UnsubscribeTopicMessage(UnsubscribeTopicMessage::new(topic))
+    }
+
     pub fn new_dkg(dkg: Dkg) -> Self {
         RpcMessage::Dkg(dkg)
     }
@@ -105,6 +113,33 @@ impl SubscribeTopicMessage {
     }
 }
 
+#[derive(Debug, PartialEq)]
+pub struct UnsubscribeTopicMessage {
+    pub topic: String,
+}
+
+impl From<services::UnsubscribeTopicMessage> for UnsubscribeTopicMessage {
+    fn from(message: services::UnsubscribeTopicMessage) -> Self {
+        UnsubscribeTopicMessage {
+            topic: message.topic,
+        }
+    }
+}
+
+impl From<UnsubscribeTopicMessage> for services::UnsubscribeTopicMessage {
+    fn from(message: UnsubscribeTopicMessage) -> Self {
+        services::UnsubscribeTopicMessage {
+            topic: message.topic,
+        }
+    }
+}
+
+impl UnsubscribeTopicMessage {
+    pub fn new(topic: String) -> Self {
+        UnsubscribeTopicMessage { topic }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -145,4 +180,21 @@ mod tests {
             _ => panic!("no intent message"),
         }
     }
+
+    #[test]
+    fn test_unsubscribe_topic_message() {
+        let topic = "arbitrary string".to_owned();
+        let topic_message = UnsubscribeTopicMessage::new(topic.clone());
+
+        let topic_rpc_message = RpcMessage::new_unsubscribe_topic(topic);
+        let services_rpc_message: services::RpcMessage =
+            topic_rpc_message.into();
+        match services_rpc_message.message {
+            Some(services::rpc_message::Message::UnsubscribeTopic(t)) => {
+                let message_from_types = UnsubscribeTopicMessage::from(t);
+                assert_eq!(topic_message, message_from_types);
+            }
+            _ => panic!("no unsubscribe topic message"),
+        }
+    }
 }
@@ -0,0 +1,197 @@
+//! Hierarchical deterministic keys derived from a BIP39 mnemonic code, using
+//! the SLIP-0010 derivation scheme for ed25519.
+
+use std::fmt::Display;
+use std::str::FromStr;
+
+use anoma::types::key::*;
+use bip39::{Language, Mnemonic, MnemonicType, Seed};
+use hmac::{Hmac, Mac, NewMac};
+use sha2::Sha512;
+use thiserror::Error;
+
+/// The default derivation path for ed25519 keys, using the SLIP-0044 coin
+/// type 877.
+pub const DEFAULT_DERIVATION_PATH: &str = "m/44'/877'/0'/0'/0'";
+
+/// The HMAC key used to derive the master key from a seed for ed25519.
+const ED25519_SEED_KEY: &[u8] = b"ed25519 seed";
+
+/// The offset of hardened child indices.
+const HARDENED_OFFSET: u32 = 1 << 31;
+
+type HmacSha512 = Hmac<Sha512>;
+
+#[allow(missing_docs)]
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("Invalid mnemonic code: {0}")]
+    InvalidMnemonic(String),
+    #[error("Invalid derivation path {0}: {1}")]
+    InvalidPath(String, String),
+}
+
+/// Result of HD key operations
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// A derivation path of hardened child indices. SLIP-0010 only supports
+/// hardened derivation for ed25519 keys.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DerivationPath(Vec<u32>);
+
+impl Default for DerivationPath {
+    fn default() -> Self {
+        DEFAULT_DERIVATION_PATH.parse().unwrap()
+    }
+}
+
+impl FromStr for DerivationPath {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let invalid = |msg: &str| Error::InvalidPath(s.into(), msg.into());
+        let mut segments = s.split('/');
+        if segments.next() != Some("m") {
+            return Err(invalid("The path must start with \"m\""));
+        }
+        segments
+            .map(|segment| {
+                let index = segment.strip_suffix('\'').ok_or_else(|| {
+                    invalid("Only hardened indices (e.g. 0') are supported")
+                })?;
+                let index: u32 = index
+                    .parse()
+                    .map_err(|_| invalid("Invalid child index"))?;
+                if index >= HARDENED_OFFSET {
+                    return Err(invalid("Child index out of range"));
+                }
+                Ok(index)
+            })
+            .collect::<Result<_>>()
+            .map(Self)
+    }
+}
+
+impl Display for DerivationPath {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "m")?;
+        for index in &self.0 {
+            write!(f, "/{}'", index)?;
+        }
+        Ok(())
+    }
+}
+
+/// Generate a new random 24-word mnemonic code.
+pub fn gen_mnemonic() -> Mnemonic {
+    Mnemonic::new(MnemonicType::Words24, Language::English)
+}
+
+/// Parse a mnemonic code phrase.
+pub fn parse_mnemonic(phrase: &str) -> Result<Mnemonic> {
+    Mnemonic::from_phrase(phrase.trim(), Language::English)
+        .map_err(|err| Error::InvalidMnemonic(err.to_string()))
+}
+
+/// Derive a keypair from a mnemonic code (with an empty BIP39 passphrase) at
+/// the given derivation path.
+pub fn derive_key(
+    mnemonic: &Mnemonic,
+    path: &DerivationPath,
+) -> common::SecretKey {
+    let seed = Seed::new(mnemonic, "");
+    derive_key_from_seed(seed.as_bytes(), path)
+}
+
+/// Derive a keypair from a seed at the given derivation path as specified by
+/// SLIP-0010.
+pub fn derive_key_from_seed(
+    seed: &[u8],
+    path: &DerivationPath,
+) -> common::SecretKey {
+    let (key, _chain_code) = derive_secret_from_seed(seed, path);
+    let sk = ed25519::SecretKey(ed25519_consensus::SigningKey::from(key));
+    common::SecretKey::Ed25519(sk)
+}
+
+/// Derive the raw secret key and chain code at the given path.
+fn derive_secret_from_seed(
+    seed: &[u8],
+    path: &DerivationPath,
+) -> ([u8; 32], [u8; 32]) {
+    let mut node = hmac_sha512(ED25519_SEED_KEY, seed);
+    for index in &path.0 {
+        let (key, chain_code) = node;
+        let mut data = Vec::with_capacity(37);
+        data.push(0);
+        data.extend_from_slice(&key);
+        data.extend_from_slice(&(index + HARDENED_OFFSET).to_be_bytes());
+        node = hmac_sha512(&chain_code, &data);
+    }
+    node
+}
+
+/// Compute HMAC-SHA512 and split it into its left and right halves.
+fn hmac_sha512(key: &[u8], data: &[u8]) -> ([u8; 32], [u8; 32]) {
+    let mut mac = HmacSha512::new_from_slice(key)
+        .expect("HMAC can take a key of any size");
+    mac.update(data);
+    let result = mac.finalize().into_bytes();
+    let mut left = [0; 32];
+    let mut right = [0; 32];
+    left.copy_from_slice(&result[..32]);
+    right.copy_from_slice(&result[32..]);
+    (left, right)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test vector 1 for ed25519 from SLIP-0010
+    #[test]
+    fn test_slip10_vector() {
+        let seed = hex::decode("000102030405060708090a0b0c0d0e0f").unwrap();
+        let master = "m".parse().unwrap();
+        let (key, chain_code) = derive_secret_from_seed(&seed, &master);
+        assert_eq!(
+            hex::encode(key),
+            "2b4be7f19ee27bbf30c667b642d5f4aa69fd169872f8fc3059c08ebae2eb19e7"
+        );
+        assert_eq!(
+            hex::encode(chain_code),
+            "90046a93de5380a72b5e45010748567d5ea02bbf6522f979e05c0d8d8ca9fffb"
+        );
+        let path = "m/0'".parse().unwrap();
+        let (key, chain_code) = derive_secret_from_seed(&seed, &path);
+        assert_eq!(
+            hex::encode(key),
+            "68e0fe46dfb67e368c75379acec591dad19df3cde26e63b93a8e704f1dade7a3"
+        );
+        assert_eq!(
+            hex::encode(chain_code),
+            "8b59aa11380b624e81507a27fedda59fea6d0b779a778918a2fd3590e16e9c69"
+        );
+    }
+
+    #[test]
+    fn test_derivation_path() {
+        let path = DerivationPath::default();
+        assert_eq!(path.to_string(), DEFAULT_DERIVATION_PATH);
+        assert!("44'/877'".parse::<DerivationPath>().is_err());
+        assert!("m/44'/0".parse::<DerivationPath>().is_err());
+        assert!("m/2147483648'".parse::<DerivationPath>().is_err());
+    }
+
+    #[test]
+    fn test_restore_from_mnemonic() {
+        let mnemonic = gen_mnemonic();
+        assert_eq!(mnemonic.phrase().split_whitespace().count(), 24);
+        let restored = parse_mnemonic(mnemonic.phrase()).unwrap();
+        let path = DerivationPath::default();
+        let pk: common::PublicKey = derive_key(&mnemonic, &path).ref_to();
+        let restored_pk: common::PublicKey =
+            derive_key(&restored, &path).ref_to();
+        assert_eq!(pk, restored_pk);
+    }
+}
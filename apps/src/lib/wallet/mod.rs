@@ -1,16 +1,20 @@
 mod alias;
 pub mod defaults;
+pub mod hd;
 mod keys;
 pub mod pre_genesis;
 mod store;
 
 use std::collections::HashMap;
+use std::io::{self, Write};
 use std::path::{Path, PathBuf};
 use std::rc::Rc;
 use std::{env, fs};
 
 use anoma::types::address::Address;
 use anoma::types::key::*;
+use rand::rngs::OsRng;
+use rand::Rng;
 pub use store::wallet_file;
 use thiserror::Error;
 
@@ -111,6 +115,65 @@ impl Wallet {
         (alias.into(), key)
     }
 
+    /// Import the given keypair and derive an implicit address from its
+    /// public key and insert them into the store with the provided alias,
+    /// converted to lower case. If none provided, the alias will be the public
+    /// key hash (in lowercase too). If the key is to be encrypted, will prompt
+    /// for password from stdin. Stores the key in decrypted key cache and
+    /// returns the alias of the key and a reference-counting pointer to the
+    /// key.
+    pub fn import_key(
+        &mut self,
+        keypair: common::SecretKey,
+        alias: Option<String>,
+        unsafe_dont_encrypt: bool,
+    ) -> (String, Rc<common::SecretKey>) {
+        let password = read_and_confirm_pwd(unsafe_dont_encrypt);
+        let (alias, key) = self.store.import_key(keypair, alias, password);
+        // Cache the newly added key
+        self.decrypted_key_cache.insert(alias.clone(), key.clone());
+        (alias.into(), key)
+    }
+
+    /// Generate a new 24-word mnemonic code and derive a keypair from it at the
+    /// given derivation path. The mnemonic code is printed only once and the
+    /// user has to confirm that it has been backed up before the keypair is
+    /// inserted into the store, as with [`Wallet::import_key`].
+    pub fn gen_hd_key(
+        &mut self,
+        alias: Option<String>,
+        unsafe_dont_encrypt: bool,
+        derivation_path: &hd::DerivationPath,
+    ) -> (String, Rc<common::SecretKey>) {
+        let mnemonic = hd::gen_mnemonic();
+        println!(
+            "Safely store your 24 words mnemonic code. It will NOT be shown \
+             again and it's the only way to restore your key:"
+        );
+        println!("{}", mnemonic.phrase());
+        confirm_mnemonic_backup(mnemonic.phrase());
+        let keypair = hd::derive_key(&mnemonic, derivation_path);
+        self.import_key(keypair, alias, unsafe_dont_encrypt)
+    }
+
+    /// Restore a keypair from a mnemonic code read from stdin, derived at the
+    /// given derivation path, and insert it into the store as with
+    /// [`Wallet::import_key`].
+    pub fn restore_hd_key(
+        &mut self,
+        alias: Option<String>,
+        unsafe_dont_encrypt: bool,
+        derivation_path: &hd::DerivationPath,
+    ) -> (String, Rc<common::SecretKey>) {
+        let phrase = read_input("Enter your mnemonic code: ");
+        let mnemonic = hd::parse_mnemonic(&phrase).unwrap_or_else(|err| {
+            eprintln!("{}", err);
+            cli::safe_exit(1)
+        });
+        let keypair = hd::derive_key(&mnemonic, derivation_path);
+        self.import_key(keypair, alias, unsafe_dont_encrypt)
+    }
+
     /// Generate keypair
     /// for signing protocol txs and for the DKG (which will also be stored)
     /// A protocol keypair may be optionally provided, indicating that
@@ -360,6 +423,38 @@ pub fn read_and_confirm_pwd(unsafe_dont_encrypt: bool) -> Option<String> {
     password
 }
 
+/// Ask the user to confirm that the mnemonic code has been backed up by
+/// entering one of its words, chosen at random. Exits if the word doesn't
+/// match.
+fn confirm_mnemonic_backup(phrase: &str) {
+    let words: Vec<&str> = phrase.split_whitespace().collect();
+    let ix = OsRng.gen_range(0..words.len());
+    let word = read_input(&format!(
+        "To confirm that you've stored the mnemonic code, please enter the \
+         word #{}: ",
+        ix + 1
+    ));
+    if word != words[ix] {
+        eprintln!(
+            "The word doesn't match the mnemonic code. Action cancelled, no \
+             changes persisted."
+        );
+        cli::safe_exit(1)
+    }
+}
+
+/// Read a line of input from stdin after printing the given prompt.
+fn read_input(prompt_msg: &str) -> String {
+    print!("{}", prompt_msg);
+    io::stdout().flush().unwrap();
+    let mut input = String::new();
+    io::stdin().read_line(&mut input).unwrap_or_else(|err| {
+        eprintln!("Couldn't read the input: {}", err);
+        cli::safe_exit(1)
+    });
+    input.trim().to_owned()
+}
+
 /// Read the password for encryption/decryption from the file/env/stdin. Panics
 /// if all options are empty/invalid.
 pub fn read_password(prompt_msg: &str) -> String {
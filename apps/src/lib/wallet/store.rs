@@ -262,7 +262,19 @@ impl Store {
         alias: Option<String>,
         password: Option<String>,
     ) -> (Alias, Rc<common::SecretKey>) {
-        let sk = gen_sk();
+        self.import_key(gen_sk(), alias, password)
+    }
+
+    /// Insert the given keypair and the implicit address derived from its
+    /// public key into the store with the provided alias. If none provided,
+    /// the alias will be the public key hash. If a password is given, the
+    /// keypair will be encrypted with it.
+    pub fn import_key(
+        &mut self,
+        sk: common::SecretKey,
+        alias: Option<String>,
+        password: Option<String>,
+    ) -> (Alias, Rc<common::SecretKey>) {
         let pkh: PublicKeyHash = PublicKeyHash::from(&sk.ref_to());
         let (keypair_to_store, raw_keypair) = StoredKeypair::new(sk, password);
         let address = Address::Implicit(ImplicitAddress(pkh.clone()));
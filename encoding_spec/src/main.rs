@@ -72,11 +72,20 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     let signature_schema = Signature::schema_container();
     let signed_tx_data_schema = SignedTxData::schema_container();
     let init_account_schema = transaction::InitAccount::schema_container();
+    let init_multisig_account_schema =
+        transaction::InitMultisigAccount::schema_container();
     let init_validator_schema = transaction::InitValidator::schema_container();
     let token_transfer_schema = token::Transfer::schema_container();
     let update_vp_schema = transaction::UpdateVp::schema_container();
     let pos_bond_schema = pos::Bond::schema_container();
     let pos_withdraw_schema = pos::Withdraw::schema_container();
+    let pos_change_consensus_key_schema =
+        pos::ChangeConsensusKey::schema_container();
+    let pos_update_validator_metadata_schema =
+        pos::UpdateValidatorMetadata::schema_container();
+    let init_token_schema = transaction::token::InitToken::schema_container();
+    let change_supply_schema =
+        transaction::token::ChangeSupply::schema_container();
     let wrapper_tx_schema = transaction::WrapperTx::schema_container();
     // TODO derive BorshSchema after <https://github.com/near/borsh-rs/issues/82>
     // let tx_result_schema = transaction::TxResult::schema_container();
@@ -98,11 +107,16 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     definitions.extend(signature_schema.definitions);
     definitions.extend(signed_tx_data_schema.definitions);
     definitions.extend(init_account_schema.definitions);
+    definitions.extend(init_multisig_account_schema.definitions);
     definitions.extend(init_validator_schema.definitions);
     definitions.extend(token_transfer_schema.definitions);
     definitions.extend(update_vp_schema.definitions);
     definitions.extend(pos_bond_schema.definitions);
     definitions.extend(pos_withdraw_schema.definitions);
+    definitions.extend(pos_change_consensus_key_schema.definitions);
+    definitions.extend(pos_update_validator_metadata_schema.definitions);
+    definitions.extend(init_token_schema.definitions);
+    definitions.extend(change_supply_schema.definitions);
     definitions.extend(wrapper_tx_schema.definitions);
     // definitions.extend(tx_result_schema.definitions);
     definitions.extend(tx_type_schema.definitions);
@@ -172,6 +186,15 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     ).with_rust_doc_link("https://dev.anoma.net/master/rustdoc/anoma/types/transaction/struct.InitAccount.html");
     tables.push(init_account_table);
 
+    let init_multisig_account_definition = definitions
+        .remove(&init_multisig_account_schema.declaration)
+        .unwrap();
+    let init_multisig_account_table = definition_to_table(
+        init_multisig_account_schema.declaration,
+        init_multisig_account_definition,
+    ).with_rust_doc_link("https://dev.anoma.net/master/rustdoc/anoma/types/transaction/struct.InitMultisigAccount.html");
+    tables.push(init_multisig_account_table);
+
     let init_validator_definition = definitions
         .remove(&init_validator_schema.declaration)
         .unwrap();
@@ -211,6 +234,40 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     ).with_rust_doc_link("https://dev.anoma.net/master/rustdoc/anoma/types/transaction/pos/struct.Withdraw.html");
     tables.push(pos_withdraw_table);
 
+    let pos_change_consensus_key_definition = definitions
+        .remove(&pos_change_consensus_key_schema.declaration)
+        .unwrap();
+    let pos_change_consensus_key_table = definition_to_table(
+        pos_change_consensus_key_schema.declaration,
+        pos_change_consensus_key_definition,
+    ).with_rust_doc_link("https://dev.anoma.net/master/rustdoc/anoma/types/transaction/pos/struct.ChangeConsensusKey.html");
+    tables.push(pos_change_consensus_key_table);
+
+    let pos_update_validator_metadata_definition = definitions
+        .remove(&pos_update_validator_metadata_schema.declaration)
+        .unwrap();
+    let pos_update_validator_metadata_table = definition_to_table(
+        pos_update_validator_metadata_schema.declaration,
+        pos_update_validator_metadata_definition,
+    ).with_rust_doc_link("https://dev.anoma.net/master/rustdoc/anoma/types/transaction/pos/struct.UpdateValidatorMetadata.html");
+    tables.push(pos_update_validator_metadata_table);
+
+    let init_token_definition =
+        definitions.remove(&init_token_schema.declaration).unwrap();
+    let init_token_table = definition_to_table(
+        init_token_schema.declaration,
+        init_token_definition,
+    ).with_rust_doc_link("https://dev.anoma.net/master/rustdoc/anoma/types/transaction/token/struct.InitToken.html");
+    tables.push(init_token_table);
+
+    let change_supply_definition =
+        definitions.remove(&change_supply_schema.declaration).unwrap();
+    let change_supply_table = definition_to_table(
+        change_supply_schema.declaration,
+        change_supply_definition,
+    ).with_rust_doc_link("https://dev.anoma.net/master/rustdoc/anoma/types/transaction/token/struct.ChangeSupply.html");
+    tables.push(change_supply_table);
+
     let wrapper_tx_definition =
         definitions.remove(&wrapper_tx_schema.declaration).unwrap();
     let wrapper_tx_table = definition_to_table(
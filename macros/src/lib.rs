@@ -26,6 +26,12 @@ pub fn transaction(_attr: TokenStream, input: TokenStream) -> TokenStream {
         #[global_allocator]
         static ALLOC: wee_alloc::WeeAlloc = wee_alloc::WeeAlloc::INIT;
 
+        // Declare the version of the host interface targeted by this module
+        #[link_section = "anoma_host_interface_version"]
+        #[used]
+        static _HOST_INTERFACE_VERSION: [u8; 4] =
+            HOST_INTERFACE_VERSION.to_le_bytes();
+
         #ast
 
         // The module entrypoint callable by wasm runtime
@@ -68,6 +74,12 @@ pub fn validity_predicate(
         #[global_allocator]
         static ALLOC: wee_alloc::WeeAlloc = wee_alloc::WeeAlloc::INIT;
 
+        // Declare the version of the host interface targeted by this module
+        #[link_section = "anoma_host_interface_version"]
+        #[used]
+        static _HOST_INTERFACE_VERSION: [u8; 4] =
+            HOST_INTERFACE_VERSION.to_le_bytes();
+
         #ast
 
         // The module entrypoint callable by wasm runtime
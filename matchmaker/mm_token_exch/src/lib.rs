@@ -3,6 +3,7 @@ use std::collections::{HashMap, HashSet, VecDeque};
 use anoma::types::address::Address;
 use anoma::types::intent::{Exchange, FungibleTokenIntent, MatchedExchanges};
 use anoma::types::matchmaker::{AddIntent, AddIntentResult};
+use anoma::types::time::DateTimeUtc;
 use anoma::types::token;
 use anoma_macros::Matchmaker;
 use borsh::{BorshDeserialize, BorshSerialize};
@@ -29,15 +30,23 @@ impl AddIntent for TokenExchange {
         let intent = decode_intent_data(&intent_data[..]);
         let exchanges = intent.data.exchange.clone();
 
+        // Drop the exchanges that have expired since they were added
+        let now = DateTimeUtc::now();
+        self.graph
+            .retain_nodes(|graph, ix| !graph[ix].exchange.data.is_expired(now));
+
         println!("trying to match new intent");
-        exchanges.into_iter().for_each(|exchange| {
-            add_intent_node(
-                &mut self.graph,
-                intent_id.to_vec(),
-                exchange,
-                intent.clone(),
-            )
-        });
+        exchanges
+            .into_iter()
+            .filter(|exchange| !exchange.data.is_expired(now))
+            .for_each(|exchange| {
+                add_intent_node(
+                    &mut self.graph,
+                    intent_id.to_vec(),
+                    exchange,
+                    intent.clone(),
+                )
+            });
         let (tx, matched_intents) = match try_match(&mut self.graph) {
             Some((tx, matched_intents)) => (Some(tx), Some(matched_intents)),
             None => (None, None),
@@ -90,6 +90,7 @@ pub trait PosReadOnly {
     /// Cryptographic public key type
     type PublicKey: Debug
         + Clone
+        + PartialEq
         + BorshDeserialize
         + BorshSerialize
         + BorshSchema;
@@ -153,8 +154,13 @@ pub trait PosReadOnly {
 pub trait PosActions: PosReadOnly {
     /// Write PoS parameters.
     fn write_pos_params(&mut self, params: &PosParams);
-    /// Write PoS validator's raw hash its address.
-    fn write_validator_address_raw_hash(&mut self, address: &Self::Address);
+    /// Write PoS validator's address under the raw hash of its consensus key,
+    /// which is the validator's address in Tendermint.
+    fn write_validator_address_raw_hash(
+        &mut self,
+        address: &Self::Address,
+        consensus_key: &Self::PublicKey,
+    );
     /// Write PoS validator's staking reward address, into which staking rewards
     /// will be credited.
     fn write_validator_staking_reward_address(
@@ -244,7 +250,7 @@ pub trait PosActions: PosReadOnly {
             );
         }
         let BecomeValidatorData {
-            consensus_key,
+            consensus_key: consensus_keys,
             state,
             total_deltas,
             voting_power,
@@ -259,15 +265,44 @@ pub trait PosActions: PosReadOnly {
             address,
             staking_reward_address.clone(),
         );
-        self.write_validator_consensus_key(address, consensus_key);
+        self.write_validator_consensus_key(address, consensus_keys);
         self.write_validator_state(address, state);
         self.write_validator_set(validator_set);
-        self.write_validator_address_raw_hash(address);
+        self.write_validator_address_raw_hash(address, consensus_key);
         self.write_validator_total_deltas(address, total_deltas);
         self.write_validator_voting_power(address, voting_power);
         Ok(())
     }
 
+    /// Change a validator's consensus key. The new key will be used from the
+    /// pipeline epoch and it is given to Tendermint in the validator set
+    /// update at the beginning of that epoch.
+    fn change_consensus_key(
+        &mut self,
+        address: &Self::Address,
+        consensus_key: &Self::PublicKey,
+        current_epoch: impl Into<Epoch>,
+    ) -> Result<(), ChangeConsensusKeyError<Self::Address>> {
+        let current_epoch = current_epoch.into();
+        let params = self.read_pos_params();
+        let mut consensus_keys =
+            self.read_validator_consensus_key(address).ok_or_else(|| {
+                ChangeConsensusKeyError::NotAValidator(address.clone())
+            })?;
+        let pipeline_epoch = current_epoch + params.pipeline_len;
+        if consensus_keys.get(pipeline_epoch) == Some(consensus_key) {
+            return Err(ChangeConsensusKeyError::SameConsensusKey(
+                address.clone(),
+            ));
+        }
+        consensus_keys.set(consensus_key.clone(), current_epoch, &params);
+        self.write_validator_consensus_key(address, consensus_keys);
+        // The raw hash of the previous key is kept, so that the validator can
+        // still be found by it, e.g. from the evidence of its misbehaviour
+        self.write_validator_address_raw_hash(address, consensus_key);
+        Ok(())
+    }
+
     /// Check if the given address is a validator by checking that it has some
     /// state.
     fn is_validator(&self, address: &Self::Address) -> bool {
@@ -524,6 +559,7 @@ pub trait PosBase {
     type PublicKey: 'static
         + Debug
         + Clone
+        + PartialEq
         + BorshDeserialize
         + BorshSerialize
         + BorshSchema;
@@ -539,7 +575,7 @@ pub trait PosBase {
 
     /// Read PoS parameters.
     fn read_pos_params(&self) -> PosParams;
-    /// Read PoS raw hash of validator's address.
+    /// Read PoS validator's address by the raw hash of its consensus key.
     fn read_validator_address_raw_hash(
         &self,
         raw_hash: impl AsRef<str>,
@@ -574,8 +610,13 @@ pub trait PosBase {
 
     /// Write PoS parameters.
     fn write_pos_params(&mut self, params: &PosParams);
-    /// Write PoS validator's raw hash its address.
-    fn write_validator_address_raw_hash(&mut self, address: &Self::Address);
+    /// Write PoS validator's address under the raw hash of its consensus key,
+    /// which is the validator's address in Tendermint.
+    fn write_validator_address_raw_hash(
+        &mut self,
+        address: &Self::Address,
+        consensus_key: &Self::PublicKey,
+    );
     /// Write PoS validator's staking reward address, into which staking rewards
     /// will be credited.
     fn write_validator_staking_reward_address(
@@ -685,7 +726,9 @@ pub trait PosBase {
                 voting_power,
                 bond: (bond_id, bond),
             } = res?;
-            self.write_validator_address_raw_hash(address);
+            if let Some(key) = consensus_key.get(current_epoch) {
+                self.write_validator_address_raw_hash(address, key);
+            }
             self.write_validator_staking_reward_address(
                 address,
                 &staking_reward_address,
@@ -742,8 +785,39 @@ pub trait PosBase {
         // For active validators, this would only ever happen until all the
         // validator slots are filled with non-0 voting power validators, but we
         // still need to guard against it.
-        let active_validators = cur_validators.active.iter().filter_map(
+        let active_validators = cur_validators.active.iter().flat_map(
             |validator: &WeightedValidator<_>| {
+                let consensus_keys = self
+                    .read_validator_consensus_key(&validator.address)
+                    .unwrap();
+                let consensus_key =
+                    consensus_keys.get(current_epoch).unwrap().clone();
+                // If the validator was active in the previous epoch with a
+                // different consensus key, the old key has to be removed from
+                // the validator set.
+                let prev_consensus_key =
+                    match (previous_epoch, prev_validators) {
+                        (Some(prev_epoch), Some(prev_validators))
+                            if prev_validators.active.iter().any(|prev| {
+                                prev.address == validator.address
+                            }) =>
+                        {
+                            consensus_keys
+                                .get(prev_epoch)
+                                .filter(|prev_key| **prev_key != consensus_key)
+                                .cloned()
+                        }
+                        _ => None,
+                    };
+                if let Some(prev_consensus_key) = prev_consensus_key {
+                    return vec![
+                        ValidatorSetUpdate::Deactivated(prev_consensus_key),
+                        ValidatorSetUpdate::Active(ActiveValidator {
+                            consensus_key,
+                            voting_power: validator.voting_power,
+                        }),
+                    ];
+                }
                 // If the validators set from previous epoch contains the same
                 // validator, it means its voting power hasn't changed and hence
                 // doesn't need to updated.
@@ -755,7 +829,7 @@ pub trait PosBase {
                             "skipping validator update, still the same {}",
                             validator.address
                         );
-                        return None;
+                        return vec![];
                     }
                     if validator.voting_power == 0.into() {
                         // If the validator was `Pending` in the previous epoch,
@@ -771,21 +845,15 @@ pub trait PosBase {
                                     "skipping validator update, it's new {}",
                                     validator.address
                                 );
-                                return None;
+                                return vec![];
                             }
                         }
                     }
                 }
-                let consensus_key = self
-                    .read_validator_consensus_key(&validator.address)
-                    .unwrap()
-                    .get(current_epoch)
-                    .unwrap()
-                    .clone();
-                Some(ValidatorSetUpdate::Active(ActiveValidator {
+                vec![ValidatorSetUpdate::Active(ActiveValidator {
                     consensus_key,
                     voting_power: validator.voting_power,
-                }))
+                })]
             },
         );
         let inactive_validators = cur_validators.inactive.iter().filter_map(
@@ -814,10 +882,12 @@ pub trait PosBase {
                         }
                     }
                 }
+                // Deactivate the consensus key that was used in the previous
+                // epoch, in case it has been changed since
                 let consensus_key = self
                     .read_validator_consensus_key(&validator.address)
                     .unwrap()
-                    .get(current_epoch)
+                    .get(previous_epoch.unwrap_or(current_epoch))
                     .unwrap()
                     .clone();
                 Some(ValidatorSetUpdate::Deactivated(consensus_key))
@@ -907,6 +977,18 @@ pub enum BecomeValidatorError<Address: Display + Debug> {
     StakingRewardAddressEqValidatorAddress(Address),
 }
 
+#[allow(missing_docs)]
+#[derive(Error, Debug)]
+pub enum ChangeConsensusKeyError<Address: Display + Debug> {
+    #[error("The given address {0} is not a validator address")]
+    NotAValidator(Address),
+    #[error(
+        "The given consensus key is already the consensus key of the \
+         validator {0}"
+    )]
+    SameConsensusKey(Address),
+}
+
 #[allow(missing_docs)]
 #[derive(Error, Debug)]
 pub enum BondError<Address: Display + Debug> {
@@ -328,6 +328,30 @@ pub struct Slash {
 /// their staked tokens at and before the epoch of the slash.
 pub type Slashes = Vec<Slash>;
 
+/// The maximum length in bytes of each of the validator's metadata strings.
+pub const MAX_VALIDATOR_METADATA_LEN: usize = 256;
+
+/// Validator's metadata displayed to delegators.
+#[derive(
+    Debug, Clone, BorshDeserialize, BorshSerialize, BorshSchema, PartialEq, Eq,
+)]
+pub struct ValidatorMetadata {
+    /// A human-readable name of the validator
+    pub moniker: String,
+    /// The website of the validator
+    pub website: Option<String>,
+    /// Contact information of the validator, e.g. an e-mail address
+    pub contact: Option<String>,
+    /// The portion of the staking rewards that the validator takes from its
+    /// delegators
+    pub commission_rate: BasisPoints,
+    /// The maximum change of the commission rate in a single epoch. This is
+    /// set when the metadata are first registered and cannot be changed.
+    pub max_commission_rate_change: BasisPoints,
+    /// The epoch of the last change of the commission rate
+    pub commission_rate_last_update: Epoch,
+}
+
 /// A type of slashsable event.
 #[derive(Debug, Clone, BorshDeserialize, BorshSerialize, BorshSchema)]
 pub enum SlashType {
@@ -725,10 +749,69 @@ impl Display for SlashType {
 }
 
 impl BasisPoints {
+    /// Basis points of a whole, i.e. 100%.
+    pub const WHOLE: Self = Self(10_000);
+
     /// Initialize basis points from an integer.
     pub fn new(value: u64) -> Self {
         Self(value)
     }
+
+    /// The absolute difference between two values.
+    pub fn abs_diff(self, other: Self) -> Self {
+        if self.0 > other.0 {
+            Self(self.0 - other.0)
+        } else {
+            Self(other.0 - self.0)
+        }
+    }
+}
+
+impl ValidatorMetadata {
+    /// Check that the metadata are well-formed, i.e. that the moniker is not
+    /// empty, no string is longer than [`MAX_VALIDATOR_METADATA_LEN`] and the
+    /// rates are at most 100%.
+    pub fn is_well_formed(&self) -> bool {
+        let mut strings = std::iter::once(&self.moniker)
+            .chain(self.website.iter())
+            .chain(self.contact.iter());
+        !self.moniker.is_empty()
+            && strings.all(|s| s.len() <= MAX_VALIDATOR_METADATA_LEN)
+            && self.commission_rate <= BasisPoints::WHOLE
+            && self.max_commission_rate_change <= BasisPoints::WHOLE
+    }
+
+    /// Check that the metadata are a valid update of the `pre` metadata in
+    /// the current epoch. The maximum commission rate change cannot be
+    /// modified and the commission rate can be changed at most once per epoch
+    /// by at most the maximum commission rate change.
+    pub fn is_valid_update(
+        &self,
+        pre: Option<&Self>,
+        current_epoch: Epoch,
+    ) -> bool {
+        if !self.is_well_formed() {
+            return false;
+        }
+        match pre {
+            None => self.commission_rate_last_update == current_epoch,
+            Some(pre) => {
+                if self.max_commission_rate_change
+                    != pre.max_commission_rate_change
+                {
+                    return false;
+                }
+                if self.commission_rate == pre.commission_rate {
+                    return self.commission_rate_last_update
+                        == pre.commission_rate_last_update;
+                }
+                pre.commission_rate_last_update < current_epoch
+                    && self.commission_rate_last_update == current_epoch
+                    && self.commission_rate.abs_diff(pre.commission_rate)
+                        <= self.max_commission_rate_change
+            }
+        }
+    }
 }
 
 impl Display for BasisPoints {
@@ -768,4 +851,49 @@ pub mod tests {
     pub fn arb_epoch(range: Range<u64>) -> impl Strategy<Value = Epoch> {
         range.prop_map(Epoch)
     }
+
+    fn metadata(commission_rate: u64, last_update: u64) -> ValidatorMetadata {
+        ValidatorMetadata {
+            moniker: "validator".into(),
+            website: None,
+            contact: None,
+            commission_rate: BasisPoints::new(commission_rate),
+            max_commission_rate_change: BasisPoints::new(100),
+            commission_rate_last_update: Epoch(last_update),
+        }
+    }
+
+    #[test]
+    fn test_validator_metadata_update() {
+        let current_epoch = Epoch(5);
+        let pre = metadata(500, 2);
+
+        // New metadata must be well-formed
+        assert!(metadata(500, 5).is_valid_update(None, current_epoch));
+        assert!(!metadata(10_001, 5).is_valid_update(None, current_epoch));
+        let mut unnamed = metadata(500, 5);
+        unnamed.moniker = String::new();
+        assert!(!unnamed.is_valid_update(None, current_epoch));
+
+        // The commission rate may change by at most the max change
+        assert!(metadata(600, 5).is_valid_update(Some(&pre), current_epoch));
+        assert!(metadata(400, 5).is_valid_update(Some(&pre), current_epoch));
+        assert!(!metadata(601, 5).is_valid_update(Some(&pre), current_epoch));
+
+        // The commission rate may change at most once per epoch
+        let updated = metadata(600, 5);
+        assert!(
+            !metadata(650, 5).is_valid_update(Some(&updated), current_epoch)
+        );
+
+        // The max change cannot be modified
+        let mut post = metadata(500, 2);
+        post.max_commission_rate_change = BasisPoints::new(200);
+        assert!(!post.is_valid_update(Some(&pre), current_epoch));
+
+        // Other metadata may change in any epoch
+        let mut post = metadata(600, 5);
+        post.website = Some("https://example.com".into());
+        assert!(post.is_valid_update(Some(&updated), current_epoch));
+    }
 }
@@ -1,7 +1,7 @@
 //! Validation of updated PoS data
 
 use std::borrow::Cow;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::convert::TryFrom;
 use std::fmt::{Debug, Display};
 use std::hash::Hash;
@@ -241,7 +241,7 @@ where
     ValidatorAddressRawHash {
         /// Raw hash value
         raw_hash: String,
-        /// The address and raw hash derived from it
+        /// The address and the raw hash of its consensus key
         data: Data<(Address, String)>,
     },
 }
@@ -409,6 +409,9 @@ where
     > = HashMap::default();
 
     let mut new_validators: HashMap<Address, NewValidator> = HashMap::default();
+    // The validators with a new raw hash of a consensus key, which are either
+    // new validators or validators changing their consensus key
+    let mut raw_hash_validators: HashSet<Address> = HashSet::default();
 
     for change in changes {
         match change {
@@ -1238,9 +1241,7 @@ where
                                 expected_raw_hash,
                             ))
                         }
-                        let validator =
-                            new_validators.entry(address.clone()).or_default();
-                        validator.has_address_raw_hash = true;
+                        raw_hash_validators.insert(address);
                     }
                     (pre, post) if pre != post => {
                         errors.push(Error::InvalidRawHashUpdate)
@@ -1251,6 +1252,12 @@ where
         }
     }
 
+    for address in raw_hash_validators {
+        if let Some(validator) = new_validators.get_mut(&address) {
+            validator.has_address_raw_hash = true;
+        }
+    }
+
     // Check total deltas against bonds
     for (validator, total_delta) in total_deltas.iter() {
         let bond_delta = bond_delta.get(validator).copied().unwrap_or_default();
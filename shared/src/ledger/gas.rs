@@ -15,6 +15,8 @@ pub enum Error {
     BlockGasExceeded,
     #[error("Overflow during gas operations")]
     GasOverflow,
+    #[error("Validity predicate gas budget exceeded")]
+    VpGasBudgetExceeded,
 }
 
 const COMPILE_GAS_PER_BYTE: u64 = 1;
@@ -26,8 +28,25 @@ const PARALLEL_GAS_DIVIDER: u64 = 10;
 const BLOCK_GAS_LIMIT: u64 = 10_000_000_000_000;
 const TRANSACTION_GAS_LIMIT: u64 = 10_000_000_000;
 
+/// The maximum gas that a single validity predicate run may consume. Unlike
+/// the transaction gas limit, this budget is enforced on every VP run
+/// separately, so that a pathological VP cannot stall block production.
+pub const VP_GAS_BUDGET: u64 = 1_000_000_000;
+
 /// The minimum gas cost for accessing the storage
 pub const MIN_STORAGE_GAS: u64 = 1;
+/// The gas cost per byte of a key or a value read from the storage
+pub const STORAGE_READ_GAS_PER_BYTE: u64 = 1;
+/// The gas cost per byte of a key or a value written to the storage. The
+/// written data is hashed into the merkle tree and persisted by every node, so
+/// it's priced higher than the reads.
+pub const STORAGE_WRITE_GAS_PER_BYTE: u64 = 10;
+/// The number of the Merkle tree nodes hashed to update the tree with a
+/// changed storage key: the nodes on the key's path in its sub-tree and on the
+/// sub-tree's path in the base tree, both of which have 256-bit keys.
+pub const MERKLE_TREE_NODES_PER_KEY: u64 = 2 * 256;
+/// The gas cost of hashing a node of the Merkle tree
+pub const MERKLE_TREE_NODE_GAS: u64 = 1;
 
 /// Gas module result for functions that may fail
 pub type Result<T> = std::result::Result<T, Error>;
@@ -47,6 +66,8 @@ pub struct VpGasMeter {
     initial_gas: u64,
     /// The current gas usage in the VP
     pub current_gas: u64,
+    /// The maximum gas the VP run may consume, if any
+    gas_budget: Option<u64>,
 }
 
 /// Gas meter for VPs parallel runs
@@ -86,6 +107,12 @@ impl BlockGasMeter {
         self.add(bytes_len as u64 * COMPILE_GAS_PER_BYTE)
     }
 
+    /// Add the cost of hashing the storage keys changed by the transaction into
+    /// the Merkle tree, see [`merkle_tree_update_gas`].
+    pub fn add_merkle_tree_fee(&mut self, num_keys: usize) -> Result<()> {
+        self.add(merkle_tree_update_gas(num_keys))
+    }
+
     /// Add the transaction gas to the block's total gas. Returns the
     /// transaction's gas cost and resets the transaction meter. It will return
     /// error when the consumed gas exceeds the block gas limit, but the state
@@ -128,6 +155,18 @@ impl VpGasMeter {
         Self {
             initial_gas,
             current_gas: 0,
+            gas_budget: None,
+        }
+    }
+
+    /// Initialize a new VP gas meter with a gas budget that is checked every
+    /// time any gas is consumed. The WASM VPs consume gas throughout their
+    /// execution, so they get interrupted once the budget is exhausted.
+    pub fn new_with_budget(initial_gas: u64, gas_budget: u64) -> Self {
+        Self {
+            initial_gas,
+            current_gas: 0,
+            gas_budget: Some(gas_budget),
         }
     }
 
@@ -150,6 +189,11 @@ impl VpGasMeter {
         if current_total > TRANSACTION_GAS_LIMIT {
             return Err(Error::TransactionGasExceedededError);
         }
+        if let Some(gas_budget) = self.gas_budget {
+            if self.current_gas > gas_budget {
+                return Err(Error::VpGasBudgetExceeded);
+            }
+        }
         Ok(())
     }
 
@@ -213,6 +257,25 @@ impl VpsGas {
     }
 }
 
+/// The gas cost of reading a key and its value of the given lengths from the
+/// storage. The value length is `0` when the key is not present.
+pub fn storage_read_gas(key_len: usize, value_len: usize) -> u64 {
+    MIN_STORAGE_GAS + (key_len + value_len) as u64 * STORAGE_READ_GAS_PER_BYTE
+}
+
+/// The gas cost of writing a key and its value of the given lengths to the
+/// storage. A deletion is a write of the key with an empty value.
+pub fn storage_write_gas(key_len: usize, value_len: usize) -> u64 {
+    MIN_STORAGE_GAS + (key_len + value_len) as u64 * STORAGE_WRITE_GAS_PER_BYTE
+}
+
+/// The gas cost of updating the Merkle tree with the given number of changed
+/// storage keys
+pub fn merkle_tree_update_gas(num_keys: usize) -> u64 {
+    (num_keys as u64)
+        .saturating_mul(MERKLE_TREE_NODES_PER_KEY * MERKLE_TREE_NODE_GAS)
+}
+
 /// Convert the gas from signed to unsigned int. This will panic on overflow,
 /// but it should never occur for our gas limits (see
 /// `tests::gas_limits_cannot_overflow_i64`).
@@ -242,6 +305,14 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_storage_gas_by_size() {
+        assert_eq!(storage_read_gas(0, 0), MIN_STORAGE_GAS);
+        assert!(storage_read_gas(10, 100) > storage_read_gas(10, 10));
+        assert!(storage_write_gas(10, 100) > storage_write_gas(10, 10));
+        assert!(storage_write_gas(10, 10) > storage_read_gas(10, 10));
+    }
+
     #[test]
     fn test_vp_gas_overflow() {
         let mut meter = VpGasMeter::new(1);
@@ -262,6 +333,16 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_vp_gas_budget() {
+        let mut meter = VpGasMeter::new_with_budget(1, 10);
+        meter.add(10).expect("cannot add the gas");
+        assert_matches!(
+            meter.add(1).expect_err("unexpectedly succeeded"),
+            Error::VpGasBudgetExceeded
+        );
+    }
+
     #[test]
     fn test_tx_gas_overflow() {
         let mut meter = BlockGasMeter::default();
@@ -440,11 +440,16 @@ pub fn get_vote_proposal_key(
         .expect("Cannot obtain a storage key")
 }
 
-/// Get the proposal execution key
-pub fn get_proposal_execution_key(id: u64) -> Key {
+/// Get the prefix of the execution keys of the proposals being executed
+pub fn get_proposal_execution_prefix_key() -> Key {
     Key::from(ADDRESS.to_db_key())
         .push(&PENDING_PROPOSAL.to_owned())
         .expect("Cannot obtain a storage key")
+}
+
+/// Get the proposal execution key
+pub fn get_proposal_execution_key(id: u64) -> Key {
+    get_proposal_execution_prefix_key()
         .push(&id.to_string())
         .expect("Cannot obtain a storage key")
 }
@@ -1,4 +1,5 @@
 use std::collections::HashMap;
+use std::fmt::Display;
 use std::str::FromStr;
 
 use anoma_proof_of_stake::types::{Slash, Slashes};
@@ -17,13 +18,116 @@ use crate::types::token;
 
 /// Proposal structure holding votes information necessary to compute the
 /// outcome
+#[derive(Debug, Clone, Default)]
 pub struct Votes {
     /// Map from validators who votes yay to their total stake amount
     pub yay_validators: HashMap<Address, token::Amount>,
-    /// Map from delegation who votes yay to their bond amount
-    pub yay_delegators: HashMap<Address, token::Amount>,
-    /// Map from delegation who votes nay to their bond amount
-    pub nay_delegators: HashMap<Address, token::Amount>,
+    /// Map from validators who votes nay to their total stake amount
+    pub nay_validators: HashMap<Address, token::Amount>,
+    /// Map from delegators who votes yay to their bond amounts by the
+    /// validators they're delegated to
+    pub yay_delegators: HashMap<Address, HashMap<Address, token::Amount>>,
+    /// Map from delegators who votes nay to their bond amounts by the
+    /// validators they're delegated to
+    pub nay_delegators: HashMap<Address, HashMap<Address, token::Amount>>,
+}
+
+/// The outcome of a proposal with the stake that voted for and against it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProposalResult {
+    /// The result of the tally
+    pub result: TallyResult,
+    /// The total stake at the proposal's start epoch
+    pub total_voting_power: token::Amount,
+    /// The stake that voted yay
+    pub total_yay_power: token::Amount,
+    /// The stake that voted nay
+    pub total_nay_power: token::Amount,
+}
+
+impl Display for ProposalResult {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{} with {} yay votes and {} nay votes over {} total voting power",
+            self.result,
+            self.total_yay_power,
+            self.total_nay_power,
+            self.total_voting_power
+        )
+    }
+}
+
+impl Votes {
+    /// Get the vote of a validator, if it voted
+    fn validator_vote(&self, validator: &Address) -> Option<bool> {
+        if self.yay_validators.contains_key(validator) {
+            Some(true)
+        } else if self.nay_validators.contains_key(validator) {
+            Some(false)
+        } else {
+            None
+        }
+    }
+
+    /// Tally the votes by the stake. A validator votes with its whole stake,
+    /// except for the bonds of its delegators who voted differently, which
+    /// are counted for their own votes. The votes of the delegators whose
+    /// validator didn't vote are counted with their bonds. A proposal passes
+    /// if at least 2/3 of the total stake voted yay.
+    pub fn tally(&self, total_voting_power: token::Amount) -> ProposalResult {
+        let mut total_yay_power = token::Amount::default();
+        let mut total_nay_power = token::Amount::default();
+        // The bonds whose delegators overrode their validator's vote
+        let mut overridden: HashMap<&Address, token::Amount> = HashMap::new();
+        for (delegators, is_yay) in
+            [(&self.yay_delegators, true), (&self.nay_delegators, false)]
+        {
+            for (validator, amount) in delegators.values().flatten() {
+                match self.validator_vote(validator) {
+                    // Already counted in the validator's stake
+                    Some(validator_vote) if validator_vote == is_yay => {
+                        continue;
+                    }
+                    Some(_) => {
+                        *overridden.entry(validator).or_default() += *amount;
+                    }
+                    None => {}
+                }
+                if is_yay {
+                    total_yay_power += *amount;
+                } else {
+                    total_nay_power += *amount;
+                }
+            }
+        }
+        for (validators, is_yay) in
+            [(&self.yay_validators, true), (&self.nay_validators, false)]
+        {
+            for (validator, stake) in validators {
+                let overridden =
+                    overridden.get(validator).copied().unwrap_or_default();
+                let power = *stake - std::cmp::min(*stake, overridden);
+                if is_yay {
+                    total_yay_power += power;
+                } else {
+                    total_nay_power += power;
+                }
+            }
+        }
+
+        let result = if 3 * total_yay_power >= 2 * total_voting_power {
+            TallyResult::Passed
+        } else {
+            TallyResult::Rejected
+        };
+        ProposalResult {
+            result,
+            total_voting_power,
+            total_yay_power,
+            total_nay_power,
+        }
+    }
 }
 
 /// Proposal errors
@@ -73,12 +177,13 @@ impl ProposalEvent {
     }
 }
 
-/// Return a proposal result and his associated proposal code (if any)
+/// Tally the votes of a proposal by the stake at the given epoch, which is
+/// the proposal's start epoch
 pub fn compute_tally<D, H>(
     storage: &Storage<D, H>,
     epoch: Epoch,
     votes: Votes,
-) -> TallyResult
+) -> ProposalResult
 where
     D: DB + for<'iter> DBIter<'iter> + Sync + 'static,
     H: StorageHasher + Sync + 'static,
@@ -86,37 +191,7 @@ where
     let validators = get_all_validators(storage, epoch);
     let total_stacked_tokens =
         get_total_stacked_tokens(storage, epoch, &validators);
-
-    let Votes {
-        yay_validators,
-        yay_delegators,
-        nay_delegators,
-    } = votes;
-
-    let mut total_yay_stacked_tokens = token::Amount::from(0);
-    for (_, amount) in yay_validators.clone().into_iter() {
-        total_yay_stacked_tokens += amount;
-    }
-
-    // YAY: Add delegator amount whose validator didn't vote / voted nay
-    for (validator_address, amount) in yay_delegators.into_iter() {
-        if !yay_validators.contains_key(&validator_address) {
-            total_yay_stacked_tokens += amount;
-        }
-    }
-
-    // NAY: Remove delegator amount whose validator validator vote yay
-    for (validator_address, amount) in nay_delegators.into_iter() {
-        if yay_validators.contains_key(&validator_address) {
-            total_yay_stacked_tokens -= amount;
-        }
-    }
-
-    if 3 * total_yay_stacked_tokens >= 2 * total_stacked_tokens {
-        TallyResult::Passed
-    } else {
-        TallyResult::Rejected
-    }
+    votes.tally(total_stacked_tokens)
 }
 
 // Get bond token amount
@@ -205,63 +280,43 @@ where
         gov_storage::get_proposal_vote_prefix_key(proposal_id);
     let (vote_iter, _) = storage.iter_prefix(&vote_prefix_key);
 
-    let mut yay_validators: HashMap<Address, token::Amount> = HashMap::new();
-    let mut yay_delegators: HashMap<Address, token::Amount> = HashMap::new();
-    let mut nay_delegators: HashMap<Address, token::Amount> = HashMap::new();
-
+    let mut votes = Votes::default();
     for (key, vote_bytes, _) in vote_iter {
         let vote_key = Key::from_str(key.as_str()).ok();
         let vote = ProposalVote::try_from_slice(&vote_bytes[..]).ok();
-        match (vote_key, vote) {
-            (Some(key), Some(vote)) => {
-                let voter_address = gov_storage::get_voter_address(&key);
-                match voter_address {
-                    Some(address) => {
-                        if vote.is_yay() && validators.contains(address) {
-                            let amount =
-                                get_validator_stake(storage, epoch, address);
-                            yay_validators.insert(address.clone(), amount);
-                        } else if !validators.contains(address) {
-                            let validator_address =
-                                gov_storage::get_vote_delegation_address(&key);
-                            match validator_address {
-                                Some(validator_address) => {
-                                    let amount = get_bond_amount_at(
-                                        storage,
-                                        address,
-                                        validator_address,
-                                        epoch,
-                                    );
-                                    if let Some(amount) = amount {
-                                        if vote.is_yay() {
-                                            yay_delegators.insert(
-                                                address.clone(),
-                                                amount,
-                                            );
-                                        } else {
-                                            nay_delegators.insert(
-                                                address.clone(),
-                                                amount,
-                                            );
-                                        }
-                                    }
-                                }
-                                None => continue,
-                            }
-                        }
-                    }
-                    None => continue,
-                }
-            }
+        let (key, vote) = match (vote_key, vote) {
+            (Some(key), Some(vote)) => (key, vote),
             _ => continue,
+        };
+        let voter = match gov_storage::get_voter_address(&key) {
+            Some(voter) => voter,
+            None => continue,
+        };
+        if validators.contains(voter) {
+            let amount = get_validator_stake(storage, epoch, voter);
+            if vote.is_yay() {
+                votes.yay_validators.insert(voter.clone(), amount);
+            } else {
+                votes.nay_validators.insert(voter.clone(), amount);
+            }
+        } else if let Some(validator) =
+            gov_storage::get_vote_delegation_address(&key)
+        {
+            let amount = get_bond_amount_at(storage, voter, validator, epoch);
+            if let Some(amount) = amount {
+                let delegators = if vote.is_yay() {
+                    &mut votes.yay_delegators
+                } else {
+                    &mut votes.nay_delegators
+                };
+                delegators
+                    .entry(voter.clone())
+                    .or_default()
+                    .insert(validator.clone(), amount);
+            }
         }
     }
-
-    Votes {
-        yay_validators,
-        yay_delegators,
-        nay_delegators,
-    }
+    votes
 }
 
 fn get_all_validators<D, H>(
@@ -337,3 +392,99 @@ where
     }
     token::Amount::from(0)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::address::testing::{
+        established_address_1, established_address_2, established_address_3,
+        established_address_4,
+    };
+
+    fn delegation(
+        validator: &Address,
+        amount: u64,
+    ) -> HashMap<Address, token::Amount> {
+        HashMap::from([(validator.clone(), token::Amount::from(amount))])
+    }
+
+    /// Test that the delegators' votes override their validators' votes with
+    /// their bonds
+    #[test]
+    fn test_tally_delegator_override() {
+        let validator_1 = established_address_1();
+        let validator_2 = established_address_2();
+        let delegator_1 = established_address_3();
+        let delegator_2 = established_address_4();
+        let total = token::Amount::from(300);
+
+        // Only the validators vote
+        let mut votes = Votes {
+            yay_validators: HashMap::from([(
+                validator_1.clone(),
+                token::Amount::from(200),
+            )]),
+            nay_validators: HashMap::from([(
+                validator_2.clone(),
+                token::Amount::from(100),
+            )]),
+            ..Votes::default()
+        };
+        let result = votes.tally(total);
+        assert_eq!(result.result, TallyResult::Passed);
+        assert_eq!(result.total_yay_power, token::Amount::from(200));
+        assert_eq!(result.total_nay_power, token::Amount::from(100));
+
+        // A delegator of the yay validator votes nay with its bond
+        votes
+            .nay_delegators
+            .insert(delegator_1.clone(), delegation(&validator_1, 50));
+        let result = votes.tally(total);
+        assert_eq!(result.result, TallyResult::Rejected);
+        assert_eq!(result.total_yay_power, token::Amount::from(150));
+        assert_eq!(result.total_nay_power, token::Amount::from(150));
+
+        // A delegator that votes as its validator isn't counted twice
+        votes
+            .nay_delegators
+            .insert(delegator_2.clone(), delegation(&validator_2, 20));
+        let result = votes.tally(total);
+        assert_eq!(result.total_yay_power, token::Amount::from(150));
+        assert_eq!(result.total_nay_power, token::Amount::from(150));
+
+        // The delegator of the nay validator votes yay with its bond
+        votes.nay_delegators.remove(&delegator_2);
+        votes.yay_delegators.insert(delegator_2, delegation(&validator_2, 50));
+        let result = votes.tally(total);
+        assert_eq!(result.result, TallyResult::Passed);
+        assert_eq!(result.total_yay_power, token::Amount::from(200));
+        assert_eq!(result.total_nay_power, token::Amount::from(100));
+    }
+
+    /// Test that the delegators of a validator that didn't vote vote with
+    /// their bonds
+    #[test]
+    fn test_tally_without_validator_vote() {
+        let validator = established_address_1();
+        let delegator_1 = established_address_2();
+        let delegator_2 = established_address_3();
+        let votes = Votes {
+            yay_delegators: HashMap::from([(
+                delegator_1,
+                delegation(&validator, 70),
+            )]),
+            nay_delegators: HashMap::from([(
+                delegator_2,
+                delegation(&validator, 30),
+            )]),
+            ..Votes::default()
+        };
+        let result = votes.tally(token::Amount::from(100));
+        assert_eq!(result.result, TallyResult::Passed);
+        assert_eq!(result.total_yay_power, token::Amount::from(70));
+        assert_eq!(result.total_nay_power, token::Amount::from(30));
+
+        let result = votes.tally(token::Amount::from(200));
+        assert_eq!(result.result, TallyResult::Rejected);
+    }
+}
@@ -10,7 +10,7 @@ use crate::ledger::storage::{self as ledger_storage, StorageHasher};
 use crate::types::address::{xan as m1t, Address, InternalAddress};
 use crate::types::storage::{Epoch, Key};
 use crate::types::token;
-use crate::vm::WasmCacheAccess;
+use crate::vm::{validate_untrusted_wasm, WasmCacheAccess};
 
 /// Internal governance address
 pub const ADDRESS: Address = Address::Internal(InternalAddress::Governance);
@@ -334,7 +334,8 @@ where
     }
 }
 
-/// Validate a proposal_code key
+/// Validate a proposal_code key. The code must be a new valid WASM tx, no
+/// larger than the `max_proposal_code_size` parameter.
 pub fn validate_proposal_code_key<'a, DB, H, CA>(
     ctx: &Ctx<'a, DB, H, CA>,
     proposal_id: u64,
@@ -344,19 +345,19 @@ where
     H: 'static + StorageHasher,
     CA: 'static + WasmCacheAccess,
 {
-    let content_key: Key = gov_storage::get_content_key(proposal_id);
-    let max_content_length_parameter_key =
-        gov_storage::get_max_proposal_content_key();
-    let max_content_length =
-        read(ctx, &max_content_length_parameter_key, ReadType::PRE).ok();
-    let has_pre_content = ctx.has_key_pre(&content_key).ok();
-    let post_content = ctx.read_post(&content_key).unwrap();
-    match (has_pre_content, post_content, max_content_length) {
-        (
-            Some(has_pre_content),
-            Some(post_content),
-            Some(max_content_length),
-        ) => !has_pre_content && post_content.len() < max_content_length,
+    let code_key: Key = gov_storage::get_proposal_code_key(proposal_id);
+    let max_code_size_parameter_key =
+        gov_storage::get_max_proposal_code_size_key();
+    let max_code_size: Option<u64> =
+        read(ctx, &max_code_size_parameter_key, ReadType::PRE).ok();
+    let has_pre_code = ctx.has_key_pre(&code_key).ok();
+    let post_code = ctx.read_post(&code_key).ok().flatten();
+    match (has_pre_code, post_code, max_code_size) {
+        (Some(has_pre_code), Some(post_code), Some(max_code_size)) => {
+            !has_pre_code
+                && post_code.len() as u64 <= max_code_size
+                && validate_untrusted_wasm(&post_code).is_ok()
+        }
         _ => false,
     }
 }
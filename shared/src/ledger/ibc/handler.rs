@@ -44,8 +44,8 @@ use crate::ibc::core::ics04_channel::events::{
     AcknowledgePacket, Attributes as ChannelAttributes,
     CloseConfirm as ChanCloseConfirm, CloseInit as ChanCloseInit,
     OpenAck as ChanOpenAck, OpenConfirm as ChanOpenConfirm,
-    OpenInit as ChanOpenInit, OpenTry as ChanOpenTry, SendPacket,
-    TimeoutPacket, WriteAcknowledgement,
+    OpenInit as ChanOpenInit, OpenTry as ChanOpenTry, ReceivePacket,
+    SendPacket, TimeoutPacket, WriteAcknowledgement,
 };
 use crate::ibc::core::ics04_channel::msgs::acknowledgement::MsgAcknowledgement;
 use crate::ibc::core::ics04_channel::msgs::chan_close_confirm::MsgChannelCloseConfirm;
@@ -635,6 +635,10 @@ pub trait IbcActions {
         let seq_key = storage::next_sequence_recv_key(&port_channel_id);
         self.get_and_inc_sequence(&seq_key)?;
 
+        let event = make_recv_packet_event(msg.packet.clone())
+            .try_into()
+            .unwrap();
+        self.emit_ibc_event(event);
         let event = make_write_ack_event(msg.packet.clone(), ack)
             .try_into()
             .unwrap();
@@ -738,6 +742,9 @@ pub trait IbcActions {
             );
         }
 
+        let event = make_timeout_event(msg.packet.clone()).try_into().unwrap();
+        self.emit_ibc_event(event);
+
         Ok(())
     }
 
@@ -1338,6 +1345,15 @@ pub fn make_send_packet_event(packet: Packet) -> IbcEvent {
     })
 }
 
+/// Makes ReceivePacket event
+pub fn make_recv_packet_event(packet: Packet) -> IbcEvent {
+    IbcEvent::ReceivePacket(ReceivePacket {
+        // this height is not used
+        height: Height::default(),
+        packet,
+    })
+}
+
 /// Makes WriteAcknowledgement event
 pub fn make_write_ack_event(packet: Packet, ack: Vec<u8>) -> IbcEvent {
     IbcEvent::WriteAcknowledgement(WriteAcknowledgement {
@@ -104,7 +104,7 @@ pub fn is_capability_index_key(key: &Key) -> bool {
 }
 
 /// Returns a key of the IBC-related data
-fn ibc_key(path: impl AsRef<str>) -> Result<Key> {
+pub fn ibc_key(path: impl AsRef<str>) -> Result<Key> {
     let path = Key::parse(path).map_err(Error::StorageKey)?;
     let addr = Address::Internal(InternalAddress::Ibc);
     let key = Key::from(addr.to_db_key());
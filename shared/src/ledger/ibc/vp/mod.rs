@@ -226,22 +226,19 @@ where
     }
 
     fn check_emitted_event(&self, expected_event: IbcEvent) -> Result<()> {
-        match self.ctx.write_log.get_ibc_event() {
-            Some(event) => {
-                let expected = WrappedIbcEvent::try_from(expected_event)
-                    .map_err(|e| Error::IbcEvent(e.to_string()))?;
-                if *event == expected {
-                    Ok(())
-                } else {
-                    Err(Error::IbcEvent(format!(
-                        "The IBC event is invalid: Event {}",
-                        event
-                    )))
-                }
-            }
-            None => {
-                Err(Error::IbcEvent("No event has been emitted".to_owned()))
-            }
+        let events = self.ctx.write_log.get_ibc_events();
+        if events.is_empty() {
+            return Err(Error::IbcEvent("No event has been emitted".to_owned()));
+        }
+        let expected = WrappedIbcEvent::try_from(expected_event)
+            .map_err(|e| Error::IbcEvent(e.to_string()))?;
+        if events.contains(&expected) {
+            Ok(())
+        } else {
+            Err(Error::IbcEvent(format!(
+                "The expected IBC event hasn't been emitted: Event {}",
+                expected
+            )))
         }
     }
 }
@@ -575,7 +572,7 @@ mod tests {
             signer: Signer::new("account0"),
         };
         let event = make_create_client_event(&get_client_id(), &msg);
-        write_log.set_ibc_event(event.try_into().unwrap());
+        write_log.emit_ibc_event(event.try_into().unwrap());
 
         let tx_code = vec![];
         let mut tx_data = vec![];
@@ -663,7 +660,7 @@ mod tests {
             .write(&consensus_key, bytes)
             .expect("write failed");
         let event = make_update_client_event(&client_id, &msg);
-        write_log.set_ibc_event(event.try_into().unwrap());
+        write_log.emit_ibc_event(event.try_into().unwrap());
         // update time and height for this updating
         let key = client_update_timestamp_key(&client_id);
         write_log
@@ -724,7 +721,7 @@ mod tests {
         let bytes = conn.encode_vec().expect("encoding failed");
         write_log.write(&conn_key, bytes).expect("write failed");
         let event = make_open_init_connection_event(&conn_id, &msg);
-        write_log.set_ibc_event(event.try_into().unwrap());
+        write_log.emit_ibc_event(event.try_into().unwrap());
 
         let tx_code = vec![];
         let mut tx_data = vec![];
@@ -842,7 +839,7 @@ mod tests {
         let bytes = conn.encode_vec().expect("encoding failed");
         write_log.write(&conn_key, bytes).expect("write failed");
         let event = make_open_try_connection_event(&conn_id, &msg);
-        write_log.set_ibc_event(event.try_into().unwrap());
+        write_log.emit_ibc_event(event.try_into().unwrap());
 
         let tx_code = vec![];
         let mut tx_data = vec![];
@@ -921,7 +918,7 @@ mod tests {
             signer: Signer::new("account0"),
         };
         let event = make_open_ack_connection_event(&msg);
-        write_log.set_ibc_event(event.try_into().unwrap());
+        write_log.emit_ibc_event(event.try_into().unwrap());
 
         let mut tx_data = vec![];
         msg.to_any().encode(&mut tx_data).expect("encoding failed");
@@ -986,7 +983,7 @@ mod tests {
             signer: Signer::new("account0"),
         };
         let event = make_open_confirm_connection_event(&msg);
-        write_log.set_ibc_event(event.try_into().unwrap());
+        write_log.emit_ibc_event(event.try_into().unwrap());
 
         let mut tx_data = vec![];
         msg.to_any().encode(&mut tx_data).expect("encoding failed");
@@ -1036,7 +1033,7 @@ mod tests {
         let bytes = channel.encode_vec().expect("encoding failed");
         write_log.write(&channel_key, bytes).expect("write failed");
         let event = make_open_init_channel_event(&get_channel_id(), &msg);
-        write_log.set_ibc_event(event.try_into().unwrap());
+        write_log.emit_ibc_event(event.try_into().unwrap());
 
         let tx_code = vec![];
         let mut tx_data = vec![];
@@ -1106,7 +1103,7 @@ mod tests {
         let bytes = channel.encode_vec().expect("encoding failed");
         write_log.write(&channel_key, bytes).expect("write failed");
         let event = make_open_try_channel_event(&get_channel_id(), &msg);
-        write_log.set_ibc_event(event.try_into().unwrap());
+        write_log.emit_ibc_event(event.try_into().unwrap());
 
         let tx_code = vec![];
         let mut tx_data = vec![];
@@ -1184,7 +1181,7 @@ mod tests {
         let bytes = channel.encode_vec().expect("encoding failed");
         write_log.write(&channel_key, bytes).expect("write failed");
         let event = make_open_ack_channel_event(&msg);
-        write_log.set_ibc_event(event.try_into().unwrap());
+        write_log.emit_ibc_event(event.try_into().unwrap());
 
         let tx_code = vec![];
         let mut tx_data = vec![];
@@ -1257,7 +1254,7 @@ mod tests {
         let bytes = channel.encode_vec().expect("encoding failed");
         write_log.write(&channel_key, bytes).expect("write failed");
         let event = make_open_confirm_channel_event(&msg);
-        write_log.set_ibc_event(event.try_into().unwrap());
+        write_log.emit_ibc_event(event.try_into().unwrap());
 
         let tx_code = vec![];
         let mut tx_data = vec![];
@@ -1640,7 +1637,7 @@ mod tests {
             .write(&commitment_key, commitment_bytes)
             .expect("write failed");
         let event = make_send_packet_event(packet);
-        write_log.set_ibc_event(event.try_into().unwrap());
+        write_log.emit_ibc_event(event.try_into().unwrap());
 
         let tx_code = vec![];
         let mut tx_data = vec![];
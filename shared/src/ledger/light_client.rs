@@ -0,0 +1,154 @@
+//! Light-client helpers to verify the storage values served by an untrusted
+//! RPC node. A value read at a block height is committed to by the merkle root
+//! of the storage after that block, which is the app hash in the header of the
+//! next block. Given a trusted header, e.g. one verified by a Tendermint light
+//! client, a value and its proof can be checked without trusting the node that
+//! served them.
+
+use borsh::BorshDeserialize;
+use thiserror::Error;
+
+use crate::ledger::storage::{
+    verify_existence_proof, verify_non_existence_proof, MerkleRoot,
+    MerkleTreeError, StorageHasher,
+};
+use crate::tendermint::block::Header;
+use crate::tendermint::merkle::proof::Proof;
+use crate::types::address::Address;
+use crate::types::storage::{BlockHeight, Key};
+use crate::types::token;
+
+#[allow(missing_docs)]
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error(
+        "The header at the height {header_height} doesn't commit to the \
+         values at the height {height}"
+    )]
+    HeightMismatch {
+        header_height: u64,
+        height: BlockHeight,
+    },
+    #[error("Invalid proof: {0}")]
+    InvalidProof(MerkleTreeError),
+    #[error("Decoding the value failed: {0}")]
+    Decoding(std::io::Error),
+}
+
+/// Result of the verification functions
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// Get the merkle root that the app hash of the header commits to
+pub fn app_hash(header: &Header) -> MerkleRoot {
+    MerkleRoot(header.app_hash.value())
+}
+
+/// Verify a proof that the given key has the given value, or no value at all
+/// if it's `None`, in the storage with the given merkle root
+pub fn verify_value<H: StorageHasher>(
+    root: &MerkleRoot,
+    key: &Key,
+    value: Option<&[u8]>,
+    proof: &Proof,
+) -> Result<()> {
+    match value {
+        Some(value) => verify_existence_proof::<H>(proof, root, key, value),
+        None => verify_non_existence_proof::<H>(proof, root, key),
+    }
+    .map_err(Error::InvalidProof)
+}
+
+/// Verify a proof of the value of the given key read at the given height
+/// against the trusted header of the next block
+pub fn verify_value_with_header<H: StorageHasher>(
+    header: &Header,
+    height: BlockHeight,
+    key: &Key,
+    value: Option<&[u8]>,
+    proof: &Proof,
+) -> Result<()> {
+    let header_height = header.height.value();
+    if header_height != height.0 + 1 {
+        return Err(Error::HeightMismatch {
+            header_height,
+            height,
+        });
+    }
+    verify_value::<H>(&app_hash(header), key, value, proof)
+}
+
+/// Verify a proof of the value of the given key read at the given height
+/// against the trusted header of the next block and decode the value
+pub fn verify_decoded_value<H: StorageHasher, T: BorshDeserialize>(
+    header: &Header,
+    height: BlockHeight,
+    key: &Key,
+    value: Option<&[u8]>,
+    proof: &Proof,
+) -> Result<Option<T>> {
+    verify_value_with_header::<H>(header, height, key, value, proof)?;
+    value
+        .map(|value| T::try_from_slice(value).map_err(Error::Decoding))
+        .transpose()
+}
+
+/// Verify a proof of the balance of the owner of the token read at the given
+/// height against the trusted header of the next block. A proven missing
+/// balance is a zero balance.
+pub fn verify_balance<H: StorageHasher>(
+    header: &Header,
+    height: BlockHeight,
+    token: &Address,
+    owner: &Address,
+    value: Option<&[u8]>,
+    proof: &Proof,
+) -> Result<token::Amount> {
+    let key = token::balance_key(token, owner);
+    let balance = verify_decoded_value::<H, token::Amount>(
+        header, height, &key, value, proof,
+    )?;
+    Ok(balance.unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests {
+    use borsh::BorshSerialize;
+
+    use super::*;
+    use crate::ledger::storage::{MerkleTree, Sha256Hasher};
+    use crate::types::address::testing::{
+        established_address_1, established_address_2,
+    };
+
+    /// Test that a balance and a missing balance are verified against the
+    /// root
+    #[test]
+    fn test_verify_value() {
+        let mut tree = MerkleTree::<Sha256Hasher>::default();
+        let token = established_address_1();
+        let owner = established_address_2();
+        let key = token::balance_key(&token, &owner);
+        let value = token::Amount::from(100).try_to_vec().unwrap();
+        tree.update(&key, &value).unwrap();
+        let root = tree.root();
+
+        let proof = tree.get_existence_proof(&key, value.clone()).unwrap();
+        verify_value::<Sha256Hasher>(&root, &key, Some(&value[..]), &proof)
+            .unwrap();
+        assert!(
+            verify_value::<Sha256Hasher>(&root, &key, Some(&[0][..]), &proof)
+                .is_err()
+        );
+        assert!(
+            verify_value::<Sha256Hasher>(&root, &key, None, &proof).is_err()
+        );
+
+        let missing_key = token::balance_key(&token, &token);
+        let proof = tree.get_non_existence_proof(&missing_key).unwrap();
+        verify_value::<Sha256Hasher>(&root, &missing_key, None, &proof)
+            .unwrap();
+        assert!(
+            verify_value::<Sha256Hasher>(&root, &key, None, &proof).is_err()
+        );
+    }
+}
@@ -4,9 +4,12 @@ pub mod eth_bridge;
 pub mod gas;
 pub mod governance;
 pub mod ibc;
+pub mod light_client;
 pub mod native_vp;
 pub mod parameters;
 pub mod pos;
+pub mod reserved_keys;
 pub mod storage;
+pub mod token;
 pub mod treasury;
 pub mod vp_env;
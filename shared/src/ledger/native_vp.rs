@@ -11,6 +11,7 @@ use crate::ledger::storage::{Storage, StorageHasher};
 use crate::ledger::{storage, vp_env};
 use crate::proto::Tx;
 use crate::types::address::{Address, InternalAddress};
+use crate::types::hash::Hash;
 use crate::types::storage::{BlockHash, BlockHeight, Epoch, Key};
 use crate::vm::prefix_iter::PrefixIterators;
 use crate::vm::WasmCacheAccess;
@@ -120,7 +121,7 @@ where
 
     /// Storage read posterior state (after tx execution). It will try to read
     /// from the write log first and if no entry found then from the
-    /// storage.
+    /// storage. The temporary values written by the tx are visible too.
     pub fn read_post(&self, key: &Key) -> Result<Option<Vec<u8>>> {
         vp_env::read_post(
             &mut *self.gas_meter.borrow_mut(),
@@ -195,6 +196,18 @@ where
             .map_err(Error::ContextError)
     }
 
+    /// Get the hash of the code of the transaction that is being applied.
+    pub fn get_tx_code_hash(&self) -> Result<Hash> {
+        vp_env::get_tx_code_hash(&mut *self.gas_meter.borrow_mut(), self.tx)
+            .map_err(Error::ContextError)
+    }
+
+    /// Get the canonical hash of the transaction that is being applied.
+    pub fn get_tx_hash(&self) -> Result<Hash> {
+        vp_env::get_tx_hash(&mut *self.gas_meter.borrow_mut(), self.tx)
+            .map_err(Error::ContextError)
+    }
+
     /// Storage prefix iterator. It will try to get an iterator from the
     /// storage.
     pub fn iter_prefix(
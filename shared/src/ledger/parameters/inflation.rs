@@ -0,0 +1,235 @@
+//! The inflation controller. At the beginning of every epoch, the annual
+//! inflation rate of the native token is adjusted towards the
+//! [`InflationParams::target_staked_ratio`]: the rate grows while less than
+//! the target ratio of the supply is staked and it shrinks while more of it is
+//! staked, within the minimum and the maximum rates. The resulting
+//! [`Inflation`] is written into the parameters storage and the inflation is
+//! minted over the epoch as the rewards of the block proposers.
+
+use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
+
+use super::EpochDuration;
+use crate::types::storage::Epoch;
+use crate::types::time::DurationSecs;
+use crate::types::token;
+
+/// The number of basis points in a whole
+pub const BASIS_POINTS: u64 = 10_000;
+
+/// The number of seconds in a year, used to split the annual inflation into
+/// epochs
+const SECONDS_PER_YEAR: u64 = 365 * 24 * 60 * 60;
+
+/// The parameters of the inflation controller. The ratio and the rates are in
+/// basis points.
+#[derive(
+    Clone,
+    Debug,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshSchema,
+)]
+pub struct InflationParams {
+    /// The ratio of the native token's supply that should be staked
+    pub target_staked_ratio: u64,
+    /// The minimum annual inflation rate
+    pub min_rate: u64,
+    /// The maximum annual inflation rate
+    pub max_rate: u64,
+    /// The maximum change of the annual inflation rate in one epoch
+    pub max_rate_change: u64,
+}
+
+impl Default for InflationParams {
+    fn default() -> Self {
+        Self {
+            target_staked_ratio: 6_667,
+            min_rate: 700,
+            max_rate: 2_000,
+            max_rate_change: 10,
+        }
+    }
+}
+
+/// The inflation of an epoch, as computed by the controller at its beginning
+#[derive(
+    Clone,
+    Debug,
+    PartialEq,
+    Eq,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshSchema,
+)]
+pub struct Inflation {
+    /// The epoch that the inflation was computed for
+    pub epoch: Epoch,
+    /// The total supply of the native token at the beginning of the epoch
+    pub total_supply: token::Amount,
+    /// The total amount of the native token staked at the beginning of the
+    /// epoch
+    pub total_staked: token::Amount,
+    /// The ratio of the supply that is staked, in basis points
+    pub staked_ratio: u64,
+    /// The annual inflation rate, in basis points
+    pub rate: u64,
+    /// The reward minted for the proposer of every block in the epoch
+    pub block_reward: token::Amount,
+}
+
+impl InflationParams {
+    /// Compute the inflation of an epoch from the total supply and stake at
+    /// its beginning. The rate of the previous epoch is adjusted by at most
+    /// the `max_rate_change`, proportionally to the distance of the staked
+    /// ratio from the target. Without a previous rate, the controller starts
+    /// from the minimum rate.
+    pub fn compute(
+        &self,
+        epoch: Epoch,
+        last_rate: Option<u64>,
+        total_supply: token::Amount,
+        total_staked: token::Amount,
+        epoch_duration: &EpochDuration,
+        max_expected_time_per_block: &DurationSecs,
+    ) -> Inflation {
+        let staked_ratio = staked_ratio(total_staked, total_supply);
+        let rate =
+            self.next_rate(last_rate.unwrap_or(self.min_rate), staked_ratio);
+        let block_reward = block_reward(
+            total_supply,
+            rate,
+            epoch_duration,
+            max_expected_time_per_block,
+        );
+        Inflation {
+            epoch,
+            total_supply,
+            total_staked,
+            staked_ratio,
+            rate,
+            block_reward,
+        }
+    }
+
+    /// Adjust the rate towards the target staked ratio
+    pub fn next_rate(&self, last_rate: u64, staked_ratio: u64) -> u64 {
+        let target = i128::from(self.target_staked_ratio.max(1));
+        let max_change = i128::from(self.max_rate_change);
+        // Positive while less than the target is staked
+        let gap = target - i128::from(staked_ratio);
+        let change = (max_change * gap / target)
+            .max(-max_change)
+            .min(max_change);
+        let rate = (i128::from(last_rate) + change)
+            .max(i128::from(self.min_rate))
+            .min(i128::from(self.max_rate))
+            .max(0);
+        rate as u64
+    }
+}
+
+/// Get the ratio of the staked amount to the total supply in basis points
+pub fn staked_ratio(
+    total_staked: token::Amount,
+    total_supply: token::Amount,
+) -> u64 {
+    let supply = u128::from(u64::from(total_supply));
+    if supply == 0 {
+        return 0;
+    }
+    let ratio = u128::from(u64::from(total_staked))
+        * u128::from(BASIS_POINTS)
+        / supply;
+    ratio.min(u128::from(BASIS_POINTS)) as u64
+}
+
+/// Get the reward of a block proposer that mints the epoch's share of the
+/// annual inflation over the expected number of blocks in an epoch. An epoch
+/// is expected to last its minimum duration, with no fewer blocks than its
+/// minimum number of blocks, or than the blocks produced in the maximum
+/// expected time per block.
+pub fn block_reward(
+    total_supply: token::Amount,
+    rate: u64,
+    epoch_duration: &EpochDuration,
+    max_expected_time_per_block: &DurationSecs,
+) -> token::Amount {
+    let epoch_secs = epoch_duration.min_duration.0.max(1);
+    let epochs_per_year = (SECONDS_PER_YEAR / epoch_secs).max(1);
+    let blocks_per_epoch = epoch_duration
+        .min_num_of_blocks
+        .max(epoch_secs / max_expected_time_per_block.0.max(1))
+        .max(1);
+    let reward = u128::from(u64::from(total_supply)) * u128::from(rate)
+        / u128::from(BASIS_POINTS)
+        / u128::from(epochs_per_year)
+        / u128::from(blocks_per_epoch);
+    token::Amount::from(reward as u64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test that the rate moves towards the target staked ratio within the
+    /// bounds
+    #[test]
+    fn test_next_rate() {
+        let params = InflationParams {
+            target_staked_ratio: 5_000,
+            min_rate: 500,
+            max_rate: 2_000,
+            max_rate_change: 100,
+        };
+        // nothing is staked, the rate grows by the max. change
+        assert_eq!(params.next_rate(1_000, 0), 1_100);
+        // half of the target is staked
+        assert_eq!(params.next_rate(1_000, 2_500), 1_050);
+        // on target
+        assert_eq!(params.next_rate(1_000, 5_000), 1_000);
+        // everything is staked, the rate shrinks by no more than the max.
+        // change
+        assert_eq!(params.next_rate(1_000, 10_000), 900);
+        // the bounds are respected
+        assert_eq!(params.next_rate(1_950, 0), 2_000);
+        assert_eq!(params.next_rate(550, 10_000), 500);
+    }
+
+    /// Test that the inflation of an epoch is split into the block rewards
+    #[test]
+    fn test_compute() {
+        let params = InflationParams {
+            target_staked_ratio: 5_000,
+            min_rate: 1_000,
+            max_rate: 2_000,
+            max_rate_change: 100,
+        };
+        // A year of 365 daily epochs of 1 000 blocks
+        let epoch_duration = EpochDuration {
+            min_num_of_blocks: 1_000,
+            min_duration: DurationSecs(24 * 60 * 60),
+        };
+        let inflation = params.compute(
+            Epoch(1),
+            None,
+            token::Amount::from(365_000_000_000),
+            token::Amount::from(182_500_000_000),
+            &epoch_duration,
+            &DurationSecs(100),
+        );
+        assert_eq!(inflation.staked_ratio, 5_000);
+        assert_eq!(inflation.rate, 1_000);
+        // 10% of the supply over 365 000 blocks
+        assert_eq!(inflation.block_reward, token::Amount::from(100_000));
+
+        assert_eq!(
+            staked_ratio(token::Amount::from(1), token::Amount::default()),
+            0
+        );
+    }
+}
@@ -1,20 +1,24 @@
 //! Protocol parameters
+pub mod inflation;
 pub mod storage;
 
-use std::collections::BTreeSet;
+use std::collections::{BTreeMap, BTreeSet};
 
 use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
 use thiserror::Error;
 
+use self::inflation::{Inflation, InflationParams};
 use self::storage as parameter_storage;
 use super::governance::vp::is_proposal_accepted;
 use super::storage::types::{decode, encode};
+use super::storage::write_log::SizeLimits;
 use super::storage::{types, Storage};
 use crate::ledger::native_vp::{self, Ctx, NativeVp};
 use crate::ledger::storage::{self as ledger_storage, StorageHasher};
-use crate::types::address::{Address, InternalAddress};
+use crate::types::address::{self, Address, InternalAddress};
 use crate::types::storage::Key;
 use crate::types::time::DurationSecs;
+use crate::types::token;
 use crate::vm::WasmCacheAccess;
 
 const ADDRESS: Address = Address::Internal(InternalAddress::Parameters);
@@ -122,6 +126,24 @@ pub struct Parameters {
     pub vp_whitelist: Vec<String>,
     /// Whitelisted tx hashes
     pub tx_whitelist: Vec<String>,
+    /// Limits of the sizes of the keys and values written by transactions
+    pub tx_write_size_limits: SizeLimits,
+    /// The tokens approved for paying the fees of wrapper transactions, with
+    /// their minimum gas prices
+    pub fee_tokens: FeeTokens,
+    /// The parameters of the inflation controller
+    pub inflation_params: InflationParams,
+}
+
+/// The tokens accepted for the fees of wrapper transactions, with the minimum
+/// price of a unit of gas in each token
+pub type FeeTokens = BTreeMap<Address, token::Amount>;
+
+/// The fee tokens of the chains initialized before the fee tokens were
+/// configurable, which only accepted the native token, without a minimum gas
+/// price.
+pub fn default_fee_tokens() -> FeeTokens {
+    BTreeMap::from([(address::xan(), token::Amount::from(0))])
 }
 
 /// Epoch duration. A new epoch begins as soon as both the `min_num_of_blocks`
@@ -187,6 +209,30 @@ impl Parameters {
                 "Max expected time per block parameters must be initialized \
                  in the genesis block",
             );
+
+        // write tx write size limits parameter
+        let tx_write_size_limits_key = storage::get_tx_write_size_limits_key();
+        let tx_write_size_limits_value = encode(&self.tx_write_size_limits);
+        storage
+            .write(&tx_write_size_limits_key, tx_write_size_limits_value)
+            .expect(
+                "Tx write size limits parameters must be initialized in the \
+                 genesis block",
+            );
+
+        // write fee tokens parameter
+        let fee_tokens_key = storage::get_fee_tokens_key();
+        let fee_tokens_value = encode(&self.fee_tokens);
+        storage.write(&fee_tokens_key, fee_tokens_value).expect(
+            "Fee tokens parameters must be initialized in the genesis block",
+        );
+
+        // write inflation parameters
+        let inflation_params_key = storage::get_inflation_params_key();
+        let inflation_params_value = encode(&self.inflation_params);
+        storage.write(&inflation_params_key, inflation_params_value).expect(
+            "Inflation parameters must be initialized in the genesis block",
+        );
     }
 }
 
@@ -232,6 +278,62 @@ where
     update(storage, &value, key)
 }
 
+/// Update the tx write size limits parameter in storage. Returns the
+/// parameters and gas cost.
+pub fn update_tx_write_size_limits_parameter<DB, H>(
+    storage: &mut Storage<DB, H>,
+    value: &SizeLimits,
+) -> std::result::Result<u64, WriteError>
+where
+    DB: ledger_storage::DB + for<'iter> ledger_storage::DBIter<'iter>,
+    H: ledger_storage::StorageHasher,
+{
+    let key = storage::get_tx_write_size_limits_key();
+    update(storage, value, key)
+}
+
+/// Update the fee tokens parameter in storage. Returns the parameters and gas
+/// cost.
+pub fn update_fee_tokens_parameter<DB, H>(
+    storage: &mut Storage<DB, H>,
+    value: &FeeTokens,
+) -> std::result::Result<u64, WriteError>
+where
+    DB: ledger_storage::DB + for<'iter> ledger_storage::DBIter<'iter>,
+    H: ledger_storage::StorageHasher,
+{
+    let key = storage::get_fee_tokens_key();
+    update(storage, value, key)
+}
+
+/// Update the inflation parameters in storage. Returns the parameters and gas
+/// cost.
+pub fn update_inflation_params_parameter<DB, H>(
+    storage: &mut Storage<DB, H>,
+    value: &InflationParams,
+) -> std::result::Result<u64, WriteError>
+where
+    DB: ledger_storage::DB + for<'iter> ledger_storage::DBIter<'iter>,
+    H: ledger_storage::StorageHasher,
+{
+    let key = storage::get_inflation_params_key();
+    update(storage, value, key)
+}
+
+/// Write the inflation computed by the controller for the current epoch.
+/// Returns the gas cost.
+pub fn write_inflation<DB, H>(
+    storage: &mut Storage<DB, H>,
+    value: &Inflation,
+) -> std::result::Result<u64, WriteError>
+where
+    DB: ledger_storage::DB + for<'iter> ledger_storage::DBIter<'iter>,
+    H: ledger_storage::StorageHasher,
+{
+    let key = storage::get_inflation_key();
+    update(storage, value, key)
+}
+
 /// Update the epoch parameter in storage. Returns the parameters and gas
 /// cost.
 pub fn update_epoch_parameter<DB, H>(
@@ -286,6 +388,97 @@ where
     Ok((epoch_duration, gas))
 }
 
+/// Read the limits of the sizes of the writes from transactions. The chains
+/// initialized before the limits were added use the default limits.
+pub fn read_tx_write_size_limits<DB, H>(
+    storage: &Storage<DB, H>,
+) -> std::result::Result<(SizeLimits, u64), ReadError>
+where
+    DB: ledger_storage::DB + for<'iter> ledger_storage::DBIter<'iter>,
+    H: ledger_storage::StorageHasher,
+{
+    let key = storage::get_tx_write_size_limits_key();
+    let (value, gas) = storage.read(&key).map_err(ReadError::StorageError)?;
+    let limits = match value {
+        Some(value) => decode(value).map_err(ReadError::StorageTypeError)?,
+        None => SizeLimits::default(),
+    };
+    Ok((limits, gas))
+}
+
+/// Read the whitelist of the hashes of the tx codes that may be applied. An
+/// empty whitelist allows any tx code.
+pub fn read_tx_whitelist<DB, H>(
+    storage: &Storage<DB, H>,
+) -> std::result::Result<(Vec<String>, u64), ReadError>
+where
+    DB: ledger_storage::DB + for<'iter> ledger_storage::DBIter<'iter>,
+    H: ledger_storage::StorageHasher,
+{
+    let key = storage::get_tx_whitelist_storage_key();
+    let (value, gas) = storage.read(&key).map_err(ReadError::StorageError)?;
+    let whitelist = value
+        .map(|value| decode(value).map_err(ReadError::StorageTypeError))
+        .transpose()?
+        .unwrap_or_default();
+    Ok((whitelist, gas))
+}
+
+/// Read the tokens accepted for the fees with their minimum gas prices. The
+/// chains initialized before the fee tokens were added use the
+/// [`default_fee_tokens`].
+pub fn read_fee_tokens<DB, H>(
+    storage: &Storage<DB, H>,
+) -> std::result::Result<(FeeTokens, u64), ReadError>
+where
+    DB: ledger_storage::DB + for<'iter> ledger_storage::DBIter<'iter>,
+    H: ledger_storage::StorageHasher,
+{
+    let key = storage::get_fee_tokens_key();
+    let (value, gas) = storage.read(&key).map_err(ReadError::StorageError)?;
+    let fee_tokens = match value {
+        Some(value) => decode(value).map_err(ReadError::StorageTypeError)?,
+        None => default_fee_tokens(),
+    };
+    Ok((fee_tokens, gas))
+}
+
+/// Read the parameters of the inflation controller. The chains initialized
+/// before the inflation controller was added use the default parameters.
+pub fn read_inflation_params<DB, H>(
+    storage: &Storage<DB, H>,
+) -> std::result::Result<(InflationParams, u64), ReadError>
+where
+    DB: ledger_storage::DB + for<'iter> ledger_storage::DBIter<'iter>,
+    H: ledger_storage::StorageHasher,
+{
+    let key = storage::get_inflation_params_key();
+    let (value, gas) = storage.read(&key).map_err(ReadError::StorageError)?;
+    let params = match value {
+        Some(value) => decode(value).map_err(ReadError::StorageTypeError)?,
+        None => InflationParams::default(),
+    };
+    Ok((params, gas))
+}
+
+/// Read the inflation computed by the controller for the current epoch. It's
+/// not set on the chains initialized before the inflation controller was
+/// added, until their next epoch.
+pub fn read_inflation<DB, H>(
+    storage: &Storage<DB, H>,
+) -> std::result::Result<(Option<Inflation>, u64), ReadError>
+where
+    DB: ledger_storage::DB + for<'iter> ledger_storage::DBIter<'iter>,
+    H: ledger_storage::StorageHasher,
+{
+    let key = storage::get_inflation_key();
+    let (value, gas) = storage.read(&key).map_err(ReadError::StorageError)?;
+    let inflation = value
+        .map(|value| decode(value).map_err(ReadError::StorageTypeError))
+        .transpose()?;
+    Ok((inflation, gas))
+}
+
 // Read the all the parameters from storage. Returns the parameters and gas
 /// cost.
 pub fn read<DB, H>(
@@ -326,14 +519,30 @@ where
         decode(value.ok_or(ReadError::ParametersMissing)?)
             .map_err(ReadError::StorageTypeError)?;
 
+    let (tx_write_size_limits, gas_limits) =
+        read_tx_write_size_limits(storage)?;
+
+    let (fee_tokens, gas_fees) = read_fee_tokens(storage)?;
+
+    let (inflation_params, gas_inflation) = read_inflation_params(storage)?;
+
     Ok((
         Parameters {
             epoch_duration,
             max_expected_time_per_block,
             vp_whitelist,
             tx_whitelist,
+            tx_write_size_limits,
+            fee_tokens,
+            inflation_params,
         },
-        gas_epoch + gas_tx + gas_vp + gas_time,
+        gas_epoch
+            + gas_tx
+            + gas_vp
+            + gas_time
+            + gas_limits
+            + gas_fees
+            + gas_inflation,
     ))
 }
 
@@ -6,6 +6,11 @@ const EPOCH_DURATION_KEY: &str = "epoch_duration";
 const VP_WHITELIST_KEY: &str = "vp_whitelist";
 const TX_WHITELIST_KEY: &str = "tx_whitelist";
 const MAX_EXPECTED_TIME_PER_BLOCK_KEY: &str = "max_expected_time_per_block";
+const MAX_EVIDENCE_BYTES_KEY: &str = "max_evidence_bytes";
+const TX_WRITE_SIZE_LIMITS_KEY: &str = "tx_write_size_limits";
+const FEE_TOKENS_KEY: &str = "fee_tokens";
+const INFLATION_PARAMS_KEY: &str = "inflation_params";
+const INFLATION_KEY: &str = "inflation";
 
 /// Returns if the key is a parameter key.
 pub fn is_parameter_key(key: &Key) -> bool {
@@ -18,6 +23,9 @@ pub fn is_protocol_parameter_key(key: &Key) -> bool {
         || is_max_expected_time_per_block_key(key)
         || is_tx_whitelist_key(key)
         || is_vp_whitelist_key(key)
+        || is_tx_write_size_limits_key(key)
+        || is_fee_tokens_key(key)
+        || is_inflation_params_key(key)
 }
 
 /// Returns if the key is an epoch storage key.
@@ -52,6 +60,30 @@ pub fn is_vp_whitelist_key(key: &Key) -> bool {
     ] if addr == &ADDRESS && vp_whitelist == VP_WHITELIST_KEY)
 }
 
+/// Returns if the key is the tx_write_size_limits key.
+pub fn is_tx_write_size_limits_key(key: &Key) -> bool {
+    matches!(&key.segments[..], [
+        DbKeySeg::AddressSeg(addr),
+        DbKeySeg::StringSeg(tx_write_size_limits),
+    ] if addr == &ADDRESS && tx_write_size_limits == TX_WRITE_SIZE_LIMITS_KEY)
+}
+
+/// Returns if the key is the fee_tokens key.
+pub fn is_fee_tokens_key(key: &Key) -> bool {
+    matches!(&key.segments[..], [
+        DbKeySeg::AddressSeg(addr),
+        DbKeySeg::StringSeg(fee_tokens),
+    ] if addr == &ADDRESS && fee_tokens == FEE_TOKENS_KEY)
+}
+
+/// Returns if the key is the inflation_params key.
+pub fn is_inflation_params_key(key: &Key) -> bool {
+    matches!(&key.segments[..], [
+        DbKeySeg::AddressSeg(addr),
+        DbKeySeg::StringSeg(inflation_params),
+    ] if addr == &ADDRESS && inflation_params == INFLATION_PARAMS_KEY)
+}
+
 /// Storage key used for epoch parameter.
 pub fn get_epoch_storage_key() -> Key {
     Key {
@@ -91,3 +123,59 @@ pub fn get_max_expected_time_per_block_key() -> Key {
         ],
     }
 }
+
+/// Storage key used for the limits of the sizes of the keys and values written
+/// by transactions.
+pub fn get_tx_write_size_limits_key() -> Key {
+    Key {
+        segments: vec![
+            DbKeySeg::AddressSeg(ADDRESS),
+            DbKeySeg::StringSeg(TX_WRITE_SIZE_LIMITS_KEY.to_string()),
+        ],
+    }
+}
+
+/// Storage key used for the tokens accepted for the fees of wrapper txs with
+/// their minimum gas prices.
+pub fn get_fee_tokens_key() -> Key {
+    Key {
+        segments: vec![
+            DbKeySeg::AddressSeg(ADDRESS),
+            DbKeySeg::StringSeg(FEE_TOKENS_KEY.to_string()),
+        ],
+    }
+}
+
+/// Storage key used for the maximum size of the evidence in a block. It's set
+/// from the genesis and it's not a protocol parameter, so it cannot be changed
+/// by a transaction.
+pub fn get_max_evidence_bytes_key() -> Key {
+    Key {
+        segments: vec![
+            DbKeySeg::AddressSeg(ADDRESS),
+            DbKeySeg::StringSeg(MAX_EVIDENCE_BYTES_KEY.to_string()),
+        ],
+    }
+}
+
+/// Storage key used for the parameters of the inflation controller.
+pub fn get_inflation_params_key() -> Key {
+    Key {
+        segments: vec![
+            DbKeySeg::AddressSeg(ADDRESS),
+            DbKeySeg::StringSeg(INFLATION_PARAMS_KEY.to_string()),
+        ],
+    }
+}
+
+/// Storage key used for the inflation computed by the controller for the
+/// current epoch. It's written by the protocol and it's not a protocol
+/// parameter, so it cannot be changed by a transaction.
+pub fn get_inflation_key() -> Key {
+    Key {
+        segments: vec![
+            DbKeySeg::AddressSeg(ADDRESS),
+            DbKeySeg::StringSeg(INFLATION_KEY.to_string()),
+        ],
+    }
+}
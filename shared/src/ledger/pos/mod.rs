@@ -6,8 +6,8 @@ pub mod vp;
 pub use anoma_proof_of_stake;
 pub use anoma_proof_of_stake::parameters::PosParams;
 pub use anoma_proof_of_stake::types::{
-    self, Slash, Slashes, TotalVotingPowers, ValidatorStates,
-    ValidatorVotingPowers,
+    self, Slash, Slashes, TotalVotingPowers, ValidatorMetadata,
+    ValidatorStates, ValidatorVotingPowers,
 };
 use anoma_proof_of_stake::PosBase;
 pub use storage::*;
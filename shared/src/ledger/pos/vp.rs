@@ -6,8 +6,8 @@ use std::panic::{RefUnwindSafe, UnwindSafe};
 pub use anoma_proof_of_stake;
 pub use anoma_proof_of_stake::parameters::PosParams;
 pub use anoma_proof_of_stake::types::{
-    self, Slash, Slashes, TotalVotingPowers, ValidatorStates,
-    ValidatorVotingPowers,
+    self, Slash, Slashes, TotalVotingPowers, ValidatorMetadata,
+    ValidatorStates, ValidatorVotingPowers,
 };
 use anoma_proof_of_stake::validation::validate;
 use anoma_proof_of_stake::{validation, PosReadOnly};
@@ -30,7 +30,7 @@ use crate::ledger::governance::vp::is_proposal_accepted;
 use crate::ledger::native_vp::{self, Ctx, NativeVp};
 use crate::ledger::pos::{
     is_validator_address_raw_hash_key, is_validator_consensus_key_key,
-    is_validator_state_key,
+    is_validator_metadata_key, is_validator_state_key,
 };
 use crate::ledger::storage::types::decode;
 use crate::ledger::storage::{self as ledger_storage, StorageHasher};
@@ -178,6 +178,11 @@ where
                 let post = self.ctx.read_post(key)?.and_then(|bytes| {
                     ValidatorConsensusKeys::try_from_slice(&bytes[..]).ok()
                 });
+                // A change of an existing validator's consensus key must be
+                // verified by the validator
+                if pre.is_some() && !verifiers.contains(validator) {
+                    return Ok(false);
+                }
                 changes.push(Validator {
                     address: validator.clone(),
                     update: ConsensusKey(Data { pre, post }),
@@ -215,17 +220,23 @@ where
                     .ctx
                     .read_post(key)?
                     .and_then(|bytes| Address::try_from_slice(&bytes[..]).ok());
-                // Find the raw hashes of the addresses
-                let pre = pre.map(|pre| {
-                    let raw_hash =
-                        pre.raw_hash().map(String::from).unwrap_or_default();
-                    (pre, raw_hash)
-                });
-                let post = post.map(|post| {
-                    let raw_hash =
-                        post.raw_hash().map(String::from).unwrap_or_default();
-                    (post, raw_hash)
-                });
+                // Find the raw hashes of the addresses' consensus keys
+                let pre = match pre {
+                    Some(pre) => {
+                        let keys_key = validator_consensus_key_key(&pre);
+                        let keys = self.ctx.read_pre(&keys_key)?;
+                        Some((pre, consensus_key_raw_hash(keys, raw_hash)))
+                    }
+                    None => None,
+                };
+                let post = match post {
+                    Some(post) => {
+                        let keys_key = validator_consensus_key_key(&post);
+                        let keys = self.ctx.read_post(&keys_key)?;
+                        Some((post, consensus_key_raw_hash(keys, raw_hash)))
+                    }
+                    None => None,
+                };
                 changes.push(ValidatorAddressRawHash {
                     raw_hash: raw_hash.to_string(),
                     data: Data { pre, post },
@@ -283,6 +294,34 @@ where
                     data: Data { pre, post },
                     slashes,
                 });
+            } else if let Some(validator) = is_validator_metadata_key(key) {
+                let pre = self.ctx.read_pre(key)?.and_then(|bytes| {
+                    ValidatorMetadata::try_from_slice(&bytes[..]).ok()
+                });
+                let post = self.ctx.read_post(key)?.and_then(|bytes| {
+                    ValidatorMetadata::try_from_slice(&bytes[..]).ok()
+                });
+                // The metadata can only be set for an existing validator, must
+                // be verified by the validator and cannot be deleted
+                if self.read_validator_state(validator).is_none()
+                    || !verifiers.contains(validator)
+                {
+                    return Ok(false);
+                }
+                match post {
+                    Some(post)
+                        if post.is_valid_update(
+                            pre.as_ref(),
+                            current_epoch.into(),
+                        ) => {}
+                    _ => {
+                        tracing::info!(
+                            "PoS invalid validator metadata update {}",
+                            key
+                        );
+                        return Ok(false);
+                    }
+                }
             } else if is_total_voting_power_key(key) {
                 let pre = self.ctx.read_pre(key)?.and_then(|bytes| {
                     TotalVotingPowers::try_from_slice(&bytes[..]).ok()
@@ -417,6 +456,25 @@ where
     }
 }
 
+/// Find the raw hash of a validator's consensus key from its encoded
+/// consensus keys. The given raw hash is returned if it's the raw hash of any
+/// of the keys, otherwise the raw hash of the last key, if any.
+fn consensus_key_raw_hash(keys: Option<Vec<u8>>, raw_hash: &str) -> String {
+    let keys = keys.and_then(|bytes| {
+        ValidatorConsensusKeys::try_from_slice(&bytes[..]).ok()
+    });
+    let raw_hashes: Vec<String> = keys
+        .iter()
+        .flat_map(|keys| keys.data.iter().flatten())
+        .map(key::tm_consensus_key_raw_hash)
+        .collect();
+    if raw_hashes.iter().any(|hash| hash == raw_hash) {
+        raw_hash.to_owned()
+    } else {
+        raw_hashes.last().cloned().unwrap_or_default()
+    }
+}
+
 impl From<native_vp::Error> for Error {
     fn from(err: native_vp::Error) -> Self {
         Self::NativeVpError(err)
@@ -0,0 +1,85 @@
+//! The storage keys reserved for the protocol, which the tx code cannot write
+//! or delete directly with the `tx_write` and `tx_delete` host calls:
+//!
+//! - the validity predicate key of any account (the `?` segment), which can
+//!   only be written with the `tx_init_account` and
+//!   `tx_update_validity_predicate` host calls, as they validate the VP code,
+//! - the protocol parameter keys, which can only be written by the code of an
+//!   accepted governance proposal while the ledger executes it, and
+//! - the PoS parameters and the validators' slashes, which are only written by
+//!   the ledger itself.
+
+use std::fmt::Display;
+
+use crate::ledger::parameters::storage as parameters_storage;
+use crate::ledger::pos;
+use crate::types::storage::Key;
+
+/// The kinds of the reserved storage keys
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ReservedKey {
+    /// The validity predicate key of an account
+    ValidityPredicate,
+    /// A protocol parameter key
+    Parameter,
+    /// The PoS parameters key or a validator's slashes key
+    ProofOfStake,
+}
+
+impl Display for ReservedKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let kind = match self {
+            ReservedKey::ValidityPredicate => "validity predicate",
+            ReservedKey::Parameter => "protocol parameter",
+            ReservedKey::ProofOfStake => "proof-of-stake",
+        };
+        write!(f, "{}", kind)
+    }
+}
+
+/// Find the kind of the given key, if it's reserved for the protocol
+pub fn find_reserved_key(key: &Key) -> Option<ReservedKey> {
+    if key.segments.is_empty() {
+        None
+    } else if key.is_validity_predicate().is_some() {
+        Some(ReservedKey::ValidityPredicate)
+    } else if parameters_storage::is_parameter_key(key) {
+        Some(ReservedKey::Parameter)
+    } else if pos::is_params_key(key)
+        || pos::is_validator_slashes_key(key).is_some()
+    {
+        Some(ReservedKey::ProofOfStake)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::address::testing::established_address_1;
+
+    #[test]
+    fn test_find_reserved_key() {
+        let address = established_address_1();
+        assert_eq!(
+            find_reserved_key(&Key::validity_predicate(&address)),
+            Some(ReservedKey::ValidityPredicate)
+        );
+        assert_eq!(
+            find_reserved_key(&parameters_storage::get_epoch_storage_key()),
+            Some(ReservedKey::Parameter)
+        );
+        assert_eq!(
+            find_reserved_key(&pos::params_key()),
+            Some(ReservedKey::ProofOfStake)
+        );
+        assert_eq!(
+            find_reserved_key(&pos::validator_slashes_key(&address)),
+            Some(ReservedKey::ProofOfStake)
+        );
+        // The other PoS keys are written by the PoS txs
+        assert_eq!(find_reserved_key(&pos::validator_set_key()), None);
+        assert_eq!(find_reserved_key(&Key::parse("key").unwrap()), None);
+    }
+}
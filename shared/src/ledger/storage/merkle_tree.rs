@@ -1,6 +1,7 @@
 //! The merkle tree in the storage
 
-use std::convert::TryInto;
+use std::collections::BTreeSet;
+use std::convert::{TryFrom, TryInto};
 use std::fmt;
 use std::str::FromStr;
 
@@ -14,7 +15,8 @@ use prost::Message;
 use sha2::{Digest, Sha256};
 use sparse_merkle_tree::default_store::DefaultStore;
 use sparse_merkle_tree::error::Error as SmtError;
-use sparse_merkle_tree::traits::Hasher;
+use sparse_merkle_tree::traits::{Hasher, Store};
+use sparse_merkle_tree::tree::{BranchNode, LeafNode};
 use sparse_merkle_tree::{SparseMerkleTree, H256};
 #[cfg(not(feature = "ABCI"))]
 use tendermint::merkle::proof::{Proof, ProofOp};
@@ -37,6 +39,10 @@ pub enum Error {
     Smt(SmtError),
     #[error("Invalid store type: {0}")]
     StoreType(String),
+    #[error("Invalid proof: {0}")]
+    InvalidProof(String),
+    #[error("Invalid node key: {0}")]
+    InvalidNodeKey(String),
 }
 
 /// Result for functions that may fail
@@ -125,13 +131,16 @@ impl fmt::Display for StoreType {
     }
 }
 
+/// A sparse merkle tree with a store that tracks its changed nodes
+type Smt<H> = SparseMerkleTree<H, H256, TrackedStore>;
+
 /// Merkle tree storage
 #[derive(Default)]
 pub struct MerkleTree<H: StorageHasher + Default> {
-    base: SparseMerkleTree<H, H256, DefaultStore<H256>>,
-    account: SparseMerkleTree<H, H256, DefaultStore<H256>>,
-    ibc: SparseMerkleTree<H, H256, DefaultStore<H256>>,
-    pos: SparseMerkleTree<H, H256, DefaultStore<H256>>,
+    base: Smt<H>,
+    account: Smt<H>,
+    ibc: Smt<H>,
+    pos: Smt<H>,
 }
 
 impl<H: StorageHasher + Default> core::fmt::Debug for MerkleTree<H> {
@@ -146,10 +155,11 @@ impl<H: StorageHasher + Default> core::fmt::Debug for MerkleTree<H> {
 impl<H: StorageHasher + Default> MerkleTree<H> {
     /// Restore the tree from the stores
     pub fn new(stores: MerkleTreeStoresRead) -> Self {
-        let base = SparseMerkleTree::new(stores.base.0, stores.base.1);
-        let account = SparseMerkleTree::new(stores.account.0, stores.account.1);
-        let ibc = SparseMerkleTree::new(stores.ibc.0, stores.ibc.1);
-        let pos = SparseMerkleTree::new(stores.pos.0, stores.pos.1);
+        let base = SparseMerkleTree::new(stores.base.0, stores.base.1.into());
+        let account =
+            SparseMerkleTree::new(stores.account.0, stores.account.1.into());
+        let ibc = SparseMerkleTree::new(stores.ibc.0, stores.ibc.1.into());
+        let pos = SparseMerkleTree::new(stores.pos.0, stores.pos.1.into());
 
         Self {
             base,
@@ -159,10 +169,7 @@ impl<H: StorageHasher + Default> MerkleTree<H> {
         }
     }
 
-    fn tree(
-        &self,
-        store_type: &StoreType,
-    ) -> &SparseMerkleTree<H, H256, DefaultStore<H256>> {
+    fn tree(&self, store_type: &StoreType) -> &Smt<H> {
         match store_type {
             StoreType::Base => &self.base,
             StoreType::Account => &self.account,
@@ -227,7 +234,8 @@ impl<H: StorageHasher + Default> MerkleTree<H> {
         (*self.base.root()).into()
     }
 
-    /// Get the stores of the base and sub trees
+    /// Get the roots and the nodes changed since the last commit of the base
+    /// and sub trees
     pub fn stores(&self) -> MerkleTreeStoresWrite {
         MerkleTreeStoresWrite {
             base: (self.base.root(), self.base.store()),
@@ -237,6 +245,14 @@ impl<H: StorageHasher + Default> MerkleTree<H> {
         }
     }
 
+    /// Forget the changed nodes once they've been persisted with the block
+    pub fn clear_changes(&mut self) {
+        self.base.store_mut().clear_changes();
+        self.account.store_mut().clear_changes();
+        self.ibc.store_mut().clear_changes();
+        self.pos.store_mut().clear_changes();
+    }
+
     /// Get the existence proof
     pub fn get_existence_proof(
         &self,
@@ -255,7 +271,7 @@ impl<H: StorageHasher + Default> MerkleTree<H> {
                 proof: Some(Ics23Proof::Exist(ExistenceProof {
                     key: sub_key.to_string().as_bytes().to_vec(),
                     value,
-                    leaf: Some(self.leaf_spec()),
+                    leaf: Some(leaf_spec::<H>()),
                     ..ep
                 })),
             },
@@ -313,7 +329,7 @@ impl<H: StorageHasher + Default> MerkleTree<H> {
             Ics23Proof::Exist(ep) => CommitmentProof {
                 proof: Some(Ics23Proof::Exist(ExistenceProof {
                     key: base_key.as_bytes().to_vec(),
-                    leaf: Some(self.base_leaf_spec()),
+                    leaf: Some(base_leaf_spec::<H>()),
                     ..ep
                 })),
             },
@@ -339,45 +355,201 @@ impl<H: StorageHasher + Default> MerkleTree<H> {
 
     /// Get the proof specs
     pub fn proof_specs(&self) -> Vec<ProofSpec> {
-        let spec = sparse_merkle_tree::proof_ics23::get_spec(H::hash_op());
-        let sub_tree_spec = ProofSpec {
-            leaf_spec: Some(self.leaf_spec()),
-            ..spec.clone()
-        };
-        let base_tree_spec = ProofSpec {
-            leaf_spec: Some(self.base_leaf_spec()),
-            ..spec
-        };
-        vec![sub_tree_spec, base_tree_spec]
-    }
-
-    /// Get the leaf spec for the base tree. The key is stored after hashing,
-    /// but the stored value is the subtree's root without hashing.
-    fn base_leaf_spec(&self) -> LeafOp {
-        LeafOp {
-            hash: H::hash_op().into(),
-            prehash_key: H::hash_op().into(),
-            prehash_value: HashOp::NoHash.into(),
-            length: LengthOp::NoPrefix.into(),
-            prefix: H256::zero().as_slice().to_vec(),
+        proof_specs::<H>()
+    }
+}
+
+/// Get the proof specs of the subtree and the base tree
+fn proof_specs<H: StorageHasher>() -> Vec<ProofSpec> {
+    let spec = sparse_merkle_tree::proof_ics23::get_spec(H::hash_op());
+    let sub_tree_spec = ProofSpec {
+        leaf_spec: Some(leaf_spec::<H>()),
+        ..spec.clone()
+    };
+    let base_tree_spec = ProofSpec {
+        leaf_spec: Some(base_leaf_spec::<H>()),
+        ..spec
+    };
+    vec![sub_tree_spec, base_tree_spec]
+}
+
+/// Get the leaf spec for the base tree. The key is stored after hashing,
+/// but the stored value is the subtree's root without hashing.
+fn base_leaf_spec<H: StorageHasher>() -> LeafOp {
+    LeafOp {
+        hash: H::hash_op().into(),
+        prehash_key: H::hash_op().into(),
+        prehash_value: HashOp::NoHash.into(),
+        length: LengthOp::NoPrefix.into(),
+        prefix: H256::zero().as_slice().to_vec(),
+    }
+}
+
+/// Get the leaf spec for the subtree. Non-hashed values are used for the
+/// verification with this spec because a subtree stores the key-value pairs
+/// after hashing.
+fn leaf_spec<H: StorageHasher>() -> LeafOp {
+    LeafOp {
+        hash: H::hash_op().into(),
+        prehash_key: H::hash_op().into(),
+        prehash_value: H::hash_op().into(),
+        length: LengthOp::NoPrefix.into(),
+        prefix: H256::zero().as_slice().to_vec(),
+    }
+}
+
+/// Verify a proof, as returned by [`MerkleTree::get_existence_proof`], that
+/// the given key has the given value in the tree with the given root.
+pub fn verify_existence_proof<H: StorageHasher>(
+    proof: &Proof,
+    root: &MerkleRoot,
+    key: &Key,
+    value: impl AsRef<[u8]>,
+) -> Result<()> {
+    let specs = proof_specs::<H>();
+    check_proof_ops(proof, specs.len())?;
+    let (store_type, sub_key) = StoreType::sub_key(key)?;
+    // First, the sub proof is verified. Next the base proof is verified with
+    // the sub root
+    let sub_root = verify_membership_op(
+        &proof.ops[0],
+        &specs[0],
+        &sub_key.to_string(),
+        value.as_ref(),
+    )?;
+    verify_base_op(&proof.ops[1], &specs[1], &store_type, sub_root, root)
+}
+
+/// Verify a proof, as returned by [`MerkleTree::get_non_existence_proof`],
+/// that the given key has no value in the tree with the given root.
+pub fn verify_non_existence_proof<H: StorageHasher>(
+    proof: &Proof,
+    root: &MerkleRoot,
+    key: &Key,
+) -> Result<()> {
+    let specs = proof_specs::<H>();
+    check_proof_ops(proof, specs.len())?;
+    let (store_type, sub_key) = StoreType::sub_key(key)?;
+    let commitment_proof = decode_proof_op(&proof.ops[0])?;
+    let nep = match commitment_proof.proof {
+        Some(Ics23Proof::Nonexist(nep))
+            if nep.key == sub_key.to_string().as_bytes() =>
+        {
+            nep
+        }
+        _ => {
+            return Err(Error::InvalidProof(
+                "Expected a non-existence proof of the key".to_string(),
+            ));
         }
+    };
+    // The neighbors of the key are proven with the sub root
+    let neighbor = nep
+        .left
+        .as_ref()
+        .or_else(|| nep.right.as_ref())
+        .ok_or_else(|| {
+            Error::InvalidProof("The proof has no neighbors".to_string())
+        })?;
+    let sub_root = ics23::calculate_existence_root(neighbor)
+        .map_err(|err| Error::InvalidProof(err.to_string()))?;
+    // The subtree stores the hashed keys, so the proof is verified with the
+    // hashed key and the spec of the subtree without the key hashing that
+    // [`leaf_spec`] adds for the existence proofs
+    let hashed_sub_key = H::hash(&sub_key.to_string());
+    let raw_proof = CommitmentProof {
+        proof: Some(Ics23Proof::Nonexist(NonExistenceProof {
+            key: hashed_sub_key.as_slice().to_vec(),
+            ..nep
+        })),
+    };
+    let raw_spec = sparse_merkle_tree::proof_ics23::get_spec(H::hash_op());
+    if !ics23::verify_non_membership(
+        &raw_proof,
+        &raw_spec,
+        &sub_root,
+        hashed_sub_key.as_slice(),
+    ) {
+        return Err(Error::InvalidProof(format!(
+            "The non-membership of {} cannot be verified",
+            sub_key
+        )));
+    }
+    verify_base_op(&proof.ops[1], &specs[1], &store_type, sub_root, root)
+}
+
+/// Check that the proof has the expected number of ops
+fn check_proof_ops(proof: &Proof, expected: usize) -> Result<()> {
+    if proof.ops.len() != expected {
+        return Err(Error::InvalidProof(format!(
+            "Expected {} proof ops, got {}",
+            expected,
+            proof.ops.len()
+        )));
     }
+    Ok(())
+}
+
+/// Decode the commitment proof of a proof op
+fn decode_proof_op(op: &ProofOp) -> Result<CommitmentProof> {
+    CommitmentProof::decode(&*op.data)
+        .map_err(|err| Error::InvalidProof(err.to_string()))
+}
 
-    /// Get the leaf spec for the subtree. Non-hashed values are used for the
-    /// verification with this spec because a subtree stores the key-value pairs
-    /// after hashing.
-    fn leaf_spec(&self) -> LeafOp {
-        LeafOp {
-            hash: H::hash_op().into(),
-            prehash_key: H::hash_op().into(),
-            prehash_value: H::hash_op().into(),
-            length: LengthOp::NoPrefix.into(),
-            prefix: H256::zero().as_slice().to_vec(),
+/// Verify the membership of the path with the value in a proof op and return
+/// the root that the op proves it in
+fn verify_membership_op(
+    op: &ProofOp,
+    spec: &ProofSpec,
+    path: &str,
+    value: &[u8],
+) -> Result<Vec<u8>> {
+    let commitment_proof = decode_proof_op(op)?;
+    let existence_proof = match &commitment_proof.proof {
+        Some(Ics23Proof::Exist(ep)) => ep,
+        _ => {
+            return Err(Error::InvalidProof(
+                "Expected an existence proof".to_string(),
+            ));
         }
+    };
+    let root = ics23::calculate_existence_root(existence_proof)
+        .map_err(|err| Error::InvalidProof(err.to_string()))?;
+    if !ics23::verify_membership(
+        &commitment_proof,
+        spec,
+        &root,
+        path.as_bytes(),
+        value,
+    ) {
+        return Err(Error::InvalidProof(format!(
+            "The membership of {} cannot be verified",
+            path
+        )));
+    }
+    Ok(root)
+}
+
+/// Verify the base proof op of the sub root and check the resulting root
+fn verify_base_op(
+    op: &ProofOp,
+    spec: &ProofSpec,
+    store_type: &StoreType,
+    sub_root: Vec<u8>,
+    root: &MerkleRoot,
+) -> Result<()> {
+    let base_root =
+        verify_membership_op(op, spec, &store_type.to_string(), &sub_root)?;
+    if base_root != root.0 {
+        return Err(Error::InvalidProof(
+            "The proof doesn't match the root".to_string(),
+        ));
     }
+    Ok(())
 }
 
 /// The root hash of the merkle tree as bytes
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct MerkleRoot(pub Vec<u8>);
 
 impl From<H256> for MerkleRoot {
@@ -412,27 +584,46 @@ impl MerkleTreeStoresRead {
         }
     }
 
-    /// Set the store of the given store type
-    pub fn set_store(
+    /// Insert a persisted branch node into the store of the given store type
+    pub fn insert_branch(
         &mut self,
         store_type: &StoreType,
-        store: DefaultStore<H256>,
-    ) {
+        key: H256,
+        branch: BranchNode,
+    ) -> Result<()> {
+        self.store_mut(store_type)
+            .insert_branch(key, branch)
+            .map_err(Error::Smt)
+    }
+
+    /// Insert a persisted leaf node into the store of the given store type
+    pub fn insert_leaf(
+        &mut self,
+        store_type: &StoreType,
+        key: H256,
+        leaf: LeafNode<H256>,
+    ) -> Result<()> {
+        self.store_mut(store_type)
+            .insert_leaf(key, leaf)
+            .map_err(Error::Smt)
+    }
+
+    fn store_mut(&mut self, store_type: &StoreType) -> &mut DefaultStore<H256> {
         match store_type {
-            StoreType::Base => self.base.1 = store,
-            StoreType::Account => self.account.1 = store,
-            StoreType::Ibc => self.ibc.1 = store,
-            StoreType::PoS => self.pos.1 = store,
+            StoreType::Base => &mut self.base.1,
+            StoreType::Account => &mut self.account.1,
+            StoreType::Ibc => &mut self.ibc.1,
+            StoreType::PoS => &mut self.pos.1,
         }
     }
 }
 
 /// The root and store pairs to be persistent
 pub struct MerkleTreeStoresWrite<'a> {
-    base: (&'a H256, &'a DefaultStore<H256>),
-    account: (&'a H256, &'a DefaultStore<H256>),
-    ibc: (&'a H256, &'a DefaultStore<H256>),
-    pos: (&'a H256, &'a DefaultStore<H256>),
+    base: (&'a H256, &'a TrackedStore),
+    account: (&'a H256, &'a TrackedStore),
+    ibc: (&'a H256, &'a TrackedStore),
+    pos: (&'a H256, &'a TrackedStore),
 }
 
 impl<'a> MerkleTreeStoresWrite<'a> {
@@ -447,7 +638,7 @@ impl<'a> MerkleTreeStoresWrite<'a> {
     }
 
     /// Get the store of the given store type
-    pub fn store(&self, store_type: &StoreType) -> &DefaultStore<H256> {
+    pub fn store(&self, store_type: &StoreType) -> &TrackedStore {
         match store_type {
             StoreType::Base => self.base.1,
             StoreType::Account => self.account.1,
@@ -457,6 +648,117 @@ impl<'a> MerkleTreeStoresWrite<'a> {
     }
 }
 
+/// A store of a sparse merkle tree that keeps track of the nodes changed since
+/// the last commit, so that only these have to be persisted with a block
+/// instead of the whole tree.
+#[derive(Default)]
+pub struct TrackedStore {
+    store: DefaultStore<H256>,
+    changed_branches: BTreeSet<H256>,
+    changed_leaves: BTreeSet<H256>,
+}
+
+impl TrackedStore {
+    /// Get the branch nodes changed since the last commit. A removed node has
+    /// no value.
+    pub fn changed_branches(
+        &self,
+    ) -> impl Iterator<Item = (&H256, Option<&BranchNode>)> {
+        self.changed_branches
+            .iter()
+            .map(move |key| (key, self.store.branches_map().get(key)))
+    }
+
+    /// Get the leaf nodes changed since the last commit. A removed node has no
+    /// value.
+    pub fn changed_leaves(
+        &self,
+    ) -> impl Iterator<Item = (&H256, Option<&LeafNode<H256>>)> {
+        self.changed_leaves
+            .iter()
+            .map(move |key| (key, self.store.leaves_map().get(key)))
+    }
+
+    fn clear_changes(&mut self) {
+        self.changed_branches.clear();
+        self.changed_leaves.clear();
+    }
+}
+
+impl From<DefaultStore<H256>> for TrackedStore {
+    fn from(store: DefaultStore<H256>) -> Self {
+        Self {
+            store,
+            changed_branches: BTreeSet::new(),
+            changed_leaves: BTreeSet::new(),
+        }
+    }
+}
+
+impl Store<H256> for TrackedStore {
+    fn get_branch(
+        &self,
+        node: &H256,
+    ) -> std::result::Result<Option<BranchNode>, SmtError> {
+        self.store.get_branch(node)
+    }
+
+    fn get_leaf(
+        &self,
+        leaf_hash: &H256,
+    ) -> std::result::Result<Option<LeafNode<H256>>, SmtError> {
+        self.store.get_leaf(leaf_hash)
+    }
+
+    fn insert_branch(
+        &mut self,
+        node: H256,
+        branch: BranchNode,
+    ) -> std::result::Result<(), SmtError> {
+        self.changed_branches.insert(node);
+        self.store.insert_branch(node, branch)
+    }
+
+    fn insert_leaf(
+        &mut self,
+        leaf_hash: H256,
+        leaf: LeafNode<H256>,
+    ) -> std::result::Result<(), SmtError> {
+        self.changed_leaves.insert(leaf_hash);
+        self.store.insert_leaf(leaf_hash, leaf)
+    }
+
+    fn remove_branch(
+        &mut self,
+        node: &H256,
+    ) -> std::result::Result<(), SmtError> {
+        self.changed_branches.insert(*node);
+        self.store.remove_branch(node)
+    }
+
+    fn remove_leaf(
+        &mut self,
+        leaf_hash: &H256,
+    ) -> std::result::Result<(), SmtError> {
+        self.changed_leaves.insert(*leaf_hash);
+        self.store.remove_leaf(leaf_hash)
+    }
+}
+
+/// Encode a key of a node in a tree's store to be used in a DB key
+pub fn encode_node_key(key: &H256) -> String {
+    hex::encode(key.as_slice())
+}
+
+/// Decode a key of a node in a tree's store, encoded with [`encode_node_key`]
+pub fn decode_node_key(key: &str) -> Result<H256> {
+    let bytes = hex::decode(key)
+        .map_err(|err| Error::InvalidNodeKey(err.to_string()))?;
+    let bytes = <[u8; 32]>::try_from(bytes.as_slice())
+        .map_err(|err| Error::InvalidNodeKey(err.to_string()))?;
+    Ok(bytes.into())
+}
+
 /// The storage hasher used for the merkle tree.
 pub trait StorageHasher: Hasher + Default {
     /// Hash the value to store
@@ -558,15 +860,78 @@ mod test {
         tree.update(&ibc_key, [1u8; 8]).unwrap();
         tree.update(&pos_key, [2u8; 8]).unwrap();
 
+        let stores_read =
+            persist_changes(&tree, MerkleTreeStoresRead::default());
+        let restored_tree = MerkleTree::<Sha256Hasher>::new(stores_read);
+        assert!(restored_tree.has_key(&ibc_key).unwrap());
+        assert!(restored_tree.has_key(&pos_key).unwrap());
+    }
+
+    #[test]
+    fn test_restore_tree_from_incremental_changes() {
+        let mut tree = MerkleTree::<Sha256Hasher>::default();
+
+        let key_prefix: Key =
+            Address::Internal(InternalAddress::Ibc).to_db_key().into();
+        let ibc_key = key_prefix.push(&"test".to_string()).unwrap();
+        let key_prefix: Key =
+            Address::Internal(InternalAddress::PoS).to_db_key().into();
+        let pos_key = key_prefix.push(&"test".to_string()).unwrap();
+        let other_pos_key = key_prefix.push(&"other".to_string()).unwrap();
+
+        // The first block
+        tree.update(&ibc_key, [1u8; 8]).unwrap();
+        tree.update(&pos_key, [2u8; 8]).unwrap();
+        let stores_read =
+            persist_changes(&tree, MerkleTreeStoresRead::default());
+        tree.clear_changes();
+
+        // The second block doesn't change the IBC tree
+        tree.update(&other_pos_key, [3u8; 8]).unwrap();
+        tree.delete(&pos_key).unwrap();
+        let stores = tree.stores();
+        assert_eq!(stores.store(&StoreType::Ibc).changed_leaves().count(), 0);
+        assert_eq!(stores.store(&StoreType::Ibc).changed_branches().count(), 0);
+        let stores_read = persist_changes(&tree, stores_read);
+        tree.clear_changes();
+
+        let restored_tree = MerkleTree::<Sha256Hasher>::new(stores_read);
+        assert_eq!(restored_tree.root(), tree.root());
+        assert!(restored_tree.has_key(&ibc_key).unwrap());
+        assert!(!restored_tree.has_key(&pos_key).unwrap());
+        assert!(restored_tree.has_key(&other_pos_key).unwrap());
+    }
+
+    /// Apply the changed nodes of the tree to the stores, like they're
+    /// persisted with a block
+    fn persist_changes(
+        tree: &MerkleTree<Sha256Hasher>,
+        mut stores_read: MerkleTreeStoresRead,
+    ) -> MerkleTreeStoresRead {
         let stores_write = tree.stores();
-        let mut stores_read = MerkleTreeStoresRead::default();
         for st in StoreType::iter() {
             stores_read.set_root(st, *stores_write.root(st));
-            stores_read.set_store(st, stores_write.store(st).clone());
+            let store = stores_write.store(st);
+            for (key, branch) in store.changed_branches() {
+                match branch {
+                    Some(branch) => stores_read
+                        .insert_branch(st, *key, branch.clone())
+                        .unwrap(),
+                    None => {
+                        stores_read.store_mut(st).remove_branch(key).unwrap()
+                    }
+                }
+            }
+            for (key, leaf) in store.changed_leaves() {
+                match leaf {
+                    Some(leaf) => {
+                        stores_read.insert_leaf(st, *key, leaf.clone()).unwrap()
+                    }
+                    None => stores_read.store_mut(st).remove_leaf(key).unwrap(),
+                }
+            }
         }
-        let restored_tree = MerkleTree::<Sha256Hasher>::new(stores_read);
-        assert!(restored_tree.has_key(&ibc_key).unwrap());
-        assert!(restored_tree.has_key(&pos_key).unwrap());
+        stores_read
     }
 
     #[test]
@@ -618,4 +983,82 @@ mod test {
         // Check the base root
         assert_eq!(sub_root, tree.root().0);
     }
+
+    #[test]
+    fn test_verify_existence_proof() {
+        let mut tree = MerkleTree::<Sha256Hasher>::default();
+
+        let key_prefix: Key =
+            Address::Internal(InternalAddress::Ibc).to_db_key().into();
+        let ibc_key = key_prefix.push(&"test".to_string()).unwrap();
+        let key_prefix: Key =
+            Address::Internal(InternalAddress::PoS).to_db_key().into();
+        let pos_key = key_prefix.push(&"test".to_string()).unwrap();
+
+        let ibc_val = [1u8; 8].to_vec();
+        tree.update(&ibc_key, ibc_val.clone()).unwrap();
+        tree.update(&pos_key, [2u8; 8]).unwrap();
+
+        let root = tree.root();
+        let proof =
+            tree.get_existence_proof(&ibc_key, ibc_val.clone()).unwrap();
+        verify_existence_proof::<Sha256Hasher>(
+            &proof, &root, &ibc_key, &ibc_val,
+        )
+        .unwrap();
+
+        // A different value
+        assert!(verify_existence_proof::<Sha256Hasher>(
+            &proof, &root, &ibc_key, [3u8; 8]
+        )
+        .is_err());
+        // A different key
+        assert!(verify_existence_proof::<Sha256Hasher>(
+            &proof, &root, &pos_key, &ibc_val
+        )
+        .is_err());
+        // A different root
+        assert!(verify_existence_proof::<Sha256Hasher>(
+            &proof,
+            &MerkleRoot(vec![0; 32]),
+            &ibc_key,
+            &ibc_val
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_verify_non_existence_proof() {
+        let mut tree = MerkleTree::<Sha256Hasher>::default();
+
+        let key_prefix: Key =
+            Address::Internal(InternalAddress::Ibc).to_db_key().into();
+        let ibc_key = key_prefix.push(&"test".to_string()).unwrap();
+        let missing_key = key_prefix.push(&"missing".to_string()).unwrap();
+        tree.update(&ibc_key, [1u8; 8]).unwrap();
+
+        let root = tree.root();
+        let proof = tree.get_non_existence_proof(&missing_key).unwrap();
+        verify_non_existence_proof::<Sha256Hasher>(&proof, &root, &missing_key)
+            .unwrap();
+
+        // A different key
+        assert!(verify_non_existence_proof::<Sha256Hasher>(
+            &proof, &root, &ibc_key
+        )
+        .is_err());
+        // A different root
+        assert!(verify_non_existence_proof::<Sha256Hasher>(
+            &proof,
+            &MerkleRoot(vec![0; 32]),
+            &missing_key
+        )
+        .is_err());
+        // An existence proof
+        let proof = tree.get_existence_proof(&ibc_key, vec![1u8; 8]).unwrap();
+        assert!(verify_non_existence_proof::<Sha256Hasher>(
+            &proof, &root, &ibc_key
+        )
+        .is_err());
+    }
 }
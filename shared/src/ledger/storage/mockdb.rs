@@ -6,10 +6,14 @@ use std::ops::Bound::{Excluded, Included};
 use std::path::Path;
 use std::str::FromStr;
 
-use super::merkle_tree::{MerkleTreeStoresRead, StoreType};
+use super::merkle_tree::{
+    decode_node_key, encode_node_key, MerkleTreeStoresRead,
+    MerkleTreeStoresWrite, StoreType,
+};
 use super::{
     BlockStateRead, BlockStateWrite, DBIter, DBWriteBatch, Error, Result, DB,
 };
+use crate::ledger::gas::storage_read_gas;
 use crate::ledger::storage::types::{self, KVBytes, PrefixIterator};
 use crate::tendermint::block::Header;
 use crate::tendermint_proto::Protobuf;
@@ -36,6 +40,74 @@ unsafe impl Sync for MockDB {}
 #[derive(Debug, Default)]
 pub struct MockDBWriteBatch;
 
+impl MockDB {
+    /// Write the nodes of the merkle tree changed in the block, in the same
+    /// layout as the persistent DB. The removed nodes are kept in the archive
+    /// mode.
+    fn write_tree_nodes(
+        &self,
+        merkle_tree_stores: &MerkleTreeStoresWrite,
+        archive: bool,
+    ) {
+        let mut db = self.0.borrow_mut();
+        for st in StoreType::iter() {
+            let store = merkle_tree_stores.store(st);
+            for (key, branch) in store.changed_branches() {
+                let db_key =
+                    format!("tree/{}/branch/{}", st, encode_node_key(key));
+                match branch {
+                    Some(branch) => db.insert(db_key, types::encode(branch)),
+                    None if archive => None,
+                    None => db.remove(&db_key),
+                };
+            }
+            for (key, leaf) in store.changed_leaves() {
+                let db_key =
+                    format!("tree/{}/leaf/{}", st, encode_node_key(key));
+                match leaf {
+                    Some(leaf) => db.insert(db_key, types::encode(leaf)),
+                    None if archive => None,
+                    None => db.remove(&db_key),
+                };
+            }
+        }
+    }
+
+    /// Read the nodes of the last committed merkle tree into the stores
+    fn read_tree_nodes(
+        &self,
+        merkle_tree_stores: &mut MerkleTreeStoresRead,
+    ) -> Result<()> {
+        let db = self.0.borrow();
+        // The upper bound is the prefix with the last char incremented
+        for (path, bytes) in db.range("tree/".to_owned().."tree0".to_owned()) {
+            let segments: Vec<&str> =
+                path.split(KEY_SEGMENT_SEPARATOR).collect();
+            match segments.as_slice() {
+                ["tree", st, kind, key] => {
+                    let st = StoreType::from_str(st)?;
+                    let key = decode_node_key(key)?;
+                    match *kind {
+                        "branch" => merkle_tree_stores.insert_branch(
+                            &st,
+                            key,
+                            types::decode(bytes).map_err(Error::CodingError)?,
+                        )?,
+                        "leaf" => merkle_tree_stores.insert_leaf(
+                            &st,
+                            key,
+                            types::decode(bytes).map_err(Error::CodingError)?,
+                        )?,
+                        _ => unknown_key_error(path)?,
+                    }
+                }
+                _ => unknown_key_error(path)?,
+            }
+        }
+        Ok(())
+    }
+}
+
 impl DB for MockDB {
     /// There is no cache for MockDB
     type Cache = ();
@@ -103,11 +175,6 @@ impl DB for MockDB {
                                     types::decode(bytes)
                                         .map_err(Error::CodingError)?,
                                 ),
-                                Some(&"store") => merkle_tree_stores.set_store(
-                                    &st,
-                                    types::decode(bytes)
-                                        .map_err(Error::CodingError)?,
-                                ),
                                 _ => unknown_key_error(path)?,
                             }
                         }
@@ -141,6 +208,7 @@ impl DB for MockDB {
                 None => unknown_key_error(path)?,
             }
         }
+        self.read_tree_nodes(&mut merkle_tree_stores)?;
         match (hash, epoch, pred_epochs, address_gen) {
             (Some(hash), Some(epoch), Some(pred_epochs), Some(address_gen)) => {
                 Ok(Some(BlockStateRead {
@@ -163,7 +231,12 @@ impl DB for MockDB {
         }
     }
 
-    fn write_block(&mut self, state: BlockStateWrite) -> Result<()> {
+    fn write_block(
+        &mut self,
+        state: BlockStateWrite,
+        _batch: &mut Self::WriteBatch,
+    ) -> Result<()> {
+        // The block is written directly, like the batch writes in MockDB
         let BlockStateWrite {
             merkle_tree_stores,
             header,
@@ -176,6 +249,7 @@ impl DB for MockDB {
             address_gen,
             #[cfg(feature = "ferveo-tpke")]
             tx_queue,
+            archive,
         }: BlockStateWrite = state;
 
         // Epoch start height and time
@@ -211,14 +285,8 @@ impl DB for MockDB {
                     root_key.to_string(),
                     types::encode(merkle_tree_stores.root(st)),
                 );
-                let store_key = prefix_key
-                    .push(&"store".to_owned())
-                    .map_err(Error::KeyError)?;
-                self.0.borrow_mut().insert(
-                    store_key.to_string(),
-                    types::encode(merkle_tree_stores.store(st)),
-                );
             }
+            self.write_tree_nodes(&merkle_tree_stores, archive);
         }
         // Block header
         {
@@ -289,6 +357,32 @@ impl DB for MockDB {
         }
     }
 
+    fn write_block_results(
+        &mut self,
+        height: BlockHeight,
+        results: &[u8],
+        _batch: &mut Self::WriteBatch,
+    ) -> Result<()> {
+        // The results are written directly, like the batch writes in MockDB
+        let key = Key::from(height.to_db_key())
+            .push(&"results".to_owned())
+            .map_err(Error::KeyError)?;
+        self.0
+            .borrow_mut()
+            .insert(key.to_string(), results.to_vec());
+        Ok(())
+    }
+
+    fn read_block_results(
+        &self,
+        height: BlockHeight,
+    ) -> Result<Option<Vec<u8>>> {
+        let key = Key::from(height.to_db_key())
+            .push(&"results".to_owned())
+            .map_err(Error::KeyError)?;
+        Ok(self.0.borrow().get(&key.to_string()).cloned())
+    }
+
     fn read_merkle_tree_stores(
         &self,
         height: BlockHeight,
@@ -312,22 +406,24 @@ impl DB for MockDB {
                 }
                 None => return Ok(None),
             }
-
-            let store_key = prefix_key
-                .push(&"store".to_owned())
-                .map_err(Error::KeyError)?;
-            let bytes = self.0.borrow().get(&store_key.to_string()).cloned();
-            match bytes {
-                Some(b) => {
-                    let store = types::decode(b).map_err(Error::CodingError)?;
-                    merkle_tree_stores.set_store(st, store);
-                }
-                None => return Ok(None),
-            }
         }
+        self.read_tree_nodes(&mut merkle_tree_stores)?;
         Ok(Some(merkle_tree_stores))
     }
 
+    fn prune_height(
+        &self,
+        height: BlockHeight,
+        _batch: &mut Self::WriteBatch,
+    ) -> Result<()> {
+        // The MockDB doesn't write the diffs, only the results are removed
+        let key = Key::from(height.to_db_key())
+            .push(&"results".to_owned())
+            .map_err(Error::KeyError)?;
+        self.0.borrow_mut().remove(&key.to_string());
+        Ok(())
+    }
+
     fn read_subspace_val(&self, key: &Key) -> Result<Option<Vec<u8>>> {
         let key = Key::parse("subspace").map_err(Error::KeyError)?.join(key);
         Ok(self.0.borrow().get(&key.to_string()).cloned())
@@ -385,6 +481,15 @@ impl DB for MockDB {
         Ok(())
     }
 
+    fn exec_block_batch(
+        &mut self,
+        _batch: Self::WriteBatch,
+        _sync: bool,
+    ) -> Result<()> {
+        // Nothing to do - the MockDB is in memory only
+        Ok(())
+    }
+
     fn batch_write_subspace_val(
         &self,
         _batch: &mut Self::WriteBatch,
@@ -470,8 +575,8 @@ impl Iterator for PrefixIterator<MockIterator> {
                     .expect("Cannot convert from bytes to key string");
                 match key.strip_prefix(&self.db_prefix) {
                     Some(k) => {
-                        let gas = k.len() + val.len();
-                        Some((k.to_owned(), val.to_vec(), gas as _))
+                        let gas = storage_read_gas(k.len(), val.len());
+                        Some((k.to_owned(), val.to_vec(), gas))
                     }
                     None => self.next(),
                 }
@@ -3,6 +3,7 @@
 mod merkle_tree;
 #[cfg(any(test, feature = "testing"))]
 pub mod mockdb;
+pub mod read_cache;
 pub mod types;
 pub mod write_log;
 
@@ -28,14 +29,16 @@ use thiserror::Error;
 
 use super::parameters;
 use super::parameters::Parameters;
-use crate::ledger::gas::MIN_STORAGE_GAS;
-use crate::ledger::parameters::EpochDuration;
-use crate::ledger::storage::merkle_tree::{
-    Error as MerkleTreeError, MerkleRoot,
+use crate::ledger::gas::{
+    storage_read_gas, storage_write_gas, MIN_STORAGE_GAS,
 };
+use crate::ledger::parameters::EpochDuration;
+use crate::ledger::storage::read_cache::ReadCache;
 pub use crate::ledger::storage::merkle_tree::{
+    decode_node_key, encode_node_key, verify_existence_proof,
+    verify_non_existence_proof, Error as MerkleTreeError, MerkleRoot,
     MerkleTree, MerkleTreeStoresRead, MerkleTreeStoresWrite, Sha256Hasher,
-    StorageHasher, StoreType,
+    StorageHasher, StoreType, TrackedStore,
 };
 use crate::types::address::{Address, EstablishedAddressGen, InternalAddress};
 use crate::types::chain::{ChainId, CHAIN_ID_LENGTH};
@@ -49,6 +52,10 @@ use crate::types::time::DateTimeUtc;
 /// A result of a function that may fail
 pub type Result<T> = std::result::Result<T, Error>;
 
+/// The number of the last blocks whose diffs and results are retained by a
+/// node that isn't an archive node
+pub const RETAINED_HEIGHTS: u64 = 1_000;
+
 /// The storage data
 #[derive(Debug)]
 pub struct Storage<D, H>
@@ -66,6 +73,10 @@ where
     pub header: Option<Header>,
     /// The height of the committed block
     pub last_height: BlockHeight,
+    /// The time of the committed block, if any
+    pub last_block_time: Option<DateTimeUtc>,
+    /// The Merkle root of the committed block, if any
+    pub last_root: Option<MerkleRoot>,
     /// The epoch of the committed block
     pub last_epoch: Epoch,
     /// Minimum block height at which the next epoch may start
@@ -77,6 +88,18 @@ where
     /// Wrapper txs to be decrypted in the next block proposal
     #[cfg(feature = "ferveo-tpke")]
     pub tx_queue: TxQueue,
+    /// Sync the block's writes to disk on commit
+    pub sync_commit: bool,
+    /// Write the block to the DB in the background on commit
+    pub async_commit: bool,
+    /// Retain the state of every height, so that it can be queried with
+    /// proofs at any height. Otherwise, only the merkle tree of the last
+    /// block and the diffs and the results of the last [`RETAINED_HEIGHTS`]
+    /// blocks are kept.
+    pub archive: bool,
+    /// In-memory cache of the values of the hot storage keys in front of the
+    /// DB
+    pub read_cache: ReadCache,
 }
 
 /// The block storage data
@@ -113,6 +136,16 @@ pub enum Error {
     ProtobufCodingError(TmProtoError),
     #[error("Merkle tree at the height {height} is not stored")]
     NoMerkleTree { height: BlockHeight },
+    #[error(
+        "The state at the height {height} has been pruned, it's only \
+         available from an archive node"
+    )]
+    PrunedHeight { height: BlockHeight },
+    #[error(
+        "The DB schema version {found} is newer than the supported version \
+         {supported}"
+    )]
+    UnsupportedSchemaVersion { found: u64, supported: u64 },
 }
 
 /// The block's state as stored in the database.
@@ -161,6 +194,9 @@ pub struct BlockStateWrite<'a> {
     /// Wrapper txs to be decrypted in the next block proposal
     #[cfg(feature = "ferveo-tpke")]
     pub tx_queue: &'a TxQueue,
+    /// Keep the nodes of the merkle tree that are removed in the block, so
+    /// that the trees of the older heights can be restored
+    pub archive: bool,
 }
 
 /// A database backend.
@@ -182,18 +218,49 @@ pub trait DB: std::fmt::Debug {
     /// Read the last committed block's metadata
     fn read_last_block(&mut self) -> Result<Option<BlockStateRead>>;
 
-    /// Write block's metadata
-    fn write_block(&mut self, state: BlockStateWrite) -> Result<()>;
+    /// Write block's metadata into the given batch. The block is persisted
+    /// once the batch is executed.
+    fn write_block(
+        &mut self,
+        state: BlockStateWrite,
+        batch: &mut Self::WriteBatch,
+    ) -> Result<()>;
 
     /// Read the block header with the given height from the DB
     fn read_block_header(&self, height: BlockHeight) -> Result<Option<Header>>;
 
-    /// Read the merkle tree stores with the given height
+    /// Write the encoded results of the block with the given height into the
+    /// given batch
+    fn write_block_results(
+        &mut self,
+        height: BlockHeight,
+        results: &[u8],
+        batch: &mut Self::WriteBatch,
+    ) -> Result<()>;
+
+    /// Read the encoded results of the block with the given height, if any
+    fn read_block_results(
+        &self,
+        height: BlockHeight,
+    ) -> Result<Option<Vec<u8>>>;
+
+    /// Read the merkle tree stores with the given height, if its roots are
+    /// stored. The nodes removed from the tree are only kept by an archive
+    /// node, otherwise the stores are complete only at the last committed
+    /// height.
     fn read_merkle_tree_stores(
         &self,
         height: BlockHeight,
     ) -> Result<Option<MerkleTreeStoresRead>>;
 
+    /// Delete the diffs and the results of the block with the given height in
+    /// the given batch
+    fn prune_height(
+        &self,
+        height: BlockHeight,
+        batch: &mut Self::WriteBatch,
+    ) -> Result<()>;
+
     /// Read the latest value for account subspace key from the DB
     fn read_subspace_val(&self, key: &Key) -> Result<Option<Vec<u8>>>;
 
@@ -229,6 +296,32 @@ pub trait DB: std::fmt::Debug {
     /// Execute write batch.
     fn exec_batch(&mut self, batch: Self::WriteBatch) -> Result<()>;
 
+    /// Execute the write batch of a committed block. When `sync` is set, the
+    /// writes are synced to disk before returning, otherwise they're flushed
+    /// in the background.
+    fn exec_block_batch(
+        &mut self,
+        batch: Self::WriteBatch,
+        sync: bool,
+    ) -> Result<()>;
+
+    /// Start executing the write batch of a committed block in the
+    /// background. Any following access to the DB must wait for the write to
+    /// finish, so that the block's writes are never missed.
+    fn exec_block_batch_in_background(
+        &mut self,
+        batch: Self::WriteBatch,
+        sync: bool,
+    ) -> Result<()> {
+        self.exec_block_batch(batch, sync)
+    }
+
+    /// Wait for the write of the last committed block to finish, if it's being
+    /// executed in the background, and return its result.
+    fn wait_for_block_batch(&self) -> Result<()> {
+        Ok(())
+    }
+
     /// Batch write the value with the given height and account subspace key to
     /// the DB. Returns the size difference from previous value, if any, or
     /// the size of the value otherwise.
@@ -297,6 +390,8 @@ where
             block,
             header: None,
             last_height: BlockHeight(0),
+            last_block_time: None,
+            last_root: None,
             last_epoch: Epoch::default(),
             next_epoch_min_start_height: BlockHeight::default(),
             next_epoch_min_start_time: DateTimeUtc::now(),
@@ -305,6 +400,10 @@ where
             ),
             #[cfg(feature = "ferveo-tpke")]
             tx_queue: TxQueue::default(),
+            sync_commit: false,
+            async_commit: false,
+            archive: false,
+            read_cache: ReadCache::default(),
         }
     }
 
@@ -330,6 +429,11 @@ where
             self.block.epoch = epoch;
             self.block.pred_epochs = pred_epochs;
             self.last_height = height;
+            self.last_block_time = self
+                .db
+                .read_block_header(height)?
+                .and_then(|header| header.time.try_into().ok());
+            self.last_root = Some(self.block.tree.root());
             self.last_epoch = epoch;
             self.next_epoch_min_start_height = next_epoch_min_start_height;
             self.next_epoch_min_start_time = next_epoch_min_start_time;
@@ -338,6 +442,7 @@ where
             {
                 self.tx_queue = tx_queue;
             }
+            self.read_cache.clear();
             tracing::debug!("Loaded storage from DB");
         } else {
             tracing::info!("No state could be found");
@@ -346,17 +451,37 @@ where
     }
 
     /// Returns the Merkle root hash and the height of the committed block. If
-    /// no block exists, returns None.
+    /// no block exists, returns None. The state of a block that is being
+    /// applied, but hasn't been committed yet, is not included.
     pub fn get_state(&self) -> Option<(MerkleRoot, u64)> {
-        if self.block.height.0 != 0 {
-            Some((self.block.tree.root(), self.block.height.0))
-        } else {
-            None
-        }
+        self.last_root
+            .clone()
+            .map(|root| (root, self.last_height.0))
     }
 
     /// Persist the current block's state to the database
     pub fn commit(&mut self) -> Result<()> {
+        self.commit_with_batch(Self::batch())
+    }
+
+    /// Persist the current block's state to the database together with the
+    /// writes in the given batch, e.g. the block's storage changes from the
+    /// write log and its results. The batch is written atomically, so that
+    /// after an unclean shutdown the DB contains either the whole block or
+    /// none of it and a block replayed by Tendermint is not applied twice.
+    /// The write is synced to disk only if [`Storage::sync_commit`] is set.
+    ///
+    /// If [`Storage::async_commit`] is set, the batch is written in the
+    /// background and this returns as soon as the block's state is
+    /// committed in memory. The DB waits for the write before it's accessed
+    /// again, so queries never see a partially written block, and the next
+    /// block can't begin if the write has failed. If the node crashes or
+    /// shuts down because of a failed write, the DB is at the previous block
+    /// and Tendermint replays the missing block on restart.
+    pub fn commit_with_batch(
+        &mut self,
+        mut batch: D::WriteBatch,
+    ) -> Result<()> {
         let state = BlockStateWrite {
             merkle_tree_stores: self.block.tree.stores(),
             header: self.header.as_ref(),
@@ -369,10 +494,26 @@ where
             address_gen: &self.address_gen,
             #[cfg(feature = "ferveo-tpke")]
             tx_queue: &self.tx_queue,
+            archive: self.archive,
         };
-        self.db.write_block(state)?;
+        self.db.write_block(state, &mut batch)?;
+        if !self.archive && self.block.height.0 > RETAINED_HEIGHTS {
+            let pruned = BlockHeight(self.block.height.0 - RETAINED_HEIGHTS);
+            self.db.prune_height(pruned, &mut batch)?;
+        }
+        if self.async_commit {
+            self.db
+                .exec_block_batch_in_background(batch, self.sync_commit)?;
+        } else {
+            self.db.exec_block_batch(batch, self.sync_commit)?;
+        }
+        // The changed nodes of the tree have been persisted
+        self.block.tree.clear_changes();
         self.last_height = self.block.height;
-        self.header = None;
+        self.last_root = Some(self.block.tree.root());
+        if let Some(header) = self.header.take() {
+            self.last_block_time = header.time.try_into().ok();
+        }
         Ok(())
     }
 
@@ -384,7 +525,7 @@ where
     /// Check if the given key is present in storage. Returns the result and the
     /// gas cost.
     pub fn has_key(&self, key: &Key) -> Result<(bool, u64)> {
-        Ok((self.block.tree.has_key(key)?, key.len() as _))
+        Ok((self.block.tree.has_key(key)?, storage_read_gas(key.len(), 0)))
     }
 
     /// Returns a value from the specified subspace and the gas cost
@@ -394,13 +535,18 @@ where
         if !present {
             return Ok((None, gas));
         }
+        if let Some(v) = self.read_cache.get(key) {
+            let gas = storage_read_gas(key.len(), v.len());
+            return Ok((Some(v), gas));
+        }
 
         match self.db.read_subspace_val(key)? {
             Some(v) => {
-                let gas = key.len() + v.len();
-                Ok((Some(v), gas as _))
+                self.read_cache.put(key, &v);
+                let gas = storage_read_gas(key.len(), v.len());
+                Ok((Some(v), gas))
             }
-            None => Ok((None, key.len() as _)),
+            None => Ok((None, storage_read_gas(key.len(), 0))),
         }
     }
 
@@ -413,23 +559,31 @@ where
     ) -> Result<(Option<Vec<u8>>, u64)> {
         if height >= self.get_block_height().0 {
             self.read(key)
+        } else if self.is_pruned(height) {
+            Err(Error::PrunedHeight { height })
         } else {
             match self.db.read_subspace_val_with_height(key, height)? {
                 Some(v) => {
-                    let gas = key.len() + v.len();
-                    Ok((Some(v), gas as _))
+                    let gas = storage_read_gas(key.len(), v.len());
+                    Ok((Some(v), gas))
                 }
-                None => Ok((None, key.len() as _)),
+                None => Ok((None, storage_read_gas(key.len(), 0))),
             }
         }
     }
 
+    /// Check if the diffs of the blocks after the given height, which are
+    /// needed to read the state at the height, have been pruned
+    pub fn is_pruned(&self, height: BlockHeight) -> bool {
+        !self.archive && height.0 + RETAINED_HEIGHTS < self.last_height.0
+    }
+
     /// Returns a prefix iterator and the gas cost
     pub fn iter_prefix(
         &self,
         prefix: &Key,
     ) -> (<D as DBIter<'_>>::PrefixIter, u64) {
-        (self.db.iter_prefix(prefix), prefix.len() as _)
+        (self.db.iter_prefix(prefix), storage_read_gas(prefix.len(), 0))
     }
 
     /// Write a value to the specified subspace and returns the gas cost and the
@@ -443,10 +597,11 @@ where
         self.block.tree.update(key, value.clone())?;
 
         let len = value.as_ref().len();
-        let gas = key.len() + len;
+        let gas = storage_write_gas(key.len(), len);
+        self.read_cache.put(key, value.as_ref());
         let size_diff =
             self.db.write_subspace_val(self.last_height, key, value)?;
-        Ok((gas as _, size_diff))
+        Ok((gas, size_diff))
     }
 
     /// Delete the specified subspace and returns the gas cost and the size
@@ -455,11 +610,12 @@ where
         let mut deleted_bytes_len = 0;
         if self.has_key(key)?.0 {
             self.block.tree.delete(key)?;
+            self.read_cache.remove(key);
             deleted_bytes_len =
                 self.db.delete_subspace_val(self.last_height, key)?;
         }
-        let gas = key.len() + deleted_bytes_len as usize;
-        Ok((gas as _, deleted_bytes_len))
+        let gas = storage_write_gas(key.len(), 0);
+        Ok((gas, deleted_bytes_len))
     }
 
     /// Set the block header.
@@ -472,11 +628,15 @@ where
 
     /// Block data is in the Merkle tree as it's tracked by Tendermint in the
     /// block header. Hence, we don't update the tree when this is set.
+    ///
+    /// Fails if the last block was written to the DB in the background and
+    /// the write failed, see [`Storage::commit_with_batch`].
     pub fn begin_block(
         &mut self,
         hash: BlockHash,
         height: BlockHeight,
     ) -> Result<()> {
+        self.db.wait_for_block_batch()?;
         self.block.hash = hash;
         self.block.height = height;
         Ok(())
@@ -523,6 +683,9 @@ where
     ) -> Result<Proof> {
         if height >= self.get_block_height().0 {
             Ok(self.block.tree.get_existence_proof(key, value)?)
+        } else if !self.archive && height != self.last_height {
+            // Only the last tree is retained
+            Err(Error::NoMerkleTree { height })
         } else {
             match self.db.read_merkle_tree_stores(height)? {
                 Some(stores) => Ok(MerkleTree::<H>::new(stores)
@@ -540,6 +703,9 @@ where
     ) -> Result<Proof> {
         if height >= self.get_block_height().0 {
             Ok(self.block.tree.get_non_existence_proof(key)?)
+        } else if !self.archive && height != self.last_height {
+            // Only the last tree is retained
+            Err(Error::NoMerkleTree { height })
         } else {
             match self.db.read_merkle_tree_stores(height)? {
                 Some(stores) => Ok(MerkleTree::<H>::new(stores)
@@ -595,6 +761,24 @@ where
         }
     }
 
+    /// Write the encoded results of the current block into the batch to be
+    /// committed with the block
+    pub fn write_block_results(
+        &mut self,
+        results: &[u8],
+        batch: &mut D::WriteBatch,
+    ) -> Result<()> {
+        self.db.write_block_results(self.block.height, results, batch)
+    }
+
+    /// Read the encoded results of the block with the given height, if any
+    pub fn read_block_results(
+        &self,
+        height: BlockHeight,
+    ) -> Result<Option<Vec<u8>>> {
+        self.db.read_block_results(height)
+    }
+
     /// Initialize a new epoch when the current epoch is finished. Returns
     /// `true` on a new epoch.
     pub fn update_epoch(
@@ -669,9 +853,26 @@ where
         self.db.exec_batch(batch)
     }
 
+    /// Update the merkle tree with the value of the given account subspace
+    /// key, without writing it to the DB.
+    fn update_merkle_tree(
+        &mut self,
+        key: &Key,
+        value: impl AsRef<[u8]>,
+    ) -> Result<()> {
+        Ok(self.block.tree.update(key, value)?)
+    }
+
+    /// Delete the given account subspace key from the merkle tree, without
+    /// deleting it from the DB.
+    fn delete_from_merkle_tree(&mut self, key: &Key) -> Result<()> {
+        Ok(self.block.tree.delete(key)?)
+    }
+
     /// Batch write the value with the given height and account subspace key to
     /// the DB. Returns the size difference from previous value, if any, or
-    /// the size of the value otherwise.
+    /// the size of the value otherwise. The merkle tree is not updated, see
+    /// [`Storage::update_merkle_tree`].
     fn batch_write_subspace_val(
         &mut self,
         batch: &mut D::WriteBatch,
@@ -679,20 +880,21 @@ where
         value: impl AsRef<[u8]>,
     ) -> Result<i64> {
         let value = value.as_ref();
-        self.block.tree.update(key, value)?;
+        self.read_cache.put(key, value);
         self.db
             .batch_write_subspace_val(batch, self.block.height, key, value)
     }
 
     /// Batch delete the value with the given height and account subspace key
     /// from the DB. Returns the size of the removed value, if any, 0 if no
-    /// previous value was found.
+    /// previous value was found. The merkle tree is not updated, see
+    /// [`Storage::delete_from_merkle_tree`].
     fn batch_delete_subspace_val(
         &mut self,
         batch: &mut D::WriteBatch,
         key: &Key,
     ) -> Result<i64> {
-        self.block.tree.delete(key)?;
+        self.read_cache.remove(key);
         self.db
             .batch_delete_subspace_val(batch, self.block.height, key)
     }
@@ -732,6 +934,8 @@ pub mod testing {
                 block,
                 header: None,
                 last_height: BlockHeight(0),
+                last_block_time: None,
+                last_root: None,
                 last_epoch: Epoch::default(),
                 next_epoch_min_start_height: BlockHeight::default(),
                 next_epoch_min_start_time: DateTimeUtc::now(),
@@ -740,6 +944,10 @@ pub mod testing {
                 ),
                 #[cfg(feature = "ferveo-tpke")]
                 tx_queue: TxQueue::default(),
+                sync_commit: false,
+                async_commit: false,
+                archive: false,
+                read_cache: ReadCache::default(),
             }
         }
     }
@@ -819,7 +1027,10 @@ mod tests {
                 epoch_duration: epoch_duration.clone(),
                 max_expected_time_per_block: Duration::seconds(max_expected_time_per_block).into(),
                 vp_whitelist: vec![],
-                tx_whitelist: vec![]
+                tx_whitelist: vec![],
+                tx_write_size_limits: Default::default(),
+                fee_tokens: Default::default(),
+                inflation_params: Default::default(),
             };
             parameters.init_storage(&mut storage);
 
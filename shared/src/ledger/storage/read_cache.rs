@@ -0,0 +1,156 @@
+//! In-memory LRU cache of the values of the hot storage keys, which are read
+//! by most txs and VPs: the token balances, the VPs and the protocol
+//! parameters. The cache is in front of the DB and the storage writes through
+//! it, including when the block's changes are committed from the write log,
+//! so that it never has a stale value.
+
+use std::collections::hash_map::RandomState;
+use std::num::NonZeroUsize;
+use std::sync::Mutex;
+
+use clru::{CLruCache, CLruCacheConfig, WeightScale};
+
+use crate::ledger::parameters::storage as parameters_storage;
+use crate::types::storage::Key;
+use crate::types::token;
+
+/// The default maximum size of the cached values in bytes
+pub const DEFAULT_READ_CACHE_BYTES: usize = 64 * 1024 * 1024;
+
+/// In-memory LRU cache of the values of the hot storage keys. Thread-safe.
+#[derive(Debug)]
+pub struct ReadCache {
+    /// The cached values, `None` when the cache is disabled
+    values: Option<Mutex<ValueCache>>,
+}
+
+/// LRU cache of the values by their keys
+type ValueCache = CLruCache<Key, Vec<u8>, RandomState, ValueCacheScale>;
+
+/// Configures the cache scale of values that limits the maximum capacity
+/// of the cache (CLruCache::len + CLruCache::weight <= CLruCache::capacity).
+#[derive(Debug)]
+struct ValueCacheScale;
+
+impl WeightScale<Key, Vec<u8>> for ValueCacheScale {
+    fn weight(&self, _key: &Key, value: &Vec<u8>) -> usize {
+        // We only want to limit the size of the values, not the number of
+        // elements, so we subtract 1 from it to negate the increment of the
+        // cache length.
+        value.len().saturating_sub(1)
+    }
+}
+
+impl ReadCache {
+    /// Create a cache of the values with the given maximum size in bytes. The
+    /// cache is disabled with a zero size.
+    pub fn new(max_bytes: usize) -> Self {
+        let values = NonZeroUsize::new(max_bytes).map(|max_bytes| {
+            Mutex::new(CLruCache::with_config(
+                CLruCacheConfig::new(max_bytes).with_scale(ValueCacheScale),
+            ))
+        });
+        Self { values }
+    }
+
+    /// Check if the value of the given key is cached when it's read or
+    /// written, i.e. if it's a token balance, a VP or a protocol parameter.
+    pub fn is_hot_key(key: &Key) -> bool {
+        !key.segments.is_empty()
+            && (token::is_any_token_balance_key(key).is_some()
+                || key.is_validity_predicate().is_some()
+                || parameters_storage::is_parameter_key(key))
+    }
+
+    /// Get the cached value of the given key, if any
+    pub fn get(&self, key: &Key) -> Option<Vec<u8>> {
+        let values = self.values.as_ref()?;
+        values.lock().unwrap().get(key).cloned()
+    }
+
+    /// Cache the value of the given key, if it's a hot key. The value
+    /// replaces the key's cached value, if any.
+    pub fn put(&self, key: &Key, value: &[u8]) {
+        if let Some(values) = self.values.as_ref() {
+            if Self::is_hot_key(key) {
+                let mut values = values.lock().unwrap();
+                // A value that's larger than the cache is not cached, so the
+                // key's previous value has to be removed
+                if values.put_with_weight(key.clone(), value.to_vec()).is_err()
+                {
+                    values.pop(key);
+                }
+            }
+        }
+    }
+
+    /// Remove the cached value of the given key, if any
+    pub fn remove(&self, key: &Key) {
+        if let Some(values) = self.values.as_ref() {
+            values.lock().unwrap().pop(key);
+        }
+    }
+
+    /// Remove all the cached values
+    pub fn clear(&self) {
+        if let Some(values) = self.values.as_ref() {
+            values.lock().unwrap().clear();
+        }
+    }
+}
+
+impl Default for ReadCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_READ_CACHE_BYTES)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::address::testing::{
+        established_address_1, established_address_2,
+    };
+
+    /// Test that only the values of the hot keys are cached and that a
+    /// written value replaces the cached one
+    #[test]
+    fn test_read_cache() {
+        let cache = ReadCache::default();
+        let balance_key = token::balance_key(
+            &established_address_1(),
+            &established_address_2(),
+        );
+        let vp_key = Key::validity_predicate(&established_address_1());
+        let other_key = Key::parse("other").unwrap();
+        for key in [&balance_key, &vp_key, &other_key] {
+            cache.put(key, &[1]);
+        }
+        assert_eq!(cache.get(&balance_key), Some(vec![1]));
+        assert_eq!(cache.get(&vp_key), Some(vec![1]));
+        assert_eq!(cache.get(&other_key), None);
+
+        cache.put(&balance_key, &[2]);
+        assert_eq!(cache.get(&balance_key), Some(vec![2]));
+        cache.remove(&vp_key);
+        assert_eq!(cache.get(&vp_key), None);
+        cache.clear();
+        assert_eq!(cache.get(&balance_key), None);
+    }
+
+    /// Test that a value larger than the cache replaces the cached value of
+    /// its key without being cached
+    #[test]
+    fn test_read_cache_too_large_value() {
+        let cache = ReadCache::new(4);
+        let vp_key = Key::validity_predicate(&established_address_1());
+        cache.put(&vp_key, &[1]);
+        assert_eq!(cache.get(&vp_key), Some(vec![1]));
+        cache.put(&vp_key, &[2; 8]);
+        assert_eq!(cache.get(&vp_key), None);
+
+        let disabled = ReadCache::new(0);
+        disabled.put(&vp_key, &[1]);
+        assert_eq!(disabled.get(&vp_key), None);
+    }
+}
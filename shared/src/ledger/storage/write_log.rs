@@ -2,11 +2,14 @@
 //! before they are committed to the ledger's storage.
 
 use std::collections::{BTreeSet, HashMap, HashSet};
+use std::sync::{Arc, Mutex};
 
+use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
 use thiserror::Error;
 
 use crate::ledger;
-use crate::ledger::storage::{Storage, StorageHasher};
+use crate::ledger::gas::{storage_read_gas, storage_write_gas};
+use crate::ledger::storage::{MerkleRoot, Storage, StorageHasher};
 use crate::types::address::{Address, EstablishedAddressGen};
 use crate::types::ibc::IbcEvent;
 use crate::types::storage;
@@ -27,6 +30,10 @@ pub enum Error {
     DeleteVp,
     #[error("Trying to write a temporary value after deleting")]
     WriteTempAfterDelete,
+    #[error("The key length {len} exceeds the maximum of {max} bytes")]
+    KeyTooLong { len: u64, max: u64 },
+    #[error("The value size {len} exceeds the maximum of {max} bytes")]
+    ValueTooLarge { len: u64, max: u64 },
 }
 
 /// Result for functions that may fail
@@ -57,32 +64,168 @@ pub enum StorageModification {
     },
 }
 
+/// The limits of the sizes of the keys and values that a transaction may write
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshSchema,
+)]
+pub struct SizeLimits {
+    /// Maximum length of a key in bytes
+    pub max_key_bytes: u64,
+    /// Maximum size of a value in bytes
+    pub max_value_bytes: u64,
+}
+
+impl Default for SizeLimits {
+    fn default() -> Self {
+        Self {
+            max_key_bytes: 1024,
+            max_value_bytes: 1024 * 1024,
+        }
+    }
+}
+
+/// A change of a storage value in a block, derived from the block write log
+#[derive(Clone, Debug, PartialEq, Eq, BorshSerialize, BorshDeserialize)]
+pub struct StorageChange {
+    /// The changed storage key
+    pub key: storage::Key,
+    /// The value before the block, if any
+    pub old_value: Option<Vec<u8>>,
+    /// The value after the block, `None` if it has been deleted
+    pub new_value: Option<Vec<u8>>,
+}
+
 /// The write log storage
 #[derive(Debug, Clone)]
 pub struct WriteLog {
     /// The generator of established addresses
     address_gen: Option<EstablishedAddressGen>,
+    /// The version of the write log when the address generator was last used
+    address_gen_version: Option<u64>,
     /// All the storage modification accepted by validity predicates are stored
-    /// in block write-log, before being committed to the storage
-    block_write_log: HashMap<storage::Key, StorageModification>,
+    /// in block write-log, before being committed to the storage. It's shared
+    /// with the speculative copies of the write log, see
+    /// [`WriteLog::speculate`].
+    block_write_log: Arc<HashMap<storage::Key, StorageModification>>,
+    /// The versions of the write log when the keys in the block write-log
+    /// were last committed
+    block_versions: HashMap<storage::Key, u64>,
+    /// The version of the write log, incremented at the end of every
+    /// transaction
+    version: u64,
     /// The storage modifications for the current transaction
     tx_write_log: HashMap<storage::Key, StorageModification>,
-    /// The IBC event for the current transaction
-    ibc_event: Option<IbcEvent>,
+    /// The keys in the current transaction's write log indexed by the
+    /// addresses that they contain
+    tx_keys_by_address: HashMap<Address, BTreeSet<storage::Key>>,
+    /// The addresses of the accounts initialized in the current transaction,
+    /// in the order of their initialization
+    tx_initialized_accounts: Vec<Address>,
+    /// The number of the accounts in `tx_initialized_accounts` initialized by
+    /// the already executed inner transactions of a batch
+    tx_sealed_accounts: usize,
+    /// The accounts initialized by an already executed inner transaction of
+    /// a batch and modified by a following one, whose validity predicates
+    /// must be triggered
+    tx_accounts_to_verify: HashSet<Address>,
+    /// The IBC events emitted by the current transaction, in the order of
+    /// their emission
+    ibc_events: Vec<IbcEvent>,
+    /// The limits of the sizes of the writes from transactions
+    size_limits: SizeLimits,
+    /// The keys read by the current transaction from the block write-log or
+    /// from the storage. Only recorded in a speculative copy.
+    tx_reads: Option<Arc<Mutex<HashSet<storage::Key>>>>,
+}
+
+/// The changes of a transaction executed against a speculative copy of the
+/// write log, together with the keys that it read. See
+/// [`WriteLog::speculate`].
+#[derive(Debug)]
+pub struct SpeculativeTx {
+    /// The version of the write log that the copy was made at
+    version: u64,
+    /// The keys read by the transaction
+    reads: HashSet<storage::Key>,
+    /// The storage modifications of the transaction
+    tx_write_log: HashMap<storage::Key, StorageModification>,
+    /// The modified keys indexed by the addresses that they contain
+    tx_keys_by_address: HashMap<Address, BTreeSet<storage::Key>>,
+    /// The addresses of the accounts initialized by the transaction
+    tx_initialized_accounts: Vec<Address>,
+    /// The initialized accounts whose validity predicates must be triggered
+    tx_accounts_to_verify: HashSet<Address>,
+    /// The IBC events emitted by the transaction that haven't been taken
+    ibc_events: Vec<IbcEvent>,
+    /// The address generator, if the transaction used it
+    address_gen: Option<EstablishedAddressGen>,
 }
 
 impl Default for WriteLog {
     fn default() -> Self {
         Self {
             address_gen: None,
-            block_write_log: HashMap::with_capacity(100_000),
+            address_gen_version: None,
+            block_write_log: Arc::new(HashMap::with_capacity(100_000)),
+            block_versions: HashMap::new(),
+            version: 0,
             tx_write_log: HashMap::with_capacity(100),
-            ibc_event: None,
+            tx_keys_by_address: HashMap::new(),
+            tx_initialized_accounts: Vec::new(),
+            tx_sealed_accounts: 0,
+            tx_accounts_to_verify: HashSet::new(),
+            ibc_events: Vec::new(),
+            size_limits: SizeLimits::default(),
+            tx_reads: None,
         }
     }
 }
 
 impl WriteLog {
+    /// Set the limits of the sizes of the writes from transactions
+    pub fn set_size_limits(&mut self, size_limits: SizeLimits) {
+        self.size_limits = size_limits;
+    }
+
+    /// Check that a key and a value of the given size that a transaction is
+    /// about to write are within the [`SizeLimits`]. Fails with
+    /// [`Error::KeyTooLong`] or [`Error::ValueTooLarge`] otherwise.
+    pub fn check_size(
+        &self,
+        key: &storage::Key,
+        value_len: usize,
+    ) -> Result<()> {
+        let SizeLimits {
+            max_key_bytes,
+            max_value_bytes,
+        } = self.size_limits;
+        let key_len = key.len() as u64;
+        if key_len > max_key_bytes {
+            return Err(Error::KeyTooLong {
+                len: key_len,
+                max: max_key_bytes,
+            });
+        }
+        let value_len = value_len as u64;
+        if value_len > max_value_bytes {
+            return Err(Error::ValueTooLarge {
+                len: value_len,
+                max: max_value_bytes,
+            });
+        }
+        Ok(())
+    }
+
     /// Read a value at the given key and return the value and the gas cost,
     /// returns [`None`] if the key is not present in the write log
     pub fn read(
@@ -92,24 +235,27 @@ impl WriteLog {
         // try to read from tx write log first
         match self.tx_write_log.get(key).or_else(|| {
             // if not found, then try to read from block write log
+            self.record_read(key);
             self.block_write_log.get(key)
         }) {
             Some(v) => {
                 let gas = match v {
                     StorageModification::Write { ref value } => {
-                        key.len() + value.len()
+                        storage_read_gas(key.len(), value.len())
+                    }
+                    StorageModification::Delete => {
+                        storage_read_gas(key.len(), 0)
                     }
-                    StorageModification::Delete => key.len(),
                     StorageModification::InitAccount { ref vp } => {
-                        key.len() + vp.len()
+                        storage_read_gas(key.len(), vp.len())
                     }
                     StorageModification::Temp { ref value } => {
-                        key.len() + value.len()
+                        storage_read_gas(key.len(), value.len())
                     }
                 };
-                (Some(v), gas as _)
+                (Some(v), gas)
             }
-            None => (None, key.len() as _),
+            None => (None, storage_read_gas(key.len(), 0)),
         }
     }
 
@@ -120,24 +266,26 @@ impl WriteLog {
         &self,
         key: &storage::Key,
     ) -> (Option<&StorageModification>, u64) {
-        // try to read from tx write log first
+        self.record_read(key);
         match self.block_write_log.get(key) {
             Some(v) => {
                 let gas = match v {
                     StorageModification::Write { ref value } => {
-                        key.len() + value.len()
+                        storage_read_gas(key.len(), value.len())
+                    }
+                    StorageModification::Delete => {
+                        storage_read_gas(key.len(), 0)
                     }
-                    StorageModification::Delete => key.len(),
                     StorageModification::InitAccount { ref vp } => {
-                        key.len() + vp.len()
+                        storage_read_gas(key.len(), vp.len())
                     }
                     StorageModification::Temp { ref value } => {
-                        key.len() + value.len()
+                        storage_read_gas(key.len(), value.len())
                     }
                 };
-                (Some(v), gas as _)
+                (Some(v), gas)
             }
-            None => (None, key.len() as _),
+            None => (None, storage_read_gas(key.len(), 0)),
         }
     }
 
@@ -152,7 +300,8 @@ impl WriteLog {
         value: Vec<u8>,
     ) -> Result<(u64, i64)> {
         let len = value.len();
-        let gas = key.len() + len;
+        let gas = storage_write_gas(key.len(), len);
+        self.index_key(key);
         let size_diff = match self
             .tx_write_log
             .insert(key.clone(), StorageModification::Write { value })
@@ -173,7 +322,7 @@ impl WriteLog {
             // the previous value exists on the storage
             None => len as i64,
         };
-        Ok((gas as _, size_diff))
+        Ok((gas, size_diff))
     }
 
     /// Write a key and a value and return the gas cost and the size difference
@@ -187,7 +336,8 @@ impl WriteLog {
         value: Vec<u8>,
     ) -> Result<(u64, i64)> {
         let len = value.len();
-        let gas = key.len() + len;
+        let gas = storage_write_gas(key.len(), len);
+        self.index_key(key);
         let size_diff = match self
             .tx_write_log
             .insert(key.clone(), StorageModification::Temp { value })
@@ -210,7 +360,7 @@ impl WriteLog {
             // the previous value exists on the storage
             None => len as i64,
         };
-        Ok((gas as _, size_diff))
+        Ok((gas, size_diff))
     }
 
     /// Delete a key and its value, and return the gas cost and the size
@@ -221,6 +371,7 @@ impl WriteLog {
         if key.is_validity_predicate().is_some() {
             return Err(Error::DeleteVp);
         }
+        self.index_key(key);
         let size_diff = match self
             .tx_write_log
             .insert(key.clone(), StorageModification::Delete)
@@ -237,8 +388,8 @@ impl WriteLog {
             // storage
             None => 0,
         };
-        let gas = key.len() + size_diff as usize;
-        Ok((gas as _, -size_diff))
+        let gas = storage_write_gas(key.len(), 0);
+        Ok((gas, -size_diff))
     }
 
     /// Initialize a new account and return the gas cost.
@@ -251,22 +402,26 @@ impl WriteLog {
         // the generator. Otherwise, we create a new copy from the storage
         let address_gen =
             self.address_gen.get_or_insert(storage_address_gen.clone());
+        self.address_gen_version = Some(self.version);
         let addr =
             address_gen.generate_address("TODO more randomness".as_bytes());
         let key = storage::Key::validity_predicate(&addr);
-        let gas = (key.len() + vp.len()) as _;
+        let gas = storage_write_gas(key.len(), vp.len());
+        self.index_key(&key);
         self.tx_write_log
             .insert(key, StorageModification::InitAccount { vp });
+        self.tx_initialized_accounts.push(addr.clone());
         (addr, gas)
     }
 
-    /// Set an IBC event and return the gas cost.
-    pub fn set_ibc_event(&mut self, event: IbcEvent) -> u64 {
+    /// Add an IBC event emitted by the current transaction and return the gas
+    /// cost.
+    pub fn emit_ibc_event(&mut self, event: IbcEvent) -> u64 {
         let len = event
             .attributes
             .iter()
             .fold(0, |acc, (k, v)| acc + k.len() + v.len());
-        self.ibc_event = Some(event);
+        self.ibc_events.push(event);
         len as _
     }
 
@@ -277,6 +432,58 @@ impl WriteLog {
         self.tx_write_log.keys().cloned().collect()
     }
 
+    /// Get the number of the storage keys changed in the current transaction
+    /// that update the Merkle tree when they're committed, i.e. without the
+    /// temporary writes.
+    pub fn num_merkle_tree_updates(&self) -> usize {
+        self.tx_write_log
+            .values()
+            .filter(|modification| {
+                !matches!(modification, StorageModification::Temp { .. })
+            })
+            .count()
+    }
+
+    /// Get the storage keys changed in the current transaction that contain
+    /// the given address, without going through all the changed keys.
+    pub fn get_keys_of(
+        &self,
+        addr: &Address,
+    ) -> impl Iterator<Item = &storage::Key> {
+        self.tx_keys_by_address.get(addr).into_iter().flatten()
+    }
+
+    /// Get the structured entries of the given storage keys changed in the
+    /// current transaction, with the type of each change. A key that's not in
+    /// the transaction's write log is looked up in the block's write log.
+    pub fn get_changed_keys(
+        &self,
+        keys: &BTreeSet<storage::Key>,
+    ) -> Vec<storage::ChangedKey> {
+        keys.iter()
+            .map(|key| {
+                let change = match self.tx_write_log.get(key).or_else(|| {
+                    self.record_read(key);
+                    self.block_write_log.get(key)
+                }) {
+                    Some(StorageModification::Delete) => {
+                        storage::ChangeType::Delete
+                    }
+                    Some(StorageModification::InitAccount { .. }) => {
+                        storage::ChangeType::InitAccount
+                    }
+                    Some(StorageModification::Temp { .. }) => {
+                        storage::ChangeType::Temp
+                    }
+                    Some(StorageModification::Write { .. }) | None => {
+                        storage::ChangeType::Write
+                    }
+                };
+                storage::ChangedKey::new(key.clone(), change)
+            })
+            .collect()
+    }
+
     /// Get the storage keys changed in the current transaction (left) and
     /// the addresses of accounts initialized in the current transaction
     /// (right). The first vector excludes keys of validity predicates of
@@ -296,30 +503,42 @@ impl WriteLog {
         })
     }
 
-    /// Get the addresses of accounts initialized in the current transaction.
+    /// Get the addresses of accounts initialized in the current transaction,
+    /// in the order of their initialization.
     pub fn get_initialized_accounts(&self) -> Vec<Address> {
-        self.tx_write_log
-            .iter()
-            .filter_map(|(key, value)| {
-                match (key.is_validity_predicate(), value) {
-                    (
-                        Some(address),
-                        StorageModification::InitAccount { .. },
-                    ) => Some(address.clone()),
-                    _ => None,
-                }
-            })
-            .collect()
+        self.tx_initialized_accounts.clone()
+    }
+
+    /// End an inner transaction of a batch. The accounts that it initialized
+    /// are owned by it, so that the following inner transactions cannot
+    /// modify them without triggering their validity predicates.
+    ///
+    /// The validity predicate of such an account is only triggered if a key
+    /// with its address is written or deleted by a following inner
+    /// transaction. An account that isn't modified afterwards is not verified
+    /// at all, the same as an account initialized by a standalone
+    /// transaction. When it's triggered, the validity predicate validates
+    /// all the changes of the batch, including those made by the inner
+    /// transaction that initialized the account, against the state before
+    /// the batch, in which the account doesn't exist yet.
+    pub fn end_inner_tx(&mut self) {
+        self.tx_sealed_accounts = self.tx_initialized_accounts.len();
+    }
+
+    /// Check if the account with the given address has been initialized in
+    /// the current transaction.
+    pub fn is_initialized_account(&self, addr: &Address) -> bool {
+        self.tx_initialized_accounts.contains(addr)
     }
 
-    /// Take the IBC event of the current transaction
-    pub fn take_ibc_event(&mut self) -> Option<IbcEvent> {
-        self.ibc_event.take()
+    /// Take the IBC events of the current transaction
+    pub fn take_ibc_events(&mut self) -> Vec<IbcEvent> {
+        std::mem::take(&mut self.ibc_events)
     }
 
-    /// Get the IBC event of the current transaction
-    pub fn get_ibc_event(&self) -> Option<&IbcEvent> {
-        self.ibc_event.as_ref()
+    /// Get the IBC events of the current transaction
+    pub fn get_ibc_events(&self) -> &[IbcEvent] {
+        &self.ibc_events
     }
 
     /// Commit the current transaction's write log to the block when it's
@@ -333,13 +552,141 @@ impl WriteLog {
             &mut self.tx_write_log,
             HashMap::with_capacity(100),
         );
-        self.block_write_log.extend(tx_write_log);
+        for key in tx_write_log.keys() {
+            self.block_versions.insert(key.clone(), self.version);
+        }
+        Arc::make_mut(&mut self.block_write_log).extend(tx_write_log);
+        self.tx_keys_by_address.clear();
+        self.tx_initialized_accounts.clear();
+        self.tx_sealed_accounts = 0;
+        self.tx_accounts_to_verify.clear();
+        self.version += 1;
     }
 
     /// Drop the current transaction's write log when it's declined by any of
     /// the triggered validity predicates. Starts a new transaction write log.
     pub fn drop_tx(&mut self) {
         self.tx_write_log.clear();
+        self.tx_keys_by_address.clear();
+        self.tx_initialized_accounts.clear();
+        self.tx_sealed_accounts = 0;
+        self.tx_accounts_to_verify.clear();
+        self.ibc_events.clear();
+        self.version += 1;
+    }
+
+    /// Make a copy of the write log for executing a transaction speculatively
+    /// against the current block write-log, e.g. in parallel with other
+    /// transactions. The copy records the keys that the transaction reads, so
+    /// that its changes can be merged later with
+    /// [`WriteLog::merge_speculative_tx`] if none of them has been changed in
+    /// the meantime. Must be called between transactions.
+    pub fn speculate(&self) -> Self {
+        debug_assert!(
+            self.tx_write_log.is_empty(),
+            "A speculative copy must be made between transactions"
+        );
+        Self {
+            address_gen: self.address_gen.clone(),
+            address_gen_version: None,
+            block_write_log: self.block_write_log.clone(),
+            block_versions: HashMap::new(),
+            version: self.version,
+            tx_write_log: HashMap::with_capacity(100),
+            tx_keys_by_address: HashMap::new(),
+            tx_initialized_accounts: Vec::new(),
+            tx_sealed_accounts: 0,
+            tx_accounts_to_verify: HashSet::new(),
+            ibc_events: Vec::new(),
+            size_limits: self.size_limits,
+            tx_reads: Some(Default::default()),
+        }
+    }
+
+    /// Take the changes of the transaction executed against this speculative
+    /// copy of the write log
+    pub fn into_speculative_tx(self) -> SpeculativeTx {
+        let reads = self
+            .tx_reads
+            .map(|reads| std::mem::take(&mut *reads.lock().unwrap()))
+            .unwrap_or_default();
+        // The generator is only taken if the transaction used it
+        let address_gen = self
+            .address_gen
+            .filter(|_| self.address_gen_version == Some(self.version));
+        SpeculativeTx {
+            version: self.version,
+            reads,
+            tx_write_log: self.tx_write_log,
+            tx_keys_by_address: self.tx_keys_by_address,
+            tx_initialized_accounts: self.tx_initialized_accounts,
+            tx_accounts_to_verify: self.tx_accounts_to_verify,
+            ibc_events: self.ibc_events,
+            address_gen,
+        }
+    }
+
+    /// Make the changes of a transaction executed speculatively the changes of
+    /// the current transaction, as if it has been executed against this write
+    /// log now. Returns `false` without any changes if the transaction
+    /// conflicts with the transactions ended since the speculative copy was
+    /// made, i.e. if it read a key that they committed or if both used the
+    /// address generator, or if the current transaction has any changes. A
+    /// conflicting transaction must be executed again.
+    pub fn merge_speculative_tx(&mut self, tx: SpeculativeTx) -> bool {
+        if !self.tx_write_log.is_empty()
+            || !self.tx_initialized_accounts.is_empty()
+            || !self.ibc_events.is_empty()
+        {
+            return false;
+        }
+        let changed_since = |version: Option<u64>| {
+            matches!(version, Some(v) if v >= tx.version)
+        };
+        if tx.address_gen.is_some() && changed_since(self.address_gen_version) {
+            return false;
+        }
+        if tx
+            .reads
+            .iter()
+            .any(|key| changed_since(self.block_versions.get(key).copied()))
+        {
+            return false;
+        }
+        self.tx_write_log = tx.tx_write_log;
+        self.tx_keys_by_address = tx.tx_keys_by_address;
+        self.tx_initialized_accounts = tx.tx_initialized_accounts;
+        self.tx_accounts_to_verify = tx.tx_accounts_to_verify;
+        self.ibc_events = tx.ibc_events;
+        if let Some(address_gen) = tx.address_gen {
+            self.address_gen = Some(address_gen);
+            self.address_gen_version = Some(self.version);
+        }
+        true
+    }
+
+    /// Add a key changed by the current transaction to the index of the
+    /// changed keys by the addresses that they contain
+    fn index_key(&mut self, key: &storage::Key) {
+        let sealed_accounts =
+            &self.tx_initialized_accounts[..self.tx_sealed_accounts];
+        for addr in key.find_addresses() {
+            if sealed_accounts.contains(&addr) {
+                self.tx_accounts_to_verify.insert(addr.clone());
+            }
+            self.tx_keys_by_address
+                .entry(addr)
+                .or_default()
+                .insert(key.clone());
+        }
+    }
+
+    /// Record a key read by the current transaction, if the reads are
+    /// recorded
+    fn record_read(&self, key: &storage::Key) {
+        if let Some(reads) = &self.tx_reads {
+            reads.lock().unwrap().insert(key.clone());
+        }
     }
 
     /// Commit the current block's write log to the storage. Starts a new block
@@ -355,39 +702,122 @@ impl WriteLog {
         H: StorageHasher,
     {
         let mut batch = Storage::<DB, H>::batch();
+        self.commit_block_to_merkle_tree(storage)?;
+        self.commit_block_to_batch(storage, &mut batch)?;
+        storage.exec_batch(batch).map_err(Error::StorageError)
+    }
+
+    /// Apply the current block's write log to the storage's Merkle tree and
+    /// return its new root. Only the in-memory tree is updated, so the root
+    /// of the block is known before its changes are written to the DB with
+    /// [`WriteLog::commit_block_to_batch`], which must follow.
+    pub fn commit_block_to_merkle_tree<DB, H>(
+        &self,
+        storage: &mut Storage<DB, H>,
+    ) -> Result<MerkleRoot>
+    where
+        DB: 'static
+            + ledger::storage::DB
+            + for<'iter> ledger::storage::DBIter<'iter>,
+        H: StorageHasher,
+    {
+        for (key, entry) in self.block_write_log.iter() {
+            match entry {
+                StorageModification::Write { value } => {
+                    storage.update_merkle_tree(key, value)
+                }
+                StorageModification::Delete => {
+                    storage.delete_from_merkle_tree(key)
+                }
+                StorageModification::InitAccount { vp } => {
+                    storage.update_merkle_tree(key, vp)
+                }
+                // temporary value isn't persisted
+                StorageModification::Temp { .. } => continue,
+            }
+            .map_err(Error::StorageError)?;
+        }
+        Ok(storage.merkle_root())
+    }
+
+    /// Commit the current block's write log into the given batch. The
+    /// storage's Merkle tree must already be updated with the write log by
+    /// [`WriteLog::commit_block_to_merkle_tree`]. The changes are persisted
+    /// once the batch is executed, e.g. with [`Storage::commit_with_batch`].
+    pub fn commit_block_to_batch<DB, H>(
+        &mut self,
+        storage: &mut Storage<DB, H>,
+        batch: &mut DB::WriteBatch,
+    ) -> Result<()>
+    where
+        DB: 'static
+            + ledger::storage::DB
+            + for<'iter> ledger::storage::DBIter<'iter>,
+        H: StorageHasher,
+    {
         for (key, entry) in self.block_write_log.iter() {
             match entry {
                 StorageModification::Write { value } => {
                     storage
-                        .batch_write_subspace_val(
-                            &mut batch,
-                            key,
-                            value.clone(),
-                        )
+                        .batch_write_subspace_val(batch, key, value.clone())
                         .map_err(Error::StorageError)?;
                 }
                 StorageModification::Delete => {
                     storage
-                        .batch_delete_subspace_val(&mut batch, key)
+                        .batch_delete_subspace_val(batch, key)
                         .map_err(Error::StorageError)?;
                 }
                 StorageModification::InitAccount { vp } => {
                     storage
-                        .batch_write_subspace_val(&mut batch, key, vp.clone())
+                        .batch_write_subspace_val(batch, key, vp.clone())
                         .map_err(Error::StorageError)?;
                 }
                 // temporary value isn't persisted
                 StorageModification::Temp { .. } => {}
             }
         }
-        storage.exec_batch(batch).map_err(Error::StorageError)?;
         if let Some(address_gen) = self.address_gen.take() {
             storage.address_gen = address_gen
         }
-        self.block_write_log.clear();
+        Arc::make_mut(&mut self.block_write_log).clear();
+        self.block_versions.clear();
         Ok(())
     }
 
+    /// Get the changes of the storage values in the current block's write log,
+    /// ordered by their keys. The old values are read from the storage, so
+    /// this must be called before the block is committed. Temporary values
+    /// are not included.
+    pub fn block_changes<DB, H>(
+        &self,
+        storage: &Storage<DB, H>,
+    ) -> Result<Vec<StorageChange>>
+    where
+        DB: 'static
+            + ledger::storage::DB
+            + for<'iter> ledger::storage::DBIter<'iter>,
+        H: StorageHasher,
+    {
+        let mut changes = Vec::with_capacity(self.block_write_log.len());
+        for (key, entry) in self.block_write_log.iter() {
+            let new_value = match entry {
+                StorageModification::Write { value } => Some(value.clone()),
+                StorageModification::Delete => None,
+                StorageModification::InitAccount { vp } => Some(vp.clone()),
+                StorageModification::Temp { .. } => continue,
+            };
+            let (old_value, _gas) =
+                storage.read(key).map_err(Error::StorageError)?;
+            changes.push(StorageChange {
+                key: key.clone(),
+                old_value,
+                new_value,
+            });
+        }
+        changes.sort_by(|a, b| a.key.cmp(&b.key));
+        Ok(changes)
+    }
+
     /// Get the verifiers set whose validity predicates should validate the
     /// current transaction changes and the storage keys that have been
     /// modified created, updated and deleted via the write log.
@@ -399,27 +829,28 @@ impl WriteLog {
         verifiers_from_tx: &BTreeSet<Address>,
     ) -> (BTreeSet<Address>, BTreeSet<storage::Key>) {
         let changed_keys: BTreeSet<storage::Key> = self.get_keys();
-        let initialized_accounts = self.get_initialized_accounts();
         let mut verifiers = verifiers_from_tx.clone();
-
-        // get changed keys grouped by the address
-        for key in changed_keys.iter() {
-            for addr in &key.find_addresses() {
-                if verifiers_from_tx.contains(addr)
-                    || initialized_accounts.contains(addr)
-                {
-                    // We can skip this when the address has been added from the
-                    // Tx above.
-                    // Also skip if it's an address of a newly initialized
-                    // account, because anything can be written into an
-                    // account's storage in the same tx in which it's
-                    // initialized (there is no VP in the state prior to tx
-                    // execution).
-                    continue;
-                }
-                // Add the address as a verifier
-                verifiers.insert(addr.clone());
+        let initialized_accounts: HashSet<&Address> =
+            self.tx_initialized_accounts.iter().collect();
+
+        // the changed keys are already grouped by the address
+        for addr in self.tx_keys_by_address.keys() {
+            if verifiers_from_tx.contains(addr)
+                || (initialized_accounts.contains(addr)
+                    && !self.tx_accounts_to_verify.contains(addr))
+            {
+                // We can skip this when the address has been added from the
+                // Tx above.
+                // Also skip if it's an address of a newly initialized
+                // account, because anything can be written into an
+                // account's storage in the same tx in which it's
+                // initialized (there is no VP in the state prior to tx
+                // execution), unless it has been modified by another inner
+                // tx of a batch than the one that initialized it.
+                continue;
             }
+            // Add the address as a verifier
+            verifiers.insert(addr.clone());
         }
         (verifiers, changed_keys)
     }
@@ -431,7 +862,8 @@ mod tests {
     use proptest::prelude::*;
 
     use super::*;
-    use crate::types::{address, storage};
+    use crate::types::storage::KeySeg;
+    use crate::types::{address, storage, token};
 
     #[test]
     fn test_crud_value() {
@@ -442,17 +874,17 @@ mod tests {
         // read a non-existing key
         let (value, gas) = write_log.read(&key);
         assert!(value.is_none());
-        assert_eq!(gas, key.len() as u64);
+        assert_eq!(gas, storage_read_gas(key.len(), 0));
 
         // delete a non-existing key
         let (gas, diff) = write_log.delete(&key).unwrap();
-        assert_eq!(gas, key.len() as u64);
+        assert_eq!(gas, storage_write_gas(key.len(), 0));
         assert_eq!(diff, 0);
 
         // insert a value
         let inserted = "inserted".as_bytes().to_vec();
         let (gas, diff) = write_log.write(&key, inserted.clone()).unwrap();
-        assert_eq!(gas, (key.len() + inserted.len()) as u64);
+        assert_eq!(gas, storage_write_gas(key.len(), inserted.len()));
         assert_eq!(diff, inserted.len() as i64);
 
         // read the value
@@ -463,22 +895,22 @@ mod tests {
             }
             _ => panic!("unexpected read result"),
         }
-        assert_eq!(gas, (key.len() + inserted.len()) as u64);
+        assert_eq!(gas, storage_read_gas(key.len(), inserted.len()));
 
         // update the value
         let updated = "updated".as_bytes().to_vec();
         let (gas, diff) = write_log.write(&key, updated.clone()).unwrap();
-        assert_eq!(gas, (key.len() + updated.len()) as u64);
+        assert_eq!(gas, storage_write_gas(key.len(), updated.len()));
         assert_eq!(diff, updated.len() as i64 - inserted.len() as i64);
 
         // delete the key
         let (gas, diff) = write_log.delete(&key).unwrap();
-        assert_eq!(gas, (key.len() + updated.len()) as u64);
+        assert_eq!(gas, storage_write_gas(key.len(), 0));
         assert_eq!(diff, -(updated.len() as i64));
 
         // delete the deleted key again
         let (gas, diff) = write_log.delete(&key).unwrap();
-        assert_eq!(gas, key.len() as u64);
+        assert_eq!(gas, storage_write_gas(key.len(), 0));
         assert_eq!(diff, 0);
 
         // read the deleted key
@@ -487,15 +919,122 @@ mod tests {
             StorageModification::Delete => {}
             _ => panic!("unexpected result"),
         }
-        assert_eq!(gas, key.len() as u64);
+        assert_eq!(gas, storage_read_gas(key.len(), 0));
 
         // insert again
         let reinserted = "reinserted".as_bytes().to_vec();
         let (gas, diff) = write_log.write(&key, reinserted.clone()).unwrap();
-        assert_eq!(gas, (key.len() + reinserted.len()) as u64);
+        assert_eq!(gas, storage_write_gas(key.len(), reinserted.len()));
         assert_eq!(diff, reinserted.len() as i64);
     }
 
+    #[test]
+    fn test_check_size() {
+        let mut write_log = WriteLog::default();
+        write_log.set_size_limits(SizeLimits {
+            max_key_bytes: 3,
+            max_value_bytes: 4,
+        });
+        let key =
+            storage::Key::parse("key").expect("cannot parse the key string");
+        assert!(write_log.check_size(&key, 4).is_ok());
+        assert!(matches!(
+            write_log.check_size(&key, 5),
+            Err(Error::ValueTooLarge { len: 5, max: 4 })
+        ));
+        let long_key =
+            storage::Key::parse("long").expect("cannot parse the key string");
+        assert!(matches!(
+            write_log.check_size(&long_key, 0),
+            Err(Error::KeyTooLong { len: 4, max: 3 })
+        ));
+    }
+
+    #[test]
+    fn test_get_changed_keys() {
+        let mut write_log = WriteLog::default();
+        let address_gen = EstablishedAddressGen::new("test");
+        let (addr, _gas) = write_log.init_account(&address_gen, vec![]);
+        let vp_key = storage::Key::validity_predicate(&addr);
+        let written = storage::Key::parse("written").unwrap();
+        let deleted = storage::Key::parse("deleted").unwrap();
+        let temp = storage::Key::parse("temp").unwrap();
+        write_log.write(&written, vec![1]).unwrap();
+        write_log.delete(&deleted).unwrap();
+        write_log.write_temp(&temp, vec![1]).unwrap();
+
+        let keys = write_log.get_keys();
+        let changed: HashMap<_, _> = write_log
+            .get_changed_keys(&keys)
+            .into_iter()
+            .map(|entry| (entry.key.clone(), entry))
+            .collect();
+        assert_eq!(changed.len(), 4);
+        assert_eq!(changed[&vp_key].change, storage::ChangeType::InitAccount);
+        assert_eq!(changed[&vp_key].owner, Some(addr));
+        assert_eq!(changed[&written].change, storage::ChangeType::Write);
+        assert_eq!(changed[&written].owner, None);
+        assert_eq!(changed[&deleted].change, storage::ChangeType::Delete);
+        assert_eq!(changed[&temp].change, storage::ChangeType::Temp);
+    }
+
+    /// Test that only the non-temporary changes of the current transaction
+    /// are counted as the Merkle tree updates
+    #[test]
+    fn test_num_merkle_tree_updates() {
+        let mut write_log = WriteLog::default();
+        let key = |key: &str| storage::Key::parse(key).unwrap();
+        write_log.write(&key("written"), vec![1]).unwrap();
+        write_log.delete(&key("deleted")).unwrap();
+        write_log.write_temp(&key("temp"), vec![1]).unwrap();
+        let address_gen = EstablishedAddressGen::new("test");
+        let _ = write_log.init_account(&address_gen, vec![]);
+        // The temporary write doesn't update the tree
+        assert_eq!(write_log.num_merkle_tree_updates(), 3);
+
+        write_log.commit_tx();
+        assert_eq!(write_log.num_merkle_tree_updates(), 0);
+    }
+
+    /// Test that the changed keys are indexed by the addresses in them until
+    /// the end of the transaction
+    #[test]
+    fn test_get_keys_of() {
+        let mut write_log = WriteLog::default();
+        let address_gen = EstablishedAddressGen::new("test");
+        let (addr1, _gas) = write_log.init_account(&address_gen, vec![]);
+        let (addr2, _gas) = write_log.init_account(&address_gen, vec![]);
+        let key1 = storage::Key::from(addr1.to_db_key())
+            .push(&"data".to_owned())
+            .unwrap();
+        let key2 = key1.push(&addr2).unwrap();
+        write_log.write(&key1, vec![1]).unwrap();
+        write_log.delete(&key2).unwrap();
+
+        let keys_of = |write_log: &WriteLog, addr: &Address| {
+            write_log.get_keys_of(addr).cloned().collect::<BTreeSet<_>>()
+        };
+        let expected: BTreeSet<_> = [
+            storage::Key::validity_predicate(&addr1),
+            key1.clone(),
+            key2.clone(),
+        ]
+        .into_iter()
+        .collect();
+        assert_eq!(keys_of(&write_log, &addr1), expected);
+        let expected: BTreeSet<_> =
+            [storage::Key::validity_predicate(&addr2), key2]
+                .into_iter()
+                .collect();
+        assert_eq!(keys_of(&write_log, &addr2), expected);
+
+        write_log.commit_tx();
+        assert!(keys_of(&write_log, &addr1).is_empty());
+        write_log.write(&key1, vec![2]).unwrap();
+        write_log.drop_tx();
+        assert!(keys_of(&write_log, &addr1).is_empty());
+    }
+
     #[test]
     fn test_crud_account() {
         let mut write_log = WriteLog::default();
@@ -505,7 +1044,7 @@ mod tests {
         let init_vp = "initialized".as_bytes().to_vec();
         let (addr, gas) = write_log.init_account(&address_gen, init_vp.clone());
         let vp_key = storage::Key::validity_predicate(&addr);
-        assert_eq!(gas, (vp_key.len() + init_vp.len()) as u64);
+        assert_eq!(gas, storage_write_gas(vp_key.len(), init_vp.len()));
 
         // read
         let (value, gas) = write_log.read(&vp_key);
@@ -513,7 +1052,7 @@ mod tests {
             StorageModification::InitAccount { vp } => assert_eq!(*vp, init_vp),
             _ => panic!("unexpected result"),
         }
-        assert_eq!(gas, (vp_key.len() + init_vp.len()) as u64);
+        assert_eq!(gas, storage_read_gas(vp_key.len(), init_vp.len()));
 
         // get all
         let (_changed_keys, init_accounts) = write_log.get_partitioned_keys();
@@ -521,6 +1060,80 @@ mod tests {
         assert_eq!(init_accounts.len(), 1);
     }
 
+    #[test]
+    fn test_initialized_accounts() {
+        let mut write_log = WriteLog::default();
+        let address_gen = EstablishedAddressGen::new("test");
+
+        let (addr1, _gas) = write_log.init_account(&address_gen, vec![]);
+        let (addr2, _gas) = write_log.init_account(&address_gen, vec![]);
+        assert_eq!(
+            write_log.get_initialized_accounts(),
+            vec![addr1.clone(), addr2.clone()]
+        );
+        assert!(write_log.is_initialized_account(&addr1));
+
+        // Writing into a new account doesn't trigger its VP
+        let key = storage::Key::from(addr1.to_db_key())
+            .push(&"balance".to_owned())
+            .unwrap();
+        write_log.write(&key, vec![1]).unwrap();
+        let (verifiers, _changed_keys) =
+            write_log.verifiers_and_changed_keys(&BTreeSet::new());
+        assert!(verifiers.is_empty());
+
+        // Writing into a new account from a following inner tx of a batch
+        // triggers its VP, but an account that isn't modified by it doesn't
+        write_log.end_inner_tx();
+        let (verifiers, _changed_keys) =
+            write_log.verifiers_and_changed_keys(&BTreeSet::new());
+        assert!(verifiers.is_empty());
+        write_log.write(&key, vec![2]).unwrap();
+        let (verifiers, changed_keys) =
+            write_log.verifiers_and_changed_keys(&BTreeSet::new());
+        assert_eq!(verifiers, BTreeSet::from([addr1.clone()]));
+        // The VP validates the changes of the whole batch, including the
+        // initialization of the account
+        let vp_key = storage::Key::validity_predicate(&addr1);
+        assert!(changed_keys.contains(&vp_key));
+
+        // The accounts are only recorded for the current transaction
+        write_log.drop_tx();
+        assert!(write_log.get_initialized_accounts().is_empty());
+        let (addr3, _gas) = write_log.init_account(&address_gen, vec![]);
+        write_log.commit_tx();
+        assert!(write_log.get_initialized_accounts().is_empty());
+        assert!(!write_log.is_initialized_account(&addr3));
+    }
+
+    #[test]
+    fn test_ibc_events() {
+        let mut write_log = WriteLog::default();
+        let event = |event_type: &str| IbcEvent {
+            event_type: event_type.to_owned(),
+            attributes: HashMap::from([(
+                "packet_sequence".to_owned(),
+                "1".to_owned(),
+            )]),
+        };
+
+        // All the events of a transaction are kept in order
+        let gas = write_log.emit_ibc_event(event("recv_packet"));
+        assert_eq!(gas, ("packet_sequence".len() + "1".len()) as u64);
+        write_log.emit_ibc_event(event("write_acknowledgement"));
+        assert_eq!(
+            write_log.get_ibc_events(),
+            &[event("recv_packet"), event("write_acknowledgement")]
+        );
+        assert_eq!(write_log.take_ibc_events().len(), 2);
+        assert!(write_log.get_ibc_events().is_empty());
+
+        // The events of a dropped transaction are discarded
+        write_log.emit_ibc_event(event("send_packet"));
+        write_log.drop_tx();
+        assert!(write_log.take_ibc_events().is_empty());
+    }
+
     #[test]
     fn test_update_initialized_account_should_fail() {
         let mut write_log = WriteLog::default();
@@ -619,6 +1232,164 @@ mod tests {
         assert_eq!(value, None);
     }
 
+    /// Test that the committed block's changes are written through the
+    /// storage's read cache
+    #[test]
+    fn test_commit_block_to_merkle_tree() {
+        let mut storage =
+            crate::ledger::storage::testing::TestStorage::default();
+        let mut expected =
+            crate::ledger::storage::testing::TestStorage::default();
+        let mut write_log = WriteLog::default();
+        let key1 = storage::Key::parse("key1").unwrap();
+        let key2 = storage::Key::parse("key2").unwrap();
+        let val = "val".as_bytes().to_vec();
+        write_log.write(&key1, val.clone()).unwrap();
+        write_log.write_temp(&key2, val.clone()).unwrap();
+        write_log.commit_tx();
+        expected.write(&key1, val).unwrap();
+
+        // The root is known before the changes are written to the DB
+        let root = write_log
+            .commit_block_to_merkle_tree(&mut storage)
+            .expect("updating the tree failed");
+        assert_eq!(root, expected.merkle_root());
+        let (value, _) = storage.read(&key1).expect("read failed");
+        assert!(value.is_none());
+
+        let mut batch = crate::ledger::storage::testing::TestStorage::batch();
+        write_log
+            .commit_block_to_batch(&mut storage, &mut batch)
+            .expect("commit failed");
+        storage.exec_batch(batch).expect("commit failed");
+        assert_eq!(storage.merkle_root(), root);
+        let (value, _) = storage.read(&key1).expect("read failed");
+        assert!(value.is_some());
+    }
+
+    #[test]
+    fn test_commit_updates_read_cache() {
+        let mut storage =
+            crate::ledger::storage::testing::TestStorage::default();
+        let mut write_log = WriteLog::default();
+        let key = token::balance_key(
+            &address::xan(),
+            &address::testing::established_address_1(),
+        );
+
+        let val1 = "val1".as_bytes().to_vec();
+        write_log.write(&key, val1.clone()).unwrap();
+        write_log.commit_tx();
+        write_log.commit_block(&mut storage).expect("commit failed");
+        let (value, _) = storage.read(&key).expect("read failed");
+        assert_eq!(value, Some(val1.clone()));
+        assert_eq!(storage.read_cache.get(&key), Some(val1));
+
+        let val2 = "val2".as_bytes().to_vec();
+        write_log.write(&key, val2.clone()).unwrap();
+        write_log.commit_tx();
+        write_log.commit_block(&mut storage).expect("commit failed");
+        let (value, _) = storage.read(&key).expect("read failed");
+        assert_eq!(value, Some(val2));
+
+        write_log.delete(&key).unwrap();
+        write_log.commit_tx();
+        write_log.commit_block(&mut storage).expect("commit failed");
+        let (value, _) = storage.read(&key).expect("read failed");
+        assert_eq!(value, None);
+        assert_eq!(storage.read_cache.get(&key), None);
+    }
+
+    #[test]
+    fn test_merge_speculative_tx() {
+        let mut write_log = WriteLog::default();
+        let address_gen = EstablishedAddressGen::new("test");
+        let key1 =
+            storage::Key::parse("key1").expect("cannot parse the key string");
+        let key2 =
+            storage::Key::parse("key2").expect("cannot parse the key string");
+        let val1 = "val1".as_bytes().to_vec();
+        let val2 = "val2".as_bytes().to_vec();
+
+        // a tx reads key1 and writes key2
+        let mut copy1 = write_log.speculate();
+        let _ = copy1.read(&key1);
+        copy1.write(&key2, val1.clone()).unwrap();
+        let tx1 = copy1.into_speculative_tx();
+        // another tx reads key2 and writes key1
+        let mut copy2 = write_log.speculate();
+        let _ = copy2.read(&key2);
+        copy2.write(&key1, val2.clone()).unwrap();
+        let tx2 = copy2.into_speculative_tx();
+
+        // the first tx is merged, but the second one read its write
+        assert!(write_log.merge_speculative_tx(tx1));
+        write_log.commit_tx();
+        assert!(!write_log.merge_speculative_tx(tx2));
+        match write_log.read(&key2).0 {
+            Some(StorageModification::Write { value }) => {
+                assert_eq!(*value, val1)
+            }
+            _ => panic!("unexpected read result"),
+        }
+        assert!(write_log.read(&key1).0.is_none());
+
+        // txs that both initialize accounts conflict
+        let mut copy1 = write_log.speculate();
+        let (addr1, _) = copy1.init_account(&address_gen, vec![]);
+        let tx1 = copy1.into_speculative_tx();
+        let mut copy2 = write_log.speculate();
+        copy2.init_account(&address_gen, vec![]);
+        let tx2 = copy2.into_speculative_tx();
+        assert!(write_log.merge_speculative_tx(tx1));
+        assert_eq!(write_log.get_initialized_accounts(), vec![addr1]);
+        write_log.drop_tx();
+        assert!(!write_log.merge_speculative_tx(tx2));
+        let (addr2, _) = write_log.init_account(&address_gen, vec![]);
+        assert_ne!(addr1, addr2);
+    }
+
+    #[test]
+    fn test_block_changes() {
+        let mut storage =
+            crate::ledger::storage::testing::TestStorage::default();
+        let mut write_log = WriteLog::default();
+
+        let key1 =
+            storage::Key::parse("key1").expect("cannot parse the key string");
+        let key2 =
+            storage::Key::parse("key2").expect("cannot parse the key string");
+        let key3 =
+            storage::Key::parse("key3").expect("cannot parse the key string");
+        let val1 = "val1".as_bytes().to_vec();
+        let val2 = "val2".as_bytes().to_vec();
+        storage.write(&key1, val1.clone()).expect("write failed");
+        storage.write(&key2, val1.clone()).expect("write failed");
+
+        // update, delete, and write a new and a temporary value
+        write_log.write(&key2, val2.clone()).unwrap();
+        write_log.delete(&key1).unwrap();
+        write_log.write_temp(&key3, val2.clone()).unwrap();
+        write_log.commit_tx();
+
+        let changes = write_log.block_changes(&storage).unwrap();
+        assert_eq!(
+            changes,
+            vec![
+                StorageChange {
+                    key: key1,
+                    old_value: Some(val1.clone()),
+                    new_value: None,
+                },
+                StorageChange {
+                    key: key2,
+                    old_value: Some(val1),
+                    new_value: Some(val2),
+                },
+            ]
+        );
+    }
+
     prop_compose! {
         fn arb_verifiers_changed_key_tx_all_key()
             (verifiers_from_tx in testing::arb_verifiers_from_tx())
@@ -644,7 +1415,22 @@ mod tests {
         fn verifiers_changed_key_tx_all_key(
             (verifiers_from_tx, tx_write_log) in arb_verifiers_changed_key_tx_all_key(),
         ) {
-            let write_log = WriteLog { tx_write_log, ..WriteLog::default() };
+            let tx_initialized_accounts = tx_write_log
+                .iter()
+                .filter_map(|(key, value)| match value {
+                    StorageModification::InitAccount { .. } =>
+                        key.is_validity_predicate().cloned(),
+                    _ => None,
+                })
+                .collect();
+            let mut write_log = WriteLog {
+                tx_write_log,
+                tx_initialized_accounts,
+                ..WriteLog::default()
+            };
+            for key in write_log.get_keys() {
+                write_log.index_key(&key);
+            }
 
             let (verifiers, changed_keys) = write_log.verifiers_and_changed_keys(&verifiers_from_tx);
 
@@ -0,0 +1,274 @@
+//! Native validity predicate for fungible tokens
+
+use std::collections::BTreeSet;
+
+use borsh::BorshDeserialize;
+use thiserror::Error;
+
+use crate::ledger::native_vp::{self, Ctx, NativeVp};
+use crate::ledger::parameters::storage as parameters_storage;
+use crate::ledger::storage::{self as ledger_storage, StorageHasher};
+use crate::types::address::{self, Address, InternalAddress};
+use crate::types::hash::Hash;
+use crate::types::storage::Key;
+use crate::types::token::{self, Amount, TokenMetadata, TokenVpStorage};
+use crate::types::transaction::TxBatch;
+use crate::vm::WasmCacheAccess;
+
+#[allow(missing_docs)]
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("Native VP error: {0}")]
+    NativeVpError(native_vp::Error),
+    #[error("Decoding error: {0}")]
+    DecodingError(std::io::Error),
+}
+
+/// Token functions result
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// Check if the given address is a token whose balances are checked by the
+/// native [`TokenVp`], in addition to the validity predicate stored in its
+/// account.
+pub fn is_native_token(addr: &Address) -> bool {
+    address::tokens().contains_key(addr)
+}
+
+/// Fungible token VP. Runs the same checks of the token's storage as the
+/// `vp_token` WASM, see [`token::validate_token_changes`], and checks the tx
+/// and VP whitelists. Unlike the internal modules' native VPs, it's run for
+/// the established address of a token.
+pub struct TokenVp<'a, DB, H, CA>
+where
+    DB: ledger_storage::DB + for<'iter> ledger_storage::DBIter<'iter>,
+    H: StorageHasher,
+    CA: WasmCacheAccess,
+{
+    /// Context to interact with the host structures.
+    pub ctx: Ctx<'a, DB, H, CA>,
+    /// The address of the token
+    pub token: &'a Address,
+}
+
+impl<'a, DB, H, CA> NativeVp for TokenVp<'a, DB, H, CA>
+where
+    DB: 'static + ledger_storage::DB + for<'iter> ledger_storage::DBIter<'iter>,
+    H: 'static + StorageHasher,
+    CA: 'static + WasmCacheAccess,
+{
+    type Error = Error;
+
+    const ADDR: InternalAddress = InternalAddress::NativeToken;
+
+    fn validate_tx(
+        &self,
+        _tx_data: &[u8],
+        keys_changed: &BTreeSet<Key>,
+        verifiers: &BTreeSet<Address>,
+    ) -> Result<bool> {
+        if !self.is_tx_whitelisted()? {
+            return Ok(false);
+        }
+        for key in keys_changed {
+            if key.is_validity_predicate().is_some() {
+                let vp = self
+                    .ctx
+                    .read_post(key)
+                    .map_err(Error::NativeVpError)?
+                    .unwrap_or_default();
+                if !self.is_vp_whitelisted(&vp)? {
+                    return Ok(false);
+                }
+            }
+        }
+        token::validate_token_changes(self, self.token, keys_changed, verifiers)
+    }
+}
+
+impl<'a, DB, H, CA> TokenVpStorage for TokenVp<'a, DB, H, CA>
+where
+    DB: 'static + ledger_storage::DB + for<'iter> ledger_storage::DBIter<'iter>,
+    H: 'static + StorageHasher,
+    CA: 'static + WasmCacheAccess,
+{
+    type Error = Error;
+
+    fn read_amount_pre(&self, key: &Key) -> Result<Option<Amount>> {
+        decode(self.ctx.read_pre(key))
+    }
+
+    fn read_amount_post(&self, key: &Key) -> Result<Option<Amount>> {
+        decode(self.ctx.read_post(key))
+    }
+
+    fn read_amount_temp(&self, key: &Key) -> Result<Option<Amount>> {
+        decode(self.ctx.read_temp(key))
+    }
+
+    fn read_metadata_pre(
+        &self,
+        token: &Address,
+    ) -> Result<Option<TokenMetadata>> {
+        decode(self.ctx.read_pre(&token::metadata_key(token)))
+    }
+}
+
+impl<'a, DB, H, CA> TokenVp<'a, DB, H, CA>
+where
+    DB: 'static + ledger_storage::DB + for<'iter> ledger_storage::DBIter<'iter>,
+    H: 'static + StorageHasher,
+    CA: 'static + WasmCacheAccess,
+{
+    /// Read a whitelist of hashes from the parameters
+    fn read_whitelist(&self, key: &Key) -> Result<Vec<String>> {
+        Ok(decode(self.ctx.read_pre(key))?.unwrap_or_default())
+    }
+
+    fn is_tx_whitelisted(&self) -> Result<bool> {
+        // The codes of the inner txs of a batch are checked against the
+        // whitelist by the protocol before they're run
+        if TxBatch::is_batch(self.ctx.tx) {
+            return Ok(true);
+        }
+        let tx_hash =
+            self.ctx.get_tx_code_hash().map_err(Error::NativeVpError)?;
+        let key = parameters_storage::get_tx_whitelist_storage_key();
+        let whitelist = self.read_whitelist(&key)?;
+        // if whitelist is empty, allow any transaction
+        Ok(whitelist.is_empty() || whitelist.contains(&tx_hash.to_string()))
+    }
+
+    fn is_vp_whitelisted(&self, vp: &[u8]) -> Result<bool> {
+        let vp_hash = Hash::sha256(vp);
+        let key = parameters_storage::get_vp_whitelist_storage_key();
+        let whitelist = self.read_whitelist(&key)?;
+        // if whitelist is empty, allow any validity predicate
+        Ok(whitelist.is_empty() || whitelist.contains(&vp_hash.to_string()))
+    }
+}
+
+/// Decode a value from a storage read result
+fn decode<T: BorshDeserialize>(
+    value: native_vp::Result<Option<Vec<u8>>>,
+) -> Result<Option<T>> {
+    value
+        .map_err(Error::NativeVpError)?
+        .map(|bytes| {
+            T::try_from_slice(&bytes[..]).map_err(Error::DecodingError)
+        })
+        .transpose()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ledger::gas::VpGasMeter;
+    use crate::ledger::storage::testing::TestStorage;
+    use crate::ledger::storage::types::encode;
+    use crate::ledger::storage::write_log::WriteLog;
+    use crate::proto::Tx;
+    use crate::types::address::{xan, Address};
+    use crate::vm::{wasm, WasmCacheRwAccess};
+
+    fn validate(
+        storage: &TestStorage,
+        write_log: &WriteLog,
+        verifiers: &BTreeSet<Address>,
+    ) -> bool {
+        let tx = Tx::new(vec![], None);
+        let (vp_wasm_cache, _vp_cache_dir) =
+            wasm::compilation_cache::common::testing::cache();
+        let ctx = Ctx::<_, _, WasmCacheRwAccess>::new(
+            storage,
+            write_log,
+            &tx,
+            VpGasMeter::new(0),
+            vp_wasm_cache,
+        );
+        let token = xan();
+        let vp = TokenVp { ctx, token: &token };
+        vp.validate_tx(&[], &write_log.get_keys(), verifiers)
+            .unwrap()
+    }
+
+    /// Test that a transfer is accepted only when the total supply is
+    /// unchanged and the source has approved it.
+    #[test]
+    fn test_token_transfer() {
+        let mut storage = TestStorage::default();
+        let source = storage.address_gen.generate_address("source");
+        let target = storage.address_gen.generate_address("target");
+        let source_key = token::balance_key(&xan(), &source);
+        let target_key = token::balance_key(&xan(), &target);
+        storage
+            .write(&source_key, encode(&Amount::from(100)))
+            .unwrap();
+
+        let mut write_log = WriteLog::default();
+        write_log
+            .write(&source_key, encode(&Amount::from(90)))
+            .unwrap();
+        write_log
+            .write(&target_key, encode(&Amount::from(10)))
+            .unwrap();
+
+        // Without the source's approval
+        assert!(!validate(&storage, &write_log, &BTreeSet::new()));
+
+        // With the source's approval
+        let verifiers = [source.clone()].into_iter().collect();
+        assert!(validate(&storage, &write_log, &verifiers));
+
+        // Changing the total supply
+        write_log
+            .write(&target_key, encode(&Amount::from(20)))
+            .unwrap();
+        assert!(!validate(&storage, &write_log, &verifiers));
+    }
+
+    /// Test that the total supply of a token may be changed together with the
+    /// balances only with the approval of its minter
+    #[test]
+    fn test_token_change_supply() {
+        let mut storage = TestStorage::default();
+        let minter = storage.address_gen.generate_address("minter");
+        let owner = storage.address_gen.generate_address("owner");
+        let metadata = TokenMetadata {
+            symbol: "XAN".to_owned(),
+            decimals: 6,
+            minter: Some(minter.clone()),
+        };
+        storage
+            .write(&token::metadata_key(&xan()), encode(&metadata))
+            .unwrap();
+        let supply_key = token::supply_key(&xan());
+        storage
+            .write(&supply_key, encode(&Amount::from(100)))
+            .unwrap();
+
+        // Mint to the owner
+        let mut write_log = WriteLog::default();
+        write_log
+            .write(
+                &token::balance_key(&xan(), &owner),
+                encode(&Amount::from(10)),
+            )
+            .unwrap();
+        write_log
+            .write(&supply_key, encode(&Amount::from(110)))
+            .unwrap();
+
+        // Without the minter's approval
+        assert!(!validate(&storage, &write_log, &BTreeSet::new()));
+
+        // With the minter's approval
+        let verifiers = [minter].into_iter().collect();
+        assert!(validate(&storage, &write_log, &verifiers));
+
+        // The supply must change by the same amount as the balances
+        write_log
+            .write(&supply_key, encode(&Amount::from(120)))
+            .unwrap();
+        assert!(!validate(&storage, &write_log, &verifiers));
+    }
+}
@@ -1,4 +1,6 @@
-//! Treasury VP
+//! Treasury VP. The treasury is funded by a share of the fees and the reward
+//! of every block and by any transfers to it, and its funds can only be spent
+//! by the accepted governance proposals.
 
 use std::collections::BTreeSet;
 /// treasury parameters
@@ -14,7 +16,7 @@ use super::governance::vp::is_proposal_accepted;
 use crate::ledger::native_vp::{self, Ctx, NativeVp};
 use crate::ledger::storage::{self as ledger_storage, StorageHasher};
 use crate::types::address::{xan as nam, Address, InternalAddress};
-use crate::types::storage::Key;
+use crate::types::storage::{DbKeySeg, Key};
 use crate::types::token;
 use crate::vm::WasmCacheAccess;
 
@@ -58,87 +60,40 @@ where
         keys_changed: &BTreeSet<Key>,
         _verifiers: &BTreeSet<Address>,
     ) -> Result<bool> {
+        let proposal_accepted = || match u64::try_from_slice(tx_data) {
+            Ok(id) => is_proposal_accepted(&self.ctx, id),
+            Err(_) => false,
+        };
         let result = keys_changed.iter().all(|key| {
             let key_type: KeyType = key.into();
             match key_type {
-                KeyType::PARAMETER => {
-                    let proposal_id = u64::try_from_slice(tx_data).ok();
-                    match proposal_id {
-                        Some(id) => is_proposal_accepted(&self.ctx, id),
-                        _ => false,
+                KeyType::PARAMETER => proposal_accepted(),
+                KeyType::BALANCE(token_addr) => {
+                    let pre_balance = self.read_amount(key, ReadType::Pre);
+                    let post_balance = self.read_amount(key, ReadType::Post);
+                    let (pre_balance, post_balance) =
+                        match (pre_balance, post_balance) {
+                            (Some(pre), Some(post)) => (pre, post),
+                            _ => return false,
+                        };
+                    // Anyone can fund the treasury
+                    if post_balance >= pre_balance {
+                        return true;
                     }
-                }
-                KeyType::BALANCE(addr) => {
-                    let proposal_id = u64::try_from_slice(tx_data).ok();
-                    if let Some(id) = proposal_id {
-                        if !is_proposal_accepted(&self.ctx, id) {
-                            return false;
-                        }
-                    } else {
+                    // The funds can only be spent by a governance proposal
+                    if !proposal_accepted() {
                         return false;
-                    };
-                    let is_max_funds_transfer_key =
-                        treasury_storage::get_max_transferable_fund_key();
-                    let balance_key = token::balance_key(&nam(), &ADDRESS);
-                    let max_transfer_amount =
-                        self.ctx.read_pre(&is_max_funds_transfer_key);
-                    let pre_balance = self.ctx.read_pre(&balance_key);
-                    let post_balance = self.ctx.read_post(&balance_key);
-                    if addr.ne(&ADDRESS) {
+                    }
+                    if token_addr != nam() {
                         return true;
                     }
-                    match (max_transfer_amount, pre_balance, post_balance) {
-                        (
-                            Ok(max_transfer_amount),
-                            Ok(pre_balance),
-                            Ok(post_balance),
-                        ) => {
-                            match (
-                                max_transfer_amount,
-                                pre_balance,
-                                post_balance,
-                            ) {
-                                (
-                                    Some(max_transfer_amount),
-                                    Some(pre_balance),
-                                    Some(post_balance),
-                                ) => {
-                                    let max_transfer_amount =
-                                        token::Amount::try_from_slice(
-                                            &max_transfer_amount[..],
-                                        )
-                                        .ok();
-                                    let pre_balance =
-                                        token::Amount::try_from_slice(
-                                            &pre_balance[..],
-                                        )
-                                        .ok();
-                                    let post_balance =
-                                        token::Amount::try_from_slice(
-                                            &post_balance[..],
-                                        )
-                                        .ok();
-                                    match (
-                                        max_transfer_amount,
-                                        pre_balance,
-                                        post_balance,
-                                    ) {
-                                        (
-                                            Some(max_transfer_amount),
-                                            Some(pre_balance),
-                                            Some(post_balance),
-                                        ) => {
-                                            post_balance > pre_balance
-                                                || (pre_balance - post_balance
-                                                    <= max_transfer_amount)
-                                        }
-                                        _ => false,
-                                    }
-                                }
-                                _ => false,
-                            }
+                    let max_transfer_key =
+                        treasury_storage::get_max_transferable_fund_key();
+                    match self.read_amount(&max_transfer_key, ReadType::Pre) {
+                        Some(max_transfer_amount) => {
+                            pre_balance - post_balance <= max_transfer_amount
                         }
-                        _ => false,
+                        None => false,
                     }
                 }
                 KeyType::UNKNOWN_TREASURY => false,
@@ -149,8 +104,48 @@ where
     }
 }
 
+impl<'a, DB, H, CA> TreasuryVp<'a, DB, H, CA>
+where
+    DB: 'static + ledger_storage::DB + for<'iter> ledger_storage::DBIter<'iter>,
+    H: 'static + StorageHasher,
+    CA: 'static + WasmCacheAccess,
+{
+    /// Read an amount before or after the tx. A missing amount is zero,
+    /// returns `None` if it can't be read.
+    fn read_amount(
+        &self,
+        key: &Key,
+        read_type: ReadType,
+    ) -> Option<token::Amount> {
+        let value = match read_type {
+            ReadType::Pre => self.ctx.read_pre(key),
+            ReadType::Post => self.ctx.read_post(key),
+        };
+        match value.ok()? {
+            Some(bytes) => token::Amount::try_from_slice(&bytes).ok(),
+            None => Some(token::Amount::default()),
+        }
+    }
+}
+
+/// Get the treasury's share of an amount of the fees or the reward of a
+/// block, given the share in basis points
+pub fn fee_share_of(amount: token::Amount, fee_share: u64) -> token::Amount {
+    let share = u128::from(u64::from(amount))
+        * u128::from(fee_share.min(10_000))
+        / 10_000;
+    token::Amount::from(share as u64)
+}
+
+/// The state of the storage to read from
+enum ReadType {
+    Pre,
+    Post,
+}
+
 #[allow(clippy::upper_case_acronyms)]
 enum KeyType {
+    /// The treasury's balance of the token
     #[allow(clippy::upper_case_acronyms)]
     BALANCE(Address),
     #[allow(clippy::upper_case_acronyms)]
@@ -168,13 +163,22 @@ impl From<&Key> for KeyType {
             KeyType::PARAMETER
         } else if treasury_storage::is_treasury_key(value) {
             KeyType::UNKNOWN_TREASURY
-        } else if token::is_any_token_balance_key(value).is_some() {
-            match token::is_balance_key(&nam(), value) {
-                Some(addr) => KeyType::BALANCE(addr.clone()),
-                None => KeyType::UNKNOWN,
-            }
+        } else if let Some(token) = treasury_balance_token(value) {
+            KeyType::BALANCE(token.clone())
         } else {
             KeyType::UNKNOWN
         }
     }
 }
+
+/// Get the token of a balance key of the treasury, if it is one
+fn treasury_balance_token(key: &Key) -> Option<&Address> {
+    match &key.segments[..] {
+        [DbKeySeg::AddressSeg(token), ..]
+            if token::is_balance_key(token, key) == Some(&ADDRESS) =>
+        {
+            Some(token)
+        }
+        _ => None,
+    }
+}
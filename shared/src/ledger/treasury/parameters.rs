@@ -20,12 +20,16 @@ use crate::types::token::Amount;
 pub struct TreasuryParams {
     /// Maximum amount of token that can be moved in a single transfer
     pub max_proposal_fund_transfer: u64,
+    /// Share of the fees and the reward of every block paid to the treasury,
+    /// in basis points (per ten thousand)
+    pub fee_share: u64,
 }
 
 impl Default for TreasuryParams {
     fn default() -> Self {
         Self {
             max_proposal_fund_transfer: 10_000,
+            fee_share: 1_000,
         }
     }
 }
@@ -43,5 +47,8 @@ impl TreasuryParams {
         storage
             .write(&max_proposal_fund_transfer_key, encode(&amount))
             .unwrap();
+
+        let fee_share_key = treasury_storage::get_fee_share_key();
+        storage.write(&fee_share_key, encode(&self.fee_share)).unwrap();
     }
 }
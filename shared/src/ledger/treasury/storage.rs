@@ -2,6 +2,7 @@ use super::ADDRESS;
 use crate::types::storage::{DbKeySeg, Key, KeySeg};
 
 const MAX_TRANSFERABLE_FUND_KEY: &str = "max_fund";
+const FEE_SHARE_KEY: &str = "fee_share";
 
 /// Check if a key is a treasury key
 pub fn is_treasury_key(key: &Key) -> bool {
@@ -20,9 +21,21 @@ pub fn is_max_funds_transfer_key(key: &Key) -> bool {
     }
 }
 
+/// Check if key is the fee share key
+pub fn is_fee_share_key(key: &Key) -> bool {
+    match &key.segments[..] {
+        [DbKeySeg::AddressSeg(addr), DbKeySeg::StringSeg(fee_share)]
+            if addr == &ADDRESS && fee_share == FEE_SHARE_KEY =>
+        {
+            true
+        }
+        _ => false,
+    }
+}
+
 /// Check if key is any parameter key
 pub fn is_parameter_key(key: &Key) -> bool {
-    is_max_funds_transfer_key(key)
+    is_max_funds_transfer_key(key) || is_fee_share_key(key)
 }
 
 /// Get key of max funds transfer parameter
@@ -31,3 +44,10 @@ pub fn get_max_transferable_fund_key() -> Key {
         .push(&MAX_TRANSFERABLE_FUND_KEY.to_owned())
         .expect("Cannot obtain a storage key")
 }
+
+/// Get key of the fee share parameter
+pub fn get_fee_share_key() -> Key {
+    Key::from(ADDRESS.to_db_key())
+        .push(&FEE_SHARE_KEY.to_owned())
+        .expect("Cannot obtain a storage key")
+}
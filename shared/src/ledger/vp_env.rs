@@ -90,7 +90,9 @@ where
 }
 
 /// Storage read posterior state (after tx execution). It will try to read from
-/// the write log first and if no entry found then from the storage.
+/// the write log first and if no entry found then from the storage. The
+/// temporary values written by the tx are visible too, so that the tx can hand
+/// data to the VPs without storing it.
 pub fn read_post<DB, H>(
     gas_meter: &mut VpGasMeter,
     storage: &Storage<DB, H>,
@@ -118,8 +120,9 @@ where
             // Read the VP of a new account
             Ok(Some(vp.clone()))
         }
-        Some(&write_log::StorageModification::Temp { .. }) => {
-            Err(RuntimeError::ReadTemporaryValueError)
+        Some(&write_log::StorageModification::Temp { ref value }) => {
+            // Read a temporary value, it will never be committed
+            Ok(Some(value.clone()))
         }
         None => {
             // When not found in write log, try to read from the storage
@@ -252,6 +255,14 @@ pub fn get_tx_code_hash(gas_meter: &mut VpGasMeter, tx: &Tx) -> Result<Hash> {
     Ok(hash)
 }
 
+/// Getting the canonical hash of the transaction that is being applied, which
+/// is the hash of the signed transaction's bytes.
+pub fn get_tx_hash(gas_meter: &mut VpGasMeter, tx: &Tx) -> Result<Hash> {
+    let hash = Hash(tx.hash());
+    add_gas(gas_meter, MIN_STORAGE_GAS)?;
+    Ok(hash)
+}
+
 /// Getting the block epoch. The epoch is that of the block to which the
 /// current transaction is being applied.
 pub fn get_block_epoch<DB, H>(
@@ -1,7 +1,10 @@
+use std::cell::RefCell;
 use std::collections::hash_map::DefaultHasher;
+use std::collections::BTreeMap;
 use std::convert::{TryFrom, TryInto};
 use std::fmt::Display;
 use std::hash::{Hash, Hasher};
+use std::str::FromStr;
 
 use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
 use prost::Message;
@@ -9,6 +12,7 @@ use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
 use super::generated::types;
+use crate::types::chain::{ChainId, ChainIdParseError};
 use crate::types::key::*;
 use crate::types::time::DateTimeUtc;
 use crate::types::transaction::hash_tx;
@@ -29,10 +33,23 @@ pub enum Error {
     NoTimestampError,
     #[error("Timestamp is invalid: {0}")]
     InvalidTimestamp(prost_types::TimestampOutOfSystemRangeError),
+    #[error("Chain ID is invalid: {0}")]
+    InvalidChainId(ChainIdParseError),
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
 
+/// The tag of the chain ID in the signed bytes of a tx
+const CHAIN_ID_SIGN_TAG: u8 = 0;
+/// The tag of the expiration in the signed bytes of a tx
+const EXPIRATION_SIGN_TAG: u8 = 1;
+
+thread_local! {
+    /// A scratch buffer for encoding txs to hash them, reused across the txs
+    /// hashed on the same thread
+    static ENCODING_BUFFER: RefCell<Vec<u8>> = RefCell::new(Vec::new());
+}
+
 /// This can be used to sign an arbitrary tx. The signature is produced and
 /// verified on the tx data concatenated with the tx code, however the tx code
 /// itself is not part of this structure.
@@ -47,6 +64,10 @@ pub struct SignedTxData {
     /// The signature is produced on the tx data concatenated with the tx code
     /// and the timestamp.
     pub sig: common::Signature,
+    /// Additional signatures of the same message by the signers of a
+    /// multisignature account, keyed by the index of the signer's public key
+    /// in the account's signer set.
+    pub multisig: BTreeMap<u8, common::Signature>,
 }
 
 /// A generic signed data wrapper for Borsh encode-able data.
@@ -127,6 +148,13 @@ pub struct Tx {
     pub code: Vec<u8>,
     pub data: Option<Vec<u8>>,
     pub timestamp: DateTimeUtc,
+    /// The ID of the chain that the tx is for. It's signed together with the
+    /// rest of the tx, so that a tx bound to a chain can't be replayed on
+    /// another one.
+    pub chain_id: Option<ChainId>,
+    /// The time after which the tx can no longer be included in a block. Like
+    /// the chain ID, it's signed together with the rest of the tx.
+    pub expiration: Option<DateTimeUtc>,
 }
 
 impl TryFrom<&[u8]> for Tx {
@@ -138,10 +166,24 @@ impl TryFrom<&[u8]> for Tx {
             Some(t) => t.try_into().map_err(Error::InvalidTimestamp)?,
             None => return Err(Error::NoTimestampError),
         };
+        let chain_id = if tx.chain_id.is_empty() {
+            None
+        } else {
+            Some(
+                ChainId::from_str(&tx.chain_id)
+                    .map_err(Error::InvalidChainId)?,
+            )
+        };
+        let expiration = tx
+            .expiration
+            .map(|t| t.try_into().map_err(Error::InvalidTimestamp))
+            .transpose()?;
         Ok(Tx {
             code: tx.code,
             data: tx.data,
             timestamp,
+            chain_id,
+            expiration,
         })
     }
 }
@@ -153,6 +195,11 @@ impl From<Tx> for types::Tx {
             code: tx.code,
             data: tx.data,
             timestamp,
+            chain_id: tx
+                .chain_id
+                .map(|chain_id| chain_id.to_string())
+                .unwrap_or_default(),
+            expiration: tx.expiration.map(Into::into),
         }
     }
 }
@@ -163,19 +210,85 @@ impl Tx {
             code,
             data,
             timestamp: DateTimeUtc::now(),
+            chain_id: None,
+            expiration: None,
         }
     }
 
+    /// Bind the tx to the chain with the given ID. This must be done before
+    /// the tx is signed.
+    pub fn with_chain_id(mut self, chain_id: ChainId) -> Self {
+        self.chain_id = Some(chain_id);
+        self
+    }
+
+    /// Set the time after which the tx is no longer valid. This must be done
+    /// before the tx is signed.
+    pub fn with_expiration(mut self, expiration: DateTimeUtc) -> Self {
+        self.expiration = Some(expiration);
+        self
+    }
+
+    /// Encode the tx without its chain ID and expiration, in the layout of the
+    /// fields that are split up by [`Tx::tx_to_encrypt`] and
+    /// [`Tx::tx_to_sign`]
+    fn to_bytes_unbound(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(self.encoded_len_hint());
+        self.encode_to(false, &mut bytes);
+        bytes
+    }
+
     pub fn to_bytes(&self) -> Vec<u8> {
-        let mut bytes = vec![];
-        let tx: types::Tx = self.clone().into();
-        tx.encode(&mut bytes)
-            .expect("encoding a transaction failed");
+        let mut bytes = Vec::with_capacity(self.encoded_len_hint());
+        self.encode_to(true, &mut bytes);
         bytes
     }
 
+    /// Encode the tx into the given buffer in the layout of its
+    /// [`types::Tx`] message, without copying its code and data into the
+    /// message first. The chain ID and the expiration are only encoded if
+    /// `bound` is set.
+    fn encode_to(&self, bound: bool, buf: &mut Vec<u8>) {
+        use prost::encoding;
+        if !self.code.is_empty() {
+            encoding::bytes::encode(1, &self.code, buf);
+        }
+        if let Some(data) = &self.data {
+            encoding::bytes::encode(2, data, buf);
+        }
+        let timestamp: prost_types::Timestamp = self.timestamp.into();
+        encoding::message::encode(3, &timestamp, buf);
+        if !bound {
+            return;
+        }
+        if let Some(chain_id) = &self.chain_id {
+            encoding::string::encode(4, &chain_id.to_string(), buf);
+        }
+        if let Some(expiration) = self.expiration {
+            let expiration: prost_types::Timestamp = expiration.into();
+            encoding::message::encode(5, &expiration, buf);
+        }
+    }
+
+    /// The length of the code and data of the tx with the overhead of their
+    /// and the other fields' encoding, used to size the encoding buffer
+    fn encoded_len_hint(&self) -> usize {
+        const FIELDS_OVERHEAD: usize = 128;
+        self.code.len()
+            + self.data.as_ref().map(Vec::len).unwrap_or_default()
+            + FIELDS_OVERHEAD
+    }
+
+    /// The canonical hash of the tx, which is the hash of its signed bytes.
+    /// It identifies the tx in the events and the tx index and it's given to
+    /// the VPs.
     pub fn hash(&self) -> [u8; 32] {
-        hash_tx(&self.to_bytes()).0
+        ENCODING_BUFFER.with(|buf| {
+            let mut buf = buf.borrow_mut();
+            buf.clear();
+            self.encode_to(true, &mut buf);
+            hash_tx(&buf).0
+        })
     }
 
     pub fn code_hash(&self) -> [u8; 32] {
@@ -183,7 +296,7 @@ impl Tx {
     }
 
     pub fn tx_to_encrypt(&self) -> (Vec<u8>,Vec<u8>,Vec<u8>,Vec<u8>) {
-        let tx_bytes = self.to_bytes().clone();
+        let tx_bytes = self.to_bytes_unbound();
 
         let mut code_byte_size : usize = 0;
         if self.code.len() != 0 {
@@ -195,7 +308,7 @@ impl Tx {
         // get the bytes of data field from bytes of tx
         let mut data_byte_size : usize = 0;
         if self.data.is_some(){
-            data_byte_size = self.data.clone().expect("Getting transaction data shouldn't fail").len() as usize;
+            data_byte_size = self.data.as_ref().map(Vec::len).unwrap_or_default();
             data_byte_size += 2;
         }
         let timestamp_shift :usize =2;
@@ -210,17 +323,20 @@ impl Tx {
         timestamp_byte_size+=2;
         let data_bytes  = (&tx_bytes[code_byte_size..
             code_byte_size + data_byte_size]).to_vec();
-        let timestamp_bytes =
+        let mut timestamp_bytes =
             (&tx_bytes[code_byte_size + data_byte_size ..
                 code_byte_size + data_byte_size + timestamp_byte_size
                 ]).to_vec();
+        // The chain ID and the expiration follow the timestamp in the encoded
+        // tx, so they're encrypted together with it
+        timestamp_bytes.extend_from_slice(&self.to_bytes()[tx_len..]);
         let code_hash = (&hash_tx(&code_bytes).0).to_vec();
 
         (code_hash, code_bytes, data_bytes, timestamp_bytes)
     }
 
     pub fn tx_to_sign(&self) -> [u8; 32] {
-        let tx_bytes = self.to_bytes();
+        let tx_bytes = self.to_bytes_unbound();
         let mut code_byte_size : usize = 0;
         if self.code.len() != 0 {
             code_byte_size = self.code.len() as usize;
@@ -231,7 +347,7 @@ impl Tx {
         // get the bytes of data field from bytes of tx
         let mut data_byte_size : usize = 0;
         if self.data.is_some(){
-            data_byte_size = self.data.clone().expect("Getting transaction data shouldn't fail").len() as usize;
+            data_byte_size = self.data.as_ref().map(Vec::len).unwrap_or_default();
             data_byte_size += 2;
         }
         let timestamp_shift :usize =2;
@@ -252,16 +368,50 @@ impl Tx {
                 ];
 
         let code_hash :&[u8] = &hash_tx(&code_bytes).0;
-        hash_tx(&([code_hash,data_bytes,timestamp_bytes].concat())).0
+        // The chain ID and the expiration are each tagged and prefixed with
+        // their length, so that the bytes of one can't be taken for the
+        // other's. A tx without either is signed as before they were added.
+        let mut bound_bytes = Vec::new();
+        if let Some(chain_id) = &self.chain_id {
+            (CHAIN_ID_SIGN_TAG, chain_id.to_string())
+                .serialize(&mut bound_bytes)
+                .expect("Encoding the chain ID shouldn't fail");
+        }
+        if let Some(expiration) = self.expiration {
+            let expiration = expiration
+                .try_to_vec()
+                .expect("Encoding the expiration shouldn't fail");
+            (EXPIRATION_SIGN_TAG, expiration)
+                .serialize(&mut bound_bytes)
+                .expect("Encoding the expiration shouldn't fail");
+        }
+        hash_tx(&([code_hash,data_bytes,timestamp_bytes,&bound_bytes].concat())).0
     }
 
     /// Sign a transaction using [`SignedTxData`].
     pub fn sign(self, keypair: &common::SecretKey) -> Self {
         let to_sign = self.tx_to_sign();
         let sig = common::SigScheme::sign(keypair, &to_sign);
+        self.attach_signature(sig)
+    }
+
+    /// Attach a signature of [`Tx::tx_to_sign`] made by an external signer
+    /// using [`SignedTxData`].
+    pub fn attach_signature(self, sig: common::Signature) -> Self {
+        self.attach_signatures(sig, BTreeMap::new())
+    }
+
+    /// Attach a signature of [`Tx::tx_to_sign`] together with the signatures
+    /// of the signers of a multisignature account using [`SignedTxData`].
+    pub fn attach_signatures(
+        self,
+        sig: common::Signature,
+        multisig: BTreeMap<u8, common::Signature>,
+    ) -> Self {
         let signed = SignedTxData {
             data: self.data,
             sig,
+            multisig,
         }
             .try_to_vec()
             .expect("Encoding transaction data shouldn't fail");
@@ -269,6 +419,8 @@ impl Tx {
             code: self.code,
             data: Some(signed),
             timestamp: self.timestamp,
+            chain_id: self.chain_id,
+            expiration: self.expiration,
         }
     }
 
@@ -280,7 +432,7 @@ impl Tx {
         sig: &common::Signature,
     ) -> std::result::Result<(), VerifySigError> {
         // Try to get the transaction data from decoded `SignedTxData`
-        let tx_data = self.data.clone().ok_or(VerifySigError::MissingData)?;
+        let tx_data = self.data.as_ref().ok_or(VerifySigError::MissingData)?;
         let signed_tx_data = SignedTxData::try_from_slice(&tx_data[..])
             .expect("Decoding transaction data shouldn't fail");
         let data = signed_tx_data.data;
@@ -288,6 +440,8 @@ impl Tx {
             code: self.code.clone(),
             data,
             timestamp: self.timestamp,
+            chain_id: self.chain_id.clone(),
+            expiration: self.expiration,
         };
         let signed_data = tx.tx_to_sign();
         common::SigScheme::verify_signature_raw(pk, &signed_data, sig)
@@ -479,6 +633,7 @@ impl Dkg {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::types::time::Duration;
 
     #[test]
     fn test_tx() {
@@ -495,6 +650,8 @@ mod tests {
             code,
             data: Some(data),
             timestamp: None,
+            chain_id: String::new(),
+            expiration: None,
         };
         let mut bytes = vec![];
         types_tx.encode(&mut bytes).expect("encoding failed");
@@ -504,6 +661,82 @@ mod tests {
         }
     }
 
+    /// Test that the tx is encoded in the same way as its protobuf message
+    #[test]
+    fn test_tx_encoding() {
+        let code = "wasm code".as_bytes().to_owned();
+        let data = "arbitrary data".as_bytes().to_owned();
+        let txs = [
+            Tx::new(vec![], None),
+            Tx::new(code.clone(), Some(vec![])),
+            Tx::new(code, Some(data))
+                .with_chain_id(ChainId::default())
+                .with_expiration(DateTimeUtc::now()),
+        ];
+        for tx in txs {
+            let mut bytes = vec![];
+            types::Tx::from(tx.clone())
+                .encode(&mut bytes)
+                .expect("encoding failed");
+            assert_eq!(tx.to_bytes(), bytes);
+            assert_eq!(tx.hash(), hash_tx(&bytes).0);
+        }
+    }
+
+    /// Test that the expiration of a tx is decoded and covered by its
+    /// signature
+    #[test]
+    fn test_tx_expiration() {
+        let keypair = crate::types::key::testing::keypair_1();
+        let expiration = DateTimeUtc::now();
+        let tx = Tx::new("wasm code".as_bytes().to_owned(), None)
+            .with_expiration(expiration)
+            .sign(&keypair);
+
+        let bytes = tx.to_bytes();
+        let tx_from_bytes =
+            Tx::try_from(bytes.as_ref()).expect("decoding failed");
+        assert_eq!(tx_from_bytes.expiration, Some(expiration));
+
+        let signed = SignedTxData::try_from_slice(&tx.data.clone().unwrap())
+            .expect("decoding failed");
+        let pk = keypair.ref_to();
+        assert!(tx.verify_sig(&pk, &signed.sig).is_ok());
+
+        let extended = Tx {
+            expiration: Some(DateTimeUtc(expiration.0 + Duration::hours(1))),
+            ..tx
+        };
+        assert!(extended.verify_sig(&pk, &signed.sig).is_err());
+    }
+
+    /// Test that the chain ID of a tx is covered by its signature
+    #[test]
+    fn test_tx_chain_id_signed() {
+        let keypair = crate::types::key::testing::keypair_1();
+        let tx = Tx::new("wasm code".as_bytes().to_owned(), None)
+            .with_chain_id(ChainId::default())
+            .sign(&keypair);
+        let signed = SignedTxData::try_from_slice(&tx.data.clone().unwrap())
+            .expect("decoding failed");
+        let pk = keypair.ref_to();
+        assert!(tx.verify_sig(&pk, &signed.sig).is_ok());
+
+        let unbound = Tx {
+            chain_id: None,
+            ..tx.clone()
+        };
+        assert!(unbound.verify_sig(&pk, &signed.sig).is_err());
+
+        let other_chain =
+            ChainId::from_str("anoma-otherchain.0000000000000").unwrap();
+        let rebound = Tx {
+            chain_id: Some(other_chain),
+            ..tx
+        };
+        assert!(rebound.verify_sig(&pk, &signed.sig).is_err());
+    }
+
     #[test]
     fn test_serialize_tx() {
         let code = "wasm code".as_bytes().to_owned();
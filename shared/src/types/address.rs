@@ -67,6 +67,8 @@ mod internal {
         "ano::IBC Mint Address                        ";
     pub const ETH_BRIDGE: &str =
         "ano::ETH Bridge Address                      ";
+    pub const NATIVE_TOKEN: &str =
+        "ano::Native Token                            ";
 }
 
 /// Fixed-length address strings prefix for established addresses.
@@ -194,6 +196,9 @@ impl Address {
                     InternalAddress::EthBridge => {
                         internal::ETH_BRIDGE.to_string()
                     }
+                    InternalAddress::NativeToken => {
+                        internal::NATIVE_TOKEN.to_string()
+                    }
                 };
                 debug_assert_eq!(string.len(), FIXED_LEN_STRING_BYTES);
                 string
@@ -254,6 +259,9 @@ impl Address {
                 internal::ETH_BRIDGE => {
                     Ok(Address::Internal(InternalAddress::EthBridge))
                 }
+                internal::NATIVE_TOKEN => {
+                    Ok(Address::Internal(InternalAddress::NativeToken))
+                }
                 _ if raw.len() == HASH_LEN => Ok(Address::Internal(
                     InternalAddress::IbcEscrow(raw.to_string()),
                 )),
@@ -451,6 +459,9 @@ pub enum InternalAddress {
     Treasury,
     /// Bridge to Ethereum
     EthBridge,
+    /// The native checks of the tokens' balances, which run in addition to
+    /// the tokens' own validity predicates
+    NativeToken,
 }
 
 impl InternalAddress {
@@ -480,6 +491,7 @@ impl Display for InternalAddress {
                 Self::IbcBurn => "IbcBurn".to_string(),
                 Self::IbcMint => "IbcMint".to_string(),
                 Self::EthBridge => "EthBridge".to_string(),
+                Self::NativeToken => "NativeToken".to_string(),
             }
         )
     }
@@ -717,8 +729,9 @@ pub mod testing {
             InternalAddress::IbcEscrow(_) => {}
             InternalAddress::IbcBurn => {}
             InternalAddress::IbcMint => {}
-            InternalAddress::EthBridge => {} /* Add new addresses in the
-                                              * `prop_oneof` below. */
+            InternalAddress::EthBridge => {}
+            InternalAddress::NativeToken => {} /* Add new addresses in the
+                                                * `prop_oneof` below. */
         };
         prop_oneof![
             Just(InternalAddress::PoS),
@@ -732,6 +745,7 @@ pub mod testing {
             Just(InternalAddress::Governance),
             Just(InternalAddress::Treasury),
             Just(InternalAddress::EthBridge),
+            Just(InternalAddress::NativeToken),
         ]
     }
 
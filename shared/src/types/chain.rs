@@ -4,7 +4,7 @@
 use std::fmt::Display;
 use std::str::FromStr;
 
-use borsh::{BorshDeserialize, BorshSerialize};
+use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 use thiserror::Error;
@@ -32,6 +32,7 @@ pub const DEFAULT_CHAIN_ID: &str = "anoma-internal.000000000000000";
     Deserialize,
     BorshSerialize,
     BorshDeserialize,
+    BorshSchema,
     PartialOrd,
     Ord,
     PartialEq,
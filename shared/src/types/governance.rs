@@ -74,6 +74,7 @@ impl FromStr for ProposalVote {
 }
 
 /// The result of a proposal
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum TallyResult {
     /// Proposal was accepted
     Passed,
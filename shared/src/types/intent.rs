@@ -1,6 +1,6 @@
 //! Intent data definitions and transaction and validity-predicate helpers.
 
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeSet, HashMap, HashSet};
 use std::convert::TryFrom;
 use std::io::ErrorKind;
 
@@ -13,6 +13,7 @@ use thiserror::Error;
 use crate::proto::Signed;
 use crate::types::address::Address;
 use crate::types::storage::{DbKeySeg, Key, KeySeg};
+use crate::types::time::DateTimeUtc;
 use crate::types::token;
 
 /// A simple intent for fungible token trade
@@ -61,6 +62,15 @@ pub struct Exchange {
     /// The vp code
     #[derivative(Debug = "ignore")]
     pub vp: Option<Vec<u8>>,
+    /// The time after which the exchange can no longer be matched, if any
+    pub expiry: Option<DateTimeUtc>,
+}
+
+impl Exchange {
+    /// Check if the exchange has expired at the given time.
+    pub fn is_expired(&self, time: DateTimeUtc) -> bool {
+        matches!(self.expiry, Some(expiry) if expiry <= time)
+    }
 }
 
 /// These are transfers crafted from matched [`Exchange`]s created by a
@@ -105,6 +115,63 @@ pub struct IntentTransfers {
     pub source: Address,
 }
 
+/// A filter that a matchmaker can register with an intent gossiper node to
+/// only receive the intents that are relevant to it. An intent passes the
+/// filter if any of its exchanges passes it. An empty filter lets any intent
+/// pass.
+#[derive(
+    Debug,
+    Clone,
+    Default,
+    PartialEq,
+    Eq,
+    BorshSerialize,
+    BorshDeserialize,
+    Serialize,
+    Deserialize,
+)]
+pub struct IntentFilter {
+    /// The pairs of the token sold and the token bought of the exchanges to
+    /// pass. If empty, exchanges of any tokens pass.
+    pub token_pairs: BTreeSet<(Address, Address)>,
+    /// The minimum amount of token sold of the exchanges to pass, if any
+    pub min_sell: Option<token::Amount>,
+}
+
+impl IntentFilter {
+    /// Check if the filter lets any intent pass.
+    pub fn is_empty(&self) -> bool {
+        self.token_pairs.is_empty() && self.min_sell.is_none()
+    }
+
+    /// Check if the given exchange passes the filter.
+    pub fn matches_exchange(&self, exchange: &Exchange) -> bool {
+        let pair = (exchange.token_sell.clone(), exchange.token_buy.clone());
+        (self.token_pairs.is_empty() || self.token_pairs.contains(&pair))
+            && self
+                .min_sell
+                .map(|min_sell| exchange.max_sell >= min_sell)
+                .unwrap_or(true)
+    }
+
+    /// Check if the given intent data passes the filter. Intent data that
+    /// cannot be decoded as a [`FungibleTokenIntent`] only passes an empty
+    /// filter.
+    pub fn matches(&self, intent_data: &[u8]) -> bool {
+        if self.is_empty() {
+            return true;
+        }
+        match Signed::<FungibleTokenIntent>::try_from_slice(intent_data) {
+            Ok(intent) => intent
+                .data
+                .exchange
+                .iter()
+                .any(|exchange| self.matches_exchange(&exchange.data)),
+            Err(_) => false,
+        }
+    }
+}
+
 /// Struct holding a safe rapresentation of a float
 #[derive(
     Debug,
@@ -259,6 +326,7 @@ mod tests {
             min_buy: token::Amount::from(1),
             rate_min: DecimalWrapper::from_str("0.1").unwrap(),
             vp: None,
+            expiry: None,
         };
         let exchange_two = Exchange {
             addr: Address::from_str(ALBERT).unwrap(),
@@ -268,6 +336,7 @@ mod tests {
             min_buy: token::Amount::from(100),
             rate_min: DecimalWrapper::from_str("10").unwrap(),
             vp: None,
+            expiry: None,
         };
 
         let signed_exchange_one = Signed::new(&bertha_keypair, exchange_one);
@@ -360,6 +429,7 @@ mod tests {
                 ))
                 .unwrap(),
             ),
+            expiry: None,
         };
         let exchange_two = Exchange {
             addr: Address::from_str(ALBERT).unwrap(),
@@ -376,6 +446,7 @@ mod tests {
                 ))
                 .unwrap(),
             ),
+            expiry: None,
         };
 
         let signed_exchange_one = Signed::new(&bertha_keypair, exchange_one);
@@ -443,6 +514,71 @@ mod tests {
         assert!(decoded_intent_transfer == it);
     }
 
+    #[test]
+    fn test_exchange_expiry() {
+        let now = DateTimeUtc::now();
+        let mut exchange = Exchange {
+            addr: Address::from_str(BERTHA).unwrap(),
+            token_buy: Address::from_str(XAN).unwrap(),
+            token_sell: Address::from_str(BTC).unwrap(),
+            max_sell: token::Amount::from(100),
+            min_buy: token::Amount::from(1),
+            rate_min: DecimalWrapper::from_str("0.1").unwrap(),
+            vp: None,
+            expiry: None,
+        };
+        assert!(!exchange.is_expired(now));
+
+        exchange.expiry = Some(now);
+        assert!(exchange.is_expired(now));
+
+        exchange.expiry = Some(now + crate::types::time::DurationSecs(60));
+        assert!(!exchange.is_expired(now));
+    }
+
+    #[test]
+    fn test_intent_filter() {
+        let xan = Address::from_str(XAN).unwrap();
+        let btc = Address::from_str(BTC).unwrap();
+        let exchange = Exchange {
+            addr: Address::from_str(BERTHA).unwrap(),
+            token_buy: xan.clone(),
+            token_sell: btc.clone(),
+            max_sell: token::Amount::from(100),
+            min_buy: token::Amount::from(1),
+            rate_min: DecimalWrapper::from_str("0.1").unwrap(),
+            vp: None,
+            expiry: None,
+        };
+        let keypair = key::testing::keypair_1();
+        let intent = Signed::new(
+            &keypair,
+            FungibleTokenIntent {
+                exchange: HashSet::from_iter(vec![Signed::new(
+                    &keypair,
+                    exchange.clone(),
+                )]),
+            },
+        );
+        let intent_data = intent.try_to_vec().unwrap();
+
+        let mut filter = IntentFilter::default();
+        assert!(filter.matches(b"not an intent"));
+        assert!(filter.matches(&intent_data));
+
+        filter.token_pairs.insert((xan.clone(), btc.clone()));
+        assert!(!filter.matches(&intent_data));
+        assert!(!filter.matches(b"not an intent"));
+
+        filter.token_pairs.insert((btc, xan));
+        assert!(filter.matches(&intent_data));
+
+        filter.min_sell = Some(token::Amount::from(101));
+        assert!(!filter.matches_exchange(&exchange));
+        filter.min_sell = Some(token::Amount::from(100));
+        assert!(filter.matches_exchange(&exchange));
+    }
+
     #[cfg(test)]
     #[allow(dead_code)]
     mod constants {
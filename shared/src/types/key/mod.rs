@@ -332,6 +332,21 @@ impl<PK: PublicKey> From<&PK> for PublicKeyHash {
     }
 }
 
+/// Get the raw hash of a validator's consensus key, which is the validator's
+/// address in Tendermint: the upper-case hex of the first 20 bytes of the
+/// SHA-256 hash of the raw key.
+pub fn tm_consensus_key_raw_hash(pk: &common::PublicKey) -> String {
+    match pk {
+        common::PublicKey::Ed25519(pk) => PublicKeyHash::from(pk).into(),
+    }
+}
+
+/// Convert a validator's address in Tendermint into the raw hash of its
+/// consensus key.
+pub fn tm_raw_hash_to_string(raw_hash: impl AsRef<[u8]>) -> String {
+    hex::encode_upper(raw_hash)
+}
+
 /// Helpers for testing with keys.
 #[cfg(any(test, feature = "testing"))]
 pub mod testing {
@@ -421,3 +436,22 @@ macro_rules! sigscheme_test {
 
 #[cfg(test)]
 sigscheme_test! {ed25519_test, ed25519::SigScheme}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Test that the raw hash of a consensus key matches the Tendermint
+    /// address of the key
+    #[test]
+    fn test_tm_consensus_key_raw_hash() {
+        let pk = testing::keypair_1().ref_to();
+        let raw_hash = tm_consensus_key_raw_hash(&pk);
+        assert_eq!(raw_hash.len(), 40);
+
+        let ed25519_pk = ed25519::PublicKey::try_from_pk(&pk).unwrap();
+        let pk_bytes = ed25519_pk.try_to_vec().unwrap();
+        let tm_address = &Sha256::digest(&pk_bytes)[..20];
+        assert_eq!(raw_hash, tm_raw_hash_to_string(tm_address));
+    }
+}
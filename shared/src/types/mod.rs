@@ -10,6 +10,7 @@ pub mod intent;
 pub mod internal;
 pub mod key;
 pub mod matchmaker;
+pub mod multisig;
 pub mod nft;
 pub mod storage;
 pub mod time;
@@ -0,0 +1,156 @@
+//! Multisignature accounts. A k-of-n multisignature account stores the public
+//! keys of its n signers and the threshold k in its storage sub-space. A
+//! transaction is authorized by the account when at least k of the signers
+//! have signed it.
+
+use std::collections::BTreeMap;
+
+use super::address::Address;
+use super::key::common;
+use super::storage::{DbKeySeg, Key, KeySeg};
+
+const MULTISIG_STORAGE_KEY: &str = "multisig";
+const PUBLIC_KEYS_STORAGE_KEY: &str = "public_keys";
+const THRESHOLD_STORAGE_KEY: &str = "threshold";
+
+/// The maximum number of signers of a multisignature account, limited by the
+/// `u8` signer indices.
+pub const MAX_SIGNERS: usize = u8::MAX as usize + 1;
+
+/// Obtain a storage key for the public keys of the signers of a
+/// multisignature account.
+pub fn public_keys_key(owner: &Address) -> Key {
+    Key::from(owner.to_db_key())
+        .push(&MULTISIG_STORAGE_KEY.to_owned())
+        .expect("Cannot obtain a storage key")
+        .push(&PUBLIC_KEYS_STORAGE_KEY.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Obtain a storage key for the signature threshold of a multisignature
+/// account.
+pub fn threshold_key(owner: &Address) -> Key {
+    Key::from(owner.to_db_key())
+        .push(&MULTISIG_STORAGE_KEY.to_owned())
+        .expect("Cannot obtain a storage key")
+        .push(&THRESHOLD_STORAGE_KEY.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Check if the given storage key is a multisignature parameter key. If it
+/// is, returns the owner.
+pub fn is_multisig_key(key: &Key) -> Option<&Address> {
+    match &key.segments[..] {
+        [
+            DbKeySeg::AddressSeg(owner),
+            DbKeySeg::StringSeg(prefix),
+            DbKeySeg::StringSeg(key),
+        ] if prefix == MULTISIG_STORAGE_KEY
+            && (key == PUBLIC_KEYS_STORAGE_KEY
+                || key == THRESHOLD_STORAGE_KEY) =>
+        {
+            Some(owner)
+        }
+        _ => None,
+    }
+}
+
+/// Check that the signer set and threshold are well-formed, i.e. that there
+/// are at most [`MAX_SIGNERS`] signers, no duplicate keys and that the
+/// threshold is between 1 and the number of signers.
+pub fn validate_params(
+    public_keys: &[common::PublicKey],
+    threshold: u8,
+) -> bool {
+    let no_duplicates = public_keys
+        .iter()
+        .enumerate()
+        .all(|(ix, pk)| !public_keys[..ix].contains(pk));
+    public_keys.len() <= MAX_SIGNERS
+        && no_duplicates
+        && threshold > 0
+        && threshold as usize <= public_keys.len()
+}
+
+/// Count the signatures that are valid for the signer at their index, using
+/// the given function to verify a signature with a public key. Signatures
+/// with an index outside of the signer set are ignored.
+pub fn count_valid_signatures(
+    public_keys: &[common::PublicKey],
+    signatures: &BTreeMap<u8, common::Signature>,
+    verify: impl Fn(&common::PublicKey, &common::Signature) -> bool,
+) -> usize {
+    signatures
+        .iter()
+        .filter(|(ix, sig)| match public_keys.get(**ix as usize) {
+            Some(pk) => verify(pk, sig),
+            None => false,
+        })
+        .count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::key::testing::{keypair_1, keypair_2};
+    use crate::types::key::{pk_key, RefTo, SigScheme};
+
+    #[test]
+    fn test_multisig_keys() {
+        let owner = crate::types::address::testing::established_address_1();
+        assert_eq!(is_multisig_key(&public_keys_key(&owner)), Some(&owner));
+        assert_eq!(is_multisig_key(&threshold_key(&owner)), Some(&owner));
+        assert_eq!(is_multisig_key(&pk_key(&owner)), None);
+    }
+
+    #[test]
+    fn test_validate_params() {
+        let pk1: common::PublicKey = keypair_1().ref_to();
+        let pk2: common::PublicKey = keypair_2().ref_to();
+        assert!(validate_params(&[pk1.clone(), pk2.clone()], 1));
+        assert!(validate_params(&[pk1.clone(), pk2.clone()], 2));
+        assert!(!validate_params(&[pk1.clone(), pk2], 3));
+        assert!(!validate_params(&[pk1.clone()], 0));
+        assert!(!validate_params(&[pk1.clone(), pk1], 2));
+    }
+
+    #[test]
+    fn test_count_valid_signatures() {
+        let (sk1, sk2) = (keypair_1(), keypair_2());
+        let public_keys: Vec<common::PublicKey> =
+            vec![sk1.ref_to(), sk2.ref_to()];
+        let msg: &[u8] = b"multisig";
+        let verify = |pk: &common::PublicKey, sig: &common::Signature| {
+            common::SigScheme::verify_signature_raw(pk, msg, sig).is_ok()
+        };
+        let sig1 = common::SigScheme::sign(&sk1, msg);
+        let sig2 = common::SigScheme::sign(&sk2, msg);
+
+        let mut signatures = BTreeMap::new();
+        signatures.insert(0, sig1.clone());
+        assert_eq!(
+            count_valid_signatures(&public_keys, &signatures, verify),
+            1
+        );
+
+        // A signature at the wrong index is not valid
+        signatures.insert(1, sig1.clone());
+        assert_eq!(
+            count_valid_signatures(&public_keys, &signatures, verify),
+            1
+        );
+
+        signatures.insert(1, sig2.clone());
+        assert_eq!(
+            count_valid_signatures(&public_keys, &signatures, verify),
+            2
+        );
+
+        // Signatures outside of the signer set are ignored
+        signatures.insert(2, sig2);
+        assert_eq!(
+            count_valid_signatures(&public_keys, &signatures, verify),
+            2
+        );
+    }
+}
@@ -709,6 +709,54 @@ pub struct PrefixValue {
     pub value: Vec<u8>,
 }
 
+/// The type of a change of a storage key made by a transaction.
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    PartialEq,
+    Eq,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshSchema,
+)]
+pub enum ChangeType {
+    /// A value has been written
+    Write,
+    /// A value has been deleted
+    Delete,
+    /// A new account has been initialized, the key points to its validity
+    /// predicate
+    InitAccount,
+    /// A temporary value has been written, it will not be committed
+    Temp,
+}
+
+/// A storage key changed by a transaction, passed to validity predicates.
+#[derive(
+    Clone, Debug, PartialEq, Eq, BorshSerialize, BorshDeserialize, BorshSchema,
+)]
+pub struct ChangedKey {
+    /// The address of the account that owns the key, i.e. the address in the
+    /// key's first segment, if any
+    pub owner: Option<Address>,
+    /// The changed key
+    pub key: Key,
+    /// The type of the change
+    pub change: ChangeType,
+}
+
+impl ChangedKey {
+    /// Create a changed key entry. The owner is found from the key.
+    pub fn new(key: Key, change: ChangeType) -> Self {
+        let owner = match key.get_at(0) {
+            Some(DbKeySeg::AddressSeg(addr)) => Some(addr.clone()),
+            _ => None,
+        };
+        Self { owner, key, change }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use proptest::prelude::*;
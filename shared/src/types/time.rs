@@ -3,6 +3,7 @@
 use std::convert::{TryFrom, TryInto};
 use std::fmt::Display;
 use std::ops::{Add, Sub};
+use std::str::FromStr;
 
 use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
 pub use chrono::{DateTime, Duration, TimeZone, Utc};
@@ -167,6 +168,27 @@ impl BorshSchema for DateTimeUtc {
     }
 }
 
+impl serde::Serialize for DateTimeUtc {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        // Encoded as rfc3339 `String`
+        Rfc3339String::from(*self).serialize(serializer)
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for DateTimeUtc {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        use serde::de::Error;
+        let raw = Rfc3339String::deserialize(deserializer)?;
+        raw.try_into().map_err(D::Error::custom)
+    }
+}
+
 impl From<DateTime<Utc>> for DateTimeUtc {
     fn from(dt: DateTime<Utc>) -> Self {
         Self(dt)
@@ -207,6 +229,15 @@ impl TryFrom<Rfc3339String> for DateTimeUtc {
     }
 }
 
+impl FromStr for DateTimeUtc {
+    type Err = chrono::ParseError;
+
+    /// Parse an RFC 3339 timestamp
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Rfc3339String(s.to_owned()).try_into()
+    }
+}
+
 impl From<DateTimeUtc> for Rfc3339String {
     fn from(dt: DateTimeUtc) -> Self {
         Self(DateTime::to_rfc3339(&dt.0))
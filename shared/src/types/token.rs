@@ -1,5 +1,6 @@
 //! A basic fungible token
 
+use std::collections::BTreeSet;
 use std::convert::TryFrom;
 use std::fmt::Display;
 use std::ops::{Add, AddAssign, Mul, Sub, SubAssign};
@@ -60,6 +61,13 @@ impl Amount {
         self.micro = self.micro.checked_add(amount.micro).unwrap();
     }
 
+    /// Add a given amount. Returns `None` on overflow.
+    pub fn checked_add(&self, amount: Amount) -> Option<Self> {
+        self.micro
+            .checked_add(amount.micro)
+            .map(|micro| Self { micro })
+    }
+
     /// Create a new amount from whole number of tokens
     pub const fn whole(amount: u64) -> Self {
         Self {
@@ -231,6 +239,24 @@ impl From<Amount> for Change {
     }
 }
 
+/// Get the change of a balance from its `pre` to its `post` amount. Returns
+/// `None` on an overflow.
+pub fn checked_balance_change(pre: Amount, post: Amount) -> Option<Change> {
+    post.change().checked_sub(pre.change())
+}
+
+/// Get the total change of the `pre` and `post` balances of a token's
+/// accounts changed by a tx, using checked arithmetic. The total of the
+/// balances is conserved when the total change is zero. Returns `None` on an
+/// overflow.
+pub fn checked_total_change(
+    balances: impl IntoIterator<Item = (Amount, Amount)>,
+) -> Option<Change> {
+    balances.into_iter().try_fold(0, |total: Change, (pre, post)| {
+        total.checked_add(checked_balance_change(pre, post)?)
+    })
+}
+
 /// Key segment for a balance key
 pub const BALANCE_STORAGE_KEY: &str = "balance";
 
@@ -297,6 +323,188 @@ pub fn is_non_owner_balance_key(key: &Key) -> Option<&Address> {
     }
 }
 
+/// Key segment for a token's metadata key
+pub const METADATA_STORAGE_KEY: &str = "metadata";
+
+/// Key segment for a token's total supply key
+pub const SUPPLY_STORAGE_KEY: &str = "supply";
+
+/// The maximum length of a token symbol
+pub const MAX_TOKEN_SYMBOL_LEN: usize = 16;
+
+/// The metadata of a token, written into its storage sub-space when the token
+/// is initialized
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshSchema,
+    Serialize,
+    Deserialize,
+)]
+pub struct TokenMetadata {
+    /// The token's symbol (ticker)
+    pub symbol: String,
+    /// The number of decimal places used to display the token amounts
+    pub decimals: u8,
+    /// The account that is allowed to change the token's supply, if any
+    pub minter: Option<Address>,
+}
+
+impl TokenMetadata {
+    /// Check that the symbol is non-empty, alphanumeric and at most
+    /// [`MAX_TOKEN_SYMBOL_LEN`] characters long.
+    pub fn is_well_formed(&self) -> bool {
+        !self.symbol.is_empty()
+            && self.symbol.len() <= MAX_TOKEN_SYMBOL_LEN
+            && self.symbol.chars().all(|c| c.is_ascii_alphanumeric())
+    }
+}
+
+/// Obtain a storage key for a token's metadata.
+pub fn metadata_key(token_addr: &Address) -> Key {
+    Key::from(token_addr.to_db_key())
+        .push(&METADATA_STORAGE_KEY.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Obtain a storage key for a token's total supply, which is tracked for
+/// tokens that are minted and burned by their minter.
+pub fn supply_key(token_addr: &Address) -> Key {
+    Key::from(token_addr.to_db_key())
+        .push(&SUPPLY_STORAGE_KEY.to_owned())
+        .expect("Cannot obtain a storage key")
+}
+
+/// Check if the given storage key is a total supply key of any token. If it
+/// is, returns the token.
+pub fn is_any_supply_key(key: &Key) -> Option<&Address> {
+    match &key.segments[..] {
+        [DbKeySeg::AddressSeg(addr), DbKeySeg::StringSeg(key)]
+            if key == SUPPLY_STORAGE_KEY =>
+        {
+            Some(addr)
+        }
+        _ => None,
+    }
+}
+
+/// Check if the given storage key is the metadata key of the given token.
+pub fn is_metadata_key(token_addr: &Address, key: &Key) -> bool {
+    match &key.segments[..] {
+        [DbKeySeg::AddressSeg(addr), DbKeySeg::StringSeg(key)] => {
+            key == METADATA_STORAGE_KEY && addr == token_addr
+        }
+        _ => false,
+    }
+}
+
+/// The storage reads of a token's validity predicate, implemented by the
+/// native and the WASM VPs so that they share the same checks, see
+/// [`validate_token_changes`]
+pub trait TokenVpStorage {
+    /// The error of a read
+    type Error;
+
+    /// Read an amount at the given key before the transaction
+    fn read_amount_pre(&self, key: &Key)
+        -> Result<Option<Amount>, Self::Error>;
+
+    /// Read an amount at the given key after the transaction
+    fn read_amount_post(
+        &self,
+        key: &Key,
+    ) -> Result<Option<Amount>, Self::Error>;
+
+    /// Read an amount at the given key written temporarily by the transaction
+    fn read_amount_temp(
+        &self,
+        key: &Key,
+    ) -> Result<Option<Amount>, Self::Error>;
+
+    /// Read the metadata of the given token before the transaction
+    fn read_metadata_pre(
+        &self,
+        token: &Address,
+    ) -> Result<Option<TokenMetadata>, Self::Error>;
+}
+
+/// Check the changes of a token's storage made by a transaction. The total of
+/// the balances may only change together with the tracked total supply, which
+/// requires the authorization of the token's minter, and every owner whose
+/// balance is decreased must be one of the verifiers. The changes are summed
+/// up with checked arithmetic and an overflow is invalid. Any other change in
+/// the token's storage is invalid.
+pub fn validate_token_changes<S: TokenVpStorage>(
+    storage: &S,
+    token: &Address,
+    keys_changed: &BTreeSet<Key>,
+    verifiers: &BTreeSet<Address>,
+) -> Result<bool, S::Error> {
+    // The pre and post balances of the changed accounts
+    let mut balances = Vec::with_capacity(keys_changed.len());
+    let mut supply_changed = false;
+    for key in keys_changed {
+        if is_any_supply_key(key) == Some(token) {
+            supply_changed = true;
+            continue;
+        }
+        let owner = match is_balance_key(token, key) {
+            Some(owner) => owner,
+            None => {
+                // Unknown changes to this address space are disallowed, but
+                // unknown changes anywhere else are permitted
+                if key.segments.get(0) == Some(&token.to_db_key()) {
+                    return Ok(false);
+                }
+                continue;
+            }
+        };
+        let pre = match owner {
+            Address::Internal(InternalAddress::IbcMint) => Amount::max(),
+            Address::Internal(InternalAddress::IbcBurn) => Amount::default(),
+            _ => storage.read_amount_pre(key)?.unwrap_or_default(),
+        };
+        let post = match owner {
+            Address::Internal(InternalAddress::IbcMint) => {
+                storage.read_amount_temp(key)?.unwrap_or_else(Amount::max)
+            }
+            Address::Internal(InternalAddress::IbcBurn) => {
+                storage.read_amount_temp(key)?.unwrap_or_default()
+            }
+            _ => storage.read_amount_post(key)?.unwrap_or_default(),
+        };
+        // make sure that the spender approved the transaction
+        if post < pre && !verifiers.contains(owner) {
+            return Ok(false);
+        }
+        balances.push((pre, post));
+    }
+    let change = match checked_total_change(balances) {
+        Some(change) => change,
+        None => return Ok(false),
+    };
+    if !supply_changed {
+        return Ok(change == 0);
+    }
+    // minting and burning must be approved by the token's minter
+    let minter = storage
+        .read_metadata_pre(token)?
+        .and_then(|metadata| metadata.minter);
+    match minter {
+        Some(minter) if verifiers.contains(&minter) => {
+            let key = supply_key(token);
+            let pre = storage.read_amount_pre(&key)?.unwrap_or_default();
+            let post = storage.read_amount_post(&key)?.unwrap_or_default();
+            Ok(checked_balance_change(pre, post) == Some(change))
+        }
+        _ => Ok(false),
+    }
+}
+
 /// A simple bilateral token transfer
 #[derive(
     Debug,
@@ -378,6 +586,69 @@ mod tests {
         }
     }
 
+    proptest! {
+        /// Test that the total change of the balances is zero after any
+        /// transfers between the accounts and that a minted amount changes
+        /// it by the same amount
+        #[test]
+        fn test_checked_total_change(
+            (pre, transfers) in arb_balances_and_transfers(),
+            minted in 1..u64::MAX / 16,
+            minter in any::<prop::sample::Index>(),
+        ) {
+            let mut post = pre.clone();
+            for (source, target, share) in transfers {
+                let amount = u64::from(post[source]) / 100 * share;
+                let amount = Amount::from(amount);
+                post[source].spend(&amount);
+                post[target].receive(&amount);
+            }
+            assert_eq!(total_change(&pre, &post), Some(0));
+
+            post[minter.index(post.len())].receive(&Amount::from(minted));
+            assert_eq!(total_change(&pre, &post), Some(minted as Change));
+        }
+    }
+
+    /// Generate the balances of some accounts and the transfers between them,
+    /// as the indices of the source and the target and the transferred share
+    /// of the source's balance in percent
+    fn arb_balances_and_transfers(
+    ) -> impl Strategy<Value = (Vec<Amount>, Vec<(usize, usize, u64)>)> {
+        // The balances are small enough for their total not to overflow
+        prop::collection::vec(0..u64::MAX / 16, 1..10).prop_flat_map(
+            |balances| {
+                let len = balances.len();
+                let transfers = prop::collection::vec(
+                    (0..len, 0..len, 0..=100_u64),
+                    0..20,
+                );
+                let balances: Vec<Amount> =
+                    balances.into_iter().map(Amount::from).collect();
+                (Just(balances), transfers)
+            },
+        )
+    }
+
+    fn total_change(pre: &[Amount], post: &[Amount]) -> Option<Change> {
+        checked_total_change(pre.iter().copied().zip(post.iter().copied()))
+    }
+
+    #[test]
+    fn test_checked_balance_change() {
+        let max = Amount::max();
+        let zero = Amount::default();
+        assert_eq!(
+            checked_balance_change(max, zero),
+            Some(-(u64::MAX as Change))
+        );
+        assert_eq!(checked_balance_change(zero, max), Some(u64::MAX as Change));
+        assert_eq!(
+            checked_total_change(vec![(max, zero); 3]),
+            Some(-3 * u64::MAX as Change)
+        );
+    }
+
     #[test]
     fn test_token_display() {
         let max = Amount::from(u64::MAX);
@@ -392,4 +663,27 @@ mod tests {
         let zero = Amount::from(0);
         assert_eq!("0", zero.to_string());
     }
+
+    #[test]
+    fn test_token_metadata() {
+        let token = crate::types::address::xan();
+        let key = metadata_key(&token);
+        assert!(is_metadata_key(&token, &key));
+        assert!(!is_metadata_key(&crate::types::address::btc(), &key));
+        assert_eq!(is_any_supply_key(&supply_key(&token)), Some(&token));
+        assert_eq!(is_any_supply_key(&key), None);
+
+        let mut metadata = TokenMetadata {
+            symbol: "XAN".to_owned(),
+            decimals: 6,
+            minter: None,
+        };
+        assert!(metadata.is_well_formed());
+        metadata.symbol = "".to_owned();
+        assert!(!metadata.is_well_formed());
+        metadata.symbol = "X A N".to_owned();
+        assert!(!metadata.is_well_formed());
+        metadata.symbol = "X".repeat(MAX_TOKEN_SYMBOL_LEN + 1);
+        assert!(!metadata.is_well_formed());
+    }
 }
@@ -0,0 +1,145 @@
+//! Batched txs. A batch is a tx with an empty code whose data is a
+//! [`TxBatch`]. The inner txs of a batch are executed in order against the
+//! same write log, so that either all or none of their changes are applied,
+//! and the VPs of the accounts touched by any of them are evaluated only once
+//! over the combined changes. This allows flows such as initializing an
+//! account, funding it and setting its VP to be submitted as one tx.
+
+use std::io::{Error, ErrorKind};
+
+use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
+use serde::{Deserialize, Serialize};
+
+use crate::proto::{SignedTxData, Tx};
+
+/// An inner tx of a batch
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshSchema,
+    Serialize,
+    Deserialize,
+)]
+pub struct BatchedTx {
+    /// The tx WASM code
+    pub code: Vec<u8>,
+    /// The data that the tx code is called with, if any
+    pub data: Option<Vec<u8>>,
+}
+
+/// The ordered list of the inner txs of a batch
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshSchema,
+    Serialize,
+    Deserialize,
+)]
+pub struct TxBatch {
+    /// The inner txs, in the order of their execution
+    pub txs: Vec<BatchedTx>,
+}
+
+impl TxBatch {
+    /// Create a new batch from the inner txs
+    pub fn new(txs: Vec<BatchedTx>) -> Self {
+        Self { txs }
+    }
+
+    /// Make an unsigned tx from the batch. Like any other tx, it has to be
+    /// signed before it's submitted.
+    pub fn into_tx(self) -> Tx {
+        let data = self
+            .try_to_vec()
+            .expect("Encoding a tx batch shouldn't fail");
+        Tx::new(vec![], Some(data))
+    }
+
+    /// Check if the tx is a batch
+    pub fn is_batch(tx: &Tx) -> bool {
+        tx.code.is_empty()
+    }
+
+    /// Get the inner txs of a signed batch tx. The data of every inner tx is
+    /// signed with the signature of the whole batch, so that the tx code can
+    /// decode it in the same way as the data of a standalone tx.
+    pub fn signed_inner_txs(tx: &Tx) -> std::io::Result<Vec<BatchedTx>> {
+        let data = tx.data.as_ref().ok_or_else(|| {
+            Error::new(ErrorKind::InvalidData, "The batch has no data")
+        })?;
+        let signed = SignedTxData::try_from_slice(data)?;
+        let batch = Self::try_from_slice(
+            signed.data.as_deref().unwrap_or_default(),
+        )?;
+        if batch.txs.is_empty() {
+            return Err(Error::new(
+                ErrorKind::InvalidData,
+                "The batch is empty",
+            ));
+        }
+        batch
+            .txs
+            .into_iter()
+            .map(|BatchedTx { code, data }| {
+                let data = SignedTxData {
+                    data,
+                    sig: signed.sig.clone(),
+                    multisig: signed.multisig.clone(),
+                }
+                .try_to_vec()?;
+                Ok(BatchedTx {
+                    code,
+                    data: Some(data),
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::key::testing::keypair_1;
+
+    /// Test that the inner txs of a signed batch are given the signature of
+    /// the batch
+    #[test]
+    fn test_signed_inner_txs() {
+        let batch = TxBatch::new(vec![
+            BatchedTx {
+                code: vec![1],
+                data: Some(vec![2]),
+            },
+            BatchedTx {
+                code: vec![3],
+                data: None,
+            },
+        ]);
+        let tx = batch.clone().into_tx();
+        assert!(TxBatch::is_batch(&tx));
+        // An unsigned batch is rejected
+        assert!(TxBatch::signed_inner_txs(&tx).is_err());
+
+        let tx = tx.sign(&keypair_1());
+        let signed = SignedTxData::try_from_slice(&tx.data.clone().unwrap())
+            .expect("the batch should be signed");
+        let inner_txs = TxBatch::signed_inner_txs(&tx).unwrap();
+        assert_eq!(inner_txs.len(), 2);
+        for (inner, original) in inner_txs.into_iter().zip(batch.txs) {
+            assert_eq!(inner.code, original.code);
+            let inner_data =
+                SignedTxData::try_from_slice(&inner.data.unwrap()).unwrap();
+            assert_eq!(inner_data.data, original.data);
+            assert_eq!(inner_data.sig, signed.sig);
+        }
+
+        let empty = TxBatch::new(vec![]).into_tx().sign(&keypair_1());
+        assert!(TxBatch::signed_inner_txs(&empty).is_err());
+    }
+}
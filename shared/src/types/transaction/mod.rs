@@ -1,5 +1,7 @@
 //! Types that are used in transactions.
 
+/// txs batching several inner txs
+pub mod batch;
 /// txs that contain decrypted payloads or assertions of
 /// non-decryptability
 pub mod decrypted;
@@ -9,19 +11,25 @@ pub mod encrypted;
 pub mod governance;
 /// txs to manage nfts
 pub mod nft;
+/// self-describing payloads of the built-in txs
+pub mod payload;
 pub mod pos;
 /// transaction protocols made by validators
 pub mod protocol;
+/// txs to manage fungible tokens
+pub mod token;
 /// wrapper txs with encrypted payloads
 pub mod wrapper;
 
 use std::collections::BTreeSet;
 use std::fmt;
 
+pub use batch::{BatchedTx, TxBatch};
 use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
 pub use decrypted::*;
 #[cfg(feature = "ferveo-tpke")]
 pub use encrypted::EncryptionKey;
+pub use payload::{PayloadV1, TxPayload};
 pub use protocol::UpdateDkgSessionKey;
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
@@ -52,8 +60,9 @@ pub struct TxResult {
     pub vps_result: VpsResult,
     /// New established addresses created by the transaction
     pub initialized_accounts: Vec<Address>,
-    /// Optional IBC event emitted by the transaction
-    pub ibc_event: Option<IbcEvent>,
+    /// The IBC events emitted by the transaction, in the order of their
+    /// emission
+    pub ibc_events: Vec<IbcEvent>,
 }
 
 impl TxResult {
@@ -168,6 +177,29 @@ pub struct InitAccount {
     pub vp_code: Vec<u8>,
 }
 
+/// A tx data type to initialize a new established k-of-n multisignature
+/// account
+#[derive(
+Debug,
+Clone,
+PartialEq,
+BorshSerialize,
+BorshDeserialize,
+BorshSchema,
+Serialize,
+Deserialize,
+)]
+pub struct InitMultisigAccount {
+    /// Public keys of the signers to be written into the account's storage.
+    /// The index of a key in this list is the index of the signer's
+    /// signature in [`crate::proto::SignedTxData::multisig`].
+    pub public_keys: Vec<common::PublicKey>,
+    /// The number of signers required to authorize a transaction
+    pub threshold: u8,
+    /// The VP code
+    pub vp_code: Vec<u8>,
+}
+
 /// A tx data type to initialize a new validator account and its staking reward
 /// account.
 #[derive(
@@ -290,22 +322,24 @@ pub mod tx_types {
         if let Some(Ok(SignedTxData {
                            data: Some(data),
                            ref sig,
+                           ..
                        })) = tx
             .data
             .as_ref()
             .map(|data| SignedTxData::try_from_slice(&data[..]))
         {
-           let signed_hash = Tx {
+            // The signed tx is hashed and then reused for its data, without
+            // copying the data
+            let mut signed_tx = Tx {
                 code: tx.code,
-                data: Some(data.clone()),
-                timestamp: tx.timestamp,
-            }
-                .tx_to_sign();
-            match TxType::try_from(Tx {
-                code: vec![],
                 data: Some(data),
                 timestamp: tx.timestamp,
-            })
+                chain_id: tx.chain_id,
+                expiration: tx.expiration,
+            };
+            let signed_hash = signed_tx.tx_to_sign();
+            signed_tx.code = vec![];
+            match TxType::try_from(signed_tx)
                 .map_err(|err| TxError::Deserialization(err.to_string()))?
             {
                 // verify signature and extract signed data
@@ -340,10 +374,35 @@ pub mod tx_types {
         }
     }
 
+    /// Determines the type of the input Tx like [`process_tx`], but without
+    /// verifying the signature of a wrapper or protocol tx. It must only be
+    /// used for a tx whose signature has already been verified.
+    pub fn process_tx_unverified(tx: Tx) -> Result<TxType, TxError> {
+        if let Some(Ok(SignedTxData {
+            data: Some(data), ..
+        })) = tx
+            .data
+            .as_ref()
+            .map(|data| SignedTxData::try_from_slice(&data[..]))
+        {
+            TxType::try_from(Tx {
+                code: vec![],
+                data: Some(data),
+                timestamp: tx.timestamp,
+                chain_id: tx.chain_id,
+                expiration: tx.expiration,
+            })
+            .map_err(|err| TxError::Deserialization(err.to_string()))
+        } else {
+            process_tx(tx)
+        }
+    }
+
     #[cfg(test)]
     mod test_process_tx {
         use super::*;
         use crate::types::address::xan;
+        use crate::types::chain::ChainId;
         use crate::types::storage::Epoch;
 
         fn gen_keypair() -> common::SecretKey {
@@ -435,7 +494,7 @@ pub mod tx_types {
                 tx.clone(),
                 Default::default(),
             )
-                .sign(&keypair)
+                .sign(&keypair, ChainId::default())
                 .expect("Test failed");
 
             match process_tx(wrapper).expect("Test failed") {
@@ -480,6 +539,41 @@ pub mod tx_types {
             let result = process_tx(tx).expect_err("Test failed");
             assert_matches!(result, TxError::Unsigned(_));
         }
+
+        /// Test that process_tx_unverified extracts a wrapper tx with an
+        /// invalid signature, which is rejected by process_tx
+        #[test]
+        fn test_process_tx_unverified_wrapper_tx() {
+            let keypair = gen_keypair();
+            let tx = Tx::new(
+                "wasm code".as_bytes().to_owned(),
+                Some("transaction data".as_bytes().to_owned()),
+            );
+            let wrapper = WrapperTx::new(
+                Fee {
+                    amount: 10.into(),
+                    token: xan(),
+                },
+                &keypair,
+                Epoch(0),
+                0.into(),
+                tx,
+                Default::default(),
+            );
+            // signed with a key other than the wrapper's
+            let tx = wrapper
+                .to_unsigned_tx(ChainId::default())
+                .sign(&gen_keypair());
+
+            let result = process_tx(tx.clone()).expect_err("Test failed");
+            assert_matches!(result, TxError::SigError(_));
+            match process_tx_unverified(tx).expect("Test failed") {
+                TxType::Wrapper(unverified) => {
+                    assert_eq!(unverified.pk, wrapper.pk)
+                }
+                _ => panic!("Test failed: Expected Wrapper Tx"),
+            }
+        }
     }
 
     /// Test that process_tx correctly identifies a DecryptedTx
@@ -520,6 +614,7 @@ pub mod tx_types {
                     .expect("Test failed"),
             ),
             sig: common::Signature::try_from_sig(&ed_sig).unwrap(),
+            multisig: Default::default(),
         };
         // create the tx with signed decrypted data
         let tx =
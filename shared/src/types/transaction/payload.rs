@@ -0,0 +1,176 @@
+//! Self-describing payloads of the built-in txs. The data of a built-in tx is
+//! a [`TxPayload`], whose encoding carries both the version of the payload
+//! format and the kind of the tx, so that clients and indexers can decode the
+//! data of any built-in tx without knowing which tx code it was submitted
+//! with.
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use serde::{Deserialize, Serialize};
+
+use super::governance::{InitProposalData, VoteProposalData};
+use super::{pos, InitAccount};
+use crate::types::token;
+
+/// A versioned payload of a built-in tx. The index of the variant, which is
+/// the first byte of the Borsh encoding, is the version of the format. New
+/// versions must be added as new variants, so that the payloads of the
+/// previous versions can still be decoded.
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    BorshSerialize,
+    BorshDeserialize,
+    Serialize,
+    Deserialize,
+)]
+pub enum TxPayload {
+    /// The first version of the payloads
+    V1(PayloadV1),
+}
+
+/// The payloads of the built-in txs in the first version of the format
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    BorshSerialize,
+    BorshDeserialize,
+    Serialize,
+    Deserialize,
+)]
+pub enum PayloadV1 {
+    /// A transfer of fungible tokens
+    Transfer(token::Transfer),
+    /// A PoS bond
+    Bond(pos::Bond),
+    /// A PoS unbond
+    Unbond(pos::Unbond),
+    /// A PoS withdrawal
+    Withdraw(pos::Withdraw),
+    /// An initialization of an established account
+    InitAccount(InitAccount),
+    /// A new governance proposal
+    InitProposal(InitProposalData),
+    /// A vote on a governance proposal
+    VoteProposal(VoteProposalData),
+}
+
+impl From<PayloadV1> for TxPayload {
+    fn from(payload: PayloadV1) -> Self {
+        Self::V1(payload)
+    }
+}
+
+impl TxPayload {
+    /// Decode a payload from the data of a tx
+    pub fn decode(bytes: &[u8]) -> std::io::Result<Self> {
+        Self::try_from_slice(bytes)
+    }
+
+    /// Encode the payload into the data of a tx
+    pub fn encode(&self) -> Vec<u8> {
+        self.try_to_vec().expect("Encoding a tx payload shouldn't fail")
+    }
+
+    /// The name of the kind of the tx, e.g. for display by clients
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::V1(payload) => match payload {
+                PayloadV1::Transfer(_) => "transfer",
+                PayloadV1::Bond(_) => "bond",
+                PayloadV1::Unbond(_) => "unbond",
+                PayloadV1::Withdraw(_) => "withdraw",
+                PayloadV1::InitAccount(_) => "init_account",
+                PayloadV1::InitProposal(_) => "init_proposal",
+                PayloadV1::VoteProposal(_) => "vote_proposal",
+            },
+        }
+    }
+
+    /// Get the transfer, if it's a transfer payload
+    pub fn into_transfer(self) -> Option<token::Transfer> {
+        match self {
+            Self::V1(PayloadV1::Transfer(transfer)) => Some(transfer),
+            _ => None,
+        }
+    }
+
+    /// Get the bond, if it's a bond payload
+    pub fn into_bond(self) -> Option<pos::Bond> {
+        match self {
+            Self::V1(PayloadV1::Bond(bond)) => Some(bond),
+            _ => None,
+        }
+    }
+
+    /// Get the unbond, if it's an unbond payload
+    pub fn into_unbond(self) -> Option<pos::Unbond> {
+        match self {
+            Self::V1(PayloadV1::Unbond(unbond)) => Some(unbond),
+            _ => None,
+        }
+    }
+
+    /// Get the withdrawal, if it's a withdrawal payload
+    pub fn into_withdraw(self) -> Option<pos::Withdraw> {
+        match self {
+            Self::V1(PayloadV1::Withdraw(withdraw)) => Some(withdraw),
+            _ => None,
+        }
+    }
+
+    /// Get the account initialization, if it's an init account payload
+    pub fn into_init_account(self) -> Option<InitAccount> {
+        match self {
+            Self::V1(PayloadV1::InitAccount(init_account)) => {
+                Some(init_account)
+            }
+            _ => None,
+        }
+    }
+
+    /// Get the proposal, if it's an init proposal payload
+    pub fn into_init_proposal(self) -> Option<InitProposalData> {
+        match self {
+            Self::V1(PayloadV1::InitProposal(proposal)) => Some(proposal),
+            _ => None,
+        }
+    }
+
+    /// Get the vote, if it's a vote proposal payload
+    pub fn into_vote_proposal(self) -> Option<VoteProposalData> {
+        match self {
+            Self::V1(PayloadV1::VoteProposal(vote)) => Some(vote),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::address::testing::{
+        established_address_1, established_address_2,
+    };
+
+    /// Test that a payload is decoded with its kind and that the version is
+    /// the first byte of its encoding
+    #[test]
+    fn test_payload_round_trip() {
+        let bond = pos::Bond {
+            validator: established_address_1(),
+            amount: token::Amount::from(10),
+            source: Some(established_address_2()),
+        };
+        let payload = TxPayload::from(PayloadV1::Bond(bond.clone()));
+        let bytes = payload.encode();
+        assert_eq!(bytes[0], 0);
+
+        let decoded = TxPayload::decode(&bytes).expect("decoding failed");
+        assert_eq!(decoded, payload);
+        assert_eq!(decoded.kind(), "bond");
+        assert_eq!(decoded.clone().into_unbond(), None);
+        assert_eq!(decoded.into_bond(), Some(bond));
+    }
+}
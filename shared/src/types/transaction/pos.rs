@@ -3,7 +3,9 @@
 use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
 use serde::{Deserialize, Serialize};
 
+use crate::ledger::pos::types::BasisPoints;
 use crate::types::address::Address;
+use crate::types::key::common;
 use crate::types::token;
 
 /// A bond is a validator's self-bond or a delegation from non-validator to a
@@ -53,3 +55,53 @@ pub struct Withdraw {
     /// from self-bonds, the validator is also the source
     pub source: Option<Address>,
 }
+
+/// A change of a validator's consensus key. The new key takes effect from the
+/// pipeline epoch.
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshSchema,
+    Hash,
+    Eq,
+    Serialize,
+    Deserialize,
+)]
+pub struct ChangeConsensusKey {
+    /// Validator address
+    pub validator: Address,
+    /// The new consensus key of the validator
+    pub consensus_key: common::PublicKey,
+}
+
+/// A registration or an update of a validator's metadata.
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshSchema,
+    Hash,
+    Eq,
+)]
+pub struct UpdateValidatorMetadata {
+    /// Validator address
+    pub validator: Address,
+    /// A human-readable name of the validator
+    pub moniker: String,
+    /// The website of the validator
+    pub website: Option<String>,
+    /// Contact information of the validator
+    pub contact: Option<String>,
+    /// The portion of the staking rewards that the validator takes from its
+    /// delegators
+    pub commission_rate: BasisPoints,
+    /// The maximum change of the commission rate in a single epoch. It is
+    /// only used when the metadata are first registered, after which it
+    /// cannot be changed.
+    pub max_commission_rate_change: BasisPoints,
+}
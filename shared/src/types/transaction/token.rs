@@ -0,0 +1,66 @@
+use borsh::{BorshDeserialize, BorshSchema, BorshSerialize};
+use serde::{Deserialize, Serialize};
+
+use crate::types::address::Address;
+use crate::types::token::{Amount, TokenMetadata};
+
+/// A tx data type to initialize a new fungible token
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshSchema,
+    Serialize,
+    Deserialize,
+)]
+pub struct InitToken {
+    /// The token's metadata
+    pub metadata: TokenMetadata,
+    /// The token's VP code
+    pub vp_code: Vec<u8>,
+}
+
+/// The direction of a change of a token's supply
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshSchema,
+    Serialize,
+    Deserialize,
+)]
+pub enum SupplyChange {
+    /// Credit new tokens to the owner and increase the supply
+    Mint,
+    /// Debit tokens from the owner and decrease the supply
+    Burn,
+}
+
+/// A tx data type to mint or burn tokens. It must be authorized by the
+/// token's minter.
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    BorshSerialize,
+    BorshDeserialize,
+    BorshSchema,
+    Serialize,
+    Deserialize,
+)]
+pub struct ChangeSupply {
+    /// The token's address
+    pub token: Address,
+    /// The owner whose balance is credited or debited
+    pub owner: Address,
+    /// The amount of tokens to mint or burn
+    pub amount: Amount,
+    /// Whether the tokens are minted or burned
+    pub change: SupplyChange,
+}
@@ -13,6 +13,7 @@ pub mod wrapper_tx {
 
     use crate::proto::Tx;
     use crate::types::address::Address;
+    use crate::types::chain::ChainId;
     use crate::types::key::*;
     use crate::types::storage::Epoch;
     use crate::types::token::Amount;
@@ -255,15 +256,24 @@ pub mod wrapper_tx {
             }
         }
 
-        /// Sign the wrapper transaction and convert to a normal Tx type
+        /// Sign the wrapper transaction for the chain with the given ID and
+        /// convert to a normal Tx type
         pub fn sign(
             &self,
             keypair: &common::SecretKey,
+            chain_id: ChainId,
         ) -> Result<Tx, WrapperTxErr> {
             if self.pk != keypair.ref_to() {
                 return Err(WrapperTxErr::InvalidKeyPair);
             }
-            Ok(Tx::new(
+            Ok(self.to_unsigned_tx(chain_id).sign(keypair))
+        }
+
+        /// Convert the wrapper transaction to a normal Tx type for the chain
+        /// with the given ID that is yet to be signed with the key of
+        /// [`WrapperTx::pk`]
+        pub fn to_unsigned_tx(&self, chain_id: ChainId) -> Tx {
+            Tx::new(
                 vec![],
                 Some(
                     TxType::Wrapper(self.clone())
@@ -271,7 +281,7 @@ pub mod wrapper_tx {
                         .expect("Could not serialize WrapperTx"),
                 ),
             )
-                .sign(keypair))
+            .with_chain_id(chain_id)
         }
 
         /// Validate the signature of a wrapper tx
@@ -357,6 +367,7 @@ pub mod wrapper_tx {
         use super::*;
         use crate::proto::SignedTxData;
         use crate::types::address::xan;
+        use crate::types::time::DateTimeUtc;
 
         fn gen_keypair() -> common::SecretKey {
             use rand::prelude::ThreadRng;
@@ -393,6 +404,33 @@ pub mod wrapper_tx {
             assert_eq!(tx, decrypted);
         }
 
+        /// We test that the chain ID and the expiration of the inner tx are
+        /// encrypted with it and match the hash commitment when decrypted
+        #[test]
+        fn test_bound_encryption_round_trip() {
+            let tx = Tx::new(
+                "wasm code".as_bytes().to_owned(),
+                Some("transaction data".as_bytes().to_owned()),
+            )
+            .with_chain_id(ChainId::default())
+            .with_expiration(DateTimeUtc::now());
+
+            let wrapper = WrapperTx::new(
+                Fee {
+                    amount: 10.into(),
+                    token: xan(),
+                },
+                &gen_keypair(),
+                Epoch(0),
+                0.into(),
+                tx.clone(),
+                Default::default(),
+            );
+            let privkey = <EllipticCurve as PairingEngine>::G2Affine::prime_subgroup_generator();
+            let decrypted = wrapper.decrypt(privkey).expect("Test failed");
+            assert_eq!(tx, decrypted);
+        }
+
         /// We test that when we try to decrypt a tx and it
         /// does not match the commitment, an error is returned
         #[test]
@@ -445,7 +483,7 @@ pub mod wrapper_tx {
                 tx,
                 Default::default(),
             )
-                .sign(&keypair)
+                .sign(&keypair, ChainId::default())
                 .expect("Test failed");
 
             // we now try to alter the inner tx maliciously
@@ -3,6 +3,7 @@
 use std::collections::BTreeSet;
 use std::convert::TryInto;
 use std::num::TryFromIntError;
+use std::sync::atomic::{AtomicBool, Ordering};
 
 use borsh::{BorshDeserialize, BorshSerialize};
 use thiserror::Error;
@@ -13,6 +14,8 @@ use super::wasm::TxCache;
 use super::wasm::VpCache;
 use super::WasmCacheAccess;
 use crate::ledger::gas::{self, BlockGasMeter, VpGasMeter};
+use crate::ledger::governance::storage as gov_storage;
+use crate::ledger::reserved_keys::{self, ReservedKey};
 use crate::ledger::storage::write_log::{self, WriteLog};
 use crate::ledger::storage::{self, Storage, StorageHasher};
 use crate::ledger::vp_env;
@@ -31,6 +34,24 @@ use crate::vm::{
 
 const VERIFY_TX_SIG_GAS_COST: u64 = 1000;
 const WASM_VALIDATION_GAS_PER_BYTE: u64 = 1;
+const WASM_LOG_GAS_PER_BYTE: u64 = 1;
+
+/// Are the messages from `log_string` host calls forwarded to the node's
+/// tracing output? Disabled by default.
+static WASM_LOG_ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// Enable or disable forwarding of the messages from `log_string` host calls
+/// to the node's tracing output. The gas for these calls is charged either
+/// way, so that the gas costs don't depend on the node's configuration.
+pub fn set_wasm_log_enabled(enabled: bool) {
+    WASM_LOG_ENABLED.store(enabled, Ordering::Relaxed);
+}
+
+/// Are the messages from `log_string` host calls forwarded to the node's
+/// tracing output?
+pub fn is_wasm_log_enabled() -> bool {
+    WASM_LOG_ENABLED.load(Ordering::Relaxed)
+}
 
 /// These runtime errors will abort tx WASM execution immediately
 #[allow(missing_docs)]
@@ -44,6 +65,8 @@ pub enum TxRuntimeError {
     UpdateVpInvalid(WasmValidationError),
     #[error("A validity predicate of an account cannot be deleted")]
     CannotDeleteVp,
+    #[error("Trying to modify a reserved {0} key {1}")]
+    ReservedKeyWrite(ReservedKey, Key),
     #[error(
         "Trying to initialize an account with an invalid validity predicate \
          WASM {0}"
@@ -808,9 +831,13 @@ where
 
     let key = Key::parse(key).map_err(TxRuntimeError::StorageDataError)?;
 
+    check_reserved_key(env, &key)?;
     check_address_existence(env, &key)?;
 
     let write_log = unsafe { env.ctx.write_log.get() };
+    write_log
+        .check_size(&key, value.len())
+        .map_err(TxRuntimeError::StorageModificationError)?;
     let (gas, _size_diff) = write_log
         .write(&key, value)
         .map_err(TxRuntimeError::StorageModificationError)?;
@@ -852,6 +879,9 @@ where
     check_address_existence(env, &key)?;
 
     let write_log = unsafe { env.ctx.write_log.get() };
+    write_log
+        .check_size(&key, value.len())
+        .map_err(TxRuntimeError::StorageModificationError)?;
     let (gas, _size_diff) = write_log
         .write_temp(&key, value)
         .map_err(TxRuntimeError::StorageModificationError)?;
@@ -859,6 +889,36 @@ where
     // TODO: charge the size diff
 }
 
+/// Check that the key is not reserved for the protocol, see
+/// [`reserved_keys`]. The parameter keys can only be modified while the ledger
+/// executes the code of an accepted governance proposal.
+fn check_reserved_key<MEM, DB, H, CA>(
+    env: &TxEnv<MEM, DB, H, CA>,
+    key: &Key,
+) -> TxResult<()>
+where
+    MEM: VmMemory,
+    DB: storage::DB + for<'iter> storage::DBIter<'iter>,
+    H: StorageHasher,
+    CA: WasmCacheAccess,
+{
+    let reserved = match reserved_keys::find_reserved_key(key) {
+        Some(reserved) => reserved,
+        None => return Ok(()),
+    };
+    if reserved == ReservedKey::Parameter {
+        let storage = unsafe { env.ctx.storage.get() };
+        let prefix = gov_storage::get_proposal_execution_prefix_key();
+        let (mut iter, gas) = storage.iter_prefix(&prefix);
+        tx_add_gas(env, gas)?;
+        if iter.next().is_some() {
+            return Ok(());
+        }
+    }
+    tracing::info!("Trying to modify a reserved {} key {}", reserved, key);
+    Err(TxRuntimeError::ReservedKeyWrite(reserved, key.clone()))
+}
+
 fn check_address_existence<MEM, DB, H, CA>(
     env: &TxEnv<MEM, DB, H, CA>,
     key: &Key,
@@ -926,6 +986,7 @@ where
     if key.is_validity_predicate().is_some() {
         return Err(TxRuntimeError::CannotDeleteVp);
     }
+    check_reserved_key(env, &key)?;
 
     let write_log = unsafe { env.ctx.write_log.get() };
     let (gas, _size_diff) = write_log
@@ -936,7 +997,7 @@ where
 }
 
 /// Emitting an IBC event function exposed to the wasm VM Tx environment.
-/// The given IBC event will be set to the write log.
+/// The given IBC event will be added to the write log.
 pub fn tx_emit_ibc_event<MEM, DB, H, CA>(
     env: &TxEnv<MEM, DB, H, CA>,
     event_ptr: u64,
@@ -956,7 +1017,7 @@ where
     let event: IbcEvent = BorshDeserialize::try_from_slice(&event)
         .map_err(TxRuntimeError::EncodingError)?;
     let write_log = unsafe { env.ctx.write_log.get() };
-    let gas = write_log.set_ibc_event(event);
+    let gas = write_log.emit_ibc_event(event);
     tx_add_gas(env, gas)
 }
 
@@ -1012,7 +1073,8 @@ where
 
 /// Storage read posterior state (after tx execution) function exposed to the
 /// wasm VM VP environment. It will try to read from the write log first and if
-/// no entry found then from the storage.
+/// no entry found then from the storage. The temporary values written by the
+/// transaction are visible too.
 ///
 /// Returns `-1` when the key is not present, or the length of the data when
 /// the key is present (the length may be `0`).
@@ -1596,6 +1658,44 @@ where
     vp_env::add_gas(gas_meter, gas)
 }
 
+/// Getting the block time function exposed to the wasm VM VP
+/// environment. The time is that of the block header to which the current
+/// transaction is being applied.
+pub fn vp_get_block_time<MEM, DB, H, EVAL, CA>(
+    env: &VpEnv<MEM, DB, H, EVAL, CA>,
+) -> vp_env::Result<i64>
+where
+    MEM: VmMemory,
+    DB: storage::DB + for<'iter> storage::DBIter<'iter>,
+    H: StorageHasher,
+    EVAL: VpEvaluator,
+    CA: WasmCacheAccess,
+{
+    let gas_meter = unsafe { env.ctx.gas_meter.get() };
+    let storage = unsafe { env.ctx.storage.get() };
+    let (header, gas) = storage
+        .get_block_header(None)
+        .map_err(vp_env::RuntimeError::StorageError)?;
+    vp_env::add_gas(gas_meter, gas)?;
+    Ok(match header {
+        Some(h) => {
+            let time = h
+                .time
+                .to_rfc3339()
+                .try_to_vec()
+                .map_err(vp_env::RuntimeError::EncodingError)?;
+            let len: i64 = time
+                .len()
+                .try_into()
+                .map_err(vp_env::RuntimeError::NumConversionError)?;
+            let result_buffer = unsafe { env.ctx.result_buffer.get() };
+            result_buffer.replace(time);
+            len
+        }
+        None => HostEnvResult::Fail.to_i64(),
+    })
+}
+
 /// Getting the transaction hash function exposed to the wasm VM VP environment.
 pub fn vp_get_tx_code_hash<MEM, DB, H, EVAL, CA>(
     env: &VpEnv<MEM, DB, H, EVAL, CA>,
@@ -1618,6 +1718,59 @@ where
     vp_env::add_gas(gas_meter, gas)
 }
 
+/// Getting the canonical transaction hash function exposed to the wasm VM VP
+/// environment.
+pub fn vp_get_tx_hash<MEM, DB, H, EVAL, CA>(
+    env: &VpEnv<MEM, DB, H, EVAL, CA>,
+    result_ptr: u64,
+) -> vp_env::Result<()>
+where
+    MEM: VmMemory,
+    DB: storage::DB + for<'iter> storage::DBIter<'iter>,
+    H: StorageHasher,
+    EVAL: VpEvaluator,
+    CA: WasmCacheAccess,
+{
+    let gas_meter = unsafe { env.ctx.gas_meter.get() };
+    let tx = unsafe { env.ctx.tx.get() };
+    let hash = vp_env::get_tx_hash(gas_meter, tx)?;
+    let gas = env
+        .memory
+        .write_bytes(result_ptr, hash.0)
+        .map_err(|e| vp_env::RuntimeError::MemoryError(Box::new(e)))?;
+    vp_env::add_gas(gas_meter, gas)
+}
+
+/// Getting the structured entries of the storage keys changed by the
+/// transaction function exposed to the wasm VM VP environment. The entries are
+/// Borsh encoded into the result buffer and the function returns their length.
+pub fn vp_get_changed_keys<MEM, DB, H, EVAL, CA>(
+    env: &VpEnv<MEM, DB, H, EVAL, CA>,
+) -> vp_env::Result<i64>
+where
+    MEM: VmMemory,
+    DB: storage::DB + for<'iter> storage::DBIter<'iter>,
+    H: StorageHasher,
+    EVAL: VpEvaluator,
+    CA: WasmCacheAccess,
+{
+    let gas_meter = unsafe { env.ctx.gas_meter.get() };
+    let keys_changed = unsafe { env.ctx.keys_changed.get() };
+    let write_log = unsafe { env.ctx.write_log.get() };
+    let changed_keys = write_log
+        .get_changed_keys(keys_changed)
+        .try_to_vec()
+        .map_err(vp_env::RuntimeError::EncodingError)?;
+    vp_env::add_gas(gas_meter, changed_keys.len() as _)?;
+    let len: i64 = changed_keys
+        .len()
+        .try_into()
+        .map_err(vp_env::RuntimeError::NumConversionError)?;
+    let result_buffer = unsafe { env.ctx.result_buffer.get() };
+    result_buffer.replace(changed_keys);
+    Ok(len)
+}
+
 /// Getting the block epoch function exposed to the wasm VM VP
 /// environment. The epoch is that of the block to which the current
 /// transaction is being applied.
@@ -1675,8 +1828,8 @@ where
 }
 
 /// Log a string from exposed to the wasm VM Tx environment. The message will be
-/// printed at the [`tracing::Level::INFO`]. This function is for development
-/// only.
+/// printed at the [`tracing::Level::INFO`] only when enabled with
+/// [`set_wasm_log_enabled`]. This function is for development only.
 pub fn tx_log_string<MEM, DB, H, CA>(
     env: &TxEnv<MEM, DB, H, CA>,
     str_ptr: u64,
@@ -1688,11 +1841,15 @@ where
     H: StorageHasher,
     CA: WasmCacheAccess,
 {
-    let (str, _gas) = env
+    let (str, gas) = env
         .memory
         .read_string(str_ptr, str_len as _)
         .map_err(|e| TxRuntimeError::MemoryError(Box::new(e)))?;
-    tracing::info!("WASM Transaction log: {}", str);
+    tx_add_gas(env, gas)?;
+    tx_add_gas(env, str_len * WASM_LOG_GAS_PER_BYTE)?;
+    if is_wasm_log_enabled() {
+        tracing::info!("WASM Transaction log: {}", str);
+    }
     Ok(())
 }
 
@@ -1731,8 +1888,8 @@ where
 }
 
 /// Log a string from exposed to the wasm VM VP environment. The message will be
-/// printed at the [`tracing::Level::INFO`]. This function is for development
-/// only.
+/// printed at the [`tracing::Level::INFO`] only when enabled with
+/// [`set_wasm_log_enabled`]. This function is for development only.
 pub fn vp_log_string<MEM, DB, H, EVAL, CA>(
     env: &VpEnv<MEM, DB, H, EVAL, CA>,
     str_ptr: u64,
@@ -1745,11 +1902,16 @@ where
     EVAL: VpEvaluator,
     CA: WasmCacheAccess,
 {
-    let (str, _gas) = env
+    let (str, gas) = env
         .memory
         .read_string(str_ptr, str_len as _)
         .map_err(|e| vp_env::RuntimeError::MemoryError(Box::new(e)))?;
-    tracing::info!("WASM Validity predicate log: {}", str);
+    let gas_meter = unsafe { env.ctx.gas_meter.get() };
+    vp_env::add_gas(gas_meter, gas)?;
+    vp_env::add_gas(gas_meter, str_len * WASM_LOG_GAS_PER_BYTE)?;
+    if is_wasm_log_enabled() {
+        tracing::info!("WASM Validity predicate log: {}", str);
+    }
     Ok(())
 }
 
@@ -5,7 +5,7 @@ use std::ffi::c_void;
 use std::marker::PhantomData;
 use std::slice;
 
-use wasmparser::{Validator, WasmFeatures};
+use wasmparser::{Parser, Payload, Validator, WasmFeatures};
 
 pub mod host_env;
 pub mod memory;
@@ -15,6 +15,34 @@ pub mod types;
 pub mod wasm;
 use thiserror::Error;
 
+/// The version of the host interface (the host functions and their
+/// signatures) provided to transactions and validity predicates. This must be
+/// incremented whenever a host function is added or changed, so that a WASM
+/// module importing it can't be run by a host that doesn't provide it:
+///
+/// 1. The initial versioned interface
+/// 2. Added `anoma_vp_get_block_time`
+/// 3. Added `anoma_vp_get_changed_keys`
+/// 4. Added `anoma_vp_get_tx_hash`
+pub const HOST_INTERFACE_VERSION: u32 = 4;
+
+/// The oldest version of the host interface still provided by this host. The
+/// host functions of the versions from this one up to the
+/// [`HOST_INTERFACE_VERSION`] are only ever added, so a WASM module targeting
+/// any of them can be run. This must be raised on every breaking change to
+/// the existing host functions.
+pub const MIN_HOST_INTERFACE_VERSION: u32 = 1;
+
+/// The name of the custom section in which a WASM module declares the version
+/// of the host interface it targets, encoded as a little-endian `u32`.
+pub const HOST_INTERFACE_VERSION_SECTION: &str = "anoma_host_interface_version";
+
+/// The WASM features allowed in untrusted code. Because the consensus depends
+/// on bit-for-bit deterministic execution of transactions and validity
+/// predicates, everything beyond the MVP that may introduce non-determinism is
+/// disabled (SIMD, threads, bulk memory, etc.) and with `deterministic_only`,
+/// floating point operations, which may produce non-deterministic NaN
+/// payloads, are rejected.
 const UNTRUSTED_WASM_FEATURES: WasmFeatures = WasmFeatures {
     reference_types: false,
     multi_value: false,
@@ -42,6 +70,23 @@ pub enum WasmValidationError {
          {UNTRUSTED_WASM_FEATURES:?}"
     )]
     ForbiddenWasmFeatures(wasmparser::BinaryReaderError),
+    #[error(
+        "The WASM module doesn't declare the host interface version it \
+         targets. Expected version {HOST_INTERFACE_VERSION} in a custom \
+         section \"{HOST_INTERFACE_VERSION_SECTION}\""
+    )]
+    MissingHostInterfaceVersion,
+    #[error(
+        "Invalid host interface version declaration, expected a 4 bytes long \
+         little-endian u32, got {0} bytes"
+    )]
+    InvalidHostInterfaceVersion(usize),
+    #[error(
+        "The WASM module targets host interface version {found}, but the host \
+         provides versions {MIN_HOST_INTERFACE_VERSION} to \
+         {HOST_INTERFACE_VERSION}"
+    )]
+    HostInterfaceVersionMismatch { found: u32 },
 }
 
 /// WASM Cache access level, used to limit dry-ran transactions to read-only
@@ -237,5 +282,37 @@ pub fn validate_untrusted_wasm(
     validator.wasm_features(UNTRUSTED_WASM_FEATURES);
     validator
         .validate_all(wasm_code.as_ref())
-        .map_err(WasmValidationError::ForbiddenWasmFeatures)
+        .map_err(WasmValidationError::ForbiddenWasmFeatures)?;
+    check_host_interface_version(wasm_code)
+}
+
+/// Check that the wasm code declares the host interface version it targets and
+/// that it's one of the versions provided by this host, i.e. it's between the
+/// [`MIN_HOST_INTERFACE_VERSION`] and the [`HOST_INTERFACE_VERSION`].
+pub fn check_host_interface_version(
+    wasm_code: impl AsRef<[u8]>,
+) -> Result<(), WasmValidationError> {
+    let declared = Parser::new(0)
+        .parse_all(wasm_code.as_ref())
+        // Any malformed payloads are rejected by the validator
+        .filter_map(|payload| payload.ok())
+        .find_map(|payload| match payload {
+            Payload::CustomSection { name, data, .. }
+                if name == HOST_INTERFACE_VERSION_SECTION =>
+            {
+                Some(data)
+            }
+            _ => None,
+        })
+        .ok_or(WasmValidationError::MissingHostInterfaceVersion)?;
+    let bytes: [u8; 4] = declared.try_into().map_err(|_| {
+        WasmValidationError::InvalidHostInterfaceVersion(declared.len())
+    })?;
+    let found = u32::from_le_bytes(bytes);
+    if !(MIN_HOST_INTERFACE_VERSION..=HOST_INTERFACE_VERSION).contains(&found) {
+        return Err(WasmValidationError::HostInterfaceVersionMismatch {
+            found,
+        });
+    }
+    Ok(())
 }
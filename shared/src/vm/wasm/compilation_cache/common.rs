@@ -18,7 +18,7 @@ use clru::{CLruCache, CLruCacheConfig, WeightScale};
 use wasmer::{Module, Store};
 use wasmer_cache::{FileSystemCache, Hash};
 
-use crate::vm::wasm::run::untrusted_wasm_store;
+use crate::vm::wasm::run::{untrusted_wasm_compiler, untrusted_wasm_store};
 use crate::vm::wasm::{self, memory};
 use crate::vm::{WasmCacheAccess, WasmCacheRoAccess};
 
@@ -517,7 +517,7 @@ mod dylib {
     /// Dylib WASM store
     #[allow(dead_code)]
     pub fn store() -> Store {
-        let compiler = wasmer_compiler_singlepass::Singlepass::default();
+        let compiler = untrusted_wasm_compiler();
         let engine = wasmer_engine_dylib::Dylib::new(compiler).engine();
         Store::new_with_tunables(&engine, memory::vp_limit())
     }
@@ -1,6 +1,7 @@
 //! Wasm memory is used for bi-directionally passing data between the host and a
 //! wasm instance.
 
+use std::cell::RefCell;
 use std::ptr::NonNull;
 use std::str::Utf8Error;
 use std::sync::Arc;
@@ -48,6 +49,12 @@ pub const VP_MEMORY_INIT_PAGES: u32 = 100; // 6.4 MiB
 /// Mamixmum pages in VP memory
 pub const VP_MEMORY_MAX_PAGES: u32 = 200; // 12.8 MiB
 
+thread_local! {
+    /// A scratch buffer for encoding the inputs of validity predicates,
+    /// reused across the validity predicates ran on the same thread
+    static VP_INPUTS_BUFFER: RefCell<Vec<u8>> = RefCell::new(Vec::new());
+}
+
 /// Prepare memory for instantiating a transaction module
 pub fn prepare_tx_memory(store: &wasmer::Store) -> Result<wasmer::Memory> {
     let mem_type = wasmer::MemoryType::new(
@@ -125,41 +132,47 @@ pub fn write_vp_inputs(
         verifiers,
     }: VpInput,
 ) -> Result<VpCallInput> {
-    let addr_ptr = 0;
-    let addr_bytes = addr.try_to_vec().map_err(Error::EncodingError)?;
-    let addr_len = addr_bytes.len() as _;
-
-    let data_ptr = addr_ptr + addr_len;
-    let data_len = data.len() as _;
-
-    let keys_changed_bytes =
-        keys_changed.try_to_vec().map_err(Error::EncodingError)?;
-    let keys_changed_ptr = data_ptr + data_len;
-    let keys_changed_len = keys_changed_bytes.len() as _;
-
-    let verifiers_bytes =
-        verifiers.try_to_vec().map_err(Error::EncodingError)?;
-    let verifiers_ptr = keys_changed_ptr + keys_changed_len;
-    let verifiers_len = verifiers_bytes.len() as _;
-
-    let bytes = [
-        &addr_bytes[..],
-        data,
-        &keys_changed_bytes[..],
-        &verifiers_bytes[..],
-    ]
-    .concat();
-    write_memory_bytes(memory, addr_ptr, bytes)?;
-
-    Ok(VpCallInput {
-        addr_ptr,
-        addr_len,
-        data_ptr,
-        data_len,
-        keys_changed_ptr,
-        keys_changed_len,
-        verifiers_ptr,
-        verifiers_len,
+    VP_INPUTS_BUFFER.with(|buffer| {
+        // The address, the changed keys and the verifiers are encoded into
+        // the buffer and written around the data, which is written directly
+        let mut buffer = buffer.borrow_mut();
+        buffer.clear();
+        addr.serialize(&mut *buffer).map_err(Error::EncodingError)?;
+        let addr_end = buffer.len();
+        keys_changed
+            .serialize(&mut *buffer)
+            .map_err(Error::EncodingError)?;
+        let keys_changed_end = buffer.len();
+        verifiers
+            .serialize(&mut *buffer)
+            .map_err(Error::EncodingError)?;
+
+        let addr_ptr = 0;
+        let addr_len = addr_end as u64;
+
+        let data_ptr = addr_ptr + addr_len;
+        let data_len = data.len() as _;
+
+        let keys_changed_ptr = data_ptr + data_len;
+        let keys_changed_len = (keys_changed_end - addr_end) as u64;
+
+        let verifiers_ptr = keys_changed_ptr + keys_changed_len;
+        let verifiers_len = (buffer.len() - keys_changed_end) as u64;
+
+        write_memory_bytes(memory, addr_ptr, &buffer[..addr_end])?;
+        write_memory_bytes(memory, data_ptr, data)?;
+        write_memory_bytes(memory, keys_changed_ptr, &buffer[addr_end..])?;
+
+        Ok(VpCallInput {
+            addr_ptr,
+            addr_len,
+            data_ptr,
+            data_len,
+            keys_changed_ptr,
+            keys_changed_len,
+            verifiers_ptr,
+            verifiers_len,
+        })
     })
 }
 
@@ -193,11 +206,10 @@ fn read_memory_bytes(
 ) -> Result<Vec<u8>> {
     check_bounds(memory, offset, len)?;
     let offset = offset as usize;
-    let vec: Vec<_> = memory.view()[offset..(offset + len)]
-        .iter()
-        .map(|cell| cell.get())
-        .collect();
-    Ok(vec)
+    // SAFETY: The memory is only accessed from the thread that's running the
+    // wasm instance and the slice doesn't outlive this call
+    let data = unsafe { memory.data_unchecked() };
+    Ok(data[offset..(offset + len)].to_vec())
 }
 
 /// Write bytes into memory at the given offset
@@ -210,10 +222,10 @@ fn write_memory_bytes(
     let len = slice.len();
     check_bounds(memory, offset, len as _)?;
     let offset = offset as usize;
-    memory.view()[offset..(offset + len)]
-        .iter()
-        .zip(slice.iter())
-        .for_each(|(cell, v)| cell.set(*v));
+    // SAFETY: The memory is only accessed from the thread that's running the
+    // wasm instance and the slice doesn't outlive this call
+    let data = unsafe { memory.data_unchecked_mut() };
+    data[offset..(offset + len)].copy_from_slice(slice);
     Ok(())
 }
 
@@ -370,14 +370,23 @@ where
 
 /// Prepare a wasm store for untrusted code.
 pub fn untrusted_wasm_store(limit: Limit<BaseTunables>) -> wasmer::Store {
-    // Use Singlepass compiler with the default settings
-    let compiler = wasmer_compiler_singlepass::Singlepass::default();
     wasmer::Store::new_with_tunables(
-        &wasmer_engine_universal::Universal::new(compiler).engine(),
+        &wasmer_engine_universal::Universal::new(untrusted_wasm_compiler())
+            .engine(),
         limit,
     )
 }
 
+/// Get a compiler for untrusted code. The untrusted code is validated with
+/// [`validate_untrusted_wasm`] to not contain any non-deterministic operations,
+/// but on top of that we also canonicalize NaNs as a defense in depth.
+pub fn untrusted_wasm_compiler() -> wasmer_compiler_singlepass::Singlepass {
+    // Use Singlepass compiler with the default settings
+    let mut compiler = wasmer_compiler_singlepass::Singlepass::default();
+    compiler.canonicalize_nans(true);
+    compiler
+}
+
 /// Inject gas counter and stack-height limiter into the given wasm code
 pub fn prepare_wasm_code<T: AsRef<[u8]>>(code: T) -> Result<Vec<u8>> {
     let module: elements::Module = elements::deserialize_buffer(code.as_ref())
@@ -883,6 +892,160 @@ mod tests {
         assert!(!passed);
     }
 
+    /// Test that WASM code using any non-deterministic features is rejected by
+    /// the validation.
+    #[test]
+    fn test_untrusted_wasm_determinism() {
+        let module = |body: &str| {
+            wasmer::wat2wasm(
+                format!(
+                    r#"
+                (module
+                    (memory (;0;) 16)
+                    {}
+                    (@custom "anoma_host_interface_version" "\01\00\00\00"))
+                "#,
+                    body
+                )
+                .as_bytes(),
+            )
+            .expect("unexpected error converting wat2wasm")
+            .into_owned()
+        };
+
+        // Integer operations are allowed
+        let deterministic = [
+            "(func (param i64 i64) (result i64)
+                (i64.add (local.get 0) (local.get 1)))",
+            "(func (param i32) (result i32) (i32.load (local.get 0)))",
+        ];
+        for body in deterministic {
+            let code = module(body);
+            let result = validate_untrusted_wasm(&code);
+            assert!(result.is_ok(), "Expected success, got {:?}", result);
+        }
+
+        let non_deterministic = [
+            // Floating point arithmetic may produce non-deterministic NaNs
+            "(func (param f32 f32) (result f32)
+                (f32.add (local.get 0) (local.get 1)))",
+            "(func (param f64) (result f64) (f64.sqrt (local.get 0)))",
+            "(func (param f32) (result f64) (f64.promote_f32 (local.get 0)))",
+            // SIMD
+            "(func (result v128) (v128.const i64x2 0 0))",
+            // Threads
+            "(func (param i32) (result i32) (i32.atomic.load (local.get 0)))",
+            // Bulk memory
+            "(func (param i32 i32 i32)
+                (memory.copy (local.get 0) (local.get 1) (local.get 2)))",
+        ];
+        for body in non_deterministic {
+            let code = module(body);
+            let result = validate_untrusted_wasm(&code);
+            assert!(
+                matches!(
+                    result,
+                    Err(WasmValidationError::ForbiddenWasmFeatures(_))
+                ),
+                "Expected {} to be rejected, got {:?}",
+                body,
+                result
+            );
+        }
+    }
+
+    /// Test that a transaction wasm that doesn't declare a host interface
+    /// version or that targets a version not provided by the host is rejected
+    /// before it's executed.
+    #[test]
+    fn test_tx_host_interface_version() {
+        let result = no_op_tx_wasm(Some(r#""\01\00\00\00""#));
+        assert!(result.is_ok(), "Expected success. Got {:?}", result);
+        let result = no_op_tx_wasm(Some(r#""\04\00\00\00""#));
+        assert!(result.is_ok(), "Expected success. Got {:?}", result);
+
+        let error = no_op_tx_wasm(None).expect_err("Expected to fail");
+        assert!(matches!(
+            error,
+            Error::ValidationError(
+                WasmValidationError::MissingHostInterfaceVersion
+            )
+        ));
+
+        let error = no_op_tx_wasm(Some(r#""\05\00\00\00""#))
+            .expect_err("Expected to fail");
+        assert!(matches!(
+            error,
+            Error::ValidationError(
+                WasmValidationError::HostInterfaceVersionMismatch { found: 5 }
+            )
+        ));
+
+        let error = no_op_tx_wasm(Some(r#""\00\00\00\00""#))
+            .expect_err("Expected to fail");
+        assert!(matches!(
+            error,
+            Error::ValidationError(
+                WasmValidationError::HostInterfaceVersionMismatch { found: 0 }
+            )
+        ));
+
+        let error =
+            no_op_tx_wasm(Some(r#""\01""#)).expect_err("Expected to fail");
+        assert!(matches!(
+            error,
+            Error::ValidationError(
+                WasmValidationError::InvalidHostInterfaceVersion(1)
+            )
+        ));
+    }
+
+    /// Run a no-op transaction wasm with the given custom section data, if
+    /// any, declaring the host interface version.
+    fn no_op_tx_wasm(
+        host_interface_version: Option<&str>,
+    ) -> Result<BTreeSet<Address>> {
+        let version_section = host_interface_version
+            .map(|data| {
+                format!("(@custom \"anoma_host_interface_version\" {})", data)
+            })
+            .unwrap_or_default();
+        let tx_code = wasmer::wat2wasm(
+            format!(
+                r#"
+            (module
+                (type (;0;) (func (param i64 i64)))
+                (func $_apply_tx (type 0) (param i64 i64))
+                (memory (;0;) 16)
+                (export "memory" (memory 0))
+                (export "_apply_tx" (func $_apply_tx))
+                {})
+            "#,
+                version_section
+            )
+            .as_bytes(),
+        )
+        .expect("unexpected error converting wat2wasm")
+        .into_owned();
+
+        let storage = TestStorage::default();
+        let mut write_log = WriteLog::default();
+        let mut gas_meter = BlockGasMeter::default();
+        let (mut vp_cache, _) =
+            wasm::compilation_cache::common::testing::cache();
+        let (mut tx_cache, _) =
+            wasm::compilation_cache::common::testing::cache();
+        tx(
+            &storage,
+            &mut write_log,
+            &mut gas_meter,
+            tx_code,
+            vec![],
+            &mut vp_cache,
+            &mut tx_cache,
+        )
+    }
+
     fn loop_in_tx_wasm(loops: u32) -> Result<BTreeSet<Address>> {
         // A transaction with a recursive loop.
         // The boilerplate code is generated from tx_template.wasm using
@@ -909,7 +1072,8 @@ mod tests {
                 (memory (;0;) 16)
                 (global (;0;) (mut i32) (i32.const 1048576))
                 (export "memory" (memory 0))
-                (export "_apply_tx" (func $_apply_tx)))
+                (export "_apply_tx" (func $_apply_tx))
+                (@custom "anoma_host_interface_version" "\01\00\00\00"))
             "#,
                 loops
             )
@@ -961,7 +1125,8 @@ mod tests {
                 (memory (;0;) 16)
                 (global (;0;) (mut i32) (i32.const 1048576))
                 (export "memory" (memory 0))
-                (export "_validate_tx" (func $_validate_tx)))
+                (export "_validate_tx" (func $_validate_tx))
+                (@custom "anoma_host_interface_version" "\01\00\00\00"))
             "#, loops).as_bytes(),
         )
         .expect("unexpected error converting wat2wasm").into_owned();
@@ -240,6 +240,7 @@ fn run_ledger_load_state_and_reset() -> Result<()> {
 /// 5. Submit a tx to initialize a new account
 /// 6. Query token balance
 /// 7. Query the raw bytes of a storage key
+/// 8. Query the voting power with JSON output
 #[test]
 fn ledger_txs_and_queries() -> Result<()> {
     let test = setup::network(|genesis| genesis, None)?;
@@ -411,6 +412,24 @@ fn ledger_txs_and_queries() -> Result<()> {
         client.assert_success();
     }
 
+    // 8. Query the voting power with JSON output
+    let query_args = vec![
+        "--output",
+        "json",
+        "voting-power",
+        "--ledger-address",
+        &validator_one_rpc,
+    ];
+    let mut client = run!(test, Bin::Client, query_args, Some(40))?;
+    let output = client.exp_eof()?;
+    // The progress messages printed to stderr are also captured before the
+    // JSON result
+    let json_start = output.find('{').expect("Expected a JSON output");
+    let result: serde_json::Value = serde_json::from_str(&output[json_start..])
+        .expect("Expected a valid JSON output");
+    assert!(result["total_voting_power"].is_u64());
+    assert!(!result["validators"].as_array().unwrap().is_empty());
+
     Ok(())
 }
 
@@ -818,8 +837,8 @@ fn pos_init_validator() -> Result<()> {
     client.exp_string("Transaction is valid.")?;
     client.assert_success();
 
-    // 3. Submit a delegation to the new validator
-    //    First, transfer some tokens to the validator's key for fees:
+    // 3. Submit a delegation to the new validator First, transfer some tokens
+    //    to the validator's key for fees:
     let tx_args = vec![
         "transfer",
         "--source",
@@ -1812,7 +1831,7 @@ fn test_genesis_validators() -> Result<()> {
             .tendermint
             .rpc_address
             .set_port(first_port + 1);
-        config.ledger.shell.ledger_address.set_port(first_port + 2);
+        config.ledger.shell.set_ledger_port(first_port + 2);
         config
     };
 
@@ -0,0 +1,49 @@
+//! Tests of the governance native VP's checks of the code of custom-action
+//! proposals.
+
+#[cfg(test)]
+mod tests {
+    use anoma::ledger::governance::parameters::GovParams;
+    use anoma::ledger::governance::storage as gov_storage;
+    use anoma::ledger::governance::GovernanceVp;
+
+    use crate::native_vp::TestNativeVpEnv;
+    use crate::tx::TestTxEnv;
+
+    const TX_NO_OP_WASM: &str = "../wasm_for_tests/tx_no_op.wasm";
+    const PROPOSAL_ID: u64 = 0;
+
+    /// Write the code of a proposal in the tx and validate it with the
+    /// governance VP
+    fn validate(params: GovParams, proposal_code: Vec<u8>) -> bool {
+        let mut env = TestTxEnv::default();
+        params.init_storage(&mut env.storage);
+        env.write_log
+            .write(
+                &gov_storage::get_proposal_code_key(PROPOSAL_ID),
+                proposal_code,
+            )
+            .unwrap();
+        let vp_env = TestNativeVpEnv::new(env);
+        vp_env
+            .validate_tx(|ctx| GovernanceVp { ctx }, |_tx_data| {})
+            .expect("The VP shouldn't fail")
+    }
+
+    /// Test that the proposal code must be a valid WASM tx that's no larger
+    /// than the maximum proposal code size
+    #[test]
+    fn test_proposal_code_validation() {
+        let code = std::fs::read(TX_NO_OP_WASM).expect("cannot load wasm");
+        assert!(validate(GovParams::default(), code.clone()));
+
+        // not a WASM module
+        assert!(!validate(GovParams::default(), b"proposal code".to_vec()));
+
+        let params = GovParams {
+            max_proposal_code_size: code.len() as u64 - 1,
+            ..GovParams::default()
+        };
+        assert!(!validate(params, code));
+    }
+}
@@ -1,4 +1,6 @@
+mod governance;
 mod pos;
+mod treasury;
 
 use anoma::ledger::native_vp::{Ctx, NativeVp};
 use anoma::ledger::storage::mockdb::MockDB;
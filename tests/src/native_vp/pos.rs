@@ -68,7 +68,7 @@
 //!
 //! - Init validator: No state requirements.
 //!     - `#{PoS}/address_raw_hash/{raw_hash}` (the raw_hash is the validator's
-//!       address in Tendermint)
+//!       address in Tendermint, derived from its consensus key)
 //!     - `#{PoS}/validator_set`
 //!     - `#{PoS}/validator/#{validator}/consensus_key`
 //!     - `#{PoS}/validator/#{validator}/staking_reward_address`
@@ -655,6 +655,8 @@ pub mod testing {
         },
         ValidatorAddressRawHash {
             address: Address,
+            #[derivative(Debug = "ignore")]
+            consensus_key: PublicKey,
         },
     }
 
@@ -822,6 +824,7 @@ pub mod testing {
                         },
                         PosStorageChange::ValidatorAddressRawHash {
                             address: addr.clone(),
+                            consensus_key: key::testing::keypair_1().ref_to(),
                         },
                         PosStorageChange::ValidatorSet {
                             validator: addr.clone(),
@@ -1285,8 +1288,11 @@ pub mod testing {
                 }
                 PoS.write_total_voting_power(total_voting_powers)
             }
-            PosStorageChange::ValidatorAddressRawHash { address } => {
-                PoS.write_validator_address_raw_hash(&address);
+            PosStorageChange::ValidatorAddressRawHash {
+                address,
+                consensus_key,
+            } => {
+                PoS.write_validator_address_raw_hash(&address, &consensus_key);
             }
             PosStorageChange::ValidatorSet {
                 validator,
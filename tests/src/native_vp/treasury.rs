@@ -0,0 +1,100 @@
+//! Tests of the treasury native VP, which must only let the treasury funds be
+//! spent by the accepted governance proposals.
+
+#[cfg(test)]
+mod tests {
+    use anoma::ledger::governance::storage as gov_storage;
+    use anoma::ledger::treasury::parameters::TreasuryParams;
+    use anoma::ledger::treasury::{TreasuryVp, ADDRESS};
+    use anoma::proto::Tx;
+    use anoma::types::address::{btc, xan, Address};
+    use anoma::types::token;
+    use anoma_vm_env::tx_prelude::BorshSerialize;
+
+    use crate::native_vp::TestNativeVpEnv;
+    use crate::tx::TestTxEnv;
+
+    const PROPOSAL_ID: u64 = 0;
+
+    /// Set up the treasury with some funds in the native token and in another
+    /// token and an accepted proposal
+    fn init_env() -> TestTxEnv {
+        let mut env = TestTxEnv::default();
+        TreasuryParams::default().init_storage(&mut env.storage);
+        env.credit_tokens(&ADDRESS, &xan(), token::Amount::whole(20_000));
+        env.credit_tokens(&ADDRESS, &btc(), token::Amount::whole(100));
+        env.storage
+            .write(&gov_storage::get_proposal_execution_key(PROPOSAL_ID), "")
+            .unwrap();
+        env
+    }
+
+    /// Change the treasury's balance of the token in the tx and validate it
+    /// with the treasury VP
+    fn validate(
+        mut env: TestTxEnv,
+        token_addr: &Address,
+        balance: token::Amount,
+    ) -> bool {
+        env.write_log
+            .write(
+                &token::balance_key(token_addr, &ADDRESS),
+                balance.try_to_vec().unwrap(),
+            )
+            .unwrap();
+        let vp_env = TestNativeVpEnv::new(env);
+        vp_env
+            .validate_tx(|ctx| TreasuryVp { ctx }, |_tx_data| {})
+            .expect("The VP shouldn't fail")
+    }
+
+    /// Test that anyone can fund the treasury
+    #[test]
+    fn test_funding_accepted() {
+        for (token_addr, balance) in [(xan(), 30_000), (btc(), 200)] {
+            let env = init_env();
+            assert!(validate(env, &token_addr, token::Amount::whole(balance)));
+        }
+    }
+
+    /// Test that the treasury funds of any token can't be spent without an
+    /// accepted proposal
+    #[test]
+    fn test_spending_without_proposal_rejected() {
+        for (token_addr, balance) in [(xan(), 19_000), (btc(), 50)] {
+            let balance = token::Amount::whole(balance);
+            let env = init_env();
+            assert!(!validate(env, &token_addr, balance));
+
+            // the proposal with this ID wasn't accepted
+            let mut env = init_env();
+            let tx_data = (PROPOSAL_ID + 1).try_to_vec().unwrap();
+            env.tx = Tx::new(vec![], Some(tx_data));
+            assert!(!validate(env, &token_addr, balance));
+        }
+    }
+
+    /// Test that an accepted proposal can spend the treasury funds, up to the
+    /// maximum transfer of the native token
+    #[test]
+    fn test_spending_with_proposal() {
+        let with_proposal = || {
+            let mut env = init_env();
+            env.tx = Tx::new(vec![], Some(PROPOSAL_ID.try_to_vec().unwrap()));
+            env
+        };
+        let max_transfer =
+            TreasuryParams::default().max_proposal_fund_transfer;
+        assert!(validate(
+            with_proposal(),
+            &xan(),
+            token::Amount::whole(20_000 - max_transfer)
+        ));
+        assert!(!validate(
+            with_proposal(),
+            &xan(),
+            token::Amount::whole(20_000 - max_transfer - 1)
+        ));
+        assert!(validate(with_proposal(), &btc(), token::Amount::default()));
+    }
+}
@@ -18,11 +18,15 @@ pub mod vp;
 #[cfg(test)]
 mod tests {
 
-    use std::panic;
+    use std::sync::{Arc, Mutex};
+    use std::{io, panic};
 
     use anoma::ibc::tx_msg::Msg;
     use anoma::ledger::ibc::handler::IbcActions;
     use anoma::ledger::ibc::vp::Error as IbcError;
+    use anoma::ledger::parameters::storage as parameters_storage;
+    use anoma::ledger::pos;
+    use anoma::ledger::storage::write_log::SizeLimits;
     use anoma::proto::{SignedTxData, Tx};
     use anoma::tendermint_proto::Protobuf;
     use anoma::types::key::*;
@@ -30,6 +34,7 @@ mod tests {
     use anoma::types::time::DateTimeUtc;
     use anoma::types::token::{self, Amount};
     use anoma::types::{address, key};
+    use anoma::vm::host_env;
     use anoma_vm_env::tx_prelude::{
         BorshDeserialize, BorshSerialize, KeyValIterator,
     };
@@ -80,6 +85,17 @@ mod tests {
         );
     }
 
+    #[test]
+    #[should_panic]
+    fn test_tx_write_value_too_large() {
+        // The environment must be initialized first
+        tx_host_env::init();
+
+        let max_value_bytes = SizeLimits::default().max_value_bytes;
+        let value = vec![0_u8; max_value_bytes as usize + 1];
+        tx_host_env::write_bytes("key", value);
+    }
+
     #[test]
     fn test_tx_has_key() {
         // The environment must be initialized first
@@ -139,6 +155,32 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_tx_write_reserved_key() {
+        // The environment must be initialized first
+        let mut env = TestTxEnv::default();
+        let test_account = address::testing::established_address_1();
+        env.spawn_accounts([&test_account]);
+        tx_host_env::set(env);
+
+        // Trying to write a validity predicate, a protocol parameter outside
+        // of a governance proposal or the PoS parameters should fail
+        for key in [
+            storage::Key::validity_predicate(&test_account),
+            parameters_storage::get_epoch_storage_key(),
+            pos::params_key(),
+        ] {
+            let key = key.to_string();
+            assert!(
+                panic::catch_unwind(|| { tx_host_env::write(key, 1_u8) })
+                    .err()
+                    .map(|a| a.downcast_ref::<String>().cloned().unwrap())
+                    .unwrap()
+                    .contains("ReservedKeyWrite")
+            );
+        }
+    }
+
     #[test]
     fn test_tx_iter_prefix() {
         // The environment must be initialized first
@@ -341,6 +383,38 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_vp_read_temp() {
+        let tx_env = TestTxEnv::default();
+
+        let addr = address::testing::established_address_1();
+        let addr_key = Key::from(addr.to_db_key());
+        let temp_key = addr_key.join(&Key::parse("temp").unwrap());
+        let temp_key_raw = temp_key.to_string();
+        let temp_value = "temp".to_string();
+
+        // Initialize the VP environment via a transaction
+        vp_host_env::init_from_tx(addr, tx_env, |_addr| {
+            tx_host_env::write_temp(&temp_key_raw, &temp_value);
+        });
+
+        // The temporary value is visible in the posterior state only
+        let pre: Option<String> = vp_host_env::read_pre(&temp_key_raw);
+        assert_eq!(pre, None);
+        let post: Option<String> = vp_host_env::read_post(&temp_key_raw);
+        assert_eq!(post, Some(temp_value.clone()));
+        let temp: Option<String> = vp_host_env::read_temp(&temp_key_raw);
+        assert_eq!(temp, Some(temp_value));
+
+        // The temporary value is never committed
+        vp_host_env::with(|env| {
+            env.write_log.commit_tx();
+            env.write_log.commit_block(&mut env.storage).unwrap();
+            let (value, _gas) = env.storage.read(&temp_key).unwrap();
+            assert_eq!(value, None);
+        });
+    }
+
     #[test]
     fn test_vp_iter_prefix() {
         let mut tx_env = TestTxEnv::default();
@@ -387,6 +461,43 @@ mod tests {
         itertools::assert_equal(iter_post.sorted(), expected_post.sorted());
     }
 
+    #[test]
+    fn test_vp_get_changed_keys() {
+        let mut tx_env = TestTxEnv::default();
+
+        let addr = address::testing::established_address_1();
+        let addr_key = Key::from(addr.to_db_key());
+        let existing_key = addr_key.join(&Key::parse("existing").unwrap());
+        tx_env.storage.write(&existing_key, vec![1]).unwrap();
+        tx_env.storage.commit().unwrap();
+        let written_key = addr_key.join(&Key::parse("written").unwrap());
+        let existing_key_raw = existing_key.to_string();
+        let written_key_raw = written_key.to_string();
+
+        // Initialize the VP environment via a transaction
+        vp_host_env::init_from_tx(addr.clone(), tx_env, |_addr| {
+            tx_host_env::delete(&existing_key_raw);
+            tx_host_env::write(&written_key_raw, 1_u8);
+        });
+
+        let changed_keys = vp_host_env::get_changed_keys();
+        assert_eq!(
+            changed_keys,
+            vec![
+                storage::ChangedKey {
+                    owner: Some(addr.clone()),
+                    key: existing_key,
+                    change: storage::ChangeType::Delete,
+                },
+                storage::ChangedKey {
+                    owner: Some(addr),
+                    key: written_key,
+                    change: storage::ChangeType::Write,
+                },
+            ]
+        );
+    }
+
     #[test]
     fn test_vp_verify_tx_signature() {
         let mut env = TestVpEnv::default();
@@ -429,6 +540,21 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_vp_get_tx_hash() {
+        // The environment must be initialized first
+        vp_host_env::init();
+
+        let keypair = key::testing::keypair_1();
+        let tx = vp_host_env::with(|env| {
+            env.tx = Tx::new(vec![4, 3, 2, 1, 0], Some(vec![1, 2, 3]))
+                .sign(&keypair);
+            env.tx.clone()
+        });
+        assert_eq!(vp_host_env::get_tx_hash().0, tx.hash());
+        assert_ne!(vp_host_env::get_tx_hash().0, tx.code_hash());
+    }
+
     #[test]
     fn test_vp_get_metadata() {
         // The environment must be initialized first
@@ -479,6 +605,74 @@ mod tests {
         assert!(!result);
     }
 
+    /// Test that the messages logged by txs and VPs are forwarded to the
+    /// tracing output only when the WASM log is enabled, but that the same
+    /// gas is charged for them either way. The tx and VP logs are tested
+    /// together, because the flag is global.
+    #[test]
+    fn test_log_string() {
+        tx_host_env::init();
+        vp_host_env::init();
+
+        let msg = "a message from the WASM";
+        let mut tx_gas = vec![];
+        let mut vp_gas = vec![];
+        for enabled in [false, true] {
+            host_env::set_wasm_log_enabled(enabled);
+
+            let gas_before = tx_host_env::with(|env| {
+                env.gas_meter.get_current_transaction_gas()
+            });
+            let output = capture_log(|| tx_host_env::log_string(msg));
+            assert_eq!(output.contains(msg), enabled);
+            tx_gas.push(
+                tx_host_env::with(|env| {
+                    env.gas_meter.get_current_transaction_gas()
+                }) - gas_before,
+            );
+
+            let gas_before = vp_host_env::with(|env| env.gas_meter.current_gas);
+            let output = capture_log(|| vp_host_env::log_string(msg));
+            assert_eq!(output.contains(msg), enabled);
+            vp_gas.push(
+                vp_host_env::with(|env| env.gas_meter.current_gas) - gas_before,
+            );
+        }
+        host_env::set_wasm_log_enabled(false);
+
+        assert!(tx_gas[0] > 0);
+        assert_eq!(tx_gas[0], tx_gas[1]);
+        assert!(vp_gas[0] > 0);
+        assert_eq!(vp_gas[0], vp_gas[1]);
+    }
+
+    /// A writer of the tracing output into a shared buffer
+    #[derive(Clone, Default)]
+    struct LogBuffer(Arc<Mutex<Vec<u8>>>);
+
+    impl io::Write for LogBuffer {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    /// Run the given function and return its tracing output
+    fn capture_log(f: impl FnOnce()) -> String {
+        let buffer = LogBuffer::default();
+        let writer = buffer.clone();
+        let subscriber = tracing_subscriber::fmt()
+            .with_writer(move || writer.clone())
+            .finish();
+        tracing::subscriber::with_default(subscriber, f);
+        let output = buffer.0.lock().unwrap().clone();
+        String::from_utf8(output).unwrap()
+    }
+
     #[test]
     fn test_ibc_client() {
         // The environment must be initialized first
@@ -497,6 +691,8 @@ mod tests {
             code: vec![],
             data: Some(tx_data.clone()),
             timestamp: DateTimeUtc::now(),
+            chain_id: None,
+            expiration: None,
         }
         .sign(&key::testing::keypair_1());
         // get and increment the connection counter
@@ -534,6 +730,8 @@ mod tests {
             code: vec![],
             data: Some(tx_data.clone()),
             timestamp: DateTimeUtc::now(),
+            chain_id: None,
+            expiration: None,
         }
         .sign(&key::testing::keypair_1());
 
@@ -571,6 +769,8 @@ mod tests {
             code: vec![],
             data: Some(tx_data.clone()),
             timestamp: DateTimeUtc::now(),
+            chain_id: None,
+            expiration: None,
         }
         .sign(&key::testing::keypair_1());
         // get and update the client without a header
@@ -615,6 +815,8 @@ mod tests {
             code: vec![],
             data: Some(tx_data.clone()),
             timestamp: DateTimeUtc::now(),
+            chain_id: None,
+            expiration: None,
         }
         .sign(&key::testing::keypair_1());
         // update the client with the message
@@ -648,6 +850,8 @@ mod tests {
             code: vec![],
             data: Some(tx_data.clone()),
             timestamp: DateTimeUtc::now(),
+            chain_id: None,
+            expiration: None,
         }
         .sign(&key::testing::keypair_1());
         // upgrade the client with the message
@@ -690,6 +894,8 @@ mod tests {
             code: vec![],
             data: Some(tx_data.clone()),
             timestamp: DateTimeUtc::now(),
+            chain_id: None,
+            expiration: None,
         }
         .sign(&key::testing::keypair_1());
         // get and increment the connection counter
@@ -727,6 +933,8 @@ mod tests {
             code: vec![],
             data: Some(tx_data.clone()),
             timestamp: DateTimeUtc::now(),
+            chain_id: None,
+            expiration: None,
         }
         .sign(&key::testing::keypair_1());
         // init a connection with the message
@@ -757,6 +965,8 @@ mod tests {
             code: vec![],
             data: Some(tx_data.clone()),
             timestamp: DateTimeUtc::now(),
+            chain_id: None,
+            expiration: None,
         }
         .sign(&key::testing::keypair_1());
         // open the connection with the message
@@ -797,6 +1007,8 @@ mod tests {
             code: vec![],
             data: Some(tx_data.clone()),
             timestamp: DateTimeUtc::now(),
+            chain_id: None,
+            expiration: None,
         }
         .sign(&key::testing::keypair_1());
         // open try a connection with the message
@@ -828,6 +1040,8 @@ mod tests {
             code: vec![],
             data: Some(tx_data.clone()),
             timestamp: DateTimeUtc::now(),
+            chain_id: None,
+            expiration: None,
         }
         .sign(&key::testing::keypair_1());
         // open the connection with the mssage
@@ -873,6 +1087,8 @@ mod tests {
             code: vec![],
             data: Some(tx_data.clone()),
             timestamp: DateTimeUtc::now(),
+            chain_id: None,
+            expiration: None,
         }
         .sign(&key::testing::keypair_1());
         // not bind a port
@@ -917,6 +1133,8 @@ mod tests {
             code: vec![],
             data: Some(tx_data.clone()),
             timestamp: DateTimeUtc::now(),
+            chain_id: None,
+            expiration: None,
         }
         .sign(&key::testing::keypair_1());
         // bind a port
@@ -961,6 +1179,8 @@ mod tests {
             code: vec![],
             data: Some(tx_data.clone()),
             timestamp: DateTimeUtc::now(),
+            chain_id: None,
+            expiration: None,
         }
         .sign(&key::testing::keypair_1());
         // init a channel with the message
@@ -989,6 +1209,8 @@ mod tests {
             code: vec![],
             data: Some(tx_data.clone()),
             timestamp: DateTimeUtc::now(),
+            chain_id: None,
+            expiration: None,
         }
         .sign(&key::testing::keypair_1());
         // open the channle with the message
@@ -1031,6 +1253,8 @@ mod tests {
             code: vec![],
             data: Some(tx_data.clone()),
             timestamp: DateTimeUtc::now(),
+            chain_id: None,
+            expiration: None,
         }
         .sign(&key::testing::keypair_1());
         // try open a channel with the message
@@ -1060,6 +1284,8 @@ mod tests {
             code: vec![],
             data: Some(tx_data.clone()),
             timestamp: DateTimeUtc::now(),
+            chain_id: None,
+            expiration: None,
         }
         .sign(&key::testing::keypair_1());
         // open a channel with the message
@@ -1104,6 +1330,8 @@ mod tests {
             code: vec![],
             data: Some(tx_data.clone()),
             timestamp: DateTimeUtc::now(),
+            chain_id: None,
+            expiration: None,
         }
         .sign(&key::testing::keypair_1());
         // close the channel with the message
@@ -1148,6 +1376,8 @@ mod tests {
             code: vec![],
             data: Some(tx_data.clone()),
             timestamp: DateTimeUtc::now(),
+            chain_id: None,
+            expiration: None,
         }
         .sign(&key::testing::keypair_1());
 
@@ -1197,6 +1427,8 @@ mod tests {
             code: vec![],
             data: Some(tx_data.clone()),
             timestamp: DateTimeUtc::now(),
+            chain_id: None,
+            expiration: None,
         }
         .sign(&key::testing::keypair_1());
         // send the token and a packet with the data
@@ -1241,6 +1473,8 @@ mod tests {
             code: vec![],
             data: Some(tx_data.clone()),
             timestamp: DateTimeUtc::now(),
+            chain_id: None,
+            expiration: None,
         }
         .sign(&key::testing::keypair_1());
         // ack the packet with the message
@@ -1287,6 +1521,8 @@ mod tests {
             code: vec![],
             data: Some(tx_data.clone()),
             timestamp: DateTimeUtc::now(),
+            chain_id: None,
+            expiration: None,
         }
         .sign(&key::testing::keypair_1());
         // send the token and a packet with the data
@@ -1349,6 +1585,8 @@ mod tests {
             code: vec![],
             data: Some(tx_data.clone()),
             timestamp: DateTimeUtc::now(),
+            chain_id: None,
+            expiration: None,
         }
         .sign(&key::testing::keypair_1());
         // receive a packet with the message
@@ -1431,6 +1669,8 @@ mod tests {
             code: vec![],
             data: Some(tx_data.clone()),
             timestamp: DateTimeUtc::now(),
+            chain_id: None,
+            expiration: None,
         }
         .sign(&key::testing::keypair_1());
         // receive a packet with the message
@@ -1486,6 +1726,8 @@ mod tests {
             code: vec![],
             data: Some(tx_data.clone()),
             timestamp: DateTimeUtc::now(),
+            chain_id: None,
+            expiration: None,
         }
         .sign(&key::testing::keypair_1());
         // send a packet with the message
@@ -1519,6 +1761,8 @@ mod tests {
             code: vec![],
             data: Some(tx_data.clone()),
             timestamp: DateTimeUtc::now(),
+            chain_id: None,
+            expiration: None,
         }
         .sign(&key::testing::keypair_1());
         // ack the packet with the message
@@ -1574,6 +1818,8 @@ mod tests {
             code: vec![],
             data: Some(tx_data.clone()),
             timestamp: DateTimeUtc::now(),
+            chain_id: None,
+            expiration: None,
         }
         .sign(&key::testing::keypair_1());
         // receive a packet with the message
@@ -1640,6 +1886,8 @@ mod tests {
             code: vec![],
             data: Some(tx_data.clone()),
             timestamp: DateTimeUtc::now(),
+            chain_id: None,
+            expiration: None,
         }
         .sign(&key::testing::keypair_1());
 
@@ -1717,6 +1965,8 @@ mod tests {
             code: vec![],
             data: Some(tx_data.clone()),
             timestamp: DateTimeUtc::now(),
+            chain_id: None,
+            expiration: None,
         }
         .sign(&key::testing::keypair_1());
 
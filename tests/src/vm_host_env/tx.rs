@@ -10,7 +10,7 @@ use anoma::proto::Tx;
 use anoma::types::address::Address;
 use anoma::types::storage::Key;
 use anoma::types::time::DurationSecs;
-use anoma::types::{key, token};
+use anoma::types::{key, multisig, token};
 use anoma::vm::prefix_iter::PrefixIterators;
 use anoma::vm::wasm::{self, TxCache, VpCache};
 use anoma::vm::{self, WasmCacheRwAccess};
@@ -151,6 +151,35 @@ impl TestTxEnv {
             .write(&storage_key, public_key.try_to_vec().unwrap())
             .unwrap();
     }
+
+    /// Set the signer set and threshold of a multisignature account.
+    pub fn write_multisig_params(
+        &mut self,
+        address: &Address,
+        public_keys: &[key::common::PublicKey],
+        threshold: u8,
+    ) {
+        let storage_key = multisig::public_keys_key(address);
+        self.storage
+            .write(&storage_key, public_keys.to_vec().try_to_vec().unwrap())
+            .unwrap();
+        let storage_key = multisig::threshold_key(address);
+        self.storage
+            .write(&storage_key, threshold.try_to_vec().unwrap())
+            .unwrap();
+    }
+
+    /// Set the metadata of a token.
+    pub fn write_token_metadata(
+        &mut self,
+        token: &Address,
+        metadata: &token::TokenMetadata,
+    ) {
+        let storage_key = token::metadata_key(token);
+        self.storage
+            .write(&storage_key, metadata.try_to_vec().unwrap())
+            .unwrap();
+    }
 }
 
 /// This module allows to test code with tx host environment functions.
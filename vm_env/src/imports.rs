@@ -366,8 +366,9 @@ pub mod vp {
     use anoma::types::internal::HostEnvResult;
     use anoma::types::key::*;
     use anoma::types::storage::{
-        BlockHash, BlockHeight, Epoch, BLOCK_HASH_LENGTH,
+        BlockHash, BlockHeight, ChangedKey, Epoch, BLOCK_HASH_LENGTH,
     };
+    use anoma::types::time::Rfc3339String;
     pub use borsh::{BorshDeserialize, BorshSerialize};
 
     pub struct PreKeyValIterator<T>(pub u64, pub PhantomData<T>);
@@ -517,6 +518,18 @@ pub mod vp {
         BlockHash::try_from(slice).expect("Cannot convert the hash")
     }
 
+    /// Get time of the current block header as rfc 3339 string
+    pub fn get_block_time() -> Rfc3339String {
+        let read_result = unsafe { anoma_vp_get_block_time() };
+        let time_value =
+            super::read_from_buffer(read_result, anoma_vp_result_buffer)
+                .expect("The block time should exist");
+        Rfc3339String(
+            String::try_from_slice(&time_value[..])
+                .expect("The conversion shouldn't fail"),
+        )
+    }
+
     /// Get a tx hash
     pub fn get_tx_code_hash() -> Hash {
         let result = Vec::with_capacity(HASH_LENGTH);
@@ -528,6 +541,30 @@ pub mod vp {
         Hash::try_from(slice).expect("Cannot convert the hash")
     }
 
+    /// Get the canonical hash of the tx, which is the hash of the signed tx
+    /// bytes. It's unique to every tx, so it can be used for replay
+    /// protection.
+    pub fn get_tx_hash() -> Hash {
+        let result = Vec::with_capacity(HASH_LENGTH);
+        unsafe {
+            anoma_vp_get_tx_hash(result.as_ptr() as _);
+        }
+        let slice =
+            unsafe { slice::from_raw_parts(result.as_ptr(), HASH_LENGTH) };
+        Hash::try_from(slice).expect("Cannot convert the hash")
+    }
+
+    /// Get the storage keys changed by the transaction, with their owners and
+    /// the types of the changes
+    pub fn get_changed_keys() -> Vec<ChangedKey> {
+        let read_result = unsafe { anoma_vp_get_changed_keys() };
+        let changed_keys =
+            super::read_from_buffer(read_result, anoma_vp_result_buffer)
+                .expect("The changed keys should exist");
+        Vec::try_from_slice(&changed_keys[..])
+            .expect("The conversion shouldn't fail")
+    }
+
     /// Get epoch of the current block
     pub fn get_block_epoch() -> Epoch {
         Epoch(unsafe { anoma_vp_get_block_epoch() })
@@ -638,9 +675,18 @@ pub mod vp {
         // Get the current block hash
         fn anoma_vp_get_block_hash(result_ptr: u64);
 
+        // Get the time of the current block header
+        fn anoma_vp_get_block_time() -> i64;
+
         // Get the current tx hash
         fn anoma_vp_get_tx_code_hash(result_ptr: u64);
 
+        // Get the canonical hash of the current tx
+        fn anoma_vp_get_tx_hash(result_ptr: u64);
+
+        // Get the storage keys changed by the transaction
+        fn anoma_vp_get_changed_keys() -> i64;
+
         // Get the current block epoch
         fn anoma_vp_get_block_epoch() -> u64;
 
@@ -11,6 +11,7 @@ pub mod ibc;
 pub mod imports;
 pub mod intent;
 pub mod key;
+pub mod multisig;
 pub mod nft;
 pub mod proof_of_stake;
 pub mod token;
@@ -24,6 +25,7 @@ pub mod tx_prelude {
     pub use anoma::types::address::Address;
     pub use anoma::types::storage::Key;
     pub use anoma::types::*;
+    pub use anoma::vm::HOST_INTERFACE_VERSION;
     pub use anoma_macros::transaction;
 
     pub use crate::governance::tx as governance;
@@ -45,11 +47,13 @@ pub mod vp_prelude {
     pub use anoma::types::address::Address;
     pub use anoma::types::storage::Key;
     pub use anoma::types::*;
+    pub use anoma::vm::HOST_INTERFACE_VERSION;
     pub use anoma_macros::validity_predicate;
 
     pub use crate::imports::vp::*;
     pub use crate::intent::vp as intent;
     pub use crate::key::vp as key;
+    pub use crate::multisig::vp as multisig;
     pub use crate::nft::vp as nft;
     pub use crate::token::vp as token;
 }
@@ -0,0 +1,45 @@
+use std::collections::BTreeMap;
+
+use anoma::types::address::Address;
+use anoma::types::key::common;
+
+/// Vp imports and functions.
+pub mod vp {
+    pub use anoma::types::multisig::*;
+
+    use super::*;
+    use crate::imports::vp;
+
+    /// Get the public keys of the signers of the given multisignature
+    /// account. Returns an empty list if the account is not a multisignature
+    /// account.
+    pub fn get_public_keys(owner: &Address) -> Vec<common::PublicKey> {
+        let key = public_keys_key(owner).to_string();
+        vp::read_pre(&key).unwrap_or_default()
+    }
+
+    /// Get the signature threshold of the given multisignature account.
+    pub fn get_threshold(owner: &Address) -> Option<u8> {
+        let key = threshold_key(owner).to_string();
+        vp::read_pre(&key)
+    }
+
+    /// Check that the current transaction has been signed by at least the
+    /// threshold number of signers of the given multisignature account.
+    pub fn verify_tx_signatures(
+        owner: &Address,
+        signatures: &BTreeMap<u8, common::Signature>,
+    ) -> bool {
+        let threshold = match get_threshold(owner) {
+            Some(threshold) => threshold,
+            None => return false,
+        };
+        let public_keys = get_public_keys(owner);
+        let valid = count_valid_signatures(
+            &public_keys,
+            signatures,
+            vp::verify_tx_signature,
+        );
+        valid >= threshold as usize
+    }
+}
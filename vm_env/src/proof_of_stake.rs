@@ -1,18 +1,20 @@
 //! Proof of Stake system integration with functions for transactions
 
 use anoma::ledger::pos::anoma_proof_of_stake::{
-    BecomeValidatorError, BondError, UnbondError, WithdrawError,
+    BecomeValidatorError, BondError, ChangeConsensusKeyError, UnbondError,
+    WithdrawError,
 };
-use anoma::ledger::pos::types::Slash;
+use anoma::ledger::pos::types::{Slash, ValidatorMetadata};
 pub use anoma::ledger::pos::*;
 use anoma::ledger::pos::{
     anoma_proof_of_stake, bond_key, params_key, total_voting_power_key,
     unbond_key, validator_address_raw_hash_key, validator_consensus_key_key,
-    validator_set_key, validator_slashes_key,
+    validator_metadata_key, validator_set_key, validator_slashes_key,
     validator_staking_reward_address_key, validator_state_key,
     validator_total_deltas_key, validator_voting_power_key,
 };
 use anoma::types::address::{self, Address, InternalAddress};
+use anoma::types::transaction::pos::UpdateValidatorMetadata;
 use anoma::types::transaction::InitValidator;
 use anoma::types::{key, token};
 pub use anoma_proof_of_stake::{
@@ -56,6 +58,58 @@ pub fn withdraw_tokens(
     PoS.withdraw_tokens(source, validator, current_epoch)
 }
 
+/// Change the consensus key of a validator. The new key takes effect from the
+/// pipeline epoch.
+pub fn change_consensus_key(
+    validator: &Address,
+    consensus_key: &key::common::PublicKey,
+) -> Result<(), ChangeConsensusKeyError<Address>> {
+    let current_epoch = tx::get_block_epoch();
+    PoS.change_consensus_key(validator, consensus_key, current_epoch)
+}
+
+/// Register or update a validator's metadata. The commission rate's last
+/// update epoch is set to the current epoch when the commission rate changes.
+/// The maximum commission rate change is only set on registration.
+pub fn update_validator_metadata(
+    UpdateValidatorMetadata {
+        validator,
+        moniker,
+        website,
+        contact,
+        commission_rate,
+        max_commission_rate_change,
+    }: UpdateValidatorMetadata,
+) {
+    let current_epoch = tx::get_block_epoch();
+    let key = validator_metadata_key(&validator).to_string();
+    let metadata = match tx::read::<ValidatorMetadata>(&key) {
+        Some(pre) => ValidatorMetadata {
+            moniker,
+            website,
+            contact,
+            commission_rate,
+            max_commission_rate_change: pre.max_commission_rate_change,
+            commission_rate_last_update: if commission_rate
+                == pre.commission_rate
+            {
+                pre.commission_rate_last_update
+            } else {
+                current_epoch.into()
+            },
+        },
+        None => ValidatorMetadata {
+            moniker,
+            website,
+            contact,
+            commission_rate,
+            max_commission_rate_change,
+            commission_rate_last_update: current_epoch.into(),
+        },
+    };
+    tx::write(&key, &metadata);
+}
+
 /// Attempt to initialize a validator account. On success, returns the
 /// initialized validator account's address and its staking reward address.
 pub fn init_validator(
@@ -174,8 +228,12 @@ impl anoma_proof_of_stake::PosActions for PoS {
         tx::write(params_key().to_string(), params)
     }
 
-    fn write_validator_address_raw_hash(&mut self, address: &Self::Address) {
-        let raw_hash = address.raw_hash().unwrap().to_owned();
+    fn write_validator_address_raw_hash(
+        &mut self,
+        address: &Self::Address,
+        consensus_key: &Self::PublicKey,
+    ) {
+        let raw_hash = key::tm_consensus_key_raw_hash(consensus_key);
         tx::write(
             validator_address_raw_hash_key(raw_hash).to_string(),
             address,
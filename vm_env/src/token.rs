@@ -6,64 +6,77 @@ use anoma::types::token;
 
 /// Vp imports and functions.
 pub mod vp {
-    use anoma::types::storage::KeySeg;
     pub use anoma::types::token::*;
 
     use super::*;
     use crate::imports::vp;
 
-    /// A token validity predicate.
+    /// A token validity predicate, see [`token::validate_token_changes`]
     pub fn vp(
         token: &Address,
         keys_changed: &BTreeSet<Key>,
         verifiers: &BTreeSet<Address>,
     ) -> bool {
-        let mut change: Change = 0;
-        let all_checked = keys_changed.iter().all(|key| {
-            match token::is_balance_key(token, key) {
-                None => {
-                    // Unknown changes to this address space are disallowed, but
-                    // unknown changes anywhere else are permitted
-                    key.segments.get(0) != Some(&token.to_db_key())
-                }
-                Some(owner) => {
-                    // accumulate the change
-                    let key = key.to_string();
-                    let pre: Amount = match owner {
-                        Address::Internal(InternalAddress::IbcMint) => {
-                            Amount::max()
-                        }
-                        Address::Internal(InternalAddress::IbcBurn) => {
-                            Amount::default()
-                        }
-                        _ => vp::read_pre(&key).unwrap_or_default(),
-                    };
-                    let post: Amount = match owner {
-                        Address::Internal(InternalAddress::IbcMint) => {
-                            vp::read_temp(&key).unwrap_or_else(Amount::max)
-                        }
-                        Address::Internal(InternalAddress::IbcBurn) => {
-                            vp::read_temp(&key).unwrap_or_default()
-                        }
-                        _ => vp::read_post(&key).unwrap_or_default(),
-                    };
-                    let this_change = post.change() - pre.change();
-                    change += this_change;
-                    // make sure that the spender approved the transaction
-                    if this_change < 0 {
-                        return verifiers.contains(owner);
-                    }
-                    true
-                }
-            }
-        });
-        all_checked && change == 0
+        match token::validate_token_changes(
+            &VpStorage,
+            token,
+            keys_changed,
+            verifiers,
+        ) {
+            Ok(accepted) => accepted,
+            Err(never) => match never {},
+        }
+    }
+
+    /// The storage reads of the token validity predicate from the host
+    struct VpStorage;
+
+    impl TokenVpStorage for VpStorage {
+        type Error = std::convert::Infallible;
+
+        fn read_amount_pre(
+            &self,
+            key: &Key,
+        ) -> Result<Option<Amount>, Self::Error> {
+            Ok(vp::read_pre(key.to_string()))
+        }
+
+        fn read_amount_post(
+            &self,
+            key: &Key,
+        ) -> Result<Option<Amount>, Self::Error> {
+            Ok(vp::read_post(key.to_string()))
+        }
+
+        fn read_amount_temp(
+            &self,
+            key: &Key,
+        ) -> Result<Option<Amount>, Self::Error> {
+            Ok(vp::read_temp(key.to_string()))
+        }
+
+        fn read_metadata_pre(
+            &self,
+            token: &Address,
+        ) -> Result<Option<TokenMetadata>, Self::Error> {
+            Ok(vp::read_pre(token::metadata_key(token).to_string()))
+        }
+    }
+
+    /// Read the minter of a token from its metadata before the transaction.
+    pub fn read_minter_pre(token: &Address) -> Option<Address> {
+        let key = token::metadata_key(token).to_string();
+        let metadata: Option<TokenMetadata> = vp::read_pre(&key);
+        metadata.and_then(|metadata| metadata.minter)
     }
 }
 
 /// Tx imports and functions.
 pub mod tx {
     pub use anoma::types::token::*;
+    use anoma::types::transaction::token::{
+        ChangeSupply, InitToken, SupplyChange,
+    };
 
     use super::*;
     use crate::imports::tx;
@@ -110,4 +123,51 @@ pub mod tx {
             _ => tx::write(&dest_key.to_string(), dest_bal),
         }
     }
+
+    /// Initialize a new token account with the given metadata and VP.
+    pub fn init_token(init_token: InitToken) -> Address {
+        let address = tx::init_account(&init_token.vp_code);
+        let metadata_key = token::metadata_key(&address);
+        tx::write(&metadata_key.to_string(), &init_token.metadata);
+        address
+    }
+
+    /// Mint or burn tokens of the given owner and update the token's total
+    /// supply. The token's minter is added to the verifiers to authorize the
+    /// change.
+    pub fn change_supply(change_supply: ChangeSupply) {
+        let ChangeSupply {
+            token,
+            owner,
+            amount,
+            change,
+        } = change_supply;
+        let metadata_key = token::metadata_key(&token);
+        let metadata: Option<TokenMetadata> =
+            tx::read(&metadata_key.to_string());
+        let minter = match metadata.and_then(|metadata| metadata.minter) {
+            Some(minter) => minter,
+            None => {
+                tx::log_string(format!("token {} has no minter", token));
+                unreachable!()
+            }
+        };
+        let balance_key = token::balance_key(&token, &owner).to_string();
+        let supply_key = token::supply_key(&token).to_string();
+        let mut balance: Amount = tx::read(&balance_key).unwrap_or_default();
+        let mut supply: Amount = tx::read(&supply_key).unwrap_or_default();
+        match change {
+            SupplyChange::Mint => {
+                balance.receive(&amount);
+                supply.receive(&amount);
+            }
+            SupplyChange::Burn => {
+                balance.spend(&amount);
+                supply.spend(&amount);
+            }
+        }
+        tx::write(&balance_key, balance);
+        tx::write(&supply_key, supply);
+        tx::insert_verifier(&minter);
+    }
 }
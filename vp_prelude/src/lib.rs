@@ -19,6 +19,11 @@ pub fn sha256(bytes: &[u8]) -> Hash {
 
 pub fn is_tx_whitelisted() -> bool {
     let tx_hash = get_tx_code_hash();
+    // A batch has an empty code. The codes of its inner txs are checked
+    // against the whitelist by the protocol before they're run.
+    if tx_hash == sha256(&[]) {
+        return true;
+    }
     let key = parameters::storage::get_tx_whitelist_storage_key();
     let whitelist: Vec<String> = read_pre(&key.to_string()).unwrap_or_default();
     // if whitelist is empty, allow any transaction
@@ -1,29 +1,43 @@
 #[cfg(feature = "tx_bond")]
 pub mod tx_bond;
+#[cfg(feature = "tx_change_consensus_key")]
+pub mod tx_change_consensus_key;
+#[cfg(feature = "tx_change_supply")]
+pub mod tx_change_supply;
 #[cfg(feature = "tx_from_intent")]
 pub mod tx_from_intent;
 #[cfg(feature = "tx_ibc")]
 pub mod tx_ibc;
 #[cfg(feature = "tx_init_account")]
 pub mod tx_init_account;
+#[cfg(feature = "tx_init_multisig_account")]
+pub mod tx_init_multisig_account;
 #[cfg(feature = "tx_init_nft")]
 pub mod tx_init_nft;
 #[cfg(feature = "tx_init_proposal")]
 pub mod tx_init_proposal;
+#[cfg(feature = "tx_init_token")]
+pub mod tx_init_token;
 #[cfg(feature = "tx_init_validator")]
 pub mod tx_init_validator;
 #[cfg(feature = "tx_mint_nft")]
 pub mod tx_mint_nft;
+#[cfg(feature = "tx_reveal_pk")]
+pub mod tx_reveal_pk;
 #[cfg(feature = "tx_transfer")]
 pub mod tx_transfer;
 #[cfg(feature = "tx_unbond")]
 pub mod tx_unbond;
+#[cfg(feature = "tx_update_validator_metadata")]
+pub mod tx_update_validator_metadata;
 #[cfg(feature = "tx_update_vp")]
 pub mod tx_update_vp;
 #[cfg(feature = "tx_vote_proposal")]
 pub mod tx_vote_proposal;
 #[cfg(feature = "tx_withdraw")]
 pub mod tx_withdraw;
+#[cfg(feature = "vp_multisig")]
+pub mod vp_multisig;
 #[cfg(feature = "vp_nft")]
 pub mod vp_nft;
 #[cfg(feature = "vp_testnet_faucet")]
@@ -6,9 +6,10 @@ use anoma_tx_prelude::*;
 #[transaction]
 fn apply_tx(tx_data: Vec<u8>) {
     let signed = SignedTxData::try_from_slice(&tx_data[..]).unwrap();
-    let bond =
-        transaction::pos::Bond::try_from_slice(&signed.data.unwrap()[..])
-            .unwrap();
+    let bond = transaction::TxPayload::decode(&signed.data.unwrap()[..])
+        .unwrap()
+        .into_bond()
+        .unwrap();
 
     if let Err(err) =
         bond_tokens(bond.source.as_ref(), &bond.validator, bond.amount)
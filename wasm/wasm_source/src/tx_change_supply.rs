@@ -0,0 +1,15 @@
+//! A tx for a token's minter to mint or burn tokens.
+
+use anoma_tx_prelude::*;
+
+#[transaction]
+fn apply_tx(tx_data: Vec<u8>) {
+    let signed = SignedTxData::try_from_slice(&tx_data[..]).unwrap();
+    let change_supply = transaction::token::ChangeSupply::try_from_slice(
+        &signed.data.unwrap()[..],
+    )
+    .unwrap();
+    debug_log!("apply_tx called with change supply: {:#?}", change_supply);
+
+    token::change_supply(change_supply);
+}
@@ -6,9 +6,10 @@ use anoma_tx_prelude::*;
 #[transaction]
 fn apply_tx(tx_data: Vec<u8>) {
     let signed = SignedTxData::try_from_slice(&tx_data[..]).unwrap();
-    let tx_data =
-        transaction::InitAccount::try_from_slice(&signed.data.unwrap()[..])
-            .unwrap();
+    let tx_data = transaction::TxPayload::decode(&signed.data.unwrap()[..])
+        .unwrap()
+        .into_init_account()
+        .unwrap();
     debug_log!("apply_tx called to init a new established account");
 
     let address = init_account(&tx_data.vp_code);
@@ -0,0 +1,23 @@
+//! A tx to initialize a new established k-of-n multisignature address with a
+//! given signer set, threshold and a validity predicate.
+
+use anoma_tx_prelude::*;
+
+#[transaction]
+fn apply_tx(tx_data: Vec<u8>) {
+    let signed = SignedTxData::try_from_slice(&tx_data[..]).unwrap();
+    let tx_data = transaction::InitMultisigAccount::try_from_slice(
+        &signed.data.unwrap()[..],
+    )
+    .unwrap();
+    debug_log!("apply_tx called to init a new multisignature account");
+
+    if !multisig::validate_params(&tx_data.public_keys, tx_data.threshold) {
+        panic!("Invalid multisignature signer set or threshold");
+    }
+    let address = init_account(&tx_data.vp_code);
+    let public_keys_key = multisig::public_keys_key(&address);
+    write(&public_keys_key.to_string(), &tx_data.public_keys);
+    let threshold_key = multisig::threshold_key(&address);
+    write(&threshold_key.to_string(), tx_data.threshold);
+}
@@ -0,0 +1,15 @@
+//! A tx to initialize a new fungible token account.
+
+use anoma_tx_prelude::*;
+
+#[transaction]
+fn apply_tx(tx_data: Vec<u8>) {
+    let signed = SignedTxData::try_from_slice(&tx_data[..]).unwrap();
+    let tx_data = transaction::token::InitToken::try_from_slice(
+        &signed.data.unwrap()[..],
+    )
+    .unwrap();
+    debug_log!("apply_tx called to init a new token account");
+
+    token::init_token(tx_data);
+}
@@ -0,0 +1,17 @@
+//! A tx to reveal a public key of an implicit address. The key is written into
+//! the storage sub-space of the implicit address derived from it, so that the
+//! signatures of the address' transactions can be verified.
+
+use anoma_tx_prelude::*;
+
+#[transaction]
+fn apply_tx(tx_data: Vec<u8>) {
+    let signed = SignedTxData::try_from_slice(&tx_data[..]).unwrap();
+    let pk = key::common::PublicKey::try_from_slice(&signed.data.unwrap()[..])
+        .unwrap();
+    debug_log!("apply_tx called to reveal a public key {}", pk);
+
+    let address = Address::from(&pk);
+    let pk_key = key::pk_key(&address);
+    write(&pk_key.to_string(), &pk);
+}
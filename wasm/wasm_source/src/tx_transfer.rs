@@ -1,14 +1,16 @@
 //! A tx for token transfer.
-//! This tx uses `token::Transfer` wrapped inside `SignedTxData`
-//! as its input as declared in `shared` crate.
+//! This tx uses a `TxPayload` with a `token::Transfer` wrapped inside
+//! `SignedTxData` as its input as declared in `shared` crate.
 
 use anoma_tx_prelude::*;
 
 #[transaction]
 fn apply_tx(tx_data: Vec<u8>) {
     let signed = SignedTxData::try_from_slice(&tx_data[..]).unwrap();
-    let transfer =
-        token::Transfer::try_from_slice(&signed.data.unwrap()[..]).unwrap();
+    let transfer = transaction::TxPayload::decode(&signed.data.unwrap()[..])
+        .unwrap()
+        .into_transfer()
+        .unwrap();
     debug_log!("apply_tx called with transfer: {:#?}", transfer);
     let token::Transfer {
         source,
@@ -0,0 +1,17 @@
+//! A tx for a PoS validator to register or update its metadata.
+
+use anoma_tx_prelude::proof_of_stake::update_validator_metadata;
+use anoma_tx_prelude::*;
+
+#[transaction]
+fn apply_tx(tx_data: Vec<u8>) {
+    let signed = SignedTxData::try_from_slice(&tx_data[..]).unwrap();
+    let update = transaction::pos::UpdateValidatorMetadata::try_from_slice(
+        &signed.data.unwrap()[..],
+    )
+    .unwrap();
+
+    // The update has to be authorized by the validator's VP
+    insert_verifier(&update.validator);
+    update_validator_metadata(update);
+}
@@ -0,0 +1,298 @@
+//! A k-of-n multisignature account VP.
+//!
+//! This VP provides the same rules as the user VP, except that a signature is
+//! only valid if at least the threshold number of the account's signers have
+//! signed the transaction. The signatures are attached to the `multisig`
+//! field of the `SignedTxData`, keyed by the index of the signer's public key.
+//!
+//! Receiving tokens is permissive. Any other storage key changes, including
+//! changes of the signer set and threshold, are allowed only with valid
+//! signatures. A new signer set and threshold must be well-formed.
+
+use anoma_vp_prelude::*;
+use once_cell::unsync::Lazy;
+
+enum KeyType<'a> {
+    Token(&'a Address),
+    TokenSupply(&'a Address),
+    PoS,
+    Nft(&'a Address),
+    Vp(&'a Address),
+    GovernanceVote(&'a Address),
+    Multisig(&'a Address),
+    Unknown,
+}
+
+impl<'a> From<&'a storage::Key> for KeyType<'a> {
+    fn from(key: &'a storage::Key) -> KeyType<'a> {
+        if let Some(address) = token::is_any_token_balance_key(key) {
+            Self::Token(address)
+        } else if let Some(token) = token::is_any_supply_key(key) {
+            Self::TokenSupply(token)
+        } else if proof_of_stake::is_pos_key(key) {
+            Self::PoS
+        } else if let Some(address) = nft::is_nft_key(key) {
+            Self::Nft(address)
+        } else if gov_storage::is_vote_key(key) {
+            let voter_address = gov_storage::get_voter_address(key);
+            if let Some(address) = voter_address {
+                Self::GovernanceVote(address)
+            } else {
+                Self::Unknown
+            }
+        } else if let Some(address) = multisig::is_multisig_key(key) {
+            Self::Multisig(address)
+        } else if let Some(address) = key.is_validity_predicate() {
+            Self::Vp(address)
+        } else {
+            Self::Unknown
+        }
+    }
+}
+
+#[validity_predicate]
+fn validate_tx(
+    tx_data: Vec<u8>,
+    addr: Address,
+    keys_changed: BTreeSet<storage::Key>,
+    verifiers: BTreeSet<Address>,
+) -> bool {
+    debug_log!(
+        "vp_multisig called with user addr: {}, key_changed: {:?}, verifiers: \
+         {:?}",
+        addr,
+        keys_changed,
+        verifiers
+    );
+
+    let valid_sig =
+        Lazy::new(|| match SignedTxData::try_from_slice(&tx_data[..]) {
+            Ok(signed_tx_data) => {
+                multisig::verify_tx_signatures(&addr, &signed_tx_data.multisig)
+            }
+            _ => false,
+        });
+
+    if !is_tx_whitelisted() {
+        return false;
+    }
+
+    for key in keys_changed.iter() {
+        let key_type: KeyType = key.into();
+        let is_valid = match key_type {
+            KeyType::Token(owner) => {
+                if owner == &addr {
+                    let key = key.to_string();
+                    let pre: token::Amount = read_pre(&key).unwrap_or_default();
+                    let post: token::Amount =
+                        read_post(&key).unwrap_or_default();
+                    let change = post.change() - pre.change();
+                    // debit has to signed, credit doesn't
+                    let valid = change >= 0 || *valid_sig;
+                    debug_log!(
+                        "token key: {}, change: {}, valid_sig: {}, valid \
+                         modification: {}",
+                        key,
+                        change,
+                        *valid_sig,
+                        valid
+                    );
+                    valid
+                } else {
+                    // If this is not the owner, allow any change
+                    true
+                }
+            }
+            KeyType::TokenSupply(token) => {
+                // Minting and burning of a token whose minter is this
+                // account must be signed
+                token::read_minter_pre(token).as_ref() != Some(&addr)
+                    || *valid_sig
+            }
+            KeyType::PoS => {
+                // Allow the account to be used in PoS
+                let bond_id = proof_of_stake::is_bond_key(key)
+                    .or_else(|| proof_of_stake::is_unbond_key(key));
+                match bond_id {
+                    Some(bond_id) => {
+                        // Bonds and unbonds changes for this address
+                        // must be signed
+                        bond_id.source != addr || *valid_sig
+                    }
+                    None if proof_of_stake::is_validator_consensus_key_key(
+                        key,
+                    ) == Some(&addr)
+                        && has_key_pre(key.to_string()) =>
+                    {
+                        // Consensus key changes of this validator must be
+                        // signed
+                        *valid_sig
+                    }
+                    None if proof_of_stake::is_validator_metadata_key(key)
+                        == Some(&addr) =>
+                    {
+                        // Metadata changes of this validator must be signed
+                        *valid_sig
+                    }
+                    None => {
+                        // Any other PoS changes are allowed without signature
+                        true
+                    }
+                }
+            }
+            KeyType::Nft(owner) | KeyType::GovernanceVote(owner) => {
+                owner != &addr || *valid_sig
+            }
+            KeyType::Multisig(owner) => {
+                if owner == &addr {
+                    *valid_sig && has_valid_params_post(&addr)
+                } else {
+                    true
+                }
+            }
+            KeyType::Vp(owner) => {
+                let key = key.to_string();
+                let has_post: bool = has_key_post(&key);
+                if owner == &addr {
+                    if has_post {
+                        let vp: Vec<u8> = read_bytes_post(&key).unwrap();
+                        return *valid_sig && is_vp_whitelisted(&vp);
+                    } else {
+                        return false;
+                    }
+                } else {
+                    let vp: Vec<u8> = read_bytes_post(&key).unwrap();
+                    return is_vp_whitelisted(&vp);
+                }
+            }
+            KeyType::Unknown => {
+                if key.segments.get(0) == Some(&addr.to_db_key()) {
+                    // Unknown changes to this address space require a valid
+                    // signature
+                    *valid_sig
+                } else {
+                    // Unknown changes anywhere else are permitted
+                    true
+                }
+            }
+        };
+        if !is_valid {
+            debug_log!("key {} modification failed vp", key);
+            return false;
+        }
+    }
+
+    true
+}
+
+/// Check that the signer set and threshold after the transaction are
+/// well-formed.
+fn has_valid_params_post(addr: &Address) -> bool {
+    let public_keys: Option<Vec<key::common::PublicKey>> =
+        read_post(multisig::public_keys_key(addr).to_string());
+    let threshold: Option<u8> =
+        read_post(multisig::threshold_key(addr).to_string());
+    match (public_keys, threshold) {
+        (Some(public_keys), Some(threshold)) => {
+            multisig::validate_params(&public_keys, threshold)
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    // Use this as `#[test]` annotation to enable logging
+    use anoma_tests::log::test;
+    use anoma_tests::tx::{tx_host_env, TestTxEnv};
+    use anoma_tests::vp::*;
+    use anoma_vp_prelude::key::{RefTo, SigScheme};
+
+    use super::*;
+
+    /// Initialize a VP environment with a debit transfer from a 2-of-3
+    /// multisignature account and sign it with the given signers.
+    fn init_signed_debit(
+        vp_owner: &Address,
+        signers: &[u8],
+    ) -> (Vec<u8>, BTreeSet<storage::Key>) {
+        let mut tx_env = TestTxEnv::default();
+
+        let keypairs = [
+            key::testing::keypair_1(),
+            key::testing::keypair_2(),
+            key::testing::gen_keypair::<key::common::SigScheme>(),
+        ];
+        let public_keys: Vec<key::common::PublicKey> =
+            keypairs.iter().map(|keypair| keypair.ref_to()).collect();
+        let target = address::testing::established_address_2();
+        let token = address::xan();
+        let amount = token::Amount::from(10_098_123);
+
+        // Spawn the accounts to be able to modify their storage
+        tx_env.spawn_accounts([vp_owner, &target, &token]);
+        tx_env.credit_tokens(vp_owner, &token, amount);
+        tx_env.write_multisig_params(vp_owner, &public_keys, 2);
+
+        // Initialize VP environment from a transaction
+        vp_host_env::init_from_tx(vp_owner.clone(), tx_env, |address| {
+            // Apply transfer in a transaction
+            tx_host_env::token::transfer(address, &target, &token, amount);
+        });
+
+        let mut vp_env = vp_host_env::take();
+        let tx = vp_env.tx.clone();
+        let to_sign = tx.tx_to_sign();
+        let multisig: BTreeMap<u8, key::common::Signature> = signers
+            .iter()
+            .map(|ix| {
+                let keypair = &keypairs[*ix as usize];
+                (*ix, key::common::SigScheme::sign(keypair, &to_sign))
+            })
+            .collect();
+        let sig = key::common::SigScheme::sign(&keypairs[0], &to_sign);
+        let signed_tx = tx.attach_signatures(sig, multisig);
+        let tx_data: Vec<u8> = signed_tx.data.as_ref().cloned().unwrap();
+        vp_env.tx = signed_tx;
+        let keys_changed: BTreeSet<storage::Key> =
+            vp_env.all_touched_storage_keys();
+        vp_host_env::set(vp_env);
+        (tx_data, keys_changed)
+    }
+
+    /// Test that no-op transaction (i.e. no storage modifications) accepted.
+    #[test]
+    fn test_no_op_transaction() {
+        let tx_data: Vec<u8> = vec![];
+        let addr: Address = address::testing::established_address_1();
+        let keys_changed: BTreeSet<storage::Key> = BTreeSet::default();
+        let verifiers: BTreeSet<Address> = BTreeSet::default();
+
+        // The VP env must be initialized before calling `validate_tx`
+        vp_host_env::init();
+
+        assert!(validate_tx(tx_data, addr, keys_changed, verifiers));
+    }
+
+    /// Test that a debit transfer signed by the threshold number of signers
+    /// is accepted.
+    #[test]
+    fn test_threshold_signed_debit_transfer_accepted() {
+        let vp_owner = address::testing::established_address_1();
+        let (tx_data, keys_changed) = init_signed_debit(&vp_owner, &[0, 2]);
+        let verifiers: BTreeSet<Address> = BTreeSet::default();
+        assert!(validate_tx(tx_data, vp_owner, keys_changed, verifiers));
+    }
+
+    /// Test that a debit transfer signed by fewer than the threshold number
+    /// of signers is rejected.
+    #[test]
+    fn test_below_threshold_debit_transfer_rejected() {
+        let vp_owner = address::testing::established_address_1();
+        let (tx_data, keys_changed) = init_signed_debit(&vp_owner, &[1]);
+        let verifiers: BTreeSet<Address> = BTreeSet::default();
+        assert!(!validate_tx(tx_data, vp_owner, keys_changed, verifiers));
+    }
+}
@@ -1,5 +1,6 @@
 //! A VP for a fungible token. Enforces that the total supply is unchanged in a
-//! transaction that moves balance(s).
+//! transaction that moves balance(s), unless the tracked total supply is
+//! changed by the same amount with the approval of the token's minter.
 
 use anoma_vp_prelude::*;
 
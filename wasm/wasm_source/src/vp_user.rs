@@ -8,7 +8,10 @@
 //!
 //! It allows to fulfil intents that were signed by this account's key if they
 //! haven't already been fulfilled (fulfilled intents are added to the owner's
-//! invalid intent set).
+//! invalid intent set) and haven't expired.
+//!
+//! It allows to mint and burn tokens for which this account is the minter
+//! with a valid signature.
 //!
 //! Any other storage key changes are allowed only with a valid signature.
 
@@ -22,6 +25,7 @@ use rust_decimal::prelude::*;
 
 enum KeyType<'a> {
     Token(&'a Address),
+    TokenSupply(&'a Address),
     PoS,
     InvalidIntentSet(&'a Address),
     Nft(&'a Address),
@@ -34,6 +38,8 @@ impl<'a> From<&'a storage::Key> for KeyType<'a> {
     fn from(key: &'a storage::Key) -> KeyType<'a> {
         if let Some(address) = token::is_any_token_balance_key(key) {
             Self::Token(address)
+        } else if let Some(token) = token::is_any_supply_key(key) {
+            Self::TokenSupply(token)
         } else if proof_of_stake::is_pos_key(key) {
             Self::PoS
         } else if let Some(address) = intent::is_invalid_intent_key(key) {
@@ -126,6 +132,12 @@ fn validate_tx(
                     true
                 }
             }
+            KeyType::TokenSupply(token) => {
+                // Minting and burning of a token whose minter is this
+                // account must be signed
+                token::read_minter_pre(token).as_ref() != Some(&addr)
+                    || *valid_sig
+            }
             KeyType::PoS => {
                 // Allow the account to be used in PoS
                 let bond_id = proof_of_stake::is_bond_key(key)
@@ -136,6 +148,21 @@ fn validate_tx(
                         // must be signed
                         bond_id.source != addr || *valid_sig
                     }
+                    None if proof_of_stake::is_validator_consensus_key_key(
+                        key,
+                    ) == Some(&addr)
+                        && has_key_pre(key.to_string()) =>
+                    {
+                        // Consensus key changes of this validator must be
+                        // signed
+                        *valid_sig
+                    }
+                    None if proof_of_stake::is_validator_metadata_key(key)
+                        == Some(&addr) =>
+                    {
+                        // Metadata changes of this validator must be signed
+                        *valid_sig
+                    }
                     None => {
                         // Any other PoS changes are allowed without signature
                         true
@@ -264,10 +291,10 @@ fn check_intent(
     intent: anoma_vp_prelude::Signed<FungibleTokenIntent>,
     raw_intent_transfers: Vec<u8>,
 ) -> bool {
-    // verify signature
+    // verify the signatures of the intent and of the exchange
     let pk = key::get(addr);
     if let Some(pk) = pk {
-        if intent.verify(&pk).is_err() {
+        if intent.verify(&pk).is_err() || exchange.verify(&pk).is_err() {
             log_string("invalid sig");
             return false;
         }
@@ -275,6 +302,13 @@ fn check_intent(
         return false;
     }
 
+    // verify the exchange belongs to this account and is part of its intent
+    if &exchange.data.addr != addr || !intent.data.exchange.contains(&exchange)
+    {
+        log_string("exchange not from this account's intent");
+        return false;
+    }
+
     // verify the intent have not been already used
     if !intent::vp_exchange(&exchange) {
         return false;
@@ -289,8 +323,20 @@ fn check_intent(
         min_buy,
         max_sell,
         vp,
+        expiry,
     } = &exchange.data;
 
+    // verify the exchange has not expired
+    if expiry.is_some() {
+        match time::DateTimeUtc::try_from(get_block_time()) {
+            Ok(block_time) if !exchange.data.is_expired(block_time) => {}
+            _ => {
+                log_string("exchange expired");
+                return false;
+            }
+        }
+    }
+
     debug_log!("vp is: {}", vp.is_some());
 
     if let Some(code) = vp {
@@ -497,6 +543,78 @@ mod tests {
         assert!(validate_tx(tx_data, vp_owner, keys_changed, verifiers));
     }
 
+    /// Initialize a VP environment with a transaction that mints tokens of a
+    /// token whose minter is the VP owner.
+    fn init_mint(
+        vp_owner: &Address,
+        keypair: &key::common::SecretKey,
+    ) -> BTreeSet<storage::Key> {
+        let mut tx_env = TestTxEnv::default();
+
+        let token = address::testing::established_address_2();
+        let amount = token::Amount::from(10_098_123);
+
+        // Spawn the accounts to be able to modify their storage
+        tx_env.spawn_accounts([vp_owner, &token]);
+        tx_env.write_public_key(vp_owner, &keypair.ref_to());
+        tx_env.write_token_metadata(
+            &token,
+            &token::TokenMetadata {
+                symbol: "TKN".to_owned(),
+                decimals: 6,
+                minter: Some(vp_owner.clone()),
+            },
+        );
+
+        // Initialize VP environment from a transaction
+        vp_host_env::init_from_tx(vp_owner.clone(), tx_env, |address| {
+            // Mint the tokens to the minter in a transaction
+            tx_host_env::token::change_supply(
+                transaction::token::ChangeSupply {
+                    token: token.clone(),
+                    owner: address.clone(),
+                    amount,
+                    change: transaction::token::SupplyChange::Mint,
+                },
+            );
+        });
+
+        let vp_env = vp_host_env::take();
+        let keys_changed = vp_env.all_touched_storage_keys();
+        vp_host_env::set(vp_env);
+        keys_changed
+    }
+
+    /// Test that minting tokens without a valid signature of the minter is
+    /// rejected.
+    #[test]
+    fn test_unsigned_mint_rejected() {
+        let vp_owner = address::testing::established_address_1();
+        let keypair = key::testing::keypair_1();
+        let keys_changed = init_mint(&vp_owner, &keypair);
+
+        let tx_data: Vec<u8> = vec![];
+        let verifiers: BTreeSet<Address> = BTreeSet::default();
+        assert!(!validate_tx(tx_data, vp_owner, keys_changed, verifiers));
+    }
+
+    /// Test that minting tokens with a valid signature of the minter is
+    /// accepted.
+    #[test]
+    fn test_signed_mint_accepted() {
+        let vp_owner = address::testing::established_address_1();
+        let keypair = key::testing::keypair_1();
+        let keys_changed = init_mint(&vp_owner, &keypair);
+
+        let mut vp_env = vp_host_env::take();
+        let signed_tx = vp_env.tx.clone().sign(&keypair);
+        let tx_data: Vec<u8> = signed_tx.data.as_ref().cloned().unwrap();
+        vp_env.tx = signed_tx;
+        vp_host_env::set(vp_env);
+        let verifiers: BTreeSet<Address> = BTreeSet::default();
+        assert!(validate_tx(tx_data, vp_owner, keys_changed, verifiers));
+    }
+
     /// Test that a transfer on with accounts other than self is accepted.
     #[test]
     fn test_transfer_between_other_parties_accepted() {